@@ -0,0 +1,287 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::export_commands::csv_field;
+use crate::models::{PermissionChange, UserSession};
+use crate::{CurrentUser, DbConnection};
+use rusqlite::{Connection, OptionalExtension};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use tauri::State;
+
+// Permission columns on users, as (column, matrix header)
+const PERMISSION_COLUMNS: [(&str, &str); 19] = [
+    ("can_view_employees", "View Employees"),
+    ("can_add_employees", "Add Employees"),
+    ("can_edit_employees", "Edit Employees"),
+    ("can_delete_employees", "Delete Employees"),
+    ("can_view_all_departments", "View All Departments"),
+    ("can_manage_disciplinary", "Manage Disciplinary"),
+    ("can_approve_attendance", "Approve Attendance"),
+    ("can_manage_attendance", "Manage Attendance"),
+    ("can_approve_leave", "Approve Leave"),
+    ("can_export_data", "Export Data"),
+    ("can_view_reports", "View Reports"),
+    ("can_view_sensitive_data", "View Sensitive Data"),
+    ("can_run_payroll", "Run Payroll"),
+    ("can_view_salaries", "View Salaries"),
+    ("can_manage_users", "Manage Users"),
+    ("can_manage_settings", "Manage Settings"),
+    ("can_backup_database", "Database Backup"),
+    ("can_view_audit_logs", "View Audit Logs"),
+    ("can_login_anytime", "Login Anytime"),
+];
+
+// Account fields that decide what a user can reach besides the permissions
+const ACCOUNT_FIELDS: [(&str, &str); 4] =
+    [("role", "Role"), ("department_access", "Department Access"), ("is_active", "Active"), ("deleted", "Deleted")];
+
+// Audit entity holding a snapshot of a user's access after every change
+const ACCESS_ENTITY: &str = "USER_ACCESS";
+
+fn require_audit_permission(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_view_audit_logs => Ok(session.clone()),
+        Some(_) => Err(HrmError::permission_denied("Permission denied. You cannot review user access.")),
+        None => Err(HrmError::not_logged_in()),
+    }
+}
+
+// Role, department access, active and deleted flags and every permission of
+// each user (or just `user_id`), keyed by user ID
+fn access_snapshots(conn: &Connection, user_id: Option<i32>) -> rusqlite::Result<BTreeMap<i32, Map<String, Value>>> {
+    let columns: Vec<&str> = PERMISSION_COLUMNS.iter().map(|(column, _)| *column).collect();
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, username, role, department_access, is_active, deleted_at IS NOT NULL, {}
+         FROM users WHERE ?1 IS NULL OR id = ?1 ORDER BY id",
+        columns.join(", ")
+    ))?;
+    let rows = stmt
+        .query_map([user_id], |row| {
+            let mut snapshot = Map::new();
+            snapshot.insert("username".to_string(), Value::from(row.get::<_, String>(1)?));
+            snapshot.insert("role".to_string(), Value::from(row.get::<_, String>(2)?));
+            snapshot.insert("department_access".to_string(), Value::from(row.get::<_, Option<String>>(3)?));
+            snapshot.insert("is_active".to_string(), Value::from(row.get::<_, bool>(4)?));
+            snapshot.insert("deleted".to_string(), Value::from(row.get::<_, bool>(5)?));
+            for (index, column) in columns.iter().enumerate() {
+                snapshot.insert(column.to_string(), Value::from(row.get::<_, bool>(index + 6)?));
+            }
+            Ok((row.get::<_, i32>(0)?, snapshot))
+        })?
+        .collect::<Result<BTreeMap<_, _>, _>>()?;
+    Ok(rows)
+}
+
+// The user's current access as JSON, to pass to log_access_change after
+// changing the account
+pub(crate) fn access_snapshot(conn: &Connection, user_id: i32) -> rusqlite::Result<Option<String>> {
+    Ok(access_snapshots(conn, Some(user_id))?
+        .remove(&user_id)
+        .map(|snapshot| Value::Object(snapshot).to_string()))
+}
+
+// Records the user's access in the audit log if it differs from `before`
+pub(crate) fn log_access_change(conn: &Connection, actor_id: i32, actor_username: &str, user_id: i32, before: Option<&str>) {
+    let Ok(Some(after)) = access_snapshot(conn, user_id) else { return };
+    if before == Some(after.as_str()) {
+        return;
+    }
+    log_audit_action(
+        conn,
+        Some(actor_id),
+        actor_username,
+        "ACCESS_CHANGE",
+        ACCESS_ENTITY,
+        Some(&user_id.to_string()),
+        before,
+        Some(&after),
+        Some(&format!("Access changed for user ID {}", user_id)),
+    );
+}
+
+// Snapshots users whose access has never been recorded, so access reviews
+// have a starting point for accounts that predate access tracking
+pub fn record_access_baseline(conn: &Connection) -> rusqlite::Result<()> {
+    let recorded: BTreeSet<i32> = conn
+        .prepare("SELECT DISTINCT CAST(entity_id AS INTEGER) FROM audit_logs WHERE entity_type = ?1")?
+        .query_map([ACCESS_ENTITY], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    for (user_id, snapshot) in access_snapshots(conn, None)? {
+        if recorded.contains(&user_id) {
+            continue;
+        }
+        log_audit_action(
+            conn,
+            None,
+            "system",
+            "ACCESS_BASELINE",
+            ACCESS_ENTITY,
+            Some(&user_id.to_string()),
+            None,
+            Some(&Value::Object(snapshot).to_string()),
+            Some("Access recorded for review"),
+        );
+    }
+    Ok(())
+}
+
+// Each user's access as of the end of `date`, from the audit snapshots
+fn access_as_of(conn: &Connection, date: &str) -> Result<BTreeMap<i32, Map<String, Value>>, HrmError> {
+    let mut stmt = conn.prepare(
+        "SELECT CAST(entity_id AS INTEGER), new_value FROM audit_logs
+         WHERE entity_type = ?1 AND date(created_at) <= date(?2)
+         ORDER BY id",
+    )?;
+    let rows = stmt
+        .query_map(rusqlite::params![ACCESS_ENTITY, date], |row| {
+            Ok((row.get::<_, i32>(0)?, row.get::<_, Option<String>>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut access = BTreeMap::new();
+    for (user_id, value) in rows {
+        if let Some(Value::Object(snapshot)) = value.and_then(|v| serde_json::from_str(&v).ok()) {
+            access.insert(user_id, snapshot);
+        }
+    }
+    Ok(access)
+}
+
+fn display_value(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::Bool(true) => Some("Yes".to_string()),
+        Value::Bool(false) => Some("No".to_string()),
+        Value::Null => Some(String::new()),
+        Value::String(text) => Some(text.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+// Writes every user's role, department access and permissions as CSV, one
+// row per account including those in the recycle bin. Returns the number of
+// users written.
+#[tauri::command]
+pub fn export_permission_matrix(
+    destination_path: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<usize, HrmError> {
+    let user = require_audit_permission(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare("SELECT id, full_name, last_login FROM users ORDER BY id")?;
+    let details: BTreeMap<i32, (String, Option<String>)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))?
+        .collect::<Result<_, _>>()?;
+    let snapshots = access_snapshots(&conn, None)?;
+    
+    // UTF-8 BOM so Excel picks the right encoding
+    let mut csv = String::from("\u{FEFF}");
+    let mut headers = vec!["User ID", "Username", "Full Name", "Role", "Department Access", "Active", "Deleted", "Last Login"];
+    headers.extend(PERMISSION_COLUMNS.iter().map(|(_, header)| *header));
+    csv.push_str(&headers.join(","));
+    csv.push_str("\r\n");
+    for (user_id, snapshot) in &snapshots {
+        let (full_name, last_login) = details.get(user_id).cloned().unwrap_or_default();
+        let field = |key: &str| display_value(snapshot.get(key)).unwrap_or_default();
+        let mut values = vec![
+            user_id.to_string(),
+            field("username"),
+            full_name,
+            field("role"),
+            field("department_access"),
+            field("is_active"),
+            field("deleted"),
+            last_login.unwrap_or_default(),
+        ];
+        values.extend(PERMISSION_COLUMNS.iter().map(|(column, _)| field(column)));
+        let fields: Vec<String> = values.iter().map(|v| csv_field(v)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+    
+    fs::write(&destination_path, csv).map_err(|e| HrmError::io(format!("Failed to save file: {}", e)))?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "EXPORT",
+        "USER",
+        None,
+        None,
+        None,
+        Some(&format!("Exported the permission matrix for {} users", snapshots.len())),
+    );
+    
+    Ok(snapshots.len())
+}
+
+// Differences in user access between the end of `from_date` and the end of
+// `to_date`, one row per changed field. Users created in between show
+// every field as new. History starts when access tracking was
+// first recorded and does not reach into archived audit logs.
+#[tauri::command]
+pub fn get_permission_changes(
+    from_date: String,
+    to_date: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<PermissionChange>, HrmError> {
+    require_audit_permission(&current_user)?;
+    if from_date > to_date {
+        return Err(HrmError::validation("From date must be on or before the to date"));
+    }
+    
+    let conn = db.0.lock()?;
+    let before = access_as_of(&conn, &from_date)?;
+    let after = access_as_of(&conn, &to_date)?;
+    
+    let fields: Vec<(&str, &str)> = ACCOUNT_FIELDS.iter().chain(PERMISSION_COLUMNS.iter()).copied().collect();
+    let user_ids: BTreeSet<i32> = before.keys().chain(after.keys()).copied().collect();
+    let mut changes = Vec::new();
+    for user_id in user_ids {
+        let old = before.get(&user_id);
+        let new = after.get(&user_id);
+        let username = new
+            .or(old)
+            .and_then(|s| s.get("username"))
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let mut changed_by = None;
+        for (field, label) in &fields {
+            let old_value = old.and_then(|s| display_value(s.get(*field)));
+            let new_value = new.and_then(|s| display_value(s.get(*field)));
+            if old_value == new_value {
+                continue;
+            }
+            if changed_by.is_none() {
+                changed_by = last_changed_by(&conn, user_id, &from_date, &to_date)?;
+            }
+            changes.push(PermissionChange {
+                user_id,
+                username: username.clone(),
+                field: field.to_string(),
+                label: label.to_string(),
+                before: old_value,
+                after: new_value,
+                changed_by: changed_by.clone(),
+            });
+        }
+    }
+    Ok(changes)
+}
+
+// Who last changed the user's access within the period
+fn last_changed_by(conn: &Connection, user_id: i32, from_date: &str, to_date: &str) -> rusqlite::Result<Option<String>> {
+    conn.query_row(
+        "SELECT username FROM audit_logs
+         WHERE entity_type = ?1 AND entity_id = ?2 AND date(created_at) > date(?3) AND date(created_at) <= date(?4)
+         ORDER BY id DESC LIMIT 1",
+        rusqlite::params![ACCESS_ENTITY, user_id.to_string(), from_date, to_date],
+        |row| row.get(0),
+    )
+    .optional()
+}
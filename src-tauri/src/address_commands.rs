@@ -0,0 +1,362 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{District, Employee, FieldError, GnDivision, ResidenceCount, UserSession};
+use crate::{CurrentUser, DbConnection};
+use rusqlite::{Connection, OptionalExtension};
+use tauri::State;
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+// Province, district and its Divisional Secretariat divisions. Grama
+// Niladhari divisions are too many to ship and are added per installation
+// for the areas employees come from.
+const ADMINISTRATIVE_DIVISIONS: [(&str, &str, &[&str]); 25] = [
+    ("Western", "Colombo", &[
+        "Colombo", "Dehiwala", "Homagama", "Kaduwela", "Kesbewa", "Kolonnawa", "Maharagama", "Moratuwa",
+        "Padukka", "Ratmalana", "Seethawaka", "Sri Jayawardanapura Kotte", "Thimbirigasyaya",
+    ]),
+    ("Western", "Gampaha", &[
+        "Attanagalla", "Biyagama", "Divulapitiya", "Dompe", "Gampaha", "Ja-Ela", "Katana", "Kelaniya",
+        "Mahara", "Minuwangoda", "Mirigama", "Negombo", "Wattala",
+    ]),
+    ("Western", "Kalutara", &[
+        "Agalawatta", "Bandaragama", "Beruwala", "Bulathsinhala", "Dodangoda", "Horana", "Ingiriya", "Kalutara",
+        "Madurawela", "Mathugama", "Millaniya", "Palindanuwara", "Panadura", "Walallavita",
+    ]),
+    ("Central", "Kandy", &[
+        "Akurana", "Delthota", "Doluwa", "Ganga Ihala Korale", "Gangawata Korale", "Harispattuwa",
+        "Hatharaliyadda", "Kundasale", "Medadumbara", "Minipe", "Panvila", "Pasbage Korale", "Pathadumbara",
+        "Pathahewaheta", "Poojapitiya", "Thumpane", "Udadumbara", "Udapalatha", "Udunuwara", "Yatinuwara",
+    ]),
+    ("Central", "Matale", &[
+        "Ambanganga Korale", "Dambulla", "Galewela", "Laggala-Pallegama", "Matale", "Naula", "Pallepola",
+        "Rattota", "Ukuwela", "Wilgamuwa", "Yatawatta",
+    ]),
+    ("Central", "Nuwara Eliya", &["Ambagamuwa", "Hanguranketha", "Kothmale", "Nuwara Eliya", "Walapane"]),
+    ("Southern", "Galle", &[
+        "Akmeemana", "Ambalangoda", "Baddegama", "Balapitiya", "Benthota", "Bope-Poddala", "Elpitiya",
+        "Four Gravets (Galle)", "Gonapinuwala", "Habaraduwa", "Hikkaduwa", "Imaduwa", "Karandeniya", "Nagoda",
+        "Neluwa", "Niyagama", "Thawalama", "Welivitiya-Divithura", "Yakkalamulla",
+    ]),
+    ("Southern", "Matara", &[
+        "Akuressa", "Athuraliya", "Devinuwara", "Dickwella", "Four Gravets (Matara)", "Hakmana",
+        "Kamburupitiya", "Kirinda Puhulwella", "Kotapola", "Malimbada", "Mulatiyana", "Pasgoda", "Pitabeddara",
+        "Thihagoda", "Weligama", "Welipitiya",
+    ]),
+    ("Southern", "Hambantota", &[
+        "Ambalantota", "Angunakolapelessa", "Beliatta", "Hambantota", "Katuwana", "Lunugamvehera", "Okewela",
+        "Sooriyawewa", "Tangalle", "Thissamaharama", "Walasmulla", "Weeraketiya",
+    ]),
+    ("Northern", "Jaffna", &[
+        "Delft", "Island North", "Island South", "Jaffna", "Karainagar", "Nallur", "Thenmaradchi",
+        "Vadamaradchi East", "Vadamaradchi North", "Vadamaradchi South-West", "Valikamam East",
+        "Valikamam North", "Valikamam South", "Valikamam South-West", "Valikamam West",
+    ]),
+    ("Northern", "Kilinochchi", &["Kandavalai", "Karachchi", "Pachchilaipalli", "Poonakary"]),
+    ("Northern", "Mannar", &["Madhu", "Mannar Town", "Manthai West", "Musali", "Nanattan"]),
+    ("Northern", "Vavuniya", &["Vavuniya", "Vavuniya North", "Vavuniya South", "Vengalacheddikulam"]),
+    ("Northern", "Mullaitivu", &[
+        "Manthai East", "Maritimepattu", "Oddusuddan", "Puthukudiyiruppu", "Thunukkai", "Welioya",
+    ]),
+    ("Eastern", "Batticaloa", &[
+        "Eravur Pattu", "Eravur Town", "Kattankudy", "Koralai Pattu", "Koralai Pattu Central",
+        "Koralai Pattu North", "Koralai Pattu South", "Koralai Pattu West", "Manmunai North", "Manmunai Pattu",
+        "Manmunai South & Eruvil Pattu", "Manmunai South West", "Manmunai West", "Porativu Pattu",
+    ]),
+    ("Eastern", "Ampara", &[
+        "Addalachchenai", "Akkaraipattu", "Alayadiwembu", "Ampara", "Damana", "Dehiattakandiya", "Irakkamam",
+        "Kalmunai", "Kalmunai North", "Karaitivu", "Lahugala", "Mahaoya", "Navithanveli", "Ninthavur",
+        "Padiyathalawa", "Pottuvil", "Sainthamaruthu", "Sammanthurai", "Thirukkovil", "Uhana",
+    ]),
+    ("Eastern", "Trincomalee", &[
+        "Gomarankadawala", "Kantalai", "Kinniya", "Kuchchaveli", "Morawewa", "Muttur", "Padavi Sri Pura",
+        "Seruvila", "Thambalagamuwa", "Trincomalee Town and Gravets", "Verugal",
+    ]),
+    ("North Western", "Kurunegala", &[
+        "Alawwa", "Ambanpola", "Bamunakotuwa", "Bingiriya", "Ehetuwewa", "Galgamuwa", "Ganewatta", "Giribawa",
+        "Ibbagamuwa", "Kobeigane", "Kotavehera", "Kuliyapitiya East", "Kuliyapitiya West", "Kurunegala",
+        "Mahawa", "Mallawapitiya", "Maspotha", "Mawathagama", "Narammala", "Nikaweratiya",
+        "Panduwasnuwara East", "Panduwasnuwara West", "Pannala", "Polgahawela", "Polpithigama",
+        "Rasnayakapura", "Rideegama", "Udubaddawa", "Wariyapola", "Weerambugedara",
+    ]),
+    ("North Western", "Puttalam", &[
+        "Anamaduwa", "Arachchikattuwa", "Chilaw", "Dankotuwa", "Kalpitiya", "Karuwalagaswewa", "Madampe",
+        "Mahakumbukkadawala", "Mahawewa", "Mundalama", "Nattandiya", "Nawagattegama", "Pallama", "Puttalam",
+        "Vanathavilluwa", "Wennappuwa",
+    ]),
+    ("North Central", "Anuradhapura", &[
+        "Galenbindunuwewa", "Galnewa", "Horowpothana", "Ipalogama", "Kahatagasdigiliya", "Kebithigollewa",
+        "Kekirawa", "Mahavilachchiya", "Medawachchiya", "Mihinthale", "Nachchadoowa", "Nochchiyagama",
+        "Nuwaragam Palatha Central", "Nuwaragam Palatha East", "Padaviya", "Palagala", "Palugaswewa",
+        "Rajanganaya", "Rambewa", "Thalawa", "Thambuttegama", "Thirappane",
+    ]),
+    ("North Central", "Polonnaruwa", &[
+        "Dimbulagala", "Elahera", "Hingurakgoda", "Lankapura", "Medirigiriya", "Thamankaduwa", "Welikanda",
+    ]),
+    ("Uva", "Badulla", &[
+        "Badulla", "Bandarawela", "Ella", "Haldummulla", "Hali-Ela", "Haputale", "Kandaketiya", "Lunugala",
+        "Mahiyanganaya", "Meegahakivula", "Passara", "Rideemaliyadda", "Soranathota", "Uva Paranagama",
+        "Welimada",
+    ]),
+    ("Uva", "Monaragala", &[
+        "Badalkumbura", "Bibile", "Buttala", "Katharagama", "Madulla", "Medagama", "Monaragala", "Sevanagala",
+        "Siyambalanduwa", "Thanamalvila", "Wellawaya",
+    ]),
+    ("Sabaragamuwa", "Ratnapura", &[
+        "Ayagama", "Balangoda", "Eheliyagoda", "Elapatha", "Embilipitiya", "Godakawela", "Imbulpe", "Kahawatta",
+        "Kalawana", "Kiriella", "Kolonna", "Kuruvita", "Nivithigala", "Opanayaka", "Pelmadulla", "Ratnapura",
+        "Weligepola",
+    ]),
+    ("Sabaragamuwa", "Kegalle", &[
+        "Aranayaka", "Bulathkohupitiya", "Dehiovita", "Deraniyagala", "Galigamuwa", "Kegalle", "Mawanella",
+        "Rambukkana", "Ruwanwella", "Warakapola", "Yatiyantota",
+    ]),
+];
+
+// Residence report groupings, each narrower than the one before
+const RESIDENCE_LEVELS: [&str; 3] = ["district", "ds_division", "gn_division"];
+
+// Loads the districts and DS divisions. Run on every start; rows already
+// present are left alone.
+pub(crate) fn seed_administrative_divisions(conn: &Connection) -> rusqlite::Result<()> {
+    for (province, district, divisions) in ADMINISTRATIVE_DIVISIONS {
+        conn.execute(
+            "INSERT OR IGNORE INTO districts (name, province) VALUES (?1, ?2)",
+            [district, province],
+        )?;
+        for division in divisions {
+            conn.execute(
+                "INSERT OR IGNORE INTO ds_divisions (district, name) VALUES (?1, ?2)",
+                [district, *division],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn ds_division_id(conn: &Connection, district: &str, ds_division: &str) -> rusqlite::Result<Option<i64>> {
+    conn.query_row(
+        "SELECT id FROM ds_divisions WHERE district = ?1 AND name = ?2",
+        [district, ds_division],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+// Looks a typed name up in a master list, ignoring case, and returns the
+// listed spelling
+fn canonical_name(conn: &Connection, sql: &str, params: &[&dyn rusqlite::ToSql]) -> Option<String> {
+    conn.query_row(sql, params, |row| row.get(0)).ok()
+}
+
+// Checks the employee's district, DS division and GN division against the
+// master lists, replacing each with its listed spelling. A DS division must
+// lie in the district and a GN division in the DS division; GN divisions are
+// only checked once some have been added for that DS division.
+pub fn address_errors(conn: &Connection, employee: &mut Employee) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+    let mut fail = |field: &str, message: String| errors.push(FieldError { field: field.to_string(), message });
+    for slot in [&mut employee.district, &mut employee.ds_division, &mut employee.gn_division] {
+        *slot = slot.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+    }
+    
+    let district = match employee.district.as_deref() {
+        None => None,
+        Some(typed) => {
+            let found = canonical_name(conn, "SELECT name FROM districts WHERE name = ?1 COLLATE NOCASE", &[&typed]);
+            if found.is_none() {
+                fail("district", format!("Unknown district '{}'", typed));
+            }
+            found
+        }
+    };
+    let ds_division = match (employee.ds_division.as_deref(), district.as_deref()) {
+        (None, _) => None,
+        (Some(_), None) if employee.district.is_none() => {
+            fail("ds_division", "Choose the district before the DS division".to_string());
+            None
+        }
+        (Some(_), None) => None,
+        (Some(typed), Some(district)) => {
+            let found = canonical_name(
+                conn,
+                "SELECT name FROM ds_divisions WHERE district = ?1 AND name = ?2 COLLATE NOCASE",
+                &[&district, &typed],
+            );
+            if found.is_none() {
+                fail("ds_division", format!("'{}' is not a DS division of {} district", typed, district));
+            }
+            found
+        }
+    };
+    let gn_division = match (employee.gn_division.as_deref(), district.as_deref(), ds_division.as_deref()) {
+        (None, _, _) => None,
+        (Some(_), _, None) if employee.ds_division.is_none() => {
+            fail("gn_division", "Choose the DS division before the GN division".to_string());
+            None
+        }
+        (Some(typed), Some(district), Some(ds_division)) => {
+            let id = ds_division_id(conn, district, ds_division).ok().flatten();
+            let listed: bool = id
+                .and_then(|id| {
+                    conn.query_row("SELECT EXISTS(SELECT 1 FROM gn_divisions WHERE ds_division_id = ?1)", [id], |row| row.get(0))
+                        .ok()
+                })
+                .unwrap_or(false);
+            if !listed {
+                Some(typed.to_string())
+            } else {
+                let found = canonical_name(
+                    conn,
+                    "SELECT name FROM gn_divisions WHERE ds_division_id = ?1 AND name = ?2 COLLATE NOCASE",
+                    &[&id, &typed],
+                );
+                if found.is_none() {
+                    fail("gn_division", format!("'{}' is not a GN division of {}", typed, ds_division));
+                }
+                found
+            }
+        }
+        (Some(_), _, _) => None,
+    };
+    
+    if errors.is_empty() {
+        employee.district = district;
+        employee.ds_division = ds_division;
+        employee.gn_division = gn_division;
+    }
+    errors
+}
+
+#[tauri::command]
+pub fn get_districts(db: State<'_, DbConnection>) -> Result<Vec<District>, HrmError> {
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare("SELECT name, province FROM districts ORDER BY name")?;
+    let districts = stmt
+        .query_map([], |row| Ok(District { name: row.get(0)?, province: row.get(1)? }))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(districts)
+}
+
+#[tauri::command]
+pub fn get_ds_divisions(district: String, db: State<'_, DbConnection>) -> Result<Vec<String>, HrmError> {
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare("SELECT name FROM ds_divisions WHERE district = ?1 ORDER BY name")?;
+    let divisions = stmt
+        .query_map([&district], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(divisions)
+}
+
+#[tauri::command]
+pub fn get_gn_divisions(
+    district: String,
+    ds_division: String,
+    db: State<'_, DbConnection>,
+) -> Result<Vec<GnDivision>, HrmError> {
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(
+        "SELECT g.id, g.name, g.code FROM gn_divisions g
+         JOIN ds_divisions d ON d.id = g.ds_division_id
+         WHERE d.district = ?1 AND d.name = ?2 ORDER BY g.name",
+    )?;
+    let divisions = stmt
+        .query_map([&district, &ds_division], |row| {
+            Ok(GnDivision { id: row.get(0)?, name: row.get(1)?, code: row.get(2)? })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(divisions)
+}
+
+// Adds a Grama Niladhari division under a DS division. Once a DS division
+// has any, employees living there must be given one from its list.
+#[tauri::command]
+pub fn add_gn_division(
+    district: String,
+    ds_division: String,
+    name: String,
+    code: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_manage_settings {
+        return Err(HrmError::permission_denied("Permission denied. You cannot change system settings."));
+    }
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(HrmError::field("name", "GN division name is required"));
+    }
+    let code = code.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+    
+    let conn = db.0.lock()?;
+    let ds_division_id = ds_division_id(&conn, &district, &ds_division)?
+        .ok_or_else(|| HrmError::not_found(format!("DS division {} in {} district not found", ds_division, district)))?;
+    conn.execute(
+        "INSERT INTO gn_divisions (ds_division_id, name, code) VALUES (?1, ?2, ?3)",
+        rusqlite::params![ds_division_id, name, code],
+    )
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE constraint") {
+            HrmError::field("name", format!("{} already has a GN division named {}", ds_division, name))
+        } else {
+            HrmError::from(e)
+        }
+    })?;
+    let id = conn.last_insert_rowid();
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "SETTING",
+        Some(&format!("gn_division:{}", id)),
+        None,
+        Some(name),
+        Some(&format!("Added GN division {} to {}, {} district", name, ds_division, district)),
+    );
+    
+    Ok(id)
+}
+
+// Where active employees live, counted by district, DS division or GN
+// division (`level`). Employees with no address recorded at that level are
+// counted together with the missing parts left empty.
+#[tauri::command]
+pub fn get_residence_distribution(
+    level: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<ResidenceCount>, HrmError> {
+    logged_in_user(&current_user)?;
+    let depth = RESIDENCE_LEVELS
+        .iter()
+        .position(|l| *l == level)
+        .ok_or_else(|| HrmError::field("level", "Level must be district, ds_division or gn_division"))?;
+    let grouped = RESIDENCE_LEVELS[..=depth].join(", ");
+    let unused = vec!["NULL"; RESIDENCE_LEVELS.len() - depth - 1];
+    let selected = [grouped.as_str()].into_iter().chain(unused).collect::<Vec<_>>().join(", ");
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}, COUNT(*) FROM employees
+         WHERE working_status IN (SELECT code FROM working_statuses WHERE counts_as_active = 1)
+         GROUP BY {1} ORDER BY COUNT(*) DESC, {1}",
+        selected, grouped
+    ))?;
+    let counts = stmt
+        .query_map([], |row| {
+            Ok(ResidenceCount {
+                district: row.get(0)?,
+                ds_division: row.get(1)?,
+                gn_division: row.get(2)?,
+                count: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(counts)
+}
@@ -0,0 +1,166 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::AnonymizationCandidate;
+use crate::settings_commands::get_setting_i64;
+use crate::{AppDataDir, CurrentUser, DbConnection};
+use rusqlite::Connection;
+use std::fs;
+use std::path::Path;
+use tauri::State;
+
+// Years after resignation before personal data may be anonymized, unless
+// overridden by the anonymize_after_years setting
+const DEFAULT_RETENTION_YEARS: i64 = 7;
+
+// The phrase a user must type to confirm anonymizing an employee
+fn anonymize_phrase(epf_number: &str) -> String {
+    format!("ANONYMIZE {}", epf_number)
+}
+
+fn retention_years(conn: &Connection) -> i64 {
+    get_setting_i64(conn, "anonymize_after_years").unwrap_or(DEFAULT_RETENTION_YEARS)
+}
+
+// Resigned, not yet anonymized employees who resigned before now shifted by
+// `age` (an SQLite date modifier such as "-7 years"), oldest first
+pub(crate) fn anonymization_due(conn: &Connection, age: &str) -> rusqlite::Result<Vec<AnonymizationCandidate>> {
+    let mut stmt = conn.prepare(
+        "SELECT epf_number, name_with_initials, department, date_of_resign FROM employees
+         WHERE working_status = 'resign' AND anonymized_at IS NULL
+           AND date_of_resign IS NOT NULL AND date_of_resign != ''
+           AND date_of_resign < date('now', 'localtime', ?1)
+         ORDER BY date_of_resign",
+    )?;
+    let due = stmt
+        .query_map([age], |row| {
+            Ok(AnonymizationCandidate {
+                epf_number: row.get(0)?,
+                name_with_initials: row.get(1)?,
+                department: row.get(2)?,
+                date_of_resign: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(due)
+}
+
+// Irreversibly replaces an employee's personal details with placeholders.
+// Kept for headcount history: EPF number, department, cader, designation,
+// allocation, dates of joining/resigning, status and year of birth. Photos
+// and free-text records about the person are removed, and employee values
+// in the live audit log are cleared (archived audit files are not touched).
+pub(crate) fn anonymize(conn: &mut Connection, app_dir: &Path, epf_number: &str) -> Result<(), HrmError> {
+    let tx = conn.transaction()?;
+    let placeholder = format!("Former employee {}", epf_number);
+    tx.execute(
+        "UPDATE employees SET
+            name_with_initials = ?1, full_name = ?1, nic = NULL,
+            dob = CASE WHEN date(dob) IS NULL THEN NULL ELSE substr(dob, 1, 4) || '-01-01' END,
+            police_area = NULL, mobile_1 = NULL, mobile_2 = NULL, address = NULL,
+            district = NULL, ds_division = NULL, gn_division = NULL,
+            marital_status = NULL, image_path = NULL, anonymized_at = datetime('now', 'localtime')
+         WHERE epf_number = ?2",
+        rusqlite::params![placeholder, epf_number],
+    )?;
+    
+    let cleanup = [
+        "DELETE FROM employee_custom_values WHERE epf_number = ?1",
+        "DELETE FROM employee_notes WHERE epf_number = ?1",
+        "DELETE FROM image_metadata WHERE epf_number = ?1",
+        "DELETE FROM employee_medical WHERE epf_number = ?1",
+        "DELETE FROM medical_fitness_certificates WHERE epf_number = ?1",
+        "DELETE FROM employee_licenses WHERE epf_number = ?1",
+        "UPDATE exit_interviews SET comments = NULL WHERE epf_number = ?1",
+        "UPDATE insurance_dependents SET name = 'Dependent', dob = NULL
+         WHERE enrollment_id IN (SELECT id FROM insurance_enrollments WHERE epf_number = ?1)",
+        "UPDATE insurance_claims SET claimant = NULL, notes = NULL
+         WHERE enrollment_id IN (SELECT id FROM insurance_enrollments WHERE epf_number = ?1)",
+        "UPDATE candidates SET full_name = 'Former candidate', name_with_initials = NULL, nic = NULL,
+                               dob = NULL, mobile = NULL, address = NULL, notes = NULL
+         WHERE employee_epf_number = ?1",
+        "UPDATE audit_logs SET old_value = NULL, new_value = NULL
+         WHERE entity_type = 'EMPLOYEE' AND entity_id = ?1",
+    ];
+    for sql in cleanup.iter() {
+        tx.execute(sql, [epf_number])?;
+    }
+    tx.commit()?;
+    
+    // Photos go last so a failed database update leaves nothing half done
+    let image_dir = app_dir.join("employee_images").join(epf_number);
+    if image_dir.exists() {
+        fs::remove_dir_all(&image_dir).map_err(|e| HrmError::io(format!("Failed to delete photos: {}", e)))?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_anonymization_due(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<AnonymizationCandidate>, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_delete_employees => {}
+        Some(_) => return Err(HrmError::permission_denied("Permission denied. You cannot anonymize employees.")),
+        None => return Err(HrmError::not_logged_in()),
+    }
+    drop(user_lock);
+    
+    let conn = db.0.lock()?;
+    anonymization_due(&conn, &format!("-{} years", retention_years(&conn))).map_err(HrmError::from)
+}
+
+// Anonymizes one departed employee once the retention period has passed.
+// The user must type "ANONYMIZE <epf number>" to confirm.
+#[tauri::command]
+pub fn anonymize_employee(
+    epf_number: String,
+    confirmation_phrase: String,
+    app_data_dir: State<'_, AppDataDir>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user_lock = current_user.0.lock()?;
+    let (user_id, username) = match &*user_lock {
+        Some(session) if session.permissions.can_delete_employees => (session.user_id, session.username.clone()),
+        Some(_) => return Err(HrmError::permission_denied("Permission denied. You cannot anonymize employees.")),
+        None => return Err(HrmError::not_logged_in()),
+    };
+    drop(user_lock);
+    
+    let expected_phrase = anonymize_phrase(&epf_number);
+    if confirmation_phrase.trim() != expected_phrase {
+        return Err(HrmError::validation(format!("Confirmation phrase does not match. Type \"{}\" to confirm.", expected_phrase)));
+    }
+    
+    let mut conn = db.0.lock()?;
+    let years = retention_years(&conn);
+    let due = anonymization_due(&conn, &format!("-{} years", years))?;
+    let candidate = due.into_iter().find(|c| c.epf_number == epf_number).ok_or_else(|| {
+        format!(
+            "Employee {} cannot be anonymized: only employees who resigned more than {} years ago and are not already anonymized qualify",
+            epf_number, years
+        )
+    })?;
+    
+    anonymize(&mut conn, &app_data_dir.0, &epf_number)?;
+    
+    log_audit_action(
+        &conn,
+        Some(user_id),
+        &username,
+        "ANONYMIZE",
+        "EMPLOYEE",
+        Some(&epf_number),
+        None,
+        None,
+        Some(&format!(
+            "Anonymized personal data of employee {} (resigned {})",
+            epf_number,
+            candidate.date_of_resign.as_deref().unwrap_or("")
+        )),
+    );
+    
+    Ok(())
+}
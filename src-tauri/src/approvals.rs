@@ -0,0 +1,619 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{ApprovalComment, ApprovalDelegation, ApprovalRequest, ApprovalStep, UserSession};
+use crate::notification_commands::notify_user;
+use crate::validation::parse_iso_date;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::{Connection, OptionalExtension};
+use tauri::State;
+
+// Workflows that go through approval, as (request type, label, approver
+// chain). Each step of the chain is the permission its approver needs; a
+// request is approved once every step has approved it, and rejected as soon
+// as any step rejects it.
+pub const APPROVAL_CHAINS: [(&str, &str, &[&str]); 4] = [
+    ("attendance_correction", "Attendance correction", &["can_approve_attendance"]),
+    ("festival_advance", "Festival advance", &["can_run_payroll"]),
+    ("leave", "Leave", &["can_approve_leave"]),
+    ("overtime", "Overtime", &["can_approve_attendance"]),
+];
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+fn approval_chain(request_type: &str) -> Result<(&'static str, &'static [&'static str]), HrmError> {
+    APPROVAL_CHAINS
+        .iter()
+        .find(|(t, _, _)| *t == request_type)
+        .map(|(_, label, chain)| (*label, *chain))
+        .ok_or_else(|| HrmError::internal(format!("Unknown approval type '{}'", request_type)))
+}
+
+fn holds_permission(user: &UserSession, permission: &str) -> bool {
+    serde_json::to_value(&user.permissions)
+        .ok()
+        .and_then(|p| p.get(permission).and_then(|v| v.as_bool()))
+        .unwrap_or(false)
+}
+
+// Delegations in force today, as a condition on a delegator `o` and delegate `d`
+const ACTIVE_DELEGATION: &str = "d.revoked_at IS NULL
+     AND date('now', 'localtime') BETWEEN d.starts_on AND d.ends_on
+     AND o.is_active = 1 AND o.deleted_at IS NULL";
+
+// Whom the user decides a step needing `permission` for: themselves (Some(None))
+// when they hold it, otherwise an absent approver who has delegated their
+// approvals to them today (Some(Some(id))). None when they cannot decide it.
+fn acting_for(conn: &Connection, user: &UserSession, permission: &str) -> rusqlite::Result<Option<Option<i32>>> {
+    if holds_permission(user, permission) {
+        return Ok(Some(None));
+    }
+    let delegator = conn
+        .query_row(
+            &format!(
+                "SELECT o.id FROM approval_delegations d JOIN users o ON o.id = d.delegator_id
+                 WHERE d.delegate_id = ?1 AND o.{} = 1 AND {}
+                 ORDER BY d.starts_on LIMIT 1",
+                permission, ACTIVE_DELEGATION
+            ),
+            [user.user_id],
+            |row| row.get::<_, i32>(0),
+        )
+        .optional()?;
+    Ok(delegator.map(Some))
+}
+
+// Whether the user can decide steps needing `permission`, themselves or for
+// an absent approver. Workflows check this before anything else, so users
+// with no part in approving are turned away whatever state the item is in.
+pub(crate) fn may_approve(conn: &Connection, user: &UserSession, permission: &str) -> Result<bool, HrmError> {
+    Ok(acting_for(conn, user, permission)?.is_some())
+}
+
+// Tells the active users who can approve the request's current step,
+// including anyone covering for them through a delegation
+fn notify_approvers(conn: &Connection, approval_id: i64, permission: &str, requested_by: Option<i32>, label: &str, summary: &str) {
+    let _ = conn.execute(
+        &format!(
+            "INSERT INTO notifications (user_id, title, message, severity, entity_type, entity_id)
+             SELECT id, ?1, ?2, 'info', 'APPROVAL', ?3 FROM users
+             WHERE is_active = 1 AND deleted_at IS NULL AND id IS NOT ?4
+               AND ({0} = 1 OR id IN (SELECT d.delegate_id FROM approval_delegations d JOIN users o ON o.id = d.delegator_id
+                                      WHERE o.{0} = 1 AND {1}))",
+            permission, ACTIVE_DELEGATION
+        ),
+        rusqlite::params![format!("{} awaiting approval", label), summary, approval_id.to_string(), requested_by],
+    );
+}
+
+fn insert_approval(
+    conn: &Connection,
+    request_type: &str,
+    chain: &[&str],
+    entity_id: &str,
+    epf_number: Option<&str>,
+    summary: &str,
+    requested_by: Option<i32>,
+) -> rusqlite::Result<i64> {
+    conn.execute(
+        "INSERT INTO approval_requests (request_type, entity_id, epf_number, summary, requested_by)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![request_type, entity_id, epf_number, summary, requested_by],
+    )?;
+    let approval_id = conn.last_insert_rowid();
+    for (index, permission) in chain.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO approval_steps (approval_id, step_number, approver_permission) VALUES (?1, ?2, ?3)",
+            rusqlite::params![approval_id, index as i64 + 1, permission],
+        )?;
+    }
+    Ok(approval_id)
+}
+
+// Starts approval of a workflow item (entity_id is its ID in the workflow's
+// own table) and notifies the first approvers
+pub(crate) fn open_approval(
+    conn: &Connection,
+    request_type: &str,
+    entity_id: &str,
+    epf_number: Option<&str>,
+    summary: &str,
+    requester: &UserSession,
+) -> Result<i64, HrmError> {
+    let (label, chain) = approval_chain(request_type)?;
+    let approval_id = insert_approval(conn, request_type, chain, entity_id, epf_number, summary, Some(requester.user_id))?;
+    notify_approvers(conn, approval_id, chain[0], Some(requester.user_id), label, summary);
+    Ok(approval_id)
+}
+
+// The pending approval of a workflow item as (id, current step, requester)
+fn pending_approval(conn: &Connection, request_type: &str, entity_id: &str) -> rusqlite::Result<Option<(i64, i64, Option<i32>)>> {
+    conn.query_row(
+        "SELECT id, current_step, requested_by FROM approval_requests
+         WHERE request_type = ?1 AND entity_id = ?2 AND status = 'pending'",
+        [request_type, entity_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )
+    .optional()
+}
+
+// Checks that the user may decide the item's current step. Returns true when
+// approving now would complete the chain, so the workflow should carry out
+// the approval; false when further steps remain.
+pub(crate) fn check_approver(conn: &Connection, request_type: &str, entity_id: &str, user: &UserSession) -> Result<bool, HrmError> {
+    let (_, chain) = approval_chain(request_type)?;
+    // Every pending item has an approval request (older ones are backfilled
+    // at startup), so one without is not decided here
+    let Some((_, current_step, requested_by)) = pending_approval(conn, request_type, entity_id)? else {
+        return Err(HrmError::conflict("This item has no pending approval request"));
+    };
+    if requested_by == Some(user.user_id) {
+        return Err(HrmError::permission_denied("You cannot approve your own request"));
+    }
+    let permission = chain.get(current_step as usize - 1).copied().unwrap_or_default();
+    if acting_for(conn, user, permission)?.is_none() {
+        return Err(HrmError::permission_denied(format!(
+            "Permission denied. Step {} of this approval needs {}.",
+            current_step, permission
+        )));
+    }
+    Ok(current_step as usize >= chain.len())
+}
+
+// Records the user's decision on the item's current step, after
+// check_approver. An approval moves the request to the next step and
+// notifies its approvers; a rejection or the last approval closes it.
+pub(crate) fn record_decision(
+    conn: &Connection,
+    request_type: &str,
+    entity_id: &str,
+    user: &UserSession,
+    approve: bool,
+    comment: Option<&str>,
+) -> Result<(), HrmError> {
+    let (label, chain) = approval_chain(request_type)?;
+    let Some((approval_id, current_step, requested_by)) = pending_approval(conn, request_type, entity_id)? else {
+        return Ok(());
+    };
+    let permission = chain.get(current_step as usize - 1).copied().unwrap_or_default();
+    let on_behalf_of = acting_for(conn, user, permission)?.flatten();
+    conn.execute(
+        "UPDATE approval_steps SET status = ?1, decided_by = ?2, on_behalf_of = ?3, decided_at = CURRENT_TIMESTAMP,
+                                   comment = ?4
+         WHERE approval_id = ?5 AND step_number = ?6",
+        rusqlite::params![
+            if approve { "approved" } else { "rejected" },
+            user.user_id,
+            on_behalf_of,
+            comment,
+            approval_id,
+            current_step,
+        ],
+    )?;
+    if approve && (current_step as usize) < chain.len() {
+        conn.execute(
+            "UPDATE approval_requests SET current_step = current_step + 1 WHERE id = ?1",
+            [approval_id],
+        )?;
+        let summary: String = conn.query_row("SELECT summary FROM approval_requests WHERE id = ?1", [approval_id], |row| row.get(0))?;
+        notify_approvers(conn, approval_id, chain[current_step as usize], requested_by, label, &summary);
+    } else {
+        conn.execute(
+            "UPDATE approval_requests SET status = ?1, decided_at = CURRENT_TIMESTAMP WHERE id = ?2",
+            rusqlite::params![if approve { "approved" } else { "rejected" }, approval_id],
+        )?;
+        conn.execute(
+            "UPDATE approval_steps SET status = 'skipped' WHERE approval_id = ?1 AND status = 'pending'",
+            [approval_id],
+        )?;
+    }
+    Ok(())
+}
+
+// Opens approvals for workflow items that were already pending before the
+// approval engine, so they show among pending approvals
+pub fn backfill_pending_approvals(conn: &Connection) -> rusqlite::Result<()> {
+    let mut stmt = conn.prepare(
+        "SELECT 'attendance_correction', CAST(c.id AS TEXT), c.epf_number,
+                'Attendance correction for ' || c.epf_number || ' at ' || c.new_time || ': ' || c.reason, c.requested_by
+         FROM attendance_corrections c WHERE c.status = 'pending'
+         UNION ALL
+         SELECT 'festival_advance', CAST(a.id AS TEXT), a.epf_number,
+                a.festival || ' advance of ' || printf('%.2f', a.amount) || ' for ' || a.epf_number,
+                (SELECT u.id FROM users u WHERE u.username = a.requested_by)
+         FROM festival_advances a WHERE a.status = 'pending'",
+    )?;
+    let pending = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<i32>>(4)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (request_type, entity_id, epf_number, summary, requested_by) in pending {
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM approval_requests WHERE request_type = ?1 AND entity_id = ?2)",
+            [&request_type, &entity_id],
+            |row| row.get(0),
+        )?;
+        let chain = APPROVAL_CHAINS.iter().find(|(t, _, _)| *t == request_type).map(|(_, _, chain)| *chain);
+        if let (false, Some(chain)) = (exists, chain) {
+            insert_approval(conn, &request_type, chain, &entity_id, Some(&epf_number), &summary, requested_by)?;
+        }
+    }
+    Ok(())
+}
+
+const APPROVAL_COLUMNS: &str = "r.id, r.request_type, r.entity_id, r.epf_number, e.name_with_initials, e.department, r.summary,
+                                r.requested_by, u.username, r.status, r.current_step, r.created_at, r.decided_at";
+
+fn approval_from_row(row: &rusqlite::Row) -> rusqlite::Result<ApprovalRequest> {
+    let request_type: String = row.get(1)?;
+    Ok(ApprovalRequest {
+        id: row.get(0)?,
+        label: approval_chain(&request_type).map(|(label, _)| label.to_string()).unwrap_or_else(|_| request_type.clone()),
+        request_type,
+        entity_id: row.get(2)?,
+        epf_number: row.get(3)?,
+        employee_name: row.get(4)?,
+        department: row.get(5)?,
+        summary: row.get(6)?,
+        requested_by: row.get(7)?,
+        requested_by_name: row.get(8)?,
+        status: row.get(9)?,
+        current_step: row.get(10)?,
+        created_at: row.get(11)?,
+        decided_at: row.get(12)?,
+        steps: Vec::new(),
+        comments: Vec::new(),
+    })
+}
+
+// Pending requests whose current step the user can approve, themselves or
+// through a delegation, oldest first.
+// Decisions are made through each workflow's own approve and reject commands.
+#[tauri::command]
+pub fn get_my_pending_approvals(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<ApprovalRequest>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}, s.approver_permission
+         FROM approval_requests r
+         JOIN approval_steps s ON s.approval_id = r.id AND s.step_number = r.current_step
+         LEFT JOIN employees e ON e.epf_number = r.epf_number
+         LEFT JOIN users u ON u.id = r.requested_by
+         WHERE r.status = 'pending' AND r.requested_by IS NOT ?1
+         ORDER BY r.created_at, r.id",
+        APPROVAL_COLUMNS
+    ))?;
+    let rows = stmt
+        .query_map([user.user_id], |row| Ok((approval_from_row(row)?, row.get::<_, String>(13)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut can_decide = Vec::new();
+    for (_, _, chain) in APPROVAL_CHAINS {
+        for permission in chain {
+            if acting_for(&conn, &user, permission)?.is_some() {
+                can_decide.push(*permission);
+            }
+        }
+    }
+    Ok(rows
+        .into_iter()
+        .filter(|(_, permission)| can_decide.contains(&permission.as_str()))
+        .map(|(approval, _)| approval)
+        .filter(|a| a.epf_number.is_none() || user.can_access_department(a.department.as_deref()))
+        .collect())
+}
+
+// A request with its approver chain and comments
+#[tauri::command]
+pub fn get_approval(
+    approval_id: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<ApprovalRequest, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let mut approval = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM approval_requests r
+                 LEFT JOIN employees e ON e.epf_number = r.epf_number
+                 LEFT JOIN users u ON u.id = r.requested_by
+                 WHERE r.id = ?1",
+                APPROVAL_COLUMNS
+            ),
+            [approval_id],
+            approval_from_row,
+        )
+        .optional()?
+        .ok_or_else(|| HrmError::not_found("Approval request not found"))?;
+    if approval.epf_number.is_some() && !user.can_access_department(approval.department.as_deref()) {
+        return Err(HrmError::permission_denied("Permission denied. This request is outside your departments."));
+    }
+    
+    let mut stmt = conn.prepare(
+        "SELECT s.step_number, s.approver_permission, s.status, s.decided_by, u.username, s.decided_at, s.comment,
+                s.on_behalf_of, b.username
+         FROM approval_steps s
+         LEFT JOIN users u ON u.id = s.decided_by
+         LEFT JOIN users b ON b.id = s.on_behalf_of
+         WHERE s.approval_id = ?1 ORDER BY s.step_number",
+    )?;
+    approval.steps = stmt
+        .query_map([approval_id], |row| {
+            Ok(ApprovalStep {
+                step_number: row.get(0)?,
+                approver_permission: row.get(1)?,
+                status: row.get(2)?,
+                decided_by: row.get(3)?,
+                decided_by_name: row.get(4)?,
+                decided_at: row.get(5)?,
+                comment: row.get(6)?,
+                on_behalf_of: row.get(7)?,
+                on_behalf_of_name: row.get(8)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, user_id, username, comment, created_at FROM approval_comments WHERE approval_id = ?1 ORDER BY id",
+    )?;
+    approval.comments = stmt
+        .query_map([approval_id], |row| {
+            Ok(ApprovalComment {
+                id: row.get(0)?,
+                user_id: row.get(1)?,
+                username: row.get(2)?,
+                comment: row.get(3)?,
+                created_at: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(approval)
+}
+
+#[tauri::command]
+pub fn add_approval_comment(
+    approval_id: i64,
+    comment: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    let comment = comment.trim();
+    if comment.is_empty() {
+        return Err(HrmError::field("comment", "Comment cannot be empty"));
+    }
+    
+    let conn = db.0.lock()?;
+    let requested_by: Option<i32> = conn
+        .query_row("SELECT requested_by FROM approval_requests WHERE id = ?1", [approval_id], |row| row.get(0))
+        .optional()?
+        .ok_or_else(|| HrmError::not_found("Approval request not found"))?;
+    let is_approver: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM approval_steps WHERE approval_id = ?1 AND decided_by = ?2)",
+        rusqlite::params![approval_id, user.user_id],
+        |row| row.get(0),
+    )?;
+    let may_approve = conn
+        .prepare("SELECT approver_permission FROM approval_steps WHERE approval_id = ?1")?
+        .query_map([approval_id], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?
+        .iter()
+        .any(|permission| holds_permission(&user, permission));
+    if requested_by != Some(user.user_id) && !is_approver && !may_approve {
+        return Err(HrmError::permission_denied("Only the requester and approvers can comment on this request"));
+    }
+    
+    conn.execute(
+        "INSERT INTO approval_comments (approval_id, user_id, username, comment) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![approval_id, user.user_id, user.username, comment],
+    )?;
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "COMMENT",
+        "APPROVAL",
+        Some(&approval_id.to_string()),
+        None,
+        Some(comment),
+        Some(&format!("Commented on approval request {}", approval_id)),
+    );
+    
+    Ok(())
+}
+
+const DELEGATION_COLUMNS: &str = "d.id, d.delegator_id, o.username, d.delegate_id, t.username, d.starts_on, d.ends_on, d.reason,
+                                  d.created_by, d.created_at, d.revoked_at";
+
+fn delegation_from_row(row: &rusqlite::Row) -> rusqlite::Result<ApprovalDelegation> {
+    Ok(ApprovalDelegation {
+        id: row.get(0)?,
+        delegator_id: row.get(1)?,
+        delegator_name: row.get(2)?,
+        delegate_id: row.get(3)?,
+        delegate_name: row.get(4)?,
+        starts_on: row.get(5)?,
+        ends_on: row.get(6)?,
+        reason: row.get(7)?,
+        created_by: row.get(8)?,
+        created_at: row.get(9)?,
+        revoked_at: row.get(10)?,
+    })
+}
+
+// Delegations the user gave or received, or every delegation for user
+// managers, latest first
+#[tauri::command]
+pub fn get_approval_delegations(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<ApprovalDelegation>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM approval_delegations d
+         JOIN users o ON o.id = d.delegator_id
+         JOIN users t ON t.id = d.delegate_id
+         WHERE ?1 OR d.delegator_id = ?2 OR d.delegate_id = ?2
+         ORDER BY d.starts_on DESC, d.id DESC",
+        DELEGATION_COLUMNS
+    ))?;
+    let delegations = stmt
+        .query_map(rusqlite::params![user.permissions.can_manage_users, user.user_id], delegation_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(delegations)
+}
+
+// Hands the delegator's approvals (the current user's unless given, which
+// needs can_manage_users) to the delegate from starts_on to ends_on
+// inclusive. The delegate can then decide any step the delegator could.
+#[tauri::command]
+pub fn create_approval_delegation(
+    delegator_id: Option<i32>,
+    delegate_id: i32,
+    starts_on: String,
+    ends_on: String,
+    reason: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    let delegator_id = delegator_id.unwrap_or(user.user_id);
+    if delegator_id != user.user_id && !user.permissions.can_manage_users {
+        return Err(HrmError::permission_denied("Permission denied. You can only delegate your own approvals."));
+    }
+    if delegate_id == delegator_id {
+        return Err(HrmError::field("delegate_id", "Choose someone other than the absent approver"));
+    }
+    let starts_on = starts_on.trim().to_string();
+    let ends_on = ends_on.trim().to_string();
+    parse_iso_date(&starts_on).ok_or_else(|| HrmError::field("starts_on", "Start date must be a valid date (YYYY-MM-DD)"))?;
+    parse_iso_date(&ends_on).ok_or_else(|| HrmError::field("ends_on", "End date must be a valid date (YYYY-MM-DD)"))?;
+    if ends_on < starts_on {
+        return Err(HrmError::field("ends_on", "End date must be on or after the start date"));
+    }
+    let reason = reason.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+    
+    let conn = db.0.lock()?;
+    let names: Vec<(i32, String, bool)> = conn
+        .prepare("SELECT id, username, is_active = 1 AND deleted_at IS NULL FROM users WHERE id IN (?1, ?2)")?
+        .query_map([delegator_id, delegate_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let name_of = |id: i32| names.iter().find(|(uid, _, _)| *uid == id);
+    let delegator = name_of(delegator_id).ok_or_else(|| HrmError::not_found("Approver not found"))?;
+    let delegate = name_of(delegate_id).ok_or_else(|| HrmError::not_found("Delegate not found"))?;
+    if !delegate.2 {
+        return Err(HrmError::field("delegate_id", "The delegate's account is not active"));
+    }
+    let overlapping: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM approval_delegations
+                       WHERE delegator_id = ?1 AND revoked_at IS NULL AND starts_on <= ?3 AND ends_on >= ?2)",
+        rusqlite::params![delegator_id, starts_on, ends_on],
+        |row| row.get(0),
+    )?;
+    if overlapping {
+        return Err(HrmError::conflict(format!(
+            "{}'s approvals are already delegated for part of this period",
+            delegator.1
+        )));
+    }
+    
+    conn.execute(
+        "INSERT INTO approval_delegations (delegator_id, delegate_id, starts_on, ends_on, reason, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![delegator_id, delegate_id, starts_on, ends_on, reason, user.username],
+    )?;
+    let id = conn.last_insert_rowid();
+    
+    let details = format!("Delegated approvals of {} to {} from {} to {}", delegator.1, delegate.1, starts_on, ends_on);
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "APPROVAL_DELEGATION",
+        Some(&id.to_string()),
+        None,
+        None,
+        Some(&details),
+    );
+    notify_user(
+        &conn,
+        delegate_id,
+        "Approvals delegated to you",
+        &format!("You will approve on behalf of {} from {} to {}", delegator.1, starts_on, ends_on),
+        "info",
+        Some("APPROVAL_DELEGATION"),
+        Some(&id.to_string()),
+    );
+    
+    Ok(id)
+}
+
+// Ends a delegation early. Open to the delegator, the delegate and user
+// managers.
+#[tauri::command]
+pub fn revoke_approval_delegation(
+    delegation_id: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let delegation = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM approval_delegations d
+                 JOIN users o ON o.id = d.delegator_id
+                 JOIN users t ON t.id = d.delegate_id
+                 WHERE d.id = ?1",
+                DELEGATION_COLUMNS
+            ),
+            [delegation_id],
+            delegation_from_row,
+        )
+        .optional()?
+        .ok_or_else(|| HrmError::not_found("Delegation not found"))?;
+    if !user.permissions.can_manage_users && user.user_id != delegation.delegator_id && user.user_id != delegation.delegate_id {
+        return Err(HrmError::permission_denied("Permission denied. You cannot revoke this delegation."));
+    }
+    if delegation.revoked_at.is_some() {
+        return Err(HrmError::conflict("This delegation has already been revoked"));
+    }
+    conn.execute(
+        "UPDATE approval_delegations SET revoked_at = CURRENT_TIMESTAMP WHERE id = ?1",
+        [delegation_id],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "REVOKE",
+        "APPROVAL_DELEGATION",
+        Some(&delegation_id.to_string()),
+        None,
+        None,
+        Some(&format!(
+            "Revoked delegation of {}'s approvals to {}",
+            delegation.delegator_name, delegation.delegate_name
+        )),
+    );
+    
+    Ok(())
+}
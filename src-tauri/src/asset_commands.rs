@@ -0,0 +1,315 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{Asset, AssetIssue, UserSession};
+use crate::validation::parse_iso_date;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use tauri::State;
+
+const CATEGORIES: [&str; 5] = ["uniform", "locker", "tool", "laptop", "other"];
+const RETURN_CONDITIONS: [&str; 3] = ["good", "damaged", "lost"];
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+fn require_edit(user: &UserSession) -> Result<(), HrmError> {
+    if user.permissions.can_edit_employees {
+        Ok(())
+    } else {
+        Err(HrmError::permission_denied("Permission denied. You cannot edit employees."))
+    }
+}
+
+const ISSUE_COLUMNS: &str = "i.id, i.asset_id, a.name, a.category, a.asset_code, i.epf_number, e.name_with_initials,
+                             i.quantity, i.issued_on, i.returned_on, i.return_condition, i.notes, i.issued_by";
+
+fn issue_from_row(row: &rusqlite::Row) -> rusqlite::Result<AssetIssue> {
+    Ok(AssetIssue {
+        id: row.get(0)?,
+        asset_id: row.get(1)?,
+        asset_name: row.get(2)?,
+        category: row.get(3)?,
+        asset_code: row.get(4)?,
+        epf_number: row.get(5)?,
+        name_with_initials: row.get(6)?,
+        quantity: row.get(7)?,
+        issued_on: row.get(8)?,
+        returned_on: row.get(9)?,
+        return_condition: row.get(10)?,
+        notes: row.get(11)?,
+        issued_by: row.get(12)?,
+    })
+}
+
+// Issues matching the WHERE clause, oldest first
+fn query_issues(conn: &Connection, condition: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<AssetIssue>, HrmError> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM asset_issues i
+             JOIN assets a ON a.id = i.asset_id
+             LEFT JOIN employees e ON e.epf_number = i.epf_number
+             WHERE {}
+             ORDER BY i.issued_on, i.id",
+            ISSUE_COLUMNS, condition
+        ))?;
+    let issues = stmt
+        .query_map(params, issue_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(issues)
+}
+
+#[tauri::command]
+pub fn get_assets(include_inactive: bool, db: State<'_, DbConnection>) -> Result<Vec<Asset>, HrmError> {
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT a.id, a.name, a.category, a.asset_code, a.is_returnable, a.is_active,
+                    (SELECT COALESCE(SUM(i.quantity), 0) FROM asset_issues i WHERE i.asset_id = a.id AND i.returned_on IS NULL)
+             FROM assets a
+             WHERE ?1 OR a.is_active = 1
+             ORDER BY a.category, a.name, a.asset_code",
+        )?;
+    let assets = stmt
+        .query_map([include_inactive], |row| {
+            Ok(Asset {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                category: row.get(2)?,
+                asset_code: row.get(3)?,
+                is_returnable: row.get(4)?,
+                is_active: row.get(5)?,
+                outstanding_quantity: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(assets)
+}
+
+#[tauri::command]
+pub fn create_asset(
+    asset: Asset,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_edit(&user)?;
+    if asset.name.trim().is_empty() {
+        return Err(HrmError::validation("Asset name is required"));
+    }
+    if !CATEGORIES.contains(&asset.category.as_str()) {
+        return Err(HrmError::validation("Category must be uniform, locker, tool, laptop or other"));
+    }
+    let asset_code = asset.asset_code.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    
+    let conn = db.0.lock()?;
+    conn.execute(
+        "INSERT INTO assets (name, category, asset_code, is_returnable) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![asset.name.trim(), asset.category, asset_code, asset.is_returnable],
+    )
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE") {
+            format!("Asset code {} is already in use", asset_code.unwrap_or_default())
+        } else {
+            e.to_string()
+        }
+    })?;
+    let asset_id = conn.last_insert_rowid();
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "ASSET",
+        Some(&asset_id.to_string()),
+        None,
+        Some(asset.name.trim()),
+        Some(&format!("Added {} asset: {}", asset.category, asset.name.trim())),
+    );
+    
+    Ok(asset_id)
+}
+
+// Retired assets stay in the history but can no longer be issued
+#[tauri::command]
+pub fn set_asset_active(
+    asset_id: i64,
+    is_active: bool,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_edit(&user)?;
+    
+    let conn = db.0.lock()?;
+    let updated = conn
+        .execute("UPDATE assets SET is_active = ?1 WHERE id = ?2", rusqlite::params![is_active, asset_id])?;
+    if updated == 0 {
+        return Err(HrmError::not_found("Asset not found"));
+    }
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "ASSET",
+        Some(&asset_id.to_string()),
+        None,
+        Some(if is_active { "active" } else { "inactive" }),
+        Some(&format!("{} asset #{}", if is_active { "Reactivated" } else { "Retired" }, asset_id)),
+    );
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_employee_asset_issues(epf_number: String, db: State<'_, DbConnection>) -> Result<Vec<AssetIssue>, HrmError> {
+    let conn = db.0.lock()?;
+    query_issues(&conn, "i.epf_number = ?1", &[&epf_number])
+}
+
+#[tauri::command]
+pub fn issue_asset(
+    epf_number: String,
+    asset_id: i64,
+    quantity: i32,
+    issued_on: String,
+    notes: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_edit(&user)?;
+    parse_iso_date(&issued_on).ok_or("Issue date must be a valid date (YYYY-MM-DD)")?;
+    if quantity < 1 {
+        return Err(HrmError::validation("Quantity must be at least 1"));
+    }
+    
+    let conn = db.0.lock()?;
+    let (name, asset_code, is_active): (String, Option<String>, bool) = conn
+        .query_row(
+            "SELECT name, asset_code, is_active FROM assets WHERE id = ?1",
+            [asset_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|_| HrmError::not_found("Asset not found"))?;
+    if !is_active {
+        return Err(HrmError::validation(format!("{} has been retired and cannot be issued", name)));
+    }
+    
+    // A coded item (locker, laptop) can only be with one employee at a time
+    if let Some(code) = &asset_code {
+        if quantity > 1 {
+            return Err(HrmError::validation(format!("{} {} is a single item", name, code)));
+        }
+        let holder: Option<String> = conn
+            .query_row(
+                "SELECT epf_number FROM asset_issues WHERE asset_id = ?1 AND returned_on IS NULL",
+                [asset_id],
+                |row| row.get(0),
+            )
+            .ok();
+        if let Some(holder) = holder {
+            return Err(HrmError::validation(format!("{} {} is already issued to employee {}", name, code, holder)));
+        }
+    }
+    
+    let employee_exists: bool = conn
+        .query_row("SELECT COUNT(*) > 0 FROM employees WHERE epf_number = ?1", [&epf_number], |row| row.get(0))?;
+    if !employee_exists {
+        return Err(HrmError::not_found(format!("Employee {} not found", epf_number)));
+    }
+    
+    conn.execute(
+        "INSERT INTO asset_issues (asset_id, epf_number, quantity, issued_on, notes, issued_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![asset_id, epf_number, quantity, issued_on, notes, user.username],
+    )?;
+    let issue_id = conn.last_insert_rowid();
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "ISSUE",
+        "ASSET",
+        Some(&epf_number),
+        None,
+        Some(&format!("{} x{}", name, quantity)),
+        Some(&format!("Issued {} x{} to employee {}", name, quantity, epf_number)),
+    );
+    
+    Ok(issue_id)
+}
+
+// Marks an issued item as returned; lost items are closed with condition "lost"
+#[tauri::command]
+pub fn return_asset(
+    issue_id: i64,
+    returned_on: String,
+    return_condition: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_edit(&user)?;
+    parse_iso_date(&returned_on).ok_or("Return date must be a valid date (YYYY-MM-DD)")?;
+    if !RETURN_CONDITIONS.contains(&return_condition.as_str()) {
+        return Err(HrmError::validation("Condition must be good, damaged or lost"));
+    }
+    
+    let conn = db.0.lock()?;
+    let issue = query_issues(&conn, "i.id = ?1", &[&issue_id])?
+        .pop()
+        .ok_or_else(|| HrmError::not_found("Issued item not found"))?;
+    if issue.returned_on.is_some() {
+        return Err(HrmError::validation("This item has already been returned"));
+    }
+    if returned_on < issue.issued_on {
+        return Err(HrmError::validation("Return date cannot be before the issue date"));
+    }
+    
+    conn.execute(
+        "UPDATE asset_issues SET returned_on = ?1, return_condition = ?2 WHERE id = ?3",
+        rusqlite::params![returned_on, return_condition, issue_id],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "RETURN",
+        "ASSET",
+        Some(&issue.epf_number),
+        None,
+        Some(&return_condition),
+        Some(&format!(
+            "{} x{} returned by employee {} ({})",
+            issue.asset_name, issue.quantity, issue.epf_number, return_condition
+        )),
+    );
+    
+    Ok(())
+}
+
+// Returnable items not yet handed back, for resignation clearance
+pub(crate) fn outstanding_items(conn: &Connection, epf_number: Option<&str>) -> Result<Vec<AssetIssue>, HrmError> {
+    query_issues(
+        conn,
+        "i.returned_on IS NULL AND a.is_returnable = 1 AND (?1 IS NULL OR i.epf_number = ?1)",
+        &[&epf_number],
+    )
+}
+
+// Pass an EPF number to check one employee or None for everyone
+#[tauri::command]
+pub fn get_outstanding_items(
+    epf_number: Option<String>,
+    db: State<'_, DbConnection>,
+) -> Result<Vec<AssetIssue>, HrmError> {
+    let conn = db.0.lock()?;
+    outstanding_items(&conn, epf_number.as_deref())
+}
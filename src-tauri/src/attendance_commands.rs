@@ -0,0 +1,1062 @@
+use crate::approvals::{check_approver, may_approve, open_approval, record_decision};
+use crate::commands::{log_audit_action, log_data_export};
+use crate::deduction_commands::require_month;
+use crate::error::HrmError;
+use crate::export_commands::csv_field;
+use crate::models::{AttendanceCorrection, AttendancePunch, AttendanceSummary, OvertimeRequest, UserSession};
+use crate::notification_commands::notify_user;
+use crate::payroll_commands::ensure_month_open;
+use crate::settings_commands::{get_setting, get_setting_bool, get_setting_i64};
+use crate::xlsx::{write_xlsx, Cell, CellStyle, Worksheet};
+use crate::{CurrentUser, DbConnection};
+use chrono::{Datelike, Local, Months, NaiveDate, NaiveDateTime};
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::fs;
+use tauri::State;
+
+// Punches further apart than this are not treated as one shift
+const MAX_SHIFT_HOURS: i64 = 16;
+
+// Punch states as reported by the terminals: 0 check-in, 1 check-out,
+// 2 break-out, 3 break-in, 4 overtime-in, 5 overtime-out
+const MAX_PUNCH_STATE: i32 = 5;
+
+// Most overtime that can be asked for on one day
+const MAX_OVERTIME_HOURS: f64 = 16.0;
+
+// Hours in a normal shift including the meal break, when
+// attendance_standard_day_hours is not set. Time beyond this is overtime.
+const DEFAULT_STANDARD_DAY_HOURS: i64 = 9;
+
+// Columns available in the monthly attendance summary, as (field, header).
+// Fields match AttendanceSummary plus "month".
+pub const SUMMARY_COLUMNS: [(&str, &str); 17] = [
+    ("epf_number", "EPF Number"),
+    ("name_with_initials", "Name with Initials"),
+    ("full_name", "Full Name"),
+    ("department", "Department"),
+    ("designation", "Designation"),
+    ("allocation", "Allocation"),
+    ("month", "Month"),
+    ("working_days", "Working Days"),
+    ("worked_days", "Days Worked"),
+    ("ot_hours", "OT Hours"),
+    ("leave_days", "Leave Taken"),
+    ("annual_leave", "Annual Leave"),
+    ("casual_leave", "Casual Leave"),
+    ("medical_leave", "Medical Leave"),
+    ("maternity_leave", "Maternity Leave"),
+    ("no_pay_leave", "No-Pay Leave"),
+    ("no_pay_days", "No-Pay Days"),
+];
+
+fn require_manage_permission(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_manage_attendance => Ok(session.clone()),
+        Some(_) => Err(HrmError::permission_denied("Permission denied. You cannot correct attendance.")),
+        None => Err(HrmError::not_logged_in()),
+    }
+}
+
+// Approvers may be covering for an absent approver, so their permission is
+// checked against the database with may_approve
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+fn require_export_permission(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_export_data => Ok(session.clone()),
+        Some(_) => Err(HrmError::permission_denied("Permission denied. You cannot export attendance data.")),
+        None => Err(HrmError::not_logged_in()),
+    }
+}
+
+fn require_reports_permission(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_view_reports => Ok(session.clone()),
+        Some(_) => Err(HrmError::permission_denied("Permission denied. You cannot view attendance reports.")),
+        None => Err(HrmError::not_logged_in()),
+    }
+}
+
+fn require_view_permission(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_view_employees => Ok(session.clone()),
+        Some(_) => Err(HrmError::permission_denied("Permission denied. You cannot view attendance.")),
+        None => Err(HrmError::not_logged_in()),
+    }
+}
+
+// Accepts "YYYY-MM-DD HH:MM" or "YYYY-MM-DDTHH:MM[:SS]" and returns the
+// "YYYY-MM-DD HH:MM:SS" form the terminals use
+fn normalize_punch_time(value: &str) -> Result<String, HrmError> {
+    let value = value.trim().replace('T', " ");
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(time) = chrono::NaiveDateTime::parse_from_str(&value, format) {
+            return Ok(time.format("%Y-%m-%d %H:%M:%S").to_string());
+        }
+    }
+    Err(HrmError::field("new_time", "Punch time must be a date and time (YYYY-MM-DD HH:MM)"))
+}
+
+// Days of the week nobody is rostered (0 = Sunday), from the comma-separated
+// attendance_weekly_off_days setting. Defaults to Sunday.
+pub(crate) fn weekly_off_days(conn: &Connection) -> Vec<u32> {
+    get_setting(conn, "attendance_weekly_off_days")
+        .unwrap_or_else(|| "0".to_string())
+        .split(',')
+        .filter_map(|day| day.trim().parse().ok())
+        .filter(|day| *day < 7)
+        .collect()
+}
+
+// Per-employee attendance for a month (YYYY-MM), counted up to today for the
+// current month. Only approved punches and corrections are included. Work on
+// a weekly off day is all overtime; on other days overtime is the time worked
+// in the shifts starting that day beyond the standard day. With
+// attendance_ot_requires_approval on, a day's overtime is capped at the hours
+// approved for it. A rostered day without a shift (a complete pair of
+// punches) is no-pay unless covered by paid leave.
+pub(crate) fn monthly_summary(conn: &Connection, month: &str) -> Result<Vec<AttendanceSummary>, HrmError> {
+    require_month(month)?;
+    let first = NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d")
+        .map_err(|_| HrmError::validation("Month must be in YYYY-MM format"))?;
+    let last = first
+        .checked_add_months(Months::new(1))
+        .and_then(|next| next.pred_opt())
+        .ok_or_else(|| HrmError::validation("Month is out of range"))?;
+    let end = last.min(Local::now().date_naive());
+    let off_days = weekly_off_days(conn);
+    let standard_minutes = get_setting_i64(conn, "attendance_standard_day_hours").unwrap_or(DEFAULT_STANDARD_DAY_HOURS) * 60;
+    let first_text = first.format("%Y-%m-%d").to_string();
+    let last_text = last.format("%Y-%m-%d").to_string();
+    
+    // Shifts count towards the day they start, so a night shift's morning
+    // punch does not also mark the next day as worked
+    let mut punches: HashMap<(String, NaiveDate), i64> = HashMap::new();
+    for (epf_number, shifts) in work_intervals(conn, first, last)? {
+        for (start, finish) in shifts {
+            *punches.entry((epf_number.clone(), start.date())).or_insert(0) += (finish - start).num_minutes();
+        }
+    }
+    
+    let approved_ot = if get_setting_bool(conn, "attendance_ot_requires_approval") {
+        let mut stmt = conn.prepare(
+            "SELECT epf_number, work_date, SUM(hours) FROM overtime_requests
+             WHERE status = 'approved' AND work_date BETWEEN ?1 AND ?2
+             GROUP BY epf_number, work_date",
+        )?;
+        let mut approved: HashMap<(String, NaiveDate), i64> = HashMap::new();
+        for row in stmt.query_map([&first_text, &last_text], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, f64>(2)?))
+        })? {
+            let (epf_number, work_date, hours) = row?;
+            if let Ok(date) = NaiveDate::parse_from_str(&work_date, "%Y-%m-%d") {
+                approved.insert((epf_number, date), (hours * 60.0).round() as i64);
+            }
+        }
+        Some(approved)
+    } else {
+        None
+    };
+    
+    let mut stmt = conn.prepare(
+        "SELECT epf_number, leave_date, leave_type, days FROM leave_records WHERE leave_date BETWEEN ?1 AND ?2",
+    )?;
+    let mut leave: HashMap<(String, NaiveDate), Vec<(String, f64)>> = HashMap::new();
+    for row in stmt.query_map([&first_text, &last_text], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?, row.get::<_, f64>(3)?))
+    })? {
+        let (epf_number, leave_date, leave_type, days) = row?;
+        if let Ok(date) = NaiveDate::parse_from_str(&leave_date, "%Y-%m-%d") {
+            leave.entry((epf_number, date)).or_default().push((leave_type, days));
+        }
+    }
+    
+    let mut stmt = conn.prepare(
+        "SELECT epf_number, name_with_initials, full_name, department, designation, allocation,
+                date_of_join, date_of_resign
+         FROM employees
+         WHERE anonymized_at IS NULL
+           AND (date_of_join IS NULL OR date_of_join = '' OR date_of_join <= ?2)
+           AND (date_of_resign IS NULL OR date_of_resign = '' OR date_of_resign >= ?1)
+         ORDER BY epf_number",
+    )?;
+    let employees = stmt
+        .query_map([&first_text, &last_text], |row| {
+            Ok((
+                AttendanceSummary {
+                    epf_number: row.get(0)?,
+                    name_with_initials: row.get(1)?,
+                    full_name: row.get(2)?,
+                    department: row.get(3)?,
+                    designation: row.get(4)?,
+                    allocation: row.get(5)?,
+                    month: month.to_string(),
+                    working_days: 0,
+                    worked_days: 0,
+                    ot_hours: 0.0,
+                    leave_days: 0.0,
+                    annual_leave: 0.0,
+                    casual_leave: 0.0,
+                    medical_leave: 0.0,
+                    maternity_leave: 0.0,
+                    no_pay_leave: 0.0,
+                    no_pay_days: 0.0,
+                },
+                row.get::<_, Option<String>>(6)?,
+                row.get::<_, Option<String>>(7)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    let mut summaries = Vec::with_capacity(employees.len());
+    for (mut summary, date_of_join, date_of_resign) in employees {
+        let parse = |value: Option<String>| value.and_then(|v| NaiveDate::parse_from_str(&v, "%Y-%m-%d").ok());
+        let from = parse(date_of_join).map_or(first, |joined| joined.max(first));
+        let to = parse(date_of_resign).map_or(end, |resigned| resigned.min(end));
+        let mut ot_minutes = 0;
+    
+        for date in from.iter_days().take_while(|d| *d <= to) {
+            let key = (summary.epf_number.clone(), date);
+            let worked_minutes = punches.get(&key).copied();
+            let approved = |minutes: i64| match &approved_ot {
+                Some(approved) => minutes.min(approved.get(&key).copied().unwrap_or(0)),
+                None => minutes,
+            };
+            if off_days.contains(&date.weekday().num_days_from_sunday()) {
+                ot_minutes += approved(worked_minutes.unwrap_or(0));
+                continue;
+            }
+            summary.working_days += 1;
+            if let Some(minutes) = worked_minutes {
+                summary.worked_days += 1;
+                ot_minutes += approved((minutes - standard_minutes).max(0));
+            }
+    
+            let mut paid_leave = 0.0;
+            for (leave_type, days) in leave.get(&key).into_iter().flatten() {
+                match leave_type.as_str() {
+                    "annual" => summary.annual_leave += days,
+                    "casual" => summary.casual_leave += days,
+                    "medical" => summary.medical_leave += days,
+                    "maternity" => summary.maternity_leave += days,
+                    _ => summary.no_pay_leave += days,
+                }
+                if leave_type != "no_pay" {
+                    paid_leave += days;
+                }
+            }
+            summary.leave_days += paid_leave;
+            if worked_minutes.is_none() {
+                summary.no_pay_days += (1.0 - paid_leave).max(0.0);
+            }
+        }
+    
+        summary.ot_hours = (ot_minutes as f64 / 60.0 * 100.0).round() / 100.0;
+        summaries.push(summary);
+    }
+    
+    Ok(summaries)
+}
+
+// Shifts worked by each employee starting from `from` up to the end of `to`,
+// pairing each punch with the next one when they are close enough to be the
+// same shift. Devices do not reliably record in/out state, so order decides.
+pub(crate) fn work_intervals(
+    conn: &Connection,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<HashMap<String, Vec<(NaiveDateTime, NaiveDateTime)>>, HrmError> {
+    let mut stmt = conn.prepare(
+        "SELECT epf_number, punch_time FROM attendance_punches
+         WHERE date(punch_time) BETWEEN ?1 AND date(?2, '+1 day')
+         ORDER BY epf_number, punch_time",
+    )?;
+    let mut punches: HashMap<String, Vec<NaiveDateTime>> = HashMap::new();
+    for row in stmt.query_map(
+        [from.format("%Y-%m-%d").to_string(), to.format("%Y-%m-%d").to_string()],
+        |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+    )? {
+        let (epf_number, punch_time) = row?;
+        if let Ok(time) = NaiveDateTime::parse_from_str(&punch_time, "%Y-%m-%d %H:%M:%S") {
+            punches.entry(epf_number).or_default().push(time);
+        }
+    }
+    
+    let mut intervals = HashMap::new();
+    for (epf_number, times) in punches {
+        let mut shifts = Vec::new();
+        let mut i = 0;
+        while i + 1 < times.len() {
+            let (start, end) = (times[i], times[i + 1]);
+            if (end - start).num_hours() < MAX_SHIFT_HOURS {
+                // Only shifts starting inside the period; the extra day
+                // queried lets overnight shifts on the last day finish
+                if start.date() <= to {
+                    shifts.push((start, end));
+                }
+                i += 2;
+            } else {
+                i += 1;
+            }
+        }
+        intervals.insert(epf_number, shifts);
+    }
+    Ok(intervals)
+}
+
+// Parses a column layout of "field" or "field=Header" entries, so a payroll
+// provider's expected headers and order can be matched
+pub(crate) fn parse_summary_layout(columns: &[String]) -> Result<Vec<(String, String)>, HrmError> {
+    columns
+        .iter()
+        .map(|entry| {
+            let (field, header) = entry.split_once('=').unwrap_or((entry, ""));
+            let field = field.trim();
+            let (_, default_header) = SUMMARY_COLUMNS
+                .iter()
+                .find(|(f, _)| *f == field)
+                .ok_or_else(|| HrmError::validation(format!("Unknown attendance summary column: {}", field)))?;
+            let header = if header.trim().is_empty() { default_header } else { header.trim() };
+            Ok((field.to_string(), header.to_string()))
+        })
+        .collect()
+}
+
+fn load_correction(conn: &Connection, id: i64) -> Result<AttendanceCorrection, HrmError> {
+    conn.query_row(
+        "SELECT c.id, c.epf_number, e.full_name, c.punch_id, c.original_time, c.original_state,
+                c.new_time, c.new_state, c.reason, c.status, c.requested_by, ru.username, c.requested_at,
+                c.reviewed_by, vu.username, c.reviewed_at, c.review_note
+         FROM attendance_corrections c
+         LEFT JOIN employees e ON e.epf_number = c.epf_number
+         LEFT JOIN users ru ON ru.id = c.requested_by
+         LEFT JOIN users vu ON vu.id = c.reviewed_by
+         WHERE c.id = ?1",
+        [id],
+        map_correction,
+    )
+    .optional()?
+    .ok_or_else(|| HrmError::not_found("Attendance correction not found"))
+}
+
+fn map_correction(row: &rusqlite::Row) -> rusqlite::Result<AttendanceCorrection> {
+    Ok(AttendanceCorrection {
+        id: row.get(0)?,
+        epf_number: row.get(1)?,
+        employee_name: row.get(2)?,
+        punch_id: row.get(3)?,
+        original_time: row.get(4)?,
+        original_state: row.get(5)?,
+        new_time: row.get(6)?,
+        new_state: row.get(7)?,
+        reason: row.get(8)?,
+        status: row.get(9)?,
+        requested_by: row.get(10)?,
+        requested_by_name: row.get(11)?,
+        requested_at: row.get(12)?,
+        reviewed_by: row.get(13)?,
+        reviewed_by_name: row.get(14)?,
+        reviewed_at: row.get(15)?,
+        review_note: row.get(16)?,
+    })
+}
+
+// Writes an approved correction into attendance_punches. The punch keeps its
+// first recorded time in original_punch_time however often it is corrected.
+// Run inside the caller's transaction.
+fn apply_correction(tx: &Connection, correction: &AttendanceCorrection, reviewer_id: i32, note: Option<&str>) -> Result<(), HrmError> {
+    match correction.punch_id {
+        Some(punch_id) => {
+            tx.execute(
+                "UPDATE attendance_punches
+                 SET original_punch_time = COALESCE(original_punch_time, punch_time),
+                     punch_time = ?1, punch_state = ?2, source = 'corrected', correction_id = ?3
+                 WHERE id = ?4",
+                rusqlite::params![correction.new_time, correction.new_state, correction.id, punch_id],
+            )?;
+        }
+        None => {
+            tx.execute(
+                "INSERT INTO attendance_punches (epf_number, punch_time, punch_state, source, correction_id)
+                 VALUES (?1, ?2, ?3, 'manual', ?4)",
+                rusqlite::params![correction.epf_number, correction.new_time, correction.new_state, correction.id],
+            )?;
+        }
+    }
+    tx.execute(
+        "UPDATE attendance_corrections
+         SET status = 'approved', reviewed_by = ?1, reviewed_at = CURRENT_TIMESTAMP, review_note = ?2
+         WHERE id = ?3",
+        rusqlite::params![reviewer_id, note, correction.id],
+    )?;
+    Ok(())
+}
+
+// Punches for one employee between two dates, as payroll sees them
+#[tauri::command]
+pub fn get_employee_punches(
+    epf_number: String,
+    from_date: String,
+    to_date: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<AttendancePunch>, HrmError> {
+    require_view_permission(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(
+        "SELECT p.id, p.epf_number, p.punch_time, p.punch_state, p.verify_mode, p.device_serial, p.source,
+                p.original_punch_time, p.correction_id,
+                EXISTS(SELECT 1 FROM attendance_corrections c WHERE c.punch_id = p.id AND c.status = 'pending')
+         FROM attendance_punches p
+         WHERE p.epf_number = ?1 AND date(p.punch_time) BETWEEN date(?2) AND date(?3)
+         ORDER BY p.punch_time",
+    )?;
+    let punches = stmt
+        .query_map(rusqlite::params![epf_number, from_date, to_date], |row| {
+            Ok(AttendancePunch {
+                id: row.get(0)?,
+                epf_number: row.get(1)?,
+                punch_time: row.get(2)?,
+                punch_state: row.get(3)?,
+                verify_mode: row.get(4)?,
+                device_serial: row.get(5)?,
+                source: row.get(6)?,
+                original_punch_time: row.get(7)?,
+                correction_id: row.get(8)?,
+                has_pending_correction: row.get(9)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    Ok(punches)
+}
+
+// Requests a change to an existing punch (punch_id set) or a missing punch
+// (punch_id empty). Unless attendance_corrections_auto_approve is on, nothing
+// changes until a user with can_approve_attendance approves it.
+#[tauri::command]
+pub fn request_punch_correction(
+    epf_number: String,
+    punch_id: Option<i64>,
+    new_time: String,
+    new_state: i32,
+    reason: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<AttendanceCorrection, HrmError> {
+    let user = require_manage_permission(&current_user)?;
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Err(HrmError::field("reason", "A reason is required for attendance corrections"));
+    }
+    if !(0..=MAX_PUNCH_STATE).contains(&new_state) {
+        return Err(HrmError::field("new_state", "Unknown punch type"));
+    }
+    let new_time = normalize_punch_time(&new_time)?;
+    
+    let mut conn = db.0.lock()?;
+    let employee_exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM employees WHERE epf_number = ?1)",
+        [&epf_number],
+        |row| row.get(0),
+    )?;
+    if !employee_exists {
+        return Err(HrmError::not_found("Employee not found"));
+    }
+    
+    let (original_time, original_state) = match punch_id {
+        Some(punch_id) => {
+            let (punch_epf, time, state): (String, String, i32) = conn
+                .query_row(
+                    "SELECT epf_number, punch_time, punch_state FROM attendance_punches WHERE id = ?1",
+                    [punch_id],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                )
+                .optional()?
+                .ok_or_else(|| HrmError::not_found("Punch not found"))?;
+            if punch_epf != epf_number {
+                return Err(HrmError::validation("The punch belongs to a different employee"));
+            }
+            if time == new_time && state == new_state {
+                return Err(HrmError::validation("The correction does not change the punch"));
+            }
+            let pending: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM attendance_corrections WHERE punch_id = ?1 AND status = 'pending')",
+                [punch_id],
+                |row| row.get(0),
+            )?;
+            if pending {
+                return Err(HrmError::conflict("This punch already has a correction waiting for approval"));
+            }
+            (Some(time), Some(state))
+        }
+        None => (None, None),
+    };
+    ensure_month_open(&conn, &new_time)?;
+    if let Some(original) = &original_time {
+        ensure_month_open(&conn, original)?;
+    }
+    
+    // The correction, its audit entry and its approval or automatic
+    // application are saved together
+    let tx = conn.transaction()?;
+    tx.execute(
+        "INSERT INTO attendance_corrections
+            (epf_number, punch_id, original_time, original_state, new_time, new_state, reason, requested_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![epf_number, punch_id, original_time, original_state, new_time, new_state, reason, user.user_id],
+    )?;
+    let id = tx.last_insert_rowid();
+    let correction = load_correction(&tx, id)?;
+    
+    let details = match &original_time {
+        Some(original) => format!("Requested punch correction for {} from {} to {}: {}", epf_number, original, new_time, reason),
+        None => format!("Requested missing punch for {} at {}: {}", epf_number, new_time, reason),
+    };
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "ATTENDANCE_CORRECTION",
+        Some(&id.to_string()),
+        original_time.as_deref(),
+        Some(&new_time),
+        Some(&details),
+    );
+    
+    if get_setting_bool(&tx, "attendance_corrections_auto_approve") {
+        apply_correction(&tx, &correction, user.user_id, Some("Approved automatically"))?;
+        tx.commit()?;
+        return load_correction(&conn, id);
+    }
+    
+    open_approval(
+        &tx,
+        "attendance_correction",
+        &id.to_string(),
+        Some(&epf_number),
+        &format!("{} requested an attendance correction for {}: {}", user.full_name, epf_number, reason),
+        &user,
+    )?;
+    tx.commit()?;
+    
+    Ok(correction)
+}
+
+#[tauri::command]
+pub fn get_attendance_corrections(
+    status: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<AttendanceCorrection>, HrmError> {
+    require_view_permission(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(
+        "SELECT c.id, c.epf_number, e.full_name, c.punch_id, c.original_time, c.original_state,
+                c.new_time, c.new_state, c.reason, c.status, c.requested_by, ru.username, c.requested_at,
+                c.reviewed_by, vu.username, c.reviewed_at, c.review_note
+         FROM attendance_corrections c
+         LEFT JOIN employees e ON e.epf_number = c.epf_number
+         LEFT JOIN users ru ON ru.id = c.requested_by
+         LEFT JOIN users vu ON vu.id = c.reviewed_by
+         WHERE ?1 IS NULL OR c.status = ?1
+         ORDER BY c.requested_at DESC, c.id DESC",
+    )?;
+    let corrections = stmt
+        .query_map([status], map_correction)?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    Ok(corrections)
+}
+
+#[tauri::command]
+pub fn approve_attendance_correction(
+    id: i64,
+    note: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    
+    let mut conn = db.0.lock()?;
+    if !may_approve(&conn, &user, "can_approve_attendance")? {
+        return Err(HrmError::permission_denied("Permission denied. You cannot approve attendance corrections."));
+    }
+    let correction = load_correction(&conn, id)?;
+    if correction.status != "pending" {
+        return Err(HrmError::conflict(format!("This correction has already been {}", correction.status)));
+    }
+    if correction.requested_by == Some(user.user_id) {
+        return Err(HrmError::permission_denied("You cannot approve your own attendance correction"));
+    }
+    ensure_month_open(&conn, &correction.new_time)?;
+    if let Some(original) = &correction.original_time {
+        ensure_month_open(&conn, original)?;
+    }
+    
+    // Only the last approver in the chain changes the punch
+    let entity_id = id.to_string();
+    let tx = conn.transaction()?;
+    let final_step = check_approver(&tx, "attendance_correction", &entity_id, &user)?;
+    if final_step {
+        apply_correction(&tx, &correction, user.user_id, note.as_deref())?;
+    }
+    record_decision(&tx, "attendance_correction", &entity_id, &user, true, note.as_deref())?;
+    let details = if final_step {
+        format!("Approved attendance correction for {} ({})", correction.epf_number, correction.new_time)
+    } else {
+        format!(
+            "Approved attendance correction for {} ({}); passed to the next approver",
+            correction.epf_number, correction.new_time
+        )
+    };
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "APPROVE",
+        "ATTENDANCE_CORRECTION",
+        Some(&entity_id),
+        correction.original_time.as_deref(),
+        Some(&correction.new_time),
+        Some(&details),
+    );
+    tx.commit()?;
+    
+    if let (true, Some(requested_by)) = (final_step, correction.requested_by) {
+        notify_user(
+            &conn,
+            requested_by,
+            "Attendance correction approved",
+            &format!("Your correction for {} at {} was approved by {}", correction.epf_number, correction.new_time, user.full_name),
+            "info",
+            Some("ATTENDANCE_CORRECTION"),
+            Some(&id.to_string()),
+        );
+    }
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn reject_attendance_correction(
+    id: i64,
+    note: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    let note = note.trim();
+    if note.is_empty() {
+        return Err(HrmError::field("note", "Please give a reason for rejecting the correction"));
+    }
+    
+    let mut conn = db.0.lock()?;
+    if !may_approve(&conn, &user, "can_approve_attendance")? {
+        return Err(HrmError::permission_denied("Permission denied. You cannot approve attendance corrections."));
+    }
+    let correction = load_correction(&conn, id)?;
+    if correction.status != "pending" {
+        return Err(HrmError::conflict(format!("This correction has already been {}", correction.status)));
+    }
+    let tx = conn.transaction()?;
+    check_approver(&tx, "attendance_correction", &id.to_string(), &user)?;
+    record_decision(&tx, "attendance_correction", &id.to_string(), &user, false, Some(note))?;
+    tx.execute(
+        "UPDATE attendance_corrections
+         SET status = 'rejected', reviewed_by = ?1, reviewed_at = CURRENT_TIMESTAMP, review_note = ?2
+         WHERE id = ?3",
+        rusqlite::params![user.user_id, note, id],
+    )?;
+    
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "REJECT",
+        "ATTENDANCE_CORRECTION",
+        Some(&id.to_string()),
+        None,
+        Some(note),
+        Some(&format!("Rejected attendance correction for {}: {}", correction.epf_number, note)),
+    );
+    tx.commit()?;
+    if let Some(requested_by) = correction.requested_by {
+        notify_user(
+            &conn,
+            requested_by,
+            "Attendance correction rejected",
+            &format!("Your correction for {} at {} was rejected: {}", correction.epf_number, correction.new_time, note),
+            "warning",
+            Some("ATTENDANCE_CORRECTION"),
+            Some(&id.to_string()),
+        );
+    }
+    
+    Ok(())
+}
+
+const OVERTIME_COLUMNS: &str = "o.id, o.epf_number, e.full_name, o.work_date, o.hours, o.reason, o.status, o.requested_by,
+                                ru.username, o.requested_at, o.reviewed_by, vu.username, o.reviewed_at, o.review_note";
+
+fn overtime_from_row(row: &rusqlite::Row) -> rusqlite::Result<OvertimeRequest> {
+    Ok(OvertimeRequest {
+        id: row.get(0)?,
+        epf_number: row.get(1)?,
+        employee_name: row.get(2)?,
+        work_date: row.get(3)?,
+        hours: row.get(4)?,
+        reason: row.get(5)?,
+        status: row.get(6)?,
+        requested_by: row.get(7)?,
+        requested_by_name: row.get(8)?,
+        requested_at: row.get(9)?,
+        reviewed_by: row.get(10)?,
+        reviewed_by_name: row.get(11)?,
+        reviewed_at: row.get(12)?,
+        review_note: row.get(13)?,
+    })
+}
+
+fn load_overtime_request(conn: &Connection, id: i64) -> Result<OvertimeRequest, HrmError> {
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM overtime_requests o
+             LEFT JOIN employees e ON e.epf_number = o.epf_number
+             LEFT JOIN users ru ON ru.id = o.requested_by
+             LEFT JOIN users vu ON vu.id = o.reviewed_by
+             WHERE o.id = ?1",
+            OVERTIME_COLUMNS
+        ),
+        [id],
+        overtime_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| HrmError::not_found("Overtime request not found"))
+}
+
+// Asks for overtime on a day through the approval chain. Approved hours only
+// limit paid overtime while attendance_ot_requires_approval is on.
+#[tauri::command]
+pub fn request_overtime(
+    epf_number: String,
+    work_date: String,
+    hours: f64,
+    reason: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<OvertimeRequest, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    let user = match &*user_lock {
+        Some(session) if session.permissions.can_manage_attendance => session.clone(),
+        Some(_) => return Err(HrmError::permission_denied("Permission denied. You cannot request overtime.")),
+        None => return Err(HrmError::not_logged_in()),
+    };
+    drop(user_lock);
+    let work_date = work_date.trim().to_string();
+    NaiveDate::parse_from_str(&work_date, "%Y-%m-%d")
+        .map_err(|_| HrmError::field("work_date", "Date must be in YYYY-MM-DD format"))?;
+    if !hours.is_finite() || hours <= 0.0 || hours > MAX_OVERTIME_HOURS {
+        return Err(HrmError::field("hours", "Overtime must be more than 0 and at most 16 hours"));
+    }
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Err(HrmError::field("reason", "A reason is required for overtime"));
+    }
+    
+    let mut conn = db.0.lock()?;
+    let employee_exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM employees WHERE epf_number = ?1)",
+        [&epf_number],
+        |row| row.get(0),
+    )?;
+    if !employee_exists {
+        return Err(HrmError::not_found("Employee not found"));
+    }
+    ensure_month_open(&conn, &work_date)?;
+    
+    let tx = conn.transaction()?;
+    tx.execute(
+        "INSERT INTO overtime_requests (epf_number, work_date, hours, reason, requested_by) VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![epf_number, work_date, hours, reason, user.user_id],
+    )?;
+    let id = tx.last_insert_rowid();
+    let details = format!("Requested {} hour(s) of overtime for {} on {}: {}", hours, epf_number, work_date, reason);
+    open_approval(&tx, "overtime", &id.to_string(), Some(&epf_number), &details, &user)?;
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "OVERTIME_REQUEST",
+        Some(&id.to_string()),
+        None,
+        Some(&hours.to_string()),
+        Some(&details),
+    );
+    tx.commit()?;
+    
+    load_overtime_request(&conn, id)
+}
+
+#[tauri::command]
+pub fn get_overtime_requests(
+    status: Option<String>,
+    epf_number: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<OvertimeRequest>, HrmError> {
+    require_view_permission(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM overtime_requests o
+         LEFT JOIN employees e ON e.epf_number = o.epf_number
+         LEFT JOIN users ru ON ru.id = o.requested_by
+         LEFT JOIN users vu ON vu.id = o.reviewed_by
+         WHERE (?1 IS NULL OR o.status = ?1) AND (?2 IS NULL OR o.epf_number = ?2)
+         ORDER BY o.requested_at DESC, o.id DESC",
+        OVERTIME_COLUMNS
+    ))?;
+    let requests = stmt
+        .query_map(rusqlite::params![status, epf_number], overtime_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    Ok(requests)
+}
+
+#[tauri::command]
+pub fn approve_overtime_request(
+    id: i64,
+    note: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    
+    let mut conn = db.0.lock()?;
+    if !may_approve(&conn, &user, "can_approve_attendance")? {
+        return Err(HrmError::permission_denied("Permission denied. You cannot approve overtime."));
+    }
+    let request = load_overtime_request(&conn, id)?;
+    if request.status != "pending" {
+        return Err(HrmError::conflict(format!("This overtime request has already been {}", request.status)));
+    }
+    ensure_month_open(&conn, &request.work_date)?;
+    
+    let entity_id = id.to_string();
+    let tx = conn.transaction()?;
+    let final_step = check_approver(&tx, "overtime", &entity_id, &user)?;
+    if final_step {
+        tx.execute(
+            "UPDATE overtime_requests
+             SET status = 'approved', reviewed_by = ?1, reviewed_at = CURRENT_TIMESTAMP, review_note = ?2
+             WHERE id = ?3",
+            rusqlite::params![user.user_id, note, id],
+        )?;
+    }
+    record_decision(&tx, "overtime", &entity_id, &user, true, note.as_deref())?;
+    let details = format!(
+        "Approved {} hour(s) of overtime for {} on {}{}",
+        request.hours,
+        request.epf_number,
+        request.work_date,
+        if final_step { "" } else { "; passed to the next approver" }
+    );
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "APPROVE",
+        "OVERTIME_REQUEST",
+        Some(&entity_id),
+        Some("pending"),
+        Some(if final_step { "approved" } else { "pending" }),
+        Some(&details),
+    );
+    tx.commit()?;
+    
+    if let (true, Some(requested_by)) = (final_step, request.requested_by) {
+        notify_user(
+            &conn,
+            requested_by,
+            "Overtime approved",
+            &format!("Overtime for {} on {} was approved by {}", request.epf_number, request.work_date, user.full_name),
+            "info",
+            Some("OVERTIME_REQUEST"),
+            Some(&entity_id),
+        );
+    }
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn reject_overtime_request(
+    id: i64,
+    note: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    let note = note.trim();
+    if note.is_empty() {
+        return Err(HrmError::field("note", "Please give a reason for rejecting the overtime"));
+    }
+    
+    let mut conn = db.0.lock()?;
+    if !may_approve(&conn, &user, "can_approve_attendance")? {
+        return Err(HrmError::permission_denied("Permission denied. You cannot approve overtime."));
+    }
+    let request = load_overtime_request(&conn, id)?;
+    if request.status != "pending" {
+        return Err(HrmError::conflict(format!("This overtime request has already been {}", request.status)));
+    }
+    
+    let entity_id = id.to_string();
+    let tx = conn.transaction()?;
+    check_approver(&tx, "overtime", &entity_id, &user)?;
+    record_decision(&tx, "overtime", &entity_id, &user, false, Some(note))?;
+    tx.execute(
+        "UPDATE overtime_requests
+         SET status = 'rejected', reviewed_by = ?1, reviewed_at = CURRENT_TIMESTAMP, review_note = ?2
+         WHERE id = ?3",
+        rusqlite::params![user.user_id, note, id],
+    )?;
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "REJECT",
+        "OVERTIME_REQUEST",
+        Some(&entity_id),
+        Some("pending"),
+        Some("rejected"),
+        Some(&format!("Rejected overtime for {} on {}: {}", request.epf_number, request.work_date, note)),
+    );
+    tx.commit()?;
+    
+    if let Some(requested_by) = request.requested_by {
+        notify_user(
+            &conn,
+            requested_by,
+            "Overtime rejected",
+            &format!("Overtime for {} on {} was rejected: {}", request.epf_number, request.work_date, note),
+            "warning",
+            Some("OVERTIME_REQUEST"),
+            Some(&entity_id),
+        );
+    }
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_attendance_summary(
+    month: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<AttendanceSummary>, HrmError> {
+    let user = require_reports_permission(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    Ok(monthly_summary(&conn, &month)?
+        .into_iter()
+        .filter(|s| user.can_access_department(s.department.as_deref()))
+        .collect())
+}
+
+// Writes the monthly attendance summary for an outside payroll provider.
+// `format` is "csv" or "xlsx". Without `columns`, the layout saved in the
+// attendance_export_columns setting is used, else every column. Returns the
+// number of employees written.
+#[tauri::command]
+pub fn export_attendance_summary(
+    month: String,
+    format: String,
+    destination_path: String,
+    columns: Option<Vec<String>>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<usize, HrmError> {
+    let user = require_export_permission(&current_user)?;
+    if !["csv", "xlsx"].contains(&format.as_str()) {
+        return Err(HrmError::validation(format!("Unsupported export format: {}", format)));
+    }
+    
+    let conn = db.0.lock()?;
+    let layout = match columns.filter(|c| !c.is_empty()) {
+        Some(columns) => columns,
+        None => get_setting(&conn, "attendance_export_columns")
+            .map(|saved| saved.split(',').map(str::to_string).filter(|c| !c.trim().is_empty()).collect())
+            .unwrap_or_default(),
+    };
+    let selected = if layout.is_empty() {
+        SUMMARY_COLUMNS.iter().map(|(f, h)| (f.to_string(), h.to_string())).collect()
+    } else {
+        parse_summary_layout(&layout)?
+    };
+    
+    let summaries: Vec<AttendanceSummary> = monthly_summary(&conn, &month)?
+        .into_iter()
+        .filter(|s| user.can_access_department(s.department.as_deref()))
+        .collect();
+    
+    // Text for string fields, numbers without trailing zeros
+    let mut rows: Vec<Vec<(String, bool)>> = Vec::with_capacity(summaries.len());
+    for summary in &summaries {
+        let record = serde_json::to_value(summary)?;
+        rows.push(
+            selected
+                .iter()
+                .map(|(field, _)| match record.get(field.as_str()) {
+                    Some(serde_json::Value::Number(n)) => (n.as_f64().map(|v| v.to_string()).unwrap_or_default(), true),
+                    Some(serde_json::Value::String(s)) => (s.clone(), false),
+                    _ => (String::new(), false),
+                })
+                .collect(),
+        );
+    }
+    
+    if format == "csv" {
+        // UTF-8 BOM so Excel picks the right encoding for Sinhala and Tamil names
+        let mut csv = String::from("\u{FEFF}");
+        let headers: Vec<String> = selected.iter().map(|(_, header)| csv_field(header)).collect();
+        csv.push_str(&headers.join(","));
+        csv.push_str("\r\n");
+        for row in &rows {
+            let values: Vec<String> = row.iter().map(|(value, _)| csv_field(value)).collect();
+            csv.push_str(&values.join(","));
+            csv.push_str("\r\n");
+        }
+        fs::write(&destination_path, csv).map_err(|e| HrmError::io(format!("Failed to save file: {}", e)))?;
+    } else {
+        let mut sheet_rows = vec![selected.iter().map(|(_, header)| Cell::text(header.as_str(), CellStyle::Header)).collect()];
+        for row in rows {
+            sheet_rows.push(
+                row.into_iter()
+                    .map(|(value, is_number)| if is_number { Cell::number(value) } else { Cell::text(value, CellStyle::Plain) })
+                    .collect(),
+            );
+        }
+        let sheet = Worksheet { name: format!("Attendance {}", month), rows: sheet_rows, dropdowns: Vec::new() };
+        write_xlsx(&destination_path, &[sheet])?;
+    }
+    
+    let epf_numbers: Vec<String> = summaries.iter().map(|s| s.epf_number.clone()).collect();
+    log_data_export(&conn, Some(user.user_id), &user.username, &format!("{} attendance summary", format), &epf_numbers);
+    
+    Ok(summaries.len())
+}
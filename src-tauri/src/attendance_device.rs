@@ -0,0 +1,452 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{AttendanceDevice, UserSession};
+use crate::notification_commands::notify_admins;
+use crate::payroll_commands::is_month_locked;
+use crate::settings_commands::{get_setting, get_setting_bool, get_setting_i64};
+use crate::{CurrentUser, DbConnection};
+use chrono::NaiveDateTime;
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::io::Read;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::thread;
+use tauri::{AppHandle, Manager, State};
+use tiny_http::{Method, Request, Response, Server};
+
+// ZKTeco-style terminals in push (ADMS) mode call these HTTP endpoints on the
+// configured port; the terminal's user PIN is the employee's EPF number.
+// attendance_listener_address must name the network interface the terminals
+// reach, so the listener is never opened on every interface by default.
+const DEFAULT_PORT: i64 = 8081;
+// Requests are handled on this many threads. tiny_http cannot time out a
+// body read, so a terminal that stalls mid-upload only holds up one of them.
+const LISTENER_THREADS: usize = 4;
+// Terminals poll getrequest every few seconds, so one seen within this window
+// is online. After OFFLINE_ALERT_MINUTES without contact admins are notified.
+const ONLINE_WINDOW_MINUTES: i64 = 5;
+const OFFLINE_ALERT_MINUTES: i64 = 30;
+// At most one unknown terminal is recorded, and admins told, per window, so
+// made-up serial numbers cannot flood the devices list or notifications
+const UNKNOWN_DEVICE_WINDOW_MINUTES: i64 = 60;
+// Largest ATTLOG upload accepted in one request
+const MAX_BODY_BYTES: u64 = 10 * 1024 * 1024;
+
+fn require_settings_permission(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_manage_settings => Ok(session.clone()),
+        Some(_) => Err(HrmError::permission_denied("Permission denied. You cannot manage attendance devices.")),
+        None => Err(HrmError::not_logged_in()),
+    }
+}
+
+// Starts the listener when attendance_listener_enabled is set. Changes to the
+// setting or port take effect after a restart.
+pub fn start(app_handle: AppHandle) {
+    let (enabled, address, port) = {
+        let db = app_handle.state::<DbConnection>();
+        let Ok(conn) = db.0.lock() else { return };
+        (
+            get_setting_bool(&conn, "attendance_listener_enabled"),
+            get_setting(&conn, "attendance_listener_address").unwrap_or_default(),
+            get_setting_i64(&conn, "attendance_listener_port").unwrap_or(DEFAULT_PORT),
+        )
+    };
+    if !enabled {
+        return;
+    }
+    let Some(port) = u16::try_from(port).ok().filter(|p| *p > 0) else {
+        tracing::error!("Attendance listener port {} is not between 1 and 65535", port);
+        return;
+    };
+    if address.trim().is_empty() {
+        tracing::error!("Attendance listener is enabled but attendance_listener_address is not set");
+        return;
+    }
+    let Ok(address) = address.trim().parse::<IpAddr>() else {
+        tracing::error!("Attendance listener address {} is not an IP address", address);
+        return;
+    };
+    
+    let server = match Server::http((address, port)) {
+        Ok(server) => server,
+        Err(e) => {
+            tracing::error!("Attendance listener could not use {}:{}: {}", address, port, e);
+            return;
+        }
+    };
+    tracing::info!("Attendance listener started on {}:{}", address, port);
+    
+    let server = Arc::new(server);
+    for _ in 0..LISTENER_THREADS {
+        let (server, app_handle) = (Arc::clone(&server), app_handle.clone());
+        thread::spawn(move || {
+            for mut request in server.incoming_requests() {
+                let (status, body) = match handle_request(&app_handle, &mut request) {
+                    Ok(reply) => reply,
+                    Err(e) => {
+                        tracing::error!("Attendance listener error for {}: {}", request.url(), e);
+                        (500, "ERROR".to_string())
+                    }
+                };
+                let _ = request.respond(Response::from_string(body).with_status_code(status));
+            }
+        });
+    }
+}
+
+fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value)
+}
+
+// Records that a terminal made contact. Returns whether it is registered and
+// active, and the stamp of the last punch batch it delivered. A terminal is
+// tied to the address it was first seen at (or registered with); a request
+// for its serial number from anywhere else is treated as unregistered.
+fn touch_device(conn: &Connection, serial: &str, ip: Option<&str>) -> Result<(bool, Option<String>), HrmError> {
+    let device: Option<(bool, Option<String>, Option<String>, bool)> = conn
+        .query_row(
+            "SELECT is_active, ip_address, last_stamp, offline_alerted FROM attendance_devices WHERE serial_number = ?1",
+            [serial],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .optional()?;
+    
+    match device {
+        Some((_, Some(known_ip), _, _)) if ip != Some(known_ip.as_str()) => {
+            tracing::warn!(
+                "Ignored attendance device {} calling from {} instead of {}",
+                serial,
+                ip.unwrap_or("unknown address"),
+                known_ip
+            );
+            Ok((false, None))
+        }
+        Some((is_active, _, last_stamp, offline_alerted)) => {
+            conn.execute(
+                "UPDATE attendance_devices SET last_seen_at = datetime('now', 'localtime'),
+                    ip_address = COALESCE(ip_address, ?2), offline_alerted = 0
+                 WHERE serial_number = ?1",
+                rusqlite::params![serial, ip],
+            )?;
+            if offline_alerted {
+                tracing::info!("Attendance device {} is back online", serial);
+            }
+            Ok((is_active, last_stamp))
+        }
+        None => {
+            let recently_added: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM attendance_devices
+                               WHERE name IS NULL AND created_at >= datetime('now', ?1))",
+                [format!("-{} minutes", UNKNOWN_DEVICE_WINDOW_MINUTES)],
+                |row| row.get(0),
+            )?;
+            if recently_added {
+                tracing::warn!("Ignored unknown attendance device {} from {}", serial, ip.unwrap_or("unknown address"));
+                return Ok((false, None));
+            }
+            conn.execute(
+                "INSERT INTO attendance_devices (serial_number, ip_address, last_seen_at)
+                 VALUES (?1, ?2, datetime('now', 'localtime'))",
+                rusqlite::params![serial, ip],
+            )?;
+            notify_admins(
+                conn,
+                "New attendance device",
+                &format!(
+                    "Terminal {} ({}) is trying to send punches. Register it to accept them.",
+                    serial,
+                    ip.unwrap_or("unknown address")
+                ),
+                "warning",
+                Some("ATTENDANCE_DEVICE"),
+                Some(serial),
+            );
+            Ok((false, None))
+        }
+    }
+}
+
+// Stores ATTLOG lines: PIN, time, state, verify mode, then fields we ignore.
+// Terminals resend after a lost reply, so duplicates are skipped. Punches in
+// a month whose payroll is locked are quarantined instead. Returns how many
+// punches were stored and how many were quarantined.
+fn store_punches(conn: &mut Connection, serial: &str, body: &str) -> Result<(usize, usize), HrmError> {
+    let tx = conn.transaction()?;
+    let mut locked_months: HashMap<String, bool> = HashMap::new();
+    let (mut stored, mut quarantined) = (0, 0);
+    for line in body.lines() {
+        let fields: Vec<&str> = line.split('\t').map(str::trim).collect();
+        let (Some(pin), Some(time)) = (fields.first(), fields.get(1)) else { continue };
+        let time = match NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M:%S") {
+            Ok(time) if !pin.is_empty() => time.format("%Y-%m-%d %H:%M:%S").to_string(),
+            _ => {
+                tracing::warn!("Skipped malformed punch from {}: {:?}", serial, line);
+                continue;
+            }
+        };
+        let state: i32 = fields.get(2).and_then(|s| s.parse().ok()).unwrap_or(0);
+        let verify_mode: Option<i32> = fields.get(3).and_then(|s| s.parse().ok());
+        let month = &time[..7];
+        let locked = match locked_months.get(month) {
+            Some(locked) => *locked,
+            None => {
+                let locked = is_month_locked(&tx, month)?;
+                locked_months.insert(month.to_string(), locked);
+                locked
+            }
+        };
+        let table = if locked { "quarantined_punches" } else { "attendance_punches" };
+        let inserted = tx.execute(
+            &format!(
+                "INSERT OR IGNORE INTO {} (epf_number, punch_time, punch_state, verify_mode, device_serial)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                table
+            ),
+            rusqlite::params![pin, time, state, verify_mode, serial],
+        )?;
+        if locked {
+            quarantined += inserted;
+        } else {
+            stored += inserted;
+        }
+    }
+    tx.commit()?;
+    Ok((stored, quarantined))
+}
+
+fn handle_request(app_handle: &AppHandle, request: &mut Request) -> Result<(u16, String), HrmError> {
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    let Some(serial) = query_param(query, "SN").filter(|sn| !sn.is_empty()) else {
+        return Ok((400, "Missing SN".to_string()));
+    };
+    let serial = serial.to_string();
+    let ip = request.remote_addr().map(|addr| addr.ip().to_string());
+    let is_post = *request.method() == Method::Post;
+    
+    let db = app_handle.state::<DbConnection>();
+    let (is_active, last_stamp) = touch_device(&*db.0.lock()?, &serial, ip.as_deref())?;
+    // The terminal keeps its punches and retries, so nothing is lost while
+    // it waits to be registered
+    if !is_active {
+        return Ok((403, "Device not registered".to_string()));
+    }
+    
+    // Read without holding the database, which a slow upload would block
+    let mut body = String::new();
+    if is_post {
+        request.as_reader().take(MAX_BODY_BYTES).read_to_string(&mut body)?;
+    }
+    let mut conn = db.0.lock()?;
+    
+    match (path.trim_end_matches('/'), is_post) {
+        // Start-up handshake: tells the terminal which punches we already have
+        ("/iclock/cdata", false) => Ok((
+            200,
+            format!(
+                "GET OPTION FROM: {}\nATTLOGStamp={}\nOPERLOGStamp=9999\nATTPHOTOStamp=None\nErrorDelay=30\nDelay=10\nTransTimes=00:00;14:05\nTransInterval=1\nTransFlag=1000000000\nRealtime=1\nEncrypt=0\n",
+                serial,
+                last_stamp.unwrap_or_else(|| "0".to_string())
+            ),
+        )),
+        ("/iclock/cdata", true) => {
+            if !query_param(query, "table").is_some_and(|t| t.eq_ignore_ascii_case("ATTLOG")) {
+                // User, photo and operation logs are not used
+                return Ok((200, "OK".to_string()));
+            }
+            let (stored, quarantined) = store_punches(&mut conn, &serial, &body)?;
+            if quarantined > 0 {
+                notify_admins(
+                    &conn,
+                    "Punches for a locked payroll month",
+                    &format!(
+                        "Terminal {} sent {} punch(es) for a month whose payroll is locked. They are held back and \
+                         will be added if that payroll run is reversed.",
+                        serial, quarantined
+                    ),
+                    "warning",
+                    Some("ATTENDANCE_DEVICE"),
+                    Some(&serial),
+                );
+            }
+            if let Some(stamp) = query_param(query, "Stamp") {
+                conn.execute(
+                    "UPDATE attendance_devices SET last_stamp = ?1 WHERE serial_number = ?2",
+                    rusqlite::params![stamp, serial],
+                )?;
+            }
+            if stored > 0 {
+                tracing::info!("Stored {} punch(es) from {}", stored, serial);
+            }
+            Ok((200, format!("OK: {}", body.lines().count())))
+        }
+        // Heartbeat; we never queue commands for the terminal
+        ("/iclock/getrequest", _) | ("/iclock/devicecmd", _) => Ok((200, "OK".to_string())),
+        _ => Ok((404, "Not found".to_string())),
+    }
+}
+
+// Notifies admins once when a registered terminal stops calling in, e.g. it
+// lost power or network. Called by the scheduler.
+pub fn check_heartbeats(conn: &Connection) -> Result<usize, HrmError> {
+    let mut stmt = conn.prepare(
+        "SELECT serial_number, COALESCE(name, serial_number), last_seen_at FROM attendance_devices
+         WHERE is_active = 1 AND offline_alerted = 0
+           AND (last_seen_at IS NULL OR last_seen_at < datetime('now', 'localtime', ?1))",
+    )?;
+    let offline = stmt
+        .query_map([format!("-{} minutes", OFFLINE_ALERT_MINUTES)], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    for (serial, name, last_seen_at) in &offline {
+        notify_admins(
+            conn,
+            "Attendance device offline",
+            &format!(
+                "{} has not contacted the HRM system since {}. Punches are kept on the device until it reconnects.",
+                name,
+                last_seen_at.as_deref().unwrap_or("it was registered")
+            ),
+            "high",
+            Some("ATTENDANCE_DEVICE"),
+            Some(serial),
+        );
+        conn.execute("UPDATE attendance_devices SET offline_alerted = 1 WHERE serial_number = ?1", [serial])?;
+    }
+    Ok(offline.len())
+}
+
+// Registered and waiting terminals with their connection status
+#[tauri::command]
+pub fn get_attendance_devices(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<AttendanceDevice>, HrmError> {
+    require_settings_permission(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(
+        "SELECT d.id, d.serial_number, d.name, d.location, d.is_active, d.ip_address, d.last_seen_at,
+                CASE
+                    WHEN d.name IS NULL AND d.is_active = 0 THEN 'pending'
+                    WHEN d.is_active = 0 THEN 'disabled'
+                    WHEN d.last_seen_at >= datetime('now', 'localtime', ?1) THEN 'online'
+                    ELSE 'offline'
+                END,
+                (SELECT COUNT(*) FROM attendance_punches p
+                 WHERE p.device_serial = d.serial_number AND date(p.punch_time) = date('now', 'localtime'))
+         FROM attendance_devices d
+         ORDER BY d.is_active DESC, d.name, d.serial_number",
+    )?;
+    let devices = stmt
+        .query_map([format!("-{} minutes", ONLINE_WINDOW_MINUTES)], |row| {
+            Ok(AttendanceDevice {
+                id: row.get(0)?,
+                serial_number: row.get(1)?,
+                name: row.get(2)?,
+                location: row.get(3)?,
+                is_active: row.get(4)?,
+                ip_address: row.get(5)?,
+                last_seen_at: row.get(6)?,
+                status: row.get(7)?,
+                punches_today: row.get(8)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    Ok(devices)
+}
+
+// Registers a terminal by serial number, or approves one that already called
+// in, so its punches are accepted. ip_address moves the terminal to a new
+// address; otherwise it keeps the one it was first seen at.
+#[tauri::command]
+pub fn register_attendance_device(
+    serial_number: String,
+    name: String,
+    location: Option<String>,
+    ip_address: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = require_settings_permission(&current_user)?;
+    let serial_number = serial_number.trim();
+    if serial_number.is_empty() {
+        return Err(HrmError::field("serial_number", "Serial number is required"));
+    }
+    if name.trim().is_empty() {
+        return Err(HrmError::field("name", "Device name is required"));
+    }
+    let ip_address = ip_address.map(|ip| ip.trim().to_string()).filter(|ip| !ip.is_empty());
+    if ip_address.as_deref().is_some_and(|ip| ip.parse::<IpAddr>().is_err()) {
+        return Err(HrmError::field("ip_address", "Enter the terminal's IP address, e.g. 192.168.1.50"));
+    }
+    
+    let conn = db.0.lock()?;
+    conn.execute(
+        "INSERT INTO attendance_devices (serial_number, name, location, ip_address, is_active) VALUES (?1, ?2, ?3, ?4, 1)
+         ON CONFLICT(serial_number) DO UPDATE SET name = excluded.name, location = excluded.location,
+             ip_address = COALESCE(excluded.ip_address, ip_address), is_active = 1",
+        rusqlite::params![serial_number, name.trim(), location, ip_address],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "ATTENDANCE_DEVICE",
+        Some(serial_number),
+        None,
+        Some(name.trim()),
+        Some(&format!("Registered attendance device {} ({})", name.trim(), serial_number)),
+    );
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_attendance_device_active(
+    id: i64,
+    is_active: bool,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = require_settings_permission(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let serial_number: String = conn
+        .query_row("SELECT serial_number FROM attendance_devices WHERE id = ?1", [id], |row| row.get(0))
+        .map_err(|_| HrmError::not_found("Attendance device not found"))?;
+    conn.execute(
+        "UPDATE attendance_devices SET is_active = ?1, offline_alerted = 0 WHERE id = ?2",
+        rusqlite::params![is_active, id],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "ATTENDANCE_DEVICE",
+        Some(&serial_number),
+        None,
+        Some(if is_active { "active" } else { "disabled" }),
+        Some(&format!(
+            "{} attendance device {}",
+            if is_active { "Enabled" } else { "Disabled" },
+            serial_number
+        )),
+    );
+    
+    Ok(())
+}
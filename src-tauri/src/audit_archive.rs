@@ -0,0 +1,219 @@
+use crate::error::HrmError;
+use crate::models::{AuditLog, AuditLogFilters, AuditLogResult};
+use crate::settings_commands::get_setting_i64;
+use crate::{AppDataDir, CurrentUser};
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rusqlite::Connection;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+const ARCHIVE_DIR: &str = "audit_archive";
+
+fn archive_file(app_dir: &Path, year: &str) -> PathBuf {
+    app_dir.join(ARCHIVE_DIR).join(format!("audit_logs_{}.jsonl.gz", year))
+}
+
+// Moves audit rows older than the configured retention period into yearly
+// gzip-compressed JSONL archives. Returns the number of rows archived.
+pub fn archive_expired_logs(conn: &Connection, app_dir: &Path) -> Result<usize, HrmError> {
+    let retention_days = match get_setting_i64(conn, "audit_retention_days") {
+        Some(days) if days > 0 => days,
+        _ => return Ok(0),
+    };
+    let cutoff: String = conn
+        .query_row(
+            "SELECT datetime('now', ?1)",
+            [format!("-{} days", retention_days)],
+            |row| row.get(0),
+        )?;
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, user_id, username, action, entity_type, entity_id, old_value, new_value, details, created_at, severity
+             FROM audit_logs WHERE created_at < ?1 ORDER BY id",
+        )?;
+    
+    let logs = stmt
+        .query_map([&cutoff], |row| {
+            Ok(AuditLog {
+                id: row.get(0)?,
+                user_id: row.get(1)?,
+                username: row.get(2)?,
+                action: row.get(3)?,
+                entity_type: row.get(4)?,
+                entity_id: row.get(5)?,
+                old_value: row.get(6)?,
+                new_value: row.get(7)?,
+                details: row.get(8)?,
+                created_at: row.get(9)?,
+                severity: row.get(10)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    if logs.is_empty() {
+        return Ok(0);
+    }
+    
+    let mut by_year: BTreeMap<String, Vec<&AuditLog>> = BTreeMap::new();
+    for log in &logs {
+        let year = log
+            .created_at
+            .as_deref()
+            .and_then(|d| d.get(0..4))
+            .unwrap_or("unknown")
+            .to_string();
+        by_year.entry(year).or_default().push(log);
+    }
+    
+    fs::create_dir_all(app_dir.join(ARCHIVE_DIR))
+        .map_err(|e| HrmError::io(format!("Failed to create archive folder: {}", e)))?;
+    
+    for (year, year_logs) in &by_year {
+        append_to_archive(&archive_file(app_dir, year), year_logs)?;
+    }
+    
+    // Only delete once every archive file has been written and synced.
+    // If we crash before this point the rows stay live and are archived
+    // again next run; duplicate ids are skipped when reading archives.
+    let max_id = logs.iter().map(|l| l.id).max().unwrap_or(0);
+    conn.execute(
+        "DELETE FROM audit_logs WHERE id <= ?1 AND created_at < ?2",
+        rusqlite::params![max_id, cutoff],
+    )?;
+    
+    Ok(logs.len())
+}
+
+fn append_to_archive(path: &Path, logs: &[&AuditLog]) -> Result<(), HrmError> {
+    // Each run appends a new gzip member; MultiGzDecoder reads them back as one stream
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| HrmError::io(format!("Failed to open archive: {}", e)))?;
+    let mut encoder = GzEncoder::new(file, Compression::default());
+    
+    for log in logs {
+        let line = serde_json::to_string(log)?;
+        encoder
+            .write_all(line.as_bytes())
+            .and_then(|_| encoder.write_all(b"\n"))
+            .map_err(|e| HrmError::io(format!("Failed to write archive: {}", e)))?;
+    }
+    
+    let file = encoder.finish().map_err(|e| HrmError::io(format!("Failed to write archive: {}", e)))?;
+    file.sync_all().map_err(|e| HrmError::io(format!("Failed to write archive: {}", e)))?;
+    Ok(())
+}
+
+fn read_archive(path: &Path) -> Result<Vec<AuditLog>, HrmError> {
+    let file = File::open(path).map_err(|e| HrmError::io(format!("Failed to open archive: {}", e)))?;
+    let reader = BufReader::new(MultiGzDecoder::new(file));
+    
+    let mut seen = HashSet::new();
+    let mut logs = Vec::new();
+    for line in reader.lines() {
+        let line = line.map_err(|e| HrmError::io(format!("Failed to read archive: {}", e)))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let log: AuditLog = serde_json::from_str(&line).map_err(|e| HrmError::internal(format!("Corrupt archive entry: {}", e)))?;
+        if seen.insert(log.id) {
+            logs.push(log);
+        }
+    }
+    Ok(logs)
+}
+
+fn check_audit_permission(current_user: &State<'_, CurrentUser>) -> Result<(), HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_view_audit_logs => Ok(()),
+        _ => Err(HrmError::permission_denied("Permission denied")),
+    }
+}
+
+// Years with an archive file, newest first
+pub(crate) fn archived_years(app_dir: &Path) -> Result<Vec<String>, HrmError> {
+    let dir = app_dir.join(ARCHIVE_DIR);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    
+    let mut years: Vec<String> = fs::read_dir(&dir)
+        .map_err(|e| HrmError::io(format!("Failed to read archive folder: {}", e)))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.strip_prefix("audit_logs_")
+                .and_then(|n| n.strip_suffix(".jsonl.gz"))
+                .map(|y| y.to_string())
+        })
+        .collect();
+    years.sort();
+    years.reverse();
+    
+    Ok(years)
+}
+
+// Permanently removes one year's archive, for the retention rules
+pub(crate) fn delete_archive_year(app_dir: &Path, year: &str) -> Result<(), HrmError> {
+    fs::remove_file(archive_file(app_dir, year)).map_err(|e| HrmError::io(format!("Failed to delete archive for {}: {}", year, e)))
+}
+
+#[tauri::command]
+pub fn get_archived_audit_years(
+    app_data_dir: State<'_, AppDataDir>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<String>, HrmError> {
+    check_audit_permission(&current_user)?;
+    archived_years(&app_data_dir.0)
+}
+
+#[tauri::command]
+pub fn get_archived_audit_logs(
+    year: String,
+    filters: AuditLogFilters,
+    app_data_dir: State<'_, AppDataDir>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<AuditLogResult, HrmError> {
+    check_audit_permission(&current_user)?;
+    
+    if !year.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(HrmError::validation("Invalid archive year"));
+    }
+    let path = archive_file(&app_data_dir.0, &year);
+    if !path.exists() {
+        return Err(HrmError::validation(format!("No archived audit logs for {}", year)));
+    }
+    
+    let username = filters.username.to_lowercase();
+    let mut logs: Vec<AuditLog> = read_archive(&path)?
+        .into_iter()
+        .filter(|log| username.is_empty() || log.username.to_lowercase().contains(&username))
+        .filter(|log| filters.action.is_empty() || log.action == filters.action)
+        .filter(|log| filters.entity_type.is_empty() || log.entity_type == filters.entity_type)
+        .filter(|log| filters.severity.is_empty() || log.severity.as_deref() == Some(filters.severity.as_str()))
+        .filter(|log| {
+            let date = log.created_at.as_deref().and_then(|d| d.get(0..10)).unwrap_or("");
+            (filters.start_date.is_empty() || date >= filters.start_date.as_str())
+                && (filters.end_date.is_empty() || date <= filters.end_date.as_str())
+        })
+        .collect();
+    
+    logs.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    let total_count = logs.len() as i32;
+    let logs = logs
+        .into_iter()
+        .skip(filters.offset.max(0) as usize)
+        .take(filters.limit.max(0) as usize)
+        .collect();
+    
+    Ok(AuditLogResult { logs, total_count })
+}
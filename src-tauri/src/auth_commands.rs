@@ -1,21 +1,31 @@
-use crate::models::{CreateUserRequest, LoginRequest, UpdateUserRequest, UserInfo, UserPermissions, UserSession};
+use crate::access_review::{access_snapshot, log_access_change};
+use crate::commands::log_audit_action;
+use crate::error::{ErrorCode, HrmError};
+use crate::models::{CreateUserRequest, LoginHours, LoginRequest, SessionInfo, UpdateUserRequest, UserInfo, UserPermissions, UserSession};
 use crate::{hash_password, verify_password, CurrentUser, DbConnection};
-use tauri::State;
+use rusqlite::{Connection, OptionalExtension};
+use tauri::ipc::Invoke;
+use tauri::{Manager, Runtime, State};
+
+// Commands that manage the session themselves
+const SESSION_EXEMPT_COMMANDS: [&str; 4] = ["login", "logout", "get_current_user", "validate_session"];
 
 #[tauri::command]
 pub fn login(
     request: LoginRequest,
     db: State<'_, DbConnection>,
     current_user: State<'_, CurrentUser>,
-) -> Result<UserSession, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+) -> Result<UserSession, HrmError> {
+    let conn = db.0.lock()?;
     
     let result = conn.query_row(
         "SELECT id, username, password_hash, full_name, role, department_access, is_active,
                 can_view_employees, can_add_employees, can_edit_employees, can_delete_employees,
                 can_manage_users, can_view_all_departments, can_export_data, can_view_reports,
-                can_manage_settings, can_backup_database, can_view_audit_logs
-         FROM users WHERE username = ?1",
+                can_manage_settings, can_backup_database, can_view_audit_logs, can_login_anytime,
+                can_view_sensitive_data, can_manage_disciplinary, can_approve_attendance,
+                can_manage_attendance, can_approve_leave, can_run_payroll, can_view_salaries
+         FROM users WHERE username = ?1 AND deleted_at IS NULL",
         [&request.username],
         |row| {
             Ok((
@@ -37,6 +47,14 @@ pub fn login(
                 row.get::<_, bool>(15)?,
                 row.get::<_, bool>(16)?,
                 row.get::<_, bool>(17)?,
+                row.get::<_, bool>(18)?,
+                row.get::<_, bool>(19)?,
+                row.get::<_, bool>(20)?,
+                row.get::<_, bool>(21)?,
+                row.get::<_, bool>(22)?,
+                row.get::<_, bool>(23)?,
+                row.get::<_, bool>(24)?,
+                row.get::<_, bool>(25)?,
             ))
         },
     );
@@ -45,13 +63,38 @@ pub fn login(
         Ok((id, username, password_hash, full_name, role, department_access, is_active,
             can_view_employees, can_add_employees, can_edit_employees, can_delete_employees,
             can_manage_users, can_view_all_departments, can_export_data, can_view_reports,
-            can_manage_settings, can_backup_database, can_view_audit_logs)) => {
+            can_manage_settings, can_backup_database, can_view_audit_logs, can_login_anytime,
+            can_view_sensitive_data, can_manage_disciplinary, can_approve_attendance,
+            can_manage_attendance, can_approve_leave, can_run_payroll, can_view_salaries)) => {
             if !is_active {
-                return Err("Account is deactivated. Please contact administrator.".to_string());
+                return Err(HrmError::validation("Account is deactivated. Please contact administrator."));
             }
             
             if !verify_password(&request.password, &password_hash) {
-                return Err("Invalid username or password".to_string());
+                return Err(HrmError::validation("Invalid username or password"));
+            }
+            
+            // Enforce allowed login hours unless the user may log in at any time
+            if !can_login_anytime {
+                if let Some(hours) = get_login_hours(&conn, id)? {
+                    if !is_within_login_hours(&conn, &hours)? {
+                        log_audit_action(
+                            &conn,
+                            Some(id),
+                            &username,
+                            "LOGIN_DENIED",
+                            "USER",
+                            Some(&id.to_string()),
+                            None,
+                            None,
+                            Some("Login attempted outside allowed login hours"),
+                        );
+                        return Err(HrmError::validation(format!(
+                            "Login is not allowed at this time. Allowed hours: {}",
+                            describe_login_hours(&hours)
+                        )));
+                    }
+                }
             }
             
             // Update last login time
@@ -73,8 +116,23 @@ pub fn login(
                 can_manage_settings,
                 can_backup_database,
                 can_view_audit_logs,
+                can_login_anytime,
+                can_view_sensitive_data,
+                can_manage_disciplinary,
+                can_approve_attendance,
+                can_manage_attendance,
+                can_approve_leave,
+                can_run_payroll,
+                can_view_salaries,
             };
             
+            // Record the issued session so it can be revoked later
+            conn.execute(
+                "INSERT INTO sessions (user_id, device_name) VALUES (?1, ?2)",
+                rusqlite::params![id, device_name()],
+            )?;
+            let session_id = conn.last_insert_rowid();
+            
             let session = UserSession {
                 user_id: id,
                 username,
@@ -82,63 +140,234 @@ pub fn login(
                 role,
                 department_access,
                 permissions,
+                session_id: Some(session_id),
             };
             
             // Store session
-            let mut user_lock = current_user.0.lock().map_err(|e| e.to_string())?;
+            let mut user_lock = current_user.0.lock()?;
             *user_lock = Some(session.clone());
             
             Ok(session)
         }
-        Err(_) => Err("Invalid username or password".to_string()),
+        Err(_) => Err(HrmError::validation("Invalid username or password")),
     }
 }
 
 #[tauri::command]
-pub fn logout(current_user: State<'_, CurrentUser>) -> Result<(), String> {
-    let mut user_lock = current_user.0.lock().map_err(|e| e.to_string())?;
+pub fn logout(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let conn = db.0.lock()?;
+    let mut user_lock = current_user.0.lock()?;
+    if let Some(session_id) = user_lock.as_ref().and_then(|s| s.session_id) {
+        end_session(&conn, session_id, "logout")?;
+    }
     *user_lock = None;
     Ok(())
 }
 
+// Re-checks the current session: it must not have been terminated by an
+// administrator and must still be inside the user's allowed login hours.
+// The frontend calls this periodically; an invalid session is ended.
+#[tauri::command]
+pub fn validate_session(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<bool, HrmError> {
+    let conn = db.0.lock()?;
+    let mut user_lock = current_user.0.lock()?;
+    
+    let session = match &*user_lock {
+        Some(session) => session.clone(),
+        None => return Ok(false),
+    };
+    
+    if let Some(session_id) = session.session_id {
+        if !is_session_active(&conn, session_id)? {
+            *user_lock = None;
+            return Ok(false);
+        }
+        let _ = conn.execute(
+            "UPDATE sessions SET last_seen_at = CURRENT_TIMESTAMP WHERE id = ?1",
+            [&session_id],
+        );
+    }
+    
+    if session.permissions.can_login_anytime {
+        return Ok(true);
+    }
+    
+    if let Some(hours) = get_login_hours(&conn, session.user_id)? {
+        if !is_within_login_hours(&conn, &hours)? {
+            if let Some(session_id) = session.session_id {
+                end_session(&conn, session_id, "outside_login_hours")?;
+            }
+            *user_lock = None;
+            log_audit_action(
+                &conn,
+                Some(session.user_id),
+                &session.username,
+                "LOGOUT",
+                "USER",
+                Some(&session.user_id.to_string()),
+                None,
+                None,
+                Some("Session ended: outside allowed login hours"),
+            );
+            return Ok(false);
+        }
+    }
+    
+    Ok(true)
+}
+
 #[tauri::command]
-pub fn get_current_user(current_user: State<'_, CurrentUser>) -> Result<Option<UserSession>, String> {
-    let user_lock = current_user.0.lock().map_err(|e| e.to_string())?;
+pub fn get_current_user(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Option<UserSession>, HrmError> {
+    let conn = db.0.lock()?;
+    let mut user_lock = current_user.0.lock()?;
+    
+    // Drop sessions that were terminated from another workstation
+    if let Some(session_id) = user_lock.as_ref().and_then(|s| s.session_id) {
+        if !is_session_active(&conn, session_id)? {
+            *user_lock = None;
+        }
+    }
+    
     Ok(user_lock.clone())
 }
 
 #[tauri::command]
-pub fn create_user(
-    request: CreateUserRequest,
+pub fn get_active_sessions(
     db: State<'_, DbConnection>,
     current_user: State<'_, CurrentUser>,
-) -> Result<(), String> {
-    // Check if current user is admin
-    let user_lock = current_user.0.lock().map_err(|e| e.to_string())?;
+) -> Result<Vec<SessionInfo>, HrmError> {
+    let user_lock = current_user.0.lock()?;
     match &*user_lock {
         Some(session) if session.permissions.can_manage_users => {}
-        _ => return Err("Permission denied. Only administrators can create users.".to_string()),
+        _ => return Err(HrmError::permission_denied("Permission denied")),
     }
     drop(user_lock);
     
+    let conn = db.0.lock()?;
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.id, s.user_id, u.username, s.device_name, s.created_at, s.last_seen_at
+             FROM sessions s JOIN users u ON u.id = s.user_id
+             WHERE s.ended_at IS NULL
+             ORDER BY s.created_at DESC",
+        )?;
+    
+    let sessions = stmt
+        .query_map([], |row| {
+            Ok(SessionInfo {
+                id: row.get(0)?,
+                user_id: row.get(1)?,
+                username: row.get(2)?,
+                device_name: row.get(3)?,
+                created_at: row.get(4)?,
+                last_seen_at: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    Ok(sessions)
+}
+
+#[tauri::command]
+pub fn terminate_user_sessions(
+    user_id: i32,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<usize, HrmError> {
+    // Check if current user is admin
+    let user_lock = current_user.0.lock()?;
+    let (admin_id, admin_username) = match &*user_lock {
+        Some(session) if session.permissions.can_manage_users => (session.user_id, session.username.clone()),
+        _ => return Err(HrmError::permission_denied("Permission denied")),
+    };
+    drop(user_lock);
+    
+    if admin_id == user_id {
+        return Err(HrmError::validation("Cannot terminate your own sessions. Use logout instead."));
+    }
+    
+    let conn = db.0.lock()?;
+    let terminated = end_user_sessions(&conn, user_id, "terminated")?;
+    
+    log_audit_action(
+        &conn,
+        Some(admin_id),
+        &admin_username,
+        "TERMINATE_SESSIONS",
+        "USER",
+        Some(&user_id.to_string()),
+        None,
+        None,
+        Some(&format!("Terminated {} active session(s) for user ID {}", terminated, user_id)),
+    );
+    
+    Ok(terminated)
+}
+
+#[tauri::command]
+pub fn create_user(
+    request: CreateUserRequest,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    // Check if current user is admin
+    let user_lock = current_user.0.lock()?;
+    let (current_user_id, current_username) = match &*user_lock {
+        Some(session) if session.permissions.can_manage_users => (session.user_id, session.username.clone()),
+        _ => return Err(HrmError::permission_denied("Permission denied. Only administrators can create users.")),
+    };
+    drop(user_lock);
+    
     // Validate role
     let valid_roles = ["admin", "hr_manager", "hr_staff", "viewer", "custom"];
     if !valid_roles.contains(&request.role.as_str()) {
-        return Err("Invalid role specified".to_string());
+        return Err(HrmError::validation("Invalid role specified"));
+    }
+    
+    if let Some(hours) = &request.login_hours {
+        validate_login_hours(hours)?;
+    }
+    
+    let conn = db.0.lock()?;
+    
+    // Usernames of deleted accounts stay reserved so audit history is unambiguous
+    let deleted_owner: Option<i32> = conn
+        .query_row(
+            "SELECT id FROM users WHERE username = ?1 AND deleted_at IS NOT NULL",
+            [&request.username],
+            |row| row.get(0),
+        )
+        .ok();
+    if deleted_owner.is_some() {
+        return Err(HrmError::validation("Username belongs to a deleted account. Restore that account or choose another username."));
     }
     
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
     let password_hash = hash_password(&request.password);
     
     // Get permissions - either from request or from role defaults
     let permissions = request.permissions.unwrap_or_else(|| UserPermissions::from_role(&request.role));
+    let (login_start_time, login_end_time, login_days) = split_login_hours(request.login_hours.as_ref());
     
     conn.execute(
         "INSERT INTO users (username, password_hash, full_name, role, department_access,
                            can_view_employees, can_add_employees, can_edit_employees, can_delete_employees,
                            can_manage_users, can_view_all_departments, can_export_data, can_view_reports,
-                           can_manage_settings, can_backup_database) 
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+                           can_manage_settings, can_backup_database, can_view_audit_logs, can_login_anytime,
+                           can_view_sensitive_data, can_manage_disciplinary, can_approve_attendance,
+                           can_manage_attendance, can_approve_leave, can_run_payroll, can_view_salaries,
+                           login_start_time, login_end_time, login_days) 
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23,
+                 ?24, ?25, ?26, ?27)",
         rusqlite::params![
             request.username,
             password_hash,
@@ -155,15 +384,29 @@ pub fn create_user(
             permissions.can_view_reports,
             permissions.can_manage_settings,
             permissions.can_backup_database,
+            permissions.can_view_audit_logs,
+            permissions.can_login_anytime,
+            permissions.can_view_sensitive_data,
+            permissions.can_manage_disciplinary,
+            permissions.can_approve_attendance,
+            permissions.can_manage_attendance,
+            permissions.can_approve_leave,
+            permissions.can_run_payroll,
+            permissions.can_view_salaries,
+            login_start_time,
+            login_end_time,
+            login_days,
         ],
     )
     .map_err(|e| {
         if e.to_string().contains("UNIQUE constraint") {
-            "Username already exists".to_string()
+            HrmError::conflict("Username already exists")
         } else {
-            e.to_string()
+            HrmError::from(e)
         }
     })?;
+    let user_id = conn.last_insert_rowid() as i32;
+    log_access_change(&conn, current_user_id, &current_username, user_id, None);
     
     Ok(())
 }
@@ -172,58 +415,17 @@ pub fn create_user(
 pub fn get_all_users(
     db: State<'_, DbConnection>,
     current_user: State<'_, CurrentUser>,
-) -> Result<Vec<UserInfo>, String> {
+) -> Result<Vec<UserInfo>, HrmError> {
     // Check if current user is admin
-    let user_lock = current_user.0.lock().map_err(|e| e.to_string())?;
+    let user_lock = current_user.0.lock()?;
     match &*user_lock {
         Some(session) if session.permissions.can_manage_users => {}
-        _ => return Err("Permission denied".to_string()),
+        _ => return Err(HrmError::permission_denied("Permission denied")),
     }
     drop(user_lock);
     
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
-    
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, username, full_name, role, department_access, is_active, created_at, last_login,
-                    can_view_employees, can_add_employees, can_edit_employees, can_delete_employees,
-                    can_manage_users, can_view_all_departments, can_export_data, can_view_reports,
-                    can_manage_settings, can_backup_database, can_view_audit_logs
-             FROM users ORDER BY id",
-        )
-        .map_err(|e| e.to_string())?;
-    
-    let users = stmt
-        .query_map([], |row| {
-            Ok(UserInfo {
-                id: row.get(0)?,
-                username: row.get(1)?,
-                full_name: row.get(2)?,
-                role: row.get(3)?,
-                department_access: row.get(4)?,
-                is_active: row.get(5)?,
-                created_at: row.get(6)?,
-                last_login: row.get(7)?,
-                permissions: Some(UserPermissions {
-                    can_view_employees: row.get(8)?,
-                    can_add_employees: row.get(9)?,
-                    can_edit_employees: row.get(10)?,
-                    can_delete_employees: row.get(11)?,
-                    can_manage_users: row.get(12)?,
-                    can_view_all_departments: row.get(13)?,
-                    can_export_data: row.get(14)?,
-                    can_view_reports: row.get(15)?,
-                    can_manage_settings: row.get(16)?,
-                    can_backup_database: row.get(17)?,
-                    can_view_audit_logs: row.get(18)?,
-                }),
-            })
-        })
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())?;
-    
-    Ok(users)
+    let conn = db.0.lock()?;
+    load_users(&conn, false)
 }
 
 #[tauri::command]
@@ -231,27 +433,44 @@ pub fn update_user(
     request: UpdateUserRequest,
     db: State<'_, DbConnection>,
     current_user: State<'_, CurrentUser>,
-) -> Result<(), String> {
+) -> Result<(), HrmError> {
     // Check if current user is admin
-    let user_lock = current_user.0.lock().map_err(|e| e.to_string())?;
-    match &*user_lock {
-        Some(session) if session.permissions.can_manage_users => {}
-        _ => return Err("Permission denied".to_string()),
-    }
+    let user_lock = current_user.0.lock()?;
+    let (current_user_id, current_username) = match &*user_lock {
+        Some(session) if session.permissions.can_manage_users => (session.user_id, session.username.clone()),
+        _ => return Err(HrmError::permission_denied("Permission denied")),
+    };
     drop(user_lock);
     
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    if let Some(Some(hours)) = &request.login_hours {
+        validate_login_hours(hours)?;
+    }
+    
+    let conn = db.0.lock()?;
     
     // Get permissions - either from request or from role defaults
     let permissions = request.permissions.unwrap_or_else(|| UserPermissions::from_role(&request.role));
+    let (login_start_time, login_end_time, login_days) = match &request.login_hours {
+        Some(hours) => split_login_hours(hours.as_ref()),
+        None => split_login_hours(get_login_hours(&conn, request.user_id)?.as_ref()),
+    };
+    
+    // Never leave the system without an active administrator
+    if (!request.is_active || !permissions.can_manage_users) && is_last_active_admin(&conn, request.user_id)? {
+        return Err(HrmError::validation("Cannot deactivate or remove user management from the last active administrator. Grant another user administrator access first."));
+    }
+    let before = access_snapshot(&conn, request.user_id)?;
     
     conn.execute(
         "UPDATE users SET full_name = ?1, role = ?2, department_access = ?3, is_active = ?4,
                          can_view_employees = ?5, can_add_employees = ?6, can_edit_employees = ?7,
                          can_delete_employees = ?8, can_manage_users = ?9, can_view_all_departments = ?10,
                          can_export_data = ?11, can_view_reports = ?12, can_manage_settings = ?13,
-                         can_backup_database = ?14, can_view_audit_logs = ?15
-         WHERE id = ?16",
+                         can_backup_database = ?14, can_view_audit_logs = ?15, can_login_anytime = ?16,
+                         can_view_sensitive_data = ?17, can_manage_disciplinary = ?18, can_approve_attendance = ?19,
+                         can_manage_attendance = ?20, can_approve_leave = ?21, can_run_payroll = ?22, can_view_salaries = ?23,
+                         login_start_time = ?24, login_end_time = ?25, login_days = ?26
+         WHERE id = ?27",
         rusqlite::params![
             request.full_name,
             request.role,
@@ -268,10 +487,26 @@ pub fn update_user(
             permissions.can_manage_settings,
             permissions.can_backup_database,
             permissions.can_view_audit_logs,
+            permissions.can_login_anytime,
+            permissions.can_view_sensitive_data,
+            permissions.can_manage_disciplinary,
+            permissions.can_approve_attendance,
+            permissions.can_manage_attendance,
+            permissions.can_approve_leave,
+            permissions.can_run_payroll,
+            permissions.can_view_salaries,
+            login_start_time,
+            login_end_time,
+            login_days,
             request.user_id,
         ],
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
+    
+    // A deactivated account loses access immediately
+    if !request.is_active {
+        end_user_sessions(&conn, request.user_id, "deactivated")?;
+    }
+    log_access_change(&conn, current_user_id, &current_username, request.user_id, before.as_deref());
     
     Ok(())
 }
@@ -281,24 +516,179 @@ pub fn delete_user(
     user_id: i32,
     db: State<'_, DbConnection>,
     current_user: State<'_, CurrentUser>,
-) -> Result<(), String> {
+) -> Result<(), HrmError> {
     // Check if current user is admin
-    let user_lock = current_user.0.lock().map_err(|e| e.to_string())?;
-    let current_user_id = match &*user_lock {
-        Some(session) if session.permissions.can_manage_users => session.user_id,
-        _ => return Err("Permission denied".to_string()),
+    let user_lock = current_user.0.lock()?;
+    let (current_user_id, current_username) = match &*user_lock {
+        Some(session) if session.permissions.can_manage_users => (session.user_id, session.username.clone()),
+        _ => return Err(HrmError::permission_denied("Permission denied")),
     };
     drop(user_lock);
     
     // Prevent deleting self
     if current_user_id == user_id {
-        return Err("Cannot delete your own account".to_string());
+        return Err(HrmError::validation("Cannot delete your own account"));
+    }
+    
+    let conn = db.0.lock()?;
+    
+    if is_last_active_admin(&conn, user_id)? {
+        return Err(HrmError::validation("Cannot delete the last active administrator. Grant another user administrator access first."));
+    }
+    
+    let before = access_snapshot(&conn, user_id)?;
+    
+    // Soft delete: the row stays so audit log entries keep resolving to a user
+    let updated = conn
+        .execute(
+            "UPDATE users SET is_active = 0, deleted_at = CURRENT_TIMESTAMP
+             WHERE id = ?1 AND deleted_at IS NULL",
+            [&user_id],
+        )?;
+    if updated == 0 {
+        return Err(HrmError::not_found("User not found"));
+    }
+    end_user_sessions(&conn, user_id, "deleted")?;
+    
+    log_audit_action(
+        &conn,
+        Some(current_user_id),
+        &current_username,
+        "DELETE",
+        "USER",
+        Some(&user_id.to_string()),
+        None,
+        None,
+        Some(&format!("Moved user ID {} to the recycle bin", user_id)),
+    );
+    log_access_change(&conn, current_user_id, &current_username, user_id, before.as_deref());
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_deleted_users(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<UserInfo>, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_manage_users => {}
+        _ => return Err(HrmError::permission_denied("Permission denied")),
+    }
+    drop(user_lock);
+    
+    let conn = db.0.lock()?;
+    load_users(&conn, true)
+}
+
+#[tauri::command]
+pub fn restore_user(
+    user_id: i32,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user_lock = current_user.0.lock()?;
+    let (current_user_id, current_username) = match &*user_lock {
+        Some(session) if session.permissions.can_manage_users => (session.user_id, session.username.clone()),
+        _ => return Err(HrmError::permission_denied("Permission denied")),
+    };
+    drop(user_lock);
+    
+    let conn = db.0.lock()?;
+    let before = access_snapshot(&conn, user_id)?;
+    
+    let updated = conn
+        .execute(
+            "UPDATE users SET is_active = 1, deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+            [&user_id],
+        )?;
+    if updated == 0 {
+        return Err(HrmError::validation("User is not in the recycle bin"));
+    }
+    
+    log_audit_action(
+        &conn,
+        Some(current_user_id),
+        &current_username,
+        "RESTORE",
+        "USER",
+        Some(&user_id.to_string()),
+        None,
+        None,
+        Some(&format!("Restored user ID {} from the recycle bin", user_id)),
+    );
+    log_access_change(&conn, current_user_id, &current_username, user_id, before.as_deref());
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn rename_user(
+    user_id: i32,
+    new_username: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user_lock = current_user.0.lock()?;
+    let (current_user_id, current_username) = match &*user_lock {
+        Some(session) if session.permissions.can_manage_users => (session.user_id, session.username.clone()),
+        _ => return Err(HrmError::permission_denied("Permission denied")),
+    };
+    drop(user_lock);
+    
+    let new_username = new_username.trim().to_string();
+    if new_username.is_empty() || new_username.chars().any(|c| c.is_whitespace()) {
+        return Err(HrmError::validation("Username cannot be empty or contain spaces"));
     }
     
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let mut conn = db.0.lock()?;
+    
+    let (old_username, deleted_at): (String, Option<String>) = conn
+        .query_row(
+            "SELECT username, deleted_at FROM users WHERE id = ?1",
+            [&user_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|_| HrmError::not_found("User not found"))?;
+    if deleted_at.is_some() {
+        return Err(HrmError::conflict("Deleted users cannot be renamed. Restore the user first."));
+    }
     
-    conn.execute("DELETE FROM users WHERE id = ?1", [&user_id])
-        .map_err(|e| e.to_string())?;
+    if old_username == new_username {
+        return Ok(());
+    }
+    
+    // Records keep the username they were written with, as history. Approval
+    // and self-approval checks go by user ID, so they are not affected.
+    let tx = conn.transaction()?;
+    tx.execute(
+        "UPDATE users SET username = ?1 WHERE id = ?2",
+        rusqlite::params![new_username, user_id],
+    )
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE constraint") {
+            HrmError::conflict("Username already exists")
+        } else {
+            HrmError::from(e)
+        }
+    })?;
+    
+    // Open sessions still carry the old name; make the user log in again
+    end_user_sessions(&tx, user_id, "renamed")?;
+    
+    log_audit_action(
+        &tx,
+        Some(current_user_id),
+        &current_username,
+        "RENAME",
+        "USER",
+        Some(&user_id.to_string()),
+        Some(&old_username),
+        Some(&new_username),
+        Some(&format!("Renamed user '{}' to '{}'", old_username, new_username)),
+    );
+    tx.commit()?;
     
     Ok(())
 }
@@ -309,23 +699,22 @@ pub fn reset_user_password(
     new_password: String,
     db: State<'_, DbConnection>,
     current_user: State<'_, CurrentUser>,
-) -> Result<(), String> {
+) -> Result<(), HrmError> {
     // Check if current user is admin
-    let user_lock = current_user.0.lock().map_err(|e| e.to_string())?;
+    let user_lock = current_user.0.lock()?;
     match &*user_lock {
         Some(session) if session.permissions.can_manage_users => {}
-        _ => return Err("Permission denied".to_string()),
+        _ => return Err(HrmError::permission_denied("Permission denied")),
     }
     drop(user_lock);
     
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.0.lock()?;
     let password_hash = hash_password(&new_password);
     
     conn.execute(
         "UPDATE users SET password_hash = ?1 WHERE id = ?2",
         rusqlite::params![password_hash, user_id],
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
     
     Ok(())
 }
@@ -336,15 +725,15 @@ pub fn change_own_password(
     new_password: String,
     db: State<'_, DbConnection>,
     current_user: State<'_, CurrentUser>,
-) -> Result<(), String> {
-    let user_lock = current_user.0.lock().map_err(|e| e.to_string())?;
+) -> Result<(), HrmError> {
+    let user_lock = current_user.0.lock()?;
     let user_id = match &*user_lock {
         Some(session) => session.user_id,
-        None => return Err("Not logged in".to_string()),
+        None => return Err(HrmError::not_logged_in()),
     };
     drop(user_lock);
     
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.0.lock()?;
     
     // Verify current password
     let stored_hash: String = conn
@@ -353,18 +742,282 @@ pub fn change_own_password(
             [&user_id],
             |row| row.get(0),
         )
-        .map_err(|_| "User not found".to_string())?;
+        .map_err(|_| HrmError::not_found("User not found"))?;
     
     if !verify_password(&current_password, &stored_hash) {
-        return Err("Current password is incorrect".to_string());
+        return Err(HrmError::validation("Current password is incorrect"));
     }
     
     let new_hash = hash_password(&new_password);
     conn.execute(
         "UPDATE users SET password_hash = ?1 WHERE id = ?2",
         rusqlite::params![new_hash, user_id],
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
     
     Ok(())
 }
+
+// Loads either the live user accounts or those in the recycle bin
+fn load_users(conn: &Connection, deleted: bool) -> Result<Vec<UserInfo>, HrmError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, username, full_name, role, department_access, is_active, created_at, last_login,
+                    can_view_employees, can_add_employees, can_edit_employees, can_delete_employees,
+                    can_manage_users, can_view_all_departments, can_export_data, can_view_reports,
+                    can_manage_settings, can_backup_database, can_view_audit_logs, can_login_anytime,
+                    login_start_time, login_end_time, login_days, deleted_at,
+                    can_view_sensitive_data, can_manage_disciplinary, can_approve_attendance,
+                    can_manage_attendance, can_approve_leave, can_run_payroll, can_view_salaries
+             FROM users WHERE (deleted_at IS NOT NULL) = ?1 ORDER BY id",
+        )?;
+    
+    let users = stmt
+        .query_map([deleted], |row| {
+            Ok(UserInfo {
+                id: row.get(0)?,
+                username: row.get(1)?,
+                full_name: row.get(2)?,
+                role: row.get(3)?,
+                department_access: row.get(4)?,
+                is_active: row.get(5)?,
+                created_at: row.get(6)?,
+                last_login: row.get(7)?,
+                permissions: Some(UserPermissions {
+                    can_view_employees: row.get(8)?,
+                    can_add_employees: row.get(9)?,
+                    can_edit_employees: row.get(10)?,
+                    can_delete_employees: row.get(11)?,
+                    can_manage_users: row.get(12)?,
+                    can_view_all_departments: row.get(13)?,
+                    can_export_data: row.get(14)?,
+                    can_view_reports: row.get(15)?,
+                    can_manage_settings: row.get(16)?,
+                    can_backup_database: row.get(17)?,
+                    can_view_audit_logs: row.get(18)?,
+                    can_login_anytime: row.get(19)?,
+                    can_view_sensitive_data: row.get(24)?,
+                    can_manage_disciplinary: row.get(25)?,
+                    can_approve_attendance: row.get(26)?,
+                    can_manage_attendance: row.get(27)?,
+                    can_approve_leave: row.get(28)?,
+                    can_run_payroll: row.get(29)?,
+                    can_view_salaries: row.get(30)?,
+                }),
+                login_hours: login_hours_from_columns(row.get(20)?, row.get(21)?, row.get(22)?),
+                deleted_at: row.get(23)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    Ok(users)
+}
+
+// True when the user is the only active account able to manage users
+fn is_last_active_admin(conn: &Connection, user_id: i32) -> Result<bool, HrmError> {
+    let is_admin: bool = conn
+        .query_row(
+            "SELECT can_manage_users = 1 AND is_active = 1 AND deleted_at IS NULL FROM users WHERE id = ?1",
+            [&user_id],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+    if !is_admin {
+        return Ok(false);
+    }
+    
+    let other_admins: i32 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM users
+             WHERE id != ?1 AND can_manage_users = 1 AND is_active = 1 AND deleted_at IS NULL",
+            [&user_id],
+            |row| row.get(0),
+        )?;
+    
+    Ok(other_admins == 0)
+}
+
+// Session helpers
+
+fn device_name() -> Option<String> {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+}
+
+fn is_session_active(conn: &Connection, session_id: i64) -> Result<bool, HrmError> {
+    let ended_at: Option<Option<String>> = conn
+        .query_row(
+            "SELECT ended_at FROM sessions WHERE id = ?1",
+            [&session_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(matches!(ended_at, Some(None)))
+}
+
+fn end_session(conn: &Connection, session_id: i64, reason: &str) -> Result<(), HrmError> {
+    conn.execute(
+        "UPDATE sessions SET ended_at = CURRENT_TIMESTAMP, end_reason = ?1
+         WHERE id = ?2 AND ended_at IS NULL",
+        rusqlite::params![reason, session_id],
+    )?;
+    Ok(())
+}
+
+// Drops the logged-in user if their session was terminated, from this or
+// another workstation
+fn check_session(db: &DbConnection, current_user: &CurrentUser) -> Result<(), HrmError> {
+    let conn = db.0.lock()?;
+    let mut user_lock = current_user.0.lock()?;
+    let Some(session_id) = user_lock.as_ref().and_then(|s| s.session_id) else { return Ok(()) };
+    if !is_session_active(&conn, session_id)? {
+        *user_lock = None;
+        return Err(HrmError::new(ErrorCode::NotLoggedIn, "Your session has ended. Please log in again."));
+    }
+    Ok(())
+}
+
+// Wraps the generated command handler so every command checks the sessions
+// table first; a terminated session loses access on its next command
+pub fn session_guard<R: Runtime>(
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        if !SESSION_EXEMPT_COMMANDS.contains(&invoke.message.command()) {
+            let webview = invoke.message.webview_ref();
+            if let (Some(db), Some(current_user)) = (webview.try_state::<DbConnection>(), webview.try_state::<CurrentUser>()) {
+                if let Err(e) = check_session(&db, &current_user) {
+                    invoke.resolver.reject(e);
+                    return true;
+                }
+            }
+        }
+        handler(invoke)
+    }
+}
+
+// Ends every open session of a user; returns how many were closed
+pub fn end_user_sessions(conn: &Connection, user_id: i32, reason: &str) -> Result<usize, HrmError> {
+    conn.execute(
+        "UPDATE sessions SET ended_at = CURRENT_TIMESTAMP, end_reason = ?1
+         WHERE user_id = ?2 AND ended_at IS NULL",
+        rusqlite::params![reason, user_id],
+    )
+    .map_err(HrmError::from)
+}
+
+// Login hours helpers
+
+fn login_hours_from_columns(
+    start_time: Option<String>,
+    end_time: Option<String>,
+    days: Option<String>,
+) -> Option<LoginHours> {
+    match (start_time, end_time) {
+        (Some(start_time), Some(end_time)) => Some(LoginHours { start_time, end_time, days }),
+        _ => None,
+    }
+}
+
+fn split_login_hours(hours: Option<&LoginHours>) -> (Option<String>, Option<String>, Option<String>) {
+    match hours {
+        Some(h) => (Some(h.start_time.clone()), Some(h.end_time.clone()), h.days.clone()),
+        None => (None, None, None),
+    }
+}
+
+fn get_login_hours(conn: &Connection, user_id: i32) -> Result<Option<LoginHours>, HrmError> {
+    conn.query_row(
+        "SELECT login_start_time, login_end_time, login_days FROM users WHERE id = ?1",
+        [&user_id],
+        |row| Ok(login_hours_from_columns(row.get(0)?, row.get(1)?, row.get(2)?)),
+    )
+    .map_err(HrmError::from)
+}
+
+fn is_valid_time(value: &str) -> bool {
+    let parts: Vec<&str> = value.split(':').collect();
+    if parts.len() != 2 || parts[0].len() != 2 || parts[1].len() != 2 {
+        return false;
+    }
+    match (parts[0].parse::<u32>(), parts[1].parse::<u32>()) {
+        (Ok(h), Ok(m)) => h < 24 && m < 60,
+        _ => false,
+    }
+}
+
+fn parse_login_days(days: &str) -> Result<Vec<u32>, HrmError> {
+    days.split(',')
+        .map(|d| d.trim())
+        .filter(|d| !d.is_empty())
+        .map(|d| match d.parse::<u32>() {
+            Ok(n) if n <= 6 => Ok(n),
+            _ => Err(HrmError::validation(format!("Invalid login day '{}'. Use 0 (Sunday) to 6 (Saturday)", d))),
+        })
+        .collect()
+}
+
+fn validate_login_hours(hours: &LoginHours) -> Result<(), HrmError> {
+    if !is_valid_time(&hours.start_time) || !is_valid_time(&hours.end_time) {
+        return Err(HrmError::validation("Login hours must be in HH:MM format"));
+    }
+    if hours.start_time == hours.end_time {
+        return Err(HrmError::validation("Login start and end time cannot be the same"));
+    }
+    if let Some(days) = &hours.days {
+        if parse_login_days(days)?.is_empty() {
+            return Err(HrmError::validation("At least one login day must be selected"));
+        }
+    }
+    Ok(())
+}
+
+fn is_within_login_hours(conn: &Connection, hours: &LoginHours) -> Result<bool, HrmError> {
+    // Use SQLite's local clock so this matches the timestamps written elsewhere
+    let (now, weekday): (String, u32) = conn
+        .query_row(
+            "SELECT strftime('%H:%M', 'now', 'localtime'), CAST(strftime('%w', 'now', 'localtime') AS INTEGER)",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+    
+    let start = hours.start_time.as_str();
+    let end = hours.end_time.as_str();
+    
+    // Work out which day the current window started on
+    let window_day = if start < end {
+        if now.as_str() >= start && now.as_str() < end {
+            weekday
+        } else {
+            return Ok(false);
+        }
+    } else if now.as_str() >= start {
+        weekday
+    } else if now.as_str() < end {
+        // Overnight window that began yesterday
+        (weekday + 6) % 7
+    } else {
+        return Ok(false);
+    };
+    
+    match &hours.days {
+        Some(days) => Ok(parse_login_days(days)?.contains(&window_day)),
+        None => Ok(true),
+    }
+}
+
+fn describe_login_hours(hours: &LoginHours) -> String {
+    const DAY_NAMES: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+    let days = hours
+        .days
+        .as_deref()
+        .and_then(|d| parse_login_days(d).ok())
+        .map(|days| {
+            days.iter()
+                .map(|d| DAY_NAMES[*d as usize])
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .unwrap_or_else(|| "every day".to_string());
+    format!("{}-{} ({})", hours.start_time, hours.end_time, days)
+}
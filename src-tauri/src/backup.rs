@@ -0,0 +1,347 @@
+use crate::commands::{format_file_size, log_audit_action};
+use crate::diagnostics::table_row_counts;
+use crate::error::HrmError;
+use crate::journal::{self, Operation};
+use crate::models::{BackupInfo, UserSession};
+use crate::settings_commands::set_setting;
+use crate::{open_database, AppDataDir, CurrentUser, DbConnection, SCHEMA_VERSION};
+use rusqlite::{Connection, OpenFlags};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+// Backups live in their own folder under the app data directory and are named
+// hrm_backup_YYYYMMDD_HHMMSS_<kind>.db so they sort by date
+const BACKUP_DIR: &str = "backups";
+const BACKUP_PREFIX: &str = "hrm_backup_";
+// The scheduler takes one automatic backup a day and keeps this many
+const AUTO_BACKUP_KEEP: usize = 14;
+
+fn require_backup_permission(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_backup_database => Ok(session.clone()),
+        Some(_) => Err(HrmError::permission_denied("Permission denied. You cannot back up or restore the database.")),
+        None => Err(HrmError::not_logged_in()),
+    }
+}
+
+pub fn backups_dir(app_dir: &Path) -> PathBuf {
+    app_dir.join(BACKUP_DIR)
+}
+
+// Takes a consistent copy of the live database with VACUUM INTO, which is
+// safe while the app is running, then verifies it. Returns the path of the
+// new file, or an error if the copy cannot be trusted for a restore.
+pub fn write_backup(conn: &Connection, app_dir: &Path, kind: &str) -> Result<PathBuf, HrmError> {
+    let dir = backups_dir(app_dir);
+    fs::create_dir_all(&dir)?;
+    
+    let (stamp, now): (String, String) = conn.query_row(
+        "SELECT strftime('%Y%m%d_%H%M%S', 'now', 'localtime'), datetime('now', 'localtime')",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    let path = dir.join(format!("{}{}_{}.db", BACKUP_PREFIX, stamp, kind));
+    if path.exists() {
+        return Err(HrmError::conflict("A backup was taken a moment ago. Please wait a second and try again."));
+    }
+    conn.execute("VACUUM INTO ?1", [path.to_string_lossy()])?;
+    
+    // Verify before anything else is written, so the row counts must match
+    let id = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let (passed, details) = match verify_backup(conn, &path) {
+        Ok(mismatches) if mismatches.is_empty() => (true, "Integrity check passed and row counts match".to_string()),
+        Ok(mismatches) => (false, format!("Row counts differ: {}", mismatches.join(", "))),
+        Err(e) => (false, e.to_string()),
+    };
+    conn.execute(
+        "INSERT INTO backup_verifications (backup_id, passed, details) VALUES (?1, ?2, ?3)",
+        rusqlite::params![id, passed, details],
+    )?;
+    if !passed {
+        tracing::error!("Backup {} failed verification: {}", id, details);
+        return Err(HrmError::internal(format!("Backup {} failed verification: {}", id, details)));
+    }
+    
+    // Read by the startup health check to warn when backups are overdue
+    set_setting(conn, "last_backup_at", Some(&now))?;
+    Ok(path)
+}
+
+// Opens a fresh backup read-only, runs the integrity check and compares the
+// row count of every table with the live database. Returns the tables whose
+// counts differ.
+fn verify_backup(conn: &Connection, path: &Path) -> Result<Vec<String>, HrmError> {
+    verify_database_file(path)?;
+    let backup_counts = table_row_counts(&open_read_only(path)?)?;
+    let live_counts = table_row_counts(conn)?;
+    
+    let mismatches = live_counts
+        .iter()
+        .filter(|(table, count)| backup_counts.get(*table) != Some(*count))
+        .map(|(table, count)| {
+            let in_backup = backup_counts.get(table).map(|c| c.to_string()).unwrap_or_else(|| "missing".to_string());
+            format!("{} ({} live, {} in backup)", table, count, in_backup)
+        })
+        .collect();
+    Ok(mismatches)
+}
+
+// Takes the day's automatic backup if there is none yet and removes the
+// oldest automatic backups beyond AUTO_BACKUP_KEEP. Called by the scheduler.
+pub fn run_automatic_backup(conn: &Connection, app_dir: &Path) -> Result<Option<PathBuf>, HrmError> {
+    let today: String = conn.query_row("SELECT strftime('%Y%m%d', 'now', 'localtime')", [], |row| row.get(0))?;
+    let today_prefix = format!("{}{}_", BACKUP_PREFIX, today);
+    let mut automatic = automatic_backups(app_dir)?;
+    if automatic.iter().any(|id| id.starts_with(&today_prefix)) {
+        return Ok(None);
+    }
+    
+    let path = write_backup(conn, app_dir, "auto")?;
+    if let Some(id) = path.file_name() {
+        automatic.push(id.to_string_lossy().to_string());
+    }
+    
+    automatic.sort();
+    let excess = automatic.len().saturating_sub(AUTO_BACKUP_KEEP);
+    for id in &automatic[..excess] {
+        if let Err(e) = fs::remove_file(backups_dir(app_dir).join(id)) {
+            tracing::warn!("Failed to remove old automatic backup {}: {}", id, e);
+        }
+    }
+    Ok(Some(path))
+}
+
+fn automatic_backups(app_dir: &Path) -> Result<Vec<String>, HrmError> {
+    Ok(backup_ids(app_dir)?.into_iter().filter(|id| id.ends_with("_auto.db")).collect())
+}
+
+// File names of every backup in the backups folder, newest first
+pub(crate) fn backup_ids(app_dir: &Path) -> Result<Vec<String>, HrmError> {
+    let dir = backups_dir(app_dir);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut ids = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let id = entry?.file_name().to_string_lossy().to_string();
+        if id.starts_with(BACKUP_PREFIX) && id.ends_with(".db") {
+            ids.push(id);
+        }
+    }
+    ids.sort_by(|a, b| b.cmp(a));
+    Ok(ids)
+}
+
+pub fn open_read_only(path: &Path) -> Result<Connection, HrmError> {
+    Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+        .map_err(|e| HrmError::validation(format!("Invalid database file: {}", e)))
+}
+
+// Confirms a database file is intact and looks like an HRM database
+pub fn verify_database_file(path: &Path) -> Result<(), HrmError> {
+    let conn = open_read_only(path)?;
+    let integrity: String = conn
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .map_err(|e| HrmError::validation(format!("Invalid database file: {}", e)))?;
+    if integrity != "ok" {
+        return Err(HrmError::validation(format!("Backup failed the integrity check: {}", integrity)));
+    }
+    
+    let required_tables: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name IN ('employees', 'users')",
+        [],
+        |row| row.get(0),
+    )?;
+    if required_tables < 2 {
+        return Err(HrmError::validation("Invalid HRM database: missing required tables"));
+    }
+    // Older databases are migrated when opened; newer ones may rely on
+    // tables or columns this release does not know about
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version > SCHEMA_VERSION {
+        return Err(HrmError::validation(format!(
+            "This database is from a newer version of the app (schema {}, this app supports {}). Install the latest version first.",
+            version, SCHEMA_VERSION
+        )));
+    }
+    Ok(())
+}
+
+// "hrm_backup_20240131_183000_manual.db" -> "2024-01-31 18:30:00"
+fn backup_date(id: &str) -> Option<String> {
+    let stamp = id.strip_prefix(BACKUP_PREFIX)?.get(..15)?;
+    let (date, time) = stamp.split_once('_')?;
+    if date.len() != 8 || time.len() != 6 || !(date.chars().chain(time.chars())).all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(format!(
+        "{}-{}-{} {}:{}:{}",
+        &date[..4], &date[4..6], &date[6..], &time[..2], &time[2..4], &time[4..]
+    ))
+}
+
+// Backup ids come from the frontend, so only accept a plain file name that
+// exists in the backups folder
+pub(crate) fn resolve_backup(app_dir: &Path, id: &str) -> Result<PathBuf, HrmError> {
+    if !id.starts_with(BACKUP_PREFIX) || !id.ends_with(".db") || id.contains(['/', '\\']) || id.contains("..") {
+        return Err(HrmError::validation("Invalid backup id"));
+    }
+    let path = backups_dir(app_dir).join(id);
+    if !path.is_file() {
+        return Err(HrmError::not_found("Backup not found"));
+    }
+    Ok(path)
+}
+
+// Details shown for one backup file; the verification result comes from the
+// live database because a backup cannot record its own check
+fn backup_info(conn: &Connection, path: &Path) -> BackupInfo {
+    let id = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let size_bytes = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let employee_count = open_read_only(path)
+        .ok()
+        .and_then(|backup| backup.query_row("SELECT COUNT(*) FROM employees", [], |row| row.get(0)).ok());
+    let (verified, verified_at, verification_details) = conn
+        .query_row(
+            "SELECT passed, verified_at, details FROM backup_verifications
+             WHERE backup_id = ?1 ORDER BY id DESC LIMIT 1",
+            [&id],
+            |row| Ok((Some(row.get::<_, bool>(0)?), row.get(1)?, row.get(2)?)),
+        )
+        .unwrap_or((None, None, None));
+    
+    BackupInfo {
+        created_at: backup_date(&id),
+        id,
+        size_bytes,
+        size_display: format_file_size(size_bytes),
+        employee_count,
+        verified,
+        verified_at,
+        verification_details,
+    }
+}
+
+#[tauri::command]
+pub fn list_backups(
+    db: State<'_, DbConnection>,
+    app_data_dir: State<'_, AppDataDir>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<BackupInfo>, HrmError> {
+    require_backup_permission(&current_user)?;
+    
+    let dir = backups_dir(&app_data_dir.0);
+    let conn = db.0.lock()?;
+    let backups = backup_ids(&app_data_dir.0)?
+        .iter()
+        .map(|id| backup_info(&conn, &dir.join(id)))
+        .collect();
+    
+    Ok(backups)
+}
+
+#[tauri::command]
+pub fn create_backup(
+    db: State<'_, DbConnection>,
+    app_data_dir: State<'_, AppDataDir>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<BackupInfo, HrmError> {
+    let user = require_backup_permission(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let path = write_backup(&conn, &app_data_dir.0, "manual")?;
+    let info = backup_info(&conn, &path);
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "BACKUP",
+        "DATABASE",
+        Some(&info.id),
+        None,
+        None,
+        Some(&format!("Created database backup {}", info.id)),
+    );
+    
+    Ok(info)
+}
+
+// Copies the new database next to the live one and renames it over the live
+// file. The connection must be closed first.
+fn replace_database_file(app_dir: &Path, source: &Path) -> Result<(), HrmError> {
+    let staged_path = "hrm_system.db.restoring";
+    let intent = journal::begin(app_dir, &Operation::ImportDatabase { staged_path: staged_path.to_string() })?;
+    let staged = app_dir.join(staged_path);
+    fs::copy(source, &staged)
+        .and_then(|_| fs::File::open(&staged)?.sync_all())
+        .and_then(|_| fs::rename(&staged, app_dir.join("hrm_system.db")))
+        .map_err(|e| HrmError::io(format!("Failed to replace database: {}", e)))?;
+    intent.commit()
+}
+
+// Puts `source` in place of the live database behind `conn`. The caller holds
+// the database lock for the whole swap.
+pub(crate) fn swap_database_file(conn: &mut Connection, app_dir: &Path, source: &Path) -> Result<(), HrmError> {
+    // Close the live file before replacing it; Windows will not rename over
+    // a file that is still open
+    *conn = Connection::open_in_memory()?;
+    
+    if let Err(e) = replace_database_file(app_dir, source) {
+        // The live file was not replaced; go back to it rather than carry on
+        // with the empty stand-in, where every later write would be lost
+        *conn = open_database(app_dir)
+            .map_err(|reopen| HrmError::internal(format!("{} The database could not be reopened. Please restart the application. ({})", e, reopen)))?;
+        return Err(e);
+    }
+    
+    // Re-opening runs the migrations, so an older database gains any newer tables
+    *conn = open_database(app_dir)
+        .map_err(|e| HrmError::internal(format!("Database was replaced but could not be opened. Please restart the application. ({})", e)))?;
+    Ok(())
+}
+
+// Replaces the live database with a backup. The current database is backed up
+// first so a restore can itself be undone, and everyone has to log in again
+// because the restored users table may differ.
+#[tauri::command]
+pub fn restore_backup(
+    id: String,
+    db: State<'_, DbConnection>,
+    app_data_dir: State<'_, AppDataDir>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<String, HrmError> {
+    let user = require_backup_permission(&current_user)?;
+    let app_dir = &app_data_dir.0;
+    let source = resolve_backup(app_dir, &id)?;
+    verify_database_file(&source)?;
+    
+    // Hold the lock for the whole swap so no command touches the database
+    let mut conn = db.0.lock()?;
+    let safety_copy = write_backup(&conn, app_dir, "pre_restore")?;
+    
+    swap_database_file(&mut conn, app_dir, &source)?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "RESTORE",
+        "DATABASE",
+        Some(&id),
+        None,
+        None,
+        Some(&format!(
+            "Restored database from backup {} (previous database saved as {})",
+            id,
+            safety_copy.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+        )),
+    );
+    drop(conn);
+    
+    *current_user.0.lock()? = None;
+    
+    Ok(format!("Database restored from backup {}. Please log in again.", id))
+}
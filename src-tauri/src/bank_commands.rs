@@ -0,0 +1,313 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::export_commands::csv_field;
+use crate::models::{EmployeeBankAccount, FieldError, UserSession};
+use crate::payroll_commands::load_payroll_run;
+use crate::settings_commands::get_setting;
+use crate::{CurrentUser, DbConnection};
+use chrono::{Local, NaiveDate};
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::fs;
+use tauri::State;
+
+// SLIPS transaction code for a salary credit
+const SLIPS_SALARY_CREDIT: &str = "23";
+// Widest account number a SLIPS record holds
+const MAX_ACCOUNT_DIGITS: usize = 12;
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+// Bank details are only shown to users who can see sensitive personal data
+fn require_bank_access(user: &UserSession) -> Result<(), HrmError> {
+    if user.permissions.can_view_sensitive_data {
+        Ok(())
+    } else {
+        Err(HrmError::permission_denied("Permission denied. You cannot view bank details."))
+    }
+}
+
+// Checks the codes and account number fit what SLIPS accepts: a 4 digit bank
+// code, 3 digit branch code and up to 12 digit account number
+pub(crate) fn validate_bank_account(bank_code: &str, branch_code: &str, account_number: &str) -> Result<(), String> {
+    let digits = |value: &str, len: usize| value.len() == len && value.chars().all(|c| c.is_ascii_digit());
+    if !digits(bank_code, 4) {
+        return Err("Bank code must be 4 digits".to_string());
+    }
+    if !digits(branch_code, 3) {
+        return Err("Branch code must be 3 digits".to_string());
+    }
+    if account_number.is_empty()
+        || account_number.len() > MAX_ACCOUNT_DIGITS
+        || !account_number.chars().all(|c| c.is_ascii_digit())
+    {
+        return Err(format!("Account number must be 1 to {} digits", MAX_ACCOUNT_DIGITS));
+    }
+    if account_number.chars().all(|c| c == '0') {
+        return Err("Account number cannot be all zeros".to_string());
+    }
+    Ok(())
+}
+
+fn load_bank_accounts(conn: &Connection) -> Result<HashMap<String, EmployeeBankAccount>, HrmError> {
+    let mut stmt = conn.prepare(
+        "SELECT epf_number, bank_code, branch_code, account_number, account_name, updated_by, updated_at
+         FROM employee_bank_accounts",
+    )?;
+    let accounts = stmt
+        .query_map([], |row| {
+            Ok(EmployeeBankAccount {
+                epf_number: row.get(0)?,
+                bank_code: row.get(1)?,
+                branch_code: row.get(2)?,
+                account_number: row.get(3)?,
+                account_name: row.get(4)?,
+                updated_by: row.get(5)?,
+                updated_at: row.get(6)?,
+            })
+        })?
+        .map(|account| account.map(|a| (a.epf_number.clone(), a)))
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    Ok(accounts)
+}
+
+// Left-justified, space-padded text field. Banks reject anything outside
+// plain upper-case ASCII.
+fn slips_text(value: &str, width: usize) -> String {
+    let cleaned: String = value
+        .to_uppercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == ' ' { c } else { ' ' })
+        .take(width)
+        .collect();
+    format!("{:<width$}", cleaned, width = width)
+}
+
+// Right-justified, zero-padded numeric field
+fn slips_number(value: &str, width: usize) -> String {
+    format!("{:0>width$}", value, width = width)
+}
+
+#[tauri::command]
+pub fn get_employee_bank_account(
+    epf_number: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Option<EmployeeBankAccount>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_bank_access(&user)?;
+    
+    let conn = db.0.lock()?;
+    let account = conn
+        .query_row(
+            "SELECT epf_number, bank_code, branch_code, account_number, account_name, updated_by, updated_at
+             FROM employee_bank_accounts WHERE epf_number = ?1",
+            [&epf_number],
+            |row| {
+                Ok(EmployeeBankAccount {
+                    epf_number: row.get(0)?,
+                    bank_code: row.get(1)?,
+                    branch_code: row.get(2)?,
+                    account_number: row.get(3)?,
+                    account_name: row.get(4)?,
+                    updated_by: row.get(5)?,
+                    updated_at: row.get(6)?,
+                })
+            },
+        )
+        .optional()?;
+    Ok(account)
+}
+
+// Saves the account an employee's salary is credited to
+#[tauri::command]
+pub fn set_employee_bank_account(
+    account: EmployeeBankAccount,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_bank_access(&user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit employees."));
+    }
+    let bank_code = account.bank_code.trim();
+    let branch_code = account.branch_code.trim();
+    let account_number: String = account.account_number.chars().filter(|c| !c.is_whitespace() && *c != '-').collect();
+    validate_bank_account(bank_code, branch_code, &account_number).map_err(|e| HrmError::field("account_number", e))?;
+    let account_name = account.account_name.trim();
+    if account_name.is_empty() {
+        return Err(HrmError::field("account_name", "Account name is required"));
+    }
+    
+    let conn = db.0.lock()?;
+    let employee_exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM employees WHERE epf_number = ?1)",
+        [&account.epf_number],
+        |row| row.get(0),
+    )?;
+    if !employee_exists {
+        return Err(HrmError::not_found(format!("Employee {} not found", account.epf_number)));
+    }
+    conn.execute(
+        "INSERT INTO employee_bank_accounts (epf_number, bank_code, branch_code, account_number, account_name, updated_by, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, CURRENT_TIMESTAMP)
+         ON CONFLICT(epf_number) DO UPDATE SET bank_code = excluded.bank_code, branch_code = excluded.branch_code,
+             account_number = excluded.account_number, account_name = excluded.account_name,
+             updated_by = excluded.updated_by, updated_at = CURRENT_TIMESTAMP",
+        rusqlite::params![account.epf_number, bank_code, branch_code, account_number, account_name, user.username],
+    )?;
+    
+    // Account numbers stay out of the audit text; the log is readable by non-HR users
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "EMPLOYEE",
+        Some(&account.epf_number),
+        None,
+        None,
+        Some(&format!("Updated salary bank account (bank {} branch {})", bank_code, branch_code)),
+    );
+    
+    Ok(())
+}
+
+// Writes the salary credit file for a locked payroll run. `bank` picks the
+// layout: "slips" for the LankaClear SLIPS fixed-width file every member bank
+// accepts, or "csv" for internet banking bulk uploads. The company account
+// comes from the payroll_bank_code, payroll_branch_code, payroll_account_number
+// and payroll_account_name settings. Every employee's account is checked first
+// and nothing is written if any is missing or invalid. Returns the number of
+// credits.
+#[tauri::command]
+pub fn generate_bank_transfer_file(
+    payroll_run_id: i64,
+    bank: String,
+    value_date: Option<String>,
+    destination_path: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<usize, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_bank_access(&user)?;
+    if !user.permissions.can_run_payroll || !user.permissions.can_export_data {
+        return Err(HrmError::permission_denied("Permission denied. You cannot export payroll data."));
+    }
+    if !["slips", "csv"].contains(&bank.as_str()) {
+        return Err(HrmError::validation(format!("Unsupported bank file format: {}", bank)));
+    }
+    let value_date = match value_date.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+        Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| HrmError::field("value_date", "Value date must be in YYYY-MM-DD format"))?,
+        None => Local::now().date_naive(),
+    };
+    
+    let conn = db.0.lock()?;
+    let run = load_payroll_run(&conn, payroll_run_id)?;
+    if run.status != "locked" {
+        return Err(HrmError::validation("Bank files can only be made from a locked payroll run"));
+    }
+    
+    let company = (
+        get_setting(&conn, "payroll_bank_code").unwrap_or_default(),
+        get_setting(&conn, "payroll_branch_code").unwrap_or_default(),
+        get_setting(&conn, "payroll_account_number").unwrap_or_default(),
+        get_setting(&conn, "payroll_account_name").unwrap_or_default(),
+    );
+    if bank == "slips" {
+        validate_bank_account(&company.0, &company.1, &company.2)
+            .map_err(|e| HrmError::validation(format!("Company account in payroll settings: {}", e)))?;
+    }
+    
+    let accounts = load_bank_accounts(&conn)?;
+    let mut errors = Vec::new();
+    let mut credits = Vec::new();
+    for line in run.lines.iter().filter(|l| l.net_pay > 0.0) {
+        match accounts.get(&line.epf_number) {
+            None => errors.push(FieldError {
+                field: line.epf_number.clone(),
+                message: format!("{} has no bank account on record", line.name_with_initials),
+            }),
+            Some(account) => match validate_bank_account(&account.bank_code, &account.branch_code, &account.account_number) {
+                Err(e) => errors.push(FieldError {
+                    field: line.epf_number.clone(),
+                    message: format!("{}: {}", line.name_with_initials, e),
+                }),
+                Ok(()) => credits.push((line, account)),
+            },
+        }
+    }
+    if !errors.is_empty() {
+        return Err(HrmError::fields(
+            format!("{} employee(s) need bank details fixed before the file can be made", errors.len()),
+            errors,
+        ));
+    }
+    
+    let particulars = format!("SALARY {}", run.month);
+    let mut content = String::new();
+    if bank == "slips" {
+        let value_date = value_date.format("%y%m%d").to_string();
+        for (line, account) in &credits {
+            let cents = (line.net_pay * 100.0).round() as i64;
+            content.push_str("0000");
+            content.push_str(&account.bank_code);
+            content.push_str(&account.branch_code);
+            content.push_str(&slips_number(&account.account_number, 12));
+            content.push_str(&slips_text(&account.account_name, 20));
+            content.push_str(SLIPS_SALARY_CREDIT);
+            // Return code, credit/debit flag and return date, blank on submission
+            content.push_str("00");
+            content.push_str("000000");
+            content.push_str(&slips_number(&cents.to_string(), 12));
+            content.push_str("SLR");
+            content.push_str(&company.0);
+            content.push_str(&company.1);
+            content.push_str(&slips_number(&company.2, 12));
+            content.push_str(&slips_text(&company.3, 20));
+            content.push_str(&slips_text(&particulars, 15));
+            content.push_str(&slips_text(&line.epf_number, 15));
+            content.push_str(&value_date);
+            content.push_str("000000");
+            content.push_str("\r\n");
+        }
+    } else {
+        content.push_str("Bank Code,Branch Code,Account Number,Account Name,Amount,Reference,Particulars,Value Date\r\n");
+        for (line, account) in &credits {
+            let values = [
+                account.bank_code.clone(),
+                account.branch_code.clone(),
+                account.account_number.clone(),
+                account.account_name.clone(),
+                format!("{:.2}", line.net_pay),
+                line.epf_number.clone(),
+                particulars.clone(),
+                value_date.format("%Y-%m-%d").to_string(),
+            ];
+            let fields: Vec<String> = values.iter().map(|v| csv_field(v)).collect();
+            content.push_str(&fields.join(","));
+            content.push_str("\r\n");
+        }
+    }
+    
+    fs::write(&destination_path, content).map_err(|e| HrmError::io(format!("Failed to save file: {}", e)))?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "EXPORT",
+        "PAYROLL",
+        Some(&payroll_run_id.to_string()),
+        None,
+        Some(&bank),
+        Some(&format!("Generated {} bank transfer file for {} ({} credits)", bank, run.month, credits.len())),
+    );
+    
+    Ok(credits.len())
+}
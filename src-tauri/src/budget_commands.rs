@@ -0,0 +1,206 @@
+use crate::commands::log_audit_action;
+use crate::deduction_commands::require_month;
+use crate::error::HrmError;
+use crate::models::{LabourBudget, LabourBudgetComparison, UserSession};
+use crate::payroll_commands::payroll_cost_by_center;
+use crate::{CurrentUser, DbConnection};
+use std::collections::{BTreeMap, VecDeque};
+use tauri::State;
+
+// Months averaged for the trend line on the budget report
+const TREND_MONTHS: usize = 3;
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+// Budgets are compared with salary totals, so viewing them needs salary access
+fn require_budget_view(user: &UserSession) -> Result<(), HrmError> {
+    if user.permissions.can_view_salaries && user.permissions.can_view_reports {
+        Ok(())
+    } else {
+        Err(HrmError::permission_denied("Permission denied. You cannot view labour cost budgets."))
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[tauri::command]
+pub fn get_labour_budgets(
+    from_month: String,
+    to_month: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<LabourBudget>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_budget_view(&user)?;
+    require_month(&from_month)?;
+    require_month(&to_month)?;
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, department, month, amount, updated_by, updated_at FROM labour_budgets
+         WHERE month BETWEEN ?1 AND ?2 ORDER BY month, department",
+    )?;
+    let budgets = stmt
+        .query_map([&from_month, &to_month], |row| {
+            Ok(LabourBudget {
+                id: row.get(0)?,
+                department: row.get(1)?,
+                month: row.get(2)?,
+                amount: row.get(3)?,
+                updated_by: row.get(4)?,
+                updated_at: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .filter(|budget| user.can_access_department(Some(&budget.department)))
+        .collect();
+    Ok(budgets)
+}
+
+// Sets a department's labour cost budget for a month, replacing any earlier figure
+#[tauri::command]
+pub fn set_labour_budget(
+    department: String,
+    month: String,
+    amount: f64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_budget_view(&user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot set labour cost budgets."));
+    }
+    let department = department.trim();
+    if department.is_empty() {
+        return Err(HrmError::field("department", "Department is required"));
+    }
+    if !user.can_access_department(Some(department)) {
+        return Err(HrmError::permission_denied("Permission denied. You cannot set budgets for this department."));
+    }
+    require_month(&month)?;
+    if !amount.is_finite() || amount < 0.0 {
+        return Err(HrmError::field("amount", "Budget must be zero or more"));
+    }
+    
+    let conn = db.0.lock()?;
+    conn.execute(
+        "INSERT INTO labour_budgets (department, month, amount, updated_by, updated_at)
+         VALUES (?1, ?2, ?3, ?4, CURRENT_TIMESTAMP)
+         ON CONFLICT(department, month) DO UPDATE SET amount = excluded.amount,
+             updated_by = excluded.updated_by, updated_at = CURRENT_TIMESTAMP",
+        rusqlite::params![department, month, round2(amount), user.username],
+    )?;
+    
+    // Amounts stay out of the audit text; the log is readable by non-HR users
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "BUDGET",
+        Some(department),
+        None,
+        Some(&month),
+        Some(&format!("Set labour cost budget for {} in {}", department, month)),
+    );
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_labour_budget(
+    id: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_budget_view(&user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot set labour cost budgets."));
+    }
+    
+    let conn = db.0.lock()?;
+    let (department, month): (String, String) = conn
+        .query_row("SELECT department, month FROM labour_budgets WHERE id = ?1", [id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|_| HrmError::not_found("Budget not found"))?;
+    if !user.can_access_department(Some(&department)) {
+        return Err(HrmError::permission_denied("Permission denied. You cannot set budgets for this department."));
+    }
+    conn.execute("DELETE FROM labour_budgets WHERE id = ?1", [id])?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "DELETE",
+        "BUDGET",
+        Some(&department),
+        Some(&month),
+        None,
+        Some(&format!("Removed labour cost budget for {} in {}", department, month)),
+    );
+    
+    Ok(())
+}
+
+// Actual payroll cost (gross plus employer EPF and ETF, from locked runs)
+// against budget for each department and month in the range. The trend is the
+// average actual cost over the last three months up to and including the row.
+#[tauri::command]
+pub fn get_budget_vs_actual_report(
+    from_month: String,
+    to_month: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<LabourBudgetComparison>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_budget_view(&user)?;
+    
+    let conn = db.0.lock()?;
+    let mut rows: BTreeMap<(String, String), (Option<f64>, f64)> = BTreeMap::new();
+    for center in payroll_cost_by_center(&conn, &user, &from_month, &to_month, "department")? {
+        rows.entry((center.cost_center, center.month)).or_default().1 = center.total_cost;
+    }
+    let mut stmt = conn.prepare("SELECT department, month, amount FROM labour_budgets WHERE month BETWEEN ?1 AND ?2")?;
+    for budget in stmt.query_map([&from_month, &to_month], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, f64>(2)?))
+    })? {
+        let (department, month, amount) = budget?;
+        if user.can_access_department(Some(&department)) {
+            rows.entry((department, month)).or_default().0 = Some(amount);
+        }
+    }
+    
+    // Rows are ordered by department then month, so the trend window restarts per department
+    let mut report = Vec::with_capacity(rows.len());
+    let mut window: VecDeque<f64> = VecDeque::with_capacity(TREND_MONTHS);
+    let mut current_department = String::new();
+    for ((department, month), (budget, actual)) in rows {
+        if department != current_department {
+            window.clear();
+            current_department = department.clone();
+        }
+        if window.len() == TREND_MONTHS {
+            window.pop_front();
+        }
+        window.push_back(actual);
+        let variance = budget.map(|b| round2(actual - b));
+        report.push(LabourBudgetComparison {
+            department,
+            month,
+            budget,
+            actual,
+            variance,
+            variance_percent: budget.filter(|b| *b > 0.0).map(|b| round2((actual - b) / b * 100.0)),
+            trend: round2(window.iter().sum::<f64>() / window.len() as f64),
+        });
+    }
+    Ok(report)
+}
@@ -0,0 +1,408 @@
+use crate::backup::{self, backups_dir, verify_database_file};
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{CloudBackupConfig, CloudBackupSettings, UserSession};
+use crate::secrets;
+use crate::settings_commands::{get_setting, get_setting_bool, set_setting};
+use crate::{AppDataDir, CurrentUser, DbConnection};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use hmac::{Hmac, Mac};
+use rusqlite::Connection;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::State;
+
+// Backups are encrypted with a key derived from a passphrase the admin keeps
+// off the PC, so they can still be opened after the PC is stolen or dies.
+// File layout: MAGIC, salt, nonce, AES-256-GCM ciphertext of the database.
+const MAGIC: &[u8; 8] = b"HRMBAK1\0";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const PBKDF2_ROUNDS: u32 = 210_000;
+const MIN_PASSPHRASE_LEN: usize = 12;
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+const ENABLED: &str = "cloud_backup_enabled";
+const ENDPOINT: &str = "cloud_backup_endpoint";
+const BUCKET: &str = "cloud_backup_bucket";
+const REGION: &str = "cloud_backup_region";
+const PREFIX: &str = "cloud_backup_prefix";
+const ACCESS_KEY_ID: &str = "cloud_backup_access_key_id";
+const SECRET_ACCESS_KEY: &str = "cloud_backup_secret_access_key";
+const PASSPHRASE: &str = "cloud_backup_passphrase";
+const LAST_UPLOAD_AT: &str = "cloud_backup_last_upload_at";
+const LAST_UPLOADED: &str = "cloud_backup_last_uploaded";
+const LAST_ERROR: &str = "cloud_backup_last_error";
+
+// Settings stored encrypted; never returned by get_app_settings or written
+// through update_app_setting
+pub const SECRET_SETTINGS: [&str; 2] = [SECRET_ACCESS_KEY, PASSPHRASE];
+
+const DEFAULT_REGION: &str = "us-east-1";
+const DEFAULT_PREFIX: &str = "hrm-backups";
+
+fn require_backup_permission(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_backup_database => Ok(session.clone()),
+        Some(_) => Err(HrmError::permission_denied("Permission denied. You cannot manage database backups.")),
+        None => Err(HrmError::not_logged_in()),
+    }
+}
+
+// An S3-compatible bucket: AWS S3, Backblaze B2, Wasabi, Cloudflare R2, MinIO
+// or Google Cloud Storage with HMAC keys
+struct S3Target {
+    endpoint: String,
+    bucket: String,
+    region: String,
+    prefix: String,
+    access_key_id: String,
+    secret_access_key: String,
+    passphrase: String,
+}
+
+// A backup waiting to go offsite, read while the database lock is held so the
+// upload itself can run without it
+pub struct PendingUpload {
+    pub backup_id: String,
+    path: PathBuf,
+    target: S3Target,
+}
+
+fn setting_or(conn: &Connection, key: &str, default: &str) -> String {
+    get_setting(conn, key).filter(|v| !v.trim().is_empty()).unwrap_or_else(|| default.to_string())
+}
+
+fn load_target(conn: &Connection, app_dir: &Path) -> Result<S3Target, HrmError> {
+    let secret = |key: &str, label: &str| match get_setting(conn, key) {
+        Some(stored) => secrets::decrypt(app_dir, &stored),
+        None => Err(HrmError::validation(format!("Cloud backup {} is not set", label))),
+    };
+    let target = S3Target {
+        endpoint: setting_or(conn, ENDPOINT, ""),
+        bucket: setting_or(conn, BUCKET, ""),
+        region: setting_or(conn, REGION, DEFAULT_REGION),
+        prefix: setting_or(conn, PREFIX, DEFAULT_PREFIX),
+        access_key_id: setting_or(conn, ACCESS_KEY_ID, ""),
+        secret_access_key: secret(SECRET_ACCESS_KEY, "secret access key")?,
+        passphrase: secret(PASSPHRASE, "encryption passphrase")?,
+    };
+    if target.endpoint.is_empty() || target.bucket.is_empty() || target.access_key_id.is_empty() {
+        return Err(HrmError::validation("Cloud backup endpoint, bucket and access key are required"));
+    }
+    // The signed request carries the access key id, so never send it in the clear
+    if !target.endpoint.starts_with("https://") {
+        return Err(HrmError::validation("Cloud backup endpoint must start with https://"));
+    }
+    Ok(target)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Key<Aes256Gcm> {
+    let mut key = Key::<Aes256Gcm>::default();
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, PBKDF2_ROUNDS, &mut key);
+    key
+}
+
+pub fn encrypt_backup(data: &[u8], passphrase: &str) -> Result<Vec<u8>, HrmError> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = Aes256Gcm::new(&derive_key(passphrase, &salt))
+        .encrypt(&nonce, data)
+        .map_err(|_| HrmError::internal("Failed to encrypt backup"))?;
+    
+    let mut sealed = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    sealed.extend_from_slice(MAGIC);
+    sealed.extend_from_slice(&salt);
+    sealed.extend_from_slice(&nonce);
+    sealed.extend(ciphertext);
+    Ok(sealed)
+}
+
+pub fn decrypt_backup(sealed: &[u8], passphrase: &str) -> Result<Vec<u8>, HrmError> {
+    let header_len = MAGIC.len() + SALT_LEN + NONCE_LEN;
+    if sealed.len() <= header_len || &sealed[..MAGIC.len()] != MAGIC {
+        return Err(HrmError::validation("This is not an encrypted HRM backup file"));
+    }
+    let salt = &sealed[MAGIC.len()..MAGIC.len() + SALT_LEN];
+    let nonce = Nonce::from_slice(&sealed[MAGIC.len() + SALT_LEN..header_len]);
+    Aes256Gcm::new(&derive_key(passphrase, salt))
+        .decrypt(nonce, &sealed[header_len..])
+        .map_err(|_| HrmError::validation("Wrong passphrase, or the backup file is damaged"))
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+// PUT of one object using AWS Signature Version 4 with path-style addressing,
+// which every S3-compatible service accepts
+fn put_object(target: &S3Target, object_key: &str, body: &[u8]) -> Result<(), HrmError> {
+    let endpoint = target.endpoint.trim_end_matches('/');
+    let host = endpoint
+        .split_once("://")
+        .map(|(_, rest)| rest)
+        .unwrap_or(endpoint)
+        .split('/')
+        .next()
+        .unwrap_or_default();
+    let path = format!("/{}/{}", target.bucket, object_key);
+    
+    let now = chrono::Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let payload_hash = hex::encode(Sha256::digest(body));
+    
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "PUT\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        path, host, payload_hash, amz_date, signed_headers, payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, target.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    let signing_key = ["s3", "aws4_request"].iter().fold(
+        hmac_sha256(
+            &hmac_sha256(format!("AWS4{}", target.secret_access_key).as_bytes(), &date),
+            &target.region,
+        ),
+        |key, part| hmac_sha256(&key, part),
+    );
+    let signature = hex::encode(hmac_sha256(&signing_key, &string_to_sign));
+    let authorization = format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        target.access_key_id, scope, signed_headers, signature
+    );
+    
+    let agent = ureq::AgentBuilder::new().timeout(UPLOAD_TIMEOUT).build();
+    match agent
+        .put(&format!("{}{}", endpoint, path))
+        .set("x-amz-date", &amz_date)
+        .set("x-amz-content-sha256", &payload_hash)
+        .set("authorization", &authorization)
+        .send_bytes(body)
+    {
+        Ok(_) => Ok(()),
+        Err(ureq::Error::Status(code, response)) => {
+            let detail = response.into_string().unwrap_or_default();
+            Err(HrmError::io(format!("Cloud storage rejected the upload (HTTP {}): {}", code, detail.trim())))
+        }
+        Err(e) => Err(HrmError::io(format!("Could not reach cloud storage: {}", e))),
+    }
+}
+
+// The newest automatic backup if cloud backup is on and it has not been
+// uploaded yet. A failed upload is retried on the next scheduler run.
+pub fn pending_upload(conn: &Connection, app_dir: &Path) -> Result<Option<PendingUpload>, HrmError> {
+    if !get_setting_bool(conn, ENABLED) {
+        return Ok(None);
+    }
+    let latest = backup::backup_ids(app_dir)?.into_iter().find(|id| id.ends_with("_auto.db"));
+    match latest {
+        Some(id) if get_setting(conn, LAST_UPLOADED).as_deref() != Some(id.as_str()) => Ok(Some(PendingUpload {
+            path: backups_dir(app_dir).join(&id),
+            backup_id: id,
+            target: load_target(conn, app_dir)?,
+        })),
+        _ => Ok(None),
+    }
+}
+
+// Encrypts and uploads a backup; returns the object key it was stored under
+pub fn upload(pending: &PendingUpload) -> Result<String, HrmError> {
+    let data = fs::read(&pending.path)?;
+    let sealed = encrypt_backup(&data, &pending.target.passphrase)?;
+    let object_key = format!("{}/{}.enc", pending.target.prefix.trim_matches('/'), pending.backup_id);
+    put_object(&pending.target, &object_key, &sealed)?;
+    Ok(object_key)
+}
+
+pub fn record_upload(conn: &Connection, backup_id: &str, result: &Result<String, HrmError>) -> Result<(), HrmError> {
+    match result {
+        Ok(_) => {
+            let now: String = conn.query_row("SELECT datetime('now', 'localtime')", [], |row| row.get(0))?;
+            set_setting(conn, LAST_UPLOAD_AT, Some(&now))?;
+            set_setting(conn, LAST_UPLOADED, Some(backup_id))?;
+            set_setting(conn, LAST_ERROR, None)?;
+        }
+        Err(e) => set_setting(conn, LAST_ERROR, Some(&e.to_string()))?,
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_cloud_backup_config(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<CloudBackupConfig, HrmError> {
+    require_backup_permission(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    Ok(CloudBackupConfig {
+        enabled: get_setting_bool(&conn, ENABLED),
+        endpoint: setting_or(&conn, ENDPOINT, ""),
+        bucket: setting_or(&conn, BUCKET, ""),
+        region: setting_or(&conn, REGION, DEFAULT_REGION),
+        prefix: setting_or(&conn, PREFIX, DEFAULT_PREFIX),
+        access_key_id: setting_or(&conn, ACCESS_KEY_ID, ""),
+        has_secret_access_key: get_setting(&conn, SECRET_ACCESS_KEY).is_some(),
+        has_passphrase: get_setting(&conn, PASSPHRASE).is_some(),
+        last_upload_at: get_setting(&conn, LAST_UPLOAD_AT),
+        last_uploaded_backup: get_setting(&conn, LAST_UPLOADED),
+        last_error: get_setting(&conn, LAST_ERROR),
+    })
+}
+
+#[tauri::command]
+pub fn save_cloud_backup_config(
+    settings: CloudBackupSettings,
+    db: State<'_, DbConnection>,
+    app_data_dir: State<'_, AppDataDir>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = require_backup_permission(&current_user)?;
+    
+    let endpoint = settings.endpoint.trim().trim_end_matches('/');
+    let bucket = settings.bucket.trim();
+    let prefix = settings.prefix.trim().trim_matches('/');
+    let secret_access_key = settings.secret_access_key.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    let passphrase = settings.passphrase.as_deref().filter(|s| !s.is_empty());
+    
+    if !endpoint.is_empty() && !endpoint.starts_with("https://") {
+        return Err(HrmError::field("endpoint", "Endpoint must start with https://"));
+    }
+    let safe_name = |value: &str| value.chars().all(|c| c.is_ascii_alphanumeric() || "-_./".contains(c));
+    if !safe_name(bucket) || bucket.contains('/') {
+        return Err(HrmError::field("bucket", "Bucket name may only contain letters, digits, '-', '_' and '.'"));
+    }
+    if !safe_name(prefix) || prefix.contains("..") {
+        return Err(HrmError::field("prefix", "Folder may only contain letters, digits, '-', '_', '.' and '/'"));
+    }
+    if let Some(passphrase) = passphrase {
+        if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+            return Err(HrmError::field(
+                "passphrase",
+                format!("Passphrase must be at least {} characters", MIN_PASSPHRASE_LEN),
+            ));
+        }
+    }
+    
+    let conn = db.0.lock()?;
+    if settings.enabled {
+        let has_secret = secret_access_key.is_some() || get_setting(&conn, SECRET_ACCESS_KEY).is_some();
+        let has_passphrase = passphrase.is_some() || get_setting(&conn, PASSPHRASE).is_some();
+        if endpoint.is_empty() || bucket.is_empty() || settings.access_key_id.trim().is_empty() || !has_secret {
+            return Err(HrmError::validation("Endpoint, bucket, access key and secret key are required to enable cloud backup"));
+        }
+        if !has_passphrase {
+            return Err(HrmError::field("passphrase", "An encryption passphrase is required to enable cloud backup"));
+        }
+    }
+    
+    set_setting(&conn, ENABLED, Some(if settings.enabled { "true" } else { "false" }))?;
+    set_setting(&conn, ENDPOINT, Some(endpoint))?;
+    set_setting(&conn, BUCKET, Some(bucket))?;
+    set_setting(&conn, REGION, Some(settings.region.trim()).filter(|r| !r.is_empty()))?;
+    set_setting(&conn, PREFIX, Some(prefix).filter(|p| !p.is_empty()))?;
+    set_setting(&conn, ACCESS_KEY_ID, Some(settings.access_key_id.trim()))?;
+    if let Some(secret) = secret_access_key {
+        set_setting(&conn, SECRET_ACCESS_KEY, Some(&secrets::encrypt(&app_data_dir.0, secret)?))?;
+    }
+    if let Some(passphrase) = passphrase {
+        set_setting(&conn, PASSPHRASE, Some(&secrets::encrypt(&app_data_dir.0, passphrase)?))?;
+    }
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "CLOUD_BACKUP",
+        Some(bucket),
+        None,
+        None,
+        Some(&format!(
+            "Cloud backup {} ({}/{}){}{}",
+            if settings.enabled { "enabled" } else { "disabled" },
+            endpoint,
+            bucket,
+            if secret_access_key.is_some() { ", secret key changed" } else { "" },
+            if passphrase.is_some() { ", passphrase changed" } else { "" }
+        )),
+    );
+    
+    Ok(())
+}
+
+// Uploads the newest backup of any kind straight away, e.g. to test the
+// settings. The database lock is released during the upload.
+#[tauri::command]
+pub fn upload_backup_now(
+    db: State<'_, DbConnection>,
+    app_data_dir: State<'_, AppDataDir>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<String, HrmError> {
+    let user = require_backup_permission(&current_user)?;
+    let app_dir = &app_data_dir.0;
+    
+    let conn = db.0.lock()?;
+    let backup_id = backup::backup_ids(app_dir)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| HrmError::not_found("There is no backup to upload. Create a backup first."))?;
+    let pending = PendingUpload {
+        path: backups_dir(app_dir).join(&backup_id),
+        target: load_target(&conn, app_dir)?,
+        backup_id,
+    };
+    drop(conn);
+    
+    let result = upload(&pending);
+    
+    let conn = db.0.lock()?;
+    record_upload(&conn, &pending.backup_id, &result)?;
+    let object_key = result?;
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPLOAD",
+        "BACKUP",
+        Some(&pending.backup_id),
+        None,
+        None,
+        Some(&format!("Uploaded backup to {}/{}", pending.target.bucket, object_key)),
+    );
+    
+    Ok(object_key)
+}
+
+// Turns a downloaded .enc backup back into a database file, which can then be
+// brought in with Import Database (e.g. on a replacement PC)
+#[tauri::command]
+pub fn decrypt_backup_file(
+    source_path: String,
+    destination_path: String,
+    passphrase: String,
+    current_user: State<'_, CurrentUser>,
+) -> Result<String, HrmError> {
+    require_backup_permission(&current_user)?;
+    
+    let sealed = fs::read(&source_path).map_err(|e| HrmError::io(format!("Failed to read backup file: {}", e)))?;
+    let data = decrypt_backup(&sealed, &passphrase)?;
+    fs::write(&destination_path, data).map_err(|e| HrmError::io(format!("Failed to save database: {}", e)))?;
+    verify_database_file(Path::new(&destination_path))?;
+    
+    Ok(format!("Backup decrypted to {}. Use Import Database to restore it.", destination_path))
+}
@@ -1,12 +1,30 @@
-use crate::models::{AuditLog, AuditLogFilters, AuditLogResult, DashboardStats, DepartmentCount, Employee, EmployeeFilters};
+use crate::error::HrmError;
+use crate::models::{AuditLog, AuditLogFilters, AuditLogResult, DashboardStats, DepartmentCount, Employee, EmployeeFilters, EmployeePhoto, FieldError, InvalidEmployeeDate, OrphanCleanupReport, OrphanedFile, PreviousEmployment, StatusCount};
+use crate::backup;
+use crate::custom_fields;
+use crate::employee_relations::{delete_employee_photos, delete_employee_records, deletion_blocker};
+use crate::employment_periods::sync_current_period;
+use crate::epf_numbering::allocate_epf_number;
+use crate::notification_commands::notify_admins;
+use crate::org_chart_commands::prompt_reassignment;
+use crate::images;
+use crate::journal;
+use crate::settings_commands::{get_setting_bool, get_setting_i64, set_setting};
+use crate::validation::{clear_hidden_fields, invalid_employee_dates, normalize_mobile, normalize_nic, parse_iso_date, validate_employee};
+use crate::working_status_commands::counts_as_active;
 use crate::{AppDataDir, CurrentUser, DbConnection};
 use base64::{engine::general_purpose, Engine as _};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 use tauri::State;
 
+// Defaults for the mass-deletion safeguard (overridable in app settings)
+const DEFAULT_BULK_DELETE_THRESHOLD: i64 = 20;
+const DEFAULT_BULK_DELETE_WINDOW_MINUTES: i64 = 10;
+
 #[tauri::command]
-pub fn init_database() -> Result<(), String> {
+pub fn init_database() -> Result<(), HrmError> {
     // Database is initialized in main.rs, this is just a confirmation
     Ok(())
 }
@@ -15,16 +33,15 @@ pub fn init_database() -> Result<(), String> {
 pub fn get_employees(
     filters: EmployeeFilters,
     db: State<'_, DbConnection>,
-) -> Result<Vec<Employee>, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
-    
-    let mut sql = String::from(
-        "SELECT epf_number, name_with_initials, full_name, dob, police_area, 
-                transport_route, mobile_1, mobile_2, address, date_of_join, 
-                date_of_resign, working_status, marital_status, cader,
-                designation, allocation, department, image_path, created_at 
-         FROM employees WHERE 1=1"
-    );
+) -> Result<Vec<Employee>, HrmError> {
+    let conn = db.0.lock()?;
+    query_employees(&conn, filters)
+}
+
+// Employees matching the list filters, ordered by EPF number unless another
+// sort is asked for
+pub(crate) fn query_employees(conn: &rusqlite::Connection, filters: EmployeeFilters) -> Result<Vec<Employee>, HrmError> {
+    let mut sql = format!("SELECT {} FROM employees WHERE 1=1", EMPLOYEE_COLUMNS);
     let mut params: Vec<String> = Vec::new();
     
     if !filters.epf_number.is_empty() {
@@ -43,43 +60,89 @@ pub fn get_employees(
         sql.push_str(" AND working_status = ?");
         params.push(filters.working_status);
     }
+    for tag_id in filters.tags {
+        sql.push_str(" AND EXISTS (SELECT 1 FROM employee_tags t WHERE t.epf_number = employees.epf_number AND t.tag_id = ?)");
+        params.push(tag_id.to_string());
+    }
+    for (name, value) in filters.custom_fields {
+        sql.push_str(
+            " AND EXISTS (SELECT 1 FROM employee_custom_values v
+                          JOIN custom_field_definitions d ON d.id = v.field_id
+                          WHERE v.epf_number = employees.epf_number AND d.name = ? AND v.value = ?)",
+        );
+        params.push(name);
+        params.push(value);
+    }
     
-    sql.push_str(" ORDER BY epf_number ASC");
+    for (column, values) in [
+        ("department", filters.departments),
+        ("designation", filters.designations),
+        ("allocation", filters.allocations),
+        ("district", filters.districts),
+        ("ds_division", filters.ds_divisions),
+    ] {
+        if !values.is_empty() {
+            sql.push_str(&format!(" AND {} IN ({})", column, vec!["?"; values.len()].join(", ")));
+            params.extend(values);
+        }
+    }
+    for (field, condition, value) in [
+        ("joined_from", "date_of_join >= ?", filters.joined_from),
+        ("joined_to", "date_of_join <= ?", filters.joined_to),
+        ("resigned_from", "date_of_resign >= ?", filters.resigned_from),
+        ("resigned_to", "date_of_resign <= ?", filters.resigned_to),
+    ] {
+        if let Some(date) = value.map(|d| d.trim().to_string()).filter(|d| !d.is_empty()) {
+            if parse_iso_date(&date).is_none() {
+                return Err(HrmError::field(field, "Date must be a valid date (YYYY-MM-DD)"));
+            }
+            sql.push_str(&format!(" AND {}", condition));
+            params.push(date);
+        }
+    }
+    if let Some(min) = filters.age_min {
+        sql.push_str(" AND age >= CAST(? AS INTEGER)");
+        params.push(min.to_string());
+    }
+    if let Some(max) = filters.age_max {
+        sql.push_str(" AND age <= CAST(? AS INTEGER)");
+        params.push(max.to_string());
+    }
+    if let Some(min) = filters.service_years_min {
+        sql.push_str(" AND service_years >= CAST(? AS INTEGER)");
+        params.push(min.to_string());
+    }
+    if let Some(max) = filters.service_years_max {
+        sql.push_str(" AND service_years <= CAST(? AS INTEGER)");
+        params.push(max.to_string());
+    }
     
-    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let sort_column = match filters.sort_by.as_deref().unwrap_or("epf_number") {
+        "epf_number" => "epf_number",
+        "name" => "name_with_initials",
+        "age" => "age",
+        "service_years" => "service_years",
+        other => return Err(HrmError::validation(format!("Cannot sort employees by {}", other))),
+    };
+    let direction = if filters.sort_descending { "DESC" } else { "ASC" };
+    if sort_column == "epf_number" {
+        sql.push_str(&format!(" ORDER BY epf_number {}", direction));
+    } else {
+        sql.push_str(&format!(" ORDER BY {} {}, epf_number ASC", sort_column, direction));
+    }
+    
+    let mut stmt = conn.prepare(&sql)?;
     
     let params_refs: Vec<&dyn rusqlite::ToSql> = params
         .iter()
         .map(|p| p as &dyn rusqlite::ToSql)
         .collect();
     
-    let employees = stmt
-        .query_map(params_refs.as_slice(), |row| {
-            Ok(Employee {
-                epf_number: row.get(0)?,
-                name_with_initials: row.get(1)?,
-                full_name: row.get(2)?,
-                dob: row.get(3)?,
-                police_area: row.get(4)?,
-                transport_route: row.get(5)?,
-                mobile_1: row.get(6)?,
-                mobile_2: row.get(7)?,
-                address: row.get(8)?,
-                date_of_join: row.get(9)?,
-                date_of_resign: row.get(10)?,
-                working_status: row.get(11)?,
-                marital_status: row.get(12)?,
-                cader: row.get(13)?,
-                designation: row.get(14)?,
-                allocation: row.get(15)?,
-                department: row.get(16)?,
-                image_path: row.get(17)?,
-                created_at: row.get(18)?,
-            })
-        })
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())?;
+    let mut employees = stmt
+        .query_map(params_refs.as_slice(), employee_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    custom_fields::attach_custom_values(conn, &mut employees)?;
     
     Ok(employees)
 }
@@ -88,41 +151,160 @@ pub fn get_employees(
 pub fn get_employee_by_epf(
     epf_number: String,
     db: State<'_, DbConnection>,
-) -> Result<Employee, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    current_user: State<'_, CurrentUser>,
+) -> Result<Employee, HrmError> {
+    let conn = db.0.lock()?;
+    
+    let employee = query_employee(&conn, &epf_number)
+        .map_err(|e| HrmError::not_found(format!("Employee not found: {}", e)))?;
+    
+    // Opt-in accountability trail for personal data access
+    if get_setting_bool(&conn, "audit_read_access") {
+        let user_guard = current_user.0.lock()?;
+        let (user_id, username) = if let Some(ref user) = *user_guard {
+            (Some(user.user_id), user.username.clone())
+        } else {
+            (None, "system".to_string())
+        };
+        log_audit_action(
+            &conn,
+            user_id,
+            &username,
+            "VIEW",
+            "EMPLOYEE",
+            Some(&epf_number),
+            None,
+            None,
+            Some(&format!("Viewed employee profile: {} ({})", employee.name_with_initials, epf_number)),
+        );
+    }
     
-    conn.query_row(
-        "SELECT epf_number, name_with_initials, full_name, dob, police_area, 
-                transport_route, mobile_1, mobile_2, address, date_of_join, 
-                date_of_resign, working_status, marital_status, cader,
-                designation, allocation, department, image_path, created_at 
-         FROM employees WHERE epf_number = ?1",
-        [&epf_number],
-        |row| {
-            Ok(Employee {
+    Ok(employee)
+}
+
+fn nic_error(message: String) -> HrmError {
+    HrmError::field("nic", message)
+}
+
+// A new hire whose NIC matches a former employee must be entered as a rehire
+// (previous_epf_number set) so the earlier service stays linked
+fn check_rehire(conn: &rusqlite::Connection, employee: &Employee) -> Result<(), HrmError> {
+    let former = match employee.nic.as_deref() {
+        Some(nic) => find_former_employees(conn, nic)?,
+        None => Vec::new(),
+    };
+    match &employee.previous_epf_number {
+        None => match former.first() {
+            Some(previous) => Err(nic_error(format!(
+                "NIC matches former employee {} ({}). Rehire them to keep their service history.",
+                previous.name_with_initials, previous.epf_number
+            ))),
+            None => Ok(()),
+        },
+        Some(previous) if former.iter().any(|f| &f.epf_number == previous) => Ok(()),
+        Some(previous) => Err(nic_error(format!(
+            "Employee {} is not a former employee with this NIC",
+            previous
+        ))),
+    }
+}
+
+// Resigned employees with the given NIC, most recent first
+pub(crate) fn find_former_employees(conn: &rusqlite::Connection, nic: &str) -> rusqlite::Result<Vec<PreviousEmployment>> {
+    let mut stmt = conn.prepare(
+        "SELECT epf_number, name_with_initials, department, designation, date_of_join, date_of_resign
+         FROM employees WHERE nic = ?1 AND working_status = 'resign'
+         ORDER BY date_of_resign DESC",
+    )?;
+    let former = stmt
+        .query_map([nic], |row| {
+            Ok(PreviousEmployment {
                 epf_number: row.get(0)?,
                 name_with_initials: row.get(1)?,
-                full_name: row.get(2)?,
-                dob: row.get(3)?,
-                police_area: row.get(4)?,
-                transport_route: row.get(5)?,
-                mobile_1: row.get(6)?,
-                mobile_2: row.get(7)?,
-                address: row.get(8)?,
-                date_of_join: row.get(9)?,
-                date_of_resign: row.get(10)?,
-                working_status: row.get(11)?,
-                marital_status: row.get(12)?,
-                cader: row.get(13)?,
-                designation: row.get(14)?,
-                allocation: row.get(15)?,
-                department: row.get(16)?,
-                image_path: row.get(17)?,
-                created_at: row.get(18)?,
+                department: row.get(2)?,
+                designation: row.get(3)?,
+                date_of_join: row.get(4)?,
+                date_of_resign: row.get(5)?,
             })
-        },
-    )
-    .map_err(|e| format!("Employee not found: {}", e))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(former)
+}
+
+// Looks up former employees by NIC so the form can offer to rehire them
+#[tauri::command]
+pub fn find_previous_employment(nic: String, db: State<'_, DbConnection>) -> Result<Vec<PreviousEmployment>, HrmError> {
+    let nic = match normalize_nic(&nic) {
+        Ok(nic) => nic,
+        Err(_) => return Ok(Vec::new()),
+    };
+    let conn = db.0.lock()?;
+    find_former_employees(&conn, &nic).map_err(HrmError::from)
+}
+
+// Employee dates the date migration could not read, so they can be corrected
+// on the employee form
+#[tauri::command]
+pub fn get_invalid_employee_dates(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<InvalidEmployeeDate>, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(user) if user.permissions.can_edit_employees => {}
+        Some(_) => return Err(HrmError::permission_denied("Permission denied. You cannot edit employees.")),
+        None => return Err(HrmError::not_logged_in()),
+    }
+    drop(user_lock);
+    
+    let conn = db.0.lock()?;
+    invalid_employee_dates(&conn).map_err(HrmError::from)
+}
+
+// Identifies a caller from their number. Accepts 077..., +9477..., 0094...
+// and spaced forms, and compares against both mobile fields after the same
+// normalization, so older records saved in other styles still match.
+#[tauri::command]
+pub fn search_employees_by_phone(
+    phone: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<Employee>, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(user) if user.permissions.can_view_sensitive_data => {}
+        Some(_) => return Err(HrmError::permission_denied("Permission denied. You cannot view contact details.")),
+        None => return Err(HrmError::not_logged_in()),
+    }
+    drop(user_lock);
+    let phone = normalize_mobile(&phone).map_err(|message| HrmError::field("phone", message))?;
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(
+        "SELECT epf_number, mobile_1, mobile_2 FROM employees
+         WHERE COALESCE(mobile_1, '') <> '' OR COALESCE(mobile_2, '') <> ''
+         ORDER BY working_status = 'resign', epf_number",
+    )?;
+    let matches: Vec<String> = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?, row.get::<_, Option<String>>(2)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .filter(|(_, mobile_1, mobile_2)| {
+            [mobile_1, mobile_2]
+                .into_iter()
+                .flatten()
+                .any(|mobile| normalize_mobile(mobile).is_ok_and(|m| m == phone))
+        })
+        .map(|(epf_number, _, _)| epf_number)
+        .collect();
+    
+    let employees = matches
+        .iter()
+        .map(|epf_number| query_employee(&conn, epf_number))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(employees)
 }
 
 #[tauri::command]
@@ -130,41 +312,32 @@ pub fn create_employee(
     employee: Employee,
     db: State<'_, DbConnection>,
     current_user: State<'_, CurrentUser>,
-) -> Result<(), String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
-    
-    conn.execute(
-        "INSERT INTO employees (
-            epf_number, name_with_initials, full_name, dob, police_area,
-            transport_route, mobile_1, mobile_2, address, date_of_join,
-            date_of_resign, working_status, marital_status, cader,
-            designation, allocation, department, image_path
-        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
-        rusqlite::params![
-            employee.epf_number,
-            employee.name_with_initials,
-            employee.full_name,
-            employee.dob,
-            employee.police_area,
-            employee.transport_route,
-            employee.mobile_1,
-            employee.mobile_2,
-            employee.address,
-            employee.date_of_join,
-            employee.date_of_resign,
-            employee.working_status,
-            employee.marital_status,
-            employee.cader,
-            employee.designation,
-            employee.allocation,
-            employee.department,
-            employee.image_path,
-        ],
-    )
-    .map_err(|e| e.to_string())?;
+) -> Result<String, HrmError> {
+    let conn = db.0.lock()?;
+    
+    let mut employee = employee;
+    employee.epf_number = allocate_epf_number(&conn, &employee.epf_number)?;
+    clear_hidden_fields(&conn, &mut employee);
+    validate_employee(&conn, &mut employee)?;
+    check_rehire(&conn, &employee)?;
+    
+    insert_employee(&conn, &employee).map_err(|e| {
+        if e.to_string().contains("UNIQUE constraint") {
+            HrmError::fields(
+                "EPF number already exists",
+                vec![FieldError {
+                    field: "epf_number".to_string(),
+                    message: format!("EPF number {} already exists", employee.epf_number),
+                }],
+            )
+        } else {
+            HrmError::from(e)
+        }
+    })?;
+    custom_fields::save_custom_values(&conn, &employee)?;
     
     // Log audit action
-    let user_guard = current_user.0.lock().map_err(|e| e.to_string())?;
+    let user_guard = current_user.0.lock()?;
     let (user_id, username) = if let Some(ref user) = *user_guard {
         (Some(user.user_id), user.username.clone())
     } else {
@@ -172,19 +345,26 @@ pub fn create_employee(
     };
     
     let new_value = serde_json::to_string(&employee).ok();
+    let details = match &employee.previous_epf_number {
+        Some(previous) => format!(
+            "Rehired employee: {} ({}), previously {}",
+            employee.name_with_initials, employee.epf_number, previous
+        ),
+        None => format!("Created employee: {} ({})", employee.name_with_initials, employee.epf_number),
+    };
     log_audit_action(
         &conn,
         user_id,
         &username,
-        "CREATE",
+        if employee.previous_epf_number.is_some() { "REHIRE" } else { "CREATE" },
         "EMPLOYEE",
         Some(&employee.epf_number),
         None,
         new_value.as_deref(),
-        Some(&format!("Created employee: {} ({})", employee.name_with_initials, employee.epf_number)),
+        Some(&details),
     );
     
-    Ok(())
+    Ok(employee.epf_number)
 }
 
 #[tauri::command]
@@ -192,41 +372,14 @@ pub fn update_employee(
     employee: Employee,
     db: State<'_, DbConnection>,
     current_user: State<'_, CurrentUser>,
-) -> Result<(), String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+) -> Result<(), HrmError> {
+    let conn = db.0.lock()?;
+    
+    let mut employee = employee;
+    validate_employee(&conn, &mut employee)?;
     
     // Get old employee data for audit log
-    let old_employee: Option<Employee> = conn.query_row(
-        "SELECT epf_number, name_with_initials, full_name, dob, police_area, 
-                transport_route, mobile_1, mobile_2, address, date_of_join, 
-                date_of_resign, working_status, marital_status, cader,
-                designation, allocation, department, image_path, created_at 
-         FROM employees WHERE epf_number = ?1",
-        [&employee.epf_number],
-        |row| {
-            Ok(Employee {
-                epf_number: row.get(0)?,
-                name_with_initials: row.get(1)?,
-                full_name: row.get(2)?,
-                dob: row.get(3)?,
-                police_area: row.get(4)?,
-                transport_route: row.get(5)?,
-                mobile_1: row.get(6)?,
-                mobile_2: row.get(7)?,
-                address: row.get(8)?,
-                date_of_join: row.get(9)?,
-                date_of_resign: row.get(10)?,
-                working_status: row.get(11)?,
-                marital_status: row.get(12)?,
-                cader: row.get(13)?,
-                designation: row.get(14)?,
-                allocation: row.get(15)?,
-                department: row.get(16)?,
-                image_path: row.get(17)?,
-                created_at: row.get(18)?,
-            })
-        },
-    ).ok();
+    let old_employee: Option<Employee> = query_employee(&conn, &employee.epf_number).ok();
     
     conn.execute(
         "UPDATE employees SET 
@@ -234,7 +387,8 @@ pub fn update_employee(
             transport_route = ?6, mobile_1 = ?7, mobile_2 = ?8, address = ?9,
             date_of_join = ?10, date_of_resign = ?11, working_status = ?12,
             marital_status = ?13, cader = ?14, designation = ?15, allocation = ?16,
-            department = ?17, image_path = ?18
+            department = ?17, image_path = ?18, nic = ?19,
+            district = ?20, ds_division = ?21, gn_division = ?22
          WHERE epf_number = ?1",
         rusqlite::params![
             employee.epf_number,
@@ -255,12 +409,27 @@ pub fn update_employee(
             employee.allocation,
             employee.department,
             employee.image_path,
+            employee.nic,
+            employee.district,
+            employee.ds_division,
+            employee.gn_division,
         ],
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
+    sync_current_period(
+        &conn,
+        &employee.epf_number,
+        employee.date_of_join.as_deref(),
+        employee.date_of_resign.as_deref(),
+    )?;
+    custom_fields::save_custom_values(&conn, &employee)?;
+    if old_employee.as_ref().map(|o| o.working_status != employee.working_status).unwrap_or(false)
+        && !counts_as_active(&conn, &employee.working_status)?
+    {
+        prompt_reassignment(&conn, &employee.epf_number)?;
+    }
     
     // Log audit action
-    let user_guard = current_user.0.lock().map_err(|e| e.to_string())?;
+    let user_guard = current_user.0.lock()?;
     let (user_id, username) = if let Some(ref user) = *user_guard {
         (Some(user.user_id), user.username.clone())
     } else {
@@ -287,49 +456,22 @@ pub fn update_employee(
 #[tauri::command]
 pub fn delete_employee(
     epf_number: String,
+    app_data_dir: State<'_, AppDataDir>,
     db: State<'_, DbConnection>,
     current_user: State<'_, CurrentUser>,
-) -> Result<(), String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+) -> Result<(), HrmError> {
+    let mut conn = db.0.lock()?;
     
     // Get employee data for audit log before deletion
-    let old_employee: Option<Employee> = conn.query_row(
-        "SELECT epf_number, name_with_initials, full_name, dob, police_area, 
-                transport_route, mobile_1, mobile_2, address, date_of_join, 
-                date_of_resign, working_status, marital_status, cader,
-                designation, allocation, department, image_path, created_at 
-         FROM employees WHERE epf_number = ?1",
-        [&epf_number],
-        |row| {
-            Ok(Employee {
-                epf_number: row.get(0)?,
-                name_with_initials: row.get(1)?,
-                full_name: row.get(2)?,
-                dob: row.get(3)?,
-                police_area: row.get(4)?,
-                transport_route: row.get(5)?,
-                mobile_1: row.get(6)?,
-                mobile_2: row.get(7)?,
-                address: row.get(8)?,
-                date_of_join: row.get(9)?,
-                date_of_resign: row.get(10)?,
-                working_status: row.get(11)?,
-                marital_status: row.get(12)?,
-                cader: row.get(13)?,
-                designation: row.get(14)?,
-                allocation: row.get(15)?,
-                department: row.get(16)?,
-                image_path: row.get(17)?,
-                created_at: row.get(18)?,
-            })
-        },
-    ).ok();
+    let old_employee: Option<Employee> = query_employee(&conn, &epf_number).ok();
     
-    conn.execute("DELETE FROM employees WHERE epf_number = ?1", [&epf_number])
-        .map_err(|e| e.to_string())?;
+    let tx = conn.transaction()?;
+    delete_employee_records(&tx, &epf_number)?;
+    tx.commit()?;
+    delete_employee_photos(&app_data_dir.0, &epf_number)?;
     
     // Log audit action
-    let user_guard = current_user.0.lock().map_err(|e| e.to_string())?;
+    let user_guard = current_user.0.lock()?;
     let (user_id, username) = if let Some(ref user) = *user_guard {
         (Some(user.user_id), user.username.clone())
     } else {
@@ -350,111 +492,326 @@ pub fn delete_employee(
         Some(&format!("Deleted employee: {} ({})", employee_name, epf_number)),
     );
     
+    check_mass_deletion(&conn, user_id, &username);
+    
     Ok(())
 }
 
+pub(crate) fn query_employee(conn: &rusqlite::Connection, epf_number: &str) -> rusqlite::Result<Employee> {
+    let mut employee = conn.query_row(
+        &format!("SELECT {} FROM employees WHERE epf_number = ?1", EMPLOYEE_COLUMNS),
+        [epf_number],
+        employee_from_row,
+    )?;
+    custom_fields::attach_custom_values(conn, std::slice::from_mut(&mut employee))?;
+    Ok(employee)
+}
+
+// Column list matching employee_from_row. Age and service are whole years
+// worked out from the stored dates; they are NULL when a date is missing or
+// unreadable, and can be used by name in WHERE and ORDER BY.
+pub(crate) const EMPLOYEE_COLUMNS: &str = "epf_number, name_with_initials, full_name, dob, police_area,
+                transport_route, mobile_1, mobile_2, address, date_of_join,
+                date_of_resign, working_status, marital_status, cader,
+                designation, allocation, department, image_path, created_at,
+                nic, previous_epf_number, district, ds_division, gn_division,
+                CASE WHEN date(dob) IS NULL THEN NULL ELSE
+                    CAST(strftime('%Y', 'now', 'localtime') AS INTEGER) - CAST(strftime('%Y', dob) AS INTEGER)
+                    - (strftime('%m-%d', 'now', 'localtime') < strftime('%m-%d', dob)) END AS age,
+                CASE WHEN date(date_of_join) IS NULL THEN NULL ELSE
+                    CAST(strftime('%Y', COALESCE(date(date_of_resign), date('now', 'localtime'))) AS INTEGER)
+                    - CAST(strftime('%Y', date_of_join) AS INTEGER)
+                    - (strftime('%m-%d', COALESCE(date(date_of_resign), date('now', 'localtime'))) < strftime('%m-%d', date_of_join))
+                    END AS service_years";
+
+pub(crate) fn employee_from_row(row: &rusqlite::Row) -> rusqlite::Result<Employee> {
+    Ok(Employee {
+        epf_number: row.get(0)?,
+        name_with_initials: row.get(1)?,
+        full_name: row.get(2)?,
+        nic: row.get(19)?,
+        dob: row.get(3)?,
+        police_area: row.get(4)?,
+        transport_route: row.get(5)?,
+        mobile_1: row.get(6)?,
+        mobile_2: row.get(7)?,
+        address: row.get(8)?,
+        date_of_join: row.get(9)?,
+        date_of_resign: row.get(10)?,
+        working_status: row.get(11)?,
+        marital_status: row.get(12)?,
+        cader: row.get(13)?,
+        designation: row.get(14)?,
+        allocation: row.get(15)?,
+        department: row.get(16)?,
+        image_path: row.get(17)?,
+        previous_epf_number: row.get(20)?,
+        district: row.get(21)?,
+        ds_division: row.get(22)?,
+        gn_division: row.get(23)?,
+        created_at: row.get(18)?,
+        custom_fields: HashMap::new(),
+        age: row.get(24)?,
+        service_years: row.get(25)?,
+    })
+}
+
+pub(crate) fn insert_employee(conn: &rusqlite::Connection, employee: &Employee) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO employees (
+            epf_number, name_with_initials, full_name, dob, police_area,
+            transport_route, mobile_1, mobile_2, address, date_of_join,
+            date_of_resign, working_status, marital_status, cader,
+            designation, allocation, department, image_path, nic, previous_epf_number,
+            district, ds_division, gn_division
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)",
+        rusqlite::params![
+            employee.epf_number,
+            employee.name_with_initials,
+            employee.full_name,
+            employee.dob,
+            employee.police_area,
+            employee.transport_route,
+            employee.mobile_1,
+            employee.mobile_2,
+            employee.address,
+            employee.date_of_join,
+            employee.date_of_resign,
+            employee.working_status,
+            employee.marital_status,
+            employee.cader,
+            employee.designation,
+            employee.allocation,
+            employee.department,
+            employee.image_path,
+            employee.nic,
+            employee.previous_epf_number,
+            employee.district,
+            employee.ds_division,
+            employee.gn_division,
+        ],
+    )?;
+    sync_current_period(
+        conn,
+        &employee.epf_number,
+        employee.date_of_join.as_deref(),
+        employee.date_of_resign.as_deref(),
+    )?;
+    Ok(())
+}
+
+// The phrase a user must type to confirm deleting `count` employees at once
+fn bulk_delete_phrase(count: usize) -> String {
+    format!("DELETE {} EMPLOYEES", count)
+}
+
+// Raises a high-severity alert when one user deletes many employees in a
+// short time, whether one by one or through bulk delete
+fn check_mass_deletion(conn: &rusqlite::Connection, user_id: Option<i32>, username: &str) {
+    let threshold = get_setting_i64(conn, "bulk_delete_threshold").unwrap_or(DEFAULT_BULK_DELETE_THRESHOLD);
+    let window_minutes = get_setting_i64(conn, "bulk_delete_window_minutes").unwrap_or(DEFAULT_BULK_DELETE_WINDOW_MINUTES);
+    let window = format!("-{} minutes", window_minutes);
+    
+    let recent_deletions: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM audit_logs
+             WHERE username = ?1 AND action = 'DELETE' AND entity_type = 'EMPLOYEE'
+               AND created_at >= datetime('now', ?2)",
+            rusqlite::params![username, window],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+    
+    if recent_deletions <= threshold {
+        return;
+    }
+    
+    // Alert only once per window so admins aren't flooded
+    let existing_alerts: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM audit_logs
+             WHERE username = ?1 AND action = 'MASS_DELETE_ALERT'
+               AND created_at >= datetime('now', ?2)",
+            rusqlite::params![username, window],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+    
+    if existing_alerts > 0 {
+        return;
+    }
+    
+    let details = format!(
+        "{} deleted {} employees within {} minutes",
+        username, recent_deletions, window_minutes
+    );
+    let user_ref = user_id.map(|id| id.to_string());
+    log_high_severity_event(conn, user_id, username, "MASS_DELETE_ALERT", "USER", user_ref.as_deref(), Some(&details));
+    notify_admins(conn, "Mass deletion detected", &details, "high", Some("USER"), user_ref.as_deref());
+}
+
+#[tauri::command]
+pub fn bulk_delete_employees(
+    epf_numbers: Vec<String>,
+    confirmation_phrase: String,
+    app_data_dir: State<'_, AppDataDir>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<usize, HrmError> {
+    let user_guard = current_user.0.lock()?;
+    let (user_id, username) = match &*user_guard {
+        Some(user) if user.permissions.can_delete_employees => (user.user_id, user.username.clone()),
+        _ => return Err(HrmError::permission_denied("Permission denied. You cannot delete employees.")),
+    };
+    drop(user_guard);
+    
+    if epf_numbers.is_empty() {
+        return Err(HrmError::validation("No employees selected"));
+    }
+    
+    let expected_phrase = bulk_delete_phrase(epf_numbers.len());
+    if confirmation_phrase.trim() != expected_phrase {
+        return Err(HrmError::validation(format!("Confirmation phrase does not match. Type \"{}\" to confirm.", expected_phrase)));
+    }
+    
+    let mut conn = db.0.lock()?;
+    let mut blocked = Vec::new();
+    for epf_number in &epf_numbers {
+        if deletion_blocker(&conn, epf_number)?.is_some() {
+            blocked.push(epf_number.as_str());
+        }
+    }
+    if !blocked.is_empty() {
+        return Err(HrmError::conflict(format!(
+            "Employees with payroll history cannot be deleted: {}. Archive them instead.",
+            blocked.join(", ")
+        )));
+    }
+    
+    let tx = conn.transaction()?;
+    
+    let mut deleted = Vec::new();
+    for epf_number in &epf_numbers {
+        let old_employee = match query_employee(&tx, epf_number) {
+            Ok(employee) => employee,
+            Err(rusqlite::Error::QueryReturnedNoRows) => continue,
+            Err(e) => return Err(e.into()),
+        };
+        
+        delete_employee_records(&tx, epf_number)?;
+        
+        let old_value = serde_json::to_string(&old_employee).ok();
+        log_audit_action(
+            &tx,
+            Some(user_id),
+            &username,
+            "DELETE",
+            "EMPLOYEE",
+            Some(epf_number),
+            old_value.as_deref(),
+            None,
+            Some(&format!("Deleted employee (bulk): {} ({})", old_employee.name_with_initials, epf_number)),
+        );
+        deleted.push(epf_number);
+    }
+    
+    tx.commit()?;
+    for epf_number in &deleted {
+        delete_employee_photos(&app_data_dir.0, epf_number)?;
+    }
+    
+    let details = format!("{} bulk deleted {} employees", username, deleted.len());
+    log_high_severity_event(&conn, Some(user_id), &username, "BULK_DELETE", "EMPLOYEE", None, Some(&details));
+    notify_admins(&conn, "Bulk employee deletion", &details, "high", Some("USER"), Some(&user_id.to_string()));
+    
+    Ok(deleted.len())
+}
+
 #[tauri::command]
-pub fn get_distinct_departments(db: State<'_, DbConnection>) -> Result<Vec<String>, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+pub fn get_distinct_departments(db: State<'_, DbConnection>) -> Result<Vec<String>, HrmError> {
+    let conn = db.0.lock()?;
     
     let mut stmt = conn
-        .prepare("SELECT DISTINCT department FROM employees WHERE department IS NOT NULL AND department != '' ORDER BY department")
-        .map_err(|e| e.to_string())?;
+        .prepare("SELECT DISTINCT department FROM employees WHERE department IS NOT NULL AND department != '' ORDER BY department")?;
     
     let departments = stmt
-        .query_map([], |row| row.get(0))
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<String>, _>>()
-        .map_err(|e| e.to_string())?;
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
     
     Ok(departments)
 }
 
 #[tauri::command]
-pub fn get_distinct_transport_routes(db: State<'_, DbConnection>) -> Result<Vec<String>, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+pub fn get_distinct_transport_routes(db: State<'_, DbConnection>) -> Result<Vec<String>, HrmError> {
+    let conn = db.0.lock()?;
     
     let mut stmt = conn
-        .prepare("SELECT DISTINCT transport_route FROM employees WHERE transport_route IS NOT NULL AND transport_route != '' ORDER BY transport_route")
-        .map_err(|e| e.to_string())?;
+        .prepare("SELECT DISTINCT transport_route FROM employees WHERE transport_route IS NOT NULL AND transport_route != '' ORDER BY transport_route")?;
     
     let routes = stmt
-        .query_map([], |row| row.get(0))
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<String>, _>>()
-        .map_err(|e| e.to_string())?;
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
     
     Ok(routes)
 }
 
 #[tauri::command]
-pub fn get_distinct_police_areas(db: State<'_, DbConnection>) -> Result<Vec<String>, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+pub fn get_distinct_police_areas(db: State<'_, DbConnection>) -> Result<Vec<String>, HrmError> {
+    let conn = db.0.lock()?;
     
     let mut stmt = conn
-        .prepare("SELECT DISTINCT police_area FROM employees WHERE police_area IS NOT NULL AND police_area != '' ORDER BY police_area")
-        .map_err(|e| e.to_string())?;
+        .prepare("SELECT DISTINCT police_area FROM employees WHERE police_area IS NOT NULL AND police_area != '' ORDER BY police_area")?;
     
     let areas = stmt
-        .query_map([], |row| row.get(0))
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<String>, _>>()
-        .map_err(|e| e.to_string())?;
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
     
     Ok(areas)
 }
 
 #[tauri::command]
-pub fn get_distinct_designations(db: State<'_, DbConnection>) -> Result<Vec<String>, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+pub fn get_distinct_designations(db: State<'_, DbConnection>) -> Result<Vec<String>, HrmError> {
+    let conn = db.0.lock()?;
     
     let mut stmt = conn
-        .prepare("SELECT DISTINCT designation FROM employees WHERE designation IS NOT NULL AND designation != '' ORDER BY designation")
-        .map_err(|e| e.to_string())?;
+        .prepare("SELECT DISTINCT designation FROM employees WHERE designation IS NOT NULL AND designation != '' ORDER BY designation")?;
     
     let designations = stmt
-        .query_map([], |row| row.get(0))
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<String>, _>>()
-        .map_err(|e| e.to_string())?;
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
     
     Ok(designations)
 }
 
 #[tauri::command]
-pub fn get_distinct_allocations(db: State<'_, DbConnection>) -> Result<Vec<String>, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+pub fn get_distinct_allocations(db: State<'_, DbConnection>) -> Result<Vec<String>, HrmError> {
+    let conn = db.0.lock()?;
     
     let mut stmt = conn
-        .prepare("SELECT DISTINCT allocation FROM employees WHERE allocation IS NOT NULL AND allocation != '' ORDER BY allocation")
-        .map_err(|e| e.to_string())?;
+        .prepare("SELECT DISTINCT allocation FROM employees WHERE allocation IS NOT NULL AND allocation != '' ORDER BY allocation")?;
     
     let allocations = stmt
-        .query_map([], |row| row.get(0))
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<String>, _>>()
-        .map_err(|e| e.to_string())?;
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
     
     Ok(allocations)
 }
 
 #[tauri::command]
-pub fn get_dashboard_stats(db: State<'_, DbConnection>) -> Result<DashboardStats, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+pub fn get_dashboard_stats(db: State<'_, DbConnection>) -> Result<DashboardStats, HrmError> {
+    let conn = db.0.lock()?;
     
     // Total employees
     let total: i32 = conn
-        .query_row("SELECT COUNT(*) FROM employees", [], |row| row.get(0))
-        .map_err(|e| e.to_string())?;
+        .query_row("SELECT COUNT(*) FROM employees", [], |row| row.get(0))?;
     
     // Active employees
     let active: i32 = conn
         .query_row(
-            "SELECT COUNT(*) FROM employees WHERE working_status = 'active'",
+            "SELECT COUNT(*) FROM employees WHERE working_status IN (SELECT code FROM working_statuses WHERE counts_as_active = 1)",
             [],
             |row| row.get(0),
-        )
-        .map_err(|e| e.to_string())?;
+        )?;
     
     // Resigned employees
     let resigned: i32 = conn
@@ -462,19 +819,17 @@ pub fn get_dashboard_stats(db: State<'_, DbConnection>) -> Result<DashboardStats
             "SELECT COUNT(*) FROM employees WHERE working_status = 'resign'",
             [],
             |row| row.get(0),
-        )
-        .map_err(|e| e.to_string())?;
+        )?;
     
     // Departments breakdown
     let mut dept_stmt = conn
         .prepare(
             "SELECT COALESCE(department, 'Unassigned') as dept, COUNT(*) as count 
              FROM employees 
-             WHERE working_status = 'active'
+             WHERE working_status IN (SELECT code FROM working_statuses WHERE counts_as_active = 1)
              GROUP BY department 
              ORDER BY count DESC",
-        )
-        .map_err(|e| e.to_string())?;
+        )?;
     
     let departments = dept_stmt
         .query_map([], |row| {
@@ -482,21 +837,18 @@ pub fn get_dashboard_stats(db: State<'_, DbConnection>) -> Result<DashboardStats
                 name: row.get(0)?,
                 count: row.get(1)?,
             })
-        })
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())?;
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
     
     // Caders breakdown
     let mut cader_stmt = conn
         .prepare(
             "SELECT COALESCE(cader, 'Unassigned') as cader, COUNT(*) as count 
              FROM employees 
-             WHERE working_status = 'active'
+             WHERE working_status IN (SELECT code FROM working_statuses WHERE counts_as_active = 1)
              GROUP BY cader 
              ORDER BY count DESC",
-        )
-        .map_err(|e| e.to_string())?;
+        )?;
     
     let caders = cader_stmt
         .query_map([], |row| {
@@ -504,21 +856,18 @@ pub fn get_dashboard_stats(db: State<'_, DbConnection>) -> Result<DashboardStats
                 name: row.get(0)?,
                 count: row.get(1)?,
             })
-        })
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())?;
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
     
     // Allocations breakdown
     let mut alloc_stmt = conn
         .prepare(
             "SELECT COALESCE(allocation, 'Unassigned') as allocation, COUNT(*) as count 
              FROM employees 
-             WHERE working_status = 'active'
+             WHERE working_status IN (SELECT code FROM working_statuses WHERE counts_as_active = 1)
              GROUP BY allocation 
              ORDER BY count DESC",
-        )
-        .map_err(|e| e.to_string())?;
+        )?;
     
     let allocations = alloc_stmt
         .query_map([], |row| {
@@ -526,10 +875,8 @@ pub fn get_dashboard_stats(db: State<'_, DbConnection>) -> Result<DashboardStats
                 name: row.get(0)?,
                 count: row.get(1)?,
             })
-        })
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())?;
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
     
     // Recent joinings (last 30 days)
     let recent_joinings: i32 = conn
@@ -549,6 +896,27 @@ pub fn get_dashboard_stats(db: State<'_, DbConnection>) -> Result<DashboardStats
         )
         .unwrap_or(0);
     
+    // Headcount per working status, including statuses with no employees
+    let mut status_stmt = conn
+        .prepare(
+            "SELECT s.code, s.label, s.color, COUNT(e.epf_number)
+             FROM working_statuses s
+             LEFT JOIN employees e ON e.working_status = s.code
+             GROUP BY s.code
+             ORDER BY s.sort_order, s.label",
+        )?;
+    
+    let status_counts = status_stmt
+        .query_map([], |row| {
+            Ok(StatusCount {
+                code: row.get(0)?,
+                label: row.get(1)?,
+                color: row.get(2)?,
+                count: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
     Ok(DashboardStats {
         total_employees: total,
         active_employees: active,
@@ -558,6 +926,7 @@ pub fn get_dashboard_stats(db: State<'_, DbConnection>) -> Result<DashboardStats
         allocations,
         recent_joinings,
         recent_resignations,
+        status_counts,
     })
 }
 
@@ -566,10 +935,11 @@ pub fn save_employee_image(
     epf_number: String,
     image_data: String,
     app_data_dir: State<'_, AppDataDir>,
-) -> Result<String, String> {
+    db: State<'_, DbConnection>,
+) -> Result<String, HrmError> {
     // Create employee folder: employee_images/<epf_number>/
     let employee_folder = app_data_dir.0.join("employee_images").join(&epf_number);
-    fs::create_dir_all(&employee_folder).map_err(|e| format!("Failed to create folder: {}", e))?;
+    fs::create_dir_all(&employee_folder).map_err(|e| HrmError::io(format!("Failed to create folder: {}", e)))?;
     
     // Decode base64 image data
     // Remove data URL prefix if present (e.g., "data:image/jpeg;base64,")
@@ -581,57 +951,378 @@ pub fn save_employee_image(
     
     let image_bytes = general_purpose::STANDARD
         .decode(base64_data)
-        .map_err(|e| format!("Failed to decode image: {}", e))?;
+        .map_err(|e| HrmError::validation(format!("Failed to decode image: {}", e)))?;
     
-    // Determine image format from data URL or default to jpg
-    let extension = if image_data.contains("image/png") {
-        "png"
-    } else {
-        "jpg"
-    };
+    let mut conn = db.0.lock()?;
     
-    // Save image file
-    let image_filename = format!("photo.{}", extension);
-    let image_path = employee_folder.join(&image_filename);
+    // Byte-identical uploads are not stored twice
+    let hash = images::content_hash(&image_bytes);
+    let duplicate: Option<(String, String)> = conn
+        .query_row(
+            "SELECT epf_number, image_path FROM image_metadata WHERE content_hash = ?1
+             ORDER BY (epf_number = ?2) DESC LIMIT 1",
+            rusqlite::params![hash, epf_number],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .ok();
+    if let Some((owner_epf, existing_path)) = duplicate {
+        if owner_epf != epf_number {
+            return Err(HrmError::validation(format!("This photo is already used for employee {}", owner_epf)));
+        }
+        if app_data_dir.0.join(&existing_path).exists() {
+            // Re-uploading an earlier photo just makes it primary again
+            conn.execute(
+                "UPDATE image_metadata SET is_primary = (image_path = ?1) WHERE epf_number = ?2",
+                rusqlite::params![existing_path, epf_number],
+            )?;
+            conn.execute(
+                "UPDATE employees SET image_path = ?1 WHERE epf_number = ?2",
+                rusqlite::params![existing_path, epf_number],
+            )?;
+            let stored = fs::read(app_data_dir.0.join(&existing_path))
+                .map_err(|e| HrmError::io(format!("Failed to read image: {}", e)))?;
+            journal::write_atomic(&employee_folder.join(images::THUMBNAIL_FILENAME), &images::make_thumbnail(&stored)?)
+                .map_err(|e| HrmError::io(format!("Failed to save thumbnail: {}", e)))?;
+            return Ok(existing_path);
+        }
+    }
     
-    fs::write(&image_path, image_bytes).map_err(|e| format!("Failed to save image: {}", e))?;
+    // Camera photos are often several MB; shrink and re-encode before storing
+    let max_dimension = get_setting_i64(&conn, "image_max_dimension")
+        .map(|v| v as u32)
+        .unwrap_or(images::DEFAULT_MAX_DIMENSION);
+    let quality = get_setting_i64(&conn, "image_quality")
+        .map(|v| v as u8)
+        .unwrap_or(images::DEFAULT_QUALITY);
+    let processed = images::compress_image(&image_bytes, max_dimension, quality)?;
+    
+    // Earlier photos are kept, so each upload gets its own timestamped file
+    let timestamp: String = conn
+        .query_row("SELECT strftime('%Y%m%d_%H%M%S', 'now', 'localtime')", [], |row| row.get(0))?;
+    let mut image_filename = format!("photo_{}.{}", timestamp, images::PHOTO_EXTENSION);
+    let mut suffix = 1;
+    while employee_folder.join(&image_filename).exists() {
+        image_filename = format!("photo_{}_{}.{}", timestamp, suffix, images::PHOTO_EXTENSION);
+        suffix += 1;
+    }
+    let image_path = employee_folder.join(&image_filename);
     
     // Return the relative path to store in database
     let relative_path = format!("employee_images/{}/{}", epf_number, image_filename);
+    
+    // Files are written before the database rows; the journal lets startup
+    // remove a photo whose rows were never written
+    let intent = journal::begin(
+        &app_data_dir.0,
+        &journal::Operation::SaveImage { epf_number: epf_number.clone(), image_path: relative_path.clone() },
+    )?;
+    
+    journal::write_atomic(&image_path, &processed.bytes).map_err(|e| HrmError::io(format!("Failed to save image: {}", e)))?;
+    
+    let thumbnail = images::make_thumbnail(&processed.bytes)?;
+    journal::write_atomic(&employee_folder.join(images::THUMBNAIL_FILENAME), &thumbnail)
+        .map_err(|e| HrmError::io(format!("Failed to save thumbnail: {}", e)))?;
+    
+    // The new upload becomes the primary photo
+    let tx = conn.transaction()?;
+    tx.execute(
+        "UPDATE image_metadata SET is_primary = 0 WHERE epf_number = ?1",
+        [&epf_number],
+    )?;
+    tx.execute(
+        "INSERT OR REPLACE INTO image_metadata (
+            image_path, epf_number, original_size, original_width, original_height,
+            stored_size, width, height, format, content_hash, is_primary
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, 1)",
+        rusqlite::params![
+            relative_path,
+            epf_number,
+            image_bytes.len() as i64,
+            processed.original_width,
+            processed.original_height,
+            processed.bytes.len() as i64,
+            processed.width,
+            processed.height,
+            images::PHOTO_EXTENSION,
+            hash,
+        ],
+    )?;
+    tx.execute(
+        "UPDATE employees SET image_path = ?1 WHERE epf_number = ?2",
+        rusqlite::params![relative_path, epf_number],
+    )?;
+    tx.commit()?;
+    intent.commit()?;
+    
     Ok(relative_path)
 }
 
+#[tauri::command]
+pub fn list_employee_photos(
+    epf_number: String,
+    db: State<'_, DbConnection>,
+) -> Result<Vec<EmployeePhoto>, HrmError> {
+    let conn = db.0.lock()?;
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT image_path, original_size, stored_size, width, height, format, is_primary, created_at
+             FROM image_metadata WHERE epf_number = ?1
+             ORDER BY created_at DESC, image_path DESC",
+        )?;
+    
+    let mut photos = stmt
+        .query_map([&epf_number], |row| {
+            Ok(EmployeePhoto {
+                image_path: row.get(0)?,
+                original_size: row.get(1)?,
+                stored_size: row.get(2)?,
+                width: row.get(3)?,
+                height: row.get(4)?,
+                format: row.get(5)?,
+                is_primary: row.get(6)?,
+                created_at: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    // Photos saved before history was kept have no metadata row
+    let current_path: Option<String> = conn
+        .query_row(
+            "SELECT image_path FROM employees WHERE epf_number = ?1",
+            [&epf_number],
+            |row| row.get(0),
+        )
+        .ok()
+        .flatten();
+    if let Some(current_path) = current_path.filter(|p| !p.is_empty()) {
+        if !photos.iter().any(|p| p.image_path == current_path) {
+            photos.insert(0, EmployeePhoto {
+                image_path: current_path,
+                original_size: None,
+                stored_size: None,
+                width: None,
+                height: None,
+                format: None,
+                is_primary: true,
+                created_at: None,
+            });
+        }
+    }
+    
+    Ok(photos)
+}
+
+#[tauri::command]
+pub fn set_primary_photo(
+    epf_number: String,
+    image_path: String,
+    app_data_dir: State<'_, AppDataDir>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let expected_prefix = format!("employee_images/{}/", epf_number);
+    if !image_path.starts_with(&expected_prefix) || image_path.contains("..") {
+        return Err(HrmError::validation("Photo does not belong to this employee"));
+    }
+    
+    let conn = db.0.lock()?;
+    
+    let old_path: Option<String> = conn
+        .query_row(
+            "SELECT image_path FROM employees WHERE epf_number = ?1",
+            [&epf_number],
+            |row| row.get(0),
+        )
+        .map_err(|e| HrmError::not_found(format!("Employee not found: {}", e)))?;
+    // Only one of the employee's own photos, as listed by get_employee_photos
+    let in_history: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM image_metadata WHERE epf_number = ?1 AND image_path = ?2)",
+        [&epf_number, &image_path],
+        |row| row.get(0),
+    )?;
+    if !in_history && old_path.as_deref() != Some(image_path.as_str()) {
+        return Err(HrmError::validation("Photo does not belong to this employee"));
+    }
+    
+    let full_path = app_data_dir.0.join(&image_path);
+    let bytes = fs::read(&full_path).map_err(|_| HrmError::not_found("Photo file not found"))?;
+    
+    conn.execute(
+        "UPDATE image_metadata SET is_primary = (image_path = ?1) WHERE epf_number = ?2",
+        rusqlite::params![image_path, epf_number],
+    )?;
+    conn.execute(
+        "UPDATE employees SET image_path = ?1 WHERE epf_number = ?2",
+        rusqlite::params![image_path, epf_number],
+    )?;
+    
+    let thumbnail = images::make_thumbnail(&bytes)?;
+    journal::write_atomic(&app_data_dir.0.join(images::thumbnail_path_for(&image_path)), &thumbnail)
+        .map_err(|e| HrmError::io(format!("Failed to save thumbnail: {}", e)))?;
+    
+    let user_guard = current_user.0.lock()?;
+    let (user_id, username) = if let Some(ref user) = *user_guard {
+        (Some(user.user_id), user.username.clone())
+    } else {
+        (None, "system".to_string())
+    };
+    log_audit_action(
+        &conn,
+        user_id,
+        &username,
+        "UPDATE",
+        "EMPLOYEE",
+        Some(&epf_number),
+        old_path.as_deref(),
+        Some(&image_path),
+        Some(&format!("Changed primary photo for employee {}", epf_number)),
+    );
+    
+    Ok(())
+}
+
 #[tauri::command]
 pub fn get_employee_image(
     image_path: String,
     app_data_dir: State<'_, AppDataDir>,
-) -> Result<String, String> {
+) -> Result<String, HrmError> {
     let full_path = app_data_dir.0.join(&image_path);
     
     if !Path::new(&full_path).exists() {
-        return Err("Image not found".to_string());
+        return Err(HrmError::not_found("Image not found"));
     }
     
-    let image_bytes = fs::read(&full_path).map_err(|e| format!("Failed to read image: {}", e))?;
+    let image_bytes = fs::read(&full_path).map_err(|e| HrmError::io(format!("Failed to read image: {}", e)))?;
     
     // Determine MIME type from extension
-    let mime_type = if image_path.ends_with(".png") {
-        "image/png"
-    } else {
-        "image/jpeg"
-    };
+    let mime_type = images::mime_type_for_path(&image_path);
     
     // Return as base64 data URL
     let base64_data = general_purpose::STANDARD.encode(&image_bytes);
     Ok(format!("data:{};base64,{}", mime_type, base64_data))
 }
 
+// Called by the frontend after it writes an export file, so the accessed
+// records are traceable when read-access auditing is enabled
+#[tauri::command]
+pub fn record_data_export(
+    format: String,
+    epf_numbers: Vec<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let conn = db.0.lock()?;
+    
+    if !get_setting_bool(&conn, "audit_read_access") {
+        return Ok(());
+    }
+    
+    let user_guard = current_user.0.lock()?;
+    let (user_id, username) = if let Some(ref user) = *user_guard {
+        (Some(user.user_id), user.username.clone())
+    } else {
+        (None, "system".to_string())
+    };
+    
+    log_data_export(&conn, user_id, &username, &format, &epf_numbers);
+    
+    Ok(())
+}
+
+// Records which employees left the system in an export, when read-access
+// auditing is enabled
+pub(crate) fn log_data_export(
+    conn: &rusqlite::Connection,
+    user_id: Option<i32>,
+    username: &str,
+    format: &str,
+    epf_numbers: &[String],
+) {
+    if !get_setting_bool(conn, "audit_read_access") {
+        return;
+    }
+    
+    let records = serde_json::to_string(epf_numbers).ok();
+    log_audit_action(
+        conn,
+        user_id,
+        username,
+        "EXPORT",
+        "EMPLOYEE",
+        None,
+        None,
+        records.as_deref(),
+        Some(&format!("Exported {} employee records as {}", epf_numbers.len(), format)),
+    );
+}
+
+// Returns small base64 thumbnails for many employees in one call, keyed by
+// EPF number. Employees without a photo are left out of the map.
+#[tauri::command]
+pub fn get_employee_thumbnails(
+    epf_numbers: Vec<String>,
+    app_data_dir: State<'_, AppDataDir>,
+    db: State<'_, DbConnection>,
+) -> Result<HashMap<String, String>, HrmError> {
+    let mut thumbnails = HashMap::new();
+    if epf_numbers.is_empty() {
+        return Ok(thumbnails);
+    }
+    
+    let conn = db.0.lock()?;
+    
+    let mut image_paths: Vec<(String, String)> = Vec::new();
+    for chunk in epf_numbers.chunks(500) {
+        let placeholders = vec!["?"; chunk.len()].join(", ");
+        let sql = format!(
+            "SELECT epf_number, image_path FROM employees
+             WHERE image_path IS NOT NULL AND image_path != '' AND epf_number IN ({})",
+            placeholders
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt
+            .query_map(rusqlite::params_from_iter(chunk.iter()), |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<(String, String)>, _>>()?;
+        image_paths.extend(rows);
+    }
+    drop(conn);
+    
+    for (epf_number, image_path) in image_paths {
+        let thumb_path = app_data_dir.0.join(images::thumbnail_path_for(&image_path));
+        
+        let bytes = match fs::read(&thumb_path) {
+            Ok(bytes) => bytes,
+            Err(_) => {
+                // Photos saved before thumbnails existed get one generated on first use
+                let original = match fs::read(app_data_dir.0.join(&image_path)) {
+                    Ok(original) => original,
+                    Err(_) => continue,
+                };
+                let thumb = match images::make_thumbnail(&original) {
+                    Ok(thumb) => thumb,
+                    Err(_) => continue,
+                };
+                let _ = fs::write(&thumb_path, &thumb);
+                thumb
+            }
+        };
+        
+        thumbnails.insert(
+            epf_number,
+            format!("data:image/jpeg;base64,{}", general_purpose::STANDARD.encode(&bytes)),
+        );
+    }
+    
+    Ok(thumbnails)
+}
+
 #[tauri::command]
 pub fn save_binary_file(
     file_path: String,
     data: Vec<u8>,
-) -> Result<(), String> {
-    fs::write(&file_path, data).map_err(|e| format!("Failed to save file: {}", e))?;
+) -> Result<(), HrmError> {
+    fs::write(&file_path, data).map_err(|e| HrmError::io(format!("Failed to save file: {}", e)))?;
     Ok(())
 }
 
@@ -639,16 +1330,22 @@ pub fn save_binary_file(
 pub fn export_database(
     destination_path: String,
     app_data_dir: State<'_, AppDataDir>,
-) -> Result<String, String> {
+    db: State<'_, DbConnection>,
+) -> Result<String, HrmError> {
     let db_path = app_data_dir.0.join("hrm_system.db");
     
     if !db_path.exists() {
-        return Err("Database file not found".to_string());
+        return Err(HrmError::not_found("Database file not found"));
     }
     
     // Copy database file to destination
     fs::copy(&db_path, &destination_path)
-        .map_err(|e| format!("Failed to export database: {}", e))?;
+        .map_err(|e| HrmError::io(format!("Failed to export database: {}", e)))?;
+    
+    // Read by the startup health check to warn when backups are overdue
+    let conn = db.0.lock()?;
+    let now: String = conn.query_row("SELECT datetime('now', 'localtime')", [], |row| row.get(0))?;
+    set_setting(&conn, "last_backup_at", Some(&now))?;
     
     Ok(format!("Database exported successfully to: {}", destination_path))
 }
@@ -658,64 +1355,55 @@ pub fn import_database(
     source_path: String,
     app_data_dir: State<'_, AppDataDir>,
     db: State<'_, DbConnection>,
-) -> Result<String, String> {
+    current_user: State<'_, CurrentUser>,
+) -> Result<String, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    let user = match &*user_lock {
+        Some(session) if session.permissions.can_backup_database => session.clone(),
+        Some(_) => return Err(HrmError::permission_denied("Permission denied. You cannot import a database.")),
+        None => return Err(HrmError::not_logged_in()),
+    };
+    drop(user_lock);
     let source = Path::new(&source_path);
     
     if !source.exists() {
-        return Err("Source database file not found".to_string());
+        return Err(HrmError::not_found("Source database file not found"));
     }
+    backup::verify_database_file(source)?;
     
-    // Validate it's a valid SQLite database
-    let source_conn = rusqlite::Connection::open(&source_path)
-        .map_err(|e| format!("Invalid database file: {}", e))?;
+    // Hold the lock so no command writes to the database mid-import
+    let mut conn = db.0.lock()?;
+    let safety_copy = backup::write_backup(&conn, &app_data_dir.0, "pre_import")?;
+    backup::swap_database_file(&mut conn, &app_data_dir.0, source)?;
     
-    // Check if it has the required tables
-    let has_employees: Result<i32, _> = source_conn.query_row(
-        "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='employees'",
-        [],
-        |row| row.get(0),
-    );
-    
-    let has_users: Result<i32, _> = source_conn.query_row(
-        "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='users'",
-        [],
-        |row| row.get(0),
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "IMPORT",
+        "DATABASE",
+        None,
+        None,
+        None,
+        Some(&format!(
+            "Imported database from {} (previous database saved as {})",
+            source_path,
+            safety_copy.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+        )),
     );
+    drop(conn);
     
-    if has_employees.unwrap_or(0) == 0 || has_users.unwrap_or(0) == 0 {
-        return Err("Invalid HRM database: missing required tables".to_string());
-    }
-    
-    drop(source_conn);
+    // The imported users table may differ, so everyone logs in again
+    *current_user.0.lock()? = None;
     
-    // Create backup of current database first
-    let db_path = app_data_dir.0.join("hrm_system.db");
-    let backup_path = app_data_dir.0.join("hrm_system_backup.db");
-    
-    if db_path.exists() {
-        fs::copy(&db_path, &backup_path)
-            .map_err(|e| format!("Failed to create backup: {}", e))?;
-    }
-    
-    // Close current connection by acquiring and dropping the lock
-    // Note: In a real scenario, we'd need to restart the app
-    {
-        let _conn = db.0.lock().map_err(|e| e.to_string())?;
-        // Connection will be dropped at end of scope
-    }
-    
-    // Copy the source database to app data directory
-    fs::copy(&source_path, &db_path)
-        .map_err(|e| format!("Failed to import database: {}", e))?;
-    
-    Ok("Database imported successfully. Please restart the application for changes to take effect.".to_string())
+    Ok("Database imported successfully. Please log in again.".to_string())
 }
 
 #[tauri::command]
 pub fn get_database_info(
     app_data_dir: State<'_, AppDataDir>,
     db: State<'_, DbConnection>,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, HrmError> {
     let db_path = app_data_dir.0.join("hrm_system.db");
     
     let file_size = if db_path.exists() {
@@ -726,7 +1414,7 @@ pub fn get_database_info(
         0
     };
     
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.0.lock()?;
     
     let employee_count: i32 = conn
         .query_row("SELECT COUNT(*) FROM employees", [], |row| row.get(0))
@@ -745,7 +1433,142 @@ pub fn get_database_info(
     }))
 }
 
-fn format_file_size(bytes: u64) -> String {
+fn dir_size(path: &Path) -> u64 {
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| {
+                    let p = e.path();
+                    if p.is_dir() {
+                        dir_size(&p)
+                    } else {
+                        e.metadata().map(|m| m.len()).unwrap_or(0)
+                    }
+                })
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+// Finds image folders whose employee no longer exists, and files inside live
+// employee folders that aren't the current photo, a kept history photo or the
+// thumbnail. Nothing is removed unless `confirm` is true.
+#[tauri::command]
+pub fn cleanup_orphaned_files(
+    confirm: bool,
+    app_data_dir: State<'_, AppDataDir>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<OrphanCleanupReport, HrmError> {
+    let user_guard = current_user.0.lock()?;
+    let (user_id, username) = match &*user_guard {
+        Some(user) if user.permissions.can_manage_settings => (user.user_id, user.username.clone()),
+        _ => return Err(HrmError::permission_denied("Permission denied")),
+    };
+    drop(user_guard);
+    
+    let conn = db.0.lock()?;
+    let images_dir = app_data_dir.0.join("employee_images");
+    
+    let mut known_paths: HashSet<String> = HashSet::new();
+    let mut stmt = conn
+        .prepare(
+            "SELECT image_path FROM employees WHERE image_path IS NOT NULL
+             UNION SELECT image_path FROM image_metadata",
+        )?;
+    for path in stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+    {
+        known_paths.insert(path?);
+    }
+    drop(stmt);
+    
+    let mut files = Vec::new();
+    if images_dir.exists() {
+        let entries = fs::read_dir(&images_dir).map_err(|e| HrmError::io(format!("Failed to read images folder: {}", e)))?;
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let epf_number = entry.file_name().to_string_lossy().to_string();
+            let exists: bool = conn
+                .query_row(
+                    "SELECT COUNT(*) > 0 FROM employees WHERE epf_number = ?1",
+                    [&epf_number],
+                    |row| row.get(0),
+                )?;
+            
+            if !exists {
+                files.push(OrphanedFile {
+                    path: format!("employee_images/{}", epf_number),
+                    size_bytes: dir_size(&path),
+                    epf_number,
+                    is_folder: true,
+                });
+                continue;
+            }
+            
+            for file in fs::read_dir(&path).into_iter().flatten().filter_map(|e| e.ok()) {
+                let file_name = file.file_name().to_string_lossy().to_string();
+                let relative = format!("employee_images/{}/{}", epf_number, file_name);
+                if file_name == images::THUMBNAIL_FILENAME || known_paths.contains(&relative) {
+                    continue;
+                }
+                files.push(OrphanedFile {
+                    epf_number: epf_number.clone(),
+                    path: relative,
+                    size_bytes: file.metadata().map(|m| m.len()).unwrap_or(0),
+                    is_folder: false,
+                });
+            }
+        }
+    }
+    
+    let reclaimable_bytes: u64 = files.iter().map(|f| f.size_bytes).sum();
+    
+    if confirm && !files.is_empty() {
+        for file in &files {
+            let full_path = app_data_dir.0.join(&file.path);
+            let result = if file.is_folder {
+                fs::remove_dir_all(&full_path)
+            } else {
+                fs::remove_file(&full_path)
+            };
+            result.map_err(|e| HrmError::io(format!("Failed to delete {}: {}", file.path, e)))?;
+            if file.is_folder {
+                let _ = conn.execute("DELETE FROM image_metadata WHERE epf_number = ?1", [&file.epf_number]);
+            }
+        }
+        
+        let removed = serde_json::to_string(&files.iter().map(|f| &f.path).collect::<Vec<_>>()).ok();
+        log_audit_action(
+            &conn,
+            Some(user_id),
+            &username,
+            "DELETE",
+            "FILES",
+            None,
+            removed.as_deref(),
+            None,
+            Some(&format!(
+                "Cleaned up {} orphaned image files/folders ({})",
+                files.len(),
+                format_file_size(reclaimable_bytes)
+            )),
+        );
+    }
+    
+    Ok(OrphanCleanupReport {
+        deleted: confirm && !files.is_empty(),
+        files,
+        reclaimable_bytes,
+        reclaimable_formatted: format_file_size(reclaimable_bytes),
+    })
+}
+
+pub(crate) fn format_file_size(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
     const GB: u64 = MB * 1024;
@@ -789,6 +1612,23 @@ pub fn log_audit_action(
     );
 }
 
+// Records an audit event that needs administrator attention
+pub fn log_high_severity_event(
+    db: &rusqlite::Connection,
+    user_id: Option<i32>,
+    username: &str,
+    action: &str,
+    entity_type: &str,
+    entity_id: Option<&str>,
+    details: Option<&str>,
+) {
+    let _ = db.execute(
+        "INSERT INTO audit_logs (user_id, username, action, entity_type, entity_id, details, severity)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'high')",
+        rusqlite::params![user_id, username, action, entity_type, entity_id, details],
+    );
+}
+
 #[tauri::command]
 pub fn create_audit_log(
     action: String,
@@ -799,9 +1639,9 @@ pub fn create_audit_log(
     details: Option<String>,
     db: State<'_, DbConnection>,
     current_user: State<'_, CurrentUser>,
-) -> Result<(), String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
-    let user_guard = current_user.0.lock().map_err(|e| e.to_string())?;
+) -> Result<(), HrmError> {
+    let conn = db.0.lock()?;
+    let user_guard = current_user.0.lock()?;
     
     let (user_id, username) = if let Some(ref user) = *user_guard {
         (Some(user.user_id), user.username.clone())
@@ -828,11 +1668,11 @@ pub fn create_audit_log(
 pub fn get_audit_logs(
     filters: AuditLogFilters,
     db: State<'_, DbConnection>,
-) -> Result<AuditLogResult, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+) -> Result<AuditLogResult, HrmError> {
+    let conn = db.0.lock()?;
     
     let mut sql = String::from(
-        "SELECT id, user_id, username, action, entity_type, entity_id, old_value, new_value, details, created_at 
+        "SELECT id, user_id, username, action, entity_type, entity_id, old_value, new_value, details, created_at, severity 
          FROM audit_logs WHERE 1=1"
     );
     let mut count_sql = String::from("SELECT COUNT(*) FROM audit_logs WHERE 1=1");
@@ -853,6 +1693,11 @@ pub fn get_audit_logs(
         count_sql.push_str(" AND entity_type = ?");
         params.push(filters.entity_type);
     }
+    if !filters.severity.is_empty() {
+        sql.push_str(" AND severity = ?");
+        count_sql.push_str(" AND severity = ?");
+        params.push(filters.severity);
+    }
     if !filters.start_date.is_empty() {
         sql.push_str(" AND date(created_at) >= date(?)");
         count_sql.push_str(" AND date(created_at) >= date(?)");
@@ -873,13 +1718,13 @@ pub fn get_audit_logs(
         .collect();
     
     // Get total count
-    let mut count_stmt = conn.prepare(&count_sql).map_err(|e| e.to_string())?;
+    let mut count_stmt = conn.prepare(&count_sql)?;
     let total_count: i32 = count_stmt
         .query_row(params_refs.as_slice(), |row| row.get(0))
         .unwrap_or(0);
     
     // Get logs
-    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(&sql)?;
     
     let logs = stmt
         .query_map(params_refs.as_slice(), |row| {
@@ -894,11 +1739,10 @@ pub fn get_audit_logs(
                 new_value: row.get(7)?,
                 details: row.get(8)?,
                 created_at: row.get(9)?,
+                severity: row.get(10)?,
             })
-        })
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())?;
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
     
     Ok(AuditLogResult { logs, total_count })
 }
@@ -906,8 +1750,8 @@ pub fn get_audit_logs(
 #[tauri::command]
 pub fn get_audit_log_summary(
     db: State<'_, DbConnection>,
-) -> Result<serde_json::Value, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+) -> Result<serde_json::Value, HrmError> {
+    let conn = db.0.lock()?;
     
     // Total logs
     let total: i32 = conn
@@ -937,14 +1781,11 @@ pub fn get_audit_log_summary(
         .prepare(
             "SELECT action, COUNT(*) as count FROM audit_logs 
              GROUP BY action ORDER BY count DESC LIMIT 10",
-        )
-        .map_err(|e| e.to_string())?;
+        )?;
     
     let actions: Vec<(String, i32)> = action_stmt
-        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())?;
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
     
     // Recent activity by users
     let mut user_stmt = conn
@@ -952,14 +1793,11 @@ pub fn get_audit_log_summary(
             "SELECT username, COUNT(*) as count FROM audit_logs 
              WHERE date(created_at) >= date('now', '-7 days')
              GROUP BY username ORDER BY count DESC LIMIT 5",
-        )
-        .map_err(|e| e.to_string())?;
+        )?;
     
     let active_users: Vec<(String, i32)> = user_stmt
-        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())?;
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
     
     Ok(serde_json::json!({
         "total_logs": total,
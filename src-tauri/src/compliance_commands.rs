@@ -0,0 +1,325 @@
+use crate::attendance_commands::work_intervals;
+use crate::commands::log_data_export;
+use crate::error::HrmError;
+use crate::export_commands::csv_field;
+use crate::models::{NightWorkRecord, NightWorkReport, UserSession, WorkingHoursBreach};
+use crate::notification_commands::notify_hr_managers;
+use crate::settings_commands::get_setting_i64;
+use crate::validation::nic_is_female;
+use crate::{CurrentUser, DbConnection};
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveTime};
+use rusqlite::Connection;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use tauri::State;
+
+// Night work as defined by the Employment of Women, Young Persons and Children
+// Act: between 10 p.m. and 6 a.m.
+const NIGHT_START_HOUR: u32 = 22;
+const NIGHT_END_HOUR: u32 = 6;
+
+// Limits used when the working_hours_* settings are not set
+const DEFAULT_WEEKLY_HOUR_LIMIT: i64 = 60;
+const DEFAULT_MAX_CONSECUTIVE_DAYS: i64 = 6;
+// Days the scheduler looks back over when checking limits
+const CHECK_WINDOW_DAYS: i64 = 14;
+
+fn require_reports_permission(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_view_reports => Ok(session.clone()),
+        Some(_) => Err(HrmError::permission_denied("Permission denied. You cannot view compliance reports.")),
+        None => Err(HrmError::not_logged_in()),
+    }
+}
+
+fn require_export_permission(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_export_data => Ok(session.clone()),
+        Some(_) => Err(HrmError::permission_denied("Permission denied. You cannot export compliance reports.")),
+        None => Err(HrmError::not_logged_in()),
+    }
+}
+
+fn parse_period(from_date: &str, to_date: &str) -> Result<(NaiveDate, NaiveDate), HrmError> {
+    let from = NaiveDate::parse_from_str(from_date.trim(), "%Y-%m-%d")
+        .map_err(|_| HrmError::field("from_date", "Date must be in YYYY-MM-DD format"))?;
+    let to = NaiveDate::parse_from_str(to_date.trim(), "%Y-%m-%d")
+        .map_err(|_| HrmError::field("to_date", "Date must be in YYYY-MM-DD format"))?;
+    if to < from {
+        return Err(HrmError::field("to_date", "End date cannot be before the start date"));
+    }
+    Ok((from, to))
+}
+
+// Women who worked any part of a night in the period, with the police area
+// and transport route needed for the night work approval. Employees whose NIC
+// does not show gender are listed separately so they can be checked by hand.
+fn night_work_report(conn: &Connection, user: &UserSession, from: NaiveDate, to: NaiveDate) -> Result<NightWorkReport, HrmError> {
+    let intervals = work_intervals(conn, from, to)?;
+    let night_start = NaiveTime::from_hms_opt(NIGHT_START_HOUR, 0, 0).unwrap_or_default();
+    let night_end = NaiveTime::from_hms_opt(NIGHT_END_HOUR, 0, 0).unwrap_or_default();
+    
+    let mut stmt = conn.prepare(
+        "SELECT epf_number, name_with_initials, full_name, nic, department, designation, police_area,
+                transport_route, mobile_1
+         FROM employees WHERE anonymized_at IS NULL ORDER BY epf_number",
+    )?;
+    let employees = stmt
+        .query_map([], |row| {
+            Ok(NightWorkRecord {
+                epf_number: row.get(0)?,
+                name_with_initials: row.get(1)?,
+                full_name: row.get(2)?,
+                nic: row.get(3)?,
+                department: row.get(4)?,
+                designation: row.get(5)?,
+                police_area: row.get(6)?,
+                transport_route: row.get(7)?,
+                mobile_1: row.get(8)?,
+                night_shifts: 0,
+                first_night: String::new(),
+                last_night: String::new(),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    let mut report = NightWorkReport {
+        from_date: from.format("%Y-%m-%d").to_string(),
+        to_date: to.format("%Y-%m-%d").to_string(),
+        employees: Vec::new(),
+        unverified: Vec::new(),
+    };
+    for mut record in employees {
+        if !user.can_access_department(record.department.as_deref()) {
+            continue;
+        }
+        let Some(shifts) = intervals.get(&record.epf_number) else { continue };
+        let female = record.nic.as_deref().and_then(nic_is_female);
+        if female == Some(false) {
+            continue;
+        }
+    
+        // A night belongs to the date it starts on
+        let mut nights = BTreeSet::new();
+        for (start, end) in shifts {
+            let mut date = start.date() - Duration::days(1);
+            while date <= end.date() {
+                let window_start = date.and_time(night_start);
+                let window_end = (date + Duration::days(1)).and_time(night_end);
+                if *start < window_end && *end > window_start && date >= from && date <= to {
+                    nights.insert(date);
+                }
+                date += Duration::days(1);
+            }
+        }
+        let (Some(first), Some(last)) = (nights.first(), nights.last()) else { continue };
+        record.night_shifts = nights.len() as i64;
+        record.first_night = first.format("%Y-%m-%d").to_string();
+        record.last_night = last.format("%Y-%m-%d").to_string();
+        // Police area and route are what the approval is for, so only the
+        // other personal details are hidden
+        if !user.permissions.can_view_sensitive_data {
+            record.nic = None;
+            record.mobile_1 = None;
+        }
+    
+        if female == Some(true) {
+            report.employees.push(record);
+        } else {
+            report.unverified.push(record);
+        }
+    }
+    
+    Ok(report)
+}
+
+#[tauri::command]
+pub fn get_night_work_report(
+    from_date: String,
+    to_date: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<NightWorkReport, HrmError> {
+    let user = require_reports_permission(&current_user)?;
+    let (from, to) = parse_period(&from_date, &to_date)?;
+    
+    let conn = db.0.lock()?;
+    night_work_report(&conn, &user, from, to)
+}
+
+// Writes the night work report as CSV for the approval application. Returns
+// the number of employees listed, including those needing a gender check.
+#[tauri::command]
+pub fn export_night_work_report(
+    from_date: String,
+    to_date: String,
+    destination_path: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<usize, HrmError> {
+    let user = require_export_permission(&current_user)?;
+    let (from, to) = parse_period(&from_date, &to_date)?;
+    
+    let conn = db.0.lock()?;
+    let report = night_work_report(&conn, &user, from, to)?;
+    
+    // UTF-8 BOM so Excel picks the right encoding for Sinhala and Tamil names
+    let mut csv = String::from("\u{FEFF}");
+    csv.push_str(&format!("Female Employees on Night Work,{} to {}\r\n", report.from_date, report.to_date));
+    csv.push_str("EPF Number,Name with Initials,Full Name,NIC Number,Department,Designation,Police Area,Transport Route,Mobile,Nights Worked,First Night,Last Night,Note\r\n");
+    let rows = report
+        .employees
+        .iter()
+        .map(|r| (r, ""))
+        .chain(report.unverified.iter().map(|r| (r, "Gender not confirmed by NIC")));
+    for (record, note) in rows {
+        let values = [
+            record.epf_number.clone(),
+            record.name_with_initials.clone(),
+            record.full_name.clone(),
+            record.nic.clone().unwrap_or_default(),
+            record.department.clone().unwrap_or_default(),
+            record.designation.clone().unwrap_or_default(),
+            record.police_area.clone().unwrap_or_default(),
+            record.transport_route.clone().unwrap_or_default(),
+            record.mobile_1.clone().unwrap_or_default(),
+            record.night_shifts.to_string(),
+            record.first_night.clone(),
+            record.last_night.clone(),
+            note.to_string(),
+        ];
+        let fields: Vec<String> = values.iter().map(|v| csv_field(v)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+    
+    fs::write(&destination_path, csv).map_err(|e| HrmError::io(format!("Failed to save file: {}", e)))?;
+    
+    let epf_numbers: Vec<String> = report
+        .employees
+        .iter()
+        .chain(report.unverified.iter())
+        .map(|r| r.epf_number.clone())
+        .collect();
+    log_data_export(&conn, Some(user.user_id), &user.username, "night work report", &epf_numbers);
+    
+    Ok(epf_numbers.len())
+}
+
+// Employees over the weekly hour limit in any Monday-to-Sunday week touching
+// the period, or working more consecutive days than allowed in a run that
+// ends inside it. Hours count toward the day a shift starts.
+fn working_hours_breaches(conn: &Connection, from: NaiveDate, to: NaiveDate) -> Result<Vec<WorkingHoursBreach>, HrmError> {
+    let weekly_limit = get_setting_i64(conn, "working_hours_weekly_limit").unwrap_or(DEFAULT_WEEKLY_HOUR_LIMIT);
+    let max_consecutive = get_setting_i64(conn, "working_hours_max_consecutive_days").unwrap_or(DEFAULT_MAX_CONSECUTIVE_DAYS);
+    let week_start = from - Duration::days(from.weekday().num_days_from_monday() as i64);
+    // Far enough back to see the start of a long run of working days
+    let lookback = week_start.min(from - Duration::days(max_consecutive * 2 + 7));
+    let intervals = work_intervals(conn, lookback, to)?;
+    
+    let mut stmt = conn.prepare("SELECT epf_number, name_with_initials, department FROM employees WHERE anonymized_at IS NULL")?;
+    let employees: BTreeMap<String, (String, Option<String>)> = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, (row.get(1)?, row.get(2)?))))?
+        .collect::<Result<_, _>>()?;
+    
+    let mut breaches = Vec::new();
+    for (epf_number, (name_with_initials, department)) in &employees {
+        let Some(shifts) = intervals.get(epf_number) else { continue };
+        let mut daily_minutes: BTreeMap<NaiveDate, i64> = BTreeMap::new();
+        for (start, end) in shifts {
+            *daily_minutes.entry(start.date()).or_default() += (*end - *start).num_minutes();
+        }
+        let breach = |rule: &str, start: NaiveDate, end: NaiveDate, value: f64, limit: i64| WorkingHoursBreach {
+            epf_number: epf_number.clone(),
+            name_with_initials: name_with_initials.clone(),
+            department: department.clone(),
+            rule: rule.to_string(),
+            period_start: start.format("%Y-%m-%d").to_string(),
+            period_end: end.format("%Y-%m-%d").to_string(),
+            value,
+            limit,
+        };
+    
+        let mut monday = week_start;
+        while monday <= to {
+            let sunday = monday + Duration::days(6);
+            let minutes: i64 = daily_minutes.range(monday..=sunday).map(|(_, m)| m).sum();
+            let hours = (minutes as f64 / 60.0 * 10.0).round() / 10.0;
+            if hours > weekly_limit as f64 {
+                breaches.push(breach("weekly_hours", monday, sunday, hours, weekly_limit));
+            }
+            monday += Duration::days(7);
+        }
+    
+        let mut run_start: Option<NaiveDate> = None;
+        let mut previous: Option<NaiveDate> = None;
+        let days: Vec<NaiveDate> = daily_minutes.keys().copied().collect();
+        for (index, day) in days.iter().enumerate() {
+            if previous.is_none_or(|p| *day - p != Duration::days(1)) {
+                run_start = Some(*day);
+            }
+            previous = Some(*day);
+            let run_ends = days.get(index + 1).is_none_or(|next| *next - *day != Duration::days(1));
+            if let (true, Some(start)) = (run_ends, run_start) {
+                let length = (*day - start).num_days() + 1;
+                if length > max_consecutive && *day >= from {
+                    breaches.push(breach("consecutive_days", start, *day, length as f64, max_consecutive));
+                }
+            }
+        }
+    }
+    
+    Ok(breaches)
+}
+
+// Notifies HR managers of limit breaches found in the last two weeks. Each
+// week or run of days is alerted on once, though a run that keeps growing
+// stays in the report. Called by the scheduler.
+pub fn check_working_hours(conn: &Connection) -> Result<usize, HrmError> {
+    let today = Local::now().date_naive();
+    let breaches = working_hours_breaches(conn, today - Duration::days(CHECK_WINDOW_DAYS), today)?;
+    
+    let mut alerted = 0;
+    for breach in &breaches {
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO working_hours_alerts (epf_number, rule, period_start) VALUES (?1, ?2, ?3)",
+            rusqlite::params![breach.epf_number, breach.rule, breach.period_start],
+        )?;
+        if inserted == 0 {
+            continue;
+        }
+        let message = if breach.rule == "weekly_hours" {
+            format!(
+                "{} ({}) worked {} hours in the week starting {}, over the {} hour limit",
+                breach.name_with_initials, breach.epf_number, breach.value, breach.period_start, breach.limit
+            )
+        } else {
+            format!(
+                "{} ({}) has worked {} days in a row since {} without a rest day (limit {})",
+                breach.name_with_initials, breach.epf_number, breach.value, breach.period_start, breach.limit
+            )
+        };
+        notify_hr_managers(conn, "Working hours limit exceeded", &message, "high", Some("EMPLOYEE"), Some(&breach.epf_number));
+        alerted += 1;
+    }
+    Ok(alerted)
+}
+
+#[tauri::command]
+pub fn get_working_hours_report(
+    from_date: String,
+    to_date: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<WorkingHoursBreach>, HrmError> {
+    let user = require_reports_permission(&current_user)?;
+    let (from, to) = parse_period(&from_date, &to_date)?;
+    
+    let conn = db.0.lock()?;
+    Ok(working_hours_breaches(&conn, from, to)?
+        .into_iter()
+        .filter(|b| user.can_access_department(b.department.as_deref()))
+        .collect())
+}
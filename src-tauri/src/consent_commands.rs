@@ -0,0 +1,237 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{EmployeeConsent, MissingConsent, UserSession};
+use crate::validation::parse_iso_date;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use tauri::State;
+
+// Consents every employee is expected to have signed, as (type, label)
+pub const CONSENT_TYPES: [(&str, &str); 3] = [
+    ("data_processing", "Personal data processing"),
+    ("cctv", "CCTV monitoring"),
+    ("medical", "Medical information"),
+];
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+fn require_edit(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user = logged_in_user(current_user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit employees."));
+    }
+    Ok(user)
+}
+
+const CONSENT_COLUMNS: &str = "id, epf_number, consent_type, signed_on, document_path, withdrawn_on, recorded_by, created_at";
+
+fn consent_from_row(row: &rusqlite::Row) -> rusqlite::Result<EmployeeConsent> {
+    Ok(EmployeeConsent {
+        id: row.get(0)?,
+        epf_number: row.get(1)?,
+        consent_type: row.get(2)?,
+        signed_on: row.get(3)?,
+        document_path: row.get(4)?,
+        withdrawn_on: row.get(5)?,
+        recorded_by: row.get(6)?,
+        created_at: row.get(7)?,
+    })
+}
+
+fn query_consent(conn: &Connection, consent_id: i64) -> Result<EmployeeConsent, HrmError> {
+    conn.query_row(
+        &format!("SELECT {} FROM employee_consents WHERE id = ?1", CONSENT_COLUMNS),
+        [consent_id],
+        consent_from_row,
+    )
+    .map_err(|_| HrmError::not_found("Consent record not found"))
+}
+
+// All consents an employee has signed, including withdrawn ones
+#[tauri::command]
+pub fn get_employee_consents(
+    epf_number: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<EmployeeConsent>, HrmError> {
+    logged_in_user(&current_user)?;
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM employee_consents WHERE epf_number = ?1 ORDER BY signed_on DESC, id DESC",
+            CONSENT_COLUMNS
+        ))?;
+    let consents = stmt
+        .query_map([&epf_number], consent_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(consents)
+}
+
+// Records a signed consent form. Re-signing after a withdrawal adds a new
+// record so the history is kept.
+#[tauri::command]
+pub fn record_consent(
+    consent: EmployeeConsent,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = require_edit(&current_user)?;
+    if !CONSENT_TYPES.iter().any(|(t, _)| *t == consent.consent_type) {
+        return Err(HrmError::validation("Consent type must be data_processing, cctv or medical"));
+    }
+    parse_iso_date(&consent.signed_on).ok_or("Signed date must be a valid date (YYYY-MM-DD)")?;
+    let document_path = consent.document_path.as_deref().map(str::trim).filter(|p| !p.is_empty());
+    
+    let conn = db.0.lock()?;
+    let exists: bool = conn
+        .query_row("SELECT COUNT(*) > 0 FROM employees WHERE epf_number = ?1", [&consent.epf_number], |row| row.get(0))?;
+    if !exists {
+        return Err(HrmError::not_found("Employee not found"));
+    }
+    let already_signed: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM employee_consents WHERE epf_number = ?1 AND consent_type = ?2 AND withdrawn_on IS NULL",
+            rusqlite::params![consent.epf_number, consent.consent_type],
+            |row| row.get(0),
+        )?;
+    if already_signed {
+        return Err(HrmError::validation("This employee already has a current consent of this type"));
+    }
+    
+    conn.execute(
+        "INSERT INTO employee_consents (epf_number, consent_type, signed_on, document_path, recorded_by)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![consent.epf_number, consent.consent_type, consent.signed_on, document_path, user.username],
+    )?;
+    let id = conn.last_insert_rowid();
+    
+    let new_value = serde_json::to_string(&consent).ok();
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "CONSENT",
+        Some(&consent.epf_number),
+        None,
+        new_value.as_deref(),
+        Some(&format!(
+            "Recorded {} consent for employee {} signed on {}",
+            consent.consent_type.replace('_', " "),
+            consent.epf_number,
+            consent.signed_on
+        )),
+    );
+    
+    Ok(id)
+}
+
+// Marks a consent as withdrawn by the employee; the record itself is kept
+#[tauri::command]
+pub fn withdraw_consent(
+    consent_id: i64,
+    withdrawn_on: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = require_edit(&current_user)?;
+    parse_iso_date(&withdrawn_on).ok_or("Withdrawal date must be a valid date (YYYY-MM-DD)")?;
+    
+    let conn = db.0.lock()?;
+    let old = query_consent(&conn, consent_id)?;
+    if old.withdrawn_on.is_some() {
+        return Err(HrmError::validation("This consent has already been withdrawn"));
+    }
+    if withdrawn_on < old.signed_on {
+        return Err(HrmError::validation("Withdrawal date cannot be before the signed date"));
+    }
+    conn.execute(
+        "UPDATE employee_consents SET withdrawn_on = ?1 WHERE id = ?2",
+        rusqlite::params![withdrawn_on, consent_id],
+    )?;
+    
+    let old_value = serde_json::to_string(&old).ok();
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "WITHDRAW",
+        "CONSENT",
+        Some(&old.epf_number),
+        old_value.as_deref(),
+        Some(&withdrawn_on),
+        Some(&format!(
+            "Employee {} withdrew {} consent on {}",
+            old.epf_number,
+            old.consent_type.replace('_', " "),
+            withdrawn_on
+        )),
+    );
+    
+    Ok(())
+}
+
+// Active employees lacking a current (signed, not withdrawn) consent of one
+// or more types, for PDPA compliance follow-up
+#[tauri::command]
+pub fn get_missing_consents(
+    department: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<MissingConsent>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_reports {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view reports."));
+    }
+    let department = department.filter(|d| !d.trim().is_empty());
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT e.epf_number, e.name_with_initials, e.department,
+                    (SELECT GROUP_CONCAT(consent_type) FROM employee_consents c
+                     WHERE c.epf_number = e.epf_number AND c.withdrawn_on IS NULL)
+             FROM employees e
+             WHERE e.working_status IN (SELECT code FROM working_statuses WHERE counts_as_active = 1)
+               AND (?1 IS NULL OR e.department = ?1)
+             ORDER BY e.department, e.epf_number",
+        )?;
+    let rows = stmt
+        .query_map([&department], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    let missing = rows
+        .into_iter()
+        .filter(|(_, _, department, _)| user.can_access_department(department.as_deref()))
+        .filter_map(|(epf_number, name_with_initials, department, signed)| {
+            let signed = signed.unwrap_or_default();
+            let missing_types: Vec<String> = CONSENT_TYPES
+                .iter()
+                .filter(|(t, _)| !signed.split(',').any(|s| s == *t))
+                .map(|(t, _)| t.to_string())
+                .collect();
+            if missing_types.is_empty() {
+                None
+            } else {
+                Some(MissingConsent {
+                    epf_number,
+                    name_with_initials,
+                    department,
+                    missing_types,
+                })
+            }
+        })
+        .collect();
+    
+    Ok(missing)
+}
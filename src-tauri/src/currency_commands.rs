@@ -0,0 +1,169 @@
+use crate::commands::log_audit_action;
+use crate::deduction_commands::require_month;
+use crate::error::HrmError;
+use crate::models::{ExchangeRate, UserSession};
+use crate::payroll_commands::ensure_month_open;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::{Connection, OptionalExtension};
+use tauri::State;
+
+// Payroll, reports and bank files are all in rupees
+pub const BASE_CURRENCY: &str = "LKR";
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+fn require_settings_permission(user: &UserSession) -> Result<(), HrmError> {
+    if user.permissions.can_manage_settings {
+        Ok(())
+    } else {
+        Err(HrmError::permission_denied("Permission denied. You cannot change exchange rates."))
+    }
+}
+
+// Upper-cases a three letter ISO 4217 code such as USD
+pub(crate) fn normalize_currency(code: &str) -> Result<String, HrmError> {
+    let code = code.trim().to_uppercase();
+    if code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic()) {
+        Ok(code)
+    } else {
+        Err(HrmError::field("currency", "Currency must be a three letter code such as USD"))
+    }
+}
+
+// Rupees per unit of `currency` for a month (YYYY-MM). Fails if the month's
+// rate has not been entered, so nothing is converted at a guessed rate.
+pub(crate) fn rate_to_base(conn: &Connection, currency: &str, month: &str) -> Result<f64, HrmError> {
+    if currency == BASE_CURRENCY {
+        return Ok(1.0);
+    }
+    conn.query_row(
+        "SELECT rate FROM exchange_rates WHERE month = ?1 AND currency = ?2",
+        [month, currency],
+        |row| row.get(0),
+    )
+    .optional()?
+    .ok_or_else(|| HrmError::validation(format!("No {} exchange rate has been entered for {}", currency, month)))
+}
+
+// Exchange rates for a month, or every month when none is given
+#[tauri::command]
+pub fn get_exchange_rates(
+    month: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<ExchangeRate>, HrmError> {
+    logged_in_user(&current_user)?;
+    if let Some(month) = &month {
+        require_month(month)?;
+    }
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(
+        "SELECT month, currency, rate, updated_by, updated_at FROM exchange_rates
+         WHERE ?1 IS NULL OR month = ?1 ORDER BY month DESC, currency",
+    )?;
+    let rates = stmt
+        .query_map([&month], |row| {
+            Ok(ExchangeRate {
+                month: row.get(0)?,
+                currency: row.get(1)?,
+                rate: row.get(2)?,
+                updated_by: row.get(3)?,
+                updated_at: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rates)
+}
+
+// Sets the rupee rate for a currency in a month. Locked months keep the rate
+// they were paid at.
+#[tauri::command]
+pub fn set_exchange_rate(
+    month: String,
+    currency: String,
+    rate: f64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_settings_permission(&user)?;
+    require_month(&month)?;
+    let currency = normalize_currency(&currency)?;
+    if currency == BASE_CURRENCY {
+        return Err(HrmError::field("currency", "Rates are entered for foreign currencies only"));
+    }
+    if !rate.is_finite() || rate <= 0.0 {
+        return Err(HrmError::field("rate", "Rate must be greater than zero"));
+    }
+    
+    let conn = db.0.lock()?;
+    ensure_month_open(&conn, &month)?;
+    let old_rate: Option<f64> = conn
+        .query_row(
+            "SELECT rate FROM exchange_rates WHERE month = ?1 AND currency = ?2",
+            [&month, &currency],
+            |row| row.get(0),
+        )
+        .optional()?;
+    conn.execute(
+        "INSERT INTO exchange_rates (month, currency, rate, updated_by, updated_at)
+         VALUES (?1, ?2, ?3, ?4, CURRENT_TIMESTAMP)
+         ON CONFLICT(month, currency) DO UPDATE SET rate = excluded.rate,
+             updated_by = excluded.updated_by, updated_at = CURRENT_TIMESTAMP",
+        rusqlite::params![month, currency, rate, user.username],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "SETTING",
+        Some(&format!("exchange_rate:{}:{}", currency, month)),
+        old_rate.map(|r| r.to_string()).as_deref(),
+        Some(&rate.to_string()),
+        Some(&format!("Set {} exchange rate for {}", currency, month)),
+    );
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_exchange_rate(
+    month: String,
+    currency: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_settings_permission(&user)?;
+    let currency = normalize_currency(&currency)?;
+    
+    let conn = db.0.lock()?;
+    ensure_month_open(&conn, &month)?;
+    let deleted = conn.execute(
+        "DELETE FROM exchange_rates WHERE month = ?1 AND currency = ?2",
+        [&month, &currency],
+    )?;
+    if deleted == 0 {
+        return Err(HrmError::not_found("Exchange rate not found"));
+    }
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "DELETE",
+        "SETTING",
+        Some(&format!("exchange_rate:{}:{}", currency, month)),
+        None,
+        None,
+        Some(&format!("Removed {} exchange rate for {}", currency, month)),
+    );
+    
+    Ok(())
+}
@@ -0,0 +1,307 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{CustomFieldDefinition, Employee, FieldError};
+use crate::validation::normalize_entered_date;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use tauri::State;
+
+const FIELD_TYPES: [&str; 4] = ["text", "number", "date", "select"];
+
+fn definition_from_row(row: &rusqlite::Row) -> rusqlite::Result<CustomFieldDefinition> {
+    let options: Option<String> = row.get(3)?;
+    Ok(CustomFieldDefinition {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        field_type: row.get(2)?,
+        options: options
+            .and_then(|o| serde_json::from_str(&o).ok())
+            .unwrap_or_default(),
+        is_active: row.get(4)?,
+    })
+}
+
+pub fn load_definitions(conn: &Connection, active_only: bool) -> rusqlite::Result<Vec<CustomFieldDefinition>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, field_type, options, is_active FROM custom_field_definitions
+         WHERE is_active = 1 OR ?1 = 0 ORDER BY name",
+    )?;
+    let definitions = stmt
+        .query_map([active_only], definition_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(definitions)
+}
+
+// Fills in custom_fields on each employee, keyed by field name
+pub fn attach_custom_values(conn: &Connection, employees: &mut [Employee]) -> rusqlite::Result<()> {
+    if employees.is_empty() {
+        return Ok(());
+    }
+    
+    let mut values: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let epf_numbers: Vec<String> = employees.iter().map(|e| e.epf_number.clone()).collect();
+    for chunk in epf_numbers.chunks(500) {
+        let placeholders = vec!["?"; chunk.len()].join(", ");
+        let sql = format!(
+            "SELECT v.epf_number, d.name, v.value FROM employee_custom_values v
+             JOIN custom_field_definitions d ON d.id = v.field_id
+             WHERE d.is_active = 1 AND v.epf_number IN ({})",
+            placeholders
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(chunk.iter()), |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+        })?;
+        for row in rows {
+            let (epf_number, name, value) = row?;
+            values.entry(epf_number).or_default().insert(name, value);
+        }
+    }
+    
+    for employee in employees.iter_mut() {
+        if let Some(fields) = values.remove(&employee.epf_number) {
+            employee.custom_fields = fields;
+        }
+    }
+    Ok(())
+}
+
+// Checks custom values against their definitions, trimming them in place
+pub fn custom_value_errors(conn: &Connection, employee: &mut Employee) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+    if employee.custom_fields.is_empty() {
+        return errors;
+    }
+    let definitions = load_definitions(conn, true).unwrap_or_default();
+    
+    for (name, value) in employee.custom_fields.iter_mut() {
+        *value = value.trim().to_string();
+        let field = format!("custom:{}", name);
+        let definition = match definitions.iter().find(|d| &d.name == name) {
+            Some(definition) => definition,
+            None => {
+                errors.push(FieldError { field, message: format!("Unknown custom field '{}'", name) });
+                continue;
+            }
+        };
+        if value.is_empty() {
+            continue;
+        }
+        let problem = match definition.field_type.as_str() {
+            "number" if value.parse::<f64>().is_err() => Some(format!("{} must be a number", name)),
+            "date" => match normalize_entered_date(value) {
+                Some(iso) => {
+                    *value = iso;
+                    None
+                }
+                None => Some(format!("{} must be a valid date, e.g. 15/01/2024 or 2024-01-15", name)),
+            },
+            "select" if !definition.options.contains(value) => {
+                Some(format!("{} must be one of: {}", name, definition.options.join(", ")))
+            }
+            _ => None,
+        };
+        if let Some(message) = problem {
+            errors.push(FieldError { field, message });
+        }
+    }
+    errors
+}
+
+// Stores the custom values present on the employee; an empty value clears
+// that field. Fields not sent are left untouched.
+pub fn save_custom_values(conn: &Connection, employee: &Employee) -> rusqlite::Result<()> {
+    for (name, value) in &employee.custom_fields {
+        let field_id: i64 = match conn.query_row(
+            "SELECT id FROM custom_field_definitions WHERE name = ?1",
+            [name],
+            |row| row.get(0),
+        ) {
+            Ok(id) => id,
+            Err(rusqlite::Error::QueryReturnedNoRows) => continue,
+            Err(e) => return Err(e),
+        };
+        if value.is_empty() {
+            conn.execute(
+                "DELETE FROM employee_custom_values WHERE epf_number = ?1 AND field_id = ?2",
+                rusqlite::params![employee.epf_number, field_id],
+            )?;
+        } else {
+            conn.execute(
+                "INSERT INTO employee_custom_values (epf_number, field_id, value) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(epf_number, field_id) DO UPDATE SET value = excluded.value",
+                rusqlite::params![employee.epf_number, field_id, value],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+pub fn delete_custom_values(conn: &Connection, epf_number: &str) -> rusqlite::Result<usize> {
+    conn.execute("DELETE FROM employee_custom_values WHERE epf_number = ?1", [epf_number])
+}
+
+fn validate_definition(name: &str, field_type: &str, options: &[String]) -> Result<(), HrmError> {
+    if name.trim().is_empty() {
+        return Err(HrmError::validation("Field name is required"));
+    }
+    if !FIELD_TYPES.contains(&field_type) {
+        return Err(HrmError::validation("Field type must be text, number, date or select"));
+    }
+    if field_type == "select" && options.iter().all(|o| o.trim().is_empty()) {
+        return Err(HrmError::validation("A select field needs at least one option"));
+    }
+    Ok(())
+}
+
+fn require_settings_permission(current_user: &State<'_, CurrentUser>) -> Result<(i32, String), HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_manage_settings => Ok((session.user_id, session.username.clone())),
+        _ => Err(HrmError::permission_denied("Permission denied. You cannot change system settings.")),
+    }
+}
+
+#[tauri::command]
+pub fn get_custom_field_definitions(
+    include_inactive: bool,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<CustomFieldDefinition>, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    if user_lock.is_none() {
+        return Err(HrmError::not_logged_in());
+    }
+    drop(user_lock);
+    
+    let conn = db.0.lock()?;
+    load_definitions(&conn, !include_inactive).map_err(HrmError::from)
+}
+
+#[tauri::command]
+pub fn create_custom_field(
+    name: String,
+    field_type: String,
+    options: Vec<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let (user_id, username) = require_settings_permission(&current_user)?;
+    validate_definition(&name, &field_type, &options)?;
+    
+    let conn = db.0.lock()?;
+    let options_json = serde_json::to_string(&options)?;
+    conn.execute(
+        "INSERT INTO custom_field_definitions (name, field_type, options) VALUES (?1, ?2, ?3)",
+        rusqlite::params![name.trim(), field_type, options_json],
+    )
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE constraint") {
+            HrmError::conflict("A custom field with this name already exists")
+        } else {
+            HrmError::from(e)
+        }
+    })?;
+    let id = conn.last_insert_rowid();
+    
+    log_audit_action(
+        &conn,
+        Some(user_id),
+        &username,
+        "CREATE",
+        "CUSTOM_FIELD",
+        Some(&id.to_string()),
+        None,
+        Some(&options_json),
+        Some(&format!("Created custom field: {} ({})", name.trim(), field_type)),
+    );
+    
+    Ok(id)
+}
+
+// Renames a field, changes its options or (de)activates it. Deactivated
+// fields keep their stored values but are hidden from fetches and exports.
+#[tauri::command]
+pub fn update_custom_field(
+    field_id: i64,
+    name: String,
+    options: Vec<String>,
+    is_active: bool,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let (user_id, username) = require_settings_permission(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let existing = conn
+        .query_row(
+            "SELECT id, name, field_type, options, is_active FROM custom_field_definitions WHERE id = ?1",
+            [field_id],
+            definition_from_row,
+        )
+        .map_err(|_| HrmError::not_found("Custom field not found"))?;
+    validate_definition(&name, &existing.field_type, &options)?;
+    
+    let options_json = serde_json::to_string(&options)?;
+    conn.execute(
+        "UPDATE custom_field_definitions SET name = ?1, options = ?2, is_active = ?3 WHERE id = ?4",
+        rusqlite::params![name.trim(), options_json, is_active, field_id],
+    )
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE constraint") {
+            HrmError::conflict("A custom field with this name already exists")
+        } else {
+            HrmError::from(e)
+        }
+    })?;
+    
+    let old_value = serde_json::to_string(&existing).ok();
+    log_audit_action(
+        &conn,
+        Some(user_id),
+        &username,
+        "UPDATE",
+        "CUSTOM_FIELD",
+        Some(&field_id.to_string()),
+        old_value.as_deref(),
+        Some(&options_json),
+        Some(&format!("Updated custom field: {}", name.trim())),
+    );
+    
+    Ok(())
+}
+
+// Removes a field and every value stored for it
+#[tauri::command]
+pub fn delete_custom_field(
+    field_id: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let (user_id, username) = require_settings_permission(&current_user)?;
+    
+    let mut conn = db.0.lock()?;
+    let tx = conn.transaction()?;
+    let name: String = tx
+        .query_row("SELECT name FROM custom_field_definitions WHERE id = ?1", [field_id], |row| row.get(0))
+        .map_err(|_| HrmError::not_found("Custom field not found"))?;
+    let removed = tx
+        .execute("DELETE FROM employee_custom_values WHERE field_id = ?1", [field_id])?;
+    tx.execute("DELETE FROM custom_field_definitions WHERE id = ?1", [field_id])?;
+    
+    log_audit_action(
+        &tx,
+        Some(user_id),
+        &username,
+        "DELETE",
+        "CUSTOM_FIELD",
+        Some(&field_id.to_string()),
+        Some(&name),
+        None,
+        Some(&format!("Deleted custom field: {} ({} stored values)", name, removed)),
+    );
+    tx.commit()?;
+    
+    Ok(())
+}
@@ -0,0 +1,161 @@
+use crate::commands::{employee_from_row, EMPLOYEE_COLUMNS};
+use crate::custom_fields;
+use crate::deduction_commands::require_month;
+use crate::error::HrmError;
+use crate::models::{EmployeePage, MonthlyMovement};
+use crate::DbConnection;
+use rusqlite::Connection;
+use tauri::State;
+
+const ACTIVE: &str = "working_status IN (SELECT code FROM working_statuses WHERE counts_as_active = 1)";
+
+// One page of employees matching the WHERE clause plus the total match count,
+// so a dashboard card can be opened into a paged list
+fn employee_page(
+    conn: &Connection,
+    condition: &str,
+    order_by: &str,
+    params: &[&dyn rusqlite::ToSql],
+    limit: i32,
+    offset: i32,
+) -> Result<EmployeePage, HrmError> {
+    if limit < 1 || offset < 0 {
+        return Err(HrmError::validation("Invalid page"));
+    }
+    let total_count: i32 = conn
+        .query_row(&format!("SELECT COUNT(*) FROM employees WHERE {}", condition), params, |row| row.get(0))?;
+    
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM employees WHERE {} ORDER BY {} LIMIT {} OFFSET {}",
+            EMPLOYEE_COLUMNS, condition, order_by, limit, offset
+        ))?;
+    let mut employees = stmt
+        .query_map(params, employee_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    custom_fields::attach_custom_values(conn, &mut employees)?;
+    
+    Ok(EmployeePage { employees, total_count })
+}
+
+fn require_days(days: i32) -> Result<String, HrmError> {
+    if days < 1 {
+        return Err(HrmError::validation("Days must be at least 1"));
+    }
+    Ok(format!("-{} days", days))
+}
+
+// Employees behind the "recent joinings" card, newest first
+#[tauri::command]
+pub fn get_recent_joiners(days: i32, limit: i32, offset: i32, db: State<'_, DbConnection>) -> Result<EmployeePage, HrmError> {
+    let window = require_days(days)?;
+    let conn = db.0.lock()?;
+    employee_page(
+        &conn,
+        "date_of_join >= date('now', ?1)",
+        "date_of_join DESC, epf_number",
+        &[&window],
+        limit,
+        offset,
+    )
+}
+
+// Employees behind the "recent resignations" card, newest first
+#[tauri::command]
+pub fn get_recent_resignations(days: i32, limit: i32, offset: i32, db: State<'_, DbConnection>) -> Result<EmployeePage, HrmError> {
+    let window = require_days(days)?;
+    let conn = db.0.lock()?;
+    employee_page(
+        &conn,
+        "date_of_resign >= date('now', ?1)",
+        "date_of_resign DESC, epf_number",
+        &[&window],
+        limit,
+        offset,
+    )
+}
+
+// The breakdown charts count active employees only and show a missing value
+// as "Unassigned"; these drill-downs match that
+#[tauri::command]
+pub fn get_employees_by_department(name: String, limit: i32, offset: i32, db: State<'_, DbConnection>) -> Result<EmployeePage, HrmError> {
+    let conn = db.0.lock()?;
+    employee_page(
+        &conn,
+        &format!("COALESCE(department, 'Unassigned') = ?1 AND {}", ACTIVE),
+        "epf_number",
+        &[&name],
+        limit,
+        offset,
+    )
+}
+
+#[tauri::command]
+pub fn get_employees_by_cader(name: String, limit: i32, offset: i32, db: State<'_, DbConnection>) -> Result<EmployeePage, HrmError> {
+    let conn = db.0.lock()?;
+    employee_page(
+        &conn,
+        &format!("COALESCE(cader, 'Unassigned') = ?1 AND {}", ACTIVE),
+        "epf_number",
+        &[&name],
+        limit,
+        offset,
+    )
+}
+
+#[tauri::command]
+pub fn get_employees_by_allocation(name: String, limit: i32, offset: i32, db: State<'_, DbConnection>) -> Result<EmployeePage, HrmError> {
+    let conn = db.0.lock()?;
+    employee_page(
+        &conn,
+        &format!("COALESCE(allocation, 'Unassigned') = ?1 AND {}", ACTIVE),
+        "epf_number",
+        &[&name],
+        limit,
+        offset,
+    )
+}
+
+// Behind the per-status counts; pass the status code
+#[tauri::command]
+pub fn get_employees_by_status(code: String, limit: i32, offset: i32, db: State<'_, DbConnection>) -> Result<EmployeePage, HrmError> {
+    let conn = db.0.lock()?;
+    employee_page(&conn, "working_status = ?1", "epf_number", &[&code], limit, offset)
+}
+
+// Joins and resignations per month from `from` to `to` (YYYY-MM, inclusive)
+// for the trend chart. Counted from employment periods, so a seasonal worker
+// who returns shows up as a join again; months with no movement are zero.
+#[tauri::command]
+pub fn get_monthly_movement(from: String, to: String, db: State<'_, DbConnection>) -> Result<Vec<MonthlyMovement>, HrmError> {
+    require_month(&from)?;
+    require_month(&to)?;
+    if to < from {
+        return Err(HrmError::validation("The end month cannot be before the start month"));
+    }
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(
+            "WITH RECURSIVE months(month) AS (
+                 SELECT ?1
+                 UNION ALL
+                 SELECT strftime('%Y-%m', month || '-01', '+1 month') FROM months WHERE month < ?2
+             )
+             SELECT month,
+                    (SELECT COUNT(*) FROM employment_periods WHERE substr(start_date, 1, 7) = month),
+                    (SELECT COUNT(*) FROM employment_periods WHERE substr(end_date, 1, 7) = month)
+             FROM months
+             ORDER BY month",
+        )?;
+    let movement = stmt
+        .query_map([&from, &to], |row| {
+            Ok(MonthlyMovement {
+                month: row.get(0)?,
+                joined: row.get(1)?,
+                resigned: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(movement)
+}
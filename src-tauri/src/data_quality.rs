@@ -0,0 +1,176 @@
+use crate::error::HrmError;
+use crate::models::{DataQualityCategory, DataQualityIssue};
+use crate::validation::{age_outside_working_range, invalid_employee_dates, normalize_mobile, parse_iso_date, MAX_AGE, MIN_AGE};
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use std::collections::BTreeMap;
+use tauri::State;
+
+const ACTIVE: &str = "working_status IN (SELECT code FROM working_statuses WHERE counts_as_active = 1)";
+
+// EPF number, name and two further columns chosen by each check
+type EmployeeRow = (String, String, Option<String>, Option<String>);
+
+type Check = fn(&Connection) -> rusqlite::Result<Vec<DataQualityIssue>>;
+
+// An issue on one employee field; the frontend opens the employee form at
+// entity_id with `field` focused
+fn issue(epf_number: String, name_with_initials: String, field: &str, detail: String) -> DataQualityIssue {
+    DataQualityIssue {
+        epf_number: epf_number.clone(),
+        name_with_initials,
+        field: field.to_string(),
+        detail,
+        entity_type: "EMPLOYEE".to_string(),
+        entity_id: epf_number,
+    }
+}
+
+fn employee_rows(conn: &Connection, sql: &str) -> rusqlite::Result<Vec<EmployeeRow>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+fn invalid_dates(conn: &Connection) -> rusqlite::Result<Vec<DataQualityIssue>> {
+    Ok(invalid_employee_dates(conn)?
+        .into_iter()
+        .map(|d| {
+            let detail = format!("'{}' is not a valid date", d.value);
+            issue(d.epf_number, d.name_with_initials, &d.field, detail)
+        })
+        .collect())
+}
+
+// Dates of birth giving an age outside the working age limits, measured at
+// joining as on the employee form
+fn impossible_ages(conn: &Connection) -> rusqlite::Result<Vec<DataQualityIssue>> {
+    let rows = employee_rows(
+        conn,
+        "SELECT epf_number, name_with_initials, dob, date_of_join FROM employees
+         WHERE date(dob) IS NOT NULL ORDER BY epf_number",
+    )?;
+    let mut issues = Vec::new();
+    for (epf_number, name, dob, date_of_join) in rows {
+        let Some(dob) = dob.as_deref().and_then(parse_iso_date) else { continue };
+        let join = date_of_join.as_deref().and_then(parse_iso_date);
+        if let Some(age) = age_outside_working_range(conn, dob, join) {
+            let detail = format!(
+                "Date of birth gives an age of {} {}; expected between {} and {}",
+                age,
+                if join.is_some() { "at joining" } else { "today" },
+                MIN_AGE,
+                MAX_AGE
+            );
+            issues.push(issue(epf_number, name, "dob", detail));
+        }
+    }
+    Ok(issues)
+}
+
+// Phone numbers shared by more than one employee, compared after
+// normalizing so 077 123 4567 and +94771234567 count as the same number
+fn duplicate_phones(conn: &Connection) -> rusqlite::Result<Vec<DataQualityIssue>> {
+    let rows = employee_rows(
+        conn,
+        "SELECT epf_number, name_with_initials, mobile_1, mobile_2 FROM employees ORDER BY epf_number",
+    )?;
+    let mut by_number: BTreeMap<String, Vec<(String, String, &str)>> = BTreeMap::new();
+    for (epf_number, name, mobile_1, mobile_2) in &rows {
+        for (field, value) in [("mobile_1", mobile_1), ("mobile_2", mobile_2)] {
+            let Some(number) = value.as_deref().and_then(|v| normalize_mobile(v).ok()) else { continue };
+            let holders = by_number.entry(number).or_default();
+            if !holders.iter().any(|(epf, _, _)| epf == epf_number) {
+                holders.push((epf_number.clone(), name.clone(), field));
+            }
+        }
+    }
+    let mut issues = Vec::new();
+    for holders in by_number.values().filter(|h| h.len() > 1) {
+        for (epf_number, name, field) in holders {
+            let others: Vec<&str> =
+                holders.iter().filter(|(epf, _, _)| epf != epf_number).map(|(epf, _, _)| epf.as_str()).collect();
+            let detail = format!(
+                "{} is also the phone number of {}",
+                if *field == "mobile_1" { "Mobile 1" } else { "Mobile 2" },
+                others.join(", ")
+            );
+            issues.push(issue(epf_number.clone(), name.clone(), field, detail));
+        }
+    }
+    Ok(issues)
+}
+
+fn active_with_resign_date(conn: &Connection) -> rusqlite::Result<Vec<DataQualityIssue>> {
+    let rows = employee_rows(
+        conn,
+        &format!(
+            "SELECT epf_number, name_with_initials, working_status, date_of_resign FROM employees
+             WHERE {} AND TRIM(COALESCE(date_of_resign, '')) != '' ORDER BY epf_number",
+            ACTIVE
+        ),
+    )?;
+    Ok(rows
+        .into_iter()
+        .map(|(epf_number, name, status, resigned)| {
+            let detail = format!(
+                "Working status is '{}' but a resignation date of {} is recorded",
+                status.unwrap_or_default(),
+                resigned.unwrap_or_default()
+            );
+            issue(epf_number, name, "date_of_resign", detail)
+        })
+        .collect())
+}
+
+fn missing_departments(conn: &Connection) -> rusqlite::Result<Vec<DataQualityIssue>> {
+    let rows = employee_rows(
+        conn,
+        &format!(
+            "SELECT epf_number, name_with_initials, NULL, NULL FROM employees
+             WHERE {} AND TRIM(COALESCE(department, '')) = '' ORDER BY epf_number",
+            ACTIVE
+        ),
+    )?;
+    Ok(rows
+        .into_iter()
+        .map(|(epf_number, name, _, _)| issue(epf_number, name, "department", "No department recorded".to_string()))
+        .collect())
+}
+
+// Scans employee records for data that needs correcting, grouped by kind of
+// problem. Every category is returned, empty ones included, so the report
+// can show what was checked.
+#[tauri::command]
+pub fn run_data_quality_checks(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<DataQualityCategory>, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(user) if user.permissions.can_edit_employees => {}
+        Some(_) => return Err(HrmError::permission_denied("Permission denied. You cannot edit employees.")),
+        None => return Err(HrmError::not_logged_in()),
+    }
+    drop(user_lock);
+    
+    let conn = db.0.lock()?;
+    let checks: [(&str, &str, Check); 5] = [
+        ("invalid_dates", "Invalid dates", invalid_dates),
+        ("impossible_ages", "Impossible ages", impossible_ages),
+        ("duplicate_phones", "Duplicate phone numbers", duplicate_phones),
+        ("active_with_resign_date", "Active employees with a resignation date", active_with_resign_date),
+        ("missing_departments", "Active employees without a department", missing_departments),
+    ];
+    let mut categories = Vec::new();
+    for (code, label, check) in checks {
+        categories.push(DataQualityCategory {
+            code: code.to_string(),
+            label: label.to_string(),
+            issues: check(&conn)?,
+        });
+    }
+    Ok(categories)
+}
@@ -0,0 +1,346 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{DeductionLine, DeductionRemittance, DeductionType, EmployeeDeduction, UserSession};
+use crate::payroll_commands::ensure_open_from;
+use crate::validation::parse_iso_date;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use tauri::State;
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+fn require_edit(user: &UserSession) -> Result<(), HrmError> {
+    if user.permissions.can_edit_employees {
+        Ok(())
+    } else {
+        Err(HrmError::permission_denied("Permission denied. You cannot edit employees."))
+    }
+}
+
+pub(crate) fn require_month(month: &str) -> Result<(), HrmError> {
+    parse_iso_date(&format!("{}-01", month))
+        .map(|_| ())
+        .ok_or_else(|| HrmError::validation("Month must be in YYYY-MM format"))
+}
+
+// SQL condition for a deduction running at any point in month ?1 (YYYY-MM)
+const ACTIVE_IN_MONTH: &str = "d.start_date <= date(?1 || '-01', '+1 month', '-1 day')
+                               AND (d.end_date IS NULL OR d.end_date >= ?1 || '-01')";
+
+// Every recurring deduction to take from pay in a month, for payroll runs
+pub fn deductions_for_month(conn: &Connection, month: &str) -> Result<Vec<DeductionLine>, HrmError> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT d.epf_number, e.name_with_initials, t.name, d.amount
+             FROM employee_deductions d
+             JOIN deduction_types t ON t.id = d.deduction_type_id
+             LEFT JOIN employees e ON e.epf_number = d.epf_number
+             WHERE {}
+             ORDER BY d.epf_number, t.name",
+            ACTIVE_IN_MONTH
+        ))?;
+    let lines = stmt
+        .query_map([month], |row| {
+            Ok(DeductionLine {
+                epf_number: row.get(0)?,
+                name_with_initials: row.get(1)?,
+                deduction_type: row.get(2)?,
+                amount: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(lines)
+}
+
+// Finds a deduction type by name, creating it if needed. Used by modules that
+// recover money through payroll, such as festival advances.
+pub(crate) fn ensure_deduction_type(conn: &Connection, name: &str, payee: Option<&str>) -> Result<i64, HrmError> {
+    conn.execute(
+        "INSERT OR IGNORE INTO deduction_types (name, payee) VALUES (?1, ?2)",
+        rusqlite::params![name, payee],
+    )?;
+    conn.query_row("SELECT id FROM deduction_types WHERE name = ?1", [name], |row| row.get(0))
+        .map_err(HrmError::from)
+}
+
+pub(crate) fn insert_deduction(conn: &Connection, deduction: &EmployeeDeduction, created_by: &str) -> Result<i64, HrmError> {
+    conn.execute(
+        "INSERT INTO employee_deductions (epf_number, deduction_type_id, amount, start_date, end_date, notes, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![
+            deduction.epf_number,
+            deduction.deduction_type_id,
+            deduction.amount,
+            deduction.start_date,
+            deduction.end_date,
+            deduction.notes,
+            created_by,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+#[tauri::command]
+pub fn get_deduction_types(db: State<'_, DbConnection>) -> Result<Vec<DeductionType>, HrmError> {
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare("SELECT id, name, payee, default_amount, is_active FROM deduction_types ORDER BY name")?;
+    let types = stmt
+        .query_map([], |row| {
+            Ok(DeductionType {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                payee: row.get(2)?,
+                default_amount: row.get(3)?,
+                is_active: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(types)
+}
+
+#[tauri::command]
+pub fn create_deduction_type(
+    deduction_type: DeductionType,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_manage_settings {
+        return Err(HrmError::permission_denied("Permission denied. You cannot change system settings."));
+    }
+    let name = deduction_type.name.trim();
+    if name.is_empty() {
+        return Err(HrmError::validation("Deduction name is required"));
+    }
+    if deduction_type.default_amount.map(|a| a < 0.0).unwrap_or(false) {
+        return Err(HrmError::validation("Default amount cannot be negative"));
+    }
+    
+    let conn = db.0.lock()?;
+    conn.execute(
+        "INSERT INTO deduction_types (name, payee, default_amount) VALUES (?1, ?2, ?3)",
+        rusqlite::params![name, deduction_type.payee, deduction_type.default_amount],
+    )
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE") {
+            format!("A deduction named '{}' already exists", name)
+        } else {
+            e.to_string()
+        }
+    })?;
+    let id = conn.last_insert_rowid();
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "DEDUCTION_TYPE",
+        Some(&id.to_string()),
+        None,
+        Some(name),
+        Some(&format!("Added deduction type: {}", name)),
+    );
+    
+    Ok(id)
+}
+
+fn employee_deduction_from_row(row: &rusqlite::Row) -> rusqlite::Result<EmployeeDeduction> {
+    Ok(EmployeeDeduction {
+        id: row.get(0)?,
+        epf_number: row.get(1)?,
+        deduction_type_id: row.get(2)?,
+        deduction_type: row.get(3)?,
+        amount: row.get(4)?,
+        start_date: row.get(5)?,
+        end_date: row.get(6)?,
+        notes: row.get(7)?,
+    })
+}
+
+const EMPLOYEE_DEDUCTION_COLUMNS: &str = "d.id, d.epf_number, d.deduction_type_id, t.name, d.amount, d.start_date, d.end_date, d.notes";
+
+#[tauri::command]
+pub fn get_employee_deductions(epf_number: String, db: State<'_, DbConnection>) -> Result<Vec<EmployeeDeduction>, HrmError> {
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM employee_deductions d JOIN deduction_types t ON t.id = d.deduction_type_id
+             WHERE d.epf_number = ?1
+             ORDER BY d.end_date IS NOT NULL, d.start_date DESC",
+            EMPLOYEE_DEDUCTION_COLUMNS
+        ))?;
+    let deductions = stmt
+        .query_map([&epf_number], employee_deduction_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(deductions)
+}
+
+#[tauri::command]
+pub fn add_employee_deduction(
+    mut deduction: EmployeeDeduction,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_edit(&user)?;
+    parse_iso_date(&deduction.start_date).ok_or("Start date must be a valid date (YYYY-MM-DD)")?;
+    if let Some(end_date) = deduction.end_date.as_deref() {
+        parse_iso_date(end_date).ok_or("Stop date must be a valid date (YYYY-MM-DD)")?;
+        if end_date < deduction.start_date.as_str() {
+            return Err(HrmError::validation("Stop date cannot be before the start date"));
+        }
+    }
+    
+    let conn = db.0.lock()?;
+    let (type_name, default_amount, is_active): (String, Option<f64>, bool) = conn
+        .query_row(
+            "SELECT name, default_amount, is_active FROM deduction_types WHERE id = ?1",
+            [deduction.deduction_type_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|_| HrmError::not_found("Deduction type not found"))?;
+    ensure_open_from(&conn, &deduction.start_date)?;
+    if !is_active {
+        return Err(HrmError::validation(format!("{} is no longer in use", type_name)));
+    }
+    // A zero amount falls back to the type's standard rate
+    let amount = if deduction.amount > 0.0 { deduction.amount } else { default_amount.unwrap_or(0.0) };
+    if amount <= 0.0 {
+        return Err(HrmError::validation("Amount must be greater than zero"));
+    }
+    
+    let overlapping: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM employee_deductions
+             WHERE epf_number = ?1 AND deduction_type_id = ?2
+               AND (end_date IS NULL OR end_date >= ?3) AND (?4 IS NULL OR start_date <= ?4)",
+            rusqlite::params![deduction.epf_number, deduction.deduction_type_id, deduction.start_date, deduction.end_date],
+            |row| row.get(0),
+        )?;
+    if overlapping {
+        return Err(HrmError::validation(format!("Employee {} already has {} for this period", deduction.epf_number, type_name)));
+    }
+    
+    deduction.amount = amount;
+    let id = insert_deduction(&conn, &deduction, &user.username)?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "DEDUCTION",
+        Some(&deduction.epf_number),
+        None,
+        Some(&format!("{} {:.2}", type_name, amount)),
+        Some(&format!("Started {} of {:.2} for employee {} from {}", type_name, amount, deduction.epf_number, deduction.start_date)),
+    );
+    
+    Ok(id)
+}
+
+// Stops a running deduction from the given date (e.g. when leaving the union)
+#[tauri::command]
+pub fn stop_employee_deduction(
+    deduction_id: i64,
+    end_date: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_edit(&user)?;
+    parse_iso_date(&end_date).ok_or("Stop date must be a valid date (YYYY-MM-DD)")?;
+    
+    let conn = db.0.lock()?;
+    let deduction = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM employee_deductions d JOIN deduction_types t ON t.id = d.deduction_type_id WHERE d.id = ?1",
+                EMPLOYEE_DEDUCTION_COLUMNS
+            ),
+            [deduction_id],
+            employee_deduction_from_row,
+        )
+        .map_err(|_| HrmError::not_found("Deduction not found"))?;
+    if end_date < deduction.start_date {
+        return Err(HrmError::validation("Stop date cannot be before the start date"));
+    }
+    ensure_open_from(&conn, &end_date)?;
+    
+    conn.execute(
+        "UPDATE employee_deductions SET end_date = ?1 WHERE id = ?2",
+        rusqlite::params![end_date, deduction_id],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "DEDUCTION",
+        Some(&deduction.epf_number),
+        deduction.end_date.as_deref(),
+        Some(&end_date),
+        Some(&format!("Stopped {} for employee {} from {}", deduction.deduction_type, deduction.epf_number, end_date)),
+    );
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_monthly_deductions(
+    month: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<DeductionLine>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_reports {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view reports."));
+    }
+    require_month(&month)?;
+    
+    let conn = db.0.lock()?;
+    deductions_for_month(&conn, &month)
+}
+
+// Amount to remit to each society or union for a month
+#[tauri::command]
+pub fn get_deduction_remittance_summary(
+    month: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<DeductionRemittance>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_reports {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view reports."));
+    }
+    require_month(&month)?;
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT t.name, t.payee, COUNT(DISTINCT d.epf_number), SUM(d.amount)
+             FROM employee_deductions d JOIN deduction_types t ON t.id = d.deduction_type_id
+             WHERE {}
+             GROUP BY t.id
+             ORDER BY t.name",
+            ACTIVE_IN_MONTH
+        ))?;
+    let summary = stmt
+        .query_map([&month], |row| {
+            Ok(DeductionRemittance {
+                month: month.clone(),
+                deduction_type: row.get(0)?,
+                payee: row.get(1)?,
+                employee_count: row.get(2)?,
+                total_amount: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(summary)
+}
@@ -0,0 +1,242 @@
+use crate::commands::{format_file_size, log_audit_action};
+use crate::error::HrmError;
+use crate::logging::log_files;
+use crate::models::{HealthCheck, HealthReport, UserSession};
+use crate::settings_commands::{get_setting, get_setting_i64};
+use crate::validation::invalid_employee_dates;
+use crate::{AppDataDir, CurrentUser, DbConnection, SCHEMA_VERSION};
+use rusqlite::Connection;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use sysinfo::Disks;
+use tauri::{AppHandle, Manager, State};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+// PRAGMA values included in the support bundle
+const BUNDLE_PRAGMAS: [&str; 8] = [
+    "user_version",
+    "journal_mode",
+    "synchronous",
+    "foreign_keys",
+    "auto_vacuum",
+    "page_size",
+    "page_count",
+    "freelist_count",
+];
+
+fn require_settings_permission(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_manage_settings => Ok(session.clone()),
+        Some(_) => Err(HrmError::permission_denied("Permission denied. You cannot view diagnostics.")),
+        None => Err(HrmError::not_logged_in()),
+    }
+}
+
+fn pragma_values(conn: &Connection) -> serde_json::Map<String, serde_json::Value> {
+    BUNDLE_PRAGMAS
+        .iter()
+        .map(|pragma| {
+            let value = conn
+                .query_row(&format!("PRAGMA {}", pragma), [], |row| row.get::<_, rusqlite::types::Value>(0))
+                .map(|v| match v {
+                    rusqlite::types::Value::Integer(n) => serde_json::json!(n),
+                    rusqlite::types::Value::Text(t) => serde_json::json!(t),
+                    _ => serde_json::Value::Null,
+                })
+                .unwrap_or(serde_json::Value::Null);
+            (pragma.to_string(), value)
+        })
+        .collect()
+}
+
+// Number of rows in every table. Only counts are read, never row contents.
+pub(crate) fn table_row_counts(conn: &Connection) -> Result<serde_json::Map<String, serde_json::Value>, HrmError> {
+    let mut stmt = conn.prepare(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+    )?;
+    let tables = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut counts = serde_json::Map::new();
+    for table in tables {
+        let count: i64 = conn.query_row(&format!("SELECT COUNT(*) FROM \"{}\"", table), [], |row| row.get(0))?;
+        counts.insert(table, serde_json::json!(count));
+    }
+    Ok(counts)
+}
+
+// Writes a zip for remote support of a factory install: app and OS details,
+// database pragmas, row counts per table and the retained log files. No
+// employee or user records are included. Returns the number of log files.
+#[tauri::command]
+pub fn export_support_bundle(
+    destination_path: String,
+    app_handle: AppHandle,
+    app_data_dir: State<'_, AppDataDir>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<usize, HrmError> {
+    let user = require_settings_permission(&current_user)?;
+    let conn = db.0.lock()?;
+    
+    let db_size = fs::metadata(app_data_dir.0.join("hrm_system.db")).map(|m| m.len()).unwrap_or(0);
+    let sqlite_version: String = conn.query_row("SELECT sqlite_version()", [], |row| row.get(0))?;
+    let system = serde_json::json!({
+        "app_version": app_handle.package_info().version.to_string(),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "sqlite_version": sqlite_version,
+        "database_size_bytes": db_size,
+        "pragmas": pragma_values(&conn),
+    });
+    let row_counts = serde_json::Value::Object(table_row_counts(&conn)?);
+    let logs = log_files(&app_data_dir.0);
+    
+    let zip_error = |e: zip::result::ZipError| HrmError::io(format!("Failed to write support bundle: {}", e));
+    let file = File::create(&destination_path).map_err(|e| HrmError::io(format!("Failed to save file: {}", e)))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    
+    zip.start_file("system.json", options).map_err(zip_error)?;
+    zip.write_all(serde_json::to_string_pretty(&system)?.as_bytes())?;
+    zip.start_file("row_counts.json", options).map_err(zip_error)?;
+    zip.write_all(serde_json::to_string_pretty(&row_counts)?.as_bytes())?;
+    for path in &logs {
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        zip.start_file(format!("logs/{}", name), options).map_err(zip_error)?;
+        zip.write_all(&fs::read(path)?)?;
+    }
+    zip.finish().map_err(zip_error)?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "EXPORT",
+        "SUPPORT_BUNDLE",
+        None,
+        None,
+        None,
+        Some(&format!("Exported support bundle with {} log file(s)", logs.len())),
+    );
+    
+    Ok(logs.len())
+}
+
+// Free space below these levels is reported as a warning / an error
+const LOW_DISK_WARNING_BYTES: u64 = 1024 * 1024 * 1024;
+const LOW_DISK_ERROR_BYTES: u64 = 100 * 1024 * 1024;
+const DEFAULT_BACKUP_WARNING_DAYS: i64 = 7;
+
+fn check(name: &str, status: &str, message: impl Into<String>) -> HealthCheck {
+    HealthCheck { name: name.to_string(), status: status.to_string(), message: message.into() }
+}
+
+fn database_checks(conn: &Connection) -> Vec<HealthCheck> {
+    let mut checks = Vec::new();
+    match conn.query_row("PRAGMA quick_check", [], |row| row.get::<_, String>(0)) {
+        Ok(result) if result == "ok" => checks.push(check("database", "ok", "Database opened and passed a quick check")),
+        Ok(result) => checks.push(check("database", "error", format!("Database is damaged: {}", result))),
+        Err(e) => checks.push(check("database", "error", format!("Database could not be read: {}", e))),
+    }
+    
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap_or(0);
+    checks.push(if version > SCHEMA_VERSION {
+        check(
+            "schema_version",
+            "error",
+            format!("Database schema {} is newer than this app supports ({}); install the latest version", version, SCHEMA_VERSION),
+        )
+    } else {
+        check("schema_version", "ok", format!("Database schema version {}", version))
+    });
+    checks.push(if version < SCHEMA_VERSION {
+        check(
+            "migrations",
+            "error",
+            format!("{} database migration(s) did not complete; restart the app", SCHEMA_VERSION - version),
+        )
+    } else {
+        check("migrations", "ok", "No pending migrations")
+    });
+    let invalid_dates = invalid_employee_dates(conn).map(|d| d.len()).unwrap_or(0);
+    if invalid_dates > 0 {
+        checks.push(check(
+            "employee_dates",
+            "warning",
+            format!("{} employee date(s) could not be read; correct them on the employee form", invalid_dates),
+        ));
+    }
+    
+    let warning_days = get_setting_i64(conn, "backup_warning_days").unwrap_or(DEFAULT_BACKUP_WARNING_DAYS);
+    let backup_age: Option<i64> = get_setting(conn, "last_backup_at").and_then(|at| {
+        conn.query_row("SELECT CAST(julianday('now', 'localtime') - julianday(?1) AS INTEGER)", [&at], |row| row.get(0))
+            .ok()
+    });
+    checks.push(match backup_age {
+        None => check("backup", "warning", "No database backup has been taken"),
+        Some(days) if warning_days > 0 && days > warning_days => {
+            check("backup", "warning", format!("Last backup was {} days ago", days))
+        }
+        Some(days) => check("backup", "ok", format!("Last backup was {} day(s) ago", days)),
+    });
+    checks
+}
+
+// Free space on the disk holding the app data directory
+fn available_space(app_dir: &Path) -> Option<u64> {
+    let app_dir = app_dir.canonicalize().ok()?;
+    Disks::new_with_refreshed_list()
+        .iter()
+        .filter(|disk| app_dir.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+fn disk_space_check(app_dir: &Path) -> HealthCheck {
+    match available_space(app_dir) {
+        None => check("disk_space", "warning", "Free disk space could not be determined"),
+        Some(bytes) => {
+            let free = format_file_size(bytes);
+            if bytes < LOW_DISK_ERROR_BYTES {
+                check("disk_space", "error", format!("Only {} free; free up disk space before continuing", free))
+            } else if bytes < LOW_DISK_WARNING_BYTES {
+                check("disk_space", "warning", format!("Disk space is low ({} free)", free))
+            } else {
+                check("disk_space", "ok", format!("{} free", free))
+            }
+        }
+    }
+}
+
+fn write_access_check(app_dir: &Path) -> HealthCheck {
+    let probe = app_dir.join(".write_probe");
+    match fs::write(&probe, b"ok").and_then(|_| fs::remove_file(&probe)) {
+        Ok(()) => check("write_access", "ok", "App data folder is writable"),
+        Err(e) => check("write_access", "error", format!("Cannot write to {}: {}", app_dir.display(), e)),
+    }
+}
+
+// Checks run before login so problems are reported before anyone starts
+// entering data. Needs no login.
+#[tauri::command]
+pub fn run_health_check(
+    app_data_dir: State<'_, AppDataDir>,
+    db: State<'_, DbConnection>,
+) -> Result<HealthReport, HrmError> {
+    let mut checks = match db.0.lock() {
+        Ok(conn) => database_checks(&conn),
+        Err(e) => vec![check("database", "error", format!("Database is unavailable: {}", e))],
+    };
+    checks.push(disk_space_check(&app_data_dir.0));
+    checks.push(write_access_check(&app_data_dir.0));
+    
+    let can_proceed = checks.iter().all(|c| c.status != "error");
+    for failed in checks.iter().filter(|c| c.status != "ok") {
+        tracing::warn!("Health check {} {}: {}", failed.name, failed.status, failed.message);
+    }
+    Ok(HealthReport { checks, can_proceed })
+}
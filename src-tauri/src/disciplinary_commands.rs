@@ -0,0 +1,231 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{DisciplinaryAction, DisciplinarySummary, UserSession};
+use crate::validation::parse_iso_date;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use tauri::State;
+
+// Escalation ladder, mildest first, with how many months each warning stays
+// on record (None never lapses)
+const ACTION_TYPES: [(&str, Option<i32>); 4] = [
+    ("verbal_warning", Some(6)),
+    ("written_warning", Some(12)),
+    ("final_warning", Some(12)),
+    ("suspension", None),
+];
+
+fn require_disciplinary_permission(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_manage_disciplinary => Ok(session.clone()),
+        Some(_) => Err(HrmError::permission_denied("Permission denied. You cannot access disciplinary records.")),
+        None => Err(HrmError::not_logged_in()),
+    }
+}
+
+fn escalation_level(action_type: &str) -> Option<usize> {
+    ACTION_TYPES.iter().position(|(t, _)| *t == action_type)
+}
+
+fn validate_action(action: &DisciplinaryAction) -> Result<(), HrmError> {
+    if escalation_level(&action.action_type).is_none() {
+        return Err(HrmError::validation("Action type must be verbal_warning, written_warning, final_warning or suspension"));
+    }
+    parse_iso_date(&action.action_date).ok_or("Action date must be a valid date (YYYY-MM-DD)")?;
+    if action.description.trim().is_empty() {
+        return Err(HrmError::validation("Description is required"));
+    }
+    Ok(())
+}
+
+const ACTION_COLUMNS: &str = "id, epf_number, action_date, action_type, description, document_path,
+                              expires_on, is_withdrawn, recorded_by, created_at,
+                              (NOT is_withdrawn AND (expires_on IS NULL OR expires_on >= date('now', 'localtime')))";
+
+fn action_from_row(row: &rusqlite::Row) -> rusqlite::Result<DisciplinaryAction> {
+    Ok(DisciplinaryAction {
+        id: row.get(0)?,
+        epf_number: row.get(1)?,
+        action_date: row.get(2)?,
+        action_type: row.get(3)?,
+        description: row.get(4)?,
+        document_path: row.get(5)?,
+        expires_on: row.get(6)?,
+        is_withdrawn: row.get(7)?,
+        recorded_by: row.get(8)?,
+        created_at: row.get(9)?,
+        is_active: row.get(10)?,
+    })
+}
+
+pub fn load_actions(conn: &Connection, epf_number: &str) -> rusqlite::Result<Vec<DisciplinaryAction>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM disciplinary_actions WHERE epf_number = ?1 ORDER BY action_date DESC, id DESC",
+        ACTION_COLUMNS
+    ))?;
+    let actions = stmt
+        .query_map([epf_number], action_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(actions)
+}
+
+fn query_action(conn: &Connection, action_id: i64) -> Result<DisciplinaryAction, HrmError> {
+    conn.query_row(
+        &format!("SELECT {} FROM disciplinary_actions WHERE id = ?1", ACTION_COLUMNS),
+        [action_id],
+        action_from_row,
+    )
+    .map_err(|_| HrmError::not_found("Disciplinary action not found"))
+}
+
+#[tauri::command]
+pub fn get_disciplinary_actions(
+    epf_number: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<DisciplinaryAction>, HrmError> {
+    require_disciplinary_permission(&current_user)?;
+    let conn = db.0.lock()?;
+    load_actions(&conn, &epf_number).map_err(HrmError::from)
+}
+
+// Active (unexpired, not withdrawn) warnings and the next step on the ladder
+#[tauri::command]
+pub fn get_disciplinary_summary(
+    epf_number: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<DisciplinarySummary, HrmError> {
+    require_disciplinary_permission(&current_user)?;
+    let conn = db.0.lock()?;
+    let actions = load_actions(&conn, &epf_number)?;
+    
+    let active: Vec<&DisciplinaryAction> = actions.iter().filter(|a| a.is_active).collect();
+    let highest = active.iter().filter_map(|a| escalation_level(&a.action_type)).max();
+    let next = match highest {
+        None => 0,
+        Some(level) => (level + 1).min(ACTION_TYPES.len() - 1),
+    };
+    
+    Ok(DisciplinarySummary {
+        epf_number,
+        active_warnings: active.iter().filter(|a| a.action_type.ends_with("_warning")).count() as i32,
+        total_actions: actions.len() as i32,
+        highest_active_action: highest.map(|level| ACTION_TYPES[level].0.to_string()),
+        suggested_next_action: ACTION_TYPES[next].0.to_string(),
+    })
+}
+
+#[tauri::command]
+pub fn add_disciplinary_action(
+    action: DisciplinaryAction,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = require_disciplinary_permission(&current_user)?;
+    validate_action(&action)?;
+    
+    let conn = db.0.lock()?;
+    let validity_months = ACTION_TYPES
+        .iter()
+        .find(|(t, _)| *t == action.action_type)
+        .and_then(|(_, months)| *months);
+    conn.execute(
+        "INSERT INTO disciplinary_actions (epf_number, action_date, action_type, description, document_path, expires_on, recorded_by)
+         VALUES (?1, ?2, ?3, ?4, ?5,
+                 CASE WHEN ?6 IS NULL THEN NULL ELSE date(?2, '+' || ?6 || ' months') END, ?7)",
+        rusqlite::params![
+            action.epf_number,
+            action.action_date,
+            action.action_type,
+            action.description.trim(),
+            action.document_path,
+            validity_months,
+            user.username,
+        ],
+    )?;
+    let id = conn.last_insert_rowid();
+    
+    let new_value = serde_json::to_string(&action).ok();
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "DISCIPLINARY",
+        Some(&action.epf_number),
+        None,
+        new_value.as_deref(),
+        Some(&format!("Recorded {} for employee {}", action.action_type.replace('_', " "), action.epf_number)),
+    );
+    
+    Ok(id)
+}
+
+// Edits the description or document, or withdraws the action (e.g. after an
+// appeal). Type and date are fixed once recorded.
+#[tauri::command]
+pub fn update_disciplinary_action(
+    action_id: i64,
+    description: String,
+    document_path: Option<String>,
+    is_withdrawn: bool,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = require_disciplinary_permission(&current_user)?;
+    if description.trim().is_empty() {
+        return Err(HrmError::validation("Description is required"));
+    }
+    
+    let conn = db.0.lock()?;
+    let old = query_action(&conn, action_id)?;
+    conn.execute(
+        "UPDATE disciplinary_actions SET description = ?1, document_path = ?2, is_withdrawn = ?3 WHERE id = ?4",
+        rusqlite::params![description.trim(), document_path, is_withdrawn, action_id],
+    )?;
+    
+    let old_value = serde_json::to_string(&old).ok();
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        if is_withdrawn && !old.is_withdrawn { "WITHDRAW" } else { "UPDATE" },
+        "DISCIPLINARY",
+        Some(&old.epf_number),
+        old_value.as_deref(),
+        Some(description.trim()),
+        Some(&format!("Updated disciplinary action #{} for employee {}", action_id, old.epf_number)),
+    );
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_disciplinary_action(
+    action_id: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = require_disciplinary_permission(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let old = query_action(&conn, action_id)?;
+    conn.execute("DELETE FROM disciplinary_actions WHERE id = ?1", [action_id])?;
+    
+    let old_value = serde_json::to_string(&old).ok();
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "DELETE",
+        "DISCIPLINARY",
+        Some(&old.epf_number),
+        old_value.as_deref(),
+        None,
+        Some(&format!("Deleted disciplinary action #{} for employee {}", action_id, old.epf_number)),
+    );
+    
+    Ok(())
+}
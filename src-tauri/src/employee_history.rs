@@ -0,0 +1,86 @@
+use crate::disciplinary_commands::load_actions;
+use crate::epf_numbering::epf_numbers_of;
+use crate::error::HrmError;
+use crate::models::HistoryEvent;
+use crate::{CurrentUser, DbConnection};
+use tauri::State;
+
+// One timeline per employee built from the audit trail and HR records.
+// Disciplinary entries only appear for users allowed to see them.
+#[tauri::command]
+pub fn get_employee_history(
+    epf_number: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<HistoryEvent>, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    let permissions = match &*user_lock {
+        Some(session) if session.permissions.can_view_employees => session.permissions.clone(),
+        Some(_) => return Err(HrmError::permission_denied("Permission denied")),
+        None => return Err(HrmError::not_logged_in()),
+    };
+    drop(user_lock);
+    
+    let conn = db.0.lock()?;
+    let mut events = Vec::new();
+    
+    // Audit entries stay under the number the employee had at the time
+    let mut stmt = conn
+        .prepare(
+            "SELECT created_at, action, username, details FROM audit_logs
+             WHERE entity_type = 'EMPLOYEE' AND entity_id = ?1 AND action != 'VIEW'
+             ORDER BY created_at DESC",
+        )?;
+    for number in epf_numbers_of(&conn, &epf_number)? {
+        let audit_events = stmt
+            .query_map([&number], |row| {
+                Ok(HistoryEvent {
+                    date: row.get::<_, Option<String>>(0)?.unwrap_or_default(),
+                    kind: "record".to_string(),
+                    title: row.get(1)?,
+                    details: row.get(3)?,
+                    recorded_by: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        events.extend(audit_events);
+    }
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT i.incident_date, i.severity, i.location, i.description, i.recorded_by
+             FROM incidents i JOIN incident_injuries j ON j.incident_id = i.id
+             WHERE j.epf_number = ?1",
+        )?;
+    let incident_events = stmt
+        .query_map([&epf_number], |row| {
+            Ok(HistoryEvent {
+                date: row.get(0)?,
+                kind: "incident".to_string(),
+                title: format!("{} incident at {}", row.get::<_, String>(1)?, row.get::<_, String>(2)?),
+                details: row.get(3)?,
+                recorded_by: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    events.extend(incident_events);
+    
+    if permissions.can_manage_disciplinary {
+        for action in load_actions(&conn, &epf_number)? {
+            let mut title = action.action_type.replace('_', " ");
+            if action.is_withdrawn {
+                title.push_str(" (withdrawn)");
+            }
+            events.push(HistoryEvent {
+                date: action.action_date,
+                kind: "disciplinary".to_string(),
+                title,
+                details: Some(action.description),
+                recorded_by: action.recorded_by,
+            });
+        }
+    }
+    
+    events.sort_by(|a, b| b.date.cmp(&a.date));
+    Ok(events)
+}
@@ -0,0 +1,284 @@
+use crate::commands::{log_audit_action, query_employee};
+use crate::epf_numbering::epf_numbers_of;
+use crate::error::HrmError;
+use crate::export_commands::{csv_field, html_table, printable_page, DEFAULT_COMPANY_NAME};
+use crate::localization::{employee_language, translate};
+use crate::models::{Employee, PayrollLine};
+use crate::payroll_commands::load_payroll_run;
+use crate::settings_commands::{date_display, get_setting};
+use crate::validation::format_date;
+use crate::{AppDataDir, CurrentUser, DbConnection};
+use base64::{engine::general_purpose, Engine as _};
+use rusqlite::Connection;
+use std::fs::{self, File};
+use std::io::Write;
+use tauri::State;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+fn money(value: f64) -> String {
+    format!("{:.2}", value)
+}
+
+fn csv_text(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut csv = String::from("\u{FEFF}");
+    let header_fields: Vec<String> = headers.iter().map(|h| csv_field(h)).collect();
+    csv.push_str(&header_fields.join(","));
+    csv.push_str("\r\n");
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|v| csv_field(v)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+fn profile_rows(employee: &Employee, show_date: &dyn Fn(&str) -> String) -> Vec<Vec<String>> {
+    let value = |v: &Option<String>| v.clone().unwrap_or_default();
+    let date = |v: &Option<String>| v.as_deref().map(show_date).unwrap_or_default();
+    let mut rows = vec![
+        vec!["EPF Number".to_string(), employee.epf_number.clone()],
+        vec!["Name with Initials".to_string(), employee.name_with_initials.clone()],
+        vec!["Full Name".to_string(), employee.full_name.clone()],
+        vec!["NIC".to_string(), value(&employee.nic)],
+        vec!["Date of Birth".to_string(), date(&employee.dob)],
+        vec!["Address".to_string(), value(&employee.address)],
+        vec!["District".to_string(), value(&employee.district)],
+        vec!["DS Division".to_string(), value(&employee.ds_division)],
+        vec!["GN Division".to_string(), value(&employee.gn_division)],
+        vec!["Mobile".to_string(), value(&employee.mobile_1)],
+        vec!["Mobile 2".to_string(), value(&employee.mobile_2)],
+        vec!["Police Area".to_string(), value(&employee.police_area)],
+        vec!["Transport Route".to_string(), value(&employee.transport_route)],
+        vec!["Marital Status".to_string(), value(&employee.marital_status)],
+        vec!["Date of Join".to_string(), date(&employee.date_of_join)],
+        vec!["Date of Resign".to_string(), date(&employee.date_of_resign)],
+        vec!["Working Status".to_string(), employee.working_status.clone()],
+        vec!["Cader".to_string(), value(&employee.cader)],
+        vec!["Designation".to_string(), value(&employee.designation)],
+        vec!["Allocation".to_string(), value(&employee.allocation)],
+        vec!["Department".to_string(), value(&employee.department)],
+    ];
+    let mut custom: Vec<_> = employee.custom_fields.iter().collect();
+    custom.sort();
+    rows.extend(custom.into_iter().map(|(name, value)| vec![name.clone(), value.clone()]));
+    rows
+}
+
+// Earnings, deductions and net pay for one month
+fn payslip_rows(line: &PayrollLine) -> Vec<Vec<String>> {
+    let mut rows = vec![
+        vec!["Basic salary".to_string(), money(line.basic_salary)],
+        vec![format!("No-pay ({} days)", line.no_pay_days), money(-line.no_pay_deduction)],
+        vec![format!("Overtime ({} hours)", line.ot_hours), money(line.ot_pay)],
+        vec!["Production incentive".to_string(), money(line.incentive_pay)],
+        vec!["Gross pay".to_string(), money(line.gross_pay)],
+        vec!["EPF (8%)".to_string(), money(-line.epf_employee)],
+    ];
+    rows.extend(line.deductions.iter().map(|d| vec![d.deduction_type.clone(), money(-d.amount)]));
+    rows
+}
+
+// The employee's line from each locked payroll run in the year, by month
+fn payroll_lines_for_year(conn: &Connection, epf_number: &str, year: i32) -> Result<Vec<(String, PayrollLine)>, HrmError> {
+    let run_ids = conn
+        .prepare("SELECT id FROM payroll_runs WHERE status = 'locked' AND substr(month, 1, 4) = ?1 ORDER BY month")?
+        .query_map([format!("{:04}", year)], |row| row.get::<_, i64>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    // Lines keep the EPF number the employee was paid under
+    let numbers = epf_numbers_of(conn, epf_number)?;
+    let mut lines = Vec::new();
+    for run_id in run_ids {
+        let run = load_payroll_run(conn, run_id)?;
+        if let Some(line) = run.lines.into_iter().find(|l| numbers.contains(&l.epf_number)) {
+            lines.push((run.month, line));
+        }
+    }
+    Ok(lines)
+}
+
+fn leave_rows(
+    conn: &Connection,
+    epf_number: &str,
+    year: i32,
+    show_date: &dyn Fn(&str) -> String,
+) -> Result<Vec<Vec<String>>, HrmError> {
+    let mut stmt = conn.prepare(
+        "SELECT leave_date, leave_type, days, reason FROM leave_records
+         WHERE epf_number = ?1 AND strftime('%Y', leave_date) = ?2 ORDER BY leave_date",
+    )?;
+    let rows = stmt
+        .query_map(rusqlite::params![epf_number, format!("{:04}", year)], |row| {
+            Ok(vec![
+                show_date(&row.get::<_, String>(0)?),
+                row.get::<_, String>(1)?,
+                row.get::<_, f64>(2)?.to_string(),
+                row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+            ])
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+// Bundles an employee's records for a year for handover when they ask for
+// them: profile, photo, payslips from locked payroll runs, leave history and
+// EPF/ETF contributions. Payslips and contributions are left out for users
+// who cannot view salaries. `format` is "zip" for separate files or "html" for
+// a single printable document. Headings follow the employee's preferred language.
+#[tauri::command]
+pub fn generate_employee_pack(
+    epf_number: String,
+    year: i32,
+    format: String,
+    destination_path: String,
+    db: State<'_, DbConnection>,
+    app_data_dir: State<'_, AppDataDir>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user_lock = current_user.0.lock()?;
+    let user = match &*user_lock {
+        Some(session) if session.permissions.can_view_sensitive_data && session.permissions.can_export_data => session.clone(),
+        Some(_) => return Err(HrmError::permission_denied("Permission denied. You cannot export employee records.")),
+        None => return Err(HrmError::not_logged_in()),
+    };
+    drop(user_lock);
+    if !["zip", "html"].contains(&format.as_str()) {
+        return Err(HrmError::validation(format!("Unsupported pack format: {}", format)));
+    }
+    
+    let conn = db.0.lock()?;
+    let employee = query_employee(&conn, &epf_number).map_err(|_| HrmError::not_found("Employee not found"))?;
+    if !user.can_access_department(employee.department.as_deref()) {
+        return Err(HrmError::permission_denied("Permission denied. You cannot export employees in this department."));
+    }
+    let language = employee_language(&conn, &epf_number);
+    let heading = |key: &str, default: &str| translate(&conn, &language, key, default);
+    let company = heading(
+        "company_name",
+        &get_setting(&conn, "company_name").unwrap_or_else(|| DEFAULT_COMPANY_NAME.to_string()),
+    );
+    let payslip_title = heading("pack.payslip", "Payslip");
+    let photo = employee
+        .image_path
+        .as_ref()
+        .and_then(|path| fs::read(app_data_dir.0.join(path)).ok().map(|bytes| (path.clone(), bytes)));
+    let show_pay = user.permissions.can_view_salaries;
+    let payslips = if show_pay { payroll_lines_for_year(&conn, &epf_number, year)? } else { Vec::new() };
+    let (date_format, buddhist_era) = date_display(&conn);
+    let show_date = |value: &str| format_date(value, &date_format, buddhist_era);
+    let leave = leave_rows(&conn, &epf_number, year, &show_date)?;
+    
+    let profile_headers = ["Field", "Value"];
+    let payslip_headers = ["Item", "Amount"];
+    let leave_headers = ["Date", "Type", "Days", "Reason"];
+    let epf_headers = ["Month", "Earnings", "Employee EPF (8%)", "Employer EPF (12%)", "ETF (3%)"];
+    let epf_rows: Vec<Vec<String>> = payslips
+        .iter()
+        .map(|(month, line)| {
+            vec![
+                month.clone(),
+                money(line.basic_salary - line.no_pay_deduction + line.incentive_pay),
+                money(line.epf_employee),
+                money(line.epf_employer),
+                money(line.etf_employer),
+            ]
+        })
+        .collect();
+    let epf_totals = vec![
+        "Total".to_string(),
+        money(payslips.iter().map(|(_, l)| l.basic_salary - l.no_pay_deduction + l.incentive_pay).sum()),
+        money(payslips.iter().map(|(_, l)| l.epf_employee).sum()),
+        money(payslips.iter().map(|(_, l)| l.epf_employer).sum()),
+        money(payslips.iter().map(|(_, l)| l.etf_employer).sum()),
+    ];
+    let details = vec![format!("{} ({})", employee.full_name, employee.epf_number), format!("{} {}", heading("pack.records_for", "Records for"), year)];
+    
+    if format == "html" {
+        let mut sections = Vec::new();
+        if let Some((path, bytes)) = &photo {
+            let mime = match path.rsplit_once('.').map(|(_, ext)| ext) {
+                Some("webp") => "image/webp",
+                Some("png") => "image/png",
+                _ => "image/jpeg",
+            };
+            sections.push(format!(
+                "<img class=\"photo\" src=\"data:{};base64,{}\" alt=\"Photo\">\n",
+                mime,
+                general_purpose::STANDARD.encode(bytes)
+            ));
+        }
+        sections.push(format!("<h2>{}</h2>\n", heading("pack.profile", "Profile")));
+        sections.push(html_table(&profile_headers, &[], &profile_rows(&employee, &show_date), None));
+        for (month, line) in &payslips {
+            sections.push(format!("<h2 class=\"page-break\">{} - {}</h2>\n", payslip_title, month));
+            let net = [String::from("Net pay"), money(line.net_pay)];
+            sections.push(html_table(&payslip_headers, &[1], &payslip_rows(line), Some(&net)));
+        }
+        sections.push(format!("<h2 class=\"page-break\">{}</h2>\n", heading("pack.leave_history", "Leave History")));
+        sections.push(html_table(&leave_headers, &[2], &leave, None));
+        if show_pay {
+            sections.push(format!("<h2>{}</h2>\n", heading("pack.epf_contributions", "EPF and ETF Contributions")));
+            sections.push(html_table(&epf_headers, &[1, 2, 3, 4], &epf_rows, Some(&epf_totals)));
+        }
+        let html = printable_page(&company, &heading("pack.title", "Employee Records"), &details, &sections);
+        fs::write(&destination_path, html).map_err(|e| HrmError::io(format!("Failed to save file: {}", e)))?;
+    } else {
+        let zip_error = |e: zip::result::ZipError| HrmError::io(format!("Failed to write employee pack: {}", e));
+        let file = File::create(&destination_path).map_err(|e| HrmError::io(format!("Failed to save file: {}", e)))?;
+        let mut zip = ZipWriter::new(file);
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    
+        let profile = printable_page(
+            &company,
+            &heading("pack.profile_title", "Employee Profile"),
+            &details,
+            &[html_table(&profile_headers, &[], &profile_rows(&employee, &show_date), None)],
+        );
+        zip.start_file("profile.html", options).map_err(zip_error)?;
+        zip.write_all(profile.as_bytes())?;
+        if let Some((path, bytes)) = &photo {
+            let extension = path.rsplit_once('.').map(|(_, ext)| ext).unwrap_or("jpg");
+            zip.start_file(format!("photo.{}", extension), options).map_err(zip_error)?;
+            zip.write_all(bytes)?;
+        }
+        for (month, line) in &payslips {
+            let net = [String::from("Net pay"), money(line.net_pay)];
+            let payslip = printable_page(
+                &company,
+                &format!("{} - {}", payslip_title, month),
+                &details[..1],
+                &[html_table(&payslip_headers, &[1], &payslip_rows(line), Some(&net))],
+            );
+            zip.start_file(format!("payslips/{}.html", month), options).map_err(zip_error)?;
+            zip.write_all(payslip.as_bytes())?;
+        }
+        zip.start_file("leave_history.csv", options).map_err(zip_error)?;
+        zip.write_all(csv_text(&leave_headers, &leave).as_bytes())?;
+        if show_pay {
+            let mut epf_csv_rows = epf_rows;
+            epf_csv_rows.push(epf_totals);
+            zip.start_file("epf_contributions.csv", options).map_err(zip_error)?;
+            zip.write_all(csv_text(&epf_headers, &epf_csv_rows).as_bytes())?;
+        }
+        zip.finish().map_err(zip_error)?;
+    }
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "EXPORT",
+        "EMPLOYEE",
+        Some(&epf_number),
+        None,
+        Some(&format),
+        Some(&format!(
+            "Generated records pack for {} ({}, {} payslips)",
+            epf_number,
+            year,
+            payslips.len()
+        )),
+    );
+    
+    Ok(())
+}
@@ -0,0 +1,194 @@
+use crate::commands::log_audit_action;
+use crate::custom_fields;
+use crate::employment_periods::sync_current_period;
+use crate::error::HrmError;
+use crate::org_chart_commands::prompt_reassignment;
+use crate::tag_commands::delete_employee_tags;
+use crate::validation::parse_iso_date;
+use crate::{CurrentUser, DbConnection};
+use chrono::Local;
+use rusqlite::{Connection, OptionalExtension};
+use std::fs;
+use std::path::Path;
+use tauri::State;
+
+// What happens to an employee's rows elsewhere when the employee is deleted.
+// Pay, attendance, leave and case history must be kept, so those rows block
+// the deletion and the employee is archived instead. Deletion is for records
+// entered by mistake, which have none of these.
+const BLOCKING_TABLES: [(&str, &str); 19] = [
+    ("payroll_lines", "payroll history"),
+    ("festival_advances", "festival advance records"),
+    ("salary_revisions", "salary history"),
+    ("employee_deductions", "payroll deductions"),
+    ("production_entries", "production records"),
+    ("meal_records", "meal records"),
+    ("attendance_punches", "attendance records"),
+    ("quarantined_punches", "attendance punches held for a locked month"),
+    ("attendance_corrections", "attendance corrections"),
+    ("leave_records", "leave records"),
+    ("leave_requests", "leave requests"),
+    ("overtime_requests", "overtime requests"),
+    ("disciplinary_actions", "disciplinary records"),
+    ("grievances", "grievances"),
+    ("incident_injuries", "incident records"),
+    ("letter_issues", "issued letters"),
+    ("asset_issues", "asset issue records"),
+    ("employee_transfers", "transfer records"),
+    ("insurance_enrollments", "insurance enrollments"),
+];
+
+// Rows that only describe the employee go with them
+const CASCADE_TABLES: [&str; 15] = [
+    "image_metadata",
+    "employee_notes",
+    "employee_qualifications",
+    "employee_skills",
+    "training_attendance",
+    "exit_interviews",
+    "employment_periods",
+    "successors",
+    "employee_consents",
+    "working_hours_alerts",
+    "employee_bank_accounts",
+    "employee_preferences",
+    "employee_medical",
+    "medical_fitness_certificates",
+    "employee_licenses",
+];
+
+// Records that mention the employee but belong to something else keep the
+// row and lose the reference
+const CLEARED_REFERENCES: [(&str, &str); 5] = [
+    ("employees", "reports_to"),
+    ("employees", "previous_epf_number"),
+    ("visitors", "host_epf_number"),
+    ("candidates", "employee_epf_number"),
+    ("key_positions", "incumbent_epf"),
+];
+
+// Why the employee cannot be deleted, if anything stops it
+pub(crate) fn deletion_blocker(conn: &Connection, epf_number: &str) -> rusqlite::Result<Option<String>> {
+    for (table, description) in BLOCKING_TABLES {
+        let found: bool = conn.query_row(
+            &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE epf_number = ?1)", table),
+            [epf_number],
+            |row| row.get(0),
+        )?;
+        if found {
+            return Ok(Some(format!(
+                "Employee {} has {} and cannot be deleted. Archive the employee instead.",
+                epf_number, description
+            )));
+        }
+    }
+    Ok(None)
+}
+
+// Deletes the employee and their related rows. Run inside the caller's
+// transaction; the photo folder is removed separately by
+// delete_employee_photos once that has committed.
+pub(crate) fn delete_employee_records(conn: &Connection, epf_number: &str) -> Result<(), HrmError> {
+    if let Some(reason) = deletion_blocker(conn, epf_number)? {
+        return Err(HrmError::conflict(reason));
+    }
+    for table in CASCADE_TABLES {
+        conn.execute(&format!("DELETE FROM {} WHERE epf_number = ?1", table), [epf_number])?;
+    }
+    custom_fields::delete_custom_values(conn, epf_number)?;
+    delete_employee_tags(conn, epf_number)?;
+    conn.execute("DELETE FROM employees WHERE epf_number = ?1", [epf_number])?;
+    prompt_reassignment(conn, epf_number)?;
+    for (table, column) in CLEARED_REFERENCES {
+        conn.execute(&format!("UPDATE {} SET {} = NULL WHERE {} = ?1", table, column, column), [epf_number])?;
+    }
+    // Approvals only exist for the blocking workflows, but any left over go
+    // with their steps and comments
+    for table in ["approval_steps", "approval_comments"] {
+        conn.execute(
+            &format!("DELETE FROM {} WHERE approval_id IN (SELECT id FROM approval_requests WHERE epf_number = ?1)", table),
+            [epf_number],
+        )?;
+    }
+    conn.execute("DELETE FROM approval_requests WHERE epf_number = ?1", [epf_number])?;
+    // Tasks about the employee stay with their assignee, unlinked; alerts
+    // about them have nothing left to point at
+    conn.execute(
+        "UPDATE tasks SET entity_type = NULL, entity_id = NULL WHERE entity_type = 'EMPLOYEE' AND entity_id = ?1",
+        [epf_number],
+    )?;
+    conn.execute(
+        "DELETE FROM notifications WHERE entity_type = 'EMPLOYEE' AND entity_id = ?1",
+        [epf_number],
+    )?;
+    Ok(())
+}
+
+pub(crate) fn delete_employee_photos(app_dir: &Path, epf_number: &str) -> Result<(), HrmError> {
+    let image_dir = app_dir.join("employee_images").join(epf_number);
+    if image_dir.exists() {
+        fs::remove_dir_all(&image_dir).map_err(|e| HrmError::io(format!("Failed to delete photos: {}", e)))?;
+    }
+    Ok(())
+}
+
+// Takes an employee off the active list while keeping everything on record:
+// they are marked resigned (today, unless a date is given) and their team is
+// flagged for reassignment. Used where deletion is blocked.
+#[tauri::command]
+pub fn archive_employee(
+    epf_number: String,
+    date_of_resign: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user_lock = current_user.0.lock()?;
+    let user = match &*user_lock {
+        Some(session) if session.permissions.can_delete_employees => session.clone(),
+        Some(_) => return Err(HrmError::permission_denied("Permission denied. You cannot archive employees.")),
+        None => return Err(HrmError::not_logged_in()),
+    };
+    drop(user_lock);
+    let date_of_resign = match date_of_resign.map(|d| d.trim().to_string()).filter(|d| !d.is_empty()) {
+        Some(date) => {
+            parse_iso_date(&date).ok_or("Date of resignation must be a valid date (YYYY-MM-DD)")?;
+            date
+        }
+        None => Local::now().format("%Y-%m-%d").to_string(),
+    };
+    
+    let mut conn = db.0.lock()?;
+    let (working_status, date_of_join): (String, Option<String>) = conn
+        .query_row(
+            "SELECT working_status, date_of_join FROM employees WHERE epf_number = ?1",
+            [&epf_number],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?
+        .ok_or_else(|| HrmError::not_found(format!("Employee {} not found", epf_number)))?;
+    if working_status == "resign" {
+        return Err(HrmError::validation(format!("Employee {} is already archived", epf_number)));
+    }
+    
+    let tx = conn.transaction()?;
+    tx.execute(
+        "UPDATE employees SET working_status = 'resign', date_of_resign = ?1 WHERE epf_number = ?2",
+        [&date_of_resign, &epf_number],
+    )?;
+    sync_current_period(&tx, &epf_number, date_of_join.as_deref(), Some(&date_of_resign))?;
+    prompt_reassignment(&tx, &epf_number)?;
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "ARCHIVE",
+        "EMPLOYEE",
+        Some(&epf_number),
+        Some(&working_status),
+        Some("resign"),
+        Some(&format!("Archived employee {} (resigned {})", epf_number, date_of_resign)),
+    );
+    tx.commit()?;
+    
+    Ok(())
+}
@@ -0,0 +1,306 @@
+use crate::commands::{log_audit_action, query_employee};
+use crate::error::HrmError;
+use crate::export_commands::DEFAULT_COMPANY_NAME;
+use crate::letter_commands::{usable_template, write_letter};
+use crate::models::{EmployeeTransfer, EmploymentPeriod, ServiceSummary, TransferLetter, UserSession};
+use crate::org_chart_commands::prompt_reassignment;
+use crate::settings_commands::get_setting;
+use crate::validation::parse_iso_date;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::{Connection, OptionalExtension};
+use tauri::State;
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+// employees.date_of_join/date_of_resign hold the current period; this keeps
+// the latest employment_periods row in step with them. A join date after the
+// end of the latest period starts a new period (the employee came back).
+pub(crate) fn sync_current_period(
+    conn: &Connection,
+    epf_number: &str,
+    date_of_join: Option<&str>,
+    date_of_resign: Option<&str>,
+) -> rusqlite::Result<()> {
+    let date_of_join = match date_of_join.filter(|d| !d.is_empty()) {
+        Some(date) => date,
+        None => return Ok(()),
+    };
+    let date_of_resign = date_of_resign.filter(|d| !d.is_empty());
+    
+    let latest: Option<(i64, Option<String>)> = conn
+        .query_row(
+            "SELECT id, end_date FROM employment_periods WHERE epf_number = ?1 ORDER BY start_date DESC LIMIT 1",
+            [epf_number],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    
+    match latest {
+        Some((_, Some(end_date))) if date_of_join > end_date.as_str() => {
+            conn.execute(
+                "INSERT INTO employment_periods (epf_number, start_date, end_date) VALUES (?1, ?2, ?3)",
+                rusqlite::params![epf_number, date_of_join, date_of_resign],
+            )?;
+        }
+        Some((id, _)) => {
+            conn.execute(
+                "UPDATE employment_periods SET start_date = ?1, end_date = ?2 WHERE id = ?3",
+                rusqlite::params![date_of_join, date_of_resign, id],
+            )?;
+        }
+        None => {
+            conn.execute(
+                "INSERT INTO employment_periods (epf_number, start_date, end_date) VALUES (?1, ?2, ?3)",
+                rusqlite::params![epf_number, date_of_join, date_of_resign],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+// Every period for the employee, including those under earlier EPF numbers
+// linked through rehires, oldest first
+pub(crate) fn load_periods(conn: &Connection, epf_number: &str) -> rusqlite::Result<Vec<EmploymentPeriod>> {
+    let mut stmt = conn.prepare(
+        "WITH RECURSIVE chain(epf_number, previous) AS (
+             SELECT epf_number, previous_epf_number FROM employees WHERE epf_number = ?1
+             UNION
+             SELECT e.epf_number, e.previous_epf_number FROM employees e JOIN chain c ON e.epf_number = c.previous
+         )
+         SELECT p.id, p.epf_number, p.start_date, p.end_date,
+                CAST(julianday(COALESCE(p.end_date, date('now', 'localtime'))) - julianday(p.start_date) + 1 AS INTEGER)
+         FROM employment_periods p JOIN chain c ON c.epf_number = p.epf_number
+         ORDER BY p.start_date",
+    )?;
+    let periods = stmt
+        .query_map([epf_number], |row| {
+            Ok(EmploymentPeriod {
+                id: row.get(0)?,
+                epf_number: row.get(1)?,
+                start_date: row.get(2)?,
+                end_date: row.get(3)?,
+                days: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(periods)
+}
+
+// Total service across all periods, for gratuity and leave entitlements
+pub(crate) fn service_summary(conn: &Connection, epf_number: &str) -> rusqlite::Result<ServiceSummary> {
+    let periods = load_periods(conn, epf_number)?;
+    let total_days: i64 = periods.iter().map(|p| p.days.max(0)).sum();
+    let current_period_days = periods
+        .last()
+        .filter(|p| p.end_date.is_none())
+        .map(|p| p.days)
+        .unwrap_or(0);
+    Ok(ServiceSummary {
+        epf_number: epf_number.to_string(),
+        total_days,
+        completed_years: (total_days as f64 / 365.25).floor() as i64,
+        current_period_days,
+        periods,
+    })
+}
+
+#[tauri::command]
+pub fn get_service_summary(epf_number: String, db: State<'_, DbConnection>) -> Result<ServiceSummary, HrmError> {
+    let conn = db.0.lock()?;
+    service_summary(&conn, &epf_number).map_err(HrmError::from)
+}
+
+// Brings a resigned employee back under the same EPF number (seasonal
+// workers), starting a new employment period
+#[tauri::command]
+pub fn rejoin_employee(
+    epf_number: String,
+    date_of_join: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit employees."));
+    }
+    parse_iso_date(&date_of_join).ok_or("Date of joining must be a valid date (YYYY-MM-DD)")?;
+    
+    let mut conn = db.0.lock()?;
+    let (working_status, date_of_resign): (String, Option<String>) = conn
+        .query_row(
+            "SELECT working_status, date_of_resign FROM employees WHERE epf_number = ?1",
+            [&epf_number],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|_| HrmError::not_found(format!("Employee {} not found", epf_number)))?;
+    if working_status != "resign" {
+        return Err(HrmError::validation(format!("Employee {} has not resigned", epf_number)));
+    }
+    if let Some(resigned) = date_of_resign.as_deref().filter(|d| !d.is_empty()) {
+        if date_of_join.as_str() <= resigned {
+            return Err(HrmError::validation(format!("Date of joining must be after the last resignation on {}", resigned)));
+        }
+    }
+    
+    let tx = conn.transaction()?;
+    tx.execute(
+        "UPDATE employees SET working_status = 'active', date_of_join = ?1, date_of_resign = NULL WHERE epf_number = ?2",
+        rusqlite::params![date_of_join, epf_number],
+    )?;
+    sync_current_period(&tx, &epf_number, Some(&date_of_join), None)?;
+    
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "REJOIN",
+        "EMPLOYEE",
+        Some(&epf_number),
+        date_of_resign.as_deref(),
+        Some(&date_of_join),
+        Some(&format!("Employee {} rejoined on {}", epf_number, date_of_join)),
+    );
+    tx.commit()?;
+    
+    Ok(())
+}
+
+// Transfers out to another branch or company, newest first
+pub(crate) fn load_transfers(conn: &Connection, epf_number: &str) -> rusqlite::Result<Vec<EmployeeTransfer>> {
+    let mut stmt = conn.prepare(
+        "SELECT t.id, t.epf_number, t.from_location, t.to_location, t.effective_date, t.reason,
+                t.new_epf_number, u.username, t.created_at
+         FROM employee_transfers t LEFT JOIN users u ON u.id = t.transferred_by
+         WHERE t.epf_number = ?1
+         ORDER BY t.effective_date DESC, t.id DESC",
+    )?;
+    let transfers = stmt
+        .query_map([epf_number], |row| {
+            Ok(EmployeeTransfer {
+                id: row.get(0)?,
+                epf_number: row.get(1)?,
+                from_location: row.get(2)?,
+                to_location: row.get(3)?,
+                effective_date: row.get(4)?,
+                reason: row.get(5)?,
+                new_epf_number: row.get(6)?,
+                transferred_by: row.get(7)?,
+                created_at: row.get(8)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(transfers)
+}
+
+#[tauri::command]
+pub fn get_employee_transfers(
+    epf_number: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<EmployeeTransfer>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view employees."));
+    }
+    
+    let conn = db.0.lock()?;
+    load_transfers(&conn, &epf_number).map_err(HrmError::from)
+}
+
+// Transfers an employee to another branch or company. The record stays here
+// with its service history: the current period ends the day before the
+// transfer and the employee is marked resigned. new_epf_number links to the
+// employee's number at the receiving end when known. With `letter`, the
+// transfer letter is issued in the same step from a template using the
+// transfer_to and transfer_date merge fields; if it cannot be written the
+// transfer is not recorded either.
+#[tauri::command]
+pub fn transfer_employee(
+    transfer: EmployeeTransfer,
+    letter: Option<TransferLetter>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit employees."));
+    }
+    let to_location = transfer.to_location.trim();
+    if to_location.is_empty() {
+        return Err(HrmError::field("to_location", "Enter the branch or company the employee moves to"));
+    }
+    parse_iso_date(&transfer.effective_date).ok_or("Transfer date must be a valid date (YYYY-MM-DD)")?;
+    let text = |value: &Option<String>| value.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+    
+    let mut conn = db.0.lock()?;
+    let (working_status, date_of_join, department): (String, Option<String>, Option<String>) = conn
+        .query_row(
+            "SELECT working_status, date_of_join, department FROM employees WHERE epf_number = ?1",
+            [&transfer.epf_number],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?
+        .ok_or_else(|| HrmError::not_found(format!("Employee {} not found", transfer.epf_number)))?;
+    if !user.can_access_department(department.as_deref()) {
+        return Err(HrmError::permission_denied("Permission denied. This employee is outside your departments."));
+    }
+    if working_status == "resign" {
+        return Err(HrmError::validation(format!("Employee {} has already left", transfer.epf_number)));
+    }
+    if let Some(joined) = date_of_join.as_deref().filter(|d| !d.is_empty()) {
+        if transfer.effective_date.as_str() <= joined {
+            return Err(HrmError::field("effective_date", format!("Transfer date must be after joining on {}", joined)));
+        }
+    }
+    let from_location = get_setting(&conn, "company_name").unwrap_or_else(|| DEFAULT_COMPANY_NAME.to_string());
+    let template = match &letter {
+        Some(letter) => Some(usable_template(&conn, &user, letter.template_id, &letter.format)?),
+        None => None,
+    };
+    
+    let tx = conn.transaction()?;
+    let last_day: String = tx.query_row("SELECT date(?1, '-1 day')", [&transfer.effective_date], |row| row.get(0))?;
+    tx.execute(
+        "UPDATE employees SET working_status = 'resign', date_of_resign = ?1 WHERE epf_number = ?2",
+        [&last_day, &transfer.epf_number],
+    )?;
+    sync_current_period(&tx, &transfer.epf_number, date_of_join.as_deref(), Some(&last_day))?;
+    prompt_reassignment(&tx, &transfer.epf_number)?;
+    tx.execute(
+        "INSERT INTO employee_transfers
+            (epf_number, from_location, to_location, effective_date, reason, new_epf_number, transferred_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![
+            transfer.epf_number,
+            from_location,
+            to_location,
+            transfer.effective_date,
+            text(&transfer.reason),
+            text(&transfer.new_epf_number),
+            user.user_id,
+        ],
+    )?;
+    let id = tx.last_insert_rowid();
+    
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "TRANSFER",
+        "EMPLOYEE",
+        Some(&transfer.epf_number),
+        Some(&from_location),
+        Some(to_location),
+        Some(&format!("Transferred employee {} to {} from {}", transfer.epf_number, to_location, transfer.effective_date)),
+    );
+    if let (Some(letter), Some(template)) = (&letter, &template) {
+        let employee = query_employee(&tx, &transfer.epf_number)?;
+        write_letter(&tx, &user, template, &employee, &letter.format, &letter.destination_path)?;
+    }
+    tx.commit()?;
+    
+    Ok(id)
+}
@@ -0,0 +1,268 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::journal;
+use crate::settings_commands::{get_setting, get_setting_i64};
+use crate::{AppDataDir, CurrentUser, DbConnection};
+use rusqlite::{Connection, OptionalExtension};
+use std::fs;
+use tauri::State;
+
+// Columns in any table that hold an employee's EPF number
+const EMPLOYEE_REFERENCE_COLUMNS: [&str; 6] = [
+    "epf_number",
+    "previous_epf_number",
+    "employee_epf_number",
+    "host_epf_number",
+    "incumbent_epf",
+    "reports_to",
+];
+
+// Tables that record what happened under the number in use at the time.
+// Renumbering leaves them alone and records the change in
+// epf_number_changes instead.
+const HISTORICAL_TABLES: [&str; 2] = ["payroll_lines", "epf_number_changes"];
+
+// EPF numbering scheme: epf_number_prefix followed by epf_number_digits
+// zero-padded digits, e.g. "NL" and 5 give NL00042. Numbers are free-form
+// while epf_number_digits is unset or 0.
+fn numbering_scheme(conn: &Connection) -> Option<(String, usize)> {
+    let digits = get_setting_i64(conn, "epf_number_digits").filter(|d| *d > 0)?;
+    let prefix = get_setting(conn, "epf_number_prefix").unwrap_or_default();
+    Some((prefix.trim().to_string(), digits as usize))
+}
+
+fn sequence_of(epf_number: &str, prefix: &str, digits: usize) -> Option<i64> {
+    let sequence = epf_number.strip_prefix(prefix)?;
+    if sequence.len() == digits && sequence.chars().all(|c| c.is_ascii_digit()) {
+        sequence.parse().ok()
+    } else {
+        None
+    }
+}
+
+// The member number registered with the EPF and ETF: the EPF number without
+// the installation's epf_number_prefix
+pub(crate) fn member_number(conn: &Connection, epf_number: &str) -> String {
+    let prefix = get_setting(conn, "epf_number_prefix").unwrap_or_default();
+    match epf_number.strip_prefix(prefix.trim()) {
+        Some(number) if !number.is_empty() => number.to_string(),
+        _ => epf_number.to_string(),
+    }
+}
+
+// Highest sequence number already used under the scheme
+fn highest_sequence(conn: &Connection, prefix: &str, digits: usize) -> rusqlite::Result<i64> {
+    let mut stmt = conn.prepare("SELECT epf_number FROM employees WHERE substr(epf_number, 1, length(?1)) = ?1")?;
+    let numbers = stmt
+        .query_map([prefix], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(numbers.iter().filter_map(|n| sequence_of(n, prefix, digits)).max().unwrap_or(0))
+}
+
+// The number the next new employee would get. Without a scheme this is one
+// more than the highest all-digit EPF number, unpadded.
+fn next_epf_number(conn: &Connection) -> rusqlite::Result<String> {
+    match numbering_scheme(conn) {
+        Some((prefix, digits)) => {
+            let next = highest_sequence(conn, &prefix, digits)? + 1;
+            Ok(format!("{}{:0width$}", prefix, next, width = digits))
+        }
+        None => {
+            let highest: Option<i64> = conn.query_row(
+                "SELECT MAX(CAST(epf_number AS INTEGER)) FROM employees WHERE epf_number NOT GLOB '*[^0-9]*'",
+                [],
+                |row| row.get(0),
+            )?;
+            Ok((highest.unwrap_or(0) + 1).to_string())
+        }
+    }
+}
+
+// EPF number for a new employee. A blank number is allocated from the scheme
+// here, while the caller holds the database lock, so two people entering
+// employees at once cannot be given the same number. A number that was
+// typed must follow the scheme and may not skip ahead of the sequence.
+pub(crate) fn allocate_epf_number(conn: &Connection, epf_number: &str) -> Result<String, HrmError> {
+    let epf_number = epf_number.trim();
+    // The number names the employee's photo folder
+    if epf_number.contains(['/', '\\']) || epf_number.contains("..") || epf_number.chars().any(char::is_control) {
+        return Err(HrmError::field("epf_number", "EPF number cannot contain slashes or '..'"));
+    }
+    let Some((prefix, digits)) = numbering_scheme(conn) else {
+        return Ok(epf_number.to_string());
+    };
+    let next = highest_sequence(conn, &prefix, digits)? + 1;
+    if epf_number.is_empty() {
+        return Ok(format!("{}{:0width$}", prefix, next, width = digits));
+    }
+    match sequence_of(epf_number, &prefix, digits) {
+        None => Err(HrmError::field(
+            "epf_number",
+            format!(
+                "EPF number must be {}{} digits, e.g. {}{:0width$}",
+                if prefix.is_empty() { String::new() } else { format!("{} followed by ", prefix) },
+                digits,
+                prefix,
+                next,
+                width = digits
+            ),
+        )),
+        Some(sequence) if sequence > next => Err(HrmError::field(
+            "epf_number",
+            format!("EPF numbers are issued in sequence; the next number is {}{:0width$}", prefix, next, width = digits),
+        )),
+        Some(_) => Ok(epf_number.to_string()),
+    }
+}
+
+// The employee's current number and every number it replaced, for looking up
+// records kept under an earlier number
+pub(crate) fn epf_numbers_of(conn: &Connection, epf_number: &str) -> rusqlite::Result<Vec<String>> {
+    let mut numbers = vec![epf_number.to_string()];
+    let mut i = 0;
+    while i < numbers.len() {
+        let earlier = conn
+            .prepare("SELECT old_epf_number FROM epf_number_changes WHERE new_epf_number = ?1")?
+            .query_map([&numbers[i]], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        numbers.extend(earlier.into_iter().filter(|n| n != epf_number));
+        i += 1;
+    }
+    Ok(numbers)
+}
+
+// Suggested EPF number for the new employee form. Nothing is held back;
+// save the employee with a blank EPF number to be given the next one.
+#[tauri::command]
+pub fn get_next_epf_number(db: State<'_, DbConnection>) -> Result<String, HrmError> {
+    let conn = db.0.lock()?;
+    Ok(next_epf_number(&conn)?)
+}
+
+// Points every reference to the old EPF number at the new one: each table
+// column in EMPLOYEE_REFERENCE_COLUMNS outside HISTORICAL_TABLES, stored photo
+// paths and the tasks and notifications linked to the employee. Audit entries
+// keep the old number. Returns the number of rows changed.
+fn renumber_references(conn: &Connection, old_epf_number: &str, new_epf_number: &str) -> rusqlite::Result<usize> {
+    let tables = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'")?
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut changed = 0;
+    for table in tables.iter().filter(|t| !HISTORICAL_TABLES.contains(&t.as_str())) {
+        let columns = conn
+            .prepare(&format!("PRAGMA table_info({})", table))?
+            .query_map([], |row| row.get::<_, String>(1))?
+            .collect::<Result<Vec<_>, _>>()?;
+        for column in columns.iter().filter(|c| EMPLOYEE_REFERENCE_COLUMNS.contains(&c.as_str())) {
+            changed += conn.execute(
+                &format!("UPDATE {} SET {} = ?1 WHERE {} = ?2", table, column, column),
+                [new_epf_number, old_epf_number],
+            )?;
+        }
+    }
+    
+    let old_folder = format!("employee_images/{}/", old_epf_number);
+    let new_folder = format!("employee_images/{}/", new_epf_number);
+    for table in ["employees", "image_metadata"] {
+        changed += conn.execute(
+            &format!(
+                "UPDATE {} SET image_path = ?1 || substr(image_path, length(?2) + 1)
+                 WHERE substr(image_path, 1, length(?2)) = ?2",
+                table
+            ),
+            [&new_folder, &old_folder],
+        )?;
+    }
+    for table in ["tasks", "notifications"] {
+        changed += conn.execute(
+            &format!("UPDATE {} SET entity_id = ?1 WHERE entity_type = 'EMPLOYEE' AND entity_id = ?2", table),
+            [new_epf_number, old_epf_number],
+        )?;
+    }
+    Ok(changed)
+}
+
+// Corrects an EPF number that was entered wrong. The employee's records and
+// photos folder move to the new number in one step, while payroll lines and
+// audit history stay as recorded and are linked through epf_number_changes.
+// A blank new number takes the next one from the numbering scheme.
+#[tauri::command]
+pub fn change_epf_number(
+    old_epf_number: String,
+    new_epf_number: String,
+    app_data_dir: State<'_, AppDataDir>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<String, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    let user = match &*user_lock {
+        Some(session) if session.permissions.can_edit_employees => session.clone(),
+        Some(_) => return Err(HrmError::permission_denied("Permission denied. You cannot edit employees.")),
+        None => return Err(HrmError::not_logged_in()),
+    };
+    drop(user_lock);
+    
+    let mut conn = db.0.lock()?;
+    let new_epf_number = allocate_epf_number(&conn, &new_epf_number)?;
+    if new_epf_number.is_empty() {
+        return Err(HrmError::field("epf_number", "New EPF number is required"));
+    }
+    if new_epf_number == old_epf_number {
+        return Err(HrmError::field("epf_number", "New EPF number is the same as the current one"));
+    }
+    let department: Option<String> = conn
+        .query_row("SELECT department FROM employees WHERE epf_number = ?1", [&old_epf_number], |row| row.get(0))
+        .optional()?
+        .ok_or_else(|| HrmError::not_found(format!("Employee {} not found", old_epf_number)))?;
+    if !user.can_access_department(department.as_deref()) {
+        return Err(HrmError::permission_denied("Permission denied. This employee is outside your departments."));
+    }
+    let exists: bool =
+        conn.query_row("SELECT COUNT(*) > 0 FROM employees WHERE epf_number = ?1", [&new_epf_number], |row| row.get(0))?;
+    if exists {
+        return Err(HrmError::field("epf_number", format!("EPF number {} already exists", new_epf_number)));
+    }
+    
+    let images_dir = app_data_dir.0.join("employee_images");
+    let (old_folder, new_folder) = (images_dir.join(&old_epf_number), images_dir.join(&new_epf_number));
+    let intent = journal::begin(
+        &app_data_dir.0,
+        &journal::Operation::RenameEmployeeFolder {
+            old_epf_number: old_epf_number.clone(),
+            new_epf_number: new_epf_number.clone(),
+        },
+    )?;
+    let tx = conn.transaction()?;
+    let changed = renumber_references(&tx, &old_epf_number, &new_epf_number)?;
+    tx.execute(
+        "INSERT INTO epf_number_changes (old_epf_number, new_epf_number, changed_by) VALUES (?1, ?2, ?3)",
+        rusqlite::params![old_epf_number, new_epf_number, user.user_id],
+    )?;
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "RENUMBER",
+        "EMPLOYEE",
+        Some(&new_epf_number),
+        Some(&old_epf_number),
+        Some(&new_epf_number),
+        Some(&format!("Changed EPF number {} to {} ({} records updated)", old_epf_number, new_epf_number, changed)),
+    );
+    
+    // The folder is renamed before the commit and put back if the commit
+    // fails, so photos and records never point at different numbers
+    if old_folder.exists() {
+        fs::rename(&old_folder, &new_folder).map_err(|e| HrmError::io(format!("Failed to move photos: {}", e)))?;
+    }
+    if let Err(e) = tx.commit() {
+        if new_folder.exists() {
+            let _ = fs::rename(&new_folder, &old_folder);
+        }
+        return Err(e.into());
+    }
+    intent.commit()?;
+    
+    Ok(new_epf_number)
+}
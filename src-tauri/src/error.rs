@@ -0,0 +1,143 @@
+use crate::models::FieldError;
+use serde::Serialize;
+use std::fmt;
+use std::sync::PoisonError;
+
+// Broad category of a failure, so the frontend can react to it (e.g. send
+// the user back to login) and show a translated message for the code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    Validation,
+    NotLoggedIn,
+    PermissionDenied,
+    NotFound,
+    Conflict,
+    Database,
+    Io,
+    Internal,
+    ReadOnly,
+}
+
+// Error returned by every command. message is the English text shown when the
+// frontend has no translation; field_errors is only filled for validation
+// failures tied to particular form fields.
+#[derive(Debug, Serialize)]
+pub struct HrmError {
+    pub code: ErrorCode,
+    pub message: String,
+    pub field_errors: Vec<FieldError>,
+}
+
+pub type HrmResult<T> = Result<T, HrmError>;
+
+impl HrmError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        HrmError { code, message: message.into(), field_errors: Vec::new() }
+    }
+    
+    pub fn validation(message: impl Into<String>) -> Self {
+        HrmError::new(ErrorCode::Validation, message)
+    }
+    
+    pub fn fields(message: impl Into<String>, field_errors: Vec<FieldError>) -> Self {
+        HrmError { code: ErrorCode::Validation, message: message.into(), field_errors }
+    }
+    
+    // A validation failure on a single form field
+    pub fn field(field: &str, message: impl Into<String>) -> Self {
+        let message = message.into();
+        HrmError::fields(message.clone(), vec![FieldError { field: field.to_string(), message }])
+    }
+    
+    pub fn not_logged_in() -> Self {
+        HrmError::new(ErrorCode::NotLoggedIn, "Not logged in")
+    }
+    
+    pub fn permission_denied(message: impl Into<String>) -> Self {
+        HrmError::new(ErrorCode::PermissionDenied, message)
+    }
+    
+    pub fn not_found(message: impl Into<String>) -> Self {
+        HrmError::new(ErrorCode::NotFound, message)
+    }
+    
+    pub fn conflict(message: impl Into<String>) -> Self {
+        HrmError::new(ErrorCode::Conflict, message)
+    }
+    
+    pub fn database(message: impl Into<String>) -> Self {
+        HrmError::new(ErrorCode::Database, message)
+    }
+    
+    pub fn io(message: impl Into<String>) -> Self {
+        HrmError::new(ErrorCode::Io, message)
+    }
+    
+    pub fn internal(message: impl Into<String>) -> Self {
+        HrmError::new(ErrorCode::Internal, message)
+    }
+    
+    pub fn read_only() -> Self {
+        HrmError::new(ErrorCode::ReadOnly, "This is a read-only terminal. Changes can only be made from an office PC.")
+    }
+}
+
+impl fmt::Display for HrmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.field_errors.is_empty() {
+            return write!(f, "{}", self.message);
+        }
+        let details: Vec<String> = self.field_errors.iter().map(|e| e.message.clone()).collect();
+        write!(f, "{}", details.join("; "))
+    }
+}
+
+impl std::error::Error for HrmError {}
+
+// Plain messages are user-facing explanations of why a request was refused,
+// so they become validation errors. Database, file and other failures must
+// be converted with HrmError::database/io/internal or their own From impls
+// instead of being turned into a string first.
+impl From<String> for HrmError {
+    fn from(message: String) -> Self {
+        HrmError::validation(message)
+    }
+}
+
+impl From<&str> for HrmError {
+    fn from(message: &str) -> Self {
+        HrmError::validation(message)
+    }
+}
+
+impl From<rusqlite::Error> for HrmError {
+    fn from(e: rusqlite::Error) -> Self {
+        match e {
+            rusqlite::Error::QueryReturnedNoRows => HrmError::not_found("Record not found"),
+            rusqlite::Error::SqliteFailure(err, _) if err.code == rusqlite::ErrorCode::ConstraintViolation => {
+                HrmError::conflict(e.to_string())
+            }
+            _ => HrmError::database(e.to_string()),
+        }
+    }
+}
+
+impl From<std::io::Error> for HrmError {
+    fn from(e: std::io::Error) -> Self {
+        HrmError::io(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for HrmError {
+    fn from(e: serde_json::Error) -> Self {
+        HrmError::internal(e.to_string())
+    }
+}
+
+// A poisoned lock means another command panicked while holding it
+impl<T> From<PoisonError<T>> for HrmError {
+    fn from(e: PoisonError<T>) -> Self {
+        HrmError::internal(e.to_string())
+    }
+}
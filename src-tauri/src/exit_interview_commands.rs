@@ -0,0 +1,293 @@
+use crate::asset_commands::outstanding_items;
+use crate::commands::log_audit_action;
+use crate::employment_periods::sync_current_period;
+use crate::error::HrmError;
+use crate::org_chart_commands::prompt_reassignment;
+use crate::models::{AssetIssue, ExitAnalytics, ExitInterview, ReasonCount, UserSession};
+use crate::validation::parse_iso_date;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use tauri::State;
+
+const EXIT_REASONS: [&str; 11] = [
+    "better_pay",
+    "career_growth",
+    "relocation",
+    "family",
+    "health",
+    "work_environment",
+    "supervisor",
+    "workload",
+    "further_studies",
+    "retirement",
+    "other",
+];
+
+// Tenure bands for the analytics report, as (label, upper bound in years)
+const TENURE_BANDS: [(&str, f64); 5] = [
+    ("< 1 year", 1.0),
+    ("1-3 years", 3.0),
+    ("3-5 years", 5.0),
+    ("5-10 years", 10.0),
+    ("10+ years", f64::MAX),
+];
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+fn validate_interview(interview: &ExitInterview) -> Result<(), HrmError> {
+    parse_iso_date(&interview.interview_date).ok_or("Interview date must be a valid date (YYYY-MM-DD)")?;
+    for reason in std::iter::once(&interview.primary_reason).chain(&interview.secondary_reasons) {
+        if !EXIT_REASONS.contains(&reason.as_str()) {
+            return Err(HrmError::validation(format!("Unknown reason for leaving: {}", reason)));
+        }
+    }
+    Ok(())
+}
+
+// Inserts or replaces the employee's exit interview
+fn save_interview(conn: &Connection, interview: &ExitInterview, interviewer: &str) -> Result<(), HrmError> {
+    conn.execute(
+        "INSERT INTO exit_interviews (epf_number, interview_date, primary_reason, secondary_reasons, would_rejoin, comments, interviewer)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT(epf_number) DO UPDATE SET
+             interview_date = excluded.interview_date, primary_reason = excluded.primary_reason,
+             secondary_reasons = excluded.secondary_reasons, would_rejoin = excluded.would_rejoin,
+             comments = excluded.comments, interviewer = excluded.interviewer",
+        rusqlite::params![
+            interview.epf_number,
+            interview.interview_date,
+            interview.primary_reason,
+            interview.secondary_reasons.join(","),
+            interview.would_rejoin,
+            interview.comments,
+            interviewer,
+        ],
+    )?;
+    Ok(())
+}
+
+// Marks an employee as resigned, optionally recording the exit interview in
+// the same step. Returns items still to be collected for clearance.
+#[tauri::command]
+pub fn resign_employee(
+    epf_number: String,
+    date_of_resign: String,
+    exit_interview: Option<ExitInterview>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<AssetIssue>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit employees."));
+    }
+    parse_iso_date(&date_of_resign).ok_or("Resignation date must be a valid date (YYYY-MM-DD)")?;
+    if let Some(interview) = &exit_interview {
+        if interview.epf_number != epf_number {
+            return Err(HrmError::validation("Exit interview is for a different employee"));
+        }
+        validate_interview(interview)?;
+    }
+    
+    let mut conn = db.0.lock()?;
+    let (working_status, date_of_join): (String, Option<String>) = conn
+        .query_row(
+            "SELECT working_status, date_of_join FROM employees WHERE epf_number = ?1",
+            [&epf_number],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|_| HrmError::not_found(format!("Employee {} not found", epf_number)))?;
+    if working_status == "resign" {
+        return Err(HrmError::validation(format!("Employee {} has already resigned", epf_number)));
+    }
+    if date_of_join.as_deref().map(|j| date_of_resign.as_str() < j).unwrap_or(false) {
+        return Err(HrmError::validation("Resignation date cannot be before the date of joining"));
+    }
+    
+    let tx = conn.transaction()?;
+    tx.execute(
+        "UPDATE employees SET working_status = 'resign', date_of_resign = ?1 WHERE epf_number = ?2",
+        rusqlite::params![date_of_resign, epf_number],
+    )?;
+    sync_current_period(&tx, &epf_number, date_of_join.as_deref(), Some(&date_of_resign))?;
+    if let Some(interview) = &exit_interview {
+        save_interview(&tx, interview, &user.username)?;
+    }
+    prompt_reassignment(&tx, &epf_number)?;
+    
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "RESIGN",
+        "EMPLOYEE",
+        Some(&epf_number),
+        Some(&working_status),
+        Some("resign"),
+        Some(&format!(
+            "Employee {} resigned on {}{}",
+            epf_number,
+            date_of_resign,
+            exit_interview
+                .as_ref()
+                .map(|i| format!(" (reason: {})", i.primary_reason.replace('_', " ")))
+                .unwrap_or_default()
+        )),
+    );
+    tx.commit()?;
+    
+    outstanding_items(&conn, Some(&epf_number))
+}
+
+// Records or corrects the exit interview of an employee who has already left
+#[tauri::command]
+pub fn record_exit_interview(
+    interview: ExitInterview,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit employees."));
+    }
+    validate_interview(&interview)?;
+    
+    let conn = db.0.lock()?;
+    let working_status: String = conn
+        .query_row("SELECT working_status FROM employees WHERE epf_number = ?1", [&interview.epf_number], |row| row.get(0))
+        .map_err(|_| HrmError::not_found(format!("Employee {} not found", interview.epf_number)))?;
+    if working_status != "resign" {
+        return Err(HrmError::validation("Record the resignation first"));
+    }
+    save_interview(&conn, &interview, &user.username)?;
+    
+    let new_value = serde_json::to_string(&interview).ok();
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "EXIT_INTERVIEW",
+        Some(&interview.epf_number),
+        None,
+        new_value.as_deref(),
+        Some(&format!("Recorded exit interview for employee {}", interview.epf_number)),
+    );
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_exit_interview(
+    epf_number: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Option<ExitInterview>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_employees {
+        return Err(HrmError::permission_denied("Permission denied"));
+    }
+    
+    let conn = db.0.lock()?;
+    let interview = conn
+        .query_row(
+            "SELECT epf_number, interview_date, primary_reason, secondary_reasons, would_rejoin, comments, interviewer
+             FROM exit_interviews WHERE epf_number = ?1",
+            [&epf_number],
+            |row| {
+                let secondary: Option<String> = row.get(3)?;
+                Ok(ExitInterview {
+                    epf_number: row.get(0)?,
+                    interview_date: row.get(1)?,
+                    primary_reason: row.get(2)?,
+                    secondary_reasons: secondary
+                        .unwrap_or_default()
+                        .split(',')
+                        .filter(|r| !r.is_empty())
+                        .map(str::to_string)
+                        .collect(),
+                    would_rejoin: row.get(4)?,
+                    comments: row.get(5)?,
+                    interviewer: row.get(6)?,
+                })
+            },
+        )
+        .ok();
+    Ok(interview)
+}
+
+fn tenure_band(years: f64) -> &'static str {
+    TENURE_BANDS
+        .iter()
+        .find(|(_, upper)| years < *upper)
+        .map(|(label, _)| *label)
+        .unwrap_or(TENURE_BANDS[TENURE_BANDS.len() - 1].0)
+}
+
+// Counts of the primary reason for leaving, grouped by department and by
+// tenure band, most common first
+#[tauri::command]
+pub fn get_exit_interview_analytics(
+    year: Option<i32>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<ExitAnalytics, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_reports {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view reports."));
+    }
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT COALESCE(e.department, 'Unassigned'), x.primary_reason, x.would_rejoin,
+                    (julianday(COALESCE(e.date_of_resign, x.interview_date)) - julianday(e.date_of_join)) / 365.25
+             FROM exit_interviews x JOIN employees e ON e.epf_number = x.epf_number
+             WHERE ?1 IS NULL OR strftime('%Y', COALESCE(e.date_of_resign, x.interview_date)) = printf('%04d', ?1)",
+        )?;
+    let rows = stmt
+        .query_map([year], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<bool>>(2)?,
+                row.get::<_, Option<f64>>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    let mut by_department: Vec<ReasonCount> = Vec::new();
+    let mut by_tenure_band: Vec<ReasonCount> = Vec::new();
+    let add = |counts: &mut Vec<ReasonCount>, group: &str, reason: &str| {
+        match counts.iter_mut().find(|c| c.group == group && c.reason == reason) {
+            Some(count) => count.count += 1,
+            None => counts.push(ReasonCount { group: group.to_string(), reason: reason.to_string(), count: 1 }),
+        }
+    };
+    for (department, reason, _, years) in &rows {
+        add(&mut by_department, department, reason);
+        let band = years.map(tenure_band).unwrap_or("Unknown");
+        add(&mut by_tenure_band, band, reason);
+    }
+    by_department.sort_by(|a, b| a.group.cmp(&b.group).then(b.count.cmp(&a.count)));
+    by_tenure_band.sort_by(|a, b| {
+        let position = |group: &str| TENURE_BANDS.iter().position(|(label, _)| *label == group).unwrap_or(usize::MAX);
+        position(&a.group).cmp(&position(&b.group)).then(b.count.cmp(&a.count))
+    });
+    
+    let answered: Vec<bool> = rows.iter().filter_map(|(_, _, rejoin, _)| *rejoin).collect();
+    let would_rejoin_percent = if answered.is_empty() {
+        None
+    } else {
+        Some((answered.iter().filter(|r| **r).count() as f64 * 1000.0 / answered.len() as f64).round() / 10.0)
+    };
+    
+    Ok(ExitAnalytics {
+        total_interviews: rows.len() as i32,
+        would_rejoin_percent,
+        by_department,
+        by_tenure_band,
+    })
+}
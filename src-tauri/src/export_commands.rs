@@ -0,0 +1,343 @@
+use crate::commands::{log_data_export, query_employees};
+use crate::custom_fields::load_definitions;
+use crate::error::HrmError;
+use crate::images;
+use crate::import_commands::xml_escape;
+use crate::localization::DOCUMENT_FONTS;
+use crate::models::{Employee, EmployeeFilters, UserSession};
+use crate::settings_commands::date_display;
+use crate::validation::format_date;
+use crate::{AppDataDir, CurrentUser, DbConnection};
+use base64::{engine::general_purpose, Engine as _};
+use std::fs;
+use std::path::Path;
+use tauri::State;
+
+// Returns the logged-in user if they are allowed to export employee data
+fn require_export_permission(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_export_data => Ok(session.clone()),
+        Some(_) => Err(HrmError::permission_denied("Permission denied. You cannot export employee data.")),
+        None => Err(HrmError::not_logged_in()),
+    }
+}
+
+// Employees matching the filters, limited to the departments the user may see
+fn accessible_employees(
+    conn: &rusqlite::Connection,
+    filters: EmployeeFilters,
+    user: &UserSession,
+) -> Result<Vec<Employee>, HrmError> {
+    Ok(query_employees(conn, filters)?
+        .into_iter()
+        .filter(|e| user.can_access_department(e.department.as_deref()))
+        .collect())
+}
+
+// Columns available in employee exports, as (field, header)
+pub(crate) const EXPORT_COLUMNS: [(&str, &str); 21] = [
+    ("epf_number", "EPF Number"),
+    ("name_with_initials", "Name with Initials"),
+    ("full_name", "Full Name"),
+    ("nic", "NIC Number"),
+    ("department", "Department"),
+    ("cader", "Cader"),
+    ("designation", "Designation"),
+    ("allocation", "Allocation"),
+    ("date_of_join", "Date of Joining"),
+    ("working_status", "Working Status"),
+    ("dob", "Date of Birth"),
+    ("marital_status", "Marital Status"),
+    ("mobile_1", "Mobile 1"),
+    ("mobile_2", "Mobile 2"),
+    ("transport_route", "Transport Route"),
+    ("police_area", "Police Area"),
+    ("address", "Address"),
+    ("district", "District"),
+    ("ds_division", "DS Division"),
+    ("gn_division", "GN Division"),
+    ("date_of_resign", "Date of Resignation"),
+];
+
+// Personal details only exported for users with can_view_sensitive_data
+const SENSITIVE_COLUMNS: [&str; 10] = [
+    "nic", "dob", "marital_status", "mobile_1", "mobile_2", "police_area", "address", "district", "ds_division", "gn_division",
+];
+const REDACTED: &str = "[REDACTED]";
+
+// Spreadsheets run a cell starting with one of these as a formula
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+pub(crate) fn csv_field(value: &str) -> String {
+    // A leading quote makes Excel show typed text such as "=HYPERLINK(...)"
+    // as text; plain numbers like -150.00 are left alone
+    let value = if value.starts_with(FORMULA_PREFIXES) && value.parse::<f64>().is_err() {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+// Company name printed on reports, unless the company_name setting is set
+pub(crate) const DEFAULT_COMPANY_NAME: &str = "New Lanka Clothing (Pvt) Ltd";
+
+// Printable HTML page matching the employee PDF export, for reports that are
+// printed to PDF from the browser. `sections` is HTML placed under the header.
+pub(crate) fn printable_page(company: &str, title: &str, details: &[String], sections: &[String]) -> String {
+    let mut html = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"UTF-8\">\n");
+    html.push_str(&format!("<title>{}</title>\n", xml_escape(title)));
+    html.push_str("<style>\n");
+    html.push_str("  * { margin: 0; padding: 0; box-sizing: border-box; }\n");
+    html.push_str(&format!("  body {{ font-family: {}; padding: 20px; }}\n", DOCUMENT_FONTS));
+    html.push_str("  .header { text-align: center; margin-bottom: 20px; border-bottom: 2px solid #2563eb; padding-bottom: 15px; }\n");
+    html.push_str("  .header h1 { color: #1e40af; font-size: 24px; }\n");
+    html.push_str("  .header p { color: #6b7280; font-size: 14px; margin-top: 5px; }\n");
+    html.push_str("  h2 { color: #1e40af; font-size: 16px; margin: 20px 0 8px; }\n");
+    html.push_str("  table { width: 100%; border-collapse: collapse; font-size: 12px; }\n");
+    html.push_str("  th { background: #2563eb; color: white; padding: 10px 8px; text-align: left; }\n");
+    html.push_str("  td { padding: 8px; border-bottom: 1px solid #e5e7eb; }\n");
+    html.push_str("  .num { text-align: right; }\n");
+    html.push_str("  tfoot td { font-weight: bold; border-top: 2px solid #1e40af; }\n");
+    html.push_str("  .photo { max-width: 150px; max-height: 180px; border: 1px solid #e5e7eb; }\n");
+    html.push_str("  .page-break { page-break-before: always; }\n");
+    html.push_str("  @media print { body { padding: 10px; } }\n");
+    html.push_str("</style>\n</head>\n<body>\n<div class=\"header\">\n");
+    html.push_str(&format!("  <h1>{}</h1>\n", xml_escape(company)));
+    html.push_str(&format!("  <p>{}</p>\n", xml_escape(title)));
+    for detail in details {
+        html.push_str(&format!("  <p>{}</p>\n", xml_escape(detail)));
+    }
+    html.push_str("</div>\n");
+    for section in sections {
+        html.push_str(section);
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+// An HTML table for printable_page. `numeric` marks the columns to
+// right-align; `totals` is an optional last row.
+pub(crate) fn html_table(headers: &[&str], numeric: &[usize], rows: &[Vec<String>], totals: Option<&[String]>) -> String {
+    let cell = |tag: &str, index: usize, value: &str| {
+        let align = if numeric.contains(&index) { " class=\"num\"" } else { "" };
+        format!("<{0}{1}>{2}</{0}>", tag, align, xml_escape(value))
+    };
+    let mut html = String::from("<table>\n<thead><tr>");
+    for (index, header) in headers.iter().enumerate() {
+        html.push_str(&cell("th", index, header));
+    }
+    html.push_str("</tr></thead>\n<tbody>\n");
+    for row in rows {
+        html.push_str("<tr>");
+        for (index, value) in row.iter().enumerate() {
+            html.push_str(&cell("td", index, value));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</tbody>\n");
+    if let Some(totals) = totals {
+        html.push_str("<tfoot><tr>");
+        for (index, value) in totals.iter().enumerate() {
+            html.push_str(&cell("td", index, value));
+        }
+        html.push_str("</tr></tfoot>\n");
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+// Printable page with a single table
+pub(crate) fn printable_table(
+    company: &str,
+    title: &str,
+    details: &[String],
+    headers: &[&str],
+    numeric: &[usize],
+    rows: &[Vec<String>],
+    totals: Option<&[String]>,
+) -> String {
+    printable_page(company, title, details, &[html_table(headers, numeric, rows, totals)])
+}
+
+// Escapes a value for a vCard text property (RFC 6350 section 3.4)
+fn vcard_escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace(';', "\\;")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+// Appends a content line, folding it at 75 octets as vCard requires
+fn push_vcard_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for ch in line.chars() {
+        if width + ch.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += ch.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+// Small JPEG for the contact picture; phones do not need the full photo
+fn contact_photo(app_dir: &Path, image_path: &str) -> Option<Vec<u8>> {
+    if let Ok(thumb) = fs::read(app_dir.join(images::thumbnail_path_for(image_path))) {
+        return Some(thumb);
+    }
+    let original = fs::read(app_dir.join(image_path)).ok()?;
+    images::make_thumbnail(&original).ok()
+}
+
+fn employee_vcard(employee: &Employee, app_dir: &Path) -> String {
+    let mut card = String::new();
+    push_vcard_line(&mut card, "BEGIN:VCARD");
+    push_vcard_line(&mut card, "VERSION:3.0");
+    push_vcard_line(&mut card, &format!("FN:{}", vcard_escape(&employee.name_with_initials)));
+    push_vcard_line(&mut card, &format!("N:{};;;;", vcard_escape(&employee.full_name)));
+    push_vcard_line(&mut card, "ORG:Newlanka");
+    if let Some(designation) = employee.designation.as_deref().filter(|d| !d.is_empty()) {
+        push_vcard_line(&mut card, &format!("TITLE:{}", vcard_escape(designation)));
+    }
+    if let Some(department) = employee.department.as_deref().filter(|d| !d.is_empty()) {
+        push_vcard_line(&mut card, &format!("NOTE:{} department\\, EPF {}", vcard_escape(department), vcard_escape(&employee.epf_number)));
+    }
+    for mobile in [&employee.mobile_1, &employee.mobile_2].into_iter().flatten() {
+        if !mobile.trim().is_empty() {
+            push_vcard_line(&mut card, &format!("TEL;TYPE=CELL:{}", vcard_escape(mobile.trim())));
+        }
+    }
+    if let Some(photo) = employee
+        .image_path
+        .as_deref()
+        .filter(|p| !p.is_empty())
+        .and_then(|p| contact_photo(app_dir, p))
+    {
+        push_vcard_line(
+            &mut card,
+            &format!("PHOTO;ENCODING=b;TYPE=JPEG:{}", general_purpose::STANDARD.encode(&photo)),
+        );
+    }
+    push_vcard_line(&mut card, "END:VCARD");
+    card
+}
+
+// Writes a .vcf with one contact per employee so supervisors can load their
+// team onto a phone. Returns the number of contacts written.
+#[tauri::command]
+pub fn export_vcards(
+    filters: EmployeeFilters,
+    destination_path: String,
+    app_data_dir: State<'_, AppDataDir>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<usize, HrmError> {
+    let user = require_export_permission(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let employees = accessible_employees(&conn, filters, &user)?;
+    
+    let vcf: String = employees
+        .iter()
+        .map(|e| employee_vcard(e, &app_data_dir.0))
+        .collect();
+    fs::write(&destination_path, vcf).map_err(|e| HrmError::io(format!("Failed to save file: {}", e)))?;
+    
+    let epf_numbers: Vec<String> = employees.iter().map(|e| e.epf_number.clone()).collect();
+    log_data_export(&conn, Some(user.user_id), &user.username, "vcf", &epf_numbers);
+    
+    Ok(employees.len())
+}
+
+// Writes the filtered employee list as CSV. Columns the user may not see are
+// kept but redacted so the file layout does not depend on who exported it.
+// Returns the number of rows written.
+#[tauri::command]
+pub fn export_employees_csv(
+    filters: EmployeeFilters,
+    columns: Vec<String>,
+    destination_path: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<usize, HrmError> {
+    let user = require_export_permission(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    
+    // Custom fields are addressed as "custom:<name>"
+    let mut available: Vec<(String, String)> = EXPORT_COLUMNS
+        .iter()
+        .map(|(field, header)| (field.to_string(), header.to_string()))
+        .collect();
+    let mut date_columns = vec!["dob".to_string(), "date_of_join".to_string(), "date_of_resign".to_string()];
+    for definition in load_definitions(&conn, true)? {
+        if definition.field_type == "date" {
+            date_columns.push(format!("custom:{}", definition.name));
+        }
+        available.push((format!("custom:{}", definition.name), definition.name));
+    }
+    
+    let selected: Vec<(String, String)> = if columns.is_empty() {
+        available
+    } else {
+        columns
+            .iter()
+            .map(|key| {
+                available
+                    .iter()
+                    .find(|(field, _)| field == key)
+                    .cloned()
+                    .ok_or_else(|| format!("Unknown export column: {}", key))
+            })
+            .collect::<Result<_, _>>()?
+    };
+    
+    let employees = accessible_employees(&conn, filters, &user)?;
+    // Dates follow the date_format setting. Buddhist Era years are left out so
+    // the file can be imported again.
+    let (date_format, _) = date_display(&conn);
+    
+    // UTF-8 BOM so Excel picks the right encoding for Sinhala and Tamil names
+    let mut csv = String::from("\u{FEFF}");
+    let headers: Vec<String> = selected.iter().map(|(_, header)| csv_field(header)).collect();
+    csv.push_str(&headers.join(","));
+    csv.push_str("\r\n");
+    
+    for employee in &employees {
+        let record = serde_json::to_value(employee)?;
+        let row: Vec<String> = selected
+            .iter()
+            .map(|(field, _)| {
+                if SENSITIVE_COLUMNS.contains(&field.as_str()) && !user.permissions.can_view_sensitive_data {
+                    return REDACTED.to_string();
+                }
+                let value = match field.strip_prefix("custom:") {
+                    Some(name) => employee.custom_fields.get(name).map(String::as_str).unwrap_or(""),
+                    None => record.get(field.as_str()).and_then(|v| v.as_str()).unwrap_or(""),
+                };
+                if date_columns.contains(field) {
+                    return csv_field(&format_date(value, &date_format, false));
+                }
+                csv_field(value)
+            })
+            .collect();
+        csv.push_str(&row.join(","));
+        csv.push_str("\r\n");
+    }
+    
+    fs::write(&destination_path, csv).map_err(|e| HrmError::io(format!("Failed to save file: {}", e)))?;
+    
+    let epf_numbers: Vec<String> = employees.iter().map(|e| e.epf_number.clone()).collect();
+    log_data_export(&conn, Some(user.user_id), &user.username, "csv", &epf_numbers);
+    
+    Ok(employees.len())
+}
@@ -0,0 +1,353 @@
+use crate::approvals::{check_approver, may_approve, open_approval, record_decision};
+use crate::commands::log_audit_action;
+use crate::deduction_commands::{ensure_deduction_type, insert_deduction, require_month};
+use crate::error::HrmError;
+use crate::models::{EmployeeDeduction, FestivalAdvance, UserSession};
+use crate::payroll_commands::ensure_open_from;
+use crate::settings_commands::get_setting_i64;
+use crate::validation::{months_between, parse_iso_date};
+use crate::working_status_commands::counts_as_active;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use tauri::State;
+
+// Defaults when the festival_advance_* settings are not set
+const DEFAULT_MIN_SERVICE_MONTHS: i64 = 6;
+const DEFAULT_AMOUNT_CAP: i64 = 10000;
+const DEFAULT_MAX_INSTALLMENTS: i64 = 10;
+
+// Deduction type that advances are recovered under
+const RECOVERY_DEDUCTION: &str = "Festival Advance";
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+const ADVANCE_COLUMNS: &str = "a.id, a.epf_number, e.name_with_initials, a.festival, a.amount, a.installments,
+                               a.status, a.requested_on, a.first_recovery_month, a.approved_by, a.approved_at,
+                               a.rejection_reason";
+
+fn advance_from_row(row: &rusqlite::Row) -> rusqlite::Result<FestivalAdvance> {
+    Ok(FestivalAdvance {
+        id: row.get(0)?,
+        epf_number: row.get(1)?,
+        name_with_initials: row.get(2)?,
+        festival: row.get(3)?,
+        amount: row.get(4)?,
+        installments: row.get(5)?,
+        status: row.get(6)?,
+        requested_on: row.get(7)?,
+        first_recovery_month: row.get(8)?,
+        approved_by: row.get(9)?,
+        approved_at: row.get(10)?,
+        rejection_reason: row.get(11)?,
+    })
+}
+
+fn query_advance(conn: &Connection, advance_id: i64) -> Result<FestivalAdvance, HrmError> {
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM festival_advances a LEFT JOIN employees e ON e.epf_number = a.epf_number WHERE a.id = ?1",
+            ADVANCE_COLUMNS
+        ),
+        [advance_id],
+        advance_from_row,
+    )
+    .map_err(|_| HrmError::not_found("Festival advance not found"))
+}
+
+// Checks service length, the amount cap, the installment limit and that no
+// earlier advance is still being recovered
+fn check_eligibility(conn: &Connection, epf_number: &str, amount: f64, installments: i64) -> Result<(), HrmError> {
+    let min_service = get_setting_i64(conn, "festival_advance_min_service_months").unwrap_or(DEFAULT_MIN_SERVICE_MONTHS);
+    let cap = get_setting_i64(conn, "festival_advance_cap").unwrap_or(DEFAULT_AMOUNT_CAP) as f64;
+    let max_installments = get_setting_i64(conn, "festival_advance_max_installments").unwrap_or(DEFAULT_MAX_INSTALLMENTS);
+    
+    if amount <= 0.0 {
+        return Err(HrmError::validation("Amount must be greater than zero"));
+    }
+    if amount > cap {
+        return Err(HrmError::validation(format!("Festival advances are capped at {:.2}", cap)));
+    }
+    if installments < 1 || installments > max_installments {
+        return Err(HrmError::validation(format!("Recovery must be over 1 to {} months", max_installments)));
+    }
+    
+    let (working_status, date_of_join, today): (String, Option<String>, String) = conn
+        .query_row(
+            "SELECT working_status, date_of_join, date('now', 'localtime') FROM employees WHERE epf_number = ?1",
+            [epf_number],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|_| HrmError::not_found(format!("Employee {} not found", epf_number)))?;
+    if !counts_as_active(conn, &working_status)? {
+        return Err(HrmError::validation(format!("Employee {} is not active", epf_number)));
+    }
+    let joined = date_of_join
+        .as_deref()
+        .and_then(parse_iso_date)
+        .ok_or_else(|| format!("Employee {} has no valid date of joining", epf_number))?;
+    let service_months = parse_iso_date(&today).map(|t| months_between(joined, t)).unwrap_or(0);
+    if service_months < min_service {
+        return Err(HrmError::validation(format!(
+            "Employee {} has {} months of service; {} are required",
+            epf_number, service_months, min_service
+        )));
+    }
+    
+    let outstanding: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM festival_advances a
+             WHERE a.epf_number = ?1 AND (a.status = 'pending' OR (a.status = 'approved' AND EXISTS (
+                 SELECT 1 FROM employee_deductions d WHERE d.festival_advance_id = a.id
+                   AND (d.end_date IS NULL OR d.end_date >= date('now', 'localtime')))))",
+            [epf_number],
+            |row| row.get(0),
+        )?;
+    if outstanding {
+        return Err(HrmError::validation(format!("Employee {} already has a festival advance pending or being recovered", epf_number)));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_festival_advances(
+    status: Option<String>,
+    epf_number: Option<String>,
+    db: State<'_, DbConnection>,
+) -> Result<Vec<FestivalAdvance>, HrmError> {
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM festival_advances a LEFT JOIN employees e ON e.epf_number = a.epf_number
+             WHERE (?1 IS NULL OR a.status = ?1) AND (?2 IS NULL OR a.epf_number = ?2)
+             ORDER BY a.requested_on DESC, a.id DESC",
+            ADVANCE_COLUMNS
+        ))?;
+    let advances = stmt
+        .query_map(rusqlite::params![status, epf_number], advance_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(advances)
+}
+
+#[tauri::command]
+pub fn request_festival_advance(
+    epf_number: String,
+    festival: String,
+    amount: f64,
+    installments: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit employees."));
+    }
+    if festival.trim().is_empty() {
+        return Err(HrmError::validation("Festival is required"));
+    }
+    
+    let conn = db.0.lock()?;
+    check_eligibility(&conn, &epf_number, amount, installments)?;
+    
+    conn.execute(
+        "INSERT INTO festival_advances (epf_number, festival, amount, installments, status, requested_on, requested_by)
+         VALUES (?1, ?2, ?3, ?4, 'pending', date('now', 'localtime'), ?5)",
+        rusqlite::params![epf_number, festival.trim(), amount, installments, user.username],
+    )?;
+    let advance_id = conn.last_insert_rowid();
+    open_approval(
+        &conn,
+        "festival_advance",
+        &advance_id.to_string(),
+        Some(&epf_number),
+        &format!("{} advance of {:.2} over {} months for {}", festival.trim(), amount, installments, epf_number),
+        &user,
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "FESTIVAL_ADVANCE",
+        Some(&epf_number),
+        None,
+        Some(&format!("{:.2} over {} months", amount, installments)),
+        Some(&format!("Requested {} advance of {:.2} for employee {}", festival.trim(), amount, epf_number)),
+    );
+    
+    Ok(advance_id)
+}
+
+// Approves an advance and schedules its recovery as monthly deductions from
+// first_recovery_month (YYYY-MM). Any rounding is taken in the last month.
+#[tauri::command]
+pub fn approve_festival_advance(
+    advance_id: i64,
+    first_recovery_month: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_month(&first_recovery_month)?;
+    
+    let mut conn = db.0.lock()?;
+    if !may_approve(&conn, &user, "can_run_payroll")? {
+        return Err(HrmError::permission_denied("Permission denied. You cannot approve advances."));
+    }
+    let advance = query_advance(&conn, advance_id)?;
+    if advance.status != "pending" {
+        return Err(HrmError::validation(format!("This advance has already been {}", advance.status)));
+    }
+    
+    let entity_id = advance_id.to_string();
+    let final_step = check_approver(&conn, "festival_advance", &entity_id, &user)?;
+    // Recovery deductions run from first_recovery_month onwards
+    if final_step {
+        ensure_open_from(&conn, &first_recovery_month)?;
+    }
+    
+    let tx = conn.transaction()?;
+    record_decision(&tx, "festival_advance", &entity_id, &user, true, None)?;
+    if !final_step {
+        log_audit_action(
+            &tx,
+            Some(user.user_id),
+            &user.username,
+            "APPROVE",
+            "FESTIVAL_ADVANCE",
+            Some(&advance.epf_number),
+            Some("pending"),
+            Some("pending"),
+            Some(&format!(
+                "Approved {} advance of {:.2} for employee {}; passed to the next approver",
+                advance.festival, advance.amount, advance.epf_number
+            )),
+        );
+        tx.commit()?;
+        return Ok(());
+    }
+    let deduction_type_id = ensure_deduction_type(&tx, RECOVERY_DEDUCTION, None)?;
+    
+    let installment = (advance.amount * 100.0 / advance.installments as f64).floor() / 100.0;
+    let last_installment = ((advance.amount - installment * (advance.installments - 1) as f64) * 100.0).round() / 100.0;
+    let month_end = |offset: i64| -> Result<String, HrmError> {
+        tx.query_row(
+            "SELECT date(?1 || '-01', '+' || ?2 || ' months', '-1 day')",
+            rusqlite::params![first_recovery_month, offset],
+            |row| row.get(0),
+        )
+        .map_err(HrmError::from)
+    };
+    
+    let start_date = format!("{}-01", first_recovery_month);
+    let schedule = if last_installment == installment || advance.installments == 1 {
+        vec![(start_date, month_end(advance.installments)?, last_installment)]
+    } else {
+        let last_start: String = tx
+            .query_row(
+                "SELECT date(?1 || '-01', '+' || ?2 || ' months')",
+                rusqlite::params![first_recovery_month, advance.installments - 1],
+                |row| row.get(0),
+            )?;
+        vec![
+            (start_date, month_end(advance.installments - 1)?, installment),
+            (last_start, month_end(advance.installments)?, last_installment),
+        ]
+    };
+    
+    for (start_date, end_date, amount) in schedule {
+        let deduction_id = insert_deduction(
+            &tx,
+            &EmployeeDeduction {
+                id: 0,
+                epf_number: advance.epf_number.clone(),
+                deduction_type_id,
+                deduction_type: RECOVERY_DEDUCTION.to_string(),
+                amount,
+                start_date,
+                end_date: Some(end_date),
+                notes: Some(format!("{} advance #{}", advance.festival, advance_id)),
+            },
+            &user.username,
+        )?;
+        tx.execute(
+            "UPDATE employee_deductions SET festival_advance_id = ?1 WHERE id = ?2",
+            rusqlite::params![advance_id, deduction_id],
+        )?;
+    }
+    
+    tx.execute(
+        "UPDATE festival_advances SET status = 'approved', first_recovery_month = ?1,
+                approved_by = ?2, approved_at = CURRENT_TIMESTAMP
+         WHERE id = ?3",
+        rusqlite::params![first_recovery_month, user.username, advance_id],
+    )?;
+    
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "APPROVE",
+        "FESTIVAL_ADVANCE",
+        Some(&advance.epf_number),
+        Some("pending"),
+        Some("approved"),
+        Some(&format!(
+            "Approved {} advance of {:.2} for employee {}, recovered from {} over {} months",
+            advance.festival, advance.amount, advance.epf_number, first_recovery_month, advance.installments
+        )),
+    );
+    tx.commit()?;
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn reject_festival_advance(
+    advance_id: i64,
+    reason: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if reason.trim().is_empty() {
+        return Err(HrmError::validation("A reason is required"));
+    }
+    
+    let mut conn = db.0.lock()?;
+    if !may_approve(&conn, &user, "can_run_payroll")? {
+        return Err(HrmError::permission_denied("Permission denied. You cannot approve advances."));
+    }
+    let advance = query_advance(&conn, advance_id)?;
+    if advance.status != "pending" {
+        return Err(HrmError::validation(format!("This advance has already been {}", advance.status)));
+    }
+    let tx = conn.transaction()?;
+    check_approver(&tx, "festival_advance", &advance_id.to_string(), &user)?;
+    record_decision(&tx, "festival_advance", &advance_id.to_string(), &user, false, Some(reason.trim()))?;
+    
+    tx.execute(
+        "UPDATE festival_advances SET status = 'rejected', rejection_reason = ?1,
+                approved_by = ?2, approved_at = CURRENT_TIMESTAMP
+         WHERE id = ?3",
+        rusqlite::params![reason.trim(), user.username, advance_id],
+    )?;
+    
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "REJECT",
+        "FESTIVAL_ADVANCE",
+        Some(&advance.epf_number),
+        Some("pending"),
+        Some("rejected"),
+        Some(&format!("Rejected {} advance for employee {}: {}", advance.festival, advance.epf_number, reason.trim())),
+    );
+    tx.commit()?;
+    
+    Ok(())
+}
@@ -0,0 +1,313 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{Grievance, PendingGrievance, UserSession};
+use crate::notification_commands::notify_user;
+use crate::settings_commands::get_setting_i64;
+use crate::validation::parse_iso_date;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use tauri::State;
+
+// Days a grievance may stay open before it is flagged as overdue, unless
+// overridden by the grievance_sla_days setting
+const DEFAULT_SLA_DAYS: i64 = 14;
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+fn require_edit(user: &UserSession) -> Result<(), HrmError> {
+    if user.permissions.can_edit_employees {
+        Ok(())
+    } else {
+        Err(HrmError::permission_denied("Permission denied. You cannot manage grievances."))
+    }
+}
+
+// Confidential grievances are only visible to user managers
+fn can_see(user: &UserSession, is_confidential: bool) -> bool {
+    !is_confidential || user.permissions.can_manage_users
+}
+
+// open -> investigating -> resolved/closed, and resolved -> closed
+fn allowed_transition(from: &str, to: &str) -> bool {
+    matches!(
+        (from, to),
+        ("open", "investigating") | ("open", "closed") | ("investigating", "resolved") | ("investigating", "closed") | ("resolved", "closed")
+    )
+}
+
+const GRIEVANCE_COLUMNS: &str = "g.id, g.epf_number, g.subject, g.description, g.category, g.raised_on, g.status,
+                                 g.assigned_to, u.full_name, g.is_confidential, g.resolution, g.resolved_at,
+                                 g.recorded_by, g.created_at, g.updated_at";
+
+fn grievance_from_row(row: &rusqlite::Row) -> rusqlite::Result<Grievance> {
+    Ok(Grievance {
+        id: row.get(0)?,
+        epf_number: row.get(1)?,
+        subject: row.get(2)?,
+        description: row.get(3)?,
+        category: row.get(4)?,
+        raised_on: row.get(5)?,
+        status: row.get(6)?,
+        assigned_to: row.get(7)?,
+        assigned_to_name: row.get(8)?,
+        is_confidential: row.get(9)?,
+        resolution: row.get(10)?,
+        resolved_at: row.get(11)?,
+        recorded_by: row.get(12)?,
+        created_at: row.get(13)?,
+        updated_at: row.get(14)?,
+    })
+}
+
+// Loads a grievance, hiding confidential ones from users who may not see them
+fn query_grievance(conn: &Connection, grievance_id: i64, user: &UserSession) -> Result<Grievance, HrmError> {
+    let grievance = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM grievances g LEFT JOIN users u ON u.id = g.assigned_to WHERE g.id = ?1",
+                GRIEVANCE_COLUMNS
+            ),
+            [grievance_id],
+            grievance_from_row,
+        )
+        .map_err(|_| HrmError::not_found("Grievance not found"))?;
+    if !can_see(user, grievance.is_confidential) {
+        return Err(HrmError::not_found("Grievance not found"));
+    }
+    Ok(grievance)
+}
+
+#[tauri::command]
+pub fn get_grievances(
+    status: Option<String>,
+    epf_number: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<Grievance>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_edit(&user)?;
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM grievances g LEFT JOIN users u ON u.id = g.assigned_to
+             WHERE (?1 IS NULL OR g.status = ?1) AND (?2 IS NULL OR g.epf_number = ?2)
+               AND (g.is_confidential = 0 OR ?3)
+             ORDER BY g.raised_on DESC, g.id DESC",
+            GRIEVANCE_COLUMNS
+        ))?;
+    let grievances = stmt
+        .query_map(
+            rusqlite::params![status, epf_number, user.permissions.can_manage_users],
+            grievance_from_row,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(grievances)
+}
+
+#[tauri::command]
+pub fn create_grievance(
+    grievance: Grievance,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_edit(&user)?;
+    if grievance.subject.trim().is_empty() {
+        return Err(HrmError::validation("Subject is required"));
+    }
+    parse_iso_date(&grievance.raised_on).ok_or("Raised date must be a valid date (YYYY-MM-DD)")?;
+    if grievance.is_confidential && !user.permissions.can_manage_users {
+        return Err(HrmError::validation("Only user managers can record confidential grievances"));
+    }
+    
+    let conn = db.0.lock()?;
+    conn.execute(
+        "INSERT INTO grievances (epf_number, subject, description, category, raised_on, status, is_confidential, recorded_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, 'open', ?6, ?7)",
+        rusqlite::params![
+            grievance.epf_number,
+            grievance.subject.trim(),
+            grievance.description,
+            grievance.category,
+            grievance.raised_on,
+            grievance.is_confidential,
+            user.username,
+        ],
+    )?;
+    let grievance_id = conn.last_insert_rowid();
+    
+    // Keep confidential details out of the audit trail
+    let details = if grievance.is_confidential {
+        "Recorded confidential grievance".to_string()
+    } else {
+        format!("Recorded grievance: {}", grievance.subject.trim())
+    };
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "GRIEVANCE",
+        Some(&grievance_id.to_string()),
+        None,
+        None,
+        Some(&details),
+    );
+    
+    Ok(grievance_id)
+}
+
+// Assigns a grievance to an HR user, who is notified
+#[tauri::command]
+pub fn assign_grievance(
+    grievance_id: i64,
+    assigned_to: i32,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_edit(&user)?;
+    
+    let conn = db.0.lock()?;
+    let grievance = query_grievance(&conn, grievance_id, &user)?;
+    if grievance.status == "resolved" || grievance.status == "closed" {
+        return Err(HrmError::validation("Cannot reassign a grievance that is already resolved or closed"));
+    }
+    
+    let (full_name, can_edit, can_manage_users): (String, bool, bool) = conn
+        .query_row(
+            "SELECT full_name, can_edit_employees, can_manage_users FROM users
+             WHERE id = ?1 AND is_active = 1 AND deleted_at IS NULL",
+            [assigned_to],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|_| HrmError::not_found("User not found or inactive"))?;
+    if !can_edit {
+        return Err(HrmError::validation(format!("{} cannot handle grievances", full_name)));
+    }
+    if grievance.is_confidential && !can_manage_users {
+        return Err(HrmError::validation(format!("{} cannot see confidential grievances", full_name)));
+    }
+    
+    conn.execute(
+        "UPDATE grievances SET assigned_to = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2",
+        rusqlite::params![assigned_to, grievance_id],
+    )?;
+    
+    notify_user(
+        &conn,
+        assigned_to,
+        "Grievance assigned",
+        &format!("Grievance #{} has been assigned to you", grievance_id),
+        "info",
+        Some("GRIEVANCE"),
+        Some(&grievance_id.to_string()),
+    );
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "ASSIGN",
+        "GRIEVANCE",
+        Some(&grievance_id.to_string()),
+        grievance.assigned_to_name.as_deref(),
+        Some(&full_name),
+        Some(&format!("Assigned grievance #{} to {}", grievance_id, full_name)),
+    );
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn update_grievance_status(
+    grievance_id: i64,
+    status: String,
+    resolution: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_edit(&user)?;
+    
+    let conn = db.0.lock()?;
+    let grievance = query_grievance(&conn, grievance_id, &user)?;
+    if !allowed_transition(&grievance.status, &status) {
+        return Err(HrmError::validation(format!("Cannot move a grievance from {} to {}", grievance.status, status)));
+    }
+    let resolution = resolution.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+    if status == "resolved" && resolution.is_none() {
+        return Err(HrmError::validation("Describe how the grievance was resolved"));
+    }
+    
+    conn.execute(
+        "UPDATE grievances SET status = ?1, resolution = COALESCE(?2, resolution),
+                resolved_at = CASE WHEN ?1 IN ('resolved', 'closed') THEN COALESCE(resolved_at, CURRENT_TIMESTAMP) ELSE resolved_at END,
+                updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?3",
+        rusqlite::params![status, resolution, grievance_id],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "GRIEVANCE",
+        Some(&grievance_id.to_string()),
+        Some(&grievance.status),
+        Some(&status),
+        Some(&format!("Grievance #{} moved from {} to {}", grievance_id, grievance.status, status)),
+    );
+    
+    Ok(())
+}
+
+// Open and investigating grievances with their age against the SLA
+#[tauri::command]
+pub fn get_pending_grievances_report(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<PendingGrievance>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_reports {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view reports."));
+    }
+    
+    let conn = db.0.lock()?;
+    let sla_days = get_setting_i64(&conn, "grievance_sla_days").unwrap_or(DEFAULT_SLA_DAYS);
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT g.id, g.epf_number, e.name_with_initials, g.subject, g.status, u.full_name, g.raised_on,
+                    CAST(julianday('now', 'localtime') - julianday(g.raised_on) AS INTEGER), g.is_confidential
+             FROM grievances g
+             LEFT JOIN employees e ON e.epf_number = g.epf_number
+             LEFT JOIN users u ON u.id = g.assigned_to
+             WHERE g.status IN ('open', 'investigating') AND (g.is_confidential = 0 OR ?1)
+             ORDER BY g.raised_on, g.id",
+        )?;
+    let report = stmt
+        .query_map([user.permissions.can_manage_users], |row| {
+            let age_days: i64 = row.get(7)?;
+            Ok(PendingGrievance {
+                id: row.get(0)?,
+                epf_number: row.get(1)?,
+                name_with_initials: row.get(2)?,
+                subject: row.get(3)?,
+                status: row.get(4)?,
+                assigned_to_name: row.get(5)?,
+                raised_on: row.get(6)?,
+                age_days,
+                sla_days,
+                is_overdue: age_days > sla_days,
+                is_confidential: row.get(8)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    Ok(report)
+}
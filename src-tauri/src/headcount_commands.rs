@@ -0,0 +1,271 @@
+use crate::commands::log_audit_action;
+use crate::deduction_commands::require_month;
+use crate::error::HrmError;
+use crate::models::{HeadcountBudget, HeadcountSnapshot, HeadcountVariance, UserSession};
+use crate::validation::parse_iso_date;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::{Connection, OptionalExtension};
+use tauri::State;
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+const BUDGET_COLUMNS: &str = "id, department, cader, budgeted_count, effective_from, effective_to, created_by";
+
+fn budget_from_row(row: &rusqlite::Row) -> rusqlite::Result<HeadcountBudget> {
+    Ok(HeadcountBudget {
+        id: row.get(0)?,
+        department: row.get(1)?,
+        cader: row.get(2)?,
+        budgeted_count: row.get(3)?,
+        effective_from: row.get(4)?,
+        effective_to: row.get(5)?,
+        created_by: row.get(6)?,
+    })
+}
+
+// Budgets in force on a date (YYYY-MM-DD)
+fn budgets_on(conn: &Connection, date: &str) -> rusqlite::Result<Vec<HeadcountBudget>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM headcount_budgets
+         WHERE effective_from <= ?1 AND (effective_to IS NULL OR effective_to >= ?1)
+         ORDER BY department, cader",
+        BUDGET_COLUMNS
+    ))?;
+    let budgets = stmt.query_map([date], budget_from_row)?.collect::<Result<Vec<_>, _>>()?;
+    Ok(budgets)
+}
+
+#[tauri::command]
+pub fn get_headcount_budgets(db: State<'_, DbConnection>) -> Result<Vec<HeadcountBudget>, HrmError> {
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM headcount_budgets ORDER BY department, cader, effective_from DESC",
+            BUDGET_COLUMNS
+        ))?;
+    let budgets = stmt
+        .query_map([], budget_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(budgets)
+}
+
+// Budgets are per department, optionally narrowed to one cader. A department
+// or department/cader pair can only have one budget in force at a time.
+#[tauri::command]
+pub fn create_headcount_budget(
+    budget: HeadcountBudget,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_manage_settings {
+        return Err(HrmError::permission_denied("Permission denied. You cannot change system settings."));
+    }
+    let department = budget.department.trim();
+    if department.is_empty() {
+        return Err(HrmError::validation("Department is required"));
+    }
+    let cader = budget.cader.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    if budget.budgeted_count < 0 {
+        return Err(HrmError::validation("Budgeted headcount cannot be negative"));
+    }
+    parse_iso_date(&budget.effective_from).ok_or("Effective from must be a valid date (YYYY-MM-DD)")?;
+    if let Some(to) = &budget.effective_to {
+        parse_iso_date(to).ok_or("Effective to must be a valid date (YYYY-MM-DD)")?;
+        if *to < budget.effective_from {
+            return Err(HrmError::validation("Effective to cannot be before effective from"));
+        }
+    }
+    
+    let conn = db.0.lock()?;
+    let overlapping: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM headcount_budgets
+             WHERE department = ?1 AND COALESCE(cader, '') = COALESCE(?2, '')
+               AND effective_from <= COALESCE(?4, '9999-12-31')
+               AND COALESCE(effective_to, '9999-12-31') >= ?3
+             LIMIT 1",
+            rusqlite::params![department, cader, budget.effective_from, budget.effective_to],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if overlapping.is_some() {
+        return Err(HrmError::validation("Another budget for this department and cader covers part of the same period"));
+    }
+    
+    conn.execute(
+        "INSERT INTO headcount_budgets (department, cader, budgeted_count, effective_from, effective_to, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![department, cader, budget.budgeted_count, budget.effective_from, budget.effective_to, user.username],
+    )?;
+    let id = conn.last_insert_rowid();
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "HEADCOUNT_BUDGET",
+        Some(&id.to_string()),
+        None,
+        Some(&budget.budgeted_count.to_string()),
+        Some(&format!(
+            "Headcount budget of {} for {}{} from {}",
+            budget.budgeted_count,
+            department,
+            cader.map(|c| format!(" / {}", c)).unwrap_or_default(),
+            budget.effective_from
+        )),
+    );
+    
+    Ok(id)
+}
+
+#[tauri::command]
+pub fn delete_headcount_budget(
+    budget_id: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_manage_settings {
+        return Err(HrmError::permission_denied("Permission denied. You cannot change system settings."));
+    }
+    
+    let conn = db.0.lock()?;
+    let deleted = conn
+        .execute("DELETE FROM headcount_budgets WHERE id = ?1", [budget_id])?;
+    if deleted == 0 {
+        return Err(HrmError::not_found("Headcount budget not found"));
+    }
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "DELETE",
+        "HEADCOUNT_BUDGET",
+        Some(&budget_id.to_string()),
+        None,
+        None,
+        Some(&format!("Deleted headcount budget #{}", budget_id)),
+    );
+    
+    Ok(())
+}
+
+// Budgets in force on `as_of` (default today) against the live count of
+// active employees. A negative variance is a vacancy to fill.
+#[tauri::command]
+pub fn get_headcount_variance_report(
+    as_of: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<HeadcountVariance>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_reports {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view reports."));
+    }
+    if let Some(date) = &as_of {
+        parse_iso_date(date).ok_or("Date must be a valid date (YYYY-MM-DD)")?;
+    }
+    
+    let conn = db.0.lock()?;
+    let as_of = match as_of {
+        Some(date) => date,
+        None => conn
+            .query_row("SELECT date('now', 'localtime')", [], |row| row.get(0))?,
+    };
+    
+    let mut report = Vec::new();
+    for budget in budgets_on(&conn, &as_of)? {
+        let actual: i32 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM employees
+                 WHERE department = ?1 AND (?2 IS NULL OR cader = ?2)
+                   AND working_status IN (SELECT code FROM working_statuses WHERE counts_as_active = 1)",
+                rusqlite::params![budget.department, budget.cader],
+                |row| row.get(0),
+            )?;
+        report.push(HeadcountVariance {
+            department: budget.department,
+            cader: budget.cader,
+            budgeted_count: budget.budgeted_count,
+            actual_count: actual,
+            variance: actual - budget.budgeted_count,
+        });
+    }
+    Ok(report)
+}
+
+// Stores this month's headcount per department, cader and working status,
+// once per month, so past months can be reported as they were even after
+// records are edited or deleted. Run by the scheduler; returns rows stored.
+pub fn snapshot_headcount(conn: &Connection) -> Result<usize, HrmError> {
+    let month: String = conn
+        .query_row("SELECT strftime('%Y-%m', 'now', 'localtime')", [], |row| row.get(0))?;
+    let taken: bool = conn
+        .query_row("SELECT COUNT(*) > 0 FROM headcount_snapshots WHERE month = ?1", [&month], |row| row.get(0))?;
+    if taken {
+        return Ok(0);
+    }
+    
+    conn.execute(
+        "INSERT INTO headcount_snapshots (month, department, cader, working_status, headcount)
+         SELECT ?1, COALESCE(NULLIF(department, ''), 'Unassigned'), COALESCE(NULLIF(cader, ''), 'Unassigned'),
+                working_status, COUNT(*)
+         FROM employees
+         GROUP BY 2, 3, 4",
+        [&month],
+    )
+    .map_err(HrmError::from)
+}
+
+// Months that have a stored snapshot, newest first
+#[tauri::command]
+pub fn get_headcount_snapshot_months(db: State<'_, DbConnection>) -> Result<Vec<String>, HrmError> {
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare("SELECT DISTINCT month FROM headcount_snapshots ORDER BY month DESC")?;
+    let months = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(months)
+}
+
+#[tauri::command]
+pub fn get_headcount_snapshot(
+    month: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<HeadcountSnapshot>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_reports {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view reports."));
+    }
+    require_month(&month)?;
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT month, department, cader, working_status, headcount, taken_at
+             FROM headcount_snapshots WHERE month = ?1
+             ORDER BY department, cader, working_status",
+        )?;
+    let snapshot = stmt
+        .query_map([&month], |row| {
+            Ok(HeadcountSnapshot {
+                month: row.get(0)?,
+                department: row.get(1)?,
+                cader: row.get(2)?,
+                working_status: row.get(3)?,
+                headcount: row.get(4)?,
+                taken_at: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(snapshot)
+}
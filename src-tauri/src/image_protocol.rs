@@ -0,0 +1,119 @@
+use crate::images;
+use crate::{AppDataDir, CurrentUser};
+use std::fs;
+use std::path::{Component, Path};
+use std::time::UNIX_EPOCH;
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{AppHandle, Manager};
+
+// Custom URI scheme that serves employee photos straight from disk so the
+// webview can load them with a plain <img src>, instead of shipping base64
+// through JSON. URLs look like:
+//   hrm-image://localhost/employee_images/1234/photo_20240101_120000.jpg   (macOS/Linux)
+//   http://hrm-image.localhost/employee_images/1234/photo_20240101_120000.jpg (Windows)
+pub const SCHEME: &str = "hrm-image";
+
+fn percent_decode(input: &str) -> Option<String> {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = input.get(i + 1..i + 3)?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+fn empty_response(status: StatusCode) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .body(Vec::new())
+        .unwrap_or_default()
+}
+
+pub fn handle_request(app_handle: &AppHandle, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let relative_path = match percent_decode(request.uri().path().trim_start_matches('/')) {
+        Some(path) => path,
+        None => return empty_response(StatusCode::BAD_REQUEST),
+    };
+    
+    // Photos are only for a logged-in user who can view employees
+    let logged_in = match app_handle.try_state::<CurrentUser>() {
+        Some(current_user) => current_user
+            .0
+            .lock()
+            .map(|user| user.as_ref().is_some_and(|u| u.permissions.can_view_employees))
+            .unwrap_or(false),
+        None => false,
+    };
+    if !logged_in {
+        return empty_response(StatusCode::UNAUTHORIZED);
+    }
+    
+    // Only photos inside the employee image store may be served: plain
+    // forward-slash relative paths, with no backslashes, drive prefixes,
+    // roots or parent components
+    if !relative_path.starts_with("employee_images/")
+        || relative_path.contains('\\')
+        || !Path::new(&relative_path).components().all(|c| matches!(c, Component::Normal(_)))
+    {
+        return empty_response(StatusCode::FORBIDDEN);
+    }
+    
+    let (image_root, full_path) = match app_handle.try_state::<AppDataDir>() {
+        Some(app_dir) => (app_dir.0.join("employee_images"), app_dir.0.join(&relative_path)),
+        None => return empty_response(StatusCode::SERVICE_UNAVAILABLE),
+    };
+    // Links and anything else that resolves outside the store are refused
+    let full_path = match (image_root.canonicalize(), full_path.canonicalize()) {
+        (Ok(root), Ok(path)) if path.starts_with(&root) => path,
+        (Ok(_), Ok(_)) => return empty_response(StatusCode::FORBIDDEN),
+        _ => return empty_response(StatusCode::NOT_FOUND),
+    };
+    let metadata = match fs::metadata(&full_path) {
+        Ok(metadata) if metadata.is_file() => metadata,
+        _ => return empty_response(StatusCode::NOT_FOUND),
+    };
+    
+    // Weak validator from size + modification time; photo files are rarely rewritten
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let etag = format!("W/\"{:x}-{:x}\"", metadata.len(), modified);
+    
+    let not_modified = request
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v == etag)
+        .unwrap_or(false);
+    if not_modified {
+        return Response::builder()
+            .status(StatusCode::NOT_MODIFIED)
+            .header(header::ETAG, &etag)
+            .body(Vec::new())
+            .unwrap_or_default();
+    }
+    
+    let bytes = match fs::read(&full_path) {
+        Ok(bytes) => bytes,
+        Err(_) => return empty_response(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, images::mime_type_for_path(&relative_path))
+        .header(header::CACHE_CONTROL, "private, max-age=86400")
+        .header(header::ETAG, &etag)
+        .body(bytes)
+        .unwrap_or_else(|_| empty_response(StatusCode::INTERNAL_SERVER_ERROR))
+}
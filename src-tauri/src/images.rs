@@ -0,0 +1,112 @@
+use crate::error::HrmError;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::metadata::Orientation;
+use image::{DynamicImage, ExtendedColorType, GenericImageView, ImageDecoder, ImageReader};
+use sha2::{Digest, Sha256};
+use std::io::Cursor;
+
+// Defaults used when the image settings haven't been configured
+pub const DEFAULT_MAX_DIMENSION: u32 = 1024;
+pub const DEFAULT_QUALITY: u8 = 85;
+
+// Thumbnails are small square-bounded JPEGs used by list views
+pub const THUMBNAIL_SIZE: u32 = 128;
+pub const THUMBNAIL_QUALITY: u8 = 75;
+pub const THUMBNAIL_FILENAME: &str = "thumb.jpg";
+
+// Stored photos are always JPEG at the configured quality
+pub const PHOTO_EXTENSION: &str = "jpg";
+
+pub struct ProcessedImage {
+    pub bytes: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub original_width: u32,
+    pub original_height: u32,
+}
+
+// Hex SHA-256 of the uploaded bytes, used to spot duplicate uploads
+pub fn content_hash(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+// Decodes an image and bakes the EXIF orientation into the pixels, since the
+// EXIF block itself is dropped when we re-encode
+fn decode_upright(data: &[u8]) -> Result<DynamicImage, HrmError> {
+    let mut decoder = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .map_err(|e| HrmError::validation(format!("Unsupported image: {}", e)))?
+        .into_decoder()
+        .map_err(|e| HrmError::validation(format!("Unsupported image: {}", e)))?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let mut img = DynamicImage::from_decoder(decoder).map_err(|e| HrmError::validation(format!("Unsupported image: {}", e)))?;
+    img.apply_orientation(orientation);
+    Ok(img)
+}
+
+// Downscales an uploaded photo so neither side exceeds `max_dimension` and
+// re-encodes it from raw pixels. Only pixel data is written back out, so EXIF
+// metadata such as GPS position and camera/device details is stripped.
+pub fn compress_image(
+    data: &[u8],
+    max_dimension: u32,
+    quality: u8,
+) -> Result<ProcessedImage, HrmError> {
+    let img = decode_upright(data)?;
+    let (original_width, original_height) = img.dimensions();
+    
+    let img = if original_width > max_dimension || original_height > max_dimension {
+        img.resize(max_dimension, max_dimension, FilterType::Lanczos3)
+    } else {
+        img
+    };
+    let (width, height) = img.dimensions();
+    
+    // JPEG has no alpha channel
+    let rgb = img.to_rgb8();
+    let mut bytes = Vec::new();
+    JpegEncoder::new_with_quality(&mut bytes, quality.clamp(1, 100))
+        .encode(rgb.as_raw(), width, height, ExtendedColorType::Rgb8)
+        .map_err(|e| HrmError::internal(format!("Failed to encode image: {}", e)))?;
+    
+    Ok(ProcessedImage {
+        bytes,
+        width,
+        height,
+        original_width,
+        original_height,
+    })
+}
+
+pub fn make_thumbnail(data: &[u8]) -> Result<Vec<u8>, HrmError> {
+    let img = image::load_from_memory(data).map_err(|e| HrmError::validation(format!("Unsupported image: {}", e)))?;
+    let thumb = img.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE).to_rgb8();
+    
+    let mut bytes = Vec::new();
+    JpegEncoder::new_with_quality(&mut bytes, THUMBNAIL_QUALITY)
+        .encode(thumb.as_raw(), thumb.width(), thumb.height(), ExtendedColorType::Rgb8)
+        .map_err(|e| HrmError::internal(format!("Failed to encode thumbnail: {}", e)))?;
+    Ok(bytes)
+}
+
+// Thumbnail location for a stored photo: same employee folder, fixed name
+pub fn thumbnail_path_for(image_path: &str) -> String {
+    match image_path.rsplit_once('/') {
+        Some((folder, _)) => format!("{}/{}", folder, THUMBNAIL_FILENAME),
+        None => THUMBNAIL_FILENAME.to_string(),
+    }
+}
+
+pub fn mime_type_for_path(path: &str) -> &'static str {
+    if path.ends_with(".png") {
+        "image/png"
+    } else if path.ends_with(".webp") {
+        "image/webp"
+    } else {
+        "image/jpeg"
+    }
+}
@@ -0,0 +1,375 @@
+use crate::commands::{insert_employee, log_audit_action};
+use crate::error::HrmError;
+use crate::export_commands::{csv_field, EXPORT_COLUMNS};
+use crate::master_list_commands::load_master_list;
+use crate::models::{Employee, LegacyImportReport, LegacyImportRowError};
+use crate::validation::{normalize_date, validate_employee};
+use crate::working_status_commands::load_statuses;
+use crate::xlsx::{column_name, write_xlsx, Cell, CellStyle, Dropdown, Worksheet};
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use tauri::State;
+
+// Columns an import file must fill in for every row
+const REQUIRED_COLUMNS: [&str; 4] = ["epf_number", "name_with_initials", "full_name", "working_status"];
+
+// Rows of the spreadsheet template that get dropdown validation
+const TEMPLATE_ROWS: usize = 1000;
+
+// Sample rows shown below the headers, in EXPORT_COLUMNS order
+const EXAMPLE_ROWS: [[&str; 21]; 2] = [
+    [
+        "1001", "A.B. Perera", "Ashan Bandara Perera", "199517201234", "Production", "Staff", "Machine Operator",
+        "Line A", "2024-01-15", "active", "1995-06-20", "Single", "0771234567", "",
+        "Route 1", "Katunayake", "12 Temple Road, Seeduwa", "Gampaha", "Katana", "", "",
+    ],
+    [
+        "1002", "K.M. Silva", "Kumari Malini Silva", "905421234V", "Quality", "Executive", "QC Inspector",
+        "Line B", "2023-09-01", "resign", "1990-02-11", "Married", "0712345678", "0112233445",
+        "Route 3", "Negombo", "45 Lake View, Ja-Ela", "Gampaha", "Ja-Ela", "", "2025-03-31",
+    ],
+];
+
+fn get_district_names(conn: &Connection) -> Result<Vec<String>, HrmError> {
+    let mut stmt = conn.prepare("SELECT name FROM districts ORDER BY name")?;
+    let names = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(names)
+}
+
+pub(crate) fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn template_csv() -> String {
+    let mut csv = String::from("\u{FEFF}");
+    let headers: Vec<String> = EXPORT_COLUMNS.iter().map(|(_, header)| csv_field(header)).collect();
+    csv.push_str(&headers.join(","));
+    csv.push_str("\r\n");
+    for example in EXAMPLE_ROWS.iter() {
+        let row: Vec<String> = example.iter().map(|v| csv_field(v)).collect();
+        csv.push_str(&row.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+// Employees sheet with dropdowns on the listed columns, fed from a separate
+// "Lists" sheet
+fn template_workbook(lists: &[(&str, Vec<String>)]) -> Vec<Worksheet> {
+    let mut rows = vec![EXPORT_COLUMNS
+        .iter()
+        .map(|(field, header)| {
+            let style = if REQUIRED_COLUMNS.contains(field) { CellStyle::Required } else { CellStyle::Header };
+            Cell::text(*header, style)
+        })
+        .collect::<Vec<_>>()];
+    for example in EXAMPLE_ROWS.iter() {
+        rows.push(example.iter().map(|value| Cell::text(*value, CellStyle::Example)).collect());
+    }
+    let dropdowns = lists
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, values))| !values.is_empty())
+        .filter_map(|(list_index, (field, values))| {
+            let column = EXPORT_COLUMNS.iter().position(|(f, _)| f == field)?;
+            let source = column_name(list_index);
+            Some(Dropdown {
+                column,
+                last_row: TEMPLATE_ROWS,
+                source: format!("Lists!${0}$2:${0}${1}", source, values.len() + 1),
+            })
+        })
+        .collect();
+    
+    // Dropdown source values, one column per validated field
+    let longest = lists.iter().map(|(_, values)| values.len()).max().unwrap_or(0);
+    let mut list_rows = vec![lists.iter().map(|(field, _)| Cell::text(*field, CellStyle::Header)).collect::<Vec<_>>()];
+    for row in 0..longest {
+        list_rows.push(
+            lists
+                .iter()
+                .map(|(_, values)| Cell::text(values.get(row).cloned().unwrap_or_default(), CellStyle::Plain))
+                .collect(),
+        );
+    }
+    
+    vec![
+        Worksheet { name: "Employees".to_string(), rows, dropdowns },
+        Worksheet { name: "Lists".to_string(), rows: list_rows, dropdowns: Vec::new() },
+    ]
+}
+
+// Writes a blank bulk-import file with the expected headers and example rows.
+// `format` is "csv" or "xlsx"; the workbook adds dropdowns for department and
+// designation (from their master lists), district and working status.
+#[tauri::command]
+pub fn generate_import_template(
+    format: String,
+    destination_path: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_add_employees => {}
+        _ => return Err(HrmError::permission_denied("Permission denied. You cannot import employees.")),
+    }
+    drop(user_lock);
+    
+    match format.as_str() {
+        "csv" => fs::write(&destination_path, template_csv())
+            .map_err(|e| HrmError::io(format!("Failed to save file: {}", e)))?,
+        "xlsx" => {
+            let conn = db.0.lock()?;
+            let lists = vec![
+                ("department", load_master_list(&conn, "department")?),
+                ("designation", load_master_list(&conn, "designation")?),
+                ("district", get_district_names(&conn)?),
+                (
+                    "working_status",
+                    load_statuses(&conn)?.into_iter().map(|s| s.code).collect(),
+                ),
+            ];
+            drop(conn);
+            write_xlsx(&destination_path, &template_workbook(&lists))?;
+        }
+        _ => return Err(HrmError::validation(format!("Unsupported template format: {}", format))),
+    }
+    Ok(())
+}
+
+// Splits CSV text into records, handling quoted fields with embedded commas,
+// quotes and line breaks. Returns (line number, fields) per record.
+pub(crate) fn parse_csv(text: &str) -> Vec<(usize, Vec<String>)> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = text.trim_start_matches('\u{FEFF}').chars().peekable();
+    
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.trim().is_empty()) {
+                    records.push((record_line, std::mem::take(&mut record)));
+                }
+                record.clear();
+                line += 1;
+                record_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+    record.push(field);
+    if record.iter().any(|f| !f.trim().is_empty()) {
+        records.push((record_line, record));
+    }
+    records
+}
+
+fn normalize_working_status(value: &str, has_resign_date: bool) -> Result<String, String> {
+    match value.trim().to_lowercase().as_str() {
+        "" if has_resign_date => Ok("resign".to_string()),
+        "" | "active" | "working" | "yes" | "1" => Ok("active".to_string()),
+        "resign" | "resigned" | "left" | "terminated" | "inactive" | "no" | "0" => Ok("resign".to_string()),
+        // Installation-defined statuses are resolved when the row is validated
+        other => Ok(other.to_string()),
+    }
+}
+
+const DATE_FIELDS: [&str; 3] = ["dob", "date_of_join", "date_of_resign"];
+
+// Builds an employee from one register row using the header-to-field mapping
+fn employee_from_row(values: &HashMap<&str, String>) -> Result<Employee, String> {
+    let text = |field: &str| values.get(field).map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+    
+    let mut dates = HashMap::new();
+    for field in DATE_FIELDS.iter() {
+        if let Some(raw) = text(field) {
+            let date = normalize_date(&raw).ok_or_else(|| format!("Unrecognised date '{}' in {}", raw, field))?;
+            dates.insert(*field, date);
+        }
+    }
+    
+    let epf_number = text("epf_number").ok_or("Missing EPF number")?;
+    let name_with_initials = text("name_with_initials").or_else(|| text("full_name")).ok_or("Missing name")?;
+    let full_name = text("full_name").unwrap_or_else(|| name_with_initials.clone());
+    let working_status = normalize_working_status(
+        values.get("working_status").map(String::as_str).unwrap_or(""),
+        dates.contains_key("date_of_resign"),
+    )?;
+    
+    Ok(Employee {
+        epf_number,
+        name_with_initials,
+        full_name,
+        nic: text("nic"),
+        dob: dates.remove("dob"),
+        police_area: text("police_area"),
+        transport_route: text("transport_route"),
+        mobile_1: text("mobile_1"),
+        mobile_2: text("mobile_2"),
+        address: text("address"),
+        date_of_join: dates.remove("date_of_join"),
+        date_of_resign: dates.remove("date_of_resign"),
+        working_status,
+        marital_status: text("marital_status"),
+        cader: text("cader"),
+        designation: text("designation"),
+        allocation: text("allocation"),
+        department: text("department"),
+        image_path: None,
+        previous_epf_number: None,
+        district: text("district"),
+        ds_division: text("ds_division"),
+        gn_division: text("gn_division"),
+        created_at: None,
+        custom_fields: HashMap::new(),
+        age: None,
+        service_years: None,
+    })
+}
+
+// Migrates an Excel-kept HR register saved as CSV. `column_mapping` maps each
+// source header to an employee field; unmapped headers are ignored and
+// reported. Rows that cannot be converted are skipped with a reason, and the
+// rest are inserted in a single transaction. With `dry_run` nothing is saved.
+#[tauri::command]
+pub fn migrate_legacy_register(
+    file_path: String,
+    column_mapping: HashMap<String, String>,
+    dry_run: bool,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<LegacyImportReport, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    let (user_id, username) = match &*user_lock {
+        Some(session) if session.permissions.can_add_employees => (session.user_id, session.username.clone()),
+        _ => return Err(HrmError::permission_denied("Permission denied. You cannot import employees.")),
+    };
+    drop(user_lock);
+    
+    let known_fields: HashSet<&str> = EXPORT_COLUMNS.iter().map(|(field, _)| *field).collect();
+    for field in column_mapping.values() {
+        if !known_fields.contains(field.as_str()) {
+            return Err(HrmError::validation(format!("Unknown employee field in mapping: {}", field)));
+        }
+    }
+    if !column_mapping.values().any(|f| f == "epf_number") {
+        return Err(HrmError::validation("The EPF number column must be mapped"));
+    }
+    
+    let bytes = fs::read(&file_path).map_err(|e| HrmError::io(format!("Failed to read file: {}", e)))?;
+    let text = String::from_utf8(bytes)
+        .map_err(|_| "The register must be saved as UTF-8 CSV (Excel: Save As > CSV UTF-8)".to_string())?;
+    let mut records = parse_csv(&text).into_iter();
+    let (_, headers) = records.next().ok_or("The file is empty")?;
+    let headers: Vec<String> = headers.iter().map(|h| h.trim().to_string()).collect();
+    
+    let unmapped_columns: Vec<String> = headers
+        .iter()
+        .filter(|h| !h.is_empty() && !column_mapping.contains_key(*h))
+        .cloned()
+        .collect();
+    
+    let mut conn = db.0.lock()?;
+    let tx = conn.transaction()?;
+    
+    let mut total_rows = 0;
+    let mut imported = 0;
+    let mut skipped = Vec::new();
+    let mut seen_epf = HashSet::new();
+    
+    for (line, fields) in records {
+        total_rows += 1;
+        let mut values: HashMap<&str, String> = HashMap::new();
+        for (header, value) in headers.iter().zip(fields) {
+            if let Some(field) = column_mapping.get(header) {
+                values.insert(field.as_str(), value);
+            }
+        }
+        let epf_hint = values.get("epf_number").map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        
+        let mut employee = match employee_from_row(&values) {
+            Ok(employee) => employee,
+            Err(reason) => {
+                skipped.push(LegacyImportRowError { row: line, epf_number: epf_hint, reason });
+                continue;
+            }
+        };
+        if let Err(errors) = validate_employee(&tx, &mut employee) {
+            skipped.push(LegacyImportRowError { row: line, epf_number: epf_hint, reason: errors.to_string() });
+            continue;
+        }
+        
+        if !seen_epf.insert(employee.epf_number.clone()) {
+            skipped.push(LegacyImportRowError { row: line, epf_number: epf_hint, reason: "Duplicate EPF number in file".to_string() });
+            continue;
+        }
+        
+        if let Err(e) = insert_employee(&tx, &employee) {
+            let reason = if e.to_string().contains("UNIQUE constraint") {
+                "EPF number already exists".to_string()
+            } else {
+                e.to_string()
+            };
+            skipped.push(LegacyImportRowError { row: line, epf_number: epf_hint, reason });
+            continue;
+        }
+        imported += 1;
+    }
+    
+    if dry_run {
+        tx.rollback()?;
+    } else {
+        log_audit_action(
+            &tx,
+            Some(user_id),
+            &username,
+            "IMPORT",
+            "EMPLOYEE",
+            None,
+            None,
+            None,
+            Some(&format!("Migrated {} employees from legacy register ({} rows skipped)", imported, skipped.len())),
+        );
+        tx.commit()?;
+    }
+    
+    Ok(LegacyImportReport {
+        total_rows,
+        imported,
+        skipped,
+        unmapped_columns,
+        dry_run,
+    })
+}
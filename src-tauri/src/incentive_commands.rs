@@ -0,0 +1,338 @@
+use crate::commands::log_audit_action;
+use crate::deduction_commands::require_month;
+use crate::error::HrmError;
+use crate::import_commands::parse_csv;
+use crate::models::{AttendanceSummary, LegacyImportRowError, PieceRate, ProductionEntry, ProductionImportReport, UserSession};
+use crate::payroll_commands::ensure_month_open;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use tauri::State;
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+// Incentive earnings per employee for a month: their own quantities times the
+// piece rate, plus a share of their production line's quantities. A line's
+// earnings are split by days worked among the employees allocated to it.
+pub(crate) fn incentives_for_month(
+    conn: &Connection,
+    month: &str,
+    attendance: &[AttendanceSummary],
+) -> Result<HashMap<String, f64>, HrmError> {
+    let rates = conn
+        .prepare("SELECT product_code, rate FROM piece_rates")?
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?)))?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    let mut stmt = conn.prepare(
+        "SELECT epf_number, line, product_code, SUM(quantity) FROM production_entries
+         WHERE month = ?1 GROUP BY epf_number, line, product_code",
+    )?;
+    let entries = stmt
+        .query_map([month], |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, f64>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    let mut incentives: HashMap<String, f64> = HashMap::new();
+    for (epf_number, line, product_code, quantity) in entries {
+        let earnings = quantity * rates.get(&product_code).copied().unwrap_or(0.0);
+        if let Some(epf_number) = epf_number {
+            *incentives.entry(epf_number).or_default() += earnings;
+        } else if let Some(line) = line {
+            let members: Vec<&AttendanceSummary> = attendance
+                .iter()
+                .filter(|a| a.worked_days > 0)
+                .filter(|a| a.allocation.as_deref().is_some_and(|a| a.trim().eq_ignore_ascii_case(line.trim())))
+                .collect();
+            let days: i64 = members.iter().map(|a| a.worked_days).sum();
+            for member in members {
+                let share = earnings * member.worked_days as f64 / days as f64;
+                *incentives.entry(member.epf_number.clone()).or_default() += share;
+            }
+        }
+    }
+    Ok(incentives.into_iter().map(|(epf, amount)| (epf, round2(amount))).collect())
+}
+
+#[tauri::command]
+pub fn get_piece_rates(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<PieceRate>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_salaries && !user.permissions.can_run_payroll {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view piece rates."));
+    }
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, product_code, description, rate, is_active, updated_by, updated_at FROM piece_rates ORDER BY product_code",
+    )?;
+    let rates = stmt
+        .query_map([], |row| {
+            Ok(PieceRate {
+                id: row.get(0)?,
+                product_code: row.get(1)?,
+                description: row.get(2)?,
+                rate: row.get(3)?,
+                is_active: row.get(4)?,
+                updated_by: row.get(5)?,
+                updated_at: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rates)
+}
+
+// Adds a piece rate or updates the one with the same product code. A rate
+// change applies to every month not yet locked.
+#[tauri::command]
+pub fn set_piece_rate(
+    product_code: String,
+    description: Option<String>,
+    rate: f64,
+    is_active: bool,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_run_payroll {
+        return Err(HrmError::permission_denied("Permission denied. You cannot set piece rates."));
+    }
+    let product_code = product_code.trim().to_uppercase();
+    if product_code.is_empty() {
+        return Err(HrmError::field("product_code", "Product code is required"));
+    }
+    if !rate.is_finite() || rate < 0.0 {
+        return Err(HrmError::field("rate", "Rate must be zero or more"));
+    }
+    
+    let conn = db.0.lock()?;
+    conn.execute(
+        "INSERT INTO piece_rates (product_code, description, rate, is_active, updated_by, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, CURRENT_TIMESTAMP)
+         ON CONFLICT(product_code) DO UPDATE SET description = excluded.description, rate = excluded.rate,
+             is_active = excluded.is_active, updated_by = excluded.updated_by, updated_at = CURRENT_TIMESTAMP",
+        rusqlite::params![product_code, description, rate, is_active, user.username],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "INCENTIVE",
+        Some(&product_code),
+        None,
+        Some(&format!("{:.4}", rate)),
+        Some(&format!("Set piece rate for {}", product_code)),
+    );
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_production_entries(
+    month: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<ProductionEntry>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view production quantities."));
+    }
+    require_month(&month)?;
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(
+        "SELECT p.id, p.month, p.epf_number, p.line, p.product_code, p.quantity, u.username, p.created_at
+         FROM production_entries p
+         LEFT JOIN users u ON u.id = p.entered_by
+         WHERE p.month = ?1
+         ORDER BY p.line, p.epf_number, p.product_code",
+    )?;
+    let entries = stmt
+        .query_map([&month], |row| {
+            Ok(ProductionEntry {
+                id: row.get(0)?,
+                month: row.get(1)?,
+                epf_number: row.get(2)?,
+                line: row.get(3)?,
+                product_code: row.get(4)?,
+                quantity: row.get(5)?,
+                entered_by: row.get(6)?,
+                created_at: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(entries)
+}
+
+// Imports production quantities for a month from a CSV with a product_code
+// and quantity column and either an epf_number or a line column per row.
+// Rows for a line are shared among the employees allocated to it when payroll
+// is calculated. With `replace`, the month's earlier entries are removed
+// first. Nothing is saved on a dry run.
+#[tauri::command]
+pub fn import_production_quantities(
+    month: String,
+    file_path: String,
+    replace: bool,
+    dry_run: bool,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<ProductionImportReport, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot import production quantities."));
+    }
+    require_month(&month)?;
+    
+    let bytes = fs::read(&file_path).map_err(|e| HrmError::io(format!("Failed to read file: {}", e)))?;
+    let text = String::from_utf8(bytes)
+        .map_err(|_| "The file must be saved as UTF-8 CSV (Excel: Save As > CSV UTF-8)".to_string())?;
+    let mut records = parse_csv(&text).into_iter();
+    let (_, headers) = records.next().ok_or("The file is empty")?;
+    let headers: Vec<String> = headers.iter().map(|h| h.trim().to_lowercase().replace(' ', "_")).collect();
+    let column = |name: &str| headers.iter().position(|h| h == name);
+    let (Some(product_column), Some(quantity_column)) = (column("product_code"), column("quantity")) else {
+        return Err(HrmError::validation("The file needs product_code and quantity columns"));
+    };
+    let epf_column = column("epf_number");
+    let line_column = column("line");
+    if epf_column.is_none() && line_column.is_none() {
+        return Err(HrmError::validation("The file needs an epf_number or a line column"));
+    }
+    
+    let mut conn = db.0.lock()?;
+    ensure_month_open(&conn, &month)?;
+    let active_codes: HashSet<String> = conn
+        .prepare("SELECT product_code FROM piece_rates WHERE is_active = 1")?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    let employees: HashSet<String> = conn
+        .prepare("SELECT epf_number FROM employees")?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    let lines: HashSet<String> = conn
+        .prepare("SELECT DISTINCT lower(trim(allocation)) FROM employees WHERE allocation IS NOT NULL AND trim(allocation) != ''")?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    
+    let tx = conn.transaction()?;
+    if replace {
+        tx.execute("DELETE FROM production_entries WHERE month = ?1", [&month])?;
+    }
+    let mut total_rows = 0;
+    let mut imported = 0;
+    let mut skipped = Vec::new();
+    for (row, fields) in records {
+        total_rows += 1;
+        let value = |index: Option<usize>| {
+            index
+                .and_then(|i| fields.get(i))
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let epf_number = value(epf_column);
+        let line = value(line_column);
+        let product_code = value(Some(product_column)).map(|c| c.to_uppercase());
+        let quantity = value(Some(quantity_column)).map(|q| q.replace(',', "").parse::<f64>());
+    
+        let reason = match (&epf_number, &line, &product_code, &quantity) {
+            (None, None, _, _) => Some("Either an EPF number or a line is required".to_string()),
+            (Some(_), Some(_), _, _) => Some("Give an EPF number or a line, not both".to_string()),
+            (Some(epf), None, _, _) if !employees.contains(epf) => Some("Employee not found".to_string()),
+            (None, Some(l), _, _) if !lines.contains(&l.to_lowercase()) => {
+                Some(format!("No employees are allocated to line {}", l))
+            }
+            (_, _, None, _) => Some("Product code is required".to_string()),
+            (_, _, Some(code), _) if !active_codes.contains(code) => Some(format!("No active piece rate for {}", code)),
+            (_, _, _, Some(Ok(q))) if q.is_finite() && *q > 0.0 => None,
+            _ => Some("Quantity must be a positive number".to_string()),
+        };
+        if let Some(reason) = reason {
+            skipped.push(LegacyImportRowError { row, epf_number, reason });
+            continue;
+        }
+        tx.execute(
+            "INSERT INTO production_entries (month, epf_number, line, product_code, quantity, entered_by)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![month, epf_number, line, product_code, quantity.and_then(Result::ok), user.user_id],
+        )?;
+        imported += 1;
+    }
+    
+    if dry_run {
+        tx.rollback()?;
+    } else {
+        log_audit_action(
+            &tx,
+            Some(user.user_id),
+            &user.username,
+            "IMPORT",
+            "INCENTIVE",
+            Some(&month),
+            None,
+            None,
+            Some(&format!(
+                "Imported {} production entries for {} ({} rows skipped{})",
+                imported,
+                month,
+                skipped.len(),
+                if replace { ", earlier entries replaced" } else { "" }
+            )),
+        );
+        tx.commit()?;
+    }
+    
+    Ok(ProductionImportReport { total_rows, imported, skipped, dry_run })
+}
+
+#[tauri::command]
+pub fn delete_production_entry(
+    id: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit production quantities."));
+    }
+    
+    let conn = db.0.lock()?;
+    let (month, product_code): (String, String) = conn
+        .query_row("SELECT month, product_code FROM production_entries WHERE id = ?1", [id], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .map_err(|_| HrmError::not_found("Production entry not found"))?;
+    ensure_month_open(&conn, &month)?;
+    conn.execute("DELETE FROM production_entries WHERE id = ?1", [id])?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "DELETE",
+        "INCENTIVE",
+        Some(&month),
+        Some(&product_code),
+        None,
+        Some(&format!("Deleted production entry {} for {} in {}", id, product_code, month)),
+    );
+    
+    Ok(())
+}
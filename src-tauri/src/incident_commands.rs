@@ -0,0 +1,324 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{Incident, IncidentSummary, UserSession};
+use crate::validation::parse_iso_date;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use tauri::State;
+
+const SEVERITIES: [&str; 4] = ["minor", "moderate", "major", "fatal"];
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+fn validate_incident(incident: &Incident) -> Result<(), HrmError> {
+    parse_iso_date(&incident.incident_date).ok_or("Incident date must be a valid date (YYYY-MM-DD)")?;
+    if !SEVERITIES.contains(&incident.severity.as_str()) {
+        return Err(HrmError::validation("Severity must be minor, moderate, major or fatal"));
+    }
+    if incident.location.trim().is_empty() {
+        return Err(HrmError::validation("Location is required"));
+    }
+    if incident.description.trim().is_empty() {
+        return Err(HrmError::validation("Description is required"));
+    }
+    if let Some(date) = incident.labour_dept_reported_on.as_deref().filter(|d| !d.is_empty()) {
+        parse_iso_date(date).ok_or("Labour department report date must be a valid date (YYYY-MM-DD)")?;
+    }
+    Ok(())
+}
+
+fn string_list(conn: &Connection, sql: &str, incident_id: i64) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(sql)?;
+    let values = stmt
+        .query_map([incident_id], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(values)
+}
+
+// Fills in injured employees and attachments for each incident
+fn attach_details(conn: &Connection, incidents: &mut [Incident]) -> rusqlite::Result<()> {
+    for incident in incidents.iter_mut() {
+        incident.injured_employees = string_list(
+            conn,
+            "SELECT epf_number FROM incident_injuries WHERE incident_id = ?1 ORDER BY epf_number",
+            incident.id,
+        )?;
+        incident.attachments = string_list(
+            conn,
+            "SELECT file_path FROM incident_attachments WHERE incident_id = ?1 ORDER BY id",
+            incident.id,
+        )?;
+    }
+    Ok(())
+}
+
+// Replaces the injured employee and attachment lists of an incident
+fn save_details(conn: &Connection, incident_id: i64, incident: &Incident) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM incident_injuries WHERE incident_id = ?1", [incident_id])?;
+    conn.execute("DELETE FROM incident_attachments WHERE incident_id = ?1", [incident_id])?;
+    for epf_number in &incident.injured_employees {
+        conn.execute(
+            "INSERT OR IGNORE INTO incident_injuries (incident_id, epf_number) VALUES (?1, ?2)",
+            rusqlite::params![incident_id, epf_number.trim()],
+        )?;
+    }
+    for file_path in incident.attachments.iter().filter(|p| !p.trim().is_empty()) {
+        conn.execute(
+            "INSERT INTO incident_attachments (incident_id, file_path) VALUES (?1, ?2)",
+            rusqlite::params![incident_id, file_path.trim()],
+        )?;
+    }
+    Ok(())
+}
+
+const INCIDENT_COLUMNS: &str = "id, incident_date, location, severity, description,
+                                reported_to_labour_dept, labour_dept_reported_on, recorded_by, created_at";
+
+fn incident_from_row(row: &rusqlite::Row) -> rusqlite::Result<Incident> {
+    Ok(Incident {
+        id: row.get(0)?,
+        incident_date: row.get(1)?,
+        location: row.get(2)?,
+        severity: row.get(3)?,
+        description: row.get(4)?,
+        reported_to_labour_dept: row.get(5)?,
+        labour_dept_reported_on: row.get(6)?,
+        injured_employees: Vec::new(),
+        attachments: Vec::new(),
+        recorded_by: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+fn query_incident(conn: &Connection, incident_id: i64) -> Result<Incident, HrmError> {
+    let mut incident = conn
+        .query_row(
+            &format!("SELECT {} FROM incidents WHERE id = ?1", INCIDENT_COLUMNS),
+            [incident_id],
+            incident_from_row,
+        )
+        .map_err(|_| HrmError::not_found("Incident not found"))?;
+    attach_details(conn, std::slice::from_mut(&mut incident))?;
+    Ok(incident)
+}
+
+#[tauri::command]
+pub fn get_incidents(
+    year: Option<i32>,
+    epf_number: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<Incident>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_employees {
+        return Err(HrmError::permission_denied("Permission denied"));
+    }
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM incidents
+             WHERE (?1 IS NULL OR strftime('%Y', incident_date) = printf('%04d', ?1))
+               AND (?2 IS NULL OR id IN (SELECT incident_id FROM incident_injuries WHERE epf_number = ?2))
+             ORDER BY incident_date DESC, id DESC",
+            INCIDENT_COLUMNS
+        ))?;
+    let mut incidents = stmt
+        .query_map(rusqlite::params![year, epf_number], incident_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    attach_details(&conn, &mut incidents)?;
+    Ok(incidents)
+}
+
+#[tauri::command]
+pub fn create_incident(
+    incident: Incident,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit employees."));
+    }
+    validate_incident(&incident)?;
+    
+    let mut conn = db.0.lock()?;
+    let tx = conn.transaction()?;
+    
+    tx.execute(
+        "INSERT INTO incidents (incident_date, location, severity, description,
+                                reported_to_labour_dept, labour_dept_reported_on, recorded_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![
+            incident.incident_date,
+            incident.location.trim(),
+            incident.severity,
+            incident.description.trim(),
+            incident.reported_to_labour_dept,
+            incident.labour_dept_reported_on,
+            user.username,
+        ],
+    )?;
+    let incident_id = tx.last_insert_rowid();
+    save_details(&tx, incident_id, &incident)?;
+    
+    let new_value = serde_json::to_string(&incident).ok();
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "INCIDENT",
+        Some(&incident_id.to_string()),
+        None,
+        new_value.as_deref(),
+        Some(&format!("Reported {} incident at {} on {}", incident.severity, incident.location.trim(), incident.incident_date)),
+    );
+    tx.commit()?;
+    
+    Ok(incident_id)
+}
+
+#[tauri::command]
+pub fn update_incident(
+    incident: Incident,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit employees."));
+    }
+    validate_incident(&incident)?;
+    
+    let mut conn = db.0.lock()?;
+    let old = query_incident(&conn, incident.id)?;
+    let tx = conn.transaction()?;
+    
+    tx.execute(
+        "UPDATE incidents SET incident_date = ?1, location = ?2, severity = ?3, description = ?4,
+                              reported_to_labour_dept = ?5, labour_dept_reported_on = ?6
+         WHERE id = ?7",
+        rusqlite::params![
+            incident.incident_date,
+            incident.location.trim(),
+            incident.severity,
+            incident.description.trim(),
+            incident.reported_to_labour_dept,
+            incident.labour_dept_reported_on,
+            incident.id,
+        ],
+    )?;
+    save_details(&tx, incident.id, &incident)?;
+    
+    let old_value = serde_json::to_string(&old).ok();
+    let new_value = serde_json::to_string(&incident).ok();
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "INCIDENT",
+        Some(&incident.id.to_string()),
+        old_value.as_deref(),
+        new_value.as_deref(),
+        Some(&format!("Updated incident #{}", incident.id)),
+    );
+    tx.commit()?;
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_incident(
+    incident_id: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_delete_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot delete records."));
+    }
+    
+    let mut conn = db.0.lock()?;
+    let old = query_incident(&conn, incident_id)?;
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM incident_injuries WHERE incident_id = ?1", [incident_id])?;
+    tx.execute("DELETE FROM incident_attachments WHERE incident_id = ?1", [incident_id])?;
+    tx.execute("DELETE FROM incidents WHERE id = ?1", [incident_id])?;
+    
+    let old_value = serde_json::to_string(&old).ok();
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "DELETE",
+        "INCIDENT",
+        Some(&incident_id.to_string()),
+        old_value.as_deref(),
+        None,
+        Some(&format!("Deleted incident #{} of {}", incident_id, old.incident_date)),
+    );
+    tx.commit()?;
+    
+    Ok(())
+}
+
+// Yearly figures for compliance returns and insurance claims
+#[tauri::command]
+pub fn get_incident_summary(
+    year: i32,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<IncidentSummary, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_reports {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view reports."));
+    }
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT CAST(strftime('%m', i.incident_date) AS INTEGER), i.severity, i.reported_to_labour_dept,
+                    (SELECT COUNT(*) FROM incident_injuries j WHERE j.incident_id = i.id)
+             FROM incidents i
+             WHERE strftime('%Y', i.incident_date) = printf('%04d', ?1)",
+        )?;
+    let rows = stmt
+        .query_map([year], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, bool>(2)?, row.get::<_, i32>(3)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    let mut by_month = vec![0; 12];
+    let mut by_severity: HashMap<String, i32> = SEVERITIES.iter().map(|s| (s.to_string(), 0)).collect();
+    let mut injured_count = 0;
+    let mut reported_count = 0;
+    let mut unreported_serious = 0;
+    for (month, severity, reported, injured) in &rows {
+        if (1..=12).contains(month) {
+            by_month[(*month - 1) as usize] += 1;
+        }
+        *by_severity.entry(severity.clone()).or_default() += 1;
+        injured_count += injured;
+        if *reported {
+            reported_count += 1;
+        } else if severity == "major" || severity == "fatal" {
+            unreported_serious += 1;
+        }
+    }
+    
+    Ok(IncidentSummary {
+        year,
+        total_incidents: rows.len() as i32,
+        by_severity,
+        by_month,
+        injured_count,
+        reported_to_labour_dept: reported_count,
+        unreported_serious,
+    })
+}
@@ -0,0 +1,37 @@
+use crate::error::HrmError;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+
+// Held for the life of the process. The operating system releases the lock
+// when the process exits, even after a crash, so it never goes stale.
+static LOCK_FILE: OnceLock<File> = OnceLock::new();
+const LOCK_FILENAME: &str = "hrm_system.lock";
+
+// Takes an exclusive lock next to the database so a second copy of the app
+// (e.g. started by another Windows user on the same PC, which the
+// single-instance plugin does not see) cannot write to it or run the
+// scheduler at the same time
+pub fn acquire(app_handle: &AppHandle) -> Result<(), HrmError> {
+    let app_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| HrmError::io(format!("Failed to get app data directory: {}", e)))?;
+    fs::create_dir_all(&app_dir)?;
+    
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(app_dir.join(LOCK_FILENAME))?;
+    match file.try_lock() {
+        Ok(()) => {
+            let _ = LOCK_FILE.set(file);
+            Ok(())
+        }
+        Err(TryLockError::WouldBlock) => Err(HrmError::conflict(
+            "HRM System is already running on this computer. Close the other window before starting it again.",
+        )),
+        Err(TryLockError::Error(e)) => Err(e.into()),
+    }
+}
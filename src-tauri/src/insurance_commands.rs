@@ -0,0 +1,490 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{InsuranceClaim, InsuranceDependent, InsuranceEnrollment, InsurancePolicy, PolicyClaimsSummary, UserSession};
+use crate::validation::parse_iso_date;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use tauri::State;
+
+const CLAIM_STATUSES: [&str; 4] = ["submitted", "approved", "rejected", "paid"];
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+fn require_edit(user: &UserSession) -> Result<(), HrmError> {
+    if user.permissions.can_edit_employees {
+        Ok(())
+    } else {
+        Err(HrmError::permission_denied("Permission denied. You cannot edit employees."))
+    }
+}
+
+fn require_date(value: &str, label: &str) -> Result<(), HrmError> {
+    parse_iso_date(value)
+        .map(|_| ())
+        .ok_or_else(|| HrmError::validation(format!("{} must be a valid date (YYYY-MM-DD)", label)))
+}
+
+#[tauri::command]
+pub fn get_insurance_policies(db: State<'_, DbConnection>) -> Result<Vec<InsurancePolicy>, HrmError> {
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT p.id, p.policy_number, p.insurer, p.policy_type, p.start_date, p.end_date, p.premium, p.notes,
+                    (SELECT COUNT(*) FROM insurance_enrollments n WHERE n.policy_id = p.id AND n.ended_on IS NULL)
+             FROM insurance_policies p
+             ORDER BY p.start_date DESC, p.policy_number",
+        )?;
+    let policies = stmt
+        .query_map([], |row| {
+            Ok(InsurancePolicy {
+                id: row.get(0)?,
+                policy_number: row.get(1)?,
+                insurer: row.get(2)?,
+                policy_type: row.get(3)?,
+                start_date: row.get(4)?,
+                end_date: row.get(5)?,
+                premium: row.get(6)?,
+                notes: row.get(7)?,
+                enrolled_count: row.get(8)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(policies)
+}
+
+// Each renewal is recorded as a new policy covering one policy year
+#[tauri::command]
+pub fn create_insurance_policy(
+    policy: InsurancePolicy,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_manage_settings {
+        return Err(HrmError::permission_denied("Permission denied. You cannot change system settings."));
+    }
+    if policy.policy_number.trim().is_empty() || policy.insurer.trim().is_empty() {
+        return Err(HrmError::validation("Policy number and insurer are required"));
+    }
+    require_date(&policy.start_date, "Start date")?;
+    require_date(&policy.end_date, "End date")?;
+    if policy.end_date <= policy.start_date {
+        return Err(HrmError::validation("End date must be after the start date"));
+    }
+    
+    let conn = db.0.lock()?;
+    conn.execute(
+        "INSERT INTO insurance_policies (policy_number, insurer, policy_type, start_date, end_date, premium, notes)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![
+            policy.policy_number.trim(),
+            policy.insurer.trim(),
+            policy.policy_type,
+            policy.start_date,
+            policy.end_date,
+            policy.premium,
+            policy.notes,
+        ],
+    )
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE") {
+            format!("Policy {} already exists", policy.policy_number.trim())
+        } else {
+            e.to_string()
+        }
+    })?;
+    let policy_id = conn.last_insert_rowid();
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "INSURANCE_POLICY",
+        Some(&policy_id.to_string()),
+        None,
+        Some(policy.policy_number.trim()),
+        Some(&format!("Added {} policy {}", policy.insurer.trim(), policy.policy_number.trim())),
+    );
+    
+    Ok(policy_id)
+}
+
+fn load_dependents(conn: &Connection, enrollment_id: i64) -> rusqlite::Result<Vec<InsuranceDependent>> {
+    let mut stmt = conn.prepare(
+        "SELECT name, relationship, dob FROM insurance_dependents WHERE enrollment_id = ?1 ORDER BY id",
+    )?;
+    let dependents = stmt
+        .query_map([enrollment_id], |row| {
+            Ok(InsuranceDependent {
+                name: row.get(0)?,
+                relationship: row.get(1)?,
+                dob: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(dependents)
+}
+
+#[tauri::command]
+pub fn get_employee_insurance(epf_number: String, db: State<'_, DbConnection>) -> Result<Vec<InsuranceEnrollment>, HrmError> {
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT n.id, n.policy_id, p.policy_number, p.insurer, n.epf_number, n.enrolled_on, n.ended_on
+             FROM insurance_enrollments n JOIN insurance_policies p ON p.id = n.policy_id
+             WHERE n.epf_number = ?1
+             ORDER BY n.enrolled_on DESC",
+        )?;
+    let mut enrollments = stmt
+        .query_map([&epf_number], |row| {
+            Ok(InsuranceEnrollment {
+                id: row.get(0)?,
+                policy_id: row.get(1)?,
+                policy_number: row.get(2)?,
+                insurer: row.get(3)?,
+                epf_number: row.get(4)?,
+                enrolled_on: row.get(5)?,
+                ended_on: row.get(6)?,
+                dependents: Vec::new(),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    for enrollment in &mut enrollments {
+        enrollment.dependents = load_dependents(&conn, enrollment.id)?;
+    }
+    Ok(enrollments)
+}
+
+// Enrolls an employee, with any covered dependents, on a policy
+#[tauri::command]
+pub fn enroll_employee_insurance(
+    policy_id: i64,
+    epf_number: String,
+    enrolled_on: String,
+    dependents: Vec<InsuranceDependent>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_edit(&user)?;
+    require_date(&enrolled_on, "Enrollment date")?;
+    for dependent in &dependents {
+        if dependent.name.trim().is_empty() || dependent.relationship.trim().is_empty() {
+            return Err(HrmError::validation("Each dependent needs a name and relationship"));
+        }
+        if let Some(dob) = dependent.dob.as_deref().filter(|d| !d.is_empty()) {
+            require_date(dob, "Dependent date of birth")?;
+        }
+    }
+    
+    let mut conn = db.0.lock()?;
+    let policy_number: String = conn
+        .query_row("SELECT policy_number FROM insurance_policies WHERE id = ?1", [policy_id], |row| row.get(0))
+        .map_err(|_| HrmError::not_found("Policy not found"))?;
+    let already_enrolled: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM insurance_enrollments WHERE policy_id = ?1 AND epf_number = ?2 AND ended_on IS NULL",
+            rusqlite::params![policy_id, epf_number],
+            |row| row.get(0),
+        )?;
+    if already_enrolled {
+        return Err(HrmError::validation(format!("Employee {} is already enrolled on policy {}", epf_number, policy_number)));
+    }
+    
+    let tx = conn.transaction()?;
+    tx.execute(
+        "INSERT INTO insurance_enrollments (policy_id, epf_number, enrolled_on) VALUES (?1, ?2, ?3)",
+        rusqlite::params![policy_id, epf_number, enrolled_on],
+    )?;
+    let enrollment_id = tx.last_insert_rowid();
+    for dependent in &dependents {
+        tx.execute(
+            "INSERT INTO insurance_dependents (enrollment_id, name, relationship, dob) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![
+                enrollment_id,
+                dependent.name.trim(),
+                dependent.relationship.trim(),
+                dependent.dob.as_deref().filter(|d| !d.is_empty()),
+            ],
+        )?;
+    }
+    
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "INSURANCE_ENROLLMENT",
+        Some(&epf_number),
+        None,
+        Some(&policy_number),
+        Some(&format!(
+            "Enrolled employee {} on policy {} with {} dependents",
+            epf_number,
+            policy_number,
+            dependents.len()
+        )),
+    );
+    tx.commit()?;
+    
+    Ok(enrollment_id)
+}
+
+#[tauri::command]
+pub fn end_insurance_enrollment(
+    enrollment_id: i64,
+    ended_on: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_edit(&user)?;
+    require_date(&ended_on, "End date")?;
+    
+    let conn = db.0.lock()?;
+    let (epf_number, enrolled_on): (String, String) = conn
+        .query_row(
+            "SELECT epf_number, enrolled_on FROM insurance_enrollments WHERE id = ?1 AND ended_on IS NULL",
+            [enrollment_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|_| HrmError::not_found("Active enrollment not found"))?;
+    if ended_on < enrolled_on {
+        return Err(HrmError::validation("End date cannot be before the enrollment date"));
+    }
+    
+    conn.execute(
+        "UPDATE insurance_enrollments SET ended_on = ?1 WHERE id = ?2",
+        rusqlite::params![ended_on, enrollment_id],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "INSURANCE_ENROLLMENT",
+        Some(&epf_number),
+        None,
+        Some(&ended_on),
+        Some(&format!("Ended insurance enrollment #{} for employee {}", enrollment_id, epf_number)),
+    );
+    
+    Ok(())
+}
+
+const CLAIM_COLUMNS: &str = "c.id, c.enrollment_id, n.epf_number, p.policy_number, c.claimant, c.claim_date,
+                             c.amount_claimed, c.amount_settled, c.status, c.notes";
+
+fn claim_from_row(row: &rusqlite::Row) -> rusqlite::Result<InsuranceClaim> {
+    Ok(InsuranceClaim {
+        id: row.get(0)?,
+        enrollment_id: row.get(1)?,
+        epf_number: row.get(2)?,
+        policy_number: row.get(3)?,
+        claimant: row.get(4)?,
+        claim_date: row.get(5)?,
+        amount_claimed: row.get(6)?,
+        amount_settled: row.get(7)?,
+        status: row.get(8)?,
+        notes: row.get(9)?,
+        documents: Vec::new(),
+    })
+}
+
+fn attach_documents(conn: &Connection, claims: &mut [InsuranceClaim]) -> rusqlite::Result<()> {
+    let mut stmt = conn.prepare("SELECT file_path FROM insurance_claim_documents WHERE claim_id = ?1 ORDER BY id")?;
+    for claim in claims.iter_mut() {
+        claim.documents = stmt
+            .query_map([claim.id], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_insurance_claims(
+    policy_id: Option<i64>,
+    epf_number: Option<String>,
+    db: State<'_, DbConnection>,
+) -> Result<Vec<InsuranceClaim>, HrmError> {
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM insurance_claims c
+             JOIN insurance_enrollments n ON n.id = c.enrollment_id
+             JOIN insurance_policies p ON p.id = n.policy_id
+             WHERE (?1 IS NULL OR p.id = ?1) AND (?2 IS NULL OR n.epf_number = ?2)
+             ORDER BY c.claim_date DESC, c.id DESC",
+            CLAIM_COLUMNS
+        ))?;
+    let mut claims = stmt
+        .query_map(rusqlite::params![policy_id, epf_number], claim_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    attach_documents(&conn, &mut claims)?;
+    Ok(claims)
+}
+
+#[tauri::command]
+pub fn submit_insurance_claim(
+    claim: InsuranceClaim,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_edit(&user)?;
+    require_date(&claim.claim_date, "Claim date")?;
+    if claim.amount_claimed <= 0.0 {
+        return Err(HrmError::validation("Claim amount must be greater than zero"));
+    }
+    
+    let mut conn = db.0.lock()?;
+    let (epf_number, start_date, end_date): (String, String, String) = conn
+        .query_row(
+            "SELECT n.epf_number, p.start_date, p.end_date
+             FROM insurance_enrollments n JOIN insurance_policies p ON p.id = n.policy_id
+             WHERE n.id = ?1",
+            [claim.enrollment_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|_| HrmError::not_found("Enrollment not found"))?;
+    if claim.claim_date < start_date || claim.claim_date > end_date {
+        return Err(HrmError::validation(format!("Claim date must fall within the policy year ({} to {})", start_date, end_date)));
+    }
+    
+    let tx = conn.transaction()?;
+    tx.execute(
+        "INSERT INTO insurance_claims (enrollment_id, claimant, claim_date, amount_claimed, status, notes, created_by)
+         VALUES (?1, ?2, ?3, ?4, 'submitted', ?5, ?6)",
+        rusqlite::params![claim.enrollment_id, claim.claimant, claim.claim_date, claim.amount_claimed, claim.notes, user.username],
+    )?;
+    let claim_id = tx.last_insert_rowid();
+    for file_path in claim.documents.iter().filter(|p| !p.trim().is_empty()) {
+        tx.execute(
+            "INSERT INTO insurance_claim_documents (claim_id, file_path) VALUES (?1, ?2)",
+            rusqlite::params![claim_id, file_path.trim()],
+        )?;
+    }
+    
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "INSURANCE_CLAIM",
+        Some(&epf_number),
+        None,
+        Some(&format!("{:.2}", claim.amount_claimed)),
+        Some(&format!("Submitted insurance claim of {:.2} for employee {}", claim.amount_claimed, epf_number)),
+    );
+    tx.commit()?;
+    
+    Ok(claim_id)
+}
+
+#[tauri::command]
+pub fn update_insurance_claim_status(
+    claim_id: i64,
+    status: String,
+    amount_settled: Option<f64>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_edit(&user)?;
+    if !CLAIM_STATUSES.contains(&status.as_str()) {
+        return Err(HrmError::validation("Status must be submitted, approved, rejected or paid"));
+    }
+    if status == "paid" && amount_settled.is_none() {
+        return Err(HrmError::validation("Enter the settled amount for a paid claim"));
+    }
+    if amount_settled.map(|a| a < 0.0).unwrap_or(false) {
+        return Err(HrmError::validation("Settled amount cannot be negative"));
+    }
+    
+    let conn = db.0.lock()?;
+    let (old_status, epf_number): (String, String) = conn
+        .query_row(
+            "SELECT c.status, n.epf_number FROM insurance_claims c
+             JOIN insurance_enrollments n ON n.id = c.enrollment_id WHERE c.id = ?1",
+            [claim_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|_| HrmError::not_found("Claim not found"))?;
+    
+    conn.execute(
+        "UPDATE insurance_claims SET status = ?1, amount_settled = COALESCE(?2, amount_settled) WHERE id = ?3",
+        rusqlite::params![status, amount_settled, claim_id],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "INSURANCE_CLAIM",
+        Some(&epf_number),
+        Some(&old_status),
+        Some(&status),
+        Some(&format!("Insurance claim #{} for employee {} is now {}", claim_id, epf_number, status)),
+    );
+    
+    Ok(())
+}
+
+// Claims experience per policy year, for the broker renewal discussion.
+// Pass a year to limit the report to policies starting in that year.
+#[tauri::command]
+pub fn get_insurance_claims_report(
+    year: Option<i32>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<PolicyClaimsSummary>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_reports {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view reports."));
+    }
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT p.policy_number, p.insurer, p.start_date, p.end_date, p.premium,
+                    (SELECT COUNT(*) FROM insurance_enrollments n WHERE n.policy_id = p.id),
+                    COUNT(c.id),
+                    COALESCE(SUM(c.status = 'rejected'), 0),
+                    COALESCE(SUM(c.status IN ('submitted', 'approved')), 0),
+                    COALESCE(SUM(c.amount_claimed), 0),
+                    COALESCE(SUM(c.amount_settled), 0)
+             FROM insurance_policies p
+             LEFT JOIN insurance_enrollments n ON n.policy_id = p.id
+             LEFT JOIN insurance_claims c ON c.enrollment_id = n.id
+             WHERE ?1 IS NULL OR strftime('%Y', p.start_date) = printf('%04d', ?1)
+             GROUP BY p.id
+             ORDER BY p.start_date DESC, p.policy_number",
+        )?;
+    let report = stmt
+        .query_map([year], |row| {
+            let premium: Option<f64> = row.get(4)?;
+            let total_settled: f64 = row.get(10)?;
+            Ok(PolicyClaimsSummary {
+                policy_number: row.get(0)?,
+                insurer: row.get(1)?,
+                start_date: row.get(2)?,
+                end_date: row.get(3)?,
+                premium,
+                enrolled_count: row.get(5)?,
+                claim_count: row.get(6)?,
+                rejected_count: row.get(7)?,
+                open_count: row.get(8)?,
+                total_claimed: row.get(9)?,
+                total_settled,
+                loss_ratio: premium.filter(|p| *p > 0.0).map(|p| (total_settled / p * 1000.0).round() / 1000.0),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(report)
+}
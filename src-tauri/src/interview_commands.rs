@@ -0,0 +1,384 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{CandidateComparison, CriterionScore, Interview, ScoreSheet, UserSession};
+use crate::notification_commands::notify_user;
+use crate::recruitment_commands::{load_candidate, load_vacancy};
+use crate::validation::parse_iso_date;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use tauri::State;
+
+// Every score sheet rates each criterion from 1 (poor) to 5 (excellent)
+pub const SCORE_CRITERIA: [(&str, &str); 5] = [
+    ("experience", "Relevant experience"),
+    ("skills", "Job skills"),
+    ("communication", "Communication"),
+    ("attitude", "Attitude and teamwork"),
+    ("reliability", "Reliability and attendance record"),
+];
+const RECOMMENDATIONS: [&str; 3] = ["hire", "hold", "reject"];
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+// "YYYY-MM-DD HH:MM"
+fn is_valid_slot(value: &str) -> bool {
+    let (date, time) = match value.split_once(' ') {
+        Some(parts) => parts,
+        None => return false,
+    };
+    let (hours, minutes) = match time.split_once(':') {
+        Some(parts) => parts,
+        None => return false,
+    };
+    parse_iso_date(date).is_some()
+        && hours.len() == 2
+        && minutes.len() == 2
+        && hours.parse::<u32>().map(|h| h < 24).unwrap_or(false)
+        && minutes.parse::<u32>().map(|m| m < 60).unwrap_or(false)
+}
+
+// Checks a score sheet against SCORE_CRITERIA: every criterion scored once,
+// 1 to 5, nothing extra. Returns the average score.
+fn validate_score_sheet(sheet: &ScoreSheet) -> Result<f64, HrmError> {
+    for (key, label) in SCORE_CRITERIA.iter() {
+        let scores: Vec<&CriterionScore> = sheet.scores.iter().filter(|s| s.criterion == *key).collect();
+        match scores.as_slice() {
+            [score] if (1..=5).contains(&score.score) => {}
+            [_] => return Err(HrmError::validation(format!("{} must be scored from 1 to 5", label))),
+            [] => return Err(HrmError::validation(format!("{} has not been scored", label))),
+            _ => return Err(HrmError::validation(format!("{} is scored more than once", label))),
+        }
+    }
+    if let Some(unknown) = sheet.scores.iter().find(|s| !SCORE_CRITERIA.iter().any(|(key, _)| *key == s.criterion)) {
+        return Err(HrmError::validation(format!("Unknown criterion '{}'", unknown.criterion)));
+    }
+    if !RECOMMENDATIONS.contains(&sheet.recommendation.as_str()) {
+        return Err(HrmError::validation("Recommendation must be hire, hold or reject"));
+    }
+    let total: i32 = sheet.scores.iter().map(|s| s.score).sum();
+    Ok(total as f64 / SCORE_CRITERIA.len() as f64)
+}
+
+const INTERVIEW_COLUMNS: &str = "i.id, i.candidate_id, c.full_name, c.vacancy_id, i.interviewer_user_id, u.full_name,
+                                 i.scheduled_at, i.duration_minutes, i.location, i.status, i.score_sheet,
+                                 i.overall_score, i.evaluated_at, i.created_by";
+
+fn interview_from_row(row: &rusqlite::Row) -> rusqlite::Result<Interview> {
+    let score_sheet: Option<String> = row.get(10)?;
+    Ok(Interview {
+        id: row.get(0)?,
+        candidate_id: row.get(1)?,
+        candidate_name: row.get(2)?,
+        vacancy_id: row.get(3)?,
+        interviewer_user_id: row.get(4)?,
+        interviewer_name: row.get(5)?,
+        scheduled_at: row.get(6)?,
+        duration_minutes: row.get(7)?,
+        location: row.get(8)?,
+        status: row.get(9)?,
+        score_sheet: score_sheet.and_then(|s| serde_json::from_str(&s).ok()),
+        overall_score: row.get(11)?,
+        evaluated_at: row.get(12)?,
+        created_by: row.get(13)?,
+    })
+}
+
+fn query_interviews(conn: &Connection, condition: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<Interview>, HrmError> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM interviews i
+             JOIN candidates c ON c.id = i.candidate_id
+             LEFT JOIN users u ON u.id = i.interviewer_user_id
+             WHERE {}
+             ORDER BY i.scheduled_at, i.id",
+            INTERVIEW_COLUMNS, condition
+        ))?;
+    let interviews = stmt
+        .query_map(params, interview_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(interviews)
+}
+
+// The criteria a score sheet must cover, as (key, label), for building the form
+#[tauri::command]
+pub fn get_score_criteria() -> Vec<(String, String)> {
+    SCORE_CRITERIA
+        .iter()
+        .map(|(key, label)| (key.to_string(), label.to_string()))
+        .collect()
+}
+
+#[tauri::command]
+pub fn get_vacancy_interviews(vacancy_id: i64, db: State<'_, DbConnection>) -> Result<Vec<Interview>, HrmError> {
+    let conn = db.0.lock()?;
+    query_interviews(&conn, "c.vacancy_id = ?1", &[&vacancy_id])
+}
+
+// Upcoming interviews the logged-in user is on the panel for
+#[tauri::command]
+pub fn get_my_interviews(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<Interview>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    let conn = db.0.lock()?;
+    query_interviews(&conn, "i.interviewer_user_id = ?1 AND i.status = 'scheduled'", &[&user.user_id])
+}
+
+// Books an interviewer for a candidate; the interviewer is notified.
+// An interviewer cannot have two scheduled interviews that overlap.
+#[tauri::command]
+pub fn schedule_interview(
+    candidate_id: i64,
+    interviewer_user_id: i32,
+    scheduled_at: String,
+    duration_minutes: i32,
+    location: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit employees."));
+    }
+    if !is_valid_slot(&scheduled_at) {
+        return Err(HrmError::validation("Interview time must be in YYYY-MM-DD HH:MM format"));
+    }
+    if !(5..=480).contains(&duration_minutes) {
+        return Err(HrmError::validation("Duration must be between 5 and 480 minutes"));
+    }
+    
+    let conn = db.0.lock()?;
+    let candidate = load_candidate(&conn, candidate_id)?;
+    if candidate.stage == "joined" || candidate.stage == "rejected" {
+        return Err(HrmError::validation(format!("Candidate has already {}", candidate.stage)));
+    }
+    let vacancy = load_vacancy(&conn, candidate.vacancy_id)?;
+    let interviewer: String = conn
+        .query_row(
+            "SELECT full_name FROM users WHERE id = ?1 AND is_active = 1 AND deleted_at IS NULL",
+            [interviewer_user_id],
+            |row| row.get(0),
+        )
+        .map_err(|_| HrmError::not_found("Interviewer not found or inactive"))?;
+    
+    let clash: Option<String> = conn
+        .query_row(
+            "SELECT scheduled_at FROM interviews
+             WHERE interviewer_user_id = ?1 AND status = 'scheduled'
+               AND datetime(scheduled_at) < datetime(?2, '+' || ?3 || ' minutes')
+               AND datetime(?2) < datetime(scheduled_at, '+' || duration_minutes || ' minutes')
+             LIMIT 1",
+            rusqlite::params![interviewer_user_id, scheduled_at, duration_minutes],
+            |row| row.get(0),
+        )
+        .ok();
+    if let Some(clash) = clash {
+        return Err(HrmError::validation(format!("{} already has an interview at {}", interviewer, clash)));
+    }
+    
+    conn.execute(
+        "INSERT INTO interviews (candidate_id, interviewer_user_id, scheduled_at, duration_minutes, location, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![candidate_id, interviewer_user_id, scheduled_at, duration_minutes, location, user.username],
+    )?;
+    let interview_id = conn.last_insert_rowid();
+    
+    notify_user(
+        &conn,
+        interviewer_user_id,
+        "Interview scheduled",
+        &format!(
+            "Interview with {} for '{}' at {}{}",
+            candidate.full_name,
+            vacancy.title,
+            scheduled_at,
+            location.as_deref().map(|l| format!(" ({})", l)).unwrap_or_default()
+        ),
+        "info",
+        Some("INTERVIEW"),
+        Some(&interview_id.to_string()),
+    );
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "INTERVIEW",
+        Some(&interview_id.to_string()),
+        None,
+        Some(&scheduled_at),
+        Some(&format!("Scheduled {} to interview {} at {}", interviewer, candidate.full_name, scheduled_at)),
+    );
+    
+    Ok(interview_id)
+}
+
+#[tauri::command]
+pub fn cancel_interview(
+    interview_id: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit employees."));
+    }
+    
+    let conn = db.0.lock()?;
+    let updated = conn
+        .execute(
+            "UPDATE interviews SET status = 'cancelled' WHERE id = ?1 AND status = 'scheduled'",
+            [interview_id],
+        )?;
+    if updated == 0 {
+        return Err(HrmError::validation("No scheduled interview found"));
+    }
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "INTERVIEW",
+        Some(&interview_id.to_string()),
+        Some("scheduled"),
+        Some("cancelled"),
+        Some(&format!("Cancelled interview #{}", interview_id)),
+    );
+    
+    Ok(())
+}
+
+// The interviewer records their score sheet. The first evaluation moves an
+// applied candidate to the interviewed stage.
+#[tauri::command]
+pub fn record_interview_evaluation(
+    interview_id: i64,
+    score_sheet: ScoreSheet,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<f64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    let overall_score = validate_score_sheet(&score_sheet)?;
+    
+    let mut conn = db.0.lock()?;
+    let interview = query_interviews(&conn, "i.id = ?1", &[&interview_id])?
+        .pop()
+        .ok_or_else(|| HrmError::not_found("Interview not found"))?;
+    if interview.interviewer_user_id != user.user_id {
+        return Err(HrmError::validation("Only the interviewer can record this evaluation"));
+    }
+    if interview.status == "cancelled" {
+        return Err(HrmError::validation("This interview was cancelled"));
+    }
+    let sheet_json = serde_json::to_string(&score_sheet)?;
+    
+    let tx = conn.transaction()?;
+    tx.execute(
+        "UPDATE interviews SET status = 'completed', score_sheet = ?1, overall_score = ?2,
+                              evaluated_at = datetime('now', 'localtime')
+         WHERE id = ?3",
+        rusqlite::params![sheet_json, overall_score, interview_id],
+    )?;
+    tx.execute(
+        "UPDATE candidates SET stage = 'interviewed', stage_changed_on = date('now', 'localtime')
+         WHERE id = ?1 AND stage = 'applied'",
+        [interview.candidate_id],
+    )?;
+    
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "EVALUATE",
+        "INTERVIEW",
+        Some(&interview_id.to_string()),
+        interview.score_sheet.as_ref().and_then(|s| serde_json::to_string(s).ok()).as_deref(),
+        Some(&sheet_json),
+        Some(&format!(
+            "Evaluated {}: {:.1}/5, {}",
+            interview.candidate_name, overall_score, score_sheet.recommendation
+        )),
+    );
+    tx.commit()?;
+    
+    Ok(overall_score)
+}
+
+// Candidates for a vacancy side by side: average score per criterion across
+// all completed interviews and the interviewers' recommendations, best first
+#[tauri::command]
+pub fn get_candidate_comparison(
+    vacancy_id: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<CandidateComparison>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_reports {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view reports."));
+    }
+    
+    let conn = db.0.lock()?;
+    load_vacancy(&conn, vacancy_id)?;
+    let interviews = query_interviews(&conn, "c.vacancy_id = ?1 AND i.status = 'completed'", &[&vacancy_id])?;
+    
+    let mut stmt = conn
+        .prepare("SELECT id, full_name, stage FROM candidates WHERE vacancy_id = ?1 AND stage != 'rejected'")?;
+    let candidates = stmt
+        .query_map([vacancy_id], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    let mut comparison: Vec<CandidateComparison> = candidates
+        .into_iter()
+        .map(|(candidate_id, full_name, stage)| {
+            let sheets: Vec<&ScoreSheet> = interviews
+                .iter()
+                .filter(|i| i.candidate_id == candidate_id)
+                .filter_map(|i| i.score_sheet.as_ref())
+                .collect();
+            let mut criterion_averages = HashMap::new();
+            let mut recommendations: HashMap<String, i32> = HashMap::new();
+            if !sheets.is_empty() {
+                for (key, _) in SCORE_CRITERIA.iter() {
+                    let total: i32 = sheets
+                        .iter()
+                        .flat_map(|s| s.scores.iter())
+                        .filter(|s| s.criterion == *key)
+                        .map(|s| s.score)
+                        .sum();
+                    criterion_averages.insert(key.to_string(), total as f64 / sheets.len() as f64);
+                }
+                for sheet in &sheets {
+                    *recommendations.entry(sheet.recommendation.clone()).or_insert(0) += 1;
+                }
+            }
+            let overall_score = if sheets.is_empty() {
+                None
+            } else {
+                Some(criterion_averages.values().sum::<f64>() / SCORE_CRITERIA.len() as f64)
+            };
+            CandidateComparison {
+                candidate_id,
+                full_name,
+                stage,
+                interview_count: sheets.len() as i32,
+                criterion_averages,
+                overall_score,
+                recommendations,
+            }
+        })
+        .collect();
+    comparison.sort_by(|a, b| {
+        b.overall_score
+            .unwrap_or(-1.0)
+            .partial_cmp(&a.overall_score.unwrap_or(-1.0))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    
+    Ok(comparison)
+}
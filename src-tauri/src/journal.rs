@@ -0,0 +1,181 @@
+use crate::error::HrmError;
+use crate::images;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+// Multi-step file operations record their intent here before touching any
+// file and remove it once finished. An intent still present at startup means
+// the app stopped part way (e.g. a power cut) and is rolled back or completed.
+const JOURNAL_DIR: &str = "journal";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "operation", rename_all = "snake_case")]
+pub enum Operation {
+    // Paths are relative to the app data directory
+    ImportDatabase { staged_path: String },
+    SaveImage { epf_number: String, image_path: String },
+    RenameEmployeeFolder { old_epf_number: String, new_epf_number: String },
+}
+
+// An intent that has been written and not yet committed
+pub struct JournalEntry {
+    path: PathBuf,
+}
+
+impl JournalEntry {
+    // Marks the operation as finished
+    pub fn commit(self) -> Result<(), HrmError> {
+        fs::remove_file(&self.path)?;
+        Ok(())
+    }
+}
+
+fn journal_dir(app_dir: &Path) -> PathBuf {
+    app_dir.join(JOURNAL_DIR)
+}
+
+// Writes a file so that it either has the new contents or the old ones: the
+// data goes to a temporary file first, is flushed to disk, then renamed over
+// the target
+pub fn write_atomic(path: &Path, data: &[u8]) -> Result<(), HrmError> {
+    let tmp = path.with_extension("tmp");
+    let mut file = File::create(&tmp)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+// Copies a file with the same all-or-nothing guarantee as write_atomic
+pub fn copy_atomic(source: &Path, dest: &Path) -> Result<(), HrmError> {
+    let tmp = dest.with_extension("tmp");
+    fs::copy(source, &tmp)?;
+    File::open(&tmp)?.sync_all()?;
+    fs::rename(&tmp, dest)?;
+    Ok(())
+}
+
+// Records an operation before it starts
+pub fn begin(app_dir: &Path, operation: &Operation) -> Result<JournalEntry, HrmError> {
+    let dir = journal_dir(app_dir);
+    fs::create_dir_all(&dir)?;
+    let mut n = 0;
+    let path = loop {
+        let candidate = dir.join(format!("{}-{}.json", std::process::id(), n));
+        if !candidate.exists() {
+            break candidate;
+        }
+        n += 1;
+    };
+    write_atomic(&path, serde_json::to_string(operation)?.as_bytes())?;
+    Ok(JournalEntry { path })
+}
+
+// Points the thumbnail at the employee's current primary photo, or removes it
+// when they have none
+fn refresh_thumbnail(conn: &Connection, app_dir: &Path, epf_number: &str, image_path: &str) -> Result<(), HrmError> {
+    let thumbnail = app_dir.join(images::thumbnail_path_for(image_path));
+    let primary: Option<String> = conn
+        .query_row("SELECT image_path FROM employees WHERE epf_number = ?1", [epf_number], |row| row.get(0))
+        .ok()
+        .flatten();
+    match primary.map(|p| fs::read(app_dir.join(p))) {
+        Some(Ok(bytes)) => write_atomic(&thumbnail, &images::make_thumbnail(&bytes)?),
+        _ => match fs::remove_file(&thumbnail) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        },
+    }
+}
+
+fn recover_operation(conn: &Connection, app_dir: &Path, operation: &Operation) -> Result<&'static str, HrmError> {
+    match operation {
+        // The staged copy is renamed over the database in one step, so a
+        // leftover staged file means the import never replaced anything
+        Operation::ImportDatabase { staged_path } => {
+            let staged = app_dir.join(staged_path);
+            if staged.exists() {
+                fs::remove_file(&staged)?;
+                Ok("rolled back")
+            } else {
+                Ok("completed")
+            }
+        }
+        // A photo without its metadata row was never saved; remove the file
+        Operation::SaveImage { epf_number, image_path } => {
+            let recorded: bool = conn.query_row(
+                "SELECT COUNT(*) > 0 FROM image_metadata WHERE image_path = ?1",
+                [image_path],
+                |row| row.get(0),
+            )?;
+            let outcome = if recorded {
+                "completed"
+            } else {
+                let file = app_dir.join(image_path);
+                if file.exists() {
+                    fs::remove_file(&file)?;
+                }
+                "rolled back"
+            };
+            refresh_thumbnail(conn, app_dir, epf_number, image_path)?;
+            Ok(outcome)
+        }
+        // The folder follows the database: renamed if the new EPF number was
+        // saved, put back under the old one if it was not
+        Operation::RenameEmployeeFolder { old_epf_number, new_epf_number } => {
+            let saved: bool = conn.query_row(
+                "SELECT COUNT(*) > 0 FROM employees WHERE epf_number = ?1",
+                [new_epf_number],
+                |row| row.get(0),
+            )?;
+            let images_dir = app_dir.join("employee_images");
+            let (from, to, outcome) = if saved {
+                (old_epf_number, new_epf_number, "completed")
+            } else {
+                (new_epf_number, old_epf_number, "rolled back")
+            };
+            if images_dir.join(from).exists() && !images_dir.join(to).exists() {
+                fs::rename(images_dir.join(from), images_dir.join(to))?;
+            }
+            Ok(outcome)
+        }
+    }
+}
+
+// Finishes or undoes operations interrupted by a crash. Run once at startup,
+// before any command can start a new operation. Returns how many were found.
+pub fn recover(conn: &Connection, app_dir: &Path) -> usize {
+    let entries: Vec<PathBuf> = match fs::read_dir(journal_dir(app_dir)) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().map(|ext| ext == "json").unwrap_or(false))
+            .collect(),
+        Err(_) => return 0,
+    };
+    
+    for path in &entries {
+        let operation = fs::read_to_string(path)
+            .map_err(HrmError::from)
+            .and_then(|content| serde_json::from_str::<Operation>(&content).map_err(HrmError::from));
+        match operation {
+            Ok(operation) => match recover_operation(conn, app_dir, &operation) {
+                Ok(outcome) => {
+                    tracing::warn!("Interrupted operation {:?} {}", operation, outcome);
+                    let _ = fs::remove_file(path);
+                }
+                // Left in place so the next start tries again
+                Err(e) => tracing::error!("Could not recover interrupted operation {:?}: {}", operation, e),
+            },
+            Err(e) => {
+                // Corrupt entries cannot be acted on
+                tracing::warn!("Discarding unreadable journal entry {:?}: {}", path, e);
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+    entries.len()
+}
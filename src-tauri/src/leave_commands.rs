@@ -0,0 +1,498 @@
+use crate::approvals::{check_approver, may_approve, open_approval, record_decision};
+use crate::attendance_commands::weekly_off_days;
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{LeaveEntry, LeaveRecord, LeaveRequest, UserSession};
+use crate::notification_commands::notify_user;
+use crate::payroll_commands::ensure_month_open;
+use crate::{CurrentUser, DbConnection};
+use chrono::{Datelike, NaiveDate};
+use rusqlite::{Connection, OptionalExtension};
+use tauri::State;
+
+// no_pay leave is recorded so the absence is explained, but it is not paid
+pub const LEAVE_TYPES: [&str; 5] = ["annual", "casual", "medical", "maternity", "no_pay"];
+
+// Longest single leave entry, enough for 84 working days of maternity leave
+const MAX_LEAVE_SPAN_DAYS: i64 = 140;
+
+// Leave is recorded once it has been approved, so recording it takes the
+// approval permission
+fn require_approve_permission(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_approve_leave => Ok(session.clone()),
+        Some(_) => Err(HrmError::permission_denied("Permission denied. You cannot record leave.")),
+        None => Err(HrmError::not_logged_in()),
+    }
+}
+
+fn require_view_permission(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_view_employees => Ok(session.clone()),
+        Some(_) => Err(HrmError::permission_denied("Permission denied. You cannot view leave.")),
+        None => Err(HrmError::not_logged_in()),
+    }
+}
+
+fn require_request_permission(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_manage_attendance => Ok(session.clone()),
+        Some(_) => Err(HrmError::permission_denied("Permission denied. You cannot request leave.")),
+        None => Err(HrmError::not_logged_in()),
+    }
+}
+
+// Approvers may be covering for an absent approver, so their permission is
+// checked against the database with may_approve
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+fn parse_date(field: &str, value: &str) -> Result<NaiveDate, HrmError> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").map_err(|_| HrmError::field(field, "Date must be in YYYY-MM-DD format"))
+}
+
+// Checks the type and dates of a leave entry and returns its first and last day
+fn check_leave(leave_type: &str, from_date: &str, to_date: &str, half_day: bool) -> Result<(NaiveDate, NaiveDate), HrmError> {
+    if !LEAVE_TYPES.contains(&leave_type) {
+        return Err(HrmError::field("leave_type", "Unknown leave type"));
+    }
+    let from = parse_date("from_date", from_date)?;
+    let to = parse_date("to_date", to_date)?;
+    if to < from {
+        return Err(HrmError::field("to_date", "End date cannot be before the start date"));
+    }
+    if (to - from).num_days() > MAX_LEAVE_SPAN_DAYS {
+        return Err(HrmError::field("to_date", "Leave cannot span more than 140 days in one entry"));
+    }
+    if half_day && to != from {
+        return Err(HrmError::field("half_day", "A half day must start and end on the same date"));
+    }
+    Ok((from, to))
+}
+
+fn ensure_employee_exists(conn: &Connection, epf_number: &str) -> Result<(), HrmError> {
+    let employee_exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM employees WHERE epf_number = ?1)",
+        [epf_number],
+        |row| row.get(0),
+    )?;
+    if !employee_exists {
+        return Err(HrmError::not_found("Employee not found"));
+    }
+    Ok(())
+}
+
+// Leave changes no-pay days, so none of its months may be locked
+fn ensure_months_open(conn: &Connection, from: NaiveDate, to: NaiveDate) -> Result<(), HrmError> {
+    for date in from.iter_days().take_while(|d| *d <= to).filter(|d| d.day() == 1 || *d == from) {
+        ensure_month_open(conn, &date.format("%Y-%m").to_string())?;
+    }
+    Ok(())
+}
+
+// Writes a leave_records row for every working day in `period` (first and
+// last day), skipping days already on leave. Returns the number of days
+// written.
+fn insert_leave_days(
+    conn: &Connection,
+    epf_number: &str,
+    (from, to): (NaiveDate, NaiveDate),
+    leave_type: &str,
+    half_day: bool,
+    reason: Option<&str>,
+    recorded_by: i32,
+) -> Result<usize, HrmError> {
+    let off_days = weekly_off_days(conn);
+    let days = if half_day { 0.5 } else { 1.0 };
+    let mut recorded = 0;
+    for date in from.iter_days().take_while(|d| *d <= to) {
+        if off_days.contains(&date.weekday().num_days_from_sunday()) {
+            continue;
+        }
+        recorded += conn.execute(
+            "INSERT OR IGNORE INTO leave_records (epf_number, leave_date, leave_type, days, reason, recorded_by)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![epf_number, date.format("%Y-%m-%d").to_string(), leave_type, days, reason, recorded_by],
+        )?;
+    }
+    Ok(recorded)
+}
+
+const LEAVE_REQUEST_COLUMNS: &str = "r.id, r.epf_number, e.full_name, r.from_date, r.to_date, r.leave_type, r.half_day, r.reason,
+                                     r.status, r.requested_by, ru.username, r.requested_at, r.reviewed_by, vu.username,
+                                     r.reviewed_at, r.review_note";
+
+fn leave_request_from_row(row: &rusqlite::Row) -> rusqlite::Result<LeaveRequest> {
+    Ok(LeaveRequest {
+        id: row.get(0)?,
+        epf_number: row.get(1)?,
+        employee_name: row.get(2)?,
+        from_date: row.get(3)?,
+        to_date: row.get(4)?,
+        leave_type: row.get(5)?,
+        half_day: row.get(6)?,
+        reason: row.get(7)?,
+        status: row.get(8)?,
+        requested_by: row.get(9)?,
+        requested_by_name: row.get(10)?,
+        requested_at: row.get(11)?,
+        reviewed_by: row.get(12)?,
+        reviewed_by_name: row.get(13)?,
+        reviewed_at: row.get(14)?,
+        review_note: row.get(15)?,
+    })
+}
+
+fn load_leave_request(conn: &Connection, id: i64) -> Result<LeaveRequest, HrmError> {
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM leave_requests r
+             LEFT JOIN employees e ON e.epf_number = r.epf_number
+             LEFT JOIN users ru ON ru.id = r.requested_by
+             LEFT JOIN users vu ON vu.id = r.reviewed_by
+             WHERE r.id = ?1",
+            LEAVE_REQUEST_COLUMNS
+        ),
+        [id],
+        leave_request_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| HrmError::not_found("Leave request not found"))
+}
+
+#[tauri::command]
+pub fn get_leave_records(
+    epf_number: String,
+    year: Option<i32>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<LeaveRecord>, HrmError> {
+    require_view_permission(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(
+        "SELECT l.id, l.epf_number, l.leave_date, l.leave_type, l.days, l.reason, u.username, l.created_at
+         FROM leave_records l
+         LEFT JOIN users u ON u.id = l.recorded_by
+         WHERE l.epf_number = ?1 AND (?2 IS NULL OR strftime('%Y', l.leave_date) = printf('%04d', ?2))
+         ORDER BY l.leave_date DESC",
+    )?;
+    let records = stmt
+        .query_map(rusqlite::params![epf_number, year], |row| {
+            Ok(LeaveRecord {
+                id: row.get(0)?,
+                epf_number: row.get(1)?,
+                leave_date: row.get(2)?,
+                leave_type: row.get(3)?,
+                days: row.get(4)?,
+                reason: row.get(5)?,
+                recorded_by: row.get(6)?,
+                created_at: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    Ok(records)
+}
+
+// Records leave for every working day from from_date to to_date. Days already
+// on leave are skipped. Returns the number of days recorded.
+#[tauri::command]
+pub fn record_leave(
+    leave: LeaveEntry,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<usize, HrmError> {
+    let user = require_approve_permission(&current_user)?;
+    let LeaveEntry { epf_number, from_date, to_date, leave_type, half_day, reason } = leave;
+    let (from, to) = check_leave(&leave_type, &from_date, &to_date, half_day)?;
+    
+    let mut conn = db.0.lock()?;
+    ensure_employee_exists(&conn, &epf_number)?;
+    ensure_months_open(&conn, from, to)?;
+    
+    let days = if half_day { 0.5 } else { 1.0 };
+    let tx = conn.transaction()?;
+    let recorded = insert_leave_days(&tx, &epf_number, (from, to), &leave_type, half_day, reason.as_deref(), user.user_id)?;
+    tx.commit()?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "LEAVE",
+        Some(&epf_number),
+        None,
+        Some(&leave_type),
+        Some(&format!(
+            "Recorded {} day(s) of {} leave for {} from {} to {}",
+            recorded as f64 * days,
+            leave_type,
+            epf_number,
+            from_date,
+            to_date
+        )),
+    );
+    
+    Ok(recorded)
+}
+
+#[tauri::command]
+pub fn cancel_leave(
+    id: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = require_approve_permission(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let (epf_number, leave_date, leave_type): (String, String, String) = conn
+        .query_row(
+            "SELECT epf_number, leave_date, leave_type FROM leave_records WHERE id = ?1",
+            [id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|_| HrmError::not_found("Leave record not found"))?;
+    ensure_month_open(&conn, &leave_date)?;
+    conn.execute("DELETE FROM leave_records WHERE id = ?1", [id])?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "DELETE",
+        "LEAVE",
+        Some(&epf_number),
+        Some(&leave_type),
+        None,
+        Some(&format!("Cancelled {} leave for {} on {}", leave_type, epf_number, leave_date)),
+    );
+    
+    Ok(())
+}
+
+// Asks for leave through the approval chain. Nothing is written to
+// leave_records until the last approver approves it.
+#[tauri::command]
+pub fn request_leave(
+    leave: LeaveEntry,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<LeaveRequest, HrmError> {
+    let user = require_request_permission(&current_user)?;
+    let LeaveEntry { epf_number, from_date, to_date, leave_type, half_day, reason } = leave;
+    let (from, to) = check_leave(&leave_type, &from_date, &to_date, half_day)?;
+    let reason = reason.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+    
+    let mut conn = db.0.lock()?;
+    ensure_employee_exists(&conn, &epf_number)?;
+    ensure_months_open(&conn, from, to)?;
+    
+    let tx = conn.transaction()?;
+    tx.execute(
+        "INSERT INTO leave_requests (epf_number, from_date, to_date, leave_type, half_day, reason, requested_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![
+            epf_number,
+            from.format("%Y-%m-%d").to_string(),
+            to.format("%Y-%m-%d").to_string(),
+            leave_type,
+            half_day,
+            reason,
+            user.user_id,
+        ],
+    )?;
+    let id = tx.last_insert_rowid();
+    let details = format!("Requested {} leave for {} from {} to {}", leave_type, epf_number, from_date, to_date);
+    open_approval(&tx, "leave", &id.to_string(), Some(&epf_number), &details, &user)?;
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "LEAVE_REQUEST",
+        Some(&id.to_string()),
+        None,
+        Some(&leave_type),
+        Some(&details),
+    );
+    tx.commit()?;
+    
+    load_leave_request(&conn, id)
+}
+
+#[tauri::command]
+pub fn get_leave_requests(
+    status: Option<String>,
+    epf_number: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<LeaveRequest>, HrmError> {
+    require_view_permission(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM leave_requests r
+         LEFT JOIN employees e ON e.epf_number = r.epf_number
+         LEFT JOIN users ru ON ru.id = r.requested_by
+         LEFT JOIN users vu ON vu.id = r.reviewed_by
+         WHERE (?1 IS NULL OR r.status = ?1) AND (?2 IS NULL OR r.epf_number = ?2)
+         ORDER BY r.requested_at DESC, r.id DESC",
+        LEAVE_REQUEST_COLUMNS
+    ))?;
+    let requests = stmt
+        .query_map(rusqlite::params![status, epf_number], leave_request_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    Ok(requests)
+}
+
+// Records the user's approval. The last approver in the chain writes the
+// leave days; earlier approvals pass the request on.
+#[tauri::command]
+pub fn approve_leave_request(
+    id: i64,
+    note: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    
+    let mut conn = db.0.lock()?;
+    if !may_approve(&conn, &user, "can_approve_leave")? {
+        return Err(HrmError::permission_denied("Permission denied. You cannot approve leave."));
+    }
+    let request = load_leave_request(&conn, id)?;
+    if request.status != "pending" {
+        return Err(HrmError::conflict(format!("This leave request has already been {}", request.status)));
+    }
+    let (from, to) = check_leave(&request.leave_type, &request.from_date, &request.to_date, request.half_day)?;
+    ensure_months_open(&conn, from, to)?;
+    
+    let entity_id = id.to_string();
+    let tx = conn.transaction()?;
+    let final_step = check_approver(&tx, "leave", &entity_id, &user)?;
+    let details = if final_step {
+        let recorded = insert_leave_days(
+            &tx,
+            &request.epf_number,
+            (from, to),
+            &request.leave_type,
+            request.half_day,
+            request.reason.as_deref(),
+            user.user_id,
+        )?;
+        tx.execute(
+            "UPDATE leave_requests
+             SET status = 'approved', reviewed_by = ?1, reviewed_at = CURRENT_TIMESTAMP, review_note = ?2
+             WHERE id = ?3",
+            rusqlite::params![user.user_id, note, id],
+        )?;
+        format!(
+            "Approved {} leave for {} from {} to {} ({} day(s) recorded)",
+            request.leave_type,
+            request.epf_number,
+            request.from_date,
+            request.to_date,
+            recorded as f64 * if request.half_day { 0.5 } else { 1.0 }
+        )
+    } else {
+        format!(
+            "Approved {} leave for {} from {} to {}; passed to the next approver",
+            request.leave_type, request.epf_number, request.from_date, request.to_date
+        )
+    };
+    record_decision(&tx, "leave", &entity_id, &user, true, note.as_deref())?;
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "APPROVE",
+        "LEAVE_REQUEST",
+        Some(&entity_id),
+        Some("pending"),
+        Some(if final_step { "approved" } else { "pending" }),
+        Some(&details),
+    );
+    tx.commit()?;
+    
+    if let (true, Some(requested_by)) = (final_step, request.requested_by) {
+        notify_user(
+            &conn,
+            requested_by,
+            "Leave approved",
+            &format!("{} leave for {} from {} to {} was approved by {}", request.leave_type, request.epf_number, request.from_date, request.to_date, user.full_name),
+            "info",
+            Some("LEAVE_REQUEST"),
+            Some(&entity_id),
+        );
+    }
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn reject_leave_request(
+    id: i64,
+    note: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    let note = note.trim();
+    if note.is_empty() {
+        return Err(HrmError::field("note", "Please give a reason for rejecting the leave"));
+    }
+    
+    let mut conn = db.0.lock()?;
+    if !may_approve(&conn, &user, "can_approve_leave")? {
+        return Err(HrmError::permission_denied("Permission denied. You cannot approve leave."));
+    }
+    let request = load_leave_request(&conn, id)?;
+    if request.status != "pending" {
+        return Err(HrmError::conflict(format!("This leave request has already been {}", request.status)));
+    }
+    
+    let entity_id = id.to_string();
+    let tx = conn.transaction()?;
+    check_approver(&tx, "leave", &entity_id, &user)?;
+    record_decision(&tx, "leave", &entity_id, &user, false, Some(note))?;
+    tx.execute(
+        "UPDATE leave_requests
+         SET status = 'rejected', reviewed_by = ?1, reviewed_at = CURRENT_TIMESTAMP, review_note = ?2
+         WHERE id = ?3",
+        rusqlite::params![user.user_id, note, id],
+    )?;
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "REJECT",
+        "LEAVE_REQUEST",
+        Some(&entity_id),
+        Some("pending"),
+        Some("rejected"),
+        Some(&format!("Rejected {} leave for {}: {}", request.leave_type, request.epf_number, note)),
+    );
+    tx.commit()?;
+    
+    if let Some(requested_by) = request.requested_by {
+        notify_user(
+            &conn,
+            requested_by,
+            "Leave rejected",
+            &format!("{} leave for {} from {} to {} was rejected: {}", request.leave_type, request.epf_number, request.from_date, request.to_date, note),
+            "warning",
+            Some("LEAVE_REQUEST"),
+            Some(&entity_id),
+        );
+    }
+    
+    Ok(())
+}
@@ -0,0 +1,623 @@
+use crate::commands::{log_audit_action, query_employee, query_employees};
+use crate::custom_fields::load_definitions;
+use crate::employment_periods::load_transfers;
+use crate::error::HrmError;
+use crate::export_commands::DEFAULT_COMPANY_NAME;
+use crate::import_commands::xml_escape;
+use crate::localization::{docx_fonts, employee_language, translate, DOCUMENT_FONTS, LANGUAGES};
+use crate::models::{
+    BulkLetterResult, Employee, EmployeeFilters, FieldError, LetterIssue, LetterTemplate, UserPermissions, UserSession,
+};
+use crate::pdf::{write_pdf, PdfBlock};
+use crate::salary_commands::effective_salary;
+use crate::settings_commands::{date_display, get_setting};
+use crate::validation::format_date;
+use crate::{CurrentUser, DbConnection};
+use chrono::Local;
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use tauri::State;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+// Merge fields every template can use as {{name}}. Active custom employee
+// fields can also be used by their name.
+pub const MERGE_FIELDS: [&str; 19] = [
+    "epf_number",
+    "name_with_initials",
+    "full_name",
+    "nic",
+    "address",
+    "designation",
+    "department",
+    "allocation",
+    "cader",
+    "date_of_join",
+    "date_of_resign",
+    "working_status",
+    "transfer_to",
+    "transfer_date",
+    "basic_salary",
+    "salary_currency",
+    "company_name",
+    "today",
+    "reference_number",
+];
+
+// Only filled in for users who can see salaries
+const SALARY_FIELDS: [&str; 2] = ["basic_salary", "salary_currency"];
+
+// Only filled in for users who can see personal details, as in exports
+const SENSITIVE_FIELDS: [&str; 2] = ["nic", "address"];
+
+const LETTER_FORMATS: [&str; 3] = ["html", "docx", "pdf"];
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+fn require_settings_permission(user: &UserSession) -> Result<(), HrmError> {
+    if user.permissions.can_manage_settings {
+        Ok(())
+    } else {
+        Err(HrmError::permission_denied("Permission denied. You cannot manage letter templates."))
+    }
+}
+
+pub(crate) fn require_letter_permission(user: &UserSession) -> Result<(), HrmError> {
+    if user.permissions.can_view_employees && user.permissions.can_export_data {
+        Ok(())
+    } else {
+        Err(HrmError::permission_denied("Permission denied. You cannot issue letters."))
+    }
+}
+
+// Names inside {{ }} in a template body, in order of appearance
+fn merge_field_names(body: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        names.push(rest[start + 2..start + 2 + end].trim().to_string());
+        rest = &rest[start + 2 + end + 2..];
+    }
+    names
+}
+
+fn replace_merge_fields(body: &str, values: &HashMap<String, String>) -> String {
+    let mut output = String::with_capacity(body.len());
+    let mut rest = body;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        output.push_str(&rest[..start]);
+        let name = rest[start + 2..start + 2 + end].trim();
+        output.push_str(values.get(name).map(String::as_str).unwrap_or(""));
+        rest = &rest[start + 2 + end + 2..];
+    }
+    output.push_str(rest);
+    output
+}
+
+fn validate_template(conn: &Connection, template: &LetterTemplate) -> Result<(), HrmError> {
+    if template.name.trim().is_empty() {
+        return Err(HrmError::field("name", "Template name is required"));
+    }
+    if template.body.trim().is_empty() {
+        return Err(HrmError::field("body", "Template body is required"));
+    }
+    if template.language.as_deref().is_some_and(|l| !LANGUAGES.contains(&l)) {
+        return Err(HrmError::field("language", "Language must be en, si or ta"));
+    }
+    let custom_names: Vec<String> = load_definitions(conn, true)?.into_iter().map(|d| d.name).collect();
+    let unknown: Vec<String> = merge_field_names(&template.body)
+        .into_iter()
+        .filter(|name| !MERGE_FIELDS.contains(&name.as_str()) && !custom_names.contains(name))
+        .collect();
+    if !unknown.is_empty() {
+        return Err(HrmError::field("body", format!("Unknown merge fields: {}", unknown.join(", "))));
+    }
+    Ok(())
+}
+
+pub(crate) fn load_template(conn: &Connection, id: i64) -> Result<LetterTemplate, HrmError> {
+    conn.query_row(
+        "SELECT id, name, category, body, language, is_active, updated_by, updated_at FROM letter_templates WHERE id = ?1",
+        [id],
+        template_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| HrmError::not_found("Letter template not found"))
+}
+
+fn template_from_row(row: &rusqlite::Row) -> rusqlite::Result<LetterTemplate> {
+    Ok(LetterTemplate {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        category: row.get(2)?,
+        body: row.get(3)?,
+        language: row.get(4)?,
+        is_active: row.get(5)?,
+        updated_by: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+// Merge values for an employee in the letter's language. Salary and personal
+// fields are left blank unless the user may see them.
+fn letter_values(
+    conn: &Connection,
+    employee: &Employee,
+    language: &str,
+    permissions: &UserPermissions,
+    reference_number: &str,
+) -> Result<HashMap<String, String>, HrmError> {
+    let value = |v: &Option<String>| v.clone().unwrap_or_default();
+    let mut values: HashMap<String, String> = employee.custom_fields.clone();
+    values.insert("epf_number".to_string(), employee.epf_number.clone());
+    values.insert("name_with_initials".to_string(), employee.name_with_initials.clone());
+    values.insert("full_name".to_string(), employee.full_name.clone());
+    if permissions.can_view_sensitive_data {
+        values.insert("nic".to_string(), value(&employee.nic));
+        values.insert("address".to_string(), value(&employee.address));
+    }
+    values.insert("designation".to_string(), value(&employee.designation));
+    values.insert("department".to_string(), value(&employee.department));
+    values.insert("allocation".to_string(), value(&employee.allocation));
+    values.insert("cader".to_string(), value(&employee.cader));
+    let (date_format, buddhist_era) = date_display(conn);
+    let date = |v: &Option<String>| v.as_deref().map(|d| format_date(d, &date_format, buddhist_era)).unwrap_or_default();
+    values.insert("date_of_join".to_string(), date(&employee.date_of_join));
+    values.insert("date_of_resign".to_string(), date(&employee.date_of_resign));
+    values.insert("working_status".to_string(), employee.working_status.clone());
+    // The latest transfer, for transfer letters
+    if let Some(transfer) = load_transfers(conn, &employee.epf_number)?.into_iter().next() {
+        values.insert("transfer_to".to_string(), transfer.to_location);
+        values.insert("transfer_date".to_string(), date(&Some(transfer.effective_date)));
+    }
+    let today = Local::now().date_naive();
+    if permissions.can_view_salaries {
+        if let Some((salary, currency)) = effective_salary(conn, &employee.epf_number, &today.format("%Y-%m-%d").to_string())? {
+            values.insert("basic_salary".to_string(), format!("{:.2}", salary));
+            values.insert("salary_currency".to_string(), currency);
+        }
+    }
+    let company = get_setting(conn, "company_name").unwrap_or_else(|| DEFAULT_COMPANY_NAME.to_string());
+    values.insert("company_name".to_string(), translate(conn, language, "company_name", &company));
+    values.insert("today".to_string(), date(&Some(today.format("%Y-%m-%d").to_string())));
+    values.insert("reference_number".to_string(), reference_number.to_string());
+    Ok(values)
+}
+
+// Printable letter on the company heading. Blank lines in the text separate
+// paragraphs.
+fn letter_html(company: &str, title: &str, text: &str, language: &str) -> String {
+    let mut html = format!("<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"UTF-8\">\n", language);
+    html.push_str(&format!("<title>{}</title>\n", xml_escape(title)));
+    html.push_str("<style>\n");
+    html.push_str(&format!(
+        "  body {{ font-family: {}; font-size: 14px; line-height: 1.6; padding: 40px; }}\n",
+        DOCUMENT_FONTS
+    ));
+    html.push_str("  .header { text-align: center; margin-bottom: 30px; border-bottom: 2px solid #2563eb; padding-bottom: 15px; }\n");
+    html.push_str("  .header h1 { color: #1e40af; font-size: 24px; margin: 0; }\n");
+    html.push_str("  p { margin: 0 0 14px; }\n");
+    html.push_str("  @media print { body { padding: 20px; } }\n");
+    html.push_str("</style>\n</head>\n<body>\n");
+    html.push_str(&format!("<div class=\"header\">\n  <h1>{}</h1>\n</div>\n", xml_escape(company)));
+    for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        let lines: Vec<String> = paragraph.lines().map(xml_escape).collect();
+        html.push_str(&format!("<p>{}</p>\n", lines.join("<br>")));
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+// PDF letter: the company heading followed by the letter's paragraphs
+fn write_letter_pdf(path: &str, company: &str, text: &str) -> Result<(), HrmError> {
+    let mut blocks = vec![PdfBlock::Heading(company.to_string())];
+    for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        blocks.push(PdfBlock::Paragraph(paragraph.to_string()));
+        blocks.push(PdfBlock::Paragraph(String::new()));
+    }
+    write_pdf(path, false, &blocks)
+}
+
+// Minimal Word document: the company heading followed by the letter text,
+// with fonts set for the letter's language
+fn write_letter_docx(path: &str, company: &str, text: &str, language: &str) -> Result<(), HrmError> {
+    let zip_error = |e: zip::result::ZipError| HrmError::io(format!("Failed to write letter: {}", e));
+    let file = File::create(path).map_err(|e| HrmError::io(format!("Failed to save file: {}", e)))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    
+    let fonts = docx_fonts(language);
+    let mut body = format!(
+        "<w:p><w:pPr><w:jc w:val=\"center\"/></w:pPr><w:r><w:rPr>{}<w:b/><w:sz w:val=\"32\"/></w:rPr><w:t>{}</w:t></w:r></w:p>",
+        fonts,
+        xml_escape(company)
+    );
+    for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        body.push_str("<w:p>");
+        for (index, line) in paragraph.lines().enumerate() {
+            if index > 0 {
+                body.push_str("<w:r><w:br/></w:r>");
+            }
+            body.push_str(&format!("<w:r><w:rPr>{}</w:rPr><w:t xml:space=\"preserve\">{}</w:t></w:r>", fonts, xml_escape(line)));
+        }
+        body.push_str("</w:p>");
+    }
+    
+    zip.start_file("[Content_Types].xml", options).map_err(zip_error)?;
+    zip.write_all(
+        b"<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+<Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+<Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+<Override PartName=\"/word/document.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml\"/>\
+</Types>",
+    )?;
+    zip.start_file("_rels/.rels", options).map_err(zip_error)?;
+    zip.write_all(
+        b"<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+<Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"word/document.xml\"/>\
+</Relationships>",
+    )?;
+    zip.start_file("word/document.xml", options).map_err(zip_error)?;
+    zip.write_all(
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+<w:document xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\"><w:body>{}</w:body></w:document>",
+            body
+        )
+        .as_bytes(),
+    )?;
+    zip.finish().map_err(zip_error)?;
+    Ok(())
+}
+
+// Loads a template the user may issue in `format`: the user can issue
+// letters, the format is known and the template is in use and only merges in
+// details the user may see
+pub(crate) fn usable_template(
+    conn: &Connection,
+    user: &UserSession,
+    template_id: i64,
+    format: &str,
+) -> Result<LetterTemplate, HrmError> {
+    require_letter_permission(user)?;
+    if !LETTER_FORMATS.contains(&format) {
+        return Err(HrmError::validation(format!("Unsupported letter format: {}", format)));
+    }
+    let template = load_template(conn, template_id)?;
+    if !template.is_active {
+        return Err(HrmError::validation("This letter template is no longer in use"));
+    }
+    check_template_fields(user, &template)?;
+    Ok(template)
+}
+
+// Refuses templates that merge in details the user may not see, rather than
+// issuing a letter with gaps
+fn check_template_fields(user: &UserSession, template: &LetterTemplate) -> Result<(), HrmError> {
+    let fields = merge_field_names(&template.body);
+    if !user.permissions.can_view_salaries && fields.iter().any(|name| SALARY_FIELDS.contains(&name.as_str())) {
+        return Err(HrmError::permission_denied("Permission denied. This letter includes salary details."));
+    }
+    if !user.permissions.can_view_sensitive_data && fields.iter().any(|name| SENSITIVE_FIELDS.contains(&name.as_str())) {
+        return Err(HrmError::permission_denied("Permission denied. This letter includes personal details."));
+    }
+    Ok(())
+}
+
+// Renders a template for one employee, writes it to `destination_path` and
+// records the issue. Templates without a language follow the employee's
+// preferred language. Returns the letter's reference number.
+fn issue_letter(
+    conn: &mut Connection,
+    user: &UserSession,
+    template: &LetterTemplate,
+    employee: &Employee,
+    format: &str,
+    destination_path: &str,
+) -> Result<String, HrmError> {
+    let tx = conn.transaction()?;
+    let reference_number = write_letter(&tx, user, template, employee, format, destination_path)?;
+    tx.commit()?;
+    Ok(reference_number)
+}
+
+// issue_letter inside the caller's transaction, for letters that go with
+// another change such as a transfer
+pub(crate) fn write_letter(
+    tx: &Connection,
+    user: &UserSession,
+    template: &LetterTemplate,
+    employee: &Employee,
+    format: &str,
+    destination_path: &str,
+) -> Result<String, HrmError> {
+    tx.execute(
+        "INSERT INTO letter_issues (template_id, epf_number, format, issued_by) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![template.id, employee.epf_number, format, user.user_id],
+    )?;
+    let issue_id = tx.last_insert_rowid();
+    let reference_number = format!("LTR/{}/{:06}", Local::now().format("%Y"), issue_id);
+    tx.execute("UPDATE letter_issues SET reference_number = ?1 WHERE id = ?2", rusqlite::params![reference_number, issue_id])?;
+    
+    let language = template.language.clone().unwrap_or_else(|| employee_language(tx, &employee.epf_number));
+    let values = letter_values(tx, employee, &language, &user.permissions, &reference_number)?;
+    let text = replace_merge_fields(&template.body, &values);
+    let company = &values["company_name"];
+    match format {
+        "docx" => write_letter_docx(destination_path, company, &text, &language)?,
+        "pdf" => write_letter_pdf(destination_path, company, &text)?,
+        _ => fs::write(destination_path, letter_html(company, &template.name, &text, &language))
+            .map_err(|e| HrmError::io(format!("Failed to save file: {}", e)))?,
+    }
+    
+    log_audit_action(
+        tx,
+        Some(user.user_id),
+        &user.username,
+        "EXPORT",
+        "EMPLOYEE",
+        Some(&employee.epf_number),
+        None,
+        Some(&reference_number),
+        Some(&format!("Issued letter '{}' ({})", template.name, reference_number)),
+    );
+    Ok(reference_number)
+}
+
+#[tauri::command]
+pub fn get_letter_templates(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<LetterTemplate>, HrmError> {
+    logged_in_user(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, name, category, body, language, is_active, updated_by, updated_at FROM letter_templates ORDER BY category, name",
+    )?;
+    let templates = stmt.query_map([], template_from_row)?.collect::<Result<Vec<_>, _>>()?;
+    Ok(templates)
+}
+
+// Merge fields a template may use, including active custom fields
+#[tauri::command]
+pub fn get_letter_merge_fields(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<String>, HrmError> {
+    logged_in_user(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let mut fields: Vec<String> = MERGE_FIELDS.iter().map(|f| f.to_string()).collect();
+    fields.extend(load_definitions(&conn, true)?.into_iter().map(|d| d.name));
+    Ok(fields)
+}
+
+// Adds a template, or updates it when `id` is set. Returns the template id.
+#[tauri::command]
+pub fn save_letter_template(
+    template: LetterTemplate,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_settings_permission(&user)?;
+    
+    let conn = db.0.lock()?;
+    validate_template(&conn, &template)?;
+    let name = template.name.trim();
+    let result = match template.id {
+        Some(id) => conn
+            .execute(
+                "UPDATE letter_templates SET name = ?1, category = ?2, body = ?3, language = ?4, is_active = ?5,
+                     updated_by = ?6, updated_at = CURRENT_TIMESTAMP
+                 WHERE id = ?7",
+                rusqlite::params![
+                    name,
+                    template.category,
+                    template.body,
+                    template.language,
+                    template.is_active,
+                    user.username,
+                    id
+                ],
+            )
+            .map(|updated| (updated > 0).then_some(id)),
+        None => conn
+            .execute(
+                "INSERT INTO letter_templates (name, category, body, language, is_active, updated_by)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                rusqlite::params![name, template.category, template.body, template.language, template.is_active, user.username],
+            )
+            .map(|_| Some(conn.last_insert_rowid())),
+    };
+    let id = match result {
+        Ok(Some(id)) => id,
+        Ok(None) => return Err(HrmError::not_found("Letter template not found")),
+        Err(e) if e.to_string().contains("UNIQUE constraint") => {
+            return Err(HrmError::field("name", "A template with this name already exists"))
+        }
+        Err(e) => return Err(e.into()),
+    };
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        if template.id.is_some() { "UPDATE" } else { "CREATE" },
+        "LETTER_TEMPLATE",
+        Some(&id.to_string()),
+        None,
+        Some(name),
+        Some(&format!("Saved letter template '{}'", name)),
+    );
+    
+    Ok(id)
+}
+
+// Removes a template. Templates that have been issued are deactivated
+// instead, so the issue log keeps its reference.
+#[tauri::command]
+pub fn delete_letter_template(
+    id: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_settings_permission(&user)?;
+    
+    let conn = db.0.lock()?;
+    let template = load_template(&conn, id)?;
+    let issued: bool =
+        conn.query_row("SELECT EXISTS(SELECT 1 FROM letter_issues WHERE template_id = ?1)", [id], |row| row.get(0))?;
+    if issued {
+        conn.execute("UPDATE letter_templates SET is_active = 0 WHERE id = ?1", [id])?;
+    } else {
+        conn.execute("DELETE FROM letter_templates WHERE id = ?1", [id])?;
+    }
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "DELETE",
+        "LETTER_TEMPLATE",
+        Some(&id.to_string()),
+        Some(&template.name),
+        None,
+        Some(&format!(
+            "{} letter template '{}'",
+            if issued { "Deactivated" } else { "Deleted" },
+            template.name
+        )),
+    );
+    
+    Ok(())
+}
+
+// Fills a template for one employee and saves it as a printable HTML page
+// ("html"), a Word document ("docx") or a PDF ("pdf", English letters only).
+// Returns the reference number.
+#[tauri::command]
+pub fn generate_letter(
+    template_id: i64,
+    epf_number: String,
+    format: String,
+    destination_path: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<String, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    
+    let mut conn = db.0.lock()?;
+    let template = usable_template(&conn, &user, template_id, &format)?;
+    let employee = query_employee(&conn, &epf_number).map_err(|_| HrmError::not_found("Employee not found"))?;
+    if !user.can_access_department(employee.department.as_deref()) {
+        return Err(HrmError::permission_denied("Permission denied. You cannot issue letters in this department."));
+    }
+    issue_letter(&mut conn, &user, &template, &employee, &format, &destination_path)
+}
+
+// Fills a template for every employee matching `filters`, one file each in
+// `destination_folder`, e.g. increment letters for a whole department.
+// Employees outside the user's departments are left out; a letter that
+// cannot be written is reported and not recorded as issued.
+#[tauri::command]
+pub fn generate_letters_bulk(
+    template_id: i64,
+    filters: EmployeeFilters,
+    format: String,
+    destination_folder: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<BulkLetterResult, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    let folder = Path::new(&destination_folder);
+    if !folder.is_dir() {
+        return Err(HrmError::field("destination_folder", "Choose an existing folder for the letters"));
+    }
+    
+    let mut conn = db.0.lock()?;
+    let template = usable_template(&conn, &user, template_id, &format)?;
+    let employees: Vec<Employee> = query_employees(&conn, filters)?
+        .into_iter()
+        .filter(|e| user.can_access_department(e.department.as_deref()))
+        .collect();
+    let file_stem: String = template
+        .name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    
+    let mut result = BulkLetterResult { generated: 0, failed: Vec::new() };
+    for employee in &employees {
+        let file_name = format!("{}_{}.{}", employee.epf_number.replace(['/', '\\'], "_"), file_stem, format);
+        let path = folder.join(file_name).to_string_lossy().to_string();
+        match issue_letter(&mut conn, &user, &template, employee, &format, &path) {
+            Ok(_) => result.generated += 1,
+            Err(e) => result.failed.push(FieldError { field: employee.epf_number.clone(), message: e.to_string() }),
+        }
+    }
+    Ok(result)
+}
+
+// Letters issued, newest first, optionally for one employee
+#[tauri::command]
+pub fn get_letter_issues(
+    epf_number: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<LetterIssue>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view issued letters."));
+    }
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(
+        "SELECT i.id, i.reference_number, t.name, i.epf_number, e.name_with_initials, e.department, i.format,
+                u.username, i.issued_at
+         FROM letter_issues i
+         JOIN letter_templates t ON t.id = i.template_id
+         LEFT JOIN employees e ON e.epf_number = i.epf_number
+         LEFT JOIN users u ON u.id = i.issued_by
+         WHERE ?1 IS NULL OR i.epf_number = ?1
+         ORDER BY i.id DESC",
+    )?;
+    let issues = stmt
+        .query_map([&epf_number], |row| {
+            Ok((
+                row.get::<_, Option<String>>(5)?,
+                LetterIssue {
+                    id: row.get(0)?,
+                    reference_number: row.get(1)?,
+                    template_name: row.get(2)?,
+                    epf_number: row.get(3)?,
+                    name_with_initials: row.get(4)?,
+                    format: row.get(6)?,
+                    issued_by: row.get(7)?,
+                    issued_at: row.get(8)?,
+                },
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .filter(|(department, _)| user.can_access_department(department.as_deref()))
+        .map(|(_, issue)| issue)
+        .collect();
+    Ok(issues)
+}
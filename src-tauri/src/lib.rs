@@ -1,40 +1,133 @@
 use rusqlite::{Connection, Result as SqliteResult};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use tauri::Manager;
 
+pub mod access_review;
+pub mod address_commands;
+pub mod anonymization;
+pub mod approvals;
+pub mod asset_commands;
+pub mod attendance_commands;
+pub mod attendance_device;
+pub mod audit_archive;
 pub mod auth_commands;
+pub mod backup;
+pub mod bank_commands;
+pub mod budget_commands;
+pub mod cloud_backup;
 pub mod commands;
+pub mod compliance_commands;
+pub mod consent_commands;
+pub mod currency_commands;
+pub mod custom_fields;
+pub mod data_quality;
+pub mod dashboard_commands;
+pub mod deduction_commands;
+pub mod diagnostics;
+pub mod disciplinary_commands;
+pub mod employee_history;
+pub mod employee_pack;
+pub mod employee_relations;
+pub mod employment_periods;
+pub mod epf_numbering;
+pub mod error;
+pub mod exit_interview_commands;
+pub mod export_commands;
+pub mod festival_advance_commands;
+pub mod grievance_commands;
+pub mod headcount_commands;
+pub mod image_protocol;
+pub mod images;
+pub mod import_commands;
+pub mod incentive_commands;
+pub mod incident_commands;
+pub mod insurance_commands;
+pub mod instance_lock;
+pub mod interview_commands;
+pub mod journal;
+pub mod leave_commands;
+pub mod letter_commands;
+pub mod license_commands;
+pub mod localization;
+pub mod logging;
+pub mod master_list_commands;
+pub mod meal_commands;
+pub mod medical_commands;
 pub mod models;
+pub mod name_search;
+pub mod note_commands;
+pub mod org_chart_commands;
+pub mod payroll_commands;
+pub mod pdf;
+pub mod profile_completeness;
+pub mod notification_commands;
+pub mod qualification_commands;
+pub mod read_only;
+pub mod recruitment_commands;
+pub mod retention;
+pub mod salary_commands;
+pub mod saved_filter_commands;
+pub mod scheduler;
+pub mod secrets;
+pub mod settings_commands;
+pub mod skill_commands;
+pub mod statutory_commands;
+pub mod succession_commands;
+pub mod tag_commands;
+pub mod task_commands;
+pub mod training_commands;
+pub mod validation;
+pub mod visitor_commands;
+pub mod working_status_commands;
+pub mod xlsx;
+
+// Stored in PRAGMA user_version at the end of init_db. Bump it whenever
+// init_db gains a migration so the health check can spot databases that
+// were not fully migrated or come from a newer release. Version 3 covers the
+// approval engine, payroll locks, EPF number changes and the other tables
+// added alongside them.
+pub const SCHEMA_VERSION: i64 = 3;
 
 pub struct DbConnection(pub Mutex<Connection>);
 pub struct AppDataDir(pub PathBuf);
 pub struct CurrentUser(pub Mutex<Option<models::UserSession>>);
 
 pub fn init_db(app_handle: &tauri::AppHandle) -> SqliteResult<(Connection, PathBuf)> {
-    let app_dir = match app_handle.path().app_data_dir() {
-        Ok(dir) => dir,
-        Err(e) => {
-            eprintln!("Failed to get app data directory: {:?}", e);
-            // Fallback to current directory
-            std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."))
-        }
+    let (app_dir, dir_error) = match app_handle.path().app_data_dir() {
+        Ok(dir) => (dir, None),
+        // Fallback to current directory
+        Err(e) => (std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from(".")), Some(e)),
     };
+    let dir_created = std::fs::create_dir_all(&app_dir);
     
-    if let Err(e) = std::fs::create_dir_all(&app_dir) {
-        eprintln!("Failed to create app data directory: {:?}", e);
+    // Logs go under the app data directory, so start logging once it exists
+    logging::init(&app_dir);
+    if let Some(e) = dir_error {
+        tracing::error!("Failed to get app data directory: {:?}", e);
+    }
+    if let Err(e) = dir_created {
+        tracing::error!("Failed to create app data directory: {:?}", e);
     }
     
     // Create employee_images folder
     let images_dir = app_dir.join("employee_images");
     if let Err(e) = std::fs::create_dir_all(&images_dir) {
-        eprintln!("Failed to create employee images directory: {:?}", e);
+        tracing::error!("Failed to create employee images directory: {:?}", e);
     }
     
     let db_path = app_dir.join("hrm_system.db");
-    eprintln!("Database path: {:?}", db_path);
+    tracing::info!("Database path: {:?}", db_path);
+    
+    let conn = open_database(&app_dir)?;
     
-    let conn = Connection::open(&db_path)?;
+    Ok((conn, app_dir))
+}
+
+// Opens the database in the app data directory and brings its schema up to
+// date. Also used to re-open the connection after a backup is restored.
+pub fn open_database(app_dir: &Path) -> SqliteResult<Connection> {
+    let conn = Connection::open(app_dir.join("hrm_system.db"))?;
     
     // Create employees table
     conn.execute(
@@ -57,6 +150,11 @@ pub fn init_db(app_handle: &tauri::AppHandle) -> SqliteResult<(Connection, PathB
             allocation TEXT,
             department TEXT,
             image_path TEXT,
+            nic TEXT,
+            previous_epf_number TEXT,
+            district TEXT,
+            ds_division TEXT,
+            gn_division TEXT,
             created_at TEXT DEFAULT CURRENT_TIMESTAMP
         )",
         [],
@@ -82,6 +180,18 @@ pub fn init_db(app_handle: &tauri::AppHandle) -> SqliteResult<(Connection, PathB
             can_view_reports INTEGER DEFAULT 0,
             can_manage_settings INTEGER DEFAULT 0,
             can_backup_database INTEGER DEFAULT 0,
+            can_login_anytime INTEGER DEFAULT 0,
+            can_view_sensitive_data INTEGER DEFAULT 0,
+            can_manage_disciplinary INTEGER DEFAULT 0,
+            can_approve_attendance INTEGER DEFAULT 0,
+            can_manage_attendance INTEGER DEFAULT 0,
+            can_approve_leave INTEGER DEFAULT 0,
+            can_run_payroll INTEGER DEFAULT 0,
+            can_view_salaries INTEGER DEFAULT 0,
+            login_start_time TEXT,
+            login_end_time TEXT,
+            login_days TEXT,
+            deleted_at TEXT,
             created_at TEXT DEFAULT CURRENT_TIMESTAMP,
             last_login TEXT
         )",
@@ -103,7 +213,7 @@ pub fn init_db(app_handle: &tauri::AppHandle) -> SqliteResult<(Connection, PathB
              VALUES ('admin', ?1, 'System Administrator', 'admin', 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1)",
             [&default_password_hash],
         )?;
-        eprintln!("Created default admin user (username: admin, password: admin123)");
+        tracing::warn!("Created default admin user (username: admin, password: admin123)");
     }
     
     // Add new columns if they don't exist (for existing databases)
@@ -111,6 +221,12 @@ pub fn init_db(app_handle: &tauri::AppHandle) -> SqliteResult<(Connection, PathB
     let _ = conn.execute("ALTER TABLE employees ADD COLUMN designation TEXT", []);
     let _ = conn.execute("ALTER TABLE employees ADD COLUMN allocation TEXT", []);
     let _ = conn.execute("ALTER TABLE employees ADD COLUMN image_path TEXT", []);
+    let _ = conn.execute("ALTER TABLE employees ADD COLUMN nic TEXT", []);
+    let _ = conn.execute("ALTER TABLE employees ADD COLUMN previous_epf_number TEXT", []);
+    let _ = conn.execute("ALTER TABLE employees ADD COLUMN district TEXT", []);
+    let _ = conn.execute("ALTER TABLE employees ADD COLUMN ds_division TEXT", []);
+    let _ = conn.execute("ALTER TABLE employees ADD COLUMN gn_division TEXT", []);
+    let _ = conn.execute("CREATE INDEX IF NOT EXISTS idx_employees_nic ON employees(nic)", []);
     
     // Create audit_logs table for tracking all database actions
     conn.execute(
@@ -130,6 +246,24 @@ pub fn init_db(app_handle: &tauri::AppHandle) -> SqliteResult<(Connection, PathB
         [],
     )?;
     
+    let _ = conn.execute("ALTER TABLE audit_logs ADD COLUMN severity TEXT DEFAULT 'info'", []);
+    
+    // Create notifications table for alerts shown to users in the app
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS notifications (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id INTEGER NOT NULL,
+            title TEXT NOT NULL,
+            message TEXT NOT NULL,
+            severity TEXT NOT NULL DEFAULT 'info',
+            entity_type TEXT,
+            entity_id TEXT,
+            is_read INTEGER DEFAULT 0,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
     // Add permission columns to users table if they don't exist (migration)
     let _ = conn.execute("ALTER TABLE users ADD COLUMN can_view_employees INTEGER DEFAULT 1", []);
     let _ = conn.execute("ALTER TABLE users ADD COLUMN can_add_employees INTEGER DEFAULT 0", []);
@@ -142,17 +276,1385 @@ pub fn init_db(app_handle: &tauri::AppHandle) -> SqliteResult<(Connection, PathB
     let _ = conn.execute("ALTER TABLE users ADD COLUMN can_manage_settings INTEGER DEFAULT 0", []);
     let _ = conn.execute("ALTER TABLE users ADD COLUMN can_backup_database INTEGER DEFAULT 0", []);
     let _ = conn.execute("ALTER TABLE users ADD COLUMN can_view_audit_logs INTEGER DEFAULT 0", []);
+    let _ = conn.execute("ALTER TABLE users ADD COLUMN can_login_anytime INTEGER DEFAULT 0", []);
+    let _ = conn.execute("ALTER TABLE users ADD COLUMN can_view_sensitive_data INTEGER DEFAULT 0", []);
+    let _ = conn.execute("ALTER TABLE users ADD COLUMN can_manage_disciplinary INTEGER DEFAULT 0", []);
+    let _ = conn.execute("ALTER TABLE users ADD COLUMN can_approve_attendance INTEGER DEFAULT 0", []);
+    
+    // Attendance, leave and payroll used to be covered by the employee edit
+    // and sensitive data permissions; existing users keep that access
+    let split_permissions = [
+        ("can_manage_attendance", "can_edit_employees"),
+        ("can_approve_leave", "can_edit_employees"),
+        ("can_run_payroll", "can_edit_employees AND can_view_sensitive_data"),
+        ("can_view_salaries", "can_view_sensitive_data"),
+    ];
+    for (column, granted_by) in split_permissions {
+        if conn.execute(&format!("ALTER TABLE users ADD COLUMN {} INTEGER DEFAULT 0", column), []).is_ok() {
+            conn.execute(&format!("UPDATE users SET {} = CASE WHEN {} THEN 1 ELSE 0 END", column, granted_by), [])?;
+        }
+    }
+    
+    // Allowed login hours per user (NULL start/end means unrestricted)
+    let _ = conn.execute("ALTER TABLE users ADD COLUMN login_start_time TEXT", []);
+    let _ = conn.execute("ALTER TABLE users ADD COLUMN login_end_time TEXT", []);
+    let _ = conn.execute("ALTER TABLE users ADD COLUMN login_days TEXT", []);
+    
+    // Soft-deleted users stay in the table so audit logs keep their reference
+    let _ = conn.execute("ALTER TABLE users ADD COLUMN deleted_at TEXT", []);
+    
+    // Create sessions table so administrators can revoke access
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS sessions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id INTEGER NOT NULL,
+            device_name TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            last_seen_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            ended_at TEXT,
+            end_reason TEXT
+        )",
+        [],
+    )?;
     
     // Update existing admin users to have all permissions
     let _ = conn.execute(
-        "UPDATE users SET can_view_employees=1, can_add_employees=1, can_edit_employees=1, can_delete_employees=1, can_manage_users=1, can_view_all_departments=1, can_export_data=1, can_view_reports=1, can_manage_settings=1, can_backup_database=1, can_view_audit_logs=1 WHERE role='admin'",
+        "UPDATE users SET can_view_employees=1, can_add_employees=1, can_edit_employees=1, can_delete_employees=1, can_manage_users=1, can_view_all_departments=1, can_export_data=1, can_view_reports=1, can_manage_settings=1, can_backup_database=1, can_view_audit_logs=1, can_login_anytime=1, can_view_sensitive_data=1, can_manage_disciplinary=1, can_approve_attendance=1, can_manage_attendance=1, can_approve_leave=1, can_run_payroll=1, can_view_salaries=1 WHERE role='admin'",
         [],
     );
+    access_review::record_access_baseline(&conn)?;
     
-    // Migrate job_role to designation if job_role exists
-    let _ = conn.execute("UPDATE employees SET designation = job_role WHERE designation IS NULL AND job_role IS NOT NULL", []);
+    // Employee photo history with original vs stored size
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS image_metadata (
+            image_path TEXT PRIMARY KEY,
+            epf_number TEXT NOT NULL,
+            original_size INTEGER,
+            original_width INTEGER,
+            original_height INTEGER,
+            stored_size INTEGER,
+            width INTEGER,
+            height INTEGER,
+            format TEXT,
+            is_primary INTEGER DEFAULT 0,
+            content_hash TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
     
-    Ok((conn, app_dir))
+    // Photo history: every upload is kept and one is marked primary
+    let _ = conn.execute("ALTER TABLE image_metadata ADD COLUMN is_primary INTEGER DEFAULT 0", []);
+    
+    // SHA-256 of the uploaded bytes, for duplicate detection
+    let _ = conn.execute("ALTER TABLE image_metadata ADD COLUMN content_hash TEXT", []);
+    let _ = conn.execute("CREATE INDEX IF NOT EXISTS idx_image_metadata_hash ON image_metadata(content_hash)", []);
+    
+    // Create app_settings table for installation-wide configuration
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS app_settings (
+            key TEXT PRIMARY KEY,
+            value TEXT,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
+    // Per-installation choice of which employee fields are required,
+    // optional or hidden; fields without a row use the built-in default
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS field_config (
+            field TEXT PRIMARY KEY,
+            visibility TEXT NOT NULL DEFAULT 'optional',
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
+    // Installation-defined employee fields and their values
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS custom_field_definitions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT UNIQUE NOT NULL,
+            field_type TEXT NOT NULL DEFAULT 'text',
+            options TEXT,
+            is_active INTEGER DEFAULT 1,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS employee_custom_values (
+            epf_number TEXT NOT NULL,
+            field_id INTEGER NOT NULL,
+            value TEXT NOT NULL,
+            PRIMARY KEY (epf_number, field_id)
+        )",
+        [],
+    )?;
+    
+    // Free-form labels for ad-hoc employee groupings
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT UNIQUE NOT NULL,
+            color TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS employee_tags (
+            epf_number TEXT NOT NULL,
+            tag_id INTEGER NOT NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (epf_number, tag_id)
+        )",
+        [],
+    )?;
+    
+    // Timeline of HR notes on an employee (conversations, warnings, welfare)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS employee_notes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            epf_number TEXT NOT NULL,
+            author_id INTEGER,
+            author_name TEXT,
+            note TEXT NOT NULL,
+            is_pinned INTEGER DEFAULT 0,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            updated_at TEXT
+        )",
+        [],
+    )?;
+    let _ = conn.execute("CREATE INDEX IF NOT EXISTS idx_employee_notes_epf ON employee_notes(epf_number)", []);
+    
+    // Education and professional qualifications
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS employee_qualifications (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            epf_number TEXT NOT NULL,
+            level TEXT NOT NULL,
+            title TEXT,
+            institute TEXT,
+            year INTEGER,
+            document_path TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    let _ = conn.execute("CREATE INDEX IF NOT EXISTS idx_employee_qualifications_epf ON employee_qualifications(epf_number)", []);
+    
+    // Skills matrix: machines, lines and competencies per employee
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS skills (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT UNIQUE NOT NULL,
+            category TEXT,
+            description TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS employee_skills (
+            epf_number TEXT NOT NULL,
+            skill_id INTEGER NOT NULL,
+            level INTEGER NOT NULL,
+            certified_date TEXT,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (epf_number, skill_id)
+        )",
+        [],
+    )?;
+    
+    // Training sessions and who attended them
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS trainings (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            course TEXT NOT NULL,
+            training_date TEXT NOT NULL,
+            provider TEXT,
+            cost REAL,
+            notes TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS training_attendance (
+            training_id INTEGER NOT NULL,
+            epf_number TEXT NOT NULL,
+            PRIMARY KEY (training_id, epf_number)
+        )",
+        [],
+    )?;
+    
+    // Warnings and suspensions; warnings lapse on expires_on
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS disciplinary_actions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            epf_number TEXT NOT NULL,
+            action_date TEXT NOT NULL,
+            action_type TEXT NOT NULL,
+            description TEXT NOT NULL,
+            document_path TEXT,
+            expires_on TEXT,
+            is_withdrawn INTEGER DEFAULT 0,
+            recorded_by TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    let _ = conn.execute("CREATE INDEX IF NOT EXISTS idx_disciplinary_actions_epf ON disciplinary_actions(epf_number)", []);
+    
+    // Workplace accidents and incidents
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS incidents (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            incident_date TEXT NOT NULL,
+            location TEXT NOT NULL,
+            severity TEXT NOT NULL,
+            description TEXT NOT NULL,
+            reported_to_labour_dept INTEGER DEFAULT 0,
+            labour_dept_reported_on TEXT,
+            recorded_by TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS incident_injuries (
+            incident_id INTEGER NOT NULL,
+            epf_number TEXT NOT NULL,
+            PRIMARY KEY (incident_id, epf_number)
+        )",
+        [],
+    )?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS incident_attachments (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            incident_id INTEGER NOT NULL,
+            file_path TEXT NOT NULL
+        )",
+        [],
+    )?;
+    
+    // Employee grievances; confidential ones are limited to user managers
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS grievances (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            epf_number TEXT,
+            subject TEXT NOT NULL,
+            description TEXT,
+            category TEXT,
+            raised_on TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'open',
+            assigned_to INTEGER,
+            is_confidential INTEGER DEFAULT 0,
+            resolution TEXT,
+            resolved_at TEXT,
+            recorded_by TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
+    // Uniforms, lockers, tools and equipment issued to employees
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS assets (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            category TEXT NOT NULL,
+            asset_code TEXT UNIQUE,
+            is_returnable INTEGER DEFAULT 1,
+            is_active INTEGER DEFAULT 1,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS asset_issues (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            asset_id INTEGER NOT NULL,
+            epf_number TEXT NOT NULL,
+            quantity INTEGER NOT NULL DEFAULT 1,
+            issued_on TEXT NOT NULL,
+            returned_on TEXT,
+            return_condition TEXT,
+            notes TEXT,
+            issued_by TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    let _ = conn.execute("CREATE INDEX IF NOT EXISTS idx_asset_issues_epf ON asset_issues(epf_number)", []);
+    
+    // Gate office visitor log
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS visitors (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            nic TEXT NOT NULL,
+            company TEXT,
+            purpose TEXT,
+            host_epf_number TEXT,
+            badge_number TEXT,
+            time_in TEXT NOT NULL,
+            time_out TEXT,
+            checked_in_by TEXT
+        )",
+        [],
+    )?;
+    let _ = conn.execute("CREATE INDEX IF NOT EXISTS idx_visitors_time_in ON visitors(time_in)", []);
+    
+    // Canteen meals, one row per employee per meal
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS meal_records (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            epf_number TEXT NOT NULL,
+            meal_date TEXT NOT NULL,
+            meal_type TEXT NOT NULL,
+            source TEXT NOT NULL DEFAULT 'manual',
+            recorded_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE (epf_number, meal_date, meal_type)
+        )",
+        [],
+    )?;
+    let _ = conn.execute("CREATE INDEX IF NOT EXISTS idx_meal_records_date ON meal_records(meal_date)", []);
+    
+    // Recurring payroll deductions (welfare society, union, death donation fund)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS deduction_types (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT UNIQUE NOT NULL,
+            payee TEXT,
+            default_amount REAL,
+            is_active INTEGER DEFAULT 1,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS employee_deductions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            epf_number TEXT NOT NULL,
+            deduction_type_id INTEGER NOT NULL,
+            amount REAL NOT NULL,
+            start_date TEXT NOT NULL,
+            end_date TEXT,
+            notes TEXT,
+            created_by TEXT,
+            festival_advance_id INTEGER,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    let _ = conn.execute("CREATE INDEX IF NOT EXISTS idx_employee_deductions_epf ON employee_deductions(epf_number)", []);
+    
+    // Seasonal advances, recovered through employee_deductions once approved
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS festival_advances (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            epf_number TEXT NOT NULL,
+            festival TEXT NOT NULL,
+            amount REAL NOT NULL,
+            installments INTEGER NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            requested_on TEXT NOT NULL,
+            requested_by TEXT,
+            first_recovery_month TEXT,
+            approved_by TEXT,
+            approved_at TEXT,
+            rejection_reason TEXT
+        )",
+        [],
+    )?;
+    let _ = conn.execute("ALTER TABLE employee_deductions ADD COLUMN festival_advance_id INTEGER", []);
+    
+    // Group insurance: one policy row per policy year
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS insurance_policies (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            policy_number TEXT UNIQUE NOT NULL,
+            insurer TEXT NOT NULL,
+            policy_type TEXT,
+            start_date TEXT NOT NULL,
+            end_date TEXT NOT NULL,
+            premium REAL,
+            notes TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS insurance_enrollments (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            policy_id INTEGER NOT NULL,
+            epf_number TEXT NOT NULL,
+            enrolled_on TEXT NOT NULL,
+            ended_on TEXT
+        )",
+        [],
+    )?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS insurance_dependents (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            enrollment_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            relationship TEXT NOT NULL,
+            dob TEXT
+        )",
+        [],
+    )?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS insurance_claims (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            enrollment_id INTEGER NOT NULL,
+            claimant TEXT,
+            claim_date TEXT NOT NULL,
+            amount_claimed REAL NOT NULL,
+            amount_settled REAL,
+            status TEXT NOT NULL DEFAULT 'submitted',
+            notes TEXT,
+            created_by TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS insurance_claim_documents (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            claim_id INTEGER NOT NULL,
+            file_path TEXT NOT NULL
+        )",
+        [],
+    )?;
+    
+    // Structured exit interviews, one per leaver
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS exit_interviews (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            epf_number TEXT UNIQUE NOT NULL,
+            interview_date TEXT NOT NULL,
+            primary_reason TEXT NOT NULL,
+            secondary_reasons TEXT,
+            would_rejoin INTEGER,
+            comments TEXT,
+            interviewer TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
+    // Join/resign cycles; employees.date_of_join/date_of_resign mirror the latest
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS employment_periods (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            epf_number TEXT NOT NULL,
+            start_date TEXT NOT NULL,
+            end_date TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    let _ = conn.execute("CREATE INDEX IF NOT EXISTS idx_employment_periods_epf ON employment_periods(epf_number)", []);
+    
+    // Transfers out to another branch or company. The employee's record and
+    // periods stay here; new_epf_number is their number at the receiving end.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS employee_transfers (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            epf_number TEXT NOT NULL,
+            from_location TEXT,
+            to_location TEXT NOT NULL,
+            effective_date TEXT NOT NULL,
+            reason TEXT,
+            new_epf_number TEXT,
+            transferred_by INTEGER,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
+    // Corrected EPF numbers. Payroll lines and audit entries keep the number
+    // they were recorded under and are found through this mapping.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS epf_number_changes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            old_epf_number TEXT NOT NULL,
+            new_epf_number TEXT NOT NULL,
+            changed_by INTEGER,
+            changed_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    let _ = conn.execute("CREATE INDEX IF NOT EXISTS idx_epf_number_changes_new ON epf_number_changes(new_epf_number)", []);
+    
+    // Seed one period for employees recorded before periods existed
+    conn.execute(
+        "INSERT INTO employment_periods (epf_number, start_date, end_date)
+         SELECT epf_number, date_of_join, NULLIF(date_of_resign, '') FROM employees e
+         WHERE date_of_join IS NOT NULL AND date_of_join != ''
+           AND NOT EXISTS (SELECT 1 FROM employment_periods p WHERE p.epf_number = e.epf_number)",
+        [],
+    )?;
+    
+    // Installation-defined working statuses; counts_as_active decides whether
+    // employees in the status are included in headcounts
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS working_statuses (
+            code TEXT PRIMARY KEY,
+            label TEXT NOT NULL,
+            counts_as_active INTEGER NOT NULL DEFAULT 1,
+            color TEXT,
+            sort_order INTEGER NOT NULL DEFAULT 0,
+            is_system INTEGER NOT NULL DEFAULT 0
+        )",
+        [],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO working_statuses (code, label, counts_as_active, color, sort_order, is_system) VALUES
+            ('active', 'Active', 1, '#16a34a', 0, 1),
+            ('resign', 'Resigned', 0, '#dc2626', 100, 1)",
+        [],
+    )?;
+    
+    // Master lists of departments and designations offered in dropdowns,
+    // e.g. the import template. Values already on employees are added so an
+    // existing installation starts with what it uses.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS departments (
+            name TEXT PRIMARY KEY,
+            sort_order INTEGER NOT NULL DEFAULT 0
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS designations (
+            name TEXT PRIMARY KEY,
+            sort_order INTEGER NOT NULL DEFAULT 0
+        )",
+        [],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO departments (name)
+         SELECT DISTINCT trim(department) FROM employees WHERE trim(COALESCE(department, '')) != ''",
+        [],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO designations (name)
+         SELECT DISTINCT trim(designation) FROM employees WHERE trim(COALESCE(designation, '')) != ''",
+        [],
+    )?;
+    
+    // Approved headcount per department (optionally per cader) over a period
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS headcount_budgets (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            department TEXT NOT NULL,
+            cader TEXT,
+            budgeted_count INTEGER NOT NULL,
+            effective_from TEXT NOT NULL,
+            effective_to TEXT,
+            created_by TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
+    // Recruitment: open positions and the people who applied for them
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS vacancies (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            department TEXT,
+            cader TEXT,
+            designation TEXT,
+            positions INTEGER NOT NULL DEFAULT 1,
+            status TEXT NOT NULL DEFAULT 'open',
+            opened_on TEXT NOT NULL,
+            closed_on TEXT,
+            notes TEXT,
+            created_by TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS candidates (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            vacancy_id INTEGER NOT NULL,
+            full_name TEXT NOT NULL,
+            name_with_initials TEXT,
+            nic TEXT,
+            dob TEXT,
+            mobile TEXT,
+            address TEXT,
+            applied_on TEXT NOT NULL,
+            stage TEXT NOT NULL DEFAULT 'applied',
+            stage_changed_on TEXT,
+            employee_epf_number TEXT,
+            notes TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    let _ = conn.execute("CREATE INDEX IF NOT EXISTS idx_candidates_vacancy ON candidates(vacancy_id)", []);
+    
+    // Interview slots for candidates; score_sheet holds the interviewer's
+    // evaluation as JSON (see models::ScoreSheet)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS interviews (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            candidate_id INTEGER NOT NULL,
+            interviewer_user_id INTEGER NOT NULL,
+            scheduled_at TEXT NOT NULL,
+            duration_minutes INTEGER NOT NULL DEFAULT 30,
+            location TEXT,
+            status TEXT NOT NULL DEFAULT 'scheduled',
+            score_sheet TEXT,
+            overall_score REAL,
+            evaluated_at TEXT,
+            created_by TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    let _ = conn.execute("CREATE INDEX IF NOT EXISTS idx_interviews_candidate ON interviews(candidate_id)", []);
+    
+    // Basic salary changes; the latest revision effective on a date is the
+    // salary payroll uses for it
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS salary_revisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            epf_number TEXT NOT NULL,
+            old_salary REAL,
+            new_salary REAL NOT NULL,
+            effective_date TEXT NOT NULL,
+            reason TEXT NOT NULL,
+            approved_by TEXT NOT NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    let _ = conn.execute("ALTER TABLE salary_revisions ADD COLUMN currency TEXT NOT NULL DEFAULT 'LKR'", []);
+    let _ = conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_salary_revisions_epf ON salary_revisions(epf_number, effective_date)",
+        [],
+    );
+    
+    // Supervisor for the org chart; managed separately from the employee form
+    let _ = conn.execute("ALTER TABLE employees ADD COLUMN reports_to TEXT", []);
+    let _ = conn.execute("CREATE INDEX IF NOT EXISTS idx_employees_reports_to ON employees(reports_to)", []);
+    
+    // Succession planning: key positions, the skills they need and nominees
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS key_positions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            department TEXT,
+            incumbent_epf TEXT,
+            risk_level TEXT NOT NULL DEFAULT 'medium',
+            notes TEXT,
+            is_active INTEGER NOT NULL DEFAULT 1,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS key_position_skills (
+            position_id INTEGER NOT NULL,
+            skill_id INTEGER NOT NULL,
+            PRIMARY KEY (position_id, skill_id)
+        )",
+        [],
+    )?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS successors (
+            position_id INTEGER NOT NULL,
+            epf_number TEXT NOT NULL,
+            readiness TEXT NOT NULL,
+            notes TEXT,
+            nominated_by TEXT,
+            nominated_on TEXT,
+            PRIMARY KEY (position_id, epf_number)
+        )",
+        [],
+    )?;
+    
+    // Monthly headcount taken by the scheduler, for point-in-time reports
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS headcount_snapshots (
+            month TEXT NOT NULL,
+            department TEXT NOT NULL,
+            cader TEXT NOT NULL,
+            working_status TEXT NOT NULL,
+            headcount INTEGER NOT NULL,
+            taken_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (month, department, cader, working_status)
+        )",
+        [],
+    )?;
+    
+    // Set once personal details have been anonymized
+    let _ = conn.execute("ALTER TABLE employees ADD COLUMN anonymized_at TEXT", []);
+    
+    // Signed consent and acknowledgement forms (PDPA); withdrawn consents are
+    // kept with withdrawn_on set
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS employee_consents (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            epf_number TEXT NOT NULL,
+            consent_type TEXT NOT NULL,
+            signed_on TEXT NOT NULL,
+            document_path TEXT,
+            withdrawn_on TEXT,
+            recorded_by TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    let _ = conn.execute("CREATE INDEX IF NOT EXISTS idx_employee_consents_epf ON employee_consents(epf_number)", []);
+    
+    // Retention rules applied by the scheduler; all start disabled and only
+    // run after their dry run has been confirmed
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS retention_rules (
+            rule_key TEXT PRIMARY KEY,
+            retain_value INTEGER NOT NULL,
+            retain_unit TEXT NOT NULL,
+            is_enabled INTEGER NOT NULL DEFAULT 0,
+            confirmed_at TEXT,
+            last_run_at TEXT,
+            last_run_count INTEGER,
+            updated_by TEXT,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO retention_rules (rule_key, retain_value, retain_unit) VALUES
+            ('audit_logs', 7, 'years'),
+            ('resigned_employees', 10, 'years'),
+            ('visitors', 2, 'years'),
+            ('notifications', 6, 'months')",
+        [],
+    )?;
+    
+    // Result of checking each backup file after it was written
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS backup_verifications (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            backup_id TEXT NOT NULL,
+            passed INTEGER NOT NULL,
+            details TEXT,
+            verified_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_backup_verifications_backup ON backup_verifications(backup_id)",
+        [],
+    )?;
+    
+    // Fingerprint terminals that push punches to the built-in listener. A
+    // terminal that connects before it is registered is stored inactive.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS attendance_devices (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            serial_number TEXT NOT NULL UNIQUE,
+            name TEXT,
+            location TEXT,
+            is_active INTEGER NOT NULL DEFAULT 0,
+            ip_address TEXT,
+            last_seen_at TEXT,
+            last_stamp TEXT,
+            offline_alerted INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
+    // Raw clock-in/out events. punch_state follows the terminal codes:
+    // 0 check in, 1 check out, 2 break out, 3 break in, 4 OT in, 5 OT out.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS attendance_punches (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            epf_number TEXT NOT NULL,
+            punch_time TEXT NOT NULL,
+            punch_state INTEGER NOT NULL DEFAULT 0,
+            verify_mode INTEGER,
+            device_serial TEXT,
+            source TEXT NOT NULL DEFAULT 'device',
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(epf_number, punch_time, device_serial)
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_attendance_punches_time ON attendance_punches(punch_time)",
+        [],
+    )?;
+    
+    // Device punches for a month whose payroll is locked. They are added to
+    // attendance_punches when that month's payroll run is reversed.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS quarantined_punches (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            epf_number TEXT NOT NULL,
+            punch_time TEXT NOT NULL,
+            punch_state INTEGER NOT NULL DEFAULT 0,
+            verify_mode INTEGER,
+            device_serial TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(epf_number, punch_time, device_serial)
+        )",
+        [],
+    )?;
+    // Corrected punches keep the time the device first recorded
+    let _ = conn.execute("ALTER TABLE attendance_punches ADD COLUMN original_punch_time TEXT", []);
+    let _ = conn.execute("ALTER TABLE attendance_punches ADD COLUMN correction_id INTEGER", []);
+    
+    // Manual punch corrections; applied to attendance_punches once approved
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS attendance_corrections (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            epf_number TEXT NOT NULL,
+            punch_id INTEGER,
+            original_time TEXT,
+            original_state INTEGER,
+            new_time TEXT NOT NULL,
+            new_state INTEGER NOT NULL DEFAULT 0,
+            reason TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            requested_by INTEGER,
+            requested_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            reviewed_by INTEGER,
+            reviewed_at TEXT,
+            review_note TEXT
+        )",
+        [],
+    )?;
+    
+    // Leave taken, one row per day so attendance can be matched against it
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS leave_records (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            epf_number TEXT NOT NULL,
+            leave_date TEXT NOT NULL,
+            leave_type TEXT NOT NULL,
+            days REAL NOT NULL DEFAULT 1,
+            reason TEXT,
+            recorded_by INTEGER,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(epf_number, leave_date)
+        )",
+        [],
+    )?;
+    
+    // Leave asked for and waiting on the approval chain. Approval writes the
+    // days into leave_records.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS leave_requests (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            epf_number TEXT NOT NULL,
+            from_date TEXT NOT NULL,
+            to_date TEXT NOT NULL,
+            leave_type TEXT NOT NULL,
+            half_day INTEGER NOT NULL DEFAULT 0,
+            reason TEXT,
+            status TEXT NOT NULL DEFAULT 'pending',
+            requested_by INTEGER,
+            requested_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            reviewed_by INTEGER,
+            reviewed_at TEXT,
+            review_note TEXT
+        )",
+        [],
+    )?;
+    
+    // Overtime asked for on a day. With attendance_ot_requires_approval on,
+    // only approved hours count as overtime.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS overtime_requests (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            epf_number TEXT NOT NULL,
+            work_date TEXT NOT NULL,
+            hours REAL NOT NULL,
+            reason TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            requested_by INTEGER,
+            requested_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            reviewed_by INTEGER,
+            reviewed_at TEXT,
+            review_note TEXT
+        )",
+        [],
+    )?;
+    
+    // Working hours breaches already notified, so each is alerted on once
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS working_hours_alerts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            epf_number TEXT NOT NULL,
+            rule TEXT NOT NULL,
+            period_start TEXT NOT NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(epf_number, rule, period_start)
+        )",
+        [],
+    )?;
+    
+    // Payroll runs. A locked run freezes its month; reversing it keeps the
+    // lines for the record and lets the month be run again.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS payroll_runs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            month TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'locked',
+            employee_count INTEGER NOT NULL DEFAULT 0,
+            total_gross REAL NOT NULL DEFAULT 0,
+            total_net REAL NOT NULL DEFAULT 0,
+            total_epf_employee REAL NOT NULL DEFAULT 0,
+            total_epf_employer REAL NOT NULL DEFAULT 0,
+            total_etf REAL NOT NULL DEFAULT 0,
+            locked_by TEXT,
+            locked_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            reversed_by TEXT,
+            reversed_at TEXT,
+            reversal_reason TEXT
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_payroll_runs_locked ON payroll_runs(month) WHERE status = 'locked'",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS payroll_lines (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            run_id INTEGER NOT NULL,
+            epf_number TEXT NOT NULL,
+            name_with_initials TEXT NOT NULL,
+            department TEXT,
+            allocation TEXT,
+            basic_salary REAL NOT NULL,
+            working_days INTEGER NOT NULL DEFAULT 0,
+            worked_days INTEGER NOT NULL DEFAULT 0,
+            no_pay_days REAL NOT NULL DEFAULT 0,
+            no_pay_deduction REAL NOT NULL DEFAULT 0,
+            ot_hours REAL NOT NULL DEFAULT 0,
+            ot_pay REAL NOT NULL DEFAULT 0,
+            gross_pay REAL NOT NULL,
+            epf_employee REAL NOT NULL DEFAULT 0,
+            epf_employer REAL NOT NULL DEFAULT 0,
+            etf_employer REAL NOT NULL DEFAULT 0,
+            other_deductions REAL NOT NULL DEFAULT 0,
+            net_pay REAL NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_payroll_lines_run ON payroll_lines(run_id, epf_number)",
+        [],
+    )?;
+    let _ = conn.execute("ALTER TABLE payroll_lines ADD COLUMN incentive_pay REAL NOT NULL DEFAULT 0", []);
+    let _ = conn.execute("ALTER TABLE payroll_lines ADD COLUMN pay_currency TEXT NOT NULL DEFAULT 'LKR'", []);
+    let _ = conn.execute("ALTER TABLE payroll_lines ADD COLUMN exchange_rate REAL NOT NULL DEFAULT 1", []);
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS payroll_line_deductions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            line_id INTEGER NOT NULL,
+            deduction_type TEXT NOT NULL,
+            amount REAL NOT NULL
+        )",
+        [],
+    )?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS employee_bank_accounts (
+            epf_number TEXT PRIMARY KEY,
+            bank_code TEXT NOT NULL,
+            branch_code TEXT NOT NULL,
+            account_number TEXT NOT NULL,
+            account_name TEXT NOT NULL,
+            updated_by TEXT,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS labour_budgets (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            department TEXT NOT NULL,
+            month TEXT NOT NULL,
+            amount REAL NOT NULL,
+            updated_by TEXT,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE (department, month)
+        )",
+        [],
+    )?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS piece_rates (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            product_code TEXT NOT NULL UNIQUE,
+            description TEXT,
+            rate REAL NOT NULL,
+            is_active INTEGER NOT NULL DEFAULT 1,
+            updated_by TEXT,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
+    // Rupees per unit of a foreign currency, entered each month
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS exchange_rates (
+            month TEXT NOT NULL,
+            currency TEXT NOT NULL,
+            rate REAL NOT NULL,
+            updated_by TEXT,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (month, currency)
+        )",
+        [],
+    )?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS letter_templates (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            category TEXT,
+            body TEXT NOT NULL,
+            is_active INTEGER NOT NULL DEFAULT 1,
+            updated_by TEXT,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    let _ = conn.execute("ALTER TABLE letter_templates ADD COLUMN language TEXT", []);
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS letter_issues (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            template_id INTEGER NOT NULL,
+            epf_number TEXT NOT NULL,
+            reference_number TEXT,
+            format TEXT NOT NULL,
+            issued_by INTEGER,
+            issued_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
+    // Sinhala and Tamil wording for document headings; English is built in
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS translations (
+            key TEXT NOT NULL,
+            language TEXT NOT NULL,
+            text TEXT NOT NULL,
+            updated_by TEXT,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (key, language)
+        )",
+        [],
+    )?;
+    
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS employee_preferences (
+            epf_number TEXT PRIMARY KEY,
+            language TEXT NOT NULL DEFAULT 'en'
+        )",
+        [],
+    )?;
+    
+    // Employee filter presets, private to the user who saved them
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS saved_filters (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            filters TEXT NOT NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE (user_id, name)
+        )",
+        [],
+    )?;
+    
+    // Blood group and allergies, kept apart from the employee record as
+    // sensitive health data
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS employee_medical (
+            epf_number TEXT PRIMARY KEY,
+            blood_group TEXT,
+            allergies TEXT,
+            updated_by TEXT,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    
+    // Medical fitness examinations; the latest one per employee is current
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS medical_fitness_certificates (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            epf_number TEXT NOT NULL,
+            examined_on TEXT NOT NULL,
+            valid_until TEXT NOT NULL,
+            result TEXT NOT NULL,
+            restrictions TEXT,
+            examined_by TEXT,
+            document_path TEXT,
+            recorded_by TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    let _ = conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_medical_fitness_certificates_epf ON medical_fitness_certificates(epf_number)",
+        [],
+    );
+    
+    // Driving and machine operator licences. warned_on and
+    // expired_notified_on record when the expiry alerts were sent.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS employee_licenses (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            epf_number TEXT NOT NULL,
+            license_type TEXT NOT NULL,
+            license_number TEXT NOT NULL,
+            issued_on TEXT,
+            expires_on TEXT NOT NULL,
+            document_path TEXT,
+            warned_on TEXT,
+            expired_notified_on TEXT,
+            recorded_by TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(license_type, license_number)
+        )",
+        [],
+    )?;
+    let _ = conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_employee_licenses_epf ON employee_licenses(epf_number)",
+        [],
+    );
+    
+    // Approval requests shared by the workflows in approvals::APPROVAL_CHAINS.
+    // entity_id is the item's ID in the workflow's own table; each request
+    // has one step per approver in its chain.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS approval_requests (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            request_type TEXT NOT NULL,
+            entity_id TEXT NOT NULL,
+            epf_number TEXT,
+            summary TEXT NOT NULL,
+            requested_by INTEGER,
+            status TEXT NOT NULL DEFAULT 'pending',
+            current_step INTEGER NOT NULL DEFAULT 1,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            decided_at TEXT,
+            UNIQUE(request_type, entity_id)
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS approval_steps (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            approval_id INTEGER NOT NULL,
+            step_number INTEGER NOT NULL,
+            approver_permission TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            decided_by INTEGER,
+            decided_at TEXT,
+            comment TEXT,
+            on_behalf_of INTEGER,
+            UNIQUE(approval_id, step_number)
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS approval_comments (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            approval_id INTEGER NOT NULL,
+            user_id INTEGER,
+            username TEXT NOT NULL,
+            comment TEXT NOT NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    let _ = conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_approval_requests_status ON approval_requests(status)",
+        [],
+    );
+    let _ = conn.execute("ALTER TABLE approval_steps ADD COLUMN on_behalf_of INTEGER", []);
+    // Festival advances were first approved under can_manage_users
+    conn.execute(
+        "UPDATE approval_steps SET approver_permission = 'can_run_payroll'
+         WHERE approver_permission = 'can_manage_users' AND status = 'pending'
+           AND approval_id IN (SELECT id FROM approval_requests WHERE request_type = 'festival_advance')",
+        [],
+    )?;
+    
+    // "User A's approvals go to user B from starts_on to ends_on", so work
+    // does not stall while an approver is away
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS approval_delegations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            delegator_id INTEGER NOT NULL,
+            delegate_id INTEGER NOT NULL,
+            starts_on TEXT NOT NULL,
+            ends_on TEXT NOT NULL,
+            reason TEXT,
+            created_by TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            revoked_at TEXT
+        )",
+        [],
+    )?;
+    approvals::backfill_pending_approvals(&conn)?;
+    
+    // Follow-up work between HR users, optionally linked to a record such as
+    // an employee (entity_type EMPLOYEE, entity_id the EPF number)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS tasks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            description TEXT,
+            assigned_to INTEGER,
+            created_by INTEGER,
+            due_date TEXT,
+            entity_type TEXT,
+            entity_id TEXT,
+            status TEXT NOT NULL DEFAULT 'open',
+            completed_at TEXT,
+            completed_by INTEGER,
+            completion_note TEXT,
+            overdue_notified_on TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    let _ = conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_tasks_assigned_to ON tasks(assigned_to, status)",
+        [],
+    );
+    
+    // Sri Lankan administrative divisions for employee addresses. Districts
+    // and DS divisions are seeded; GN divisions are added per installation.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS districts (
+            name TEXT PRIMARY KEY,
+            province TEXT NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS ds_divisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            district TEXT NOT NULL,
+            name TEXT NOT NULL,
+            UNIQUE (district, name)
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS gn_divisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            ds_division_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            code TEXT,
+            UNIQUE (ds_division_id, name)
+        )",
+        [],
+    )?;
+    address_commands::seed_administrative_divisions(&conn)?;
+    
+    // Each entry is for one employee or one production line (an allocation)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS production_entries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            month TEXT NOT NULL,
+            epf_number TEXT,
+            line TEXT,
+            product_code TEXT NOT NULL,
+            quantity REAL NOT NULL,
+            entered_by INTEGER,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            CHECK (epf_number IS NOT NULL OR line IS NOT NULL)
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_production_entries_month ON production_entries(month)",
+        [],
+    )?;
+    
+    // Version 2: employee dates were free text before validation; convert the
+    // readable ones to YYYY-MM-DD and reject anything else from now on
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version < 2 {
+        let changed = validation::normalize_employee_dates(&conn)?;
+        tracing::info!("Normalized {} employee date(s) to YYYY-MM-DD", changed);
+    }
+    // Unchanged values pass the update check so rows still holding an
+    // unreadable legacy date can be edited until someone fixes it
+    for column in validation::EMPLOYEE_DATE_COLUMNS {
+        conn.execute(
+            &format!(
+                "CREATE TRIGGER IF NOT EXISTS employees_{0}_insert BEFORE INSERT ON employees
+                 WHEN NEW.{0} <> '' AND NEW.{0} IS NOT date(NEW.{0})
+                 BEGIN SELECT RAISE(ABORT, '{0} must be a date in YYYY-MM-DD format'); END",
+                column
+            ),
+            [],
+        )?;
+        conn.execute(
+            &format!(
+                "CREATE TRIGGER IF NOT EXISTS employees_{0}_update BEFORE UPDATE OF {0} ON employees
+                 WHEN NEW.{0} IS NOT OLD.{0} AND NEW.{0} <> '' AND NEW.{0} IS NOT date(NEW.{0})
+                 BEGIN SELECT RAISE(ABORT, '{0} must be a date in YYYY-MM-DD format'); END",
+                column
+            ),
+            [],
+        )?;
+    }
+    
+    // Migrate job_role to designation if job_role exists
+    let _ = conn.execute("UPDATE employees SET designation = job_role WHERE designation IS NULL AND job_role IS NOT NULL", []);
+    
+    if let Some(level) = settings_commands::get_setting(&conn, "log_level") {
+        logging::set_level(&level);
+    }
+    
+    // Finish or undo file operations interrupted by a crash or power cut
+    journal::recover(&conn, app_dir);
+    
+    conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+    
+    Ok(conn)
 }
 
 // Simple password hashing (in production, use bcrypt or argon2)
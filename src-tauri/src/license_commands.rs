@@ -0,0 +1,341 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{EmployeeLicense, UserSession};
+use crate::notification_commands::notify_hr_managers;
+use crate::settings_commands::get_setting_i64;
+use crate::validation::parse_iso_date;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use tauri::State;
+
+// Licences and operator certificates that are tracked, as (type, label)
+pub const LICENSE_TYPES: [(&str, &str); 6] = [
+    ("driving", "Driving licence"),
+    ("heavy_vehicle", "Heavy vehicle licence"),
+    ("forklift", "Forklift operator licence"),
+    ("crane", "Crane operator licence"),
+    ("boiler", "Boiler operator certificate"),
+    ("other", "Other licence"),
+];
+
+// Days before expiry that HR is warned, unless overridden by the
+// license_expiry_warning_days setting
+const DEFAULT_WARNING_DAYS: i64 = 30;
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+fn require_edit(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user = logged_in_user(current_user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit employees."));
+    }
+    Ok(user)
+}
+
+fn license_label(license_type: &str) -> &str {
+    LICENSE_TYPES.iter().find(|(t, _)| *t == license_type).map(|(_, label)| *label).unwrap_or(license_type)
+}
+
+fn warning_days(conn: &Connection) -> i64 {
+    get_setting_i64(conn, "license_expiry_warning_days").unwrap_or(DEFAULT_WARNING_DAYS)
+}
+
+const LICENSE_COLUMNS: &str = "l.id, l.epf_number, e.name_with_initials, e.department, l.license_type, l.license_number,
+                               l.issued_on, l.expires_on, l.document_path, l.recorded_by, l.created_at,
+                               CAST(julianday(l.expires_on) - julianday('now', 'localtime', 'start of day') AS INTEGER)";
+
+fn license_from_row(row: &rusqlite::Row) -> rusqlite::Result<EmployeeLicense> {
+    Ok(EmployeeLicense {
+        id: row.get(0)?,
+        epf_number: row.get(1)?,
+        name_with_initials: row.get(2)?,
+        department: row.get(3)?,
+        license_type: row.get(4)?,
+        license_number: row.get(5)?,
+        issued_on: row.get(6)?,
+        expires_on: row.get(7)?,
+        document_path: row.get(8)?,
+        recorded_by: row.get(9)?,
+        created_at: row.get(10)?,
+        days_remaining: row.get(11)?,
+    })
+}
+
+fn query_license(conn: &Connection, license_id: i64) -> Result<EmployeeLicense, HrmError> {
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM employee_licenses l JOIN employees e ON e.epf_number = l.epf_number WHERE l.id = ?1",
+            LICENSE_COLUMNS
+        ),
+        [license_id],
+        license_from_row,
+    )
+    .map_err(|_| HrmError::not_found("Licence not found"))
+}
+
+fn validate_license(license: &mut EmployeeLicense) -> Result<(), HrmError> {
+    if !LICENSE_TYPES.iter().any(|(t, _)| *t == license.license_type) {
+        return Err(HrmError::field(
+            "license_type",
+            "Licence type must be driving, heavy_vehicle, forklift, crane, boiler or other",
+        ));
+    }
+    license.license_number = license.license_number.trim().to_uppercase();
+    if license.license_number.is_empty() {
+        return Err(HrmError::field("license_number", "Licence number is required"));
+    }
+    parse_iso_date(&license.expires_on).ok_or_else(|| HrmError::field("expires_on", "Expiry date must be a valid date (YYYY-MM-DD)"))?;
+    license.issued_on = license.issued_on.as_deref().map(str::trim).filter(|d| !d.is_empty()).map(str::to_string);
+    if let Some(issued_on) = &license.issued_on {
+        parse_iso_date(issued_on).ok_or_else(|| HrmError::field("issued_on", "Issue date must be a valid date (YYYY-MM-DD)"))?;
+        if *issued_on >= license.expires_on {
+            return Err(HrmError::field("expires_on", "Expiry date must be after the issue date"));
+        }
+    }
+    license.document_path = license.document_path.as_deref().map(str::trim).filter(|p| !p.is_empty()).map(str::to_string);
+    Ok(())
+}
+
+fn duplicate_number_error(e: rusqlite::Error) -> HrmError {
+    if e.to_string().contains("UNIQUE constraint") {
+        HrmError::field("license_number", "A licence of this type with this number is already recorded")
+    } else {
+        HrmError::from(e)
+    }
+}
+
+#[tauri::command]
+pub fn get_employee_licenses(
+    epf_number: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<EmployeeLicense>, HrmError> {
+    logged_in_user(&current_user)?;
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM employee_licenses l JOIN employees e ON e.epf_number = l.epf_number
+         WHERE l.epf_number = ?1 ORDER BY l.expires_on DESC, l.id DESC",
+        LICENSE_COLUMNS
+    ))?;
+    let licenses = stmt
+        .query_map([&epf_number], license_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(licenses)
+}
+
+// Records a licence. A renewal is recorded as a new licence so the old
+// expiry stays on file.
+#[tauri::command]
+pub fn add_license(
+    license: EmployeeLicense,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = require_edit(&current_user)?;
+    let mut license = license;
+    validate_license(&mut license)?;
+    
+    let conn = db.0.lock()?;
+    let exists: bool = conn
+        .query_row("SELECT COUNT(*) > 0 FROM employees WHERE epf_number = ?1", [&license.epf_number], |row| row.get(0))?;
+    if !exists {
+        return Err(HrmError::not_found("Employee not found"));
+    }
+    conn.execute(
+        "INSERT INTO employee_licenses (epf_number, license_type, license_number, issued_on, expires_on, document_path, recorded_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![
+            license.epf_number,
+            license.license_type,
+            license.license_number,
+            license.issued_on,
+            license.expires_on,
+            license.document_path,
+            user.username,
+        ],
+    )
+    .map_err(duplicate_number_error)?;
+    let id = conn.last_insert_rowid();
+    
+    let new_value = serde_json::to_string(&license).ok();
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "LICENSE",
+        Some(&license.epf_number),
+        None,
+        new_value.as_deref(),
+        Some(&format!(
+            "Recorded {} {} for employee {}, expiring {}",
+            license_label(&license.license_type),
+            license.license_number,
+            license.epf_number,
+            license.expires_on
+        )),
+    );
+    
+    Ok(id)
+}
+
+// Corrects a licence. Changing the expiry date lets the expiry alerts fire
+// again for the new date.
+#[tauri::command]
+pub fn update_license(
+    license: EmployeeLicense,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = require_edit(&current_user)?;
+    let mut license = license;
+    validate_license(&mut license)?;
+    
+    let conn = db.0.lock()?;
+    let old = query_license(&conn, license.id)?;
+    conn.execute(
+        "UPDATE employee_licenses SET license_type = ?1, license_number = ?2, issued_on = ?3, document_path = ?4,
+             warned_on = CASE WHEN expires_on = ?5 THEN warned_on END,
+             expired_notified_on = CASE WHEN expires_on = ?5 THEN expired_notified_on END,
+             expires_on = ?5
+         WHERE id = ?6",
+        rusqlite::params![
+            license.license_type,
+            license.license_number,
+            license.issued_on,
+            license.document_path,
+            license.expires_on,
+            license.id,
+        ],
+    )
+    .map_err(duplicate_number_error)?;
+    
+    let old_value = serde_json::to_string(&old).ok();
+    let new_value = serde_json::to_string(&license).ok();
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "LICENSE",
+        Some(&old.epf_number),
+        old_value.as_deref(),
+        new_value.as_deref(),
+        Some(&format!("Updated {} {} for employee {}", license_label(&license.license_type), license.license_number, old.epf_number)),
+    );
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_license(
+    license_id: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = require_edit(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let old = query_license(&conn, license_id)?;
+    conn.execute("DELETE FROM employee_licenses WHERE id = ?1", [license_id])?;
+    
+    let old_value = serde_json::to_string(&old).ok();
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "DELETE",
+        "LICENSE",
+        Some(&old.epf_number),
+        old_value.as_deref(),
+        None,
+        Some(&format!("Deleted {} {} for employee {}", license_label(&old.license_type), old.license_number, old.epf_number)),
+    );
+    
+    Ok(())
+}
+
+// Active employees' licences that have expired or expire within `days` (the
+// license_expiry_warning_days setting by default), soonest first. A licence
+// that has since been renewed with a later one of the same type is left out.
+fn expiring_licenses(conn: &Connection, days: i64) -> rusqlite::Result<Vec<EmployeeLicense>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM employee_licenses l JOIN employees e ON e.epf_number = l.epf_number
+         WHERE e.working_status IN (SELECT code FROM working_statuses WHERE counts_as_active = 1)
+           AND l.expires_on <= date('now', 'localtime', '+' || CAST(?1 AS INTEGER) || ' days')
+           AND NOT EXISTS (SELECT 1 FROM employee_licenses later
+                           WHERE later.epf_number = l.epf_number AND later.license_type = l.license_type
+                             AND later.expires_on > l.expires_on)
+         ORDER BY l.expires_on, l.epf_number",
+        LICENSE_COLUMNS
+    ))?;
+    let licenses = stmt
+        .query_map([days], license_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(licenses)
+}
+
+#[tauri::command]
+pub fn get_expiring_licenses(
+    days: Option<i64>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<EmployeeLicense>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_reports {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view reports."));
+    }
+    
+    let conn = db.0.lock()?;
+    let days = days.unwrap_or_else(|| warning_days(&conn)).max(0);
+    Ok(expiring_licenses(&conn, days)?
+        .into_iter()
+        .filter(|l| user.can_access_department(l.department.as_deref()))
+        .collect())
+}
+
+// Notifies HR managers once when a licence comes within the warning period
+// and again when it expires. Called by the scheduler.
+pub fn check_license_expiry(conn: &Connection) -> Result<usize, HrmError> {
+    let mut alerted = 0;
+    for license in expiring_licenses(conn, warning_days(conn))? {
+        let expired = license.days_remaining.unwrap_or(0) < 0;
+        let column = if expired { "expired_notified_on" } else { "warned_on" };
+        let marked = conn.execute(
+            &format!(
+                "UPDATE employee_licenses SET {0} = date('now', 'localtime') WHERE id = ?1 AND {0} IS NULL",
+                column
+            ),
+            [license.id],
+        )?;
+        if marked == 0 {
+            continue;
+        }
+        let label = license_label(&license.license_type);
+        let (title, severity, message) = if expired {
+            (
+                "Operator licence expired",
+                "high",
+                format!(
+                    "{} {} of {} ({}) expired on {}",
+                    label, license.license_number, license.name_with_initials, license.epf_number, license.expires_on
+                ),
+            )
+        } else {
+            (
+                "Operator licence expiring",
+                "warning",
+                format!(
+                    "{} {} of {} ({}) expires on {}",
+                    label, license.license_number, license.name_with_initials, license.epf_number, license.expires_on
+                ),
+            )
+        };
+        notify_hr_managers(conn, title, &message, severity, Some("EMPLOYEE"), Some(&license.epf_number));
+        alerted += 1;
+    }
+    Ok(alerted)
+}
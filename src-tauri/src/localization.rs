@@ -0,0 +1,214 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{Translation, UserSession};
+use crate::settings_commands::get_setting;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::{Connection, OptionalExtension};
+use tauri::State;
+
+// English, Sinhala and Tamil
+pub const LANGUAGES: [&str; 3] = ["en", "si", "ta"];
+
+// Font stack for generated documents. Windows ships Iskoola Pota (Sinhala)
+// and Latha / Nirmala UI (Tamil); the Noto fonts cover other machines.
+pub const DOCUMENT_FONTS: &str =
+    "'Segoe UI', 'Iskoola Pota', 'Noto Sans Sinhala', 'Nirmala UI', 'Latha', 'Noto Sans Tamil', Arial, sans-serif";
+
+// Word run fonts for a document in `language`. Sinhala and Tamil are complex
+// scripts, so Word takes their font from w:cs; Latin text uses Segoe UI.
+pub(crate) fn docx_fonts(language: &str) -> String {
+    let script_font = match language {
+        "si" => "Iskoola Pota",
+        "ta" => "Latha",
+        _ => "Segoe UI",
+    };
+    format!("<w:rFonts w:ascii=\"Segoe UI\" w:hAnsi=\"Segoe UI\" w:cs=\"{}\"/>", script_font)
+}
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+fn require_language(language: &str) -> Result<(), HrmError> {
+    if LANGUAGES.contains(&language) {
+        Ok(())
+    } else {
+        Err(HrmError::field("language", "Language must be en, si or ta"))
+    }
+}
+
+// Language for documents with no other preference: the document_language
+// setting, or English
+pub(crate) fn default_language(conn: &Connection) -> String {
+    get_setting(conn, "document_language")
+        .filter(|l| LANGUAGES.contains(&l.as_str()))
+        .unwrap_or_else(|| "en".to_string())
+}
+
+// The employee's preferred language for letters and records, or the default
+pub(crate) fn employee_language(conn: &Connection, epf_number: &str) -> String {
+    conn.query_row(
+        "SELECT language FROM employee_preferences WHERE epf_number = ?1",
+        [epf_number],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .filter(|l| LANGUAGES.contains(&l.as_str()))
+    .unwrap_or_else(|| default_language(conn))
+}
+
+// Text for `key` in `language`, falling back to `default` (the English
+// wording) when no translation has been entered
+pub(crate) fn translate(conn: &Connection, language: &str, key: &str, default: &str) -> String {
+    if language == "en" {
+        return default.to_string();
+    }
+    conn.query_row(
+        "SELECT text FROM translations WHERE key = ?1 AND language = ?2",
+        [key, language],
+        |row| row.get(0),
+    )
+    .unwrap_or_else(|_| default.to_string())
+}
+
+#[tauri::command]
+pub fn get_translations(
+    language: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<Translation>, HrmError> {
+    logged_in_user(&current_user)?;
+    require_language(&language)?;
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(
+        "SELECT key, language, text, updated_by, updated_at FROM translations WHERE language = ?1 ORDER BY key",
+    )?;
+    let translations = stmt
+        .query_map([&language], |row| {
+            Ok(Translation {
+                key: row.get(0)?,
+                language: row.get(1)?,
+                text: row.get(2)?,
+                updated_by: row.get(3)?,
+                updated_at: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(translations)
+}
+
+// Sets the wording for a key in Sinhala or Tamil. An empty text removes the
+// translation so the English wording is used again.
+#[tauri::command]
+pub fn set_translation(
+    key: String,
+    language: String,
+    text: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_manage_settings {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit translations."));
+    }
+    require_language(&language)?;
+    if language == "en" {
+        return Err(HrmError::field("language", "English wording is built in; translate into si or ta"));
+    }
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(HrmError::field("key", "Key is required"));
+    }
+    
+    let conn = db.0.lock()?;
+    let old_text: Option<String> = conn
+        .query_row(
+            "SELECT text FROM translations WHERE key = ?1 AND language = ?2",
+            [key, language.as_str()],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if text.trim().is_empty() {
+        conn.execute("DELETE FROM translations WHERE key = ?1 AND language = ?2", [key, language.as_str()])?;
+    } else {
+        conn.execute(
+            "INSERT INTO translations (key, language, text, updated_by, updated_at)
+             VALUES (?1, ?2, ?3, ?4, CURRENT_TIMESTAMP)
+             ON CONFLICT(key, language) DO UPDATE SET text = excluded.text,
+                 updated_by = excluded.updated_by, updated_at = CURRENT_TIMESTAMP",
+            rusqlite::params![key, language, text.trim(), user.username],
+        )?;
+    }
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "SETTING",
+        Some(&format!("translation:{}:{}", language, key)),
+        old_text.as_deref(),
+        Some(text.trim()).filter(|t| !t.is_empty()),
+        Some(&format!("Changed {} translation for {}", language, key)),
+    );
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_employee_language(
+    epf_number: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<String, HrmError> {
+    logged_in_user(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    Ok(employee_language(&conn, &epf_number))
+}
+
+// Records the language an employee prefers their letters and records in
+#[tauri::command]
+pub fn set_employee_language(
+    epf_number: String,
+    language: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit employees."));
+    }
+    require_language(&language)?;
+    
+    let conn = db.0.lock()?;
+    let employee_exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM employees WHERE epf_number = ?1)",
+        [&epf_number],
+        |row| row.get(0),
+    )?;
+    if !employee_exists {
+        return Err(HrmError::not_found(format!("Employee {} not found", epf_number)));
+    }
+    conn.execute(
+        "INSERT INTO employee_preferences (epf_number, language) VALUES (?1, ?2)
+         ON CONFLICT(epf_number) DO UPDATE SET language = excluded.language",
+        [&epf_number, &language],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "EMPLOYEE",
+        Some(&epf_number),
+        None,
+        Some(&language),
+        Some(&format!("Set document language for {} to {}", epf_number, language)),
+    );
+    
+    Ok(())
+}
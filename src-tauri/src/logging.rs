@@ -0,0 +1,145 @@
+use crate::error::HrmError;
+use crate::models::UserSession;
+use crate::{AppDataDir, CurrentUser};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::State;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, reload, Registry};
+
+// Log files live in <app data>/logs as hrm.YYYY-MM-DD.log, one per day
+const LOG_DIR: &str = "logs";
+const LOG_PREFIX: &str = "hrm";
+const MAX_LOG_FILES: usize = 14;
+pub const LOG_LEVELS: [&str; 5] = ["error", "warn", "info", "debug", "trace"];
+const DEFAULT_RECENT_LINES: usize = 500;
+
+static LEVEL_HANDLE: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+
+fn require_settings_permission(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_manage_settings => Ok(session.clone()),
+        Some(_) => Err(HrmError::permission_denied("Permission denied. You cannot view diagnostics.")),
+        None => Err(HrmError::not_logged_in()),
+    }
+}
+
+pub fn log_dir(app_dir: &Path) -> PathBuf {
+    app_dir.join(LOG_DIR)
+}
+
+fn parse_level(level: &str) -> Option<LevelFilter> {
+    match level.trim().to_ascii_lowercase().as_str() {
+        "error" => Some(LevelFilter::ERROR),
+        "warn" => Some(LevelFilter::WARN),
+        "info" => Some(LevelFilter::INFO),
+        "debug" => Some(LevelFilter::DEBUG),
+        "trace" => Some(LevelFilter::TRACE),
+        _ => None,
+    }
+}
+
+// Starts writing logs to rotating daily files (and stderr for development).
+// Called once at startup; the level can be changed later with set_level.
+pub fn init(app_dir: &Path) {
+    let (filter, handle) = reload::Layer::new(LevelFilter::INFO);
+    let stderr_layer = fmt::layer().with_writer(std::io::stderr);
+    let file_layer = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_PREFIX)
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(log_dir(app_dir))
+        .map(|appender| fmt::layer().with_ansi(false).with_writer(appender));
+    
+    let registry = tracing_subscriber::registry().with(filter).with(stderr_layer);
+    let result = match file_layer {
+        Ok(file_layer) => registry.with(file_layer).try_init(),
+        Err(e) => {
+            eprintln!("Failed to open log directory: {}", e);
+            registry.try_init()
+        }
+    };
+    if result.is_ok() {
+        let _ = LEVEL_HANDLE.set(handle);
+    }
+}
+
+// Applies the log_level setting; unknown values are ignored
+pub fn set_level(level: &str) {
+    if let (Some(filter), Some(handle)) = (parse_level(level), LEVEL_HANDLE.get()) {
+        if let Err(e) = handle.modify(|current| *current = filter) {
+            tracing::warn!("Could not change log level: {}", e);
+        }
+    }
+}
+
+// Log files, newest first
+pub(crate) fn log_files(app_dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(log_dir(app_dir))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.is_file()
+                        && p.file_name()
+                            .and_then(|n| n.to_str())
+                            .map(|n| n.starts_with(LOG_PREFIX) && n.ends_with(".log"))
+                            .unwrap_or(false)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files.reverse();
+    files
+}
+
+// Level word of a formatted log line ("2026-01-01T00:00:00Z  INFO target: ...")
+fn line_level(line: &str) -> Option<LevelFilter> {
+    line.split_whitespace().nth(1).and_then(parse_level)
+}
+
+// The most recent log lines, oldest first, for the diagnostics screen.
+// min_level keeps only lines at that level or more severe.
+#[tauri::command]
+pub fn get_recent_logs(
+    max_lines: Option<usize>,
+    min_level: Option<String>,
+    app_data_dir: State<'_, AppDataDir>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<String>, HrmError> {
+    require_settings_permission(&current_user)?;
+    let max_lines = max_lines.unwrap_or(DEFAULT_RECENT_LINES).max(1);
+    let min_level = match min_level.as_deref().filter(|l| !l.trim().is_empty()) {
+        Some(level) => Some(parse_level(level).ok_or("Log level must be error, warn, info, debug or trace")?),
+        None => None,
+    };
+    
+    let mut lines: Vec<String> = Vec::new();
+    for file in log_files(&app_data_dir.0) {
+        let content = fs::read_to_string(&file)?;
+        let mut matching: Vec<String> = content
+            .lines()
+            .filter(|line| match (min_level, line_level(line)) {
+                (Some(min), Some(level)) => level <= min,
+                (Some(_), None) => false,
+                (None, _) => true,
+            })
+            .map(str::to_string)
+            .collect();
+        let take = (max_lines - lines.len()).min(matching.len());
+        let mut newest = matching.split_off(matching.len() - take);
+        newest.append(&mut lines);
+        lines = newest;
+        if lines.len() >= max_lines {
+            break;
+        }
+    }
+    Ok(lines)
+}
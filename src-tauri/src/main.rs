@@ -1,7 +1,7 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use hrm_system_lib::{auth_commands, commands, init_db, AppDataDir, CurrentUser, DbConnection};
+use hrm_system_lib::{access_review, address_commands, anonymization, approvals, asset_commands, attendance_commands, attendance_device, audit_archive, auth_commands, backup, bank_commands, budget_commands, cloud_backup, commands, compliance_commands, consent_commands, currency_commands, custom_fields, data_quality, dashboard_commands, deduction_commands, diagnostics, disciplinary_commands, employee_history, employee_pack, employee_relations, employment_periods, epf_numbering, exit_interview_commands, export_commands, festival_advance_commands, grievance_commands, headcount_commands, image_protocol, import_commands, incentive_commands, incident_commands, init_db, instance_lock, insurance_commands, interview_commands, leave_commands, letter_commands, license_commands, localization, logging, master_list_commands, meal_commands, medical_commands, name_search, note_commands, notification_commands, org_chart_commands, payroll_commands, profile_completeness, qualification_commands, read_only, recruitment_commands, retention, salary_commands, saved_filter_commands, scheduler, settings_commands, skill_commands, statutory_commands, succession_commands, tag_commands, task_commands, training_commands, visitor_commands, working_status_commands, AppDataDir, CurrentUser, DbConnection};
 use std::sync::Mutex;
 use tauri::Manager;
 
@@ -13,55 +13,360 @@ fn main() {
         std::env::set_var("WEBKIT_DISABLE_COMPOSITING_MODE", "1");
     }
 
+    let read_only = read_only::requested_on_command_line();
+
     tauri::Builder::default()
+        // Must be registered first: a second launch hands over to the running
+        // app, which brings its window to the front
+        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.unminimize();
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        }))
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
+        .manage(read_only::ReadOnlyMode(read_only))
+        .register_uri_scheme_protocol(image_protocol::SCHEME, |ctx, request| {
+            image_protocol::handle_request(ctx.app_handle(), &request)
+        })
         .setup(|app| {
+            instance_lock::acquire(app.handle())?;
             let (conn, app_dir) = init_db(app.handle()).expect("Failed to initialize database");
             app.manage(DbConnection(Mutex::new(conn)));
             app.manage(AppDataDir(app_dir));
             app.manage(CurrentUser(Mutex::new(None)));
+            // Maintenance jobs write to the database, so a view-only terminal skips them
+            if !read_only {
+                scheduler::start(app.handle().clone());
+                attendance_device::start(app.handle().clone());
+            }
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(auth_commands::session_guard(read_only::guard(read_only, tauri::generate_handler![
             // Auth commands
             auth_commands::login,
             auth_commands::logout,
             auth_commands::get_current_user,
+            auth_commands::validate_session,
+            auth_commands::get_active_sessions,
+            auth_commands::terminate_user_sessions,
             auth_commands::create_user,
             auth_commands::get_all_users,
             auth_commands::update_user,
             auth_commands::delete_user,
+            auth_commands::get_deleted_users,
+            auth_commands::restore_user,
+            auth_commands::rename_user,
             auth_commands::reset_user_password,
             auth_commands::change_own_password,
             // Employee commands
             commands::init_database,
             commands::get_employees,
             commands::get_employee_by_epf,
+            commands::find_previous_employment,
+            commands::get_invalid_employee_dates,
+            commands::search_employees_by_phone,
+            name_search::search_employees,
+            profile_completeness::get_profile_completeness,
+            profile_completeness::get_least_complete_profiles,
+            data_quality::run_data_quality_checks,
             commands::create_employee,
+            epf_numbering::get_next_epf_number,
+            epf_numbering::change_epf_number,
             commands::update_employee,
             commands::delete_employee,
+            employee_relations::archive_employee,
+            commands::bulk_delete_employees,
             commands::get_distinct_departments,
             commands::get_distinct_transport_routes,
             commands::get_distinct_police_areas,
+            address_commands::get_districts,
+            address_commands::get_ds_divisions,
+            address_commands::get_gn_divisions,
+            address_commands::add_gn_division,
+            address_commands::get_residence_distribution,
             commands::get_distinct_designations,
             commands::get_distinct_allocations,
             commands::get_dashboard_stats,
             commands::save_employee_image,
             commands::get_employee_image,
+            commands::get_employee_thumbnails,
+            commands::list_employee_photos,
+            commands::set_primary_photo,
             commands::save_binary_file,
+            commands::record_data_export,
+            export_commands::export_vcards,
+            export_commands::export_employees_csv,
+            import_commands::generate_import_template,
+            import_commands::migrate_legacy_register,
             commands::export_database,
             commands::import_database,
             commands::get_database_info,
+            commands::cleanup_orphaned_files,
             // Audit log commands
             commands::create_audit_log,
             commands::get_audit_logs,
             commands::get_audit_log_summary,
-        ])
+            audit_archive::get_archived_audit_years,
+            audit_archive::get_archived_audit_logs,
+            access_review::export_permission_matrix,
+            access_review::get_permission_changes,
+            // Settings commands
+            settings_commands::get_app_settings,
+            settings_commands::update_app_setting,
+            settings_commands::get_field_config,
+            settings_commands::update_field_config,
+            custom_fields::get_custom_field_definitions,
+            custom_fields::create_custom_field,
+            custom_fields::update_custom_field,
+            custom_fields::delete_custom_field,
+            tag_commands::get_tags,
+            tag_commands::get_employee_tags,
+            tag_commands::create_tag,
+            tag_commands::delete_tag,
+            tag_commands::set_employee_tag,
+            note_commands::get_employee_notes,
+            note_commands::add_employee_note,
+            note_commands::update_employee_note,
+            note_commands::delete_employee_note,
+            qualification_commands::get_employee_qualifications,
+            qualification_commands::add_employee_qualification,
+            qualification_commands::update_employee_qualification,
+            qualification_commands::delete_employee_qualification,
+            qualification_commands::get_qualification_distribution,
+            skill_commands::get_skills,
+            skill_commands::create_skill,
+            skill_commands::get_employee_skills,
+            skill_commands::set_employee_skill,
+            skill_commands::get_skill_coverage,
+            training_commands::get_trainings,
+            training_commands::get_employee_trainings,
+            training_commands::log_training_session,
+            training_commands::delete_training,
+            training_commands::get_training_needs_report,
+            disciplinary_commands::get_disciplinary_actions,
+            disciplinary_commands::get_disciplinary_summary,
+            disciplinary_commands::add_disciplinary_action,
+            disciplinary_commands::update_disciplinary_action,
+            disciplinary_commands::delete_disciplinary_action,
+            employee_history::get_employee_history,
+            incident_commands::get_incidents,
+            incident_commands::create_incident,
+            incident_commands::update_incident,
+            incident_commands::delete_incident,
+            incident_commands::get_incident_summary,
+            grievance_commands::get_grievances,
+            grievance_commands::create_grievance,
+            grievance_commands::assign_grievance,
+            grievance_commands::update_grievance_status,
+            grievance_commands::get_pending_grievances_report,
+            asset_commands::get_assets,
+            asset_commands::create_asset,
+            asset_commands::set_asset_active,
+            asset_commands::get_employee_asset_issues,
+            asset_commands::issue_asset,
+            asset_commands::return_asset,
+            asset_commands::get_outstanding_items,
+            visitor_commands::get_visitors_on_site,
+            visitor_commands::check_in_visitor,
+            visitor_commands::check_out_visitor,
+            visitor_commands::get_daily_visitors_report,
+            meal_commands::record_meal_scan,
+            meal_commands::record_meals,
+            meal_commands::get_monthly_meal_deductions,
+            meal_commands::get_meal_forecast,
+            deduction_commands::get_deduction_types,
+            deduction_commands::create_deduction_type,
+            deduction_commands::get_employee_deductions,
+            deduction_commands::add_employee_deduction,
+            deduction_commands::stop_employee_deduction,
+            deduction_commands::get_monthly_deductions,
+            deduction_commands::get_deduction_remittance_summary,
+            festival_advance_commands::get_festival_advances,
+            festival_advance_commands::request_festival_advance,
+            festival_advance_commands::approve_festival_advance,
+            festival_advance_commands::reject_festival_advance,
+            insurance_commands::get_insurance_policies,
+            insurance_commands::create_insurance_policy,
+            insurance_commands::get_employee_insurance,
+            insurance_commands::enroll_employee_insurance,
+            insurance_commands::end_insurance_enrollment,
+            insurance_commands::get_insurance_claims,
+            insurance_commands::submit_insurance_claim,
+            insurance_commands::update_insurance_claim_status,
+            insurance_commands::get_insurance_claims_report,
+            exit_interview_commands::resign_employee,
+            exit_interview_commands::record_exit_interview,
+            exit_interview_commands::get_exit_interview,
+            exit_interview_commands::get_exit_interview_analytics,
+            employment_periods::get_service_summary,
+            employment_periods::rejoin_employee,
+            employment_periods::get_employee_transfers,
+            employment_periods::transfer_employee,
+            master_list_commands::get_master_list,
+            master_list_commands::save_master_value,
+            master_list_commands::delete_master_value,
+            working_status_commands::get_working_statuses,
+            working_status_commands::save_working_status,
+            working_status_commands::delete_working_status,
+            dashboard_commands::get_recent_joiners,
+            dashboard_commands::get_recent_resignations,
+            dashboard_commands::get_employees_by_department,
+            dashboard_commands::get_employees_by_cader,
+            dashboard_commands::get_employees_by_allocation,
+            dashboard_commands::get_employees_by_status,
+            dashboard_commands::get_monthly_movement,
+            headcount_commands::get_headcount_budgets,
+            headcount_commands::create_headcount_budget,
+            headcount_commands::delete_headcount_budget,
+            headcount_commands::get_headcount_variance_report,
+            headcount_commands::get_headcount_snapshot_months,
+            headcount_commands::get_headcount_snapshot,
+            recruitment_commands::get_vacancies,
+            recruitment_commands::create_vacancy,
+            recruitment_commands::set_vacancy_status,
+            recruitment_commands::get_candidates,
+            recruitment_commands::add_candidate,
+            recruitment_commands::move_candidate_stage,
+            recruitment_commands::convert_candidate_to_employee,
+            recruitment_commands::get_time_to_fill_report,
+            interview_commands::get_score_criteria,
+            interview_commands::get_vacancy_interviews,
+            interview_commands::get_my_interviews,
+            interview_commands::schedule_interview,
+            interview_commands::cancel_interview,
+            interview_commands::record_interview_evaluation,
+            interview_commands::get_candidate_comparison,
+            salary_commands::get_salary_revisions,
+            salary_commands::record_salary_revision,
+            salary_commands::get_increment_report,
+            org_chart_commands::set_reports_to,
+            org_chart_commands::get_org_tree,
+            org_chart_commands::get_pending_reassignments,
+            succession_commands::get_key_positions,
+            succession_commands::save_key_position,
+            succession_commands::remove_key_position,
+            succession_commands::get_successors,
+            succession_commands::nominate_successor,
+            succession_commands::remove_successor,
+            succession_commands::get_succession_gaps,
+            anonymization::get_anonymization_due,
+            anonymization::anonymize_employee,
+            retention::get_retention_rules,
+            retention::save_retention_rule,
+            retention::get_retention_dry_run,
+            retention::confirm_retention_rule,
+            consent_commands::get_employee_consents,
+            consent_commands::record_consent,
+            consent_commands::withdraw_consent,
+            consent_commands::get_missing_consents,
+            medical_commands::get_medical_record,
+            medical_commands::set_medical_details,
+            medical_commands::record_fitness_certificate,
+            medical_commands::get_expiring_fitness_certificates,
+            license_commands::get_employee_licenses,
+            license_commands::add_license,
+            license_commands::update_license,
+            license_commands::delete_license,
+            license_commands::get_expiring_licenses,
+            logging::get_recent_logs,
+            diagnostics::export_support_bundle,
+            diagnostics::run_health_check,
+            read_only::get_read_only_mode,
+            backup::list_backups,
+            backup::create_backup,
+            backup::restore_backup,
+            cloud_backup::get_cloud_backup_config,
+            cloud_backup::save_cloud_backup_config,
+            cloud_backup::upload_backup_now,
+            cloud_backup::decrypt_backup_file,
+            attendance_device::get_attendance_devices,
+            attendance_device::register_attendance_device,
+            attendance_device::set_attendance_device_active,
+            attendance_commands::get_employee_punches,
+            attendance_commands::request_punch_correction,
+            attendance_commands::get_attendance_corrections,
+            attendance_commands::approve_attendance_correction,
+            attendance_commands::reject_attendance_correction,
+            attendance_commands::request_overtime,
+            attendance_commands::get_overtime_requests,
+            attendance_commands::approve_overtime_request,
+            attendance_commands::reject_overtime_request,
+            attendance_commands::get_attendance_summary,
+            attendance_commands::export_attendance_summary,
+            leave_commands::get_leave_records,
+            leave_commands::record_leave,
+            leave_commands::cancel_leave,
+            leave_commands::request_leave,
+            leave_commands::get_leave_requests,
+            leave_commands::approve_leave_request,
+            leave_commands::reject_leave_request,
+            compliance_commands::get_night_work_report,
+            compliance_commands::export_night_work_report,
+            compliance_commands::get_working_hours_report,
+            payroll_commands::preview_payroll,
+            payroll_commands::lock_payroll_period,
+            payroll_commands::get_payroll_runs,
+            payroll_commands::get_payroll_run,
+            payroll_commands::reverse_payroll_run,
+            payroll_commands::get_payroll_variance_report,
+            payroll_commands::get_payroll_cost_report,
+            payroll_commands::simulate_payroll,
+            bank_commands::get_employee_bank_account,
+            bank_commands::set_employee_bank_account,
+            bank_commands::generate_bank_transfer_file,
+            statutory_commands::generate_epf_return,
+            statutory_commands::generate_etf_return,
+            budget_commands::get_labour_budgets,
+            budget_commands::set_labour_budget,
+            budget_commands::delete_labour_budget,
+            budget_commands::get_budget_vs_actual_report,
+            incentive_commands::get_piece_rates,
+            incentive_commands::set_piece_rate,
+            incentive_commands::get_production_entries,
+            incentive_commands::import_production_quantities,
+            incentive_commands::delete_production_entry,
+            currency_commands::get_exchange_rates,
+            currency_commands::set_exchange_rate,
+            currency_commands::delete_exchange_rate,
+            employee_pack::generate_employee_pack,
+            letter_commands::get_letter_templates,
+            letter_commands::get_letter_merge_fields,
+            letter_commands::save_letter_template,
+            letter_commands::delete_letter_template,
+            letter_commands::generate_letter,
+            letter_commands::generate_letters_bulk,
+            letter_commands::get_letter_issues,
+            localization::get_translations,
+            localization::set_translation,
+            localization::get_employee_language,
+            localization::set_employee_language,
+            saved_filter_commands::get_saved_filters,
+            saved_filter_commands::save_filter,
+            saved_filter_commands::delete_saved_filter,
+            saved_filter_commands::get_employees_by_saved_filter,
+            // Notification commands
+            notification_commands::get_my_notifications,
+            notification_commands::mark_notification_read,
+            notification_commands::mark_all_notifications_read,
+            approvals::get_my_pending_approvals,
+            approvals::get_approval,
+            approvals::add_approval_comment,
+            approvals::get_approval_delegations,
+            approvals::create_approval_delegation,
+            approvals::revoke_approval_delegation,
+            task_commands::get_my_tasks,
+            task_commands::get_entity_tasks,
+            task_commands::create_task,
+            task_commands::assign_task,
+            task_commands::complete_task,
+        ])))
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -0,0 +1,139 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::UserSession;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::{Connection, OptionalExtension};
+use tauri::State;
+
+// Lists kept by the installation: list name, table and the employee column
+// whose values come from it
+const MASTER_LISTS: [(&str, &str, &str); 2] = [
+    ("department", "departments", "department"),
+    ("designation", "designations", "designation"),
+];
+
+fn require_settings_permission(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_manage_settings => Ok(session.clone()),
+        Some(_) => Err(HrmError::permission_denied("Permission denied. You cannot change system settings.")),
+        None => Err(HrmError::not_logged_in()),
+    }
+}
+
+fn master_list(list: &str) -> Result<(&'static str, &'static str), HrmError> {
+    MASTER_LISTS
+        .iter()
+        .find(|(name, _, _)| *name == list)
+        .map(|(_, table, column)| (*table, *column))
+        .ok_or_else(|| HrmError::validation(format!("Unknown list: {}", list)))
+}
+
+pub(crate) fn load_master_list(conn: &Connection, list: &str) -> Result<Vec<String>, HrmError> {
+    let (table, _) = master_list(list)?;
+    let mut stmt = conn.prepare(&format!("SELECT name FROM {} ORDER BY sort_order, name", table))?;
+    let values = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(values)
+}
+
+// `list` is "department" or "designation"
+#[tauri::command]
+pub fn get_master_list(list: String, db: State<'_, DbConnection>) -> Result<Vec<String>, HrmError> {
+    let conn = db.0.lock()?;
+    load_master_list(&conn, &list)
+}
+
+// Adds a value, or moves an existing one to `sort_order`
+#[tauri::command]
+pub fn save_master_value(
+    list: String,
+    name: String,
+    sort_order: Option<i32>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = require_settings_permission(&current_user)?;
+    let (table, _) = master_list(&list)?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(HrmError::field("name", "Name is required"));
+    }
+    
+    let conn = db.0.lock()?;
+    let existing: Option<String> = conn
+        .query_row(
+            &format!("SELECT name FROM {} WHERE lower(name) = lower(?1)", table),
+            [name],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if let Some(existing) = &existing {
+        if existing != name {
+            return Err(HrmError::conflict(format!("'{}' is already in the list", existing)));
+        }
+    }
+    conn.execute(
+        &format!(
+            "INSERT INTO {} (name, sort_order) VALUES (?1, ?2)
+             ON CONFLICT(name) DO UPDATE SET sort_order = ?2",
+            table
+        ),
+        rusqlite::params![name, sort_order.unwrap_or(0)],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        if existing.is_some() { "UPDATE" } else { "CREATE" },
+        "MASTER_LIST",
+        Some(name),
+        None,
+        Some(name),
+        Some(&format!("Saved {} '{}'", list, name)),
+    );
+    
+    Ok(())
+}
+
+// Only values no employee uses can be removed
+#[tauri::command]
+pub fn delete_master_value(
+    list: String,
+    name: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = require_settings_permission(&current_user)?;
+    let (table, column) = master_list(&list)?;
+    
+    let conn = db.0.lock()?;
+    let in_use: i64 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM employees WHERE trim({}) = ?1", column),
+        [&name],
+        |row| row.get(0),
+    )?;
+    if in_use > 0 {
+        return Err(HrmError::validation(format!("{} employee(s) still use '{}'", in_use, name)));
+    }
+    let deleted = conn.execute(&format!("DELETE FROM {} WHERE name = ?1", table), [&name])?;
+    if deleted == 0 {
+        return Err(HrmError::not_found(format!("'{}' is not in the list", name)));
+    }
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "DELETE",
+        "MASTER_LIST",
+        Some(&name),
+        Some(&name),
+        None,
+        Some(&format!("Deleted {} '{}'", list, name)),
+    );
+    
+    Ok(())
+}
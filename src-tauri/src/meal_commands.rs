@@ -0,0 +1,221 @@
+use crate::commands::log_audit_action;
+use crate::error::{ErrorCode, HrmError};
+use crate::models::{MealDeduction, MealForecast, UserSession};
+use crate::payroll_commands::ensure_month_open;
+use crate::settings_commands::get_setting_i64;
+use crate::validation::parse_iso_date;
+use crate::working_status_commands::counts_as_active;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use tauri::State;
+
+const MEAL_TYPES: [&str; 3] = ["breakfast", "lunch", "dinner"];
+
+// Name of the payroll deduction line for canteen meals
+pub(crate) const MEAL_DEDUCTION_TYPE: &str = "Meals";
+
+// Days of canteen history the forecast averages over
+const FORECAST_WINDOW_DAYS: i64 = 28;
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+// The profile QR code holds JSON with an "epf" key; a bare EPF number is also
+// accepted for hand-held scanners that only read the printed number
+fn epf_from_scan(scan: &str) -> Option<String> {
+    let scan = scan.trim();
+    if scan.starts_with('{') {
+        let value: serde_json::Value = serde_json::from_str(scan).ok()?;
+        return value.get("epf").and_then(|v| v.as_str()).map(|s| s.trim().to_string());
+    }
+    if scan.is_empty() {
+        None
+    } else {
+        Some(scan.to_string())
+    }
+}
+
+fn insert_meal(conn: &Connection, epf_number: &str, meal_date: &str, meal_type: &str, source: &str) -> Result<(), HrmError> {
+    if !MEAL_TYPES.contains(&meal_type) {
+        return Err(HrmError::validation("Meal type must be breakfast, lunch or dinner"));
+    }
+    let working_status: String = conn
+        .query_row("SELECT working_status FROM employees WHERE epf_number = ?1", [epf_number], |row| row.get(0))
+        .map_err(|_| HrmError::not_found(format!("Employee {} not found", epf_number)))?;
+    if !counts_as_active(conn, &working_status)? {
+        return Err(HrmError::validation(format!("Employee {} is not active", epf_number)));
+    }
+    
+    let inserted = conn
+        .execute(
+            "INSERT OR IGNORE INTO meal_records (epf_number, meal_date, meal_type, source) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![epf_number, meal_date, meal_type, source],
+        )?;
+    if inserted == 0 {
+        return Err(HrmError::conflict(format!("Employee {} has already had {} on {}", epf_number, meal_type, meal_date)));
+    }
+    Ok(())
+}
+
+// Records a meal from a canteen QR scan for today. Returns the EPF number so
+// the canteen screen can show who was served.
+#[tauri::command]
+pub fn record_meal_scan(
+    scan: String,
+    meal_type: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<String, HrmError> {
+    logged_in_user(&current_user)?;
+    let epf_number = epf_from_scan(&scan).ok_or("Unrecognised QR code")?;
+    
+    let conn = db.0.lock()?;
+    let today: String = conn
+        .query_row("SELECT date('now', 'localtime')", [], |row| row.get(0))?;
+    ensure_month_open(&conn, &today)?;
+    insert_meal(&conn, &epf_number, &today, &meal_type, "qr")?;
+    Ok(epf_number)
+}
+
+// Bulk entry of a day's meals, e.g. copied from the attendance sheet.
+// Employees already recorded for that meal are skipped. Returns the number added.
+#[tauri::command]
+pub fn record_meals(
+    epf_numbers: Vec<String>,
+    meal_date: String,
+    meal_type: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<usize, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit employees."));
+    }
+    parse_iso_date(&meal_date).ok_or("Meal date must be a valid date (YYYY-MM-DD)")?;
+    
+    let mut conn = db.0.lock()?;
+    // Checked up front: insert_meal's conflicts are skipped below
+    ensure_month_open(&conn, &meal_date)?;
+    let tx = conn.transaction()?;
+    let mut added = 0;
+    for epf_number in &epf_numbers {
+        match insert_meal(&tx, epf_number.trim(), &meal_date, &meal_type, "manual") {
+            Ok(()) => added += 1,
+            Err(e) if e.code == ErrorCode::Conflict => {}
+            Err(e) => return Err(e),
+        }
+    }
+    
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "MEAL",
+        Some(&meal_date),
+        None,
+        None,
+        Some(&format!("Recorded {} {} meals for {}", added, meal_type, meal_date)),
+    );
+    tx.commit()?;
+    
+    Ok(added)
+}
+
+// Meals taken in a month, priced from the meal_price setting (LKR) less the
+// company subsidy in meal_subsidy_percent. Payroll deducts these as
+// MEAL_DEDUCTION_TYPE lines.
+pub fn monthly_meal_deductions(conn: &Connection, month: &str) -> Result<Vec<MealDeduction>, HrmError> {
+    let meal_price = get_setting_i64(conn, "meal_price").unwrap_or(0) as f64;
+    let subsidy_percent = get_setting_i64(conn, "meal_subsidy_percent").unwrap_or(0).clamp(0, 100) as f64;
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT m.epf_number, e.name_with_initials, e.department, COUNT(*)
+             FROM meal_records m LEFT JOIN employees e ON e.epf_number = m.epf_number
+             WHERE strftime('%Y-%m', m.meal_date) = ?1
+             GROUP BY m.epf_number
+             ORDER BY e.department, m.epf_number",
+        )?;
+    let deductions = stmt
+        .query_map([month], |row| {
+            let meal_count: i32 = row.get(3)?;
+            let gross_cost = meal_count as f64 * meal_price;
+            let subsidy = (gross_cost * subsidy_percent / 100.0 * 100.0).round() / 100.0;
+            Ok(MealDeduction {
+                epf_number: row.get(0)?,
+                name_with_initials: row.get(1)?,
+                department: row.get(2)?,
+                meal_count,
+                gross_cost,
+                subsidy,
+                deduction: gross_cost - subsidy,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(deductions)
+}
+
+#[tauri::command]
+pub fn get_monthly_meal_deductions(
+    month: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<MealDeduction>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_reports {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view reports."));
+    }
+    parse_iso_date(&format!("{}-01", month)).ok_or("Month must be in YYYY-MM format")?;
+    
+    let conn = db.0.lock()?;
+    monthly_meal_deductions(&conn, &month)
+}
+
+// Expected meals per department and meal type, from the average of recent
+// canteen days
+#[tauri::command]
+pub fn get_meal_forecast(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<MealForecast>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_reports {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view reports."));
+    }
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(
+            "WITH recent AS (
+                 SELECT m.meal_date, m.meal_type, COALESCE(e.department, '') AS department
+                 FROM meal_records m JOIN employees e ON e.epf_number = m.epf_number
+                 WHERE m.meal_date >= date('now', 'localtime', '-' || ?1 || ' days')
+             ),
+             days AS (
+                 SELECT meal_type, COUNT(DISTINCT meal_date) AS day_count FROM recent GROUP BY meal_type
+             )
+             SELECT r.department, r.meal_type,
+                    (SELECT COUNT(*) FROM employees e
+                     WHERE e.working_status IN (SELECT code FROM working_statuses WHERE counts_as_active = 1) AND COALESCE(e.department, '') = r.department),
+                    CAST(COUNT(*) AS REAL) / d.day_count
+             FROM recent r JOIN days d ON d.meal_type = r.meal_type
+             GROUP BY r.department, r.meal_type
+             ORDER BY r.department, r.meal_type",
+        )?;
+    let forecast = stmt
+        .query_map([FORECAST_WINDOW_DAYS], |row| {
+            let average: f64 = row.get(3)?;
+            Ok(MealForecast {
+                department: row.get(0)?,
+                meal_type: row.get(1)?,
+                active_headcount: row.get(2)?,
+                average_daily_meals: (average * 10.0).round() / 10.0,
+                forecast: average.ceil() as i32,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(forecast)
+}
@@ -0,0 +1,297 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{EmployeeMedical, FitnessCertificate, FitnessDue, UserSession};
+use crate::settings_commands::get_setting;
+use crate::validation::parse_iso_date;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::{Connection, OptionalExtension};
+use tauri::State;
+
+pub const BLOOD_GROUPS: [&str; 8] = ["A+", "A-", "B+", "B-", "AB+", "AB-", "O+", "O-"];
+pub const FITNESS_RESULTS: [&str; 3] = ["fit", "fit_with_restrictions", "unfit"];
+
+// Designations that must hold a current medical fitness certificate, unless
+// overridden by the medical_fitness_designations setting (comma-separated)
+const DEFAULT_FITNESS_DESIGNATIONS: [&str; 2] = ["Machine Operator", "Food Handler"];
+
+// Days ahead the expiring-fitness report looks by default
+const DEFAULT_EXPIRY_WINDOW_DAYS: i64 = 30;
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+// Health details are only shown to users who can see sensitive personal data
+fn require_medical_access(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user = logged_in_user(current_user)?;
+    if !user.permissions.can_view_sensitive_data {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view medical records."));
+    }
+    Ok(user)
+}
+
+fn require_medical_edit(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user = require_medical_access(current_user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit employees."));
+    }
+    Ok(user)
+}
+
+fn fitness_designations(conn: &Connection) -> Vec<String> {
+    match get_setting(conn, "medical_fitness_designations") {
+        Some(value) => value
+            .split(',')
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty())
+            .collect(),
+        None => DEFAULT_FITNESS_DESIGNATIONS.iter().map(|d| d.to_string()).collect(),
+    }
+}
+
+fn require_employee(conn: &Connection, epf_number: &str) -> Result<(), HrmError> {
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM employees WHERE epf_number = ?1)",
+        [epf_number],
+        |row| row.get(0),
+    )?;
+    if exists {
+        Ok(())
+    } else {
+        Err(HrmError::not_found(format!("Employee {} not found", epf_number)))
+    }
+}
+
+const CERTIFICATE_COLUMNS: &str =
+    "id, epf_number, examined_on, valid_until, result, restrictions, examined_by, document_path, recorded_by, created_at";
+
+fn certificate_from_row(row: &rusqlite::Row) -> rusqlite::Result<FitnessCertificate> {
+    Ok(FitnessCertificate {
+        id: row.get(0)?,
+        epf_number: row.get(1)?,
+        examined_on: row.get(2)?,
+        valid_until: row.get(3)?,
+        result: row.get(4)?,
+        restrictions: row.get(5)?,
+        examined_by: row.get(6)?,
+        document_path: row.get(7)?,
+        recorded_by: row.get(8)?,
+        created_at: row.get(9)?,
+    })
+}
+
+// Blood group, allergies and the fitness certificate history, newest first
+#[tauri::command]
+pub fn get_medical_record(
+    epf_number: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<EmployeeMedical, HrmError> {
+    require_medical_access(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    require_employee(&conn, &epf_number)?;
+    let mut record = conn
+        .query_row(
+            "SELECT blood_group, allergies, updated_by, updated_at FROM employee_medical WHERE epf_number = ?1",
+            [&epf_number],
+            |row| {
+                Ok(EmployeeMedical {
+                    epf_number: epf_number.clone(),
+                    blood_group: row.get(0)?,
+                    allergies: row.get(1)?,
+                    updated_by: row.get(2)?,
+                    updated_at: row.get(3)?,
+                    certificates: Vec::new(),
+                })
+            },
+        )
+        .optional()?
+        .unwrap_or_else(|| EmployeeMedical {
+            epf_number: epf_number.clone(),
+            blood_group: None,
+            allergies: None,
+            updated_by: None,
+            updated_at: None,
+            certificates: Vec::new(),
+        });
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM medical_fitness_certificates WHERE epf_number = ?1 ORDER BY examined_on DESC, id DESC",
+        CERTIFICATE_COLUMNS
+    ))?;
+    record.certificates = stmt
+        .query_map([&epf_number], certificate_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(record)
+}
+
+#[tauri::command]
+pub fn set_medical_details(
+    epf_number: String,
+    blood_group: Option<String>,
+    allergies: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = require_medical_edit(&current_user)?;
+    let blood_group = blood_group.map(|b| b.trim().to_uppercase()).filter(|b| !b.is_empty());
+    if let Some(group) = &blood_group {
+        if !BLOOD_GROUPS.contains(&group.as_str()) {
+            return Err(HrmError::field("blood_group", "Blood group must be A+, A-, B+, B-, AB+, AB-, O+ or O-"));
+        }
+    }
+    let allergies = allergies.map(|a| a.trim().to_string()).filter(|a| !a.is_empty());
+    
+    let conn = db.0.lock()?;
+    require_employee(&conn, &epf_number)?;
+    conn.execute(
+        "INSERT INTO employee_medical (epf_number, blood_group, allergies, updated_by, updated_at)
+         VALUES (?1, ?2, ?3, ?4, CURRENT_TIMESTAMP)
+         ON CONFLICT(epf_number) DO UPDATE SET blood_group = excluded.blood_group,
+             allergies = excluded.allergies, updated_by = excluded.updated_by, updated_at = CURRENT_TIMESTAMP",
+        rusqlite::params![epf_number, blood_group, allergies, user.username],
+    )?;
+    
+    // The details themselves stay out of the audit log
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "MEDICAL",
+        Some(&epf_number),
+        None,
+        None,
+        Some(&format!("Updated blood group and allergies for employee {}", epf_number)),
+    );
+    
+    Ok(())
+}
+
+// Records a medical fitness examination. Certificates run for a year from
+// the examination unless valid_until says otherwise.
+#[tauri::command]
+pub fn record_fitness_certificate(
+    certificate: FitnessCertificate,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = require_medical_edit(&current_user)?;
+    parse_iso_date(&certificate.examined_on).ok_or("Examination date must be a valid date (YYYY-MM-DD)")?;
+    if !FITNESS_RESULTS.contains(&certificate.result.as_str()) {
+        return Err(HrmError::field("result", "Result must be fit, fit_with_restrictions or unfit"));
+    }
+    let text = |value: &Option<String>| value.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+    let restrictions = text(&certificate.restrictions);
+    if certificate.result == "fit_with_restrictions" && restrictions.is_none() {
+        return Err(HrmError::field("restrictions", "Describe the restrictions"));
+    }
+    
+    let conn = db.0.lock()?;
+    require_employee(&conn, &certificate.epf_number)?;
+    let valid_until = match text(&certificate.valid_until) {
+        Some(date) => {
+            parse_iso_date(&date).ok_or("Valid until must be a valid date (YYYY-MM-DD)")?;
+            if date <= certificate.examined_on {
+                return Err(HrmError::field("valid_until", "Valid until must be after the examination date"));
+            }
+            date
+        }
+        None => conn.query_row("SELECT date(?1, '+1 year', '-1 day')", [&certificate.examined_on], |row| row.get(0))?,
+    };
+    conn.execute(
+        "INSERT INTO medical_fitness_certificates
+            (epf_number, examined_on, valid_until, result, restrictions, examined_by, document_path, recorded_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![
+            certificate.epf_number,
+            certificate.examined_on,
+            valid_until,
+            certificate.result,
+            restrictions,
+            text(&certificate.examined_by),
+            text(&certificate.document_path),
+            user.username,
+        ],
+    )?;
+    let id = conn.last_insert_rowid();
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "MEDICAL",
+        Some(&certificate.epf_number),
+        None,
+        None,
+        Some(&format!(
+            "Recorded medical fitness certificate for employee {} examined on {}, valid until {}",
+            certificate.epf_number, certificate.examined_on, valid_until
+        )),
+    );
+    
+    Ok(id)
+}
+
+// Active employees in designations that need a fitness certificate whose
+// latest certificate is missing, unfit, expired or expires within `days`
+// (30 by default), soonest first
+#[tauri::command]
+pub fn get_expiring_fitness_certificates(
+    days: Option<i64>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<FitnessDue>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_reports {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view reports."));
+    }
+    let days = days.unwrap_or(DEFAULT_EXPIRY_WINDOW_DAYS).max(0);
+    
+    let conn = db.0.lock()?;
+    let designations: Vec<String> = fitness_designations(&conn).iter().map(|d| d.to_lowercase()).collect();
+    let mut stmt = conn.prepare(
+        "SELECT e.epf_number, e.name_with_initials, e.department, e.designation, c.valid_until, c.result,
+                CAST(julianday(c.valid_until) - julianday('now', 'localtime', 'start of day') AS INTEGER)
+         FROM employees e
+         LEFT JOIN medical_fitness_certificates c ON c.id = (
+             SELECT id FROM medical_fitness_certificates
+             WHERE epf_number = e.epf_number ORDER BY examined_on DESC, id DESC LIMIT 1)
+         WHERE e.working_status IN (SELECT code FROM working_statuses WHERE counts_as_active = 1)
+         ORDER BY c.valid_until IS NOT NULL, c.valid_until, e.epf_number",
+    )?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(FitnessDue {
+                epf_number: row.get(0)?,
+                name_with_initials: row.get(1)?,
+                department: row.get(2)?,
+                designation: row.get(3)?,
+                valid_until: row.get(4)?,
+                result: row.get(5)?,
+                days_remaining: row.get(6)?,
+                status: String::new(),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    let due = rows
+        .into_iter()
+        .filter(|r| designations.contains(&r.designation.as_deref().unwrap_or("").trim().to_lowercase()))
+        .filter(|r| user.can_access_department(r.department.as_deref()))
+        .filter_map(|mut r| {
+            let status = match (r.result.as_deref(), r.days_remaining) {
+                (None, _) | (_, None) => "missing",
+                (Some("unfit"), _) => "unfit",
+                (_, Some(remaining)) if remaining < 0 => "expired",
+                (_, Some(remaining)) if remaining <= days => "expiring",
+                _ => return None,
+            };
+            r.status = status.to_string();
+            Some(r)
+        })
+        .collect();
+    Ok(due)
+}
@@ -1,10 +1,13 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Employee {
     pub epf_number: String,
     pub name_with_initials: String,
     pub full_name: String,
+    #[serde(default)]
+    pub nic: Option<String>,
     pub dob: Option<String>,
     pub police_area: Option<String>,
     pub transport_route: Option<String>,
@@ -20,28 +23,1159 @@ pub struct Employee {
     pub allocation: Option<String>,
     pub department: Option<String>,
     pub image_path: Option<String>,
+    #[serde(default)]
+    pub previous_epf_number: Option<String>,     // Earlier record when the employee was rehired
+    #[serde(default)]
+    pub district: Option<String>,                // Residence, from the administrative master lists
+    #[serde(default)]
+    pub ds_division: Option<String>,             // Divisional Secretariat division within the district
+    #[serde(default)]
+    pub gn_division: Option<String>,             // Grama Niladhari division within the DS division
     #[serde(skip_deserializing)]
     pub created_at: Option<String>,
+    #[serde(default)]
+    pub custom_fields: HashMap<String, String>,  // Installation-defined fields, keyed by field name
+    #[serde(skip_deserializing)]
+    pub age: Option<i64>,                        // Whole years, computed from dob
+    #[serde(skip_deserializing)]
+    pub service_years: Option<i64>,              // Whole years from joining to resignation or today
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmployeeFilters {
+    pub epf_number: String,
+    pub department: String,
+    pub transport_route: String,
+    pub working_status: String,
+    #[serde(default)]
+    pub custom_fields: HashMap<String, String>,  // Exact match on custom field values, keyed by field name
+    #[serde(default)]
+    pub tags: Vec<i64>,  // Employees must carry every listed tag
+    #[serde(default)]
+    pub departments: Vec<String>,  // Any of these; combined with `department` when both are set
+    #[serde(default)]
+    pub designations: Vec<String>,
+    #[serde(default)]
+    pub allocations: Vec<String>,
+    #[serde(default)]
+    pub districts: Vec<String>,
+    #[serde(default)]
+    pub ds_divisions: Vec<String>,
+    #[serde(default)]
+    pub joined_from: Option<String>,  // YYYY-MM-DD, inclusive
+    #[serde(default)]
+    pub joined_to: Option<String>,
+    #[serde(default)]
+    pub resigned_from: Option<String>,
+    #[serde(default)]
+    pub resigned_to: Option<String>,
+    #[serde(default)]
+    pub age_min: Option<i64>,
+    #[serde(default)]
+    pub age_max: Option<i64>,
+    #[serde(default)]
+    pub service_years_min: Option<i64>,
+    #[serde(default)]
+    pub service_years_max: Option<i64>,
+    #[serde(default)]
+    pub sort_by: Option<String>,  // epf_number (default), name, age or service_years
+    #[serde(default)]
+    pub sort_descending: bool,
+}
+
+// A named set of employee filters saved by a user
+#[derive(Debug, Serialize)]
+pub struct SavedFilter {
+    pub id: i64,
+    pub name: String,
+    pub filters: EmployeeFilters,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CustomFieldDefinition {
+    pub id: i64,
+    pub name: String,
+    pub field_type: String,    // text, number, date or select
+    pub options: Vec<String>,  // Allowed values for select fields
+    pub is_active: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Tag {
+    pub id: i64,
+    pub name: String,
+    pub color: Option<String>,  // Hex colour for the badge, e.g. "#2563EB"
+    pub employee_count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmployeeNote {
+    pub id: i64,
+    pub epf_number: String,
+    pub author_id: Option<i32>,
+    pub author_name: Option<String>,
+    pub note: String,
+    pub is_pinned: bool,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmployeeQualification {
+    #[serde(default)]
+    pub id: i64,
+    pub epf_number: String,
+    pub level: String,                  // O/L, A/L, Certificate, Diploma, Degree, Postgraduate, Professional
+    pub title: Option<String>,          // e.g. "BSc in Textile Engineering"
+    pub institute: Option<String>,
+    pub year: Option<i32>,
+    pub document_path: Option<String>,  // Link or path to the scanned certificate
+    #[serde(skip_deserializing)]
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QualificationDistribution {
+    pub department: String,
+    pub level: String,
+    pub count: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Skill {
+    pub id: i64,
+    pub name: String,               // Skill, machine or line, e.g. "Overlock machine"
+    pub category: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmployeeSkill {
+    pub epf_number: String,
+    pub skill_id: i64,
+    pub skill_name: String,
+    pub level: i32,                 // 1 learning .. 4 can train others
+    pub certified_date: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SkillCoverage {
+    pub department: String,
+    pub skill_id: i64,
+    pub skill_name: String,
+    pub qualified_count: i32,       // Level 3 or above
+    pub certified_count: i32,
+    pub total_count: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Training {
+    #[serde(default)]
+    pub id: i64,
+    pub course: String,
+    pub training_date: String,
+    pub provider: Option<String>,
+    pub cost: Option<f64>,        // Total cost of the session in LKR
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub attendee_count: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TrainingGap {
+    pub epf_number: String,
+    pub name_with_initials: String,
+    pub department: Option<String>,
+    pub missing_trainings: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DisciplinaryAction {
+    #[serde(default)]
+    pub id: i64,
+    pub epf_number: String,
+    pub action_date: String,
+    pub action_type: String,             // verbal_warning, written_warning, final_warning, suspension
+    pub description: String,
+    pub document_path: Option<String>,   // Link or path to the signed letter
+    #[serde(skip_deserializing)]
+    pub expires_on: Option<String>,      // Warnings lapse after a fixed period
+    #[serde(default)]
+    pub is_withdrawn: bool,
+    #[serde(skip_deserializing)]
+    pub recorded_by: Option<String>,
+    #[serde(skip_deserializing)]
+    pub created_at: Option<String>,
+    #[serde(skip_deserializing)]
+    pub is_active: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DisciplinarySummary {
+    pub epf_number: String,
+    pub active_warnings: i32,
+    pub total_actions: i32,
+    pub highest_active_action: Option<String>,
+    pub suggested_next_action: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Incident {
+    #[serde(default)]
+    pub id: i64,
+    pub incident_date: String,
+    pub location: String,
+    pub severity: String,                // minor, moderate, major, fatal
+    pub description: String,
+    #[serde(default)]
+    pub reported_to_labour_dept: bool,
+    pub labour_dept_reported_on: Option<String>,
+    #[serde(default)]
+    pub injured_employees: Vec<String>,  // EPF numbers
+    #[serde(default)]
+    pub attachments: Vec<String>,        // Photos, medical reports, claim forms
+    #[serde(skip_deserializing)]
+    pub recorded_by: Option<String>,
+    #[serde(skip_deserializing)]
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IncidentSummary {
+    pub year: i32,
+    pub total_incidents: i32,
+    pub by_severity: HashMap<String, i32>,
+    pub by_month: Vec<i32>,              // January first
+    pub injured_count: i32,
+    pub reported_to_labour_dept: i32,
+    pub unreported_serious: i32,         // Major/fatal not yet reported
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Grievance {
+    #[serde(default)]
+    pub id: i64,
+    pub epf_number: Option<String>,      // None for grievances raised by a group
+    pub subject: String,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    pub raised_on: String,
+    #[serde(default)]
+    pub status: String,                  // open, investigating, resolved, closed
+    #[serde(skip_deserializing)]
+    pub assigned_to: Option<i32>,
+    #[serde(skip_deserializing)]
+    pub assigned_to_name: Option<String>,
+    #[serde(default)]
+    pub is_confidential: bool,
+    #[serde(skip_deserializing)]
+    pub resolution: Option<String>,
+    #[serde(skip_deserializing)]
+    pub resolved_at: Option<String>,
+    #[serde(skip_deserializing)]
+    pub recorded_by: Option<String>,
+    #[serde(skip_deserializing)]
+    pub created_at: Option<String>,
+    #[serde(skip_deserializing)]
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PendingGrievance {
+    pub id: i64,
+    pub epf_number: Option<String>,
+    pub name_with_initials: Option<String>,
+    pub subject: String,
+    pub status: String,
+    pub assigned_to_name: Option<String>,
+    pub raised_on: String,
+    pub age_days: i64,
+    pub sla_days: i64,
+    pub is_overdue: bool,
+    pub is_confidential: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Asset {
+    #[serde(default)]
+    pub id: i64,
+    pub name: String,
+    pub category: String,                // uniform, locker, tool, laptop, other
+    pub asset_code: Option<String>,      // Locker number or serial; coded items are issued singly
+    #[serde(default)]
+    pub is_returnable: bool,
+    #[serde(skip_deserializing)]
+    pub is_active: bool,
+    #[serde(skip_deserializing)]
+    pub outstanding_quantity: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AssetIssue {
+    pub id: i64,
+    pub asset_id: i64,
+    pub asset_name: String,
+    pub category: String,
+    pub asset_code: Option<String>,
+    pub epf_number: String,
+    pub name_with_initials: Option<String>,
+    pub quantity: i32,
+    pub issued_on: String,
+    pub returned_on: Option<String>,
+    pub return_condition: Option<String>, // good, damaged, lost
+    pub notes: Option<String>,
+    pub issued_by: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Visitor {
+    #[serde(default)]
+    pub id: i64,
+    pub name: String,
+    pub nic: String,
+    pub company: Option<String>,
+    pub purpose: Option<String>,
+    pub host_epf_number: Option<String>,
+    #[serde(skip_deserializing)]
+    pub host_name: Option<String>,
+    pub badge_number: Option<String>,
+    #[serde(skip_deserializing)]
+    pub time_in: String,
+    #[serde(skip_deserializing)]
+    pub time_out: Option<String>,
+    #[serde(skip_deserializing)]
+    pub checked_in_by: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VisitorDayReport {
+    pub date: String,
+    pub total_visitors: i32,
+    pub still_on_site: i32,
+    pub visitors: Vec<Visitor>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MealDeduction {
+    pub epf_number: String,
+    pub name_with_initials: Option<String>,
+    pub department: Option<String>,
+    pub meal_count: i32,
+    pub gross_cost: f64,                 // LKR
+    pub subsidy: f64,
+    pub deduction: f64,                  // Amount to recover through payroll
+}
+
+#[derive(Debug, Serialize)]
+pub struct MealForecast {
+    pub department: String,
+    pub meal_type: String,
+    pub active_headcount: i32,
+    pub average_daily_meals: f64,
+    pub forecast: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeductionType {
+    #[serde(default)]
+    pub id: i64,
+    pub name: String,                    // e.g. Welfare Society, Union Fee, Death Donation Fund
+    pub payee: Option<String>,           // Who the collected amount is remitted to
+    pub default_amount: Option<f64>,
+    #[serde(skip_deserializing)]
+    pub is_active: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmployeeDeduction {
+    #[serde(default)]
+    pub id: i64,
+    pub epf_number: String,
+    pub deduction_type_id: i64,
+    #[serde(skip_deserializing)]
+    pub deduction_type: String,
+    #[serde(default)]
+    pub amount: f64,                     // LKR per month; 0 uses the type's default
+    pub start_date: String,
+    pub end_date: Option<String>,        // None while the deduction is running
+    pub notes: Option<String>,
+}
+
+// One deduction to take from an employee's pay in a given month
+#[derive(Debug, Serialize)]
+pub struct DeductionLine {
+    pub epf_number: String,
+    pub name_with_initials: Option<String>,
+    pub deduction_type: String,
+    pub amount: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeductionRemittance {
+    pub month: String,
+    pub deduction_type: String,
+    pub payee: Option<String>,
+    pub employee_count: i32,
+    pub total_amount: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FestivalAdvance {
+    pub id: i64,
+    pub epf_number: String,
+    pub name_with_initials: Option<String>,
+    pub festival: String,
+    pub amount: f64,
+    pub installments: i64,               // Months to recover over
+    pub status: String,                  // pending, approved, rejected
+    pub requested_on: String,
+    pub first_recovery_month: Option<String>, // YYYY-MM, set on approval
+    pub approved_by: Option<String>,
+    pub approved_at: Option<String>,
+    pub rejection_reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InsurancePolicy {
+    #[serde(default)]
+    pub id: i64,
+    pub policy_number: String,
+    pub insurer: String,
+    pub policy_type: Option<String>,     // e.g. surgical & hospitalisation, life, personal accident
+    pub start_date: String,              // Policy year
+    pub end_date: String,
+    pub premium: Option<f64>,
+    pub notes: Option<String>,
+    #[serde(skip_deserializing)]
+    pub enrolled_count: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InsuranceDependent {
+    pub name: String,
+    pub relationship: String,            // spouse, child, parent
+    pub dob: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InsuranceEnrollment {
+    pub id: i64,
+    pub policy_id: i64,
+    pub policy_number: String,
+    pub insurer: String,
+    pub epf_number: String,
+    pub enrolled_on: String,
+    pub ended_on: Option<String>,
+    pub dependents: Vec<InsuranceDependent>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InsuranceClaim {
+    #[serde(default)]
+    pub id: i64,
+    pub enrollment_id: i64,
+    #[serde(skip_deserializing)]
+    pub epf_number: String,
+    #[serde(skip_deserializing)]
+    pub policy_number: String,
+    pub claimant: Option<String>,        // Dependent's name when not the employee
+    pub claim_date: String,
+    pub amount_claimed: f64,
+    #[serde(skip_deserializing)]
+    pub amount_settled: Option<f64>,
+    #[serde(skip_deserializing)]
+    pub status: String,                  // submitted, approved, rejected, paid
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub documents: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PolicyClaimsSummary {
+    pub policy_number: String,
+    pub insurer: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub premium: Option<f64>,
+    pub enrolled_count: i32,
+    pub claim_count: i32,
+    pub rejected_count: i32,
+    pub open_count: i32,
+    pub total_claimed: f64,
+    pub total_settled: f64,
+    pub loss_ratio: Option<f64>,         // Settled / premium
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExitInterview {
+    pub epf_number: String,
+    pub interview_date: String,
+    pub primary_reason: String,          // better_pay, career_growth, relocation, family, ...
+    #[serde(default)]
+    pub secondary_reasons: Vec<String>,
+    pub would_rejoin: Option<bool>,
+    pub comments: Option<String>,
+    #[serde(skip_deserializing)]
+    pub interviewer: Option<String>,
+}
+
+// Number of leavers in a group (department or tenure band) giving a reason
+#[derive(Debug, Serialize)]
+pub struct ReasonCount {
+    pub group: String,
+    pub reason: String,
+    pub count: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExitAnalytics {
+    pub total_interviews: i32,
+    pub would_rejoin_percent: Option<f64>,
+    pub by_department: Vec<ReasonCount>,
+    pub by_tenure_band: Vec<ReasonCount>,
+}
+
+// A resigned employee whose NIC matches a new hire
+#[derive(Debug, Serialize)]
+pub struct PreviousEmployment {
+    pub epf_number: String,
+    pub name_with_initials: String,
+    pub department: Option<String>,
+    pub designation: Option<String>,
+    pub date_of_join: Option<String>,
+    pub date_of_resign: Option<String>,
+}
+
+// A stored employee date that could not be read as YYYY-MM-DD
+#[derive(Debug, Serialize)]
+pub struct InvalidEmployeeDate {
+    pub epf_number: String,
+    pub name_with_initials: String,
+    pub field: String,                   // dob, date_of_join or date_of_resign
+    pub value: String,
+}
+
+// One problem found by the data quality checks. entity_type and entity_id
+// locate the record to open, as on notifications; field is the form field
+// to correct.
+#[derive(Debug, Serialize)]
+pub struct DataQualityIssue {
+    pub epf_number: String,
+    pub name_with_initials: String,
+    pub field: String,
+    pub detail: String,
+    pub entity_type: String,
+    pub entity_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DataQualityCategory {
+    pub code: String,                    // invalid_dates, impossible_ages, duplicate_phones, active_with_resign_date, missing_departments
+    pub label: String,
+    pub issues: Vec<DataQualityIssue>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct District {
+    pub name: String,
+    pub province: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GnDivision {
+    pub id: i64,
+    pub name: String,
+    pub code: Option<String>,
+}
+
+// Active employees living in one area; parts below the report level, or not
+// recorded for the employees, are None
+#[derive(Debug, Serialize)]
+pub struct ResidenceCount {
+    pub district: Option<String>,
+    pub ds_division: Option<String>,
+    pub gn_division: Option<String>,
+    pub count: i32,
+}
+
+// How much of an employee's profile is filled in
+#[derive(Debug, Serialize)]
+pub struct ProfileCompleteness {
+    pub epf_number: String,
+    pub name_with_initials: String,
+    pub department: Option<String>,
+    pub score: i32,                      // Percentage of profile items filled in
+    pub missing: Vec<String>,            // Labels of the items still blank
+}
+
+#[derive(Debug, Serialize)]
+pub struct DepartmentCompleteness {
+    pub department: String,              // "Unassigned" for employees with no department
+    pub employee_count: i32,
+    pub incomplete_count: i32,
+    pub average_score: f64,
+    pub least_complete: Vec<ProfileCompleteness>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmploymentPeriod {
+    pub id: i64,
+    pub epf_number: String,              // May be an earlier EPF number for rehires
+    pub start_date: String,
+    pub end_date: Option<String>,        // None for the current period
+    pub days: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ServiceSummary {
+    pub epf_number: String,
+    pub total_days: i64,                 // All periods added together
+    pub completed_years: i64,
+    pub current_period_days: i64,        // Continuous service in the current period
+    pub periods: Vec<EmploymentPeriod>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmployeeTransfer {
+    #[serde(default)]
+    pub id: i64,
+    pub epf_number: String,
+    #[serde(skip_deserializing)]
+    pub from_location: Option<String>,   // Company name at the time of the transfer
+    pub to_location: String,             // Receiving branch or company
+    pub effective_date: String,          // First day at the receiving end
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub new_epf_number: Option<String>,  // Number at the receiving end, when known
+    #[serde(skip_deserializing)]
+    pub transferred_by: Option<String>,
+    #[serde(skip_deserializing)]
+    pub created_at: Option<String>,
+}
+
+// Transfer letter issued with the transfer
+#[derive(Debug, Deserialize)]
+pub struct TransferLetter {
+    pub template_id: i64,
+    pub format: String,                  // html, docx or pdf
+    pub destination_path: String,
+}
+
+// One entry in an employee's history timeline
+#[derive(Debug, Serialize)]
+pub struct HistoryEvent {
+    pub date: String,
+    pub kind: String,                    // record, incident, disciplinary
+    pub title: String,
+    pub details: Option<String>,
+    pub recorded_by: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DashboardStats {
+    pub total_employees: i32,
+    pub active_employees: i32,
+    pub resigned_employees: i32,
+    pub departments: Vec<DepartmentCount>,
+    pub caders: Vec<DepartmentCount>,
+    pub allocations: Vec<DepartmentCount>,
+    pub recent_joinings: i32,
+    pub recent_resignations: i32,
+    pub status_counts: Vec<StatusCount>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmployeePhoto {
+    pub image_path: String,
+    pub original_size: Option<i64>,  // Bytes as uploaded, before compression
+    pub stored_size: Option<i64>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub format: Option<String>,
+    pub is_primary: bool,
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrphanedFile {
+    pub epf_number: String,
+    pub path: String,         // Relative to the app data directory
+    pub size_bytes: u64,
+    pub is_folder: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrphanCleanupReport {
+    pub files: Vec<OrphanedFile>,
+    pub reclaimable_bytes: u64,
+    pub reclaimable_formatted: String,
+    pub deleted: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FieldConfig {
+    pub field: String,
+    pub label: String,
+    pub visibility: String,  // required, optional or hidden
+}
+
+// Result of migrating a legacy spreadsheet register
+#[derive(Debug, Serialize)]
+pub struct LegacyImportReport {
+    pub total_rows: usize,
+    pub imported: usize,
+    pub skipped: Vec<LegacyImportRowError>,
+    pub unmapped_columns: Vec<String>,  // Source headers with no mapping, ignored
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LegacyImportRowError {
+    pub row: usize,                  // 1-based line number in the source file
+    pub epf_number: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkingStatus {
+    pub code: String,
+    pub label: String,
+    pub counts_as_active: bool,          // Included in active headcounts
+    pub color: Option<String>,           // Hex colour for status badges
+    #[serde(default)]
+    pub sort_order: i32,
+    #[serde(default)]
+    pub is_system: bool,                 // 'active' and 'resign' cannot be deleted
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatusCount {
+    pub code: String,
+    pub label: String,
+    pub color: Option<String>,
+    pub count: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HeadcountBudget {
+    #[serde(default)]
+    pub id: i64,
+    pub department: String,
+    pub cader: Option<String>,           // None budgets the whole department
+    pub budgeted_count: i32,
+    pub effective_from: String,
+    pub effective_to: Option<String>,    // None until replaced
+    #[serde(default)]
+    pub created_by: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HeadcountSnapshot {
+    pub month: String,                   // YYYY-MM
+    pub department: String,
+    pub cader: String,
+    pub working_status: String,
+    pub headcount: i32,
+    pub taken_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HeadcountVariance {
+    pub department: String,
+    pub cader: Option<String>,
+    pub budgeted_count: i32,
+    pub actual_count: i32,
+    pub variance: i32,                   // Actual minus budget; negative means unfilled positions
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Vacancy {
+    #[serde(default)]
+    pub id: i64,
+    pub title: String,
+    pub department: Option<String>,
+    pub cader: Option<String>,
+    pub designation: Option<String>,
+    pub positions: i32,
+    #[serde(default)]
+    pub status: String,                  // open, filled, cancelled
+    pub opened_on: String,
+    #[serde(default)]
+    pub closed_on: Option<String>,
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub created_by: Option<String>,
+    #[serde(default)]
+    pub candidate_count: i32,
+    #[serde(default)]
+    pub joined_count: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Candidate {
+    #[serde(default)]
+    pub id: i64,
+    pub vacancy_id: i64,
+    pub full_name: String,
+    pub name_with_initials: Option<String>,
+    pub nic: Option<String>,
+    pub dob: Option<String>,
+    pub mobile: Option<String>,
+    pub address: Option<String>,
+    pub applied_on: String,
+    #[serde(default)]
+    pub stage: String,                   // applied, interviewed, selected, joined, rejected
+    #[serde(default)]
+    pub stage_changed_on: Option<String>,
+    #[serde(default)]
+    pub employee_epf_number: Option<String>,  // Set once the candidate joins
+    pub notes: Option<String>,
+}
+
+// Score sheet schema: one score per interview_commands::SCORE_CRITERIA entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CriterionScore {
+    pub criterion: String,
+    pub score: i32,                      // 1 (poor) to 5 (excellent)
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreSheet {
+    pub scores: Vec<CriterionScore>,
+    pub recommendation: String,          // hire, hold, reject
+    pub comments: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Interview {
+    pub id: i64,
+    pub candidate_id: i64,
+    pub candidate_name: String,
+    pub vacancy_id: i64,
+    pub interviewer_user_id: i32,
+    pub interviewer_name: Option<String>,
+    pub scheduled_at: String,            // YYYY-MM-DD HH:MM
+    pub duration_minutes: i32,
+    pub location: Option<String>,
+    pub status: String,                  // scheduled, completed, cancelled
+    pub score_sheet: Option<ScoreSheet>,
+    pub overall_score: Option<f64>,
+    pub evaluated_at: Option<String>,
+    pub created_by: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CandidateComparison {
+    pub candidate_id: i64,
+    pub full_name: String,
+    pub stage: String,
+    pub interview_count: i32,
+    pub criterion_averages: HashMap<String, f64>,
+    pub overall_score: Option<f64>,      // None until interviewed
+    pub recommendations: HashMap<String, i32>,  // Recommendation -> number of interviewers
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimeToFill {
+    pub vacancy_id: i64,
+    pub title: String,
+    pub department: Option<String>,
+    pub status: String,
+    pub positions: i32,
+    pub opened_on: String,
+    pub closed_on: Option<String>,
+    pub joined_count: i32,
+    pub days: i64,                       // Opening to filling, or to today while still open
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimeToFillReport {
+    pub filled_count: i32,
+    pub open_count: i32,
+    pub average_days_to_fill: Option<f64>,
+    pub vacancies: Vec<TimeToFill>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SalaryRevision {
+    pub id: i64,
+    pub epf_number: String,
+    pub old_salary: Option<f64>,         // None for the starting salary
+    pub new_salary: f64,
+    pub currency: String,                // LKR unless paid in a foreign currency
+    pub increment_percent: Option<f64>,
+    pub effective_date: String,
+    pub reason: String,
+    pub approved_by: String,
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IncrementSummary {
+    pub department: String,
+    pub revision_count: i32,
+    pub average_percent: f64,
+    pub min_percent: f64,
+    pub max_percent: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrgNode {
+    pub epf_number: String,
+    pub name_with_initials: String,
+    pub designation: Option<String>,
+    pub department: Option<String>,
+    pub reports: Vec<OrgNode>,           // Direct reports
+}
+
+#[derive(Debug, Serialize)]
+pub struct PendingReassignment {
+    pub epf_number: String,
+    pub name_with_initials: String,
+    pub supervisor_epf: String,          // No longer active
+    pub supervisor_name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KeyPosition {
+    #[serde(default)]
+    pub id: i64,                         // 0 when flagging a new position
+    pub title: String,
+    pub department: Option<String>,
+    pub incumbent_epf: Option<String>,
+    #[serde(default)]
+    pub incumbent_name: Option<String>,
+    pub risk_level: String,              // high, medium, low
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub required_skill_ids: Vec<i64>,
+    #[serde(default)]
+    pub successor_count: i32,            // Active nominated successors
+}
+
+#[derive(Debug, Serialize)]
+pub struct Successor {
+    pub epf_number: String,
+    pub name_with_initials: String,
+    pub designation: Option<String>,
+    pub readiness: String,               // ready_now, 1_2_years, 3_plus_years; empty for suggestions
+    pub notes: Option<String>,
+    pub nominated_by: Option<String>,
+    pub nominated_on: Option<String>,
+    pub is_active: bool,
+    pub skills_met: i32,                 // Required skills held at the qualified level
+}
+
+#[derive(Debug, Serialize)]
+pub struct SuccessionGap {
+    pub position: KeyPosition,
+    pub suggestions: Vec<Successor>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnonymizationCandidate {
+    pub epf_number: String,
+    pub name_with_initials: String,
+    pub department: Option<String>,
+    pub date_of_resign: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RetentionRule {
+    pub rule_key: String,                // audit_logs, resigned_employees, visitors, notifications
+    pub description: String,
+    pub retain_value: i64,
+    pub retain_unit: String,             // days, months, years
+    pub is_enabled: bool,
+    pub confirmed_at: Option<String>,    // Set after a dry run is reviewed; cleared on change
+    pub last_run_at: Option<String>,
+    pub last_run_count: Option<i64>,
+    pub updated_by: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RetentionPreview {
+    pub rule: RetentionRule,
+    pub affected_count: i64,
+    pub sample: Vec<String>,             // First few affected records
+}
+
+// A signed consent or acknowledgement form (consent_commands::CONSENT_TYPES)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmployeeConsent {
+    #[serde(default)]
+    pub id: i64,
+    pub epf_number: String,
+    pub consent_type: String,            // data_processing, cctv, medical
+    pub signed_on: String,
+    pub document_path: Option<String>,   // Link or path to the signed form
+    #[serde(skip_deserializing)]
+    pub withdrawn_on: Option<String>,
+    #[serde(skip_deserializing)]
+    pub recorded_by: Option<String>,
+    #[serde(skip_deserializing)]
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MissingConsent {
+    pub epf_number: String,
+    pub name_with_initials: String,
+    pub department: Option<String>,
+    pub missing_types: Vec<String>,
 }
 
+// A medical fitness examination (medical_commands::FITNESS_RESULTS)
 #[derive(Debug, Serialize, Deserialize)]
-pub struct EmployeeFilters {
+pub struct FitnessCertificate {
+    #[serde(default)]
+    pub id: i64,
     pub epf_number: String,
-    pub department: String,
-    pub transport_route: String,
-    pub working_status: String,
+    pub examined_on: String,
+    #[serde(default)]
+    pub valid_until: Option<String>,     // A year from examined_on when not given
+    pub result: String,                  // fit, fit_with_restrictions, unfit
+    #[serde(default)]
+    pub restrictions: Option<String>,
+    #[serde(default)]
+    pub examined_by: Option<String>,     // Doctor or clinic
+    #[serde(default)]
+    pub document_path: Option<String>,   // Link or path to the certificate
+    #[serde(skip_deserializing)]
+    pub recorded_by: Option<String>,
+    #[serde(skip_deserializing)]
+    pub created_at: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
-pub struct DashboardStats {
-    pub total_employees: i32,
-    pub active_employees: i32,
-    pub resigned_employees: i32,
-    pub departments: Vec<DepartmentCount>,
-    pub caders: Vec<DepartmentCount>,
-    pub allocations: Vec<DepartmentCount>,
-    pub recent_joinings: i32,
-    pub recent_resignations: i32,
+pub struct EmployeeMedical {
+    pub epf_number: String,
+    pub blood_group: Option<String>,
+    pub allergies: Option<String>,
+    pub updated_by: Option<String>,
+    pub updated_at: Option<String>,
+    pub certificates: Vec<FitnessCertificate>,
+}
+
+// An employee who needs a medical fitness certificate renewed or obtained
+#[derive(Debug, Serialize)]
+pub struct FitnessDue {
+    pub epf_number: String,
+    pub name_with_initials: String,
+    pub department: Option<String>,
+    pub designation: Option<String>,
+    pub valid_until: Option<String>,     // Latest certificate, if any
+    pub result: Option<String>,
+    pub days_remaining: Option<i64>,     // Negative once expired
+    pub status: String,                  // missing, unfit, expired, expiring
+}
+
+// A driving licence or machine operator certificate
+// (license_commands::LICENSE_TYPES)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmployeeLicense {
+    #[serde(default)]
+    pub id: i64,
+    pub epf_number: String,
+    #[serde(skip_deserializing)]
+    pub name_with_initials: String,
+    #[serde(skip_deserializing)]
+    pub department: Option<String>,
+    pub license_type: String,            // driving, heavy_vehicle, forklift, crane, boiler, other
+    pub license_number: String,
+    #[serde(default)]
+    pub issued_on: Option<String>,
+    pub expires_on: String,
+    #[serde(default)]
+    pub document_path: Option<String>,   // Link or path to a scan of the licence
+    #[serde(skip_deserializing)]
+    pub recorded_by: Option<String>,
+    #[serde(skip_deserializing)]
+    pub created_at: Option<String>,
+    #[serde(skip_deserializing)]
+    pub days_remaining: Option<i64>,     // Negative once expired
+}
+
+#[derive(Debug, Serialize)]
+pub struct HealthCheck {
+    pub name: String,                    // database, schema_version, migrations, employee_dates, disk_space, write_access, backup
+    pub status: String,                  // ok, warning, error
+    pub message: String,
+}
+
+// Shown on the login screen; users may only continue when can_proceed is set
+#[derive(Debug, Serialize)]
+pub struct HealthReport {
+    pub checks: Vec<HealthCheck>,
+    pub can_proceed: bool,
+}
+
+// A database copy in the backups folder; id is its file name
+#[derive(Debug, Serialize)]
+pub struct BackupInfo {
+    pub id: String,
+    pub created_at: Option<String>,
+    pub size_bytes: u64,
+    pub size_display: String,
+    pub employee_count: Option<i64>,     // None when the file cannot be read as an HRM database
+    pub verified: Option<bool>,          // None when the file was never verified
+    pub verified_at: Option<String>,
+    pub verification_details: Option<String>,
+}
+
+// Offsite backup target as shown in settings; secrets are never sent back,
+// only whether one is saved
+#[derive(Debug, Serialize)]
+pub struct CloudBackupConfig {
+    pub enabled: bool,
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub prefix: String,
+    pub access_key_id: String,
+    pub has_secret_access_key: bool,
+    pub has_passphrase: bool,
+    pub last_upload_at: Option<String>,
+    pub last_uploaded_backup: Option<String>,
+    pub last_error: Option<String>,
+}
+
+// Secret fields left empty keep the saved value
+#[derive(Debug, Deserialize)]
+pub struct CloudBackupSettings {
+    pub enabled: bool,
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub prefix: String,
+    pub access_key_id: String,
+    pub secret_access_key: Option<String>,
+    pub passphrase: Option<String>,
+}
+
+// One page of a dashboard drill-down list
+#[derive(Debug, Serialize)]
+pub struct EmployeePage {
+    pub employees: Vec<Employee>,
+    pub total_count: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MonthlyMovement {
+    pub month: String,                   // YYYY-MM
+    pub joined: i32,
+    pub resigned: i32,
 }
 
 #[derive(Debug, Serialize)]
@@ -74,6 +1208,33 @@ pub struct UserSession {
     pub role: String,
     pub department_access: Option<String>,
     pub permissions: UserPermissions,
+    #[serde(default)]
+    pub session_id: Option<i64>,  // Row in the sessions table, used for revocation
+}
+
+impl UserSession {
+    // department_access is a comma-separated list; null or empty means every department
+    pub fn can_access_department(&self, department: Option<&str>) -> bool {
+        if self.permissions.can_view_all_departments {
+            return true;
+        }
+        match self.department_access.as_deref().map(str::trim) {
+            None | Some("") => true,
+            Some(allowed) => department
+                .map(|d| allowed.split(',').any(|a| a.trim().eq_ignore_ascii_case(d.trim())))
+                .unwrap_or(false),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionInfo {
+    pub id: i64,
+    pub user_id: i32,
+    pub username: String,
+    pub device_name: Option<String>,
+    pub created_at: Option<String>,
+    pub last_seen_at: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -89,6 +1250,22 @@ pub struct UserPermissions {
     pub can_manage_settings: bool,
     pub can_backup_database: bool,
     pub can_view_audit_logs: bool,
+    #[serde(default)]
+    pub can_login_anytime: bool,  // Bypasses per-user allowed login hours
+    #[serde(default)]
+    pub can_view_sensitive_data: bool,  // Personal details such as DOB, address and phone numbers
+    #[serde(default)]
+    pub can_manage_disciplinary: bool,  // Disciplinary records are restricted to HR
+    #[serde(default)]
+    pub can_approve_attendance: bool,  // Approves manual punch corrections and overtime before payroll uses them
+    #[serde(default)]
+    pub can_manage_attendance: bool,  // Requests punch corrections, leave and overtime
+    #[serde(default)]
+    pub can_approve_leave: bool,  // Approves leave requests; records and cancels leave
+    #[serde(default)]
+    pub can_run_payroll: bool,  // Calculates, locks and reverses payroll runs; approves festival advances
+    #[serde(default)]
+    pub can_view_salaries: bool,  // Salary revisions, payroll lines and pay costs
 }
 
 impl Default for UserPermissions {
@@ -105,6 +1282,14 @@ impl Default for UserPermissions {
             can_manage_settings: false,
             can_backup_database: false,
             can_view_audit_logs: false,
+            can_login_anytime: false,
+            can_view_sensitive_data: false,
+            can_manage_disciplinary: false,
+            can_approve_attendance: false,
+            can_manage_attendance: false,
+            can_approve_leave: false,
+            can_run_payroll: false,
+            can_view_salaries: false,
         }
     }
 }
@@ -123,6 +1308,14 @@ impl UserPermissions {
             can_manage_settings: true,
             can_backup_database: true,
             can_view_audit_logs: true,
+            can_login_anytime: true,
+            can_view_sensitive_data: true,
+            can_manage_disciplinary: true,
+            can_approve_attendance: true,
+            can_manage_attendance: true,
+            can_approve_leave: true,
+            can_run_payroll: true,
+            can_view_salaries: true,
         }
     }
 
@@ -139,6 +1332,14 @@ impl UserPermissions {
             can_manage_settings: false,
             can_backup_database: false,
             can_view_audit_logs: false,
+            can_login_anytime: true,
+            can_view_sensitive_data: true,
+            can_manage_disciplinary: true,
+            can_approve_attendance: true,
+            can_manage_attendance: true,
+            can_approve_leave: true,
+            can_run_payroll: true,
+            can_view_salaries: true,
         }
     }
 
@@ -155,6 +1356,14 @@ impl UserPermissions {
             can_manage_settings: false,
             can_backup_database: false,
             can_view_audit_logs: false,
+            can_login_anytime: false,
+            can_view_sensitive_data: false,
+            can_manage_disciplinary: false,
+            can_approve_attendance: false,
+            can_manage_attendance: true,
+            can_approve_leave: false,
+            can_run_payroll: false,
+            can_view_salaries: false,
         }
     }
 
@@ -171,6 +1380,14 @@ impl UserPermissions {
             can_manage_settings: false,
             can_backup_database: false,
             can_view_audit_logs: false,
+            can_login_anytime: false,
+            can_view_sensitive_data: false,
+            can_manage_disciplinary: false,
+            can_approve_attendance: false,
+            can_manage_attendance: false,
+            can_approve_leave: false,
+            can_run_payroll: false,
+            can_view_salaries: false,
         }
     }
 
@@ -193,6 +1410,8 @@ pub struct CreateUserRequest {
     pub role: String,
     pub department_access: Option<String>,
     pub permissions: Option<UserPermissions>,
+    #[serde(default)]
+    pub login_hours: Option<LoginHours>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -203,6 +1422,25 @@ pub struct UpdateUserRequest {
     pub department_access: Option<String>,
     pub is_active: bool,
     pub permissions: Option<UserPermissions>,
+    #[serde(default, deserialize_with = "present")]
+    pub login_hours: Option<Option<LoginHours>>,  // Omitted keeps the stored hours; null clears them
+}
+
+// Tells a field sent as null (Some(None)) apart from one left out (None)
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::deserialize(deserializer).map(Some)
+}
+
+// Allowed login window for a user; users without one can log in at any time
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LoginHours {
+    pub start_time: String,    // "HH:MM" local time, e.g. "07:30"
+    pub end_time: String,      // "HH:MM" local time; earlier than start_time means the window crosses midnight
+    pub days: Option<String>,  // comma-separated weekdays (0=Sunday .. 6=Saturday), null means every day
 }
 
 #[derive(Debug, Deserialize)]
@@ -220,8 +1458,115 @@ pub struct UserInfo {
     pub department_access: Option<String>,
     pub is_active: bool,
     pub permissions: Option<UserPermissions>,
+    pub login_hours: Option<LoginHours>,
     pub created_at: Option<String>,
     pub last_login: Option<String>,
+    pub deleted_at: Option<String>,  // Set while the account is in the recycle bin
+}
+
+// One field of a user's access that differs between two dates
+#[derive(Debug, Serialize)]
+pub struct PermissionChange {
+    pub user_id: i32,
+    pub username: String,
+    pub field: String,                   // Permission column or role, department_access, is_active, deleted
+    pub label: String,
+    pub before: Option<String>,          // None when the user had no recorded access yet
+    pub after: Option<String>,
+    pub changed_by: Option<String>,      // Who last changed the user's access in the period
+}
+
+// A workflow item going through approval (approvals::APPROVAL_CHAINS)
+#[derive(Debug, Serialize)]
+pub struct ApprovalRequest {
+    pub id: i64,
+    pub request_type: String,            // attendance_correction, festival_advance
+    pub label: String,
+    pub entity_id: String,               // ID in the workflow's own table
+    pub epf_number: Option<String>,
+    pub employee_name: Option<String>,
+    pub department: Option<String>,
+    pub summary: String,
+    pub requested_by: Option<i32>,
+    pub requested_by_name: Option<String>,
+    pub status: String,                  // pending, approved, rejected
+    pub current_step: i64,
+    pub created_at: Option<String>,
+    pub decided_at: Option<String>,
+    pub steps: Vec<ApprovalStep>,        // Filled by get_approval only
+    pub comments: Vec<ApprovalComment>,  // Filled by get_approval only
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApprovalStep {
+    pub step_number: i64,
+    pub approver_permission: String,     // Permission the approver of this step needs
+    pub status: String,                  // pending, approved, rejected, skipped
+    pub decided_by: Option<i32>,
+    pub decided_by_name: Option<String>,
+    pub decided_at: Option<String>,
+    pub comment: Option<String>,
+    pub on_behalf_of: Option<i32>,       // Absent approver, when decided through a delegation
+    pub on_behalf_of_name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApprovalComment {
+    pub id: i64,
+    pub user_id: Option<i32>,
+    pub username: String,
+    pub comment: String,
+    pub created_at: Option<String>,
+}
+
+// An approver's approvals handed to someone else for a period
+#[derive(Debug, Serialize)]
+pub struct ApprovalDelegation {
+    pub id: i64,
+    pub delegator_id: i32,
+    pub delegator_name: String,
+    pub delegate_id: i32,
+    pub delegate_name: String,
+    pub starts_on: String,
+    pub ends_on: String,                 // Inclusive
+    pub reason: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: Option<String>,
+    pub revoked_at: Option<String>,
+}
+
+// A piece of follow-up work assigned to an HR user
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Task {
+    #[serde(default)]
+    pub id: i64,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub assigned_to: Option<i32>,        // The creator when not given
+    #[serde(skip_deserializing)]
+    pub assigned_to_name: Option<String>,
+    #[serde(skip_deserializing)]
+    pub created_by: Option<i32>,
+    #[serde(skip_deserializing)]
+    pub created_by_name: Option<String>,
+    #[serde(default)]
+    pub due_date: Option<String>,
+    #[serde(default)]
+    pub entity_type: Option<String>,     // Linked record, e.g. EMPLOYEE
+    #[serde(default)]
+    pub entity_id: Option<String>,       // e.g. EPF number
+    #[serde(skip_deserializing)]
+    pub status: String,                  // open, done, cancelled
+    #[serde(skip_deserializing)]
+    pub completed_at: Option<String>,
+    #[serde(skip_deserializing)]
+    pub completion_note: Option<String>,
+    #[serde(skip_deserializing)]
+    pub created_at: Option<String>,
+    #[serde(skip_deserializing)]
+    pub is_overdue: bool,
 }
 
 // Audit Log Models
@@ -236,6 +1581,8 @@ pub struct AuditLog {
     pub old_value: Option<String>,// JSON of old values for UPDATE/DELETE
     pub new_value: Option<String>,// JSON of new values for CREATE/UPDATE
     pub details: Option<String>,  // Additional context
+    #[serde(default)]
+    pub severity: Option<String>, // info, high
     pub created_at: Option<String>,
 }
 
@@ -246,6 +1593,8 @@ pub struct AuditLogFilters {
     pub entity_type: String,
     pub start_date: String,
     pub end_date: String,
+    #[serde(default)]
+    pub severity: String,
     pub limit: i32,
     pub offset: i32,
 }
@@ -255,3 +1604,438 @@ pub struct AuditLogResult {
     pub logs: Vec<AuditLog>,
     pub total_count: i32,
 }
+
+// Attendance Device Models
+#[derive(Debug, Serialize)]
+pub struct AttendanceDevice {
+    pub id: i64,
+    pub serial_number: String,
+    pub name: Option<String>,
+    pub location: Option<String>,
+    pub is_active: bool,
+    pub ip_address: Option<String>,
+    pub last_seen_at: Option<String>,
+    pub status: String,                  // online, offline, pending, disabled
+    pub punches_today: i64,
+}
+
+// Attendance Correction Models
+#[derive(Debug, Serialize)]
+pub struct AttendancePunch {
+    pub id: i64,
+    pub epf_number: String,
+    pub punch_time: String,
+    pub punch_state: i32,
+    pub verify_mode: Option<i32>,
+    pub device_serial: Option<String>,
+    pub source: String,                  // device, manual, corrected
+    pub original_punch_time: Option<String>,
+    pub correction_id: Option<i64>,
+    pub has_pending_correction: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AttendanceCorrection {
+    pub id: i64,
+    pub epf_number: String,
+    pub employee_name: Option<String>,
+    pub punch_id: Option<i64>,           // None when adding a missing punch
+    pub original_time: Option<String>,
+    pub original_state: Option<i32>,
+    pub new_time: String,
+    pub new_state: i32,
+    pub reason: String,
+    pub status: String,                  // pending, approved, rejected
+    pub requested_by: Option<i32>,
+    pub requested_by_name: Option<String>,
+    pub requested_at: Option<String>,
+    pub reviewed_by: Option<i32>,
+    pub reviewed_by_name: Option<String>,
+    pub reviewed_at: Option<String>,
+    pub review_note: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OvertimeRequest {
+    pub id: i64,
+    pub epf_number: String,
+    pub employee_name: Option<String>,
+    pub work_date: String,
+    pub hours: f64,
+    pub reason: String,
+    pub status: String,                  // pending, approved, rejected
+    pub requested_by: Option<i32>,
+    pub requested_by_name: Option<String>,
+    pub requested_at: Option<String>,
+    pub reviewed_by: Option<i32>,
+    pub reviewed_by_name: Option<String>,
+    pub reviewed_at: Option<String>,
+    pub review_note: Option<String>,
+}
+
+// One employee's attendance for a month, as sent to payroll
+#[derive(Debug, Serialize)]
+pub struct AttendanceSummary {
+    pub epf_number: String,
+    pub name_with_initials: String,
+    pub full_name: String,
+    pub department: Option<String>,
+    pub designation: Option<String>,
+    pub allocation: Option<String>,
+    pub month: String,                   // YYYY-MM
+    pub working_days: i64,               // Rostered days while employed
+    pub worked_days: i64,
+    pub ot_hours: f64,
+    pub leave_days: f64,                 // Paid leave on rostered days
+    pub annual_leave: f64,
+    pub casual_leave: f64,
+    pub medical_leave: f64,
+    pub maternity_leave: f64,
+    pub no_pay_leave: f64,
+    pub no_pay_days: f64,                // Rostered days neither worked nor on paid leave
+}
+
+// Night Work Compliance Models
+#[derive(Debug, Serialize)]
+pub struct NightWorkRecord {
+    pub epf_number: String,
+    pub name_with_initials: String,
+    pub full_name: String,
+    pub nic: Option<String>,
+    pub department: Option<String>,
+    pub designation: Option<String>,
+    pub police_area: Option<String>,
+    pub transport_route: Option<String>,
+    pub mobile_1: Option<String>,
+    pub night_shifts: i64,
+    pub first_night: String,
+    pub last_night: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NightWorkReport {
+    pub from_date: String,
+    pub to_date: String,
+    pub employees: Vec<NightWorkRecord>,  // Women according to their NIC
+    pub unverified: Vec<NightWorkRecord>, // NIC missing or unreadable
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkingHoursBreach {
+    pub epf_number: String,
+    pub name_with_initials: String,
+    pub department: Option<String>,
+    pub rule: String,                    // weekly_hours, consecutive_days
+    pub period_start: String,
+    pub period_end: String,
+    pub value: f64,                      // Hours worked or days in a row
+    pub limit: i64,
+}
+
+// Account an employee's salary is credited to
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmployeeBankAccount {
+    pub epf_number: String,
+    pub bank_code: String,               // 4 digit SLIPS bank code, e.g. 7010
+    pub branch_code: String,             // 3 digits
+    pub account_number: String,
+    pub account_name: String,
+    #[serde(default)]
+    pub updated_by: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+}
+
+// Payroll Models
+#[derive(Debug, Serialize)]
+pub struct PayrollDeduction {
+    pub deduction_type: String,
+    pub amount: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PayrollLine {
+    pub epf_number: String,
+    pub name_with_initials: String,
+    pub department: Option<String>,
+    pub allocation: Option<String>,
+    pub basic_salary: f64,               // In rupees, converted if paid in another currency
+    pub pay_currency: String,
+    pub exchange_rate: f64,              // Rupees per unit of pay_currency; 1 for LKR
+    pub working_days: i64,
+    pub worked_days: i64,
+    pub no_pay_days: f64,
+    pub no_pay_deduction: f64,
+    pub ot_hours: f64,
+    pub ot_pay: f64,
+    pub incentive_pay: f64,              // Piece-rate production earnings
+    pub gross_pay: f64,                  // Basic less no-pay, plus OT and incentives
+    pub epf_employee: f64,
+    pub epf_employer: f64,
+    pub etf_employer: f64,
+    pub other_deductions: f64,
+    pub net_pay: f64,
+    pub deductions: Vec<PayrollDeduction>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PayrollRun {
+    pub id: Option<i64>,                 // None for a preview
+    pub month: String,                   // YYYY-MM
+    pub status: String,                  // preview, locked, reversed
+    pub employee_count: i64,
+    pub total_gross: f64,
+    pub total_net: f64,
+    pub total_epf_employee: f64,
+    pub total_epf_employer: f64,
+    pub total_etf: f64,
+    pub locked_by: Option<String>,
+    pub locked_at: Option<String>,
+    pub reversed_by: Option<String>,
+    pub reversed_at: Option<String>,
+    pub reversal_reason: Option<String>,
+    pub lines: Vec<PayrollLine>,
+    pub skipped: Vec<String>,            // EPF numbers with no salary on record
+}
+
+// Changes to try in a payroll simulation. Anything left out is taken from
+// the settings and salary revisions on record.
+#[derive(Debug, Default, Deserialize)]
+pub struct PayrollScenario {
+    pub ot_rate_percent: Option<i64>,
+    pub no_pay_divisor: Option<i64>,
+    pub salary_increase_percent: Option<f64>, // Applied to every salary on record
+    #[serde(default)]
+    pub salaries: HashMap<String, f64>,  // Proposed basic salary by EPF number, in the pay currency
+}
+
+#[derive(Debug, Serialize)]
+pub struct PayrollSimulationLine {
+    pub epf_number: String,
+    pub name_with_initials: String,
+    pub department: Option<String>,
+    pub baseline_basic: Option<f64>,     // None when the employee is not paid in that run
+    pub simulated_basic: Option<f64>,
+    pub baseline_net: Option<f64>,
+    pub simulated_net: Option<f64>,
+    pub baseline_cost: f64,              // Gross plus employer EPF and ETF
+    pub simulated_cost: f64,
+    pub cost_change: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PayrollSimulation {
+    pub month: String,
+    pub baseline: PayrollRun,            // Totals only; lines are compared below
+    pub simulated: PayrollRun,
+    pub baseline_cost: f64,
+    pub simulated_cost: f64,
+    pub cost_change: f64,
+    pub lines: Vec<PayrollSimulationLine>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PayrollVarianceLine {
+    pub epf_number: String,
+    pub name_with_initials: String,
+    pub department: Option<String>,
+    pub status: String,                  // new, removed, changed, unchanged
+    pub previous_net_pay: Option<f64>,
+    pub current_net_pay: Option<f64>,
+    pub net_pay_change: f64,
+    pub net_pay_change_percent: Option<f64>, // None when there was no previous pay
+    pub previous_ot_hours: f64,
+    pub current_ot_hours: f64,
+    pub ot_hours_change: f64,
+    pub flagged: bool,                   // Beyond a threshold, joined or left
+}
+
+#[derive(Debug, Serialize)]
+pub struct PayrollVarianceReport {
+    pub month: String,
+    pub previous_month: String,
+    pub status: String,                  // Status of the month's run: preview or locked
+    pub threshold_percent: i64,
+    pub threshold_ot_hours: i64,
+    pub lines: Vec<PayrollVarianceLine>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PayrollCostCenter {
+    pub month: String,
+    pub cost_center: String,             // Allocation or department
+    pub employee_count: i64,
+    pub basic_pay: f64,                  // Basic less no-pay
+    pub ot_pay: f64,
+    pub gross_pay: f64,
+    pub epf_employer: f64,
+    pub etf_employer: f64,
+    pub total_cost: f64,                 // Gross plus employer EPF and ETF
+}
+
+// Exchange Rate Models
+#[derive(Debug, Serialize)]
+pub struct ExchangeRate {
+    pub month: String,                   // YYYY-MM
+    pub currency: String,
+    pub rate: f64,                       // Rupees per unit
+    pub updated_by: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+// Translation Models
+#[derive(Debug, Serialize)]
+pub struct Translation {
+    pub key: String,                     // e.g. company_name, pack.title
+    pub language: String,                // si or ta
+    pub text: String,
+    pub updated_by: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+// Letter Models
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LetterTemplate {
+    #[serde(default)]
+    pub id: Option<i64>,                 // None when creating a template
+    pub name: String,
+    pub category: Option<String>,        // e.g. confirmation, warning, salary certificate
+    pub body: String,                    // Text with {{merge_field}} placeholders
+    #[serde(default)]
+    pub language: Option<String>,        // en, si or ta; None follows the employee's preference
+    pub is_active: bool,
+    #[serde(default)]
+    pub updated_by: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LetterIssue {
+    pub id: i64,
+    pub reference_number: Option<String>,
+    pub template_name: String,
+    pub epf_number: String,
+    pub name_with_initials: Option<String>,
+    pub format: String,                  // html or docx
+    pub issued_by: Option<String>,
+    pub issued_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkLetterResult {
+    pub generated: usize,
+    pub failed: Vec<FieldError>,         // field is the EPF number
+}
+
+// Labour Budget Models
+#[derive(Debug, Serialize)]
+pub struct LabourBudget {
+    pub id: i64,
+    pub department: String,
+    pub month: String,                   // YYYY-MM
+    pub amount: f64,
+    pub updated_by: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LabourBudgetComparison {
+    pub department: String,
+    pub month: String,
+    pub budget: Option<f64>,             // None when no budget was set
+    pub actual: f64,                     // Gross plus employer EPF and ETF
+    pub variance: Option<f64>,           // Actual less budget; positive is over budget
+    pub variance_percent: Option<f64>,
+    pub trend: f64,                      // Average actual over the last three months
+}
+
+// Production Incentive Models
+#[derive(Debug, Serialize)]
+pub struct PieceRate {
+    pub id: i64,
+    pub product_code: String,
+    pub description: Option<String>,
+    pub rate: f64,                       // Paid per unit produced
+    pub is_active: bool,                 // Inactive codes are refused on import
+    pub updated_by: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProductionEntry {
+    pub id: i64,
+    pub month: String,
+    pub epf_number: Option<String>,      // Set for an individual's output
+    pub line: Option<String>,            // Set for a production line's output
+    pub product_code: String,
+    pub quantity: f64,
+    pub entered_by: Option<String>,
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProductionImportReport {
+    pub total_rows: usize,
+    pub imported: usize,
+    pub skipped: Vec<LegacyImportRowError>,
+    pub dry_run: bool,
+}
+
+// Leave Models
+#[derive(Debug, Serialize)]
+pub struct LeaveRecord {
+    pub id: i64,
+    pub epf_number: String,
+    pub leave_date: String,
+    pub leave_type: String,              // annual, casual, medical, maternity, no_pay
+    pub days: f64,                       // 1 or 0.5
+    pub reason: Option<String>,
+    pub recorded_by: Option<String>,
+    pub created_at: Option<String>,
+}
+
+// Leave to record directly or to request through the approval chain
+#[derive(Debug, Deserialize)]
+pub struct LeaveEntry {
+    pub epf_number: String,
+    pub from_date: String,
+    pub to_date: String,
+    pub leave_type: String,
+    #[serde(default)]
+    pub half_day: bool,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LeaveRequest {
+    pub id: i64,
+    pub epf_number: String,
+    pub employee_name: Option<String>,
+    pub from_date: String,
+    pub to_date: String,
+    pub leave_type: String,
+    pub half_day: bool,
+    pub reason: Option<String>,
+    pub status: String,                  // pending, approved, rejected
+    pub requested_by: Option<i32>,
+    pub requested_by_name: Option<String>,
+    pub requested_at: Option<String>,
+    pub reviewed_by: Option<i32>,
+    pub reviewed_by_name: Option<String>,
+    pub reviewed_at: Option<String>,
+    pub review_note: Option<String>,
+}
+
+// Notification Models
+#[derive(Debug, Serialize)]
+pub struct Notification {
+    pub id: i64,
+    pub title: String,
+    pub message: String,
+    pub severity: String,            // info, warning, high
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+    pub is_read: bool,
+    pub created_at: Option<String>,
+}
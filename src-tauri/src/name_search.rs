@@ -0,0 +1,121 @@
+use crate::commands::query_employee;
+use crate::error::HrmError;
+use crate::models::Employee;
+use crate::DbConnection;
+use tauri::State;
+
+// Most results the search box shows
+const MAX_RESULTS: usize = 50;
+
+// Latin letters with diacritics, including the marks used when Sinhala and
+// Tamil names are romanized (ā, ṭ, ṇ, ś ...), folded to plain ASCII
+fn fold_char(c: char) -> char {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => 'a',
+        'ç' | 'ć' | 'č' => 'c',
+        'ď' | 'ḍ' => 'd',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => 'e',
+        'ì' | 'í' | 'î' | 'ï' | 'ī' | 'į' => 'i',
+        'ḷ' | 'ł' => 'l',
+        'ṁ' | 'ṃ' => 'm',
+        'ñ' | 'ń' | 'ň' | 'ṇ' | 'ṅ' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' => 'o',
+        'ṛ' | 'ř' => 'r',
+        'ś' | 'ṣ' | 'š' => 's',
+        'ṭ' | 'ť' => 't',
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' => 'u',
+        'ý' | 'ÿ' => 'y',
+        'ž' | 'ź' | 'ż' => 'z',
+        _ => c,
+    }
+}
+
+// Splits a name into lower-case words with diacritics removed. Initials
+// come out as single letters however they were written: "A.B. Perera",
+// "A. B. Perera" and "AB Perera" all give a, b, perera.
+pub(crate) fn name_tokens(name: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for word in name.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()) {
+        let is_initials = word.chars().count() <= 3 && word.chars().all(|c| c.is_ascii_uppercase());
+        let folded: String = word.chars().flat_map(char::to_lowercase).map(fold_char).collect();
+        if is_initials {
+            tokens.extend(folded.chars().map(String::from));
+        } else {
+            tokens.push(folded);
+        }
+    }
+    tokens
+}
+
+// Consonant outline of a romanized word so spelling variants compare equal:
+// Perera / Pereira, Jayawardena / Jayawardene, Wickramasinghe /
+// Wickremesinghe, Mohamed / Muhammad, Thilak / Tilak. Aspirated and doubled
+// letters are merged and vowels after the first letter dropped.
+pub(crate) fn name_skeleton(word: &str) -> String {
+    let mut word = word.to_string();
+    for (variant, plain) in [("th", "t"), ("dh", "d"), ("bh", "b"), ("kh", "k"), ("gh", "g"), ("ck", "k"), ("w", "v"), ("z", "s")] {
+        word = word.replace(variant, plain);
+    }
+    let mut skeleton = String::new();
+    for (index, c) in word.chars().enumerate() {
+        if index > 0 && "aeiouy".contains(c) {
+            continue;
+        }
+        if !skeleton.ends_with(c) {
+            skeleton.push(c);
+        }
+    }
+    skeleton
+}
+
+// True when every word of the query matches a word of the name. A single
+// letter matches an initial or the start of a word; longer words match on
+// their skeleton, so partly typed names are found as well.
+pub(crate) fn name_matches(query: &[String], name: &[String]) -> bool {
+    let name_skeletons: Vec<String> = name.iter().map(|w| name_skeleton(w)).collect();
+    !query.is_empty()
+        && query.iter().all(|q| {
+            if q.chars().count() == 1 {
+                return name.iter().any(|w| w.starts_with(q.as_str()));
+            }
+            let q_skeleton = name_skeleton(q);
+            name.iter().zip(&name_skeletons).any(|(w, s)| w.starts_with(q.as_str()) || s.starts_with(&q_skeleton))
+        })
+}
+
+// Finds employees by EPF number or by name, tolerating the different ways
+// local names are romanized. Current employees are listed first.
+#[tauri::command]
+pub fn search_employees(query: String, db: State<'_, DbConnection>) -> Result<Vec<Employee>, HrmError> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let query_tokens = name_tokens(query);
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(
+        "SELECT epf_number, name_with_initials, full_name FROM employees
+         ORDER BY working_status = 'resign', epf_number",
+    )?;
+    let candidates = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    let epf_numbers: Vec<String> = candidates
+        .into_iter()
+        .filter(|(epf_number, name_with_initials, full_name)| {
+            epf_number.to_lowercase().contains(&query.to_lowercase())
+                || name_matches(&query_tokens, &name_tokens(name_with_initials))
+                || name_matches(&query_tokens, &name_tokens(full_name))
+        })
+        .map(|(epf_number, _, _)| epf_number)
+        .take(MAX_RESULTS)
+        .collect();
+    
+    let employees = epf_numbers
+        .iter()
+        .map(|epf_number| query_employee(&conn, epf_number))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(employees)
+}
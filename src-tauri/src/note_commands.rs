@@ -0,0 +1,174 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{EmployeeNote, UserSession};
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use tauri::State;
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+fn query_note(conn: &Connection, note_id: i64) -> Result<EmployeeNote, HrmError> {
+    conn.query_row(
+        "SELECT id, epf_number, author_id, author_name, note, is_pinned, created_at, updated_at
+         FROM employee_notes WHERE id = ?1",
+        [note_id],
+        note_from_row,
+    )
+    .map_err(|_| HrmError::not_found("Note not found"))
+}
+
+fn note_from_row(row: &rusqlite::Row) -> rusqlite::Result<EmployeeNote> {
+    Ok(EmployeeNote {
+        id: row.get(0)?,
+        epf_number: row.get(1)?,
+        author_id: row.get(2)?,
+        author_name: row.get(3)?,
+        note: row.get(4)?,
+        is_pinned: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+// Only the author or a user manager may change a note
+fn check_can_modify(user: &UserSession, note: &EmployeeNote) -> Result<(), HrmError> {
+    if note.author_id == Some(user.user_id) || user.permissions.can_manage_users {
+        Ok(())
+    } else {
+        Err(HrmError::permission_denied("Permission denied. Only the author can change this note."))
+    }
+}
+
+// Notes for an employee, pinned first and then newest first
+#[tauri::command]
+pub fn get_employee_notes(
+    epf_number: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<EmployeeNote>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_employees {
+        return Err(HrmError::permission_denied("Permission denied"));
+    }
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, epf_number, author_id, author_name, note, is_pinned, created_at, updated_at
+             FROM employee_notes WHERE epf_number = ?1
+             ORDER BY is_pinned DESC, created_at DESC, id DESC",
+        )?;
+    let notes = stmt
+        .query_map([&epf_number], note_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    Ok(notes)
+}
+
+#[tauri::command]
+pub fn add_employee_note(
+    epf_number: String,
+    note: String,
+    is_pinned: bool,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit employees."));
+    }
+    if note.trim().is_empty() {
+        return Err(HrmError::validation("Note cannot be empty"));
+    }
+    
+    let conn = db.0.lock()?;
+    conn.execute(
+        "INSERT INTO employee_notes (epf_number, author_id, author_name, note, is_pinned)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![epf_number, user.user_id, user.full_name, note.trim(), is_pinned],
+    )?;
+    let note_id = conn.last_insert_rowid();
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "EMPLOYEE_NOTE",
+        Some(&note_id.to_string()),
+        None,
+        Some(note.trim()),
+        Some(&format!("Added note to employee {}", epf_number)),
+    );
+    
+    Ok(note_id)
+}
+
+#[tauri::command]
+pub fn update_employee_note(
+    note_id: i64,
+    note: String,
+    is_pinned: bool,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if note.trim().is_empty() {
+        return Err(HrmError::validation("Note cannot be empty"));
+    }
+    
+    let conn = db.0.lock()?;
+    let existing = query_note(&conn, note_id)?;
+    check_can_modify(&user, &existing)?;
+    
+    conn.execute(
+        "UPDATE employee_notes SET note = ?1, is_pinned = ?2, updated_at = CURRENT_TIMESTAMP WHERE id = ?3",
+        rusqlite::params![note.trim(), is_pinned, note_id],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "EMPLOYEE_NOTE",
+        Some(&note_id.to_string()),
+        Some(&existing.note),
+        Some(note.trim()),
+        Some(&format!("Edited note on employee {}", existing.epf_number)),
+    );
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_employee_note(
+    note_id: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let existing = query_note(&conn, note_id)?;
+    check_can_modify(&user, &existing)?;
+    
+    conn.execute("DELETE FROM employee_notes WHERE id = ?1", [note_id])?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "DELETE",
+        "EMPLOYEE_NOTE",
+        Some(&note_id.to_string()),
+        Some(&existing.note),
+        None,
+        Some(&format!("Deleted note on employee {}", existing.epf_number)),
+    );
+    
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use crate::error::HrmError;
+use crate::models::Notification;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use tauri::State;
+
+// Creates a notification for a single user
+pub fn notify_user(
+    conn: &Connection,
+    user_id: i32,
+    title: &str,
+    message: &str,
+    severity: &str,
+    entity_type: Option<&str>,
+    entity_id: Option<&str>,
+) {
+    let _ = conn.execute(
+        "INSERT INTO notifications (user_id, title, message, severity, entity_type, entity_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![user_id, title, message, severity, entity_type, entity_id],
+    );
+}
+
+// Creates a notification for every active user who can manage users
+pub fn notify_admins(
+    conn: &Connection,
+    title: &str,
+    message: &str,
+    severity: &str,
+    entity_type: Option<&str>,
+    entity_id: Option<&str>,
+) {
+    let _ = conn.execute(
+        "INSERT INTO notifications (user_id, title, message, severity, entity_type, entity_id)
+         SELECT id, ?1, ?2, ?3, ?4, ?5 FROM users WHERE can_manage_users = 1 AND is_active = 1",
+        rusqlite::params![title, message, severity, entity_type, entity_id],
+    );
+}
+
+// Creates a notification for every active HR manager and admin
+pub fn notify_hr_managers(
+    conn: &Connection,
+    title: &str,
+    message: &str,
+    severity: &str,
+    entity_type: Option<&str>,
+    entity_id: Option<&str>,
+) {
+    let _ = conn.execute(
+        "INSERT INTO notifications (user_id, title, message, severity, entity_type, entity_id)
+         SELECT id, ?1, ?2, ?3, ?4, ?5 FROM users
+         WHERE is_active = 1 AND (role = 'hr_manager' OR can_manage_users = 1)",
+        rusqlite::params![title, message, severity, entity_type, entity_id],
+    );
+}
+
+fn current_user_id(current_user: &State<'_, CurrentUser>) -> Result<i32, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) => Ok(session.user_id),
+        None => Err(HrmError::not_logged_in()),
+    }
+}
+
+#[tauri::command]
+pub fn get_my_notifications(
+    unread_only: bool,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<Notification>, HrmError> {
+    let user_id = current_user_id(&current_user)?;
+    let conn = db.0.lock()?;
+    
+    let mut sql = String::from(
+        "SELECT id, title, message, severity, entity_type, entity_id, is_read, created_at
+         FROM notifications WHERE user_id = ?1",
+    );
+    if unread_only {
+        sql.push_str(" AND is_read = 0");
+    }
+    sql.push_str(" ORDER BY created_at DESC, id DESC LIMIT 200");
+    
+    let mut stmt = conn.prepare(&sql)?;
+    
+    let notifications = stmt
+        .query_map([&user_id], |row| {
+            Ok(Notification {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                message: row.get(2)?,
+                severity: row.get(3)?,
+                entity_type: row.get(4)?,
+                entity_id: row.get(5)?,
+                is_read: row.get(6)?,
+                created_at: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    Ok(notifications)
+}
+
+#[tauri::command]
+pub fn mark_notification_read(
+    notification_id: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user_id = current_user_id(&current_user)?;
+    let conn = db.0.lock()?;
+    
+    conn.execute(
+        "UPDATE notifications SET is_read = 1 WHERE id = ?1 AND user_id = ?2",
+        rusqlite::params![notification_id, user_id],
+    )?;
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn mark_all_notifications_read(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user_id = current_user_id(&current_user)?;
+    let conn = db.0.lock()?;
+    
+    conn.execute(
+        "UPDATE notifications SET is_read = 1 WHERE user_id = ?1 AND is_read = 0",
+        [&user_id],
+    )?;
+    
+    Ok(())
+}
@@ -0,0 +1,212 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{OrgNode, PendingReassignment, UserSession};
+use crate::notification_commands::notify_admins;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::HashMap;
+use tauri::State;
+
+const ACTIVE: &str = "working_status IN (SELECT code FROM working_statuses WHERE counts_as_active = 1)";
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+// Active employees who report directly to `supervisor`
+fn active_reports(conn: &Connection, supervisor: &str) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT epf_number FROM employees WHERE reports_to = ?1 AND {} ORDER BY epf_number",
+        ACTIVE
+    ))?;
+    let reports = stmt.query_map([supervisor], |row| row.get(0))?.collect::<Result<Vec<_>, _>>()?;
+    Ok(reports)
+}
+
+// Called when a supervisor leaves: admins are asked to move their team to a
+// new supervisor. Nothing happens if they had no active reports.
+pub(crate) fn prompt_reassignment(conn: &Connection, supervisor: &str) -> rusqlite::Result<()> {
+    let reports = active_reports(conn, supervisor)?;
+    if !reports.is_empty() {
+        notify_admins(
+            conn,
+            "Reporting line needs reassignment",
+            &format!(
+                "Employee {} is no longer active but {} employee(s) still report to them: {}",
+                supervisor,
+                reports.len(),
+                reports.join(", ")
+            ),
+            "warning",
+            Some("EMPLOYEE"),
+            Some(supervisor),
+        );
+    }
+    Ok(())
+}
+
+// Sets or clears an employee's supervisor. Rejects a supervisor who already
+// reports (directly or through others) to the employee.
+#[tauri::command]
+pub fn set_reports_to(
+    epf_number: String,
+    supervisor_epf: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit employees."));
+    }
+    let supervisor_epf = supervisor_epf.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    
+    let conn = db.0.lock()?;
+    let previous: Option<String> = conn
+        .query_row("SELECT reports_to FROM employees WHERE epf_number = ?1", [&epf_number], |row| row.get(0))
+        .map_err(|_| HrmError::not_found(format!("Employee {} not found", epf_number)))?;
+    
+    if let Some(supervisor) = &supervisor_epf {
+        if *supervisor == epf_number {
+            return Err(HrmError::validation("An employee cannot report to themselves"));
+        }
+        let supervisor_active: bool = conn
+            .query_row(
+                &format!("SELECT {} FROM employees WHERE epf_number = ?1", ACTIVE),
+                [supervisor],
+                |row| row.get(0),
+            )
+            .map_err(|_| HrmError::not_found(format!("Supervisor {} not found", supervisor)))?;
+        if !supervisor_active {
+            return Err(HrmError::validation(format!("Supervisor {} is not an active employee", supervisor)));
+        }
+        
+        // Walk up from the new supervisor; meeting the employee means a cycle
+        let cycle: Option<String> = conn
+            .query_row(
+                "WITH RECURSIVE chain(epf_number, depth) AS (
+                     SELECT ?1, 0
+                     UNION
+                     SELECT e.reports_to, c.depth + 1 FROM employees e JOIN chain c ON e.epf_number = c.epf_number
+                     WHERE e.reports_to IS NOT NULL AND c.depth < 100
+                 )
+                 SELECT epf_number FROM chain WHERE epf_number = ?2 LIMIT 1",
+                [supervisor, &epf_number],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if cycle.is_some() {
+            return Err(HrmError::validation(format!(
+                "{} already reports to {} through the reporting line, so this would create a loop",
+                supervisor, epf_number
+            )));
+        }
+    }
+    
+    conn.execute(
+        "UPDATE employees SET reports_to = ?1 WHERE epf_number = ?2",
+        rusqlite::params![supervisor_epf, epf_number],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "EMPLOYEE",
+        Some(&epf_number),
+        previous.as_deref(),
+        supervisor_epf.as_deref(),
+        Some(&match &supervisor_epf {
+            Some(supervisor) => format!("Employee {} now reports to {}", epf_number, supervisor),
+            None => format!("Employee {} no longer has a supervisor", epf_number),
+        }),
+    );
+    
+    Ok(())
+}
+
+// The reporting tree of active employees under `root`, or every tree when no
+// root is given. Employees whose supervisor has left appear as roots.
+#[tauri::command]
+pub fn get_org_tree(root: Option<String>, db: State<'_, DbConnection>) -> Result<Vec<OrgNode>, HrmError> {
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT e.epf_number, e.name_with_initials, e.designation, e.department, s.epf_number
+             FROM employees e
+             LEFT JOIN employees s ON s.epf_number = e.reports_to AND s.{}
+             WHERE e.{}
+             ORDER BY e.name_with_initials",
+            ACTIVE, ACTIVE
+        ))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                OrgNode {
+                    epf_number: row.get(0)?,
+                    name_with_initials: row.get(1)?,
+                    designation: row.get(2)?,
+                    department: row.get(3)?,
+                    reports: Vec::new(),
+                },
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    let mut nodes: HashMap<String, OrgNode> = HashMap::new();
+    let mut children: HashMap<String, Vec<String>> = HashMap::new();
+    let mut top_level = Vec::new();
+    for (node, supervisor) in rows {
+        match supervisor {
+            Some(supervisor) => children.entry(supervisor).or_default().push(node.epf_number.clone()),
+            None => top_level.push(node.epf_number.clone()),
+        }
+        nodes.insert(node.epf_number.clone(), node);
+    }
+    
+    // Each node is taken out of the map as it is placed, so a loop in old
+    // data cannot recurse forever
+    fn build(epf_number: &str, nodes: &mut HashMap<String, OrgNode>, children: &HashMap<String, Vec<String>>) -> Option<OrgNode> {
+        let mut node = nodes.remove(epf_number)?;
+        node.reports = children
+            .get(epf_number)
+            .map(|reports| reports.iter().filter_map(|r| build(r, nodes, children)).collect())
+            .unwrap_or_default();
+        Some(node)
+    }
+    
+    match root {
+        Some(epf) => {
+            let node = build(&epf, &mut nodes, &children).ok_or_else(|| HrmError::not_found(format!("Active employee {} not found", epf)))?;
+            Ok(vec![node])
+        }
+        None => Ok(top_level.iter().filter_map(|epf| build(epf, &mut nodes, &children)).collect()),
+    }
+}
+
+// Active employees whose supervisor is no longer active, with that supervisor
+#[tauri::command]
+pub fn get_pending_reassignments(db: State<'_, DbConnection>) -> Result<Vec<PendingReassignment>, HrmError> {
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT e.epf_number, e.name_with_initials, s.epf_number, s.name_with_initials FROM employees e
+             JOIN employees s ON s.epf_number = e.reports_to
+             WHERE e.{} AND NOT s.{}
+             ORDER BY e.reports_to, e.epf_number",
+            ACTIVE, ACTIVE
+        ))?;
+    let pending = stmt
+        .query_map([], |row| {
+            Ok(PendingReassignment {
+                epf_number: row.get(0)?,
+                name_with_initials: row.get(1)?,
+                supervisor_epf: row.get(2)?,
+                supervisor_name: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(pending)
+}
@@ -0,0 +1,796 @@
+use crate::attendance_commands::monthly_summary;
+use crate::commands::log_audit_action;
+use crate::currency_commands::{rate_to_base, BASE_CURRENCY};
+use crate::deduction_commands::{deductions_for_month, require_month};
+use crate::error::HrmError;
+use crate::incentive_commands::incentives_for_month;
+use crate::meal_commands::{monthly_meal_deductions, MEAL_DEDUCTION_TYPE};
+use crate::models::{
+    PayrollCostCenter, PayrollDeduction, PayrollLine, PayrollRun, PayrollScenario, PayrollSimulation,
+    PayrollSimulationLine, PayrollVarianceLine, PayrollVarianceReport, UserSession,
+};
+use crate::salary_commands::effective_salary;
+use crate::settings_commands::get_setting_i64;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::{BTreeMap, HashMap};
+use tauri::State;
+
+// Statutory contribution rates, as percentages of earnings excluding overtime
+const EPF_EMPLOYEE_PERCENT: f64 = 8.0;
+const EPF_EMPLOYER_PERCENT: f64 = 12.0;
+const ETF_EMPLOYER_PERCENT: f64 = 3.0;
+
+// Overtime hourly rate is basic / 240 times the OT rate (Wages Boards
+// Ordinance). No-pay days are deducted at basic / payroll_no_pay_divisor.
+const OT_HOURS_DIVISOR: f64 = 240.0;
+const DEFAULT_OT_RATE_PERCENT: i64 = 150;
+const DEFAULT_NO_PAY_DIVISOR: i64 = 30;
+
+// Month-over-month changes beyond these are flagged on the variance report
+const DEFAULT_VARIANCE_PERCENT: i64 = 10;
+const DEFAULT_VARIANCE_OT_HOURS: i64 = 20;
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+fn require_payroll_access(user: &UserSession) -> Result<(), HrmError> {
+    if user.permissions.can_run_payroll {
+        Ok(())
+    } else {
+        Err(HrmError::permission_denied("Permission denied. You cannot run payroll."))
+    }
+}
+
+// Locked runs can be looked at by anyone who may see salaries
+fn require_salary_view(user: &UserSession) -> Result<(), HrmError> {
+    if user.permissions.can_view_salaries || user.permissions.can_run_payroll {
+        Ok(())
+    } else {
+        Err(HrmError::permission_denied("Permission denied. You cannot view payroll runs."))
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+pub(crate) fn is_month_locked(conn: &Connection, month: &str) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM payroll_runs WHERE month = ?1 AND status = 'locked')",
+        [month],
+        |row| row.get(0),
+    )
+}
+
+// Fails if a locked payroll run covers the month of `date` (YYYY-MM or
+// YYYY-MM-DD), so attendance and leave for a paid month cannot change
+pub(crate) fn ensure_month_open(conn: &Connection, date: &str) -> Result<(), HrmError> {
+    let month = date.get(..7).unwrap_or(date);
+    if is_month_locked(conn, month)? {
+        return Err(HrmError::conflict(format!(
+            "Payroll for {} is locked. The payroll run must be reversed before this change.",
+            month
+        )));
+    }
+    Ok(())
+}
+
+// Fails if any month from `date` onwards is locked. For salary and deduction
+// changes, which carry forward into every later month.
+pub(crate) fn ensure_open_from(conn: &Connection, date: &str) -> Result<(), HrmError> {
+    let month = date.get(..7).unwrap_or(date);
+    let locked: Option<String> = conn
+        .query_row(
+            "SELECT MAX(month) FROM payroll_runs WHERE month >= ?1 AND status = 'locked'",
+            [month],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten();
+    if let Some(locked) = locked {
+        return Err(HrmError::conflict(format!(
+            "Payroll is locked up to {}. The change would alter a locked month.",
+            locked
+        )));
+    }
+    Ok(())
+}
+
+// Works out pay for every employee with a salary in the month without saving
+// anything. Employees with no salary revision are listed in `skipped`.
+pub(crate) fn calculate_payroll(conn: &Connection, month: &str) -> Result<PayrollRun, HrmError> {
+    calculate_payroll_with(conn, month, &PayrollScenario::default())
+}
+
+// calculate_payroll with the rates and salaries in `scenario` used in place
+// of the settings and salary revisions on record
+fn calculate_payroll_with(conn: &Connection, month: &str, scenario: &PayrollScenario) -> Result<PayrollRun, HrmError> {
+    require_month(month)?;
+    let ot_rate_percent = scenario
+        .ot_rate_percent
+        .unwrap_or_else(|| get_setting_i64(conn, "payroll_ot_rate_percent").unwrap_or(DEFAULT_OT_RATE_PERCENT));
+    let ot_rate = ot_rate_percent as f64 / 100.0;
+    let no_pay_divisor = scenario
+        .no_pay_divisor
+        .unwrap_or_else(|| get_setting_i64(conn, "payroll_no_pay_divisor").unwrap_or(DEFAULT_NO_PAY_DIVISOR))
+        .max(1) as f64;
+    let salary_factor = 1.0 + scenario.salary_increase_percent.unwrap_or(0.0) / 100.0;
+    let month_end: String = conn.query_row("SELECT date(?1 || '-01', '+1 month', '-1 day')", [month], |row| row.get(0))?;
+    
+    let mut deductions: HashMap<String, Vec<PayrollDeduction>> = HashMap::new();
+    for line in deductions_for_month(conn, month)? {
+        deductions.entry(line.epf_number).or_default().push(PayrollDeduction {
+            deduction_type: line.deduction_type,
+            amount: line.amount,
+        });
+    }
+    for meal in monthly_meal_deductions(conn, month)? {
+        if meal.deduction > 0.0 {
+            deductions.entry(meal.epf_number).or_default().push(PayrollDeduction {
+                deduction_type: MEAL_DEDUCTION_TYPE.to_string(),
+                amount: round2(meal.deduction),
+            });
+        }
+    }
+    
+    let mut run = PayrollRun {
+        id: None,
+        month: month.to_string(),
+        status: "preview".to_string(),
+        employee_count: 0,
+        total_gross: 0.0,
+        total_net: 0.0,
+        total_epf_employee: 0.0,
+        total_epf_employer: 0.0,
+        total_etf: 0.0,
+        locked_by: None,
+        locked_at: None,
+        reversed_by: None,
+        reversed_at: None,
+        reversal_reason: None,
+        lines: Vec::new(),
+        skipped: Vec::new(),
+    };
+    let attendance_rows = monthly_summary(conn, month)?;
+    let incentives = incentives_for_month(conn, month, &attendance_rows)?;
+    for attendance in attendance_rows {
+        let on_record = effective_salary(conn, &attendance.epf_number, &month_end)?;
+        let salary = match (scenario.salaries.get(&attendance.epf_number), on_record) {
+            (Some(proposed), record) => {
+                Some((*proposed, record.map(|(_, currency)| currency).unwrap_or_else(|| BASE_CURRENCY.to_string())))
+            }
+            (None, Some((amount, currency))) => Some((round2(amount * salary_factor), currency)),
+            (None, None) => None,
+        };
+        let Some((salary, pay_currency)) = salary else {
+            run.skipped.push(attendance.epf_number);
+            continue;
+        };
+        // Foreign currency salaries are paid at the month's rate; everything after is in rupees
+        let exchange_rate = rate_to_base(conn, &pay_currency, month)?;
+        let basic_salary = round2(salary * exchange_rate);
+        let no_pay_deduction = round2(basic_salary / no_pay_divisor * attendance.no_pay_days).min(basic_salary);
+        let ot_pay = round2(basic_salary / OT_HOURS_DIVISOR * ot_rate * attendance.ot_hours);
+        // Piece-rate earnings are wages, so EPF and ETF apply to them too
+        let incentive_pay = incentives.get(&attendance.epf_number).copied().unwrap_or(0.0);
+        let contributory = basic_salary - no_pay_deduction + incentive_pay;
+        let epf_employee = round2(contributory * EPF_EMPLOYEE_PERCENT / 100.0);
+        let epf_employer = round2(contributory * EPF_EMPLOYER_PERCENT / 100.0);
+        let etf_employer = round2(contributory * ETF_EMPLOYER_PERCENT / 100.0);
+        let gross_pay = round2(contributory + ot_pay);
+        let employee_deductions = deductions.remove(&attendance.epf_number).unwrap_or_default();
+        let other_deductions = round2(employee_deductions.iter().map(|d| d.amount).sum());
+    
+        run.lines.push(PayrollLine {
+            epf_number: attendance.epf_number,
+            name_with_initials: attendance.name_with_initials,
+            department: attendance.department,
+            allocation: attendance.allocation,
+            basic_salary,
+            pay_currency,
+            exchange_rate,
+            working_days: attendance.working_days,
+            worked_days: attendance.worked_days,
+            no_pay_days: attendance.no_pay_days,
+            no_pay_deduction,
+            ot_hours: attendance.ot_hours,
+            ot_pay,
+            incentive_pay,
+            gross_pay,
+            epf_employee,
+            epf_employer,
+            etf_employer,
+            other_deductions,
+            net_pay: round2(gross_pay - epf_employee - other_deductions),
+            deductions: employee_deductions,
+        });
+    }
+    
+    run.employee_count = run.lines.len() as i64;
+    run.total_gross = round2(run.lines.iter().map(|l| l.gross_pay).sum());
+    run.total_net = round2(run.lines.iter().map(|l| l.net_pay).sum());
+    run.total_epf_employee = round2(run.lines.iter().map(|l| l.epf_employee).sum());
+    run.total_epf_employer = round2(run.lines.iter().map(|l| l.epf_employer).sum());
+    run.total_etf = round2(run.lines.iter().map(|l| l.etf_employer).sum());
+    Ok(run)
+}
+
+// A saved payroll run with its lines and deductions
+pub(crate) fn load_payroll_run(conn: &Connection, run_id: i64) -> Result<PayrollRun, HrmError> {
+    let mut run = conn
+        .query_row(
+            "SELECT id, month, status, employee_count, total_gross, total_net, total_epf_employee,
+                    total_epf_employer, total_etf, locked_by, locked_at, reversed_by, reversed_at, reversal_reason
+             FROM payroll_runs WHERE id = ?1",
+            [run_id],
+            |row| {
+                Ok(PayrollRun {
+                    id: row.get(0)?,
+                    month: row.get(1)?,
+                    status: row.get(2)?,
+                    employee_count: row.get(3)?,
+                    total_gross: row.get(4)?,
+                    total_net: row.get(5)?,
+                    total_epf_employee: row.get(6)?,
+                    total_epf_employer: row.get(7)?,
+                    total_etf: row.get(8)?,
+                    locked_by: row.get(9)?,
+                    locked_at: row.get(10)?,
+                    reversed_by: row.get(11)?,
+                    reversed_at: row.get(12)?,
+                    reversal_reason: row.get(13)?,
+                    lines: Vec::new(),
+                    skipped: Vec::new(),
+                })
+            },
+        )
+        .optional()?
+        .ok_or_else(|| HrmError::not_found("Payroll run not found"))?;
+    
+    let mut stmt = conn.prepare(
+        "SELECT d.line_id, d.deduction_type, d.amount FROM payroll_line_deductions d
+         JOIN payroll_lines l ON l.id = d.line_id WHERE l.run_id = ?1 ORDER BY d.id",
+    )?;
+    let mut deductions: HashMap<i64, Vec<PayrollDeduction>> = HashMap::new();
+    for row in stmt.query_map([run_id], |row| {
+        Ok((row.get::<_, i64>(0)?, PayrollDeduction { deduction_type: row.get(1)?, amount: row.get(2)? }))
+    })? {
+        let (line_id, deduction) = row?;
+        deductions.entry(line_id).or_default().push(deduction);
+    }
+    
+    let mut stmt = conn.prepare(
+        "SELECT id, epf_number, name_with_initials, department, allocation, basic_salary, working_days, worked_days,
+                no_pay_days, no_pay_deduction, ot_hours, ot_pay, gross_pay, epf_employee, epf_employer,
+                etf_employer, other_deductions, net_pay, incentive_pay, pay_currency, exchange_rate
+         FROM payroll_lines WHERE run_id = ?1 ORDER BY epf_number",
+    )?;
+    run.lines = stmt
+        .query_map([run_id], |row| {
+            Ok(PayrollLine {
+                epf_number: row.get(1)?,
+                name_with_initials: row.get(2)?,
+                department: row.get(3)?,
+                allocation: row.get(4)?,
+                basic_salary: row.get(5)?,
+                pay_currency: row.get(19)?,
+                exchange_rate: row.get(20)?,
+                working_days: row.get(6)?,
+                worked_days: row.get(7)?,
+                no_pay_days: row.get(8)?,
+                no_pay_deduction: row.get(9)?,
+                ot_hours: row.get(10)?,
+                ot_pay: row.get(11)?,
+                incentive_pay: row.get(18)?,
+                gross_pay: row.get(12)?,
+                epf_employee: row.get(13)?,
+                epf_employer: row.get(14)?,
+                etf_employer: row.get(15)?,
+                other_deductions: row.get(16)?,
+                net_pay: row.get(17)?,
+                deductions: deductions.remove(&row.get::<_, i64>(0)?).unwrap_or_default(),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(run)
+}
+
+// The locked run for a month, if there is one
+fn locked_run_for_month(conn: &Connection, month: &str) -> Result<Option<PayrollRun>, HrmError> {
+    let run_id: Option<i64> = conn
+        .query_row("SELECT id FROM payroll_runs WHERE month = ?1 AND status = 'locked'", [month], |row| row.get(0))
+        .optional()?;
+    run_id.map(|id| load_payroll_run(conn, id)).transpose()
+}
+
+// Compares each employee's pay for `month` with the previous month's locked
+// run. The month's locked run is used if there is one, otherwise a preview, so
+// the report can be checked before locking.
+fn payroll_variance(conn: &Connection, month: &str) -> Result<PayrollVarianceReport, HrmError> {
+    require_month(month)?;
+    let previous_month: String =
+        conn.query_row("SELECT strftime('%Y-%m', ?1 || '-01', '-1 month')", [month], |row| row.get(0))?;
+    let previous = locked_run_for_month(conn, &previous_month)?
+        .ok_or_else(|| HrmError::not_found(format!("There is no locked payroll run for {}", previous_month)))?;
+    let current = match locked_run_for_month(conn, month)? {
+        Some(run) => run,
+        None => calculate_payroll(conn, month)?,
+    };
+    let threshold_percent = get_setting_i64(conn, "payroll_variance_percent").unwrap_or(DEFAULT_VARIANCE_PERCENT);
+    let threshold_ot_hours = get_setting_i64(conn, "payroll_variance_ot_hours").unwrap_or(DEFAULT_VARIANCE_OT_HOURS);
+    
+    let mut pairs: BTreeMap<String, (Option<PayrollLine>, Option<PayrollLine>)> = BTreeMap::new();
+    for line in previous.lines {
+        let epf_number = line.epf_number.clone();
+        pairs.entry(epf_number).or_default().0 = Some(line);
+    }
+    for line in current.lines {
+        let epf_number = line.epf_number.clone();
+        pairs.entry(epf_number).or_default().1 = Some(line);
+    }
+    
+    let lines = pairs
+        .into_iter()
+        .map(|(epf_number, (before, after))| {
+            let (name_with_initials, department) = match after.as_ref().or(before.as_ref()) {
+                Some(line) => (line.name_with_initials.clone(), line.department.clone()),
+                None => (String::new(), None),
+            };
+            let previous_net_pay = before.as_ref().map(|l| l.net_pay);
+            let current_net_pay = after.as_ref().map(|l| l.net_pay);
+            let previous_ot_hours = before.as_ref().map(|l| l.ot_hours).unwrap_or(0.0);
+            let current_ot_hours = after.as_ref().map(|l| l.ot_hours).unwrap_or(0.0);
+            let net_pay_change = round2(current_net_pay.unwrap_or(0.0) - previous_net_pay.unwrap_or(0.0));
+            let net_pay_change_percent = previous_net_pay
+                .filter(|net| *net != 0.0)
+                .map(|net| round2(net_pay_change / net * 100.0));
+            let ot_hours_change = round2(current_ot_hours - previous_ot_hours);
+            let status = match (&before, &after) {
+                (None, _) => "new",
+                (_, None) => "removed",
+                _ if net_pay_change == 0.0 && ot_hours_change == 0.0 => "unchanged",
+                _ => "changed",
+            };
+            let flagged = status == "new"
+                || status == "removed"
+                || net_pay_change_percent.is_some_and(|p| p.abs() > threshold_percent as f64)
+                || ot_hours_change.abs() > threshold_ot_hours as f64;
+            PayrollVarianceLine {
+                epf_number,
+                name_with_initials,
+                department,
+                status: status.to_string(),
+                previous_net_pay,
+                current_net_pay,
+                net_pay_change,
+                net_pay_change_percent,
+                previous_ot_hours,
+                current_ot_hours,
+                ot_hours_change,
+                flagged,
+            }
+        })
+        .collect();
+    
+    Ok(PayrollVarianceReport {
+        month: month.to_string(),
+        previous_month,
+        status: current.status,
+        threshold_percent,
+        threshold_ot_hours,
+        lines,
+    })
+}
+
+// Payroll cost from locked runs between two months (YYYY-MM), one row per
+// month and cost center. `group_by` is "allocation" or "department"; lines
+// with neither set are grouped under "Unassigned". Lines are only counted for
+// departments `user` may see.
+pub(crate) fn payroll_cost_by_center(
+    conn: &Connection,
+    user: &UserSession,
+    from_month: &str,
+    to_month: &str,
+    group_by: &str,
+) -> Result<Vec<PayrollCostCenter>, HrmError> {
+    require_month(from_month)?;
+    require_month(to_month)?;
+    if to_month < from_month {
+        return Err(HrmError::field("to_month", "End month cannot be before the start month"));
+    }
+    if !["allocation", "department"].contains(&group_by) {
+        return Err(HrmError::field("group_by", "Cost centers can be grouped by allocation or department"));
+    }
+    
+    let mut stmt = conn.prepare(
+        "SELECT r.month, l.department, l.allocation, l.basic_salary - l.no_pay_deduction, l.ot_pay, l.gross_pay,
+                l.epf_employer, l.etf_employer
+         FROM payroll_lines l
+         JOIN payroll_runs r ON r.id = l.run_id
+         WHERE r.status = 'locked' AND r.month BETWEEN ?1 AND ?2",
+    )?;
+    let rows = stmt.query_map([from_month, to_month], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            [row.get::<_, f64>(3)?, row.get(4)?, row.get(5)?, row.get(6)?, row.get(7)?],
+        ))
+    })?;
+    
+    let mut centers: BTreeMap<(String, String), PayrollCostCenter> = BTreeMap::new();
+    for row in rows {
+        let (month, department, allocation, amounts) = row?;
+        if !user.can_access_department(department.as_deref()) {
+            continue;
+        }
+        let center = if group_by == "allocation" { allocation } else { department };
+        let center = center
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .unwrap_or_else(|| "Unassigned".to_string());
+        let entry = centers.entry((month.clone(), center.clone())).or_insert_with(|| PayrollCostCenter {
+            month,
+            cost_center: center,
+            employee_count: 0,
+            basic_pay: 0.0,
+            ot_pay: 0.0,
+            gross_pay: 0.0,
+            epf_employer: 0.0,
+            etf_employer: 0.0,
+            total_cost: 0.0,
+        });
+        let [basic_pay, ot_pay, gross_pay, epf_employer, etf_employer] = amounts;
+        entry.employee_count += 1;
+        entry.basic_pay = round2(entry.basic_pay + basic_pay);
+        entry.ot_pay = round2(entry.ot_pay + ot_pay);
+        entry.gross_pay = round2(entry.gross_pay + gross_pay);
+        entry.epf_employer = round2(entry.epf_employer + epf_employer);
+        entry.etf_employer = round2(entry.etf_employer + etf_employer);
+        entry.total_cost = round2(entry.gross_pay + entry.epf_employer + entry.etf_employer);
+    }
+    Ok(centers.into_values().collect())
+}
+
+// Calculates payroll for a month without saving or locking it
+#[tauri::command]
+pub fn preview_payroll(
+    month: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<PayrollRun, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_payroll_access(&user)?;
+    
+    let conn = db.0.lock()?;
+    calculate_payroll(&conn, &month)
+}
+
+// Employer cost of a payroll line: gross pay plus employer EPF and ETF
+fn employer_cost(line: &PayrollLine) -> f64 {
+    line.gross_pay + line.epf_employer + line.etf_employer
+}
+
+// Runs the month's payroll twice, as things stand and with the changes in
+// `scenario`, and compares them per employee. Nothing is saved, so a proposed
+// OT rate or salary revision batch can be costed before it is made.
+#[tauri::command]
+pub fn simulate_payroll(
+    month: String,
+    scenario: PayrollScenario,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<PayrollSimulation, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_payroll_access(&user)?;
+    if scenario.ot_rate_percent.is_some_and(|rate| !(100..=500).contains(&rate)) {
+        return Err(HrmError::field("ot_rate_percent", "OT rate must be between 100 and 500 percent"));
+    }
+    if scenario.no_pay_divisor.is_some_and(|divisor| divisor < 1) {
+        return Err(HrmError::field("no_pay_divisor", "No-pay divisor must be at least 1"));
+    }
+    if scenario.salary_increase_percent.is_some_and(|pct| !pct.is_finite() || pct <= -100.0) {
+        return Err(HrmError::field("salary_increase_percent", "Salary change must be more than -100 percent"));
+    }
+    if scenario.salaries.values().any(|salary| !salary.is_finite() || *salary < 0.0) {
+        return Err(HrmError::field("salaries", "Proposed salaries must be zero or more"));
+    }
+    
+    let conn = db.0.lock()?;
+    let mut baseline = calculate_payroll(&conn, &month)?;
+    let mut simulated = calculate_payroll_with(&conn, &month, &scenario)?;
+    
+    let mut pairs: BTreeMap<String, (Option<PayrollLine>, Option<PayrollLine>)> = BTreeMap::new();
+    for line in baseline.lines.drain(..) {
+        let epf_number = line.epf_number.clone();
+        pairs.entry(epf_number).or_default().0 = Some(line);
+    }
+    for line in simulated.lines.drain(..) {
+        let epf_number = line.epf_number.clone();
+        pairs.entry(epf_number).or_default().1 = Some(line);
+    }
+    let lines: Vec<PayrollSimulationLine> = pairs
+        .into_iter()
+        .filter_map(|(epf_number, (before, after))| {
+            let line = after.as_ref().or(before.as_ref())?;
+            let baseline_cost = round2(before.as_ref().map(employer_cost).unwrap_or(0.0));
+            let simulated_cost = round2(after.as_ref().map(employer_cost).unwrap_or(0.0));
+            Some(PayrollSimulationLine {
+                name_with_initials: line.name_with_initials.clone(),
+                department: line.department.clone(),
+                baseline_basic: before.as_ref().map(|l| l.basic_salary),
+                simulated_basic: after.as_ref().map(|l| l.basic_salary),
+                baseline_net: before.as_ref().map(|l| l.net_pay),
+                simulated_net: after.as_ref().map(|l| l.net_pay),
+                baseline_cost,
+                simulated_cost,
+                cost_change: round2(simulated_cost - baseline_cost),
+                epf_number,
+            })
+        })
+        .collect();
+    
+    let baseline_cost = round2(lines.iter().map(|l| l.baseline_cost).sum());
+    let simulated_cost = round2(lines.iter().map(|l| l.simulated_cost).sum());
+    Ok(PayrollSimulation {
+        month,
+        baseline,
+        simulated,
+        baseline_cost,
+        simulated_cost,
+        cost_change: round2(simulated_cost - baseline_cost),
+        lines,
+    })
+}
+
+// Calculates and saves the payroll for a month and locks it. Attendance,
+// leave, salary and deduction changes affecting the month are refused until
+// the run is reversed.
+#[tauri::command]
+pub fn lock_payroll_period(
+    month: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<PayrollRun, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_payroll_access(&user)?;
+    
+    let mut conn = db.0.lock()?;
+    ensure_month_open(&conn, &month)?;
+    let run = calculate_payroll(&conn, &month)?;
+    if run.lines.is_empty() {
+        return Err(HrmError::validation(format!("No employees with a salary to pay for {}", month)));
+    }
+    
+    let tx = conn.transaction()?;
+    tx.execute(
+        "INSERT INTO payroll_runs (month, status, employee_count, total_gross, total_net, total_epf_employee,
+                                   total_epf_employer, total_etf, locked_by)
+         VALUES (?1, 'locked', ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![
+            run.month,
+            run.employee_count,
+            run.total_gross,
+            run.total_net,
+            run.total_epf_employee,
+            run.total_epf_employer,
+            run.total_etf,
+            user.username
+        ],
+    )?;
+    let run_id = tx.last_insert_rowid();
+    for line in &run.lines {
+        tx.execute(
+            "INSERT INTO payroll_lines (run_id, epf_number, name_with_initials, department, allocation, basic_salary,
+                                        working_days, worked_days, no_pay_days, no_pay_deduction, ot_hours, ot_pay,
+                                        gross_pay, epf_employee, epf_employer, etf_employer, other_deductions, net_pay,
+                                        incentive_pay, pay_currency, exchange_rate)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)",
+            rusqlite::params![
+                run_id,
+                line.epf_number,
+                line.name_with_initials,
+                line.department,
+                line.allocation,
+                line.basic_salary,
+                line.working_days,
+                line.worked_days,
+                line.no_pay_days,
+                line.no_pay_deduction,
+                line.ot_hours,
+                line.ot_pay,
+                line.gross_pay,
+                line.epf_employee,
+                line.epf_employer,
+                line.etf_employer,
+                line.other_deductions,
+                line.net_pay,
+                line.incentive_pay,
+                line.pay_currency,
+                line.exchange_rate
+            ],
+        )?;
+        let line_id = tx.last_insert_rowid();
+        for deduction in &line.deductions {
+            tx.execute(
+                "INSERT INTO payroll_line_deductions (line_id, deduction_type, amount) VALUES (?1, ?2, ?3)",
+                rusqlite::params![line_id, deduction.deduction_type, deduction.amount],
+            )?;
+        }
+    }
+    tx.commit()?;
+    
+    // Amounts stay out of the audit text; the log is readable by non-HR users
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "LOCK",
+        "PAYROLL",
+        Some(&run_id.to_string()),
+        None,
+        Some(&month),
+        Some(&format!("Locked payroll for {} ({} employees)", month, run.employee_count)),
+    );
+    
+    load_payroll_run(&conn, run_id)
+}
+
+#[tauri::command]
+pub fn get_payroll_runs(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<PayrollRun>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_salary_view(&user)?;
+    
+    let conn = db.0.lock()?;
+    let ids = conn
+        .prepare("SELECT id FROM payroll_runs ORDER BY month DESC, id DESC")?
+        .query_map([], |row| row.get::<_, i64>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut runs = Vec::with_capacity(ids.len());
+    for id in ids {
+        let mut run = load_payroll_run(&conn, id)?;
+        run.lines.clear();
+        runs.push(run);
+    }
+    Ok(runs)
+}
+
+// A payroll run with the lines for departments the user may see
+#[tauri::command]
+pub fn get_payroll_run(
+    id: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<PayrollRun, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_salary_view(&user)?;
+    
+    let conn = db.0.lock()?;
+    let mut run = load_payroll_run(&conn, id)?;
+    run.lines.retain(|line| user.can_access_department(line.department.as_deref()));
+    Ok(run)
+}
+
+// Unlocks a month by marking its run reversed. Admin only. The run and its
+// lines are kept, so what was paid can still be seen next to the re-run.
+#[tauri::command]
+pub fn reverse_payroll_run(
+    id: i64,
+    reason: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if user.role != "admin" {
+        return Err(HrmError::permission_denied("Only an administrator can reverse a payroll run."));
+    }
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Err(HrmError::field("reason", "A reason is required to reverse a payroll run"));
+    }
+    
+    let mut conn = db.0.lock()?;
+    let run = load_payroll_run(&conn, id)?;
+    if run.status != "locked" {
+        return Err(HrmError::conflict(format!("This payroll run has already been {}", run.status)));
+    }
+    let tx = conn.transaction()?;
+    tx.execute(
+        "UPDATE payroll_runs SET status = 'reversed', reversed_by = ?1, reversed_at = CURRENT_TIMESTAMP,
+                                 reversal_reason = ?2
+         WHERE id = ?3",
+        rusqlite::params![user.username, reason, id],
+    )?;
+    let released = release_quarantined_punches(&tx, &run.month)?;
+    
+    // Amounts stay out of the audit text; the log is readable by non-HR users
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "REVERSE",
+        "PAYROLL",
+        Some(&id.to_string()),
+        Some("locked"),
+        Some("reversed"),
+        Some(&format!(
+            "Reversed payroll for {} ({} employees, locked by {} at {}): {}",
+            run.month,
+            run.employee_count,
+            run.locked_by.as_deref().unwrap_or("unknown"),
+            run.locked_at.as_deref().unwrap_or("unknown"),
+            reason
+        )),
+    );
+    if released > 0 {
+        log_audit_action(
+            &tx,
+            Some(user.user_id),
+            &user.username,
+            "UPDATE",
+            "ATTENDANCE",
+            None,
+            None,
+            None,
+            Some(&format!("Added {} device punch(es) for {} held back while payroll was locked", released, run.month)),
+        );
+    }
+    tx.commit()?;
+    
+    Ok(())
+}
+
+// Moves device punches held back while `month` was locked into attendance
+fn release_quarantined_punches(conn: &Connection, month: &str) -> rusqlite::Result<usize> {
+    let released = conn.execute(
+        "INSERT OR IGNORE INTO attendance_punches (epf_number, punch_time, punch_state, verify_mode, device_serial, source)
+         SELECT epf_number, punch_time, punch_state, verify_mode, device_serial, 'device'
+         FROM quarantined_punches WHERE substr(punch_time, 1, 7) = ?1",
+        [month],
+    )?;
+    conn.execute("DELETE FROM quarantined_punches WHERE substr(punch_time, 1, 7) = ?1", [month])?;
+    Ok(released)
+}
+
+// Per-employee net pay and overtime change against the previous month, with
+// changes beyond the payroll_variance_percent and payroll_variance_ot_hours
+// settings flagged. Joiners and leavers are always flagged.
+#[tauri::command]
+pub fn get_payroll_variance_report(
+    month: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<PayrollVarianceReport, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_payroll_access(&user)?;
+    
+    let conn = db.0.lock()?;
+    let mut report = payroll_variance(&conn, &month)?;
+    report.lines.retain(|line| user.can_access_department(line.department.as_deref()));
+    Ok(report)
+}
+
+// Salary, overtime and employer contributions per cost center per month, from
+// locked payroll runs, for the management accounts
+#[tauri::command]
+pub fn get_payroll_cost_report(
+    from_month: String,
+    to_month: String,
+    group_by: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<PayrollCostCenter>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_salaries || !user.permissions.can_view_reports {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view payroll cost reports."));
+    }
+    
+    let conn = db.0.lock()?;
+    payroll_cost_by_center(&conn, &user, &from_month, &to_month, &group_by)
+}
@@ -0,0 +1,160 @@
+use crate::error::HrmError;
+use std::fs;
+
+// Plain PDF documents for letters and statutory returns: A4 pages using the
+// standard Helvetica and Courier fonts, so nothing is embedded. Those fonts
+// only cover Latin text; Sinhala and Tamil documents go out as DOCX or
+// printable HTML instead.
+
+const A4: (f32, f32) = (595.0, 842.0);
+const MARGIN: f32 = 50.0;
+// Average Helvetica glyph width as a share of the font size, for wrapping
+const HELVETICA_WIDTH: f32 = 0.52;
+// Courier glyphs are all this wide
+const COURIER_WIDTH: f32 = 0.6;
+
+pub(crate) enum PdfBlock {
+    // Bold and centred, e.g. the company name
+    Heading(String),
+    // Wrapped to the page width; a blank paragraph leaves a gap
+    Paragraph(String),
+    // One line of fixed-width text, for tables laid out with padding
+    Row(String),
+}
+
+// Font resource, size and whether text is centred
+fn block_style(block: &PdfBlock) -> (&'static str, f32, bool) {
+    match block {
+        PdfBlock::Heading(_) => ("F2", 16.0, true),
+        PdfBlock::Paragraph(_) => ("F1", 11.0, false),
+        PdfBlock::Row(_) => ("F3", 8.0, false),
+    }
+}
+
+// PDF string literal in WinAnsiEncoding. Fails on characters the standard
+// fonts cannot show.
+fn pdf_string(text: &str) -> Result<Vec<u8>, HrmError> {
+    let mut out = vec![b'('];
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => out.extend([b'\\', c as u8]),
+            '\t' => out.push(b' '),
+            c if (' '..='~').contains(&c) || ('\u{a0}'..='\u{ff}').contains(&c) => out.push(c as u32 as u8),
+            _ => {
+                return Err(HrmError::validation(
+                    "PDF documents can only hold English text. Use Word (DOCX) or HTML for Sinhala or Tamil.",
+                ))
+            }
+        }
+    }
+    out.push(b')');
+    Ok(out)
+}
+
+// Splits a paragraph into lines that fit `columns` characters
+fn wrap(text: &str, columns: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for source_line in text.lines() {
+        let mut line = String::new();
+        for word in source_line.split_whitespace() {
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > columns {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines
+}
+
+// Lays the blocks out over as many pages as they need and writes the file.
+// Landscape pages suit wide tables such as the EPF return.
+pub(crate) fn write_pdf(path: &str, landscape: bool, blocks: &[PdfBlock]) -> Result<(), HrmError> {
+    let (width, height) = if landscape { (A4.1, A4.0) } else { A4 };
+    let text_width = width - 2.0 * MARGIN;
+    
+    let mut pages: Vec<Vec<u8>> = Vec::new();
+    let mut content: Vec<u8> = Vec::new();
+    let mut y = height - MARGIN;
+    for block in blocks {
+        let (font, size, centred) = block_style(block);
+        let leading = size * 1.4;
+        let lines = match block {
+            PdfBlock::Heading(text) | PdfBlock::Row(text) => vec![text.clone()],
+            PdfBlock::Paragraph(text) => wrap(text, (text_width / (size * HELVETICA_WIDTH)) as usize),
+        };
+        for line in lines {
+            if y - leading < MARGIN {
+                pages.push(std::mem::take(&mut content));
+                y = height - MARGIN;
+            }
+            y -= leading;
+            let glyph_width = if font == "F3" { COURIER_WIDTH } else { HELVETICA_WIDTH };
+            let x = if centred {
+                ((width - line.chars().count() as f32 * size * glyph_width) / 2.0).max(MARGIN)
+            } else {
+                MARGIN
+            };
+            content.extend(format!("BT /{} {} Tf {:.1} {:.1} Td ", font, size, x, y).as_bytes());
+            content.extend(pdf_string(&line)?);
+            content.extend(b" Tj ET\n");
+        }
+        if matches!(block, PdfBlock::Heading(_)) {
+            y -= 10.0;
+        }
+    }
+    pages.push(content);
+    
+    // Objects: 1 catalog, 2 page tree, 3-5 fonts, then a page and its
+    // content stream for each page
+    let mut objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            (0..pages.len()).map(|i| format!("{} 0 R", 6 + i * 2)).collect::<Vec<_>>().join(" "),
+            pages.len()
+        )
+        .into_bytes(),
+    ];
+    for font in ["Helvetica", "Helvetica-Bold", "Courier"] {
+        objects.push(format!("<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>", font).into_bytes());
+    }
+    for (i, content) in pages.into_iter().enumerate() {
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R /F3 5 0 R >> >> /Contents {} 0 R >>",
+                width,
+                height,
+                7 + i * 2
+            )
+            .into_bytes(),
+        );
+        let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+        stream.extend(content);
+        stream.extend(b"\nendstream");
+        objects.push(stream);
+    }
+    
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend(format!("{} 0 obj\n", i + 1).as_bytes());
+        pdf.extend(object);
+        pdf.extend(b"\nendobj\n");
+    }
+    let xref = pdf.len();
+    pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+    for offset in offsets {
+        pdf.extend(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    pdf.extend(format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref).as_bytes());
+    
+    fs::write(path, pdf).map_err(|e| HrmError::io(format!("Failed to save file: {}", e)))
+}
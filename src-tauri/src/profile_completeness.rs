@@ -0,0 +1,119 @@
+use crate::error::HrmError;
+use crate::models::{DepartmentCompleteness, ProfileCompleteness, UserSession};
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use std::collections::BTreeMap;
+use tauri::State;
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+// Profiles listed per department in the least-complete report by default
+const DEFAULT_PER_DEPARTMENT: usize = 10;
+
+// What a complete profile holds: label shown to HR and an SQL test on
+// employees `e` that is true when the item is filled in. Each item carries
+// the same weight. There is no separate emergency contact field, so the
+// second mobile number stands in for it.
+const PROFILE_ITEMS: [(&str, &str); 11] = [
+    ("NIC", "TRIM(COALESCE(e.nic, '')) != ''"),
+    ("Date of birth", "TRIM(COALESCE(e.dob, '')) != ''"),
+    ("Mobile number", "TRIM(COALESCE(e.mobile_1, '')) != ''"),
+    ("Emergency contact (Mobile 2)", "TRIM(COALESCE(e.mobile_2, '')) != ''"),
+    ("Address", "TRIM(COALESCE(e.address, '')) != ''"),
+    ("Photo", "TRIM(COALESCE(e.image_path, '')) != ''"),
+    ("Department", "TRIM(COALESCE(e.department, '')) != ''"),
+    ("Designation", "TRIM(COALESCE(e.designation, '')) != ''"),
+    ("Date of join", "TRIM(COALESCE(e.date_of_join, '')) != ''"),
+    ("Marital status", "TRIM(COALESCE(e.marital_status, '')) != ''"),
+    ("Bank details", "EXISTS(SELECT 1 FROM employee_bank_accounts b WHERE b.epf_number = e.epf_number)"),
+];
+
+// Completeness of every active employee's profile, least complete first
+fn active_profiles(conn: &Connection) -> rusqlite::Result<Vec<ProfileCompleteness>> {
+    let checks: Vec<&str> = PROFILE_ITEMS.iter().map(|(_, test)| *test).collect();
+    let mut stmt = conn.prepare(&format!(
+        "SELECT e.epf_number, e.name_with_initials, e.department, {}
+         FROM employees e
+         WHERE e.working_status IN (SELECT code FROM working_statuses WHERE counts_as_active = 1)
+         ORDER BY e.epf_number",
+        checks.join(", ")
+    ))?;
+    let mut profiles = stmt
+        .query_map([], |row| {
+            let mut missing = Vec::new();
+            for (index, (label, _)) in PROFILE_ITEMS.iter().enumerate() {
+                if !row.get::<_, bool>(index + 3)? {
+                    missing.push(label.to_string());
+                }
+            }
+            let filled = PROFILE_ITEMS.len() - missing.len();
+            Ok(ProfileCompleteness {
+                epf_number: row.get(0)?,
+                name_with_initials: row.get(1)?,
+                department: row.get(2)?,
+                score: (filled * 100 / PROFILE_ITEMS.len()) as i32,
+                missing,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    profiles.sort_by_key(|p| p.score);
+    Ok(profiles)
+}
+
+// Completeness percentage and missing items for each active employee,
+// least complete first
+#[tauri::command]
+pub fn get_profile_completeness(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<ProfileCompleteness>, HrmError> {
+    logged_in_user(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    Ok(active_profiles(&conn)?)
+}
+
+// Data-quality cleanup report: each department's average score and its
+// least complete profiles (10 unless `per_department` is given). Complete
+// profiles are left out; departments are ordered by average score.
+#[tauri::command]
+pub fn get_least_complete_profiles(
+    per_department: Option<usize>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<DepartmentCompleteness>, HrmError> {
+    logged_in_user(&current_user)?;
+    let per_department = per_department.unwrap_or(DEFAULT_PER_DEPARTMENT);
+    
+    let conn = db.0.lock()?;
+    let mut by_department: BTreeMap<String, Vec<ProfileCompleteness>> = BTreeMap::new();
+    for profile in active_profiles(&conn)? {
+        let department = profile
+            .department
+            .clone()
+            .filter(|d| !d.trim().is_empty())
+            .unwrap_or_else(|| "Unassigned".to_string());
+        by_department.entry(department).or_default().push(profile);
+    }
+    
+    let mut report: Vec<DepartmentCompleteness> = by_department
+        .into_iter()
+        .map(|(department, profiles)| {
+            let employee_count = profiles.len() as i32;
+            let average_score = profiles.iter().map(|p| p.score as f64).sum::<f64>() / profiles.len() as f64;
+            let incomplete_count = profiles.iter().filter(|p| p.score < 100).count() as i32;
+            DepartmentCompleteness {
+                department,
+                employee_count,
+                incomplete_count,
+                average_score: (average_score * 10.0).round() / 10.0,
+                least_complete: profiles.into_iter().filter(|p| p.score < 100).take(per_department).collect(),
+            }
+        })
+        .collect();
+    report.sort_by(|a, b| a.average_score.total_cmp(&b.average_score));
+    Ok(report)
+}
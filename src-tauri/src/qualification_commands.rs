@@ -0,0 +1,259 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{EmployeeQualification, QualificationDistribution, UserSession};
+use crate::{CurrentUser, DbConnection};
+use tauri::State;
+
+// Accepted qualification levels, lowest to highest
+const QUALIFICATION_LEVELS: [&str; 7] = [
+    "O/L",
+    "A/L",
+    "Certificate",
+    "Diploma",
+    "Degree",
+    "Postgraduate",
+    "Professional",
+];
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+fn require_edit(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user = logged_in_user(current_user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit employees."));
+    }
+    Ok(user)
+}
+
+fn validate_qualification(qualification: &EmployeeQualification) -> Result<(), HrmError> {
+    if !QUALIFICATION_LEVELS.contains(&qualification.level.as_str()) {
+        return Err(HrmError::validation(format!("Level must be one of: {}", QUALIFICATION_LEVELS.join(", "))));
+    }
+    if let Some(year) = qualification.year {
+        if !(1950..=2100).contains(&year) {
+            return Err(HrmError::validation("Year must be between 1950 and 2100"));
+        }
+    }
+    Ok(())
+}
+
+fn qualification_from_row(row: &rusqlite::Row) -> rusqlite::Result<EmployeeQualification> {
+    Ok(EmployeeQualification {
+        id: row.get(0)?,
+        epf_number: row.get(1)?,
+        level: row.get(2)?,
+        title: row.get(3)?,
+        institute: row.get(4)?,
+        year: row.get(5)?,
+        document_path: row.get(6)?,
+        created_at: row.get(7)?,
+    })
+}
+
+#[tauri::command]
+pub fn get_employee_qualifications(
+    epf_number: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<EmployeeQualification>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_employees {
+        return Err(HrmError::permission_denied("Permission denied"));
+    }
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, epf_number, level, title, institute, year, document_path, created_at
+             FROM employee_qualifications WHERE epf_number = ?1
+             ORDER BY year DESC, id DESC",
+        )?;
+    let qualifications = stmt
+        .query_map([&epf_number], qualification_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    Ok(qualifications)
+}
+
+#[tauri::command]
+pub fn add_employee_qualification(
+    qualification: EmployeeQualification,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = require_edit(&current_user)?;
+    validate_qualification(&qualification)?;
+    
+    let conn = db.0.lock()?;
+    conn.execute(
+        "INSERT INTO employee_qualifications (epf_number, level, title, institute, year, document_path)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![
+            qualification.epf_number,
+            qualification.level,
+            qualification.title,
+            qualification.institute,
+            qualification.year,
+            qualification.document_path,
+        ],
+    )?;
+    let id = conn.last_insert_rowid();
+    
+    let new_value = serde_json::to_string(&qualification).ok();
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "QUALIFICATION",
+        Some(&id.to_string()),
+        None,
+        new_value.as_deref(),
+        Some(&format!("Added {} qualification for employee {}", qualification.level, qualification.epf_number)),
+    );
+    
+    Ok(id)
+}
+
+#[tauri::command]
+pub fn update_employee_qualification(
+    qualification: EmployeeQualification,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = require_edit(&current_user)?;
+    validate_qualification(&qualification)?;
+    
+    let conn = db.0.lock()?;
+    let old = conn
+        .query_row(
+            "SELECT id, epf_number, level, title, institute, year, document_path, created_at
+             FROM employee_qualifications WHERE id = ?1",
+            [qualification.id],
+            qualification_from_row,
+        )
+        .map_err(|_| HrmError::not_found("Qualification not found"))?;
+    
+    conn.execute(
+        "UPDATE employee_qualifications SET level = ?1, title = ?2, institute = ?3, year = ?4, document_path = ?5
+         WHERE id = ?6",
+        rusqlite::params![
+            qualification.level,
+            qualification.title,
+            qualification.institute,
+            qualification.year,
+            qualification.document_path,
+            qualification.id,
+        ],
+    )?;
+    
+    let old_value = serde_json::to_string(&old).ok();
+    let new_value = serde_json::to_string(&qualification).ok();
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "QUALIFICATION",
+        Some(&qualification.id.to_string()),
+        old_value.as_deref(),
+        new_value.as_deref(),
+        Some(&format!("Updated qualification for employee {}", old.epf_number)),
+    );
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_employee_qualification(
+    qualification_id: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = require_edit(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let old = conn
+        .query_row(
+            "SELECT id, epf_number, level, title, institute, year, document_path, created_at
+             FROM employee_qualifications WHERE id = ?1",
+            [qualification_id],
+            qualification_from_row,
+        )
+        .map_err(|_| HrmError::not_found("Qualification not found"))?;
+    
+    conn.execute("DELETE FROM employee_qualifications WHERE id = ?1", [qualification_id])?;
+    
+    let old_value = serde_json::to_string(&old).ok();
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "DELETE",
+        "QUALIFICATION",
+        Some(&qualification_id.to_string()),
+        old_value.as_deref(),
+        None,
+        Some(&format!("Deleted {} qualification for employee {}", old.level, old.epf_number)),
+    );
+    
+    Ok(())
+}
+
+// Active employees per department by their highest qualification level.
+// Employees with no recorded qualification are counted as "None".
+#[tauri::command]
+pub fn get_qualification_distribution(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<QualificationDistribution>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_reports {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view reports."));
+    }
+    
+    // Rank levels in SQL so each employee is counted once, at their highest level
+    let rank_case: String = QUALIFICATION_LEVELS
+        .iter()
+        .enumerate()
+        .map(|(i, level)| format!("WHEN '{}' THEN {}", level, i + 1))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let level_case: String = QUALIFICATION_LEVELS
+        .iter()
+        .enumerate()
+        .map(|(i, level)| format!("WHEN {} THEN '{}'", i + 1, level))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let sql = format!(
+        "SELECT COALESCE(NULLIF(e.department, ''), 'Unassigned') AS department,
+                CASE best.rank {} ELSE 'None' END AS level,
+                COUNT(*) AS count
+         FROM employees e
+         LEFT JOIN (
+             SELECT epf_number, MAX(CASE level {} ELSE 0 END) AS rank
+             FROM employee_qualifications GROUP BY epf_number
+         ) best ON best.epf_number = e.epf_number
+         WHERE e.working_status IN (SELECT code FROM working_statuses WHERE counts_as_active = 1)
+         GROUP BY department, level
+         ORDER BY department, COALESCE(best.rank, 0)",
+        level_case, rank_case
+    );
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(QualificationDistribution {
+                department: row.get(0)?,
+                level: row.get(1)?,
+                count: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    Ok(rows)
+}
@@ -0,0 +1,52 @@
+use crate::error::HrmError;
+use tauri::ipc::Invoke;
+use tauri::{Runtime, State};
+
+// Start the app with this flag on the gate-house or notice-board PC to make it
+// a view-only terminal, e.g. a shortcut to "HRM System.exe --read-only"
+pub const READ_ONLY_FLAG: &str = "--read-only";
+
+// Commands that change nothing and are still allowed on a read-only terminal.
+// Anything else is rejected before it runs, so new commands are blocked by
+// default until they are known to be safe here.
+const ALLOWED_PREFIXES: [&str; 4] = ["get_", "list_", "search_", "preview_"];
+const ALLOWED_COMMANDS: [&str; 6] = [
+    "login",
+    "logout",
+    "validate_session",
+    "run_health_check",
+    "find_previous_employment",
+    "simulate_payroll",
+];
+
+pub struct ReadOnlyMode(pub bool);
+
+pub fn requested_on_command_line() -> bool {
+    std::env::args().any(|arg| arg == READ_ONLY_FLAG)
+}
+
+pub fn is_allowed(command: &str) -> bool {
+    ALLOWED_COMMANDS.contains(&command) || ALLOWED_PREFIXES.iter().any(|p| command.starts_with(p))
+}
+
+// Lets the frontend hide add/edit/delete buttons on a read-only terminal
+#[tauri::command]
+pub fn get_read_only_mode(read_only: State<'_, ReadOnlyMode>) -> bool {
+    read_only.0
+}
+
+// Wraps the generated command handler so a read-only terminal rejects every
+// command that is not on the allow list
+pub fn guard<R: Runtime>(
+    enabled: bool,
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        if enabled && !is_allowed(invoke.message.command()) {
+            tracing::warn!("Blocked {} on read-only terminal", invoke.message.command());
+            invoke.resolver.reject(HrmError::read_only());
+            return true;
+        }
+        handler(invoke)
+    }
+}
@@ -0,0 +1,479 @@
+use crate::commands::{find_former_employees, insert_employee, log_audit_action};
+use crate::epf_numbering::allocate_epf_number;
+use crate::error::HrmError;
+use crate::models::{Candidate, Employee, TimeToFill, TimeToFillReport, UserSession, Vacancy};
+use crate::validation::{clear_hidden_fields, normalize_nic, parse_iso_date, validate_employee};
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use tauri::State;
+
+// Pipeline order; "rejected" can be reached from any stage before "joined"
+const STAGES: [&str; 4] = ["applied", "interviewed", "selected", "joined"];
+const VACANCY_STATUSES: [&str; 3] = ["open", "filled", "cancelled"];
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+fn require_edit(user: &UserSession) -> Result<(), HrmError> {
+    if user.permissions.can_edit_employees {
+        Ok(())
+    } else {
+        Err(HrmError::permission_denied("Permission denied. You cannot edit employees."))
+    }
+}
+
+const VACANCY_COLUMNS: &str = "v.id, v.title, v.department, v.cader, v.designation, v.positions, v.status,
+                               v.opened_on, v.closed_on, v.notes, v.created_by,
+                               (SELECT COUNT(*) FROM candidates c WHERE c.vacancy_id = v.id),
+                               (SELECT COUNT(*) FROM candidates c WHERE c.vacancy_id = v.id AND c.stage = 'joined')";
+
+fn vacancy_from_row(row: &rusqlite::Row) -> rusqlite::Result<Vacancy> {
+    Ok(Vacancy {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        department: row.get(2)?,
+        cader: row.get(3)?,
+        designation: row.get(4)?,
+        positions: row.get(5)?,
+        status: row.get(6)?,
+        opened_on: row.get(7)?,
+        closed_on: row.get(8)?,
+        notes: row.get(9)?,
+        created_by: row.get(10)?,
+        candidate_count: row.get(11)?,
+        joined_count: row.get(12)?,
+    })
+}
+
+pub(crate) fn load_vacancy(conn: &Connection, vacancy_id: i64) -> Result<Vacancy, HrmError> {
+    conn.query_row(
+        &format!("SELECT {} FROM vacancies v WHERE v.id = ?1", VACANCY_COLUMNS),
+        [vacancy_id],
+        vacancy_from_row,
+    )
+    .map_err(|_| HrmError::not_found("Vacancy not found"))
+}
+
+const CANDIDATE_COLUMNS: &str = "id, vacancy_id, full_name, name_with_initials, nic, dob, mobile, address,
+                                 applied_on, stage, stage_changed_on, employee_epf_number, notes";
+
+fn candidate_from_row(row: &rusqlite::Row) -> rusqlite::Result<Candidate> {
+    Ok(Candidate {
+        id: row.get(0)?,
+        vacancy_id: row.get(1)?,
+        full_name: row.get(2)?,
+        name_with_initials: row.get(3)?,
+        nic: row.get(4)?,
+        dob: row.get(5)?,
+        mobile: row.get(6)?,
+        address: row.get(7)?,
+        applied_on: row.get(8)?,
+        stage: row.get(9)?,
+        stage_changed_on: row.get(10)?,
+        employee_epf_number: row.get(11)?,
+        notes: row.get(12)?,
+    })
+}
+
+pub(crate) fn load_candidate(conn: &Connection, candidate_id: i64) -> Result<Candidate, HrmError> {
+    conn.query_row(
+        &format!("SELECT {} FROM candidates WHERE id = ?1", CANDIDATE_COLUMNS),
+        [candidate_id],
+        candidate_from_row,
+    )
+    .map_err(|_| HrmError::not_found("Candidate not found"))
+}
+
+#[tauri::command]
+pub fn get_vacancies(status: Option<String>, db: State<'_, DbConnection>) -> Result<Vec<Vacancy>, HrmError> {
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM vacancies v WHERE (?1 IS NULL OR v.status = ?1) ORDER BY v.opened_on DESC, v.id DESC",
+            VACANCY_COLUMNS
+        ))?;
+    let vacancies = stmt
+        .query_map([&status], vacancy_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(vacancies)
+}
+
+#[tauri::command]
+pub fn create_vacancy(
+    vacancy: Vacancy,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_edit(&user)?;
+    let title = vacancy.title.trim();
+    if title.is_empty() {
+        return Err(HrmError::validation("Vacancy title is required"));
+    }
+    if vacancy.positions < 1 {
+        return Err(HrmError::validation("A vacancy must have at least one position"));
+    }
+    parse_iso_date(&vacancy.opened_on).ok_or("Opened on must be a valid date (YYYY-MM-DD)")?;
+    
+    let conn = db.0.lock()?;
+    conn.execute(
+        "INSERT INTO vacancies (title, department, cader, designation, positions, opened_on, notes, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![
+            title,
+            vacancy.department,
+            vacancy.cader,
+            vacancy.designation,
+            vacancy.positions,
+            vacancy.opened_on,
+            vacancy.notes,
+            user.username,
+        ],
+    )?;
+    let vacancy_id = conn.last_insert_rowid();
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "VACANCY",
+        Some(&vacancy_id.to_string()),
+        None,
+        Some(title),
+        Some(&format!("Opened vacancy '{}' for {} position(s)", title, vacancy.positions)),
+    );
+    
+    Ok(vacancy_id)
+}
+
+// Vacancies close as "filled" on their own once enough candidates join; this
+// is for cancelling or reopening
+#[tauri::command]
+pub fn set_vacancy_status(
+    vacancy_id: i64,
+    status: String,
+    closed_on: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_edit(&user)?;
+    if !VACANCY_STATUSES.contains(&status.as_str()) {
+        return Err(HrmError::validation("Status must be open, filled or cancelled"));
+    }
+    let closed_on = if status == "open" {
+        None
+    } else {
+        let date = closed_on.ok_or("Closing date is required")?;
+        parse_iso_date(&date).ok_or("Closing date must be a valid date (YYYY-MM-DD)")?;
+        Some(date)
+    };
+    
+    let conn = db.0.lock()?;
+    let vacancy = load_vacancy(&conn, vacancy_id)?;
+    conn.execute(
+        "UPDATE vacancies SET status = ?1, closed_on = ?2 WHERE id = ?3",
+        rusqlite::params![status, closed_on, vacancy_id],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "VACANCY",
+        Some(&vacancy_id.to_string()),
+        Some(&vacancy.status),
+        Some(&status),
+        Some(&format!("Vacancy '{}' marked {}", vacancy.title, status)),
+    );
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_candidates(vacancy_id: i64, db: State<'_, DbConnection>) -> Result<Vec<Candidate>, HrmError> {
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM candidates WHERE vacancy_id = ?1 ORDER BY applied_on, id",
+            CANDIDATE_COLUMNS
+        ))?;
+    let candidates = stmt
+        .query_map([vacancy_id], candidate_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(candidates)
+}
+
+#[tauri::command]
+pub fn add_candidate(
+    candidate: Candidate,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_edit(&user)?;
+    let full_name = candidate.full_name.trim();
+    if full_name.is_empty() {
+        return Err(HrmError::validation("Candidate name is required"));
+    }
+    parse_iso_date(&candidate.applied_on).ok_or("Application date must be a valid date (YYYY-MM-DD)")?;
+    if let Some(dob) = candidate.dob.as_deref().filter(|d| !d.is_empty()) {
+        parse_iso_date(dob).ok_or("Date of birth must be a valid date (YYYY-MM-DD)")?;
+    }
+    let nic = match candidate.nic.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+        Some(nic) => Some(normalize_nic(nic)?),
+        None => None,
+    };
+    
+    let conn = db.0.lock()?;
+    let vacancy = load_vacancy(&conn, candidate.vacancy_id)?;
+    if vacancy.status != "open" {
+        return Err(HrmError::validation(format!("Vacancy '{}' is not open", vacancy.title)));
+    }
+    conn.execute(
+        "INSERT INTO candidates (vacancy_id, full_name, name_with_initials, nic, dob, mobile, address, applied_on, stage_changed_on, notes)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8, ?9)",
+        rusqlite::params![
+            candidate.vacancy_id,
+            full_name,
+            candidate.name_with_initials.as_deref().map(str::trim).filter(|n| !n.is_empty()),
+            nic,
+            candidate.dob.as_deref().filter(|d| !d.is_empty()),
+            candidate.mobile,
+            candidate.address,
+            candidate.applied_on,
+            candidate.notes,
+        ],
+    )?;
+    let candidate_id = conn.last_insert_rowid();
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "CANDIDATE",
+        Some(&candidate_id.to_string()),
+        None,
+        Some(full_name),
+        Some(&format!("Candidate {} applied for '{}'", full_name, vacancy.title)),
+    );
+    
+    Ok(candidate_id)
+}
+
+// Moves a candidate one or more stages forward, or to "rejected". Joining
+// happens through convert_candidate_to_employee.
+#[tauri::command]
+pub fn move_candidate_stage(
+    candidate_id: i64,
+    stage: String,
+    changed_on: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_edit(&user)?;
+    parse_iso_date(&changed_on).ok_or("Date must be a valid date (YYYY-MM-DD)")?;
+    
+    let conn = db.0.lock()?;
+    let candidate = load_candidate(&conn, candidate_id)?;
+    if candidate.stage == "joined" || candidate.stage == "rejected" {
+        return Err(HrmError::validation(format!("Candidate has already {}", candidate.stage)));
+    }
+    if stage == "joined" {
+        return Err(HrmError::validation("Convert the candidate to an employee to record joining"));
+    }
+    if stage != "rejected" {
+        let from = STAGES.iter().position(|s| *s == candidate.stage).unwrap_or(0);
+        let to = STAGES
+            .iter()
+            .position(|s| *s == stage)
+            .ok_or("Stage must be applied, interviewed, selected or rejected")?;
+        if to <= from {
+            return Err(HrmError::validation(format!("Candidate is already at the {} stage", candidate.stage)));
+        }
+    }
+    
+    conn.execute(
+        "UPDATE candidates SET stage = ?1, stage_changed_on = ?2 WHERE id = ?3",
+        rusqlite::params![stage, changed_on, candidate_id],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "CANDIDATE",
+        Some(&candidate_id.to_string()),
+        Some(&candidate.stage),
+        Some(&stage),
+        Some(&format!("Candidate {} moved to {}", candidate.full_name, stage)),
+    );
+    
+    Ok(())
+}
+
+// Creates the employee record for a selected candidate, pre-filled from the
+// application and the vacancy. A NIC matching a former employee links the
+// new record as a rehire. The vacancy is marked filled once every position
+// has a joined candidate.
+#[tauri::command]
+pub fn convert_candidate_to_employee(
+    candidate_id: i64,
+    epf_number: String,
+    date_of_join: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Employee, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_add_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot add employees."));
+    }
+    parse_iso_date(&date_of_join).ok_or("Date of joining must be a valid date (YYYY-MM-DD)")?;
+    
+    let mut conn = db.0.lock()?;
+    let candidate = load_candidate(&conn, candidate_id)?;
+    if candidate.stage != "selected" {
+        return Err(HrmError::validation("Only selected candidates can be converted to employees"));
+    }
+    let vacancy = load_vacancy(&conn, candidate.vacancy_id)?;
+    
+    let previous_epf_number = match candidate.nic.as_deref() {
+        Some(nic) => find_former_employees(&conn, nic)?
+            .into_iter()
+            .next()
+            .map(|p| p.epf_number),
+        None => None,
+    };
+    let epf_number = allocate_epf_number(&conn, &epf_number)?;
+    let mut employee = Employee {
+        epf_number,
+        name_with_initials: candidate.name_with_initials.clone().unwrap_or_else(|| candidate.full_name.clone()),
+        full_name: candidate.full_name.clone(),
+        nic: candidate.nic.clone(),
+        dob: candidate.dob.clone(),
+        police_area: None,
+        transport_route: None,
+        mobile_1: candidate.mobile.clone(),
+        mobile_2: None,
+        address: candidate.address.clone(),
+        date_of_join: Some(date_of_join.clone()),
+        date_of_resign: None,
+        working_status: "active".to_string(),
+        marital_status: None,
+        cader: vacancy.cader.clone(),
+        designation: vacancy.designation.clone(),
+        allocation: None,
+        department: vacancy.department.clone(),
+        image_path: None,
+        previous_epf_number,
+        district: None,
+        ds_division: None,
+        gn_division: None,
+        created_at: None,
+        custom_fields: HashMap::new(),
+        age: None,
+        service_years: None,
+    };
+    clear_hidden_fields(&conn, &mut employee);
+    validate_employee(&conn, &mut employee)?;
+    
+    let tx = conn.transaction()?;
+    insert_employee(&tx, &employee).map_err(|e| {
+        if e.to_string().contains("UNIQUE constraint") {
+            HrmError::conflict(format!("EPF number {} already exists", employee.epf_number))
+        } else {
+            HrmError::from(e)
+        }
+    })?;
+    tx.execute(
+        "UPDATE candidates SET stage = 'joined', stage_changed_on = ?1, employee_epf_number = ?2 WHERE id = ?3",
+        rusqlite::params![date_of_join, employee.epf_number, candidate_id],
+    )?;
+    if vacancy.status == "open" && vacancy.joined_count + 1 >= vacancy.positions {
+        tx.execute(
+            "UPDATE vacancies SET status = 'filled', closed_on = ?1 WHERE id = ?2",
+            rusqlite::params![date_of_join, vacancy.id],
+        )?;
+    }
+    
+    let new_value = serde_json::to_string(&employee).ok();
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        if employee.previous_epf_number.is_some() { "REHIRE" } else { "CREATE" },
+        "EMPLOYEE",
+        Some(&employee.epf_number),
+        None,
+        new_value.as_deref(),
+        Some(&format!(
+            "Created employee {} ({}) from candidate #{} for vacancy '{}'",
+            employee.name_with_initials, employee.epf_number, candidate_id, vacancy.title
+        )),
+    );
+    tx.commit()?;
+    
+    Ok(employee)
+}
+
+// Days from opening to filling for vacancies opened in `year` (or all
+// years); open vacancies show how long they have been waiting so far
+#[tauri::command]
+pub fn get_time_to_fill_report(
+    year: Option<i32>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<TimeToFillReport, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_reports {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view reports."));
+    }
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT v.id, v.title, v.department, v.status, v.positions, v.opened_on, v.closed_on,
+                    (SELECT COUNT(*) FROM candidates c WHERE c.vacancy_id = v.id AND c.stage = 'joined'),
+                    CAST(julianday(COALESCE(v.closed_on, date('now', 'localtime'))) - julianday(v.opened_on) AS INTEGER)
+             FROM vacancies v
+             WHERE v.status != 'cancelled' AND (?1 IS NULL OR CAST(substr(v.opened_on, 1, 4) AS INTEGER) = ?1)
+             ORDER BY v.opened_on",
+        )?;
+    let vacancies = stmt
+        .query_map([year], |row| {
+            Ok(TimeToFill {
+                vacancy_id: row.get(0)?,
+                title: row.get(1)?,
+                department: row.get(2)?,
+                status: row.get(3)?,
+                positions: row.get(4)?,
+                opened_on: row.get(5)?,
+                closed_on: row.get(6)?,
+                joined_count: row.get(7)?,
+                days: row.get(8)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    let filled: Vec<i64> = vacancies.iter().filter(|v| v.status == "filled").map(|v| v.days).collect();
+    let average_days_to_fill = if filled.is_empty() {
+        None
+    } else {
+        Some(filled.iter().sum::<i64>() as f64 / filled.len() as f64)
+    };
+    
+    Ok(TimeToFillReport {
+        filled_count: filled.len() as i32,
+        open_count: vacancies.iter().filter(|v| v.status == "open").count() as i32,
+        average_days_to_fill,
+        vacancies,
+    })
+}
@@ -0,0 +1,284 @@
+use crate::anonymization::{anonymization_due, anonymize};
+use crate::audit_archive::{archived_years, delete_archive_year};
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{RetentionPreview, RetentionRule, UserSession};
+use crate::{AppDataDir, CurrentUser, DbConnection};
+use rusqlite::Connection;
+use std::path::Path;
+use tauri::State;
+
+// Rules the engine knows how to apply, as (key, description)
+const RULES: [(&str, &str); 4] = [
+    ("audit_logs", "Permanently delete audit log entries, live and archived"),
+    ("resigned_employees", "Anonymize personal details of resigned employees"),
+    ("visitors", "Delete visitor log entries"),
+    ("notifications", "Delete read notifications"),
+];
+const UNITS: [&str; 3] = ["days", "months", "years"];
+// Affected records listed in a dry run
+const SAMPLE_SIZE: usize = 20;
+
+fn require_settings_permission(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_manage_settings => Ok(session.clone()),
+        Some(_) => Err(HrmError::permission_denied("Permission denied. You cannot change system settings.")),
+        None => Err(HrmError::not_logged_in()),
+    }
+}
+
+fn load_rules(conn: &Connection) -> rusqlite::Result<Vec<RetentionRule>> {
+    let mut stmt = conn.prepare(
+        "SELECT rule_key, retain_value, retain_unit, is_enabled, confirmed_at, last_run_at, last_run_count, updated_by
+         FROM retention_rules ORDER BY rule_key",
+    )?;
+    let rules = stmt
+        .query_map([], |row| {
+            let key: String = row.get(0)?;
+            Ok(RetentionRule {
+                description: RULES.iter().find(|(k, _)| *k == key).map(|(_, d)| d.to_string()).unwrap_or_default(),
+                rule_key: key,
+                retain_value: row.get(1)?,
+                retain_unit: row.get(2)?,
+                is_enabled: row.get(3)?,
+                confirmed_at: row.get(4)?,
+                last_run_at: row.get(5)?,
+                last_run_count: row.get(6)?,
+                updated_by: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rules)
+}
+
+// The SQLite date modifier for a rule's retention period, e.g. "-7 years"
+fn age(rule: &RetentionRule) -> String {
+    format!("-{} {}", rule.retain_value, rule.retain_unit)
+}
+
+fn cutoff(conn: &Connection, rule: &RetentionRule) -> rusqlite::Result<String> {
+    conn.query_row("SELECT datetime('now', ?1)", [age(rule)], |row| row.get(0))
+}
+
+// What a rule would remove right now: (count, sample of affected records)
+fn affected(conn: &Connection, app_dir: &Path, rule: &RetentionRule) -> Result<(i64, Vec<String>), HrmError> {
+    let cutoff = cutoff(conn, rule)?;
+    let (count_sql, sample_sql) = match rule.rule_key.as_str() {
+        "resigned_employees" => {
+            let due = anonymization_due(conn, &age(rule))?;
+            let sample = due
+                .iter()
+                .take(SAMPLE_SIZE)
+                .map(|c| format!("{} {} (resigned {})", c.epf_number, c.name_with_initials, c.date_of_resign.as_deref().unwrap_or("")))
+                .collect();
+            return Ok((due.len() as i64, sample));
+        }
+        "audit_logs" => (
+            "SELECT COUNT(*) FROM audit_logs WHERE created_at < ?1",
+            "SELECT created_at || ' ' || action || ' ' || entity_type FROM audit_logs WHERE created_at < ?1 ORDER BY id LIMIT ?2",
+        ),
+        "visitors" => (
+            "SELECT COUNT(*) FROM visitors WHERE time_in < ?1",
+            "SELECT time_in || ' ' || name FROM visitors WHERE time_in < ?1 ORDER BY time_in LIMIT ?2",
+        ),
+        "notifications" => (
+            "SELECT COUNT(*) FROM notifications WHERE is_read = 1 AND created_at < ?1",
+            "SELECT created_at || ' ' || title FROM notifications WHERE is_read = 1 AND created_at < ?1 ORDER BY id LIMIT ?2",
+        ),
+        other => return Err(HrmError::validation(format!("Unknown retention rule '{}'", other))),
+    };
+    let mut count: i64 = conn.query_row(count_sql, [&cutoff], |row| row.get(0))?;
+    let mut stmt = conn.prepare(sample_sql)?;
+    let mut sample = stmt
+        .query_map(rusqlite::params![cutoff, SAMPLE_SIZE as i64], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    // Whole archive years that end before the cutoff are deleted too
+    if rule.rule_key == "audit_logs" {
+        for year in expired_archive_years(app_dir, &cutoff)? {
+            count += 1;
+            sample.push(format!("Archived audit log for {}", year));
+        }
+    }
+    Ok((count, sample))
+}
+
+fn expired_archive_years(app_dir: &Path, cutoff: &str) -> Result<Vec<String>, HrmError> {
+    let cutoff_year = cutoff.get(0..4).unwrap_or("0000");
+    Ok(archived_years(app_dir)?.into_iter().filter(|y| y.as_str() < cutoff_year).collect())
+}
+
+// Applies one rule. Returns the number of records removed or anonymized.
+fn apply_rule(conn: &mut Connection, app_dir: &Path, rule: &RetentionRule) -> Result<i64, HrmError> {
+    let cutoff = cutoff(conn, rule)?;
+    let removed = match rule.rule_key.as_str() {
+        "resigned_employees" => {
+            let due = anonymization_due(conn, &age(rule))?;
+            for candidate in &due {
+                anonymize(conn, app_dir, &candidate.epf_number)?;
+            }
+            due.len() as i64
+        }
+        "audit_logs" => {
+            let mut removed = conn
+                .execute("DELETE FROM audit_logs WHERE created_at < ?1", [&cutoff])? as i64;
+            for year in expired_archive_years(app_dir, &cutoff)? {
+                delete_archive_year(app_dir, &year)?;
+                removed += 1;
+            }
+            removed
+        }
+        "visitors" => conn
+            .execute("DELETE FROM visitors WHERE time_in < ?1", [&cutoff])? as i64,
+        "notifications" => conn
+            .execute("DELETE FROM notifications WHERE is_read = 1 AND created_at < ?1", [&cutoff])? as i64,
+        other => return Err(HrmError::validation(format!("Unknown retention rule '{}'", other))),
+    };
+    conn.execute(
+        "UPDATE retention_rules SET last_run_at = datetime('now', 'localtime'), last_run_count = ?1 WHERE rule_key = ?2",
+        rusqlite::params![removed, rule.rule_key],
+    )?;
+    Ok(removed)
+}
+
+// Runs every enabled rule whose dry run has been confirmed since it was last
+// changed. Called by the scheduler; returns (rule, records affected) for
+// rules that did something.
+pub fn run_retention_rules(conn: &mut Connection, app_dir: &Path) -> Result<Vec<(String, i64)>, HrmError> {
+    let rules = load_rules(conn)?;
+    let mut results = Vec::new();
+    for rule in rules.iter().filter(|r| r.is_enabled && r.confirmed_at.is_some()) {
+        let removed = apply_rule(conn, app_dir, rule)?;
+        if removed > 0 {
+            log_audit_action(
+                conn,
+                None,
+                "system",
+                "RETENTION",
+                "RETENTION_RULE",
+                Some(&rule.rule_key),
+                None,
+                Some(&removed.to_string()),
+                Some(&format!(
+                    "Retention rule '{}' ({} {}) affected {} record(s)",
+                    rule.rule_key, rule.retain_value, rule.retain_unit, removed
+                )),
+            );
+            results.push((rule.rule_key.clone(), removed));
+        }
+    }
+    Ok(results)
+}
+
+#[tauri::command]
+pub fn get_retention_rules(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<RetentionRule>, HrmError> {
+    require_settings_permission(&current_user)?;
+    let conn = db.0.lock()?;
+    load_rules(&conn).map_err(HrmError::from)
+}
+
+// Changing a rule clears its confirmation, so the scheduler will not act on
+// it until a new dry run has been reviewed and confirmed
+#[tauri::command]
+pub fn save_retention_rule(
+    rule_key: String,
+    retain_value: i64,
+    retain_unit: String,
+    is_enabled: bool,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = require_settings_permission(&current_user)?;
+    if !RULES.iter().any(|(key, _)| *key == rule_key) {
+        return Err(HrmError::validation(format!("Unknown retention rule '{}'", rule_key)));
+    }
+    if retain_value < 1 {
+        return Err(HrmError::validation("Retention period must be at least 1"));
+    }
+    if !UNITS.contains(&retain_unit.as_str()) {
+        return Err(HrmError::validation("Retention unit must be days, months or years"));
+    }
+    
+    let conn = db.0.lock()?;
+    conn.execute(
+        "UPDATE retention_rules SET retain_value = ?1, retain_unit = ?2, is_enabled = ?3, confirmed_at = NULL,
+                                    updated_by = ?4, updated_at = CURRENT_TIMESTAMP
+         WHERE rule_key = ?5",
+        rusqlite::params![retain_value, retain_unit, is_enabled, user.username, rule_key],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "RETENTION_RULE",
+        Some(&rule_key),
+        None,
+        Some(&format!("{} {}", retain_value, retain_unit)),
+        Some(&format!(
+            "Retention rule '{}' set to {} {} ({})",
+            rule_key,
+            retain_value,
+            retain_unit,
+            if is_enabled { "enabled, awaiting confirmation" } else { "disabled" }
+        )),
+    );
+    
+    Ok(())
+}
+
+// Dry run: what each rule would remove now. Nothing is changed.
+#[tauri::command]
+pub fn get_retention_dry_run(
+    app_data_dir: State<'_, AppDataDir>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<RetentionPreview>, HrmError> {
+    require_settings_permission(&current_user)?;
+    let conn = db.0.lock()?;
+    let mut preview = Vec::new();
+    for rule in load_rules(&conn)? {
+        let (affected_count, sample) = affected(&conn, &app_data_dir.0, &rule)?;
+        preview.push(RetentionPreview { rule, affected_count, sample });
+    }
+    Ok(preview)
+}
+
+// Confirms a rule after reviewing its dry run, allowing the scheduler to
+// apply it. The rule must be enabled.
+#[tauri::command]
+pub fn confirm_retention_rule(
+    rule_key: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = require_settings_permission(&current_user)?;
+    let conn = db.0.lock()?;
+    let updated = conn
+        .execute(
+            "UPDATE retention_rules SET confirmed_at = datetime('now', 'localtime') WHERE rule_key = ?1 AND is_enabled = 1",
+            [&rule_key],
+        )?;
+    if updated == 0 {
+        return Err(HrmError::validation("Enable the rule before confirming it"));
+    }
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CONFIRM",
+        "RETENTION_RULE",
+        Some(&rule_key),
+        None,
+        None,
+        Some(&format!("Confirmed retention rule '{}' after dry run", rule_key)),
+    );
+    
+    Ok(())
+}
@@ -0,0 +1,186 @@
+use crate::commands::log_audit_action;
+use crate::currency_commands::{normalize_currency, BASE_CURRENCY};
+use crate::error::HrmError;
+use crate::models::{IncrementSummary, SalaryRevision, UserSession};
+use crate::payroll_commands::ensure_open_from;
+use crate::validation::parse_iso_date;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::{Connection, OptionalExtension};
+use tauri::State;
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+fn require_salary_access(user: &UserSession) -> Result<(), HrmError> {
+    if user.permissions.can_view_salaries {
+        Ok(())
+    } else {
+        Err(HrmError::permission_denied("Permission denied. You cannot view salary details."))
+    }
+}
+
+// Basic salary and its currency in force on a date (YYYY-MM-DD), for payroll
+// runs. None if the employee has no revision effective by then.
+pub fn effective_salary(conn: &Connection, epf_number: &str, date: &str) -> rusqlite::Result<Option<(f64, String)>> {
+    conn.query_row(
+        "SELECT new_salary, currency FROM salary_revisions
+         WHERE epf_number = ?1 AND effective_date <= ?2
+         ORDER BY effective_date DESC LIMIT 1",
+        [epf_number, date],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .optional()
+}
+
+#[tauri::command]
+pub fn get_salary_revisions(
+    epf_number: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<SalaryRevision>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_salary_access(&user)?;
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, epf_number, old_salary, new_salary, effective_date, reason, approved_by, created_at, currency
+             FROM salary_revisions WHERE epf_number = ?1 ORDER BY effective_date DESC",
+        )?;
+    let revisions = stmt
+        .query_map([&epf_number], |row| {
+            let old_salary: Option<f64> = row.get(2)?;
+            let new_salary: f64 = row.get(3)?;
+            Ok(SalaryRevision {
+                id: row.get(0)?,
+                epf_number: row.get(1)?,
+                old_salary,
+                new_salary,
+                currency: row.get(8)?,
+                increment_percent: old_salary.filter(|o| *o > 0.0).map(|o| (new_salary - o) / o * 100.0),
+                effective_date: row.get(4)?,
+                reason: row.get(5)?,
+                approved_by: row.get(6)?,
+                created_at: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(revisions)
+}
+
+// Records a salary change approved by the logged-in user. The old salary is
+// taken from the previous revision; the first revision sets the starting
+// salary. Revisions must be entered in date order. `currency` defaults to LKR;
+// when it differs from the previous revision's there is no old salary to
+// compare with, so the revision counts as a starting salary.
+#[tauri::command]
+pub fn record_salary_revision(
+    epf_number: String,
+    new_salary: f64,
+    currency: Option<String>,
+    effective_date: String,
+    reason: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_manage_users {
+        return Err(HrmError::permission_denied("Permission denied. You cannot approve salary revisions."));
+    }
+    if !new_salary.is_finite() || new_salary <= 0.0 {
+        return Err(HrmError::validation("Salary must be greater than zero"));
+    }
+    let currency = normalize_currency(currency.as_deref().unwrap_or(BASE_CURRENCY))?;
+    parse_iso_date(&effective_date).ok_or("Effective date must be a valid date (YYYY-MM-DD)")?;
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Err(HrmError::validation("A reason is required"));
+    }
+    
+    let conn = db.0.lock()?;
+    let employee_exists: bool = conn
+        .query_row("SELECT COUNT(*) > 0 FROM employees WHERE epf_number = ?1", [&epf_number], |row| row.get(0))?;
+    if !employee_exists {
+        return Err(HrmError::not_found(format!("Employee {} not found", epf_number)));
+    }
+    ensure_open_from(&conn, &effective_date)?;
+    let latest: Option<(String, f64, String)> = conn
+        .query_row(
+            "SELECT effective_date, new_salary, currency FROM salary_revisions
+             WHERE epf_number = ?1 ORDER BY effective_date DESC LIMIT 1",
+            [&epf_number],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?;
+    if let Some((latest_date, _, _)) = &latest {
+        if effective_date <= *latest_date {
+            return Err(HrmError::validation(format!("The latest revision is effective from {}; a new one must come after it", latest_date)));
+        }
+    }
+    let old_salary = latest.filter(|(_, _, c)| *c == currency).map(|(_, salary, _)| salary);
+    
+    conn.execute(
+        "INSERT INTO salary_revisions (epf_number, old_salary, new_salary, effective_date, reason, approved_by, currency)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![epf_number, old_salary, new_salary, effective_date, reason, user.username, currency],
+    )?;
+    let revision_id = conn.last_insert_rowid();
+    
+    // Amounts stay out of the audit text; the log is readable by non-HR users
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "SALARY_REVISION",
+        "EMPLOYEE",
+        Some(&epf_number),
+        None,
+        None,
+        Some(&format!("Salary revised from {} ({})", effective_date, reason)),
+    );
+    
+    Ok(revision_id)
+}
+
+// Average, lowest and highest increment per department for revisions
+// effective in a year. Starting salaries (no previous salary) are left out.
+#[tauri::command]
+pub fn get_increment_report(
+    year: i32,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<IncrementSummary>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_reports {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view reports."));
+    }
+    require_salary_access(&user)?;
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT COALESCE(e.department, 'Unassigned'), COUNT(*),
+                    AVG((r.new_salary - r.old_salary) * 100.0 / r.old_salary),
+                    MIN((r.new_salary - r.old_salary) * 100.0 / r.old_salary),
+                    MAX((r.new_salary - r.old_salary) * 100.0 / r.old_salary)
+             FROM salary_revisions r
+             LEFT JOIN employees e ON e.epf_number = r.epf_number
+             WHERE r.old_salary > 0 AND substr(r.effective_date, 1, 4) = ?1
+             GROUP BY COALESCE(e.department, 'Unassigned')
+             ORDER BY 1",
+        )?;
+    let report = stmt
+        .query_map([format!("{:04}", year)], |row| {
+            Ok(IncrementSummary {
+                department: row.get(0)?,
+                revision_count: row.get(1)?,
+                average_percent: row.get(2)?,
+                min_percent: row.get(3)?,
+                max_percent: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(report)
+}
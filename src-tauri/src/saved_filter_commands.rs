@@ -0,0 +1,115 @@
+use crate::commands::query_employees;
+use crate::error::HrmError;
+use crate::models::{Employee, EmployeeFilters, SavedFilter, UserSession};
+use crate::{CurrentUser, DbConnection};
+use rusqlite::{Connection, OptionalExtension};
+use tauri::State;
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+// Presets are private to the user who saved them
+fn load_filters(conn: &Connection, user_id: i32, name: &str) -> Result<EmployeeFilters, HrmError> {
+    let filters: String = conn
+        .query_row(
+            "SELECT filters FROM saved_filters WHERE user_id = ?1 AND name = ?2",
+            rusqlite::params![user_id, name],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| HrmError::not_found(format!("Saved filter '{}' not found", name)))?;
+    Ok(serde_json::from_str(&filters)?)
+}
+
+#[tauri::command]
+pub fn get_saved_filters(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<SavedFilter>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, name, filters, created_at, updated_at FROM saved_filters WHERE user_id = ?1 ORDER BY name",
+    )?;
+    let rows = stmt
+        .query_map([user.user_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    rows.into_iter()
+        .map(|(id, name, filters, created_at, updated_at)| {
+            Ok(SavedFilter { id, name, filters: serde_json::from_str(&filters)?, created_at, updated_at })
+        })
+        .collect()
+}
+
+// Saves the filters under a name, replacing the user's preset of that name.
+// Returns the preset id.
+#[tauri::command]
+pub fn save_filter(
+    name: String,
+    filters: EmployeeFilters,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(HrmError::field("name", "Filter name is required"));
+    }
+    
+    let conn = db.0.lock()?;
+    conn.execute(
+        "INSERT INTO saved_filters (user_id, name, filters) VALUES (?1, ?2, ?3)
+         ON CONFLICT(user_id, name) DO UPDATE SET filters = excluded.filters, updated_at = CURRENT_TIMESTAMP",
+        rusqlite::params![user.user_id, name, serde_json::to_string(&filters)?],
+    )?;
+    let id = conn.query_row(
+        "SELECT id FROM saved_filters WHERE user_id = ?1 AND name = ?2",
+        rusqlite::params![user.user_id, name],
+        |row| row.get(0),
+    )?;
+    Ok(id)
+}
+
+#[tauri::command]
+pub fn delete_saved_filter(
+    name: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let deleted = conn.execute(
+        "DELETE FROM saved_filters WHERE user_id = ?1 AND name = ?2",
+        rusqlite::params![user.user_id, name],
+    )?;
+    if deleted == 0 {
+        return Err(HrmError::not_found(format!("Saved filter '{}' not found", name)));
+    }
+    Ok(())
+}
+
+// Employees matching one of the user's saved presets
+#[tauri::command]
+pub fn get_employees_by_saved_filter(
+    name: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<Employee>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let filters = load_filters(&conn, user.user_id, &name)?;
+    query_employees(&conn, filters)
+}
@@ -0,0 +1,104 @@
+use crate::{attendance_device, audit_archive, backup, cloud_backup, compliance_commands, headcount_commands, license_commands, retention, task_commands, AppDataDir, DbConnection};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+// How often the background scheduler wakes up to run maintenance jobs
+const TICK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const STARTUP_DELAY: Duration = Duration::from_secs(60);
+
+pub fn start(app_handle: AppHandle) {
+    thread::spawn(move || {
+        // Let the UI finish loading before doing maintenance work
+        thread::sleep(STARTUP_DELAY);
+        loop {
+            run_jobs(&app_handle);
+            thread::sleep(TICK_INTERVAL);
+        }
+    });
+}
+
+fn run_jobs(app_handle: &AppHandle) {
+    let app_dir = app_handle.state::<AppDataDir>().0.clone();
+    let db = app_handle.state::<DbConnection>();
+    let mut conn = match db.0.lock() {
+        Ok(conn) => conn,
+        Err(e) => {
+            tracing::error!("Scheduler could not lock database: {}", e);
+            return;
+        }
+    };
+    
+    match backup::run_automatic_backup(&conn, &app_dir) {
+        Ok(None) => {}
+        Ok(Some(path)) => tracing::info!("Automatic backup written and verified: {:?}", path),
+        Err(e) => tracing::error!("Automatic backup job failed: {}", e),
+    }
+    
+    match audit_archive::archive_expired_logs(&conn, &app_dir) {
+        Ok(0) => {}
+        Ok(count) => tracing::info!("Archived {} audit log entries", count),
+        Err(e) => tracing::error!("Audit log archive job failed: {}", e),
+    }
+    
+    match headcount_commands::snapshot_headcount(&conn) {
+        Ok(0) => {}
+        Ok(count) => tracing::info!("Stored headcount snapshot ({} rows)", count),
+        Err(e) => tracing::error!("Headcount snapshot job failed: {}", e),
+    }
+    
+    match attendance_device::check_heartbeats(&conn) {
+        Ok(0) => {}
+        Ok(count) => tracing::warn!("{} attendance device(s) went offline", count),
+        Err(e) => tracing::error!("Attendance device heartbeat check failed: {}", e),
+    }
+    
+    match compliance_commands::check_working_hours(&conn) {
+        Ok(0) => {}
+        Ok(count) => tracing::warn!("{} working hours limit breach(es) found", count),
+        Err(e) => tracing::error!("Working hours check failed: {}", e),
+    }
+    
+    match license_commands::check_license_expiry(&conn) {
+        Ok(0) => {}
+        Ok(count) => tracing::warn!("{} licence expiry alert(s) sent", count),
+        Err(e) => tracing::error!("Licence expiry check failed: {}", e),
+    }
+    
+    match task_commands::check_overdue_tasks(&conn) {
+        Ok(0) => {}
+        Ok(count) => tracing::info!("{} overdue task reminder(s) sent", count),
+        Err(e) => tracing::error!("Overdue task check failed: {}", e),
+    }
+    
+    match retention::run_retention_rules(&mut conn, &app_dir) {
+        Ok(results) => {
+            for (rule, count) in results {
+                tracing::info!("Retention rule {} affected {} records", rule, count);
+            }
+        }
+        Err(e) => tracing::error!("Retention job failed: {}", e),
+    }
+    
+    // The upload can take minutes, so it runs without holding the database
+    let pending = match cloud_backup::pending_upload(&conn, &app_dir) {
+        Ok(pending) => pending,
+        Err(e) => {
+            tracing::error!("Cloud backup is misconfigured: {}", e);
+            None
+        }
+    };
+    drop(conn);
+    if let Some(pending) = pending {
+        let result = cloud_backup::upload(&pending);
+        match &result {
+            Ok(object_key) => tracing::info!("Uploaded backup {} to {}", pending.backup_id, object_key),
+            Err(e) => tracing::error!("Cloud backup upload failed: {}", e),
+        }
+        if let Ok(conn) = db.0.lock() {
+            if let Err(e) = cloud_backup::record_upload(&conn, &pending.backup_id, &result) {
+                tracing::error!("Failed to record cloud backup result: {}", e);
+            }
+        }
+    }
+}
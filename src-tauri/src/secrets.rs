@@ -0,0 +1,51 @@
+use crate::error::HrmError;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::{engine::general_purpose, Engine as _};
+use std::fs;
+use std::path::Path;
+
+// Credentials kept in app_settings are encrypted with a key that only exists
+// on this PC, so a copied database or an offsite backup does not expose them
+const KEY_FILENAME: &str = "secret.key";
+const PREFIX: &str = "enc:v1:";
+const NONCE_LEN: usize = 12;
+
+fn load_key(app_dir: &Path) -> Result<Key<Aes256Gcm>, HrmError> {
+    let path = app_dir.join(KEY_FILENAME);
+    if let Ok(bytes) = fs::read(&path) {
+        if bytes.len() == 32 {
+            return Ok(*Key::<Aes256Gcm>::from_slice(&bytes));
+        }
+        return Err(HrmError::internal("The local secret key is damaged. Re-enter the saved credentials."));
+    }
+    let key = Aes256Gcm::generate_key(OsRng);
+    crate::journal::write_atomic(&path, &key)?;
+    Ok(key)
+}
+
+pub fn encrypt(app_dir: &Path, plain: &str) -> Result<String, HrmError> {
+    let cipher = Aes256Gcm::new(&load_key(app_dir)?);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let mut sealed = nonce.to_vec();
+    sealed.extend(
+        cipher
+            .encrypt(&nonce, plain.as_bytes())
+            .map_err(|_| HrmError::internal("Failed to encrypt secret"))?,
+    );
+    Ok(format!("{}{}", PREFIX, general_purpose::STANDARD.encode(sealed)))
+}
+
+pub fn decrypt(app_dir: &Path, stored: &str) -> Result<String, HrmError> {
+    let unreadable = || HrmError::internal("A saved credential could not be decrypted on this PC. Please enter it again.");
+    let sealed = stored
+        .strip_prefix(PREFIX)
+        .and_then(|data| general_purpose::STANDARD.decode(data).ok())
+        .filter(|sealed| sealed.len() > NONCE_LEN)
+        .ok_or_else(unreadable)?;
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let plain = Aes256Gcm::new(&load_key(app_dir)?)
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| unreadable())?;
+    String::from_utf8(plain).map_err(|_| unreadable())
+}
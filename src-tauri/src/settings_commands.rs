@@ -0,0 +1,265 @@
+use crate::attendance_commands::parse_summary_layout;
+use crate::cloud_backup::SECRET_SETTINGS;
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::localization::LANGUAGES;
+use crate::logging::{self, LOG_LEVELS};
+use crate::models::FieldConfig;
+use crate::validation::{field_config, CORE_FIELDS, DATE_FORMATS};
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use tauri::State;
+
+// Known settings and the kind of value each one accepts
+const NUMERIC_SETTINGS: [&str; 23] = [
+    "anonymize_after_years",
+    "attendance_listener_port",
+    "attendance_standard_day_hours",
+    "audit_retention_days",
+    "backup_warning_days",
+    "bulk_delete_threshold",
+    "bulk_delete_window_minutes",
+    "epf_number_digits",
+    "festival_advance_cap",
+    "festival_advance_max_installments",
+    "festival_advance_min_service_months",
+    "grievance_sla_days",
+    "image_max_dimension",
+    "image_quality",
+    "license_expiry_warning_days",
+    "meal_price",
+    "meal_subsidy_percent",
+    "payroll_no_pay_divisor",
+    "payroll_ot_rate_percent",
+    "payroll_variance_ot_hours",
+    "payroll_variance_percent",
+    "working_hours_max_consecutive_days",
+    "working_hours_weekly_limit",
+];
+const BOOLEAN_SETTINGS: [&str; 5] = [
+    "attendance_corrections_auto_approve",
+    "attendance_ot_requires_approval",
+    "attendance_listener_enabled",
+    "audit_read_access",
+    "date_buddhist_era",
+];
+
+pub fn get_setting(conn: &Connection, key: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [key],
+        |row| row.get::<_, Option<String>>(0),
+    )
+    .ok()
+    .flatten()
+}
+
+pub fn get_setting_i64(conn: &Connection, key: &str) -> Option<i64> {
+    get_setting(conn, key).and_then(|v| v.trim().parse::<i64>().ok())
+}
+
+pub fn get_setting_bool(conn: &Connection, key: &str) -> bool {
+    matches!(get_setting(conn, key).as_deref(), Some("true") | Some("1"))
+}
+
+// How exports and documents show dates: the date_format setting (DD/MM/YYYY
+// unless set) and whether years are shown in the Buddhist Era
+pub fn date_display(conn: &Connection) -> (String, bool) {
+    let format = get_setting(conn, "date_format")
+        .filter(|f| DATE_FORMATS.contains(&f.as_str()))
+        .unwrap_or_else(|| DATE_FORMATS[0].to_string());
+    (format, get_setting_bool(conn, "date_buddhist_era"))
+}
+
+pub fn set_setting(conn: &Connection, key: &str, value: Option<&str>) -> Result<(), HrmError> {
+    conn.execute(
+        "INSERT INTO app_settings (key, value, updated_at) VALUES (?1, ?2, CURRENT_TIMESTAMP)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = CURRENT_TIMESTAMP",
+        rusqlite::params![key, value],
+    )?;
+    Ok(())
+}
+
+fn validate_setting(key: &str, value: Option<&str>) -> Result<(), HrmError> {
+    if SECRET_SETTINGS.contains(&key) {
+        return Err(HrmError::validation("This setting can only be changed from the cloud backup settings"));
+    }
+    if let Some(value) = value {
+        if NUMERIC_SETTINGS.contains(&key) && value.trim().parse::<i64>().map(|n| n < 0).unwrap_or(true) {
+            return Err(HrmError::validation(format!("Setting '{}' must be a non-negative number", key)));
+        }
+        if BOOLEAN_SETTINGS.contains(&key) && !["true", "false"].contains(&value) {
+            return Err(HrmError::validation(format!("Setting '{}' must be true or false", key)));
+        }
+        if key == "image_quality" && !(1..=100).contains(&value.trim().parse::<i64>().unwrap_or(0)) {
+            return Err(HrmError::validation("Image quality must be between 1 and 100"));
+        }
+        if key == "meal_subsidy_percent" && value.trim().parse::<i64>().unwrap_or(0) > 100 {
+            return Err(HrmError::validation("Meal subsidy cannot exceed 100 percent"));
+        }
+        if key == "image_max_dimension" && value.trim().parse::<i64>().unwrap_or(0) < 64 {
+            return Err(HrmError::validation("Maximum image dimension must be at least 64 pixels"));
+        }
+        if key == "epf_number_digits" && value.trim().parse::<i64>().unwrap_or(0) > 10 {
+            return Err(HrmError::validation("EPF numbers can have at most 10 digits"));
+        }
+        if key == "epf_number_prefix" && !value.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '/') {
+            return Err(HrmError::validation("EPF number prefix may only contain letters, digits, - and /"));
+        }
+        if key == "date_format" && !DATE_FORMATS.contains(&value) {
+            return Err(HrmError::validation(format!("Date format must be one of: {}", DATE_FORMATS.join(", "))));
+        }
+        if key == "document_language" && !LANGUAGES.contains(&value) {
+            return Err(HrmError::validation("Document language must be en, si or ta"));
+        }
+        if key == "attendance_listener_port" && !(1..=65535).contains(&value.trim().parse::<i64>().unwrap_or(0)) {
+            return Err(HrmError::validation("Listener port must be between 1 and 65535"));
+        }
+        if key == "attendance_listener_address" && value.trim().parse::<std::net::IpAddr>().is_err() {
+            return Err(HrmError::validation("Listener address must be an IP address of this PC, e.g. 192.168.1.10"));
+        }
+        if key == "attendance_standard_day_hours" && !(1..=24).contains(&value.trim().parse::<i64>().unwrap_or(0)) {
+            return Err(HrmError::validation("Standard day hours must be between 1 and 24"));
+        }
+        if key == "attendance_weekly_off_days"
+            && !value.split(',').filter(|d| !d.trim().is_empty()).all(|d| matches!(d.trim().parse::<u32>(), Ok(0..=6)))
+        {
+            return Err(HrmError::validation("Weekly off days must be weekday numbers from 0 (Sunday) to 6"));
+        }
+        if key == "attendance_export_columns" {
+            let columns: Vec<String> = value.split(',').filter(|c| !c.trim().is_empty()).map(str::to_string).collect();
+            parse_summary_layout(&columns)?;
+        }
+        if key == "log_level" && !LOG_LEVELS.contains(&value) {
+            return Err(HrmError::validation("Log level must be error, warn, info, debug or trace"));
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_app_settings(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<HashMap<String, String>, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    if user_lock.is_none() {
+        return Err(HrmError::not_logged_in());
+    }
+    drop(user_lock);
+    
+    let conn = db.0.lock()?;
+    
+    let mut stmt = conn
+        .prepare("SELECT key, value FROM app_settings WHERE value IS NOT NULL ORDER BY key")?;
+    
+    let settings = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+        .filter(|setting| !matches!(setting, Ok((key, _)) if SECRET_SETTINGS.contains(&key.as_str())))
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    
+    Ok(settings)
+}
+
+#[tauri::command]
+pub fn update_app_setting(
+    key: String,
+    value: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user_lock = current_user.0.lock()?;
+    let (user_id, username) = match &*user_lock {
+        Some(session) if session.permissions.can_manage_settings => (session.user_id, session.username.clone()),
+        _ => return Err(HrmError::permission_denied("Permission denied. You cannot change system settings.")),
+    };
+    drop(user_lock);
+    
+    validate_setting(&key, value.as_deref())?;
+    
+    let conn = db.0.lock()?;
+    let old_value = get_setting(&conn, &key);
+    set_setting(&conn, &key, value.as_deref())?;
+    if key == "log_level" {
+        logging::set_level(value.as_deref().unwrap_or("info"));
+    }
+    
+    log_audit_action(
+        &conn,
+        Some(user_id),
+        &username,
+        "UPDATE",
+        "SETTING",
+        Some(&key),
+        old_value.as_deref(),
+        value.as_deref(),
+        Some(&format!("Changed setting: {}", key)),
+    );
+    
+    Ok(())
+}
+
+// Required/optional/hidden state of every employee field, for building forms
+#[tauri::command]
+pub fn get_field_config(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<FieldConfig>, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    if user_lock.is_none() {
+        return Err(HrmError::not_logged_in());
+    }
+    drop(user_lock);
+    
+    let conn = db.0.lock()?;
+    Ok(field_config(&conn))
+}
+
+#[tauri::command]
+pub fn update_field_config(
+    field: String,
+    visibility: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user_lock = current_user.0.lock()?;
+    let (user_id, username) = match &*user_lock {
+        Some(session) if session.permissions.can_manage_settings => (session.user_id, session.username.clone()),
+        _ => return Err(HrmError::permission_denied("Permission denied. You cannot change system settings.")),
+    };
+    drop(user_lock);
+    
+    if !["required", "optional", "hidden"].contains(&visibility.as_str()) {
+        return Err(HrmError::validation("Visibility must be required, optional or hidden"));
+    }
+    
+    let conn = db.0.lock()?;
+    let current = field_config(&conn)
+        .into_iter()
+        .find(|c| c.field == field)
+        .ok_or_else(|| format!("Unknown employee field: {}", field))?;
+    if CORE_FIELDS.contains(&field.as_str()) && visibility != "required" {
+        return Err(HrmError::validation(format!("{} is always required", current.label)));
+    }
+    
+    conn.execute(
+        "INSERT INTO field_config (field, visibility, updated_at) VALUES (?1, ?2, CURRENT_TIMESTAMP)
+         ON CONFLICT(field) DO UPDATE SET visibility = excluded.visibility, updated_at = excluded.updated_at",
+        rusqlite::params![field, visibility],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user_id),
+        &username,
+        "UPDATE",
+        "FIELD_CONFIG",
+        Some(&field),
+        Some(&current.visibility),
+        Some(&visibility),
+        Some(&format!("Set {} to {}", current.label, visibility)),
+    );
+    
+    Ok(())
+}
@@ -0,0 +1,214 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{EmployeeSkill, Skill, SkillCoverage, UserSession};
+use crate::validation::parse_iso_date;
+use crate::{CurrentUser, DbConnection};
+use tauri::State;
+
+// Skill levels: 1 learning, 2 works under supervision, 3 works independently,
+// 4 can train others. Level 3 and above counts as able to operate.
+const MAX_SKILL_LEVEL: i32 = 4;
+pub(crate) const QUALIFIED_LEVEL: i32 = 3;
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+fn require_edit(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user = logged_in_user(current_user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit employees."));
+    }
+    Ok(user)
+}
+
+#[tauri::command]
+pub fn get_skills(db: State<'_, DbConnection>) -> Result<Vec<Skill>, HrmError> {
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare("SELECT id, name, category, description FROM skills ORDER BY category, name")?;
+    let skills = stmt
+        .query_map([], |row| {
+            Ok(Skill {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                category: row.get(2)?,
+                description: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(skills)
+}
+
+// Adds a skill, machine or line to the matrix
+#[tauri::command]
+pub fn create_skill(
+    name: String,
+    category: Option<String>,
+    description: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = require_edit(&current_user)?;
+    if name.trim().is_empty() {
+        return Err(HrmError::validation("Skill name is required"));
+    }
+    
+    let conn = db.0.lock()?;
+    conn.execute(
+        "INSERT INTO skills (name, category, description) VALUES (?1, ?2, ?3)",
+        rusqlite::params![name.trim(), category, description],
+    )
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE constraint") {
+            HrmError::conflict("A skill with this name already exists")
+        } else {
+            HrmError::from(e)
+        }
+    })?;
+    let id = conn.last_insert_rowid();
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "SKILL",
+        Some(&id.to_string()),
+        None,
+        Some(name.trim()),
+        Some(&format!("Created skill: {}", name.trim())),
+    );
+    
+    Ok(id)
+}
+
+#[tauri::command]
+pub fn get_employee_skills(epf_number: String, db: State<'_, DbConnection>) -> Result<Vec<EmployeeSkill>, HrmError> {
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT es.epf_number, es.skill_id, s.name, es.level, es.certified_date, es.updated_at
+             FROM employee_skills es JOIN skills s ON s.id = es.skill_id
+             WHERE es.epf_number = ?1 ORDER BY s.name",
+        )?;
+    let skills = stmt
+        .query_map([&epf_number], |row| {
+            Ok(EmployeeSkill {
+                epf_number: row.get(0)?,
+                skill_id: row.get(1)?,
+                skill_name: row.get(2)?,
+                level: row.get(3)?,
+                certified_date: row.get(4)?,
+                updated_at: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(skills)
+}
+
+// Records or updates an employee's level in a skill; level 0 removes it
+#[tauri::command]
+pub fn set_employee_skill(
+    epf_number: String,
+    skill_id: i64,
+    level: i32,
+    certified_date: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = require_edit(&current_user)?;
+    if !(0..=MAX_SKILL_LEVEL).contains(&level) {
+        return Err(HrmError::validation(format!("Skill level must be between 0 and {}", MAX_SKILL_LEVEL)));
+    }
+    let certified_date = certified_date.filter(|d| !d.trim().is_empty());
+    if let Some(date) = &certified_date {
+        parse_iso_date(date).ok_or("Certified date must be a valid date (YYYY-MM-DD)")?;
+    }
+    
+    let conn = db.0.lock()?;
+    let skill_name: String = conn
+        .query_row("SELECT name FROM skills WHERE id = ?1", [skill_id], |row| row.get(0))
+        .map_err(|_| HrmError::not_found("Skill not found"))?;
+    let old_level: Option<i32> = conn
+        .query_row(
+            "SELECT level FROM employee_skills WHERE epf_number = ?1 AND skill_id = ?2",
+            rusqlite::params![epf_number, skill_id],
+            |row| row.get(0),
+        )
+        .ok();
+    
+    if level == 0 {
+        conn.execute(
+            "DELETE FROM employee_skills WHERE epf_number = ?1 AND skill_id = ?2",
+            rusqlite::params![epf_number, skill_id],
+        )?;
+    } else {
+        conn.execute(
+            "INSERT INTO employee_skills (epf_number, skill_id, level, certified_date, updated_at)
+             VALUES (?1, ?2, ?3, ?4, CURRENT_TIMESTAMP)
+             ON CONFLICT(epf_number, skill_id) DO UPDATE SET
+                level = excluded.level, certified_date = excluded.certified_date, updated_at = excluded.updated_at",
+            rusqlite::params![epf_number, skill_id, level, certified_date],
+        )?;
+    }
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "EMPLOYEE_SKILL",
+        Some(&epf_number),
+        old_level.map(|l| l.to_string()).as_deref(),
+        Some(&level.to_string()),
+        Some(&format!("Set {} level for employee {} to {}", skill_name, epf_number, level)),
+    );
+    
+    Ok(())
+}
+
+// How many active employees in each department can operate each skill,
+// so planners can spot machines or lines that depend on one person
+#[tauri::command]
+pub fn get_skill_coverage(
+    department: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<SkillCoverage>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_reports {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view reports."));
+    }
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT COALESCE(NULLIF(e.department, ''), 'Unassigned') AS department,
+                    s.id, s.name,
+                    SUM(CASE WHEN es.level >= ?1 THEN 1 ELSE 0 END),
+                    SUM(CASE WHEN es.certified_date IS NOT NULL THEN 1 ELSE 0 END),
+                    COUNT(*)
+             FROM employee_skills es
+             JOIN skills s ON s.id = es.skill_id
+             JOIN employees e ON e.epf_number = es.epf_number
+             WHERE e.working_status IN (SELECT code FROM working_statuses WHERE counts_as_active = 1) AND (?2 IS NULL OR e.department = ?2)
+             GROUP BY department, s.id
+             ORDER BY department, s.name",
+        )?;
+    let coverage = stmt
+        .query_map(rusqlite::params![QUALIFIED_LEVEL, department], |row| {
+            Ok(SkillCoverage {
+                department: row.get(0)?,
+                skill_id: row.get(1)?,
+                skill_name: row.get(2)?,
+                qualified_count: row.get(3)?,
+                certified_count: row.get(4)?,
+                total_count: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    Ok(coverage)
+}
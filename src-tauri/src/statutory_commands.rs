@@ -0,0 +1,339 @@
+use crate::commands::log_audit_action;
+use crate::deduction_commands::require_month;
+use crate::epf_numbering::member_number;
+use crate::error::HrmError;
+use crate::export_commands::{csv_field, printable_table, DEFAULT_COMPANY_NAME};
+use crate::localization::{default_language, translate};
+use crate::models::UserSession;
+use crate::payroll_commands::load_payroll_run;
+use crate::pdf::{write_pdf, PdfBlock};
+use crate::settings_commands::get_setting;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use tauri::State;
+
+// One member's contributions, summed over the runs in a return
+struct StatutoryReturnLine {
+    member_number: String,
+    nic: Option<String>,
+    name_with_initials: String,
+    months: i64,
+    total_earnings: f64,
+    epf_employee: f64,
+    epf_employer: f64,
+    epf_total: f64,
+    etf_employer: f64,
+}
+
+// A return ready to write out. Headers are translation keys with their
+// English wording; the CSV upload always uses English. Columns from the
+// fourth on are amounts.
+struct ReturnDocument<'a> {
+    title: String,
+    details: Vec<String>,
+    headers: &'a [(&'a str, &'a str)],
+    rows: Vec<Vec<String>>,
+    totals: Vec<String>,
+}
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+// Returns carry earnings and NICs, so they need payroll, sensitive data and
+// export rights
+fn require_return_access(user: &UserSession) -> Result<(), HrmError> {
+    if user.permissions.can_run_payroll && user.permissions.can_view_sensitive_data && user.permissions.can_export_data {
+        Ok(())
+    } else {
+        Err(HrmError::permission_denied("Permission denied. You cannot produce EPF/ETF returns."))
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn employee_nics(conn: &Connection) -> Result<HashMap<String, Option<String>>, HrmError> {
+    let mut stmt = conn.prepare("SELECT epf_number, nic FROM employees")?;
+    let nics = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    Ok(nics)
+}
+
+// Member-wise contributions from the given payroll runs, summed per member.
+// Earnings exclude overtime, as for the contributions.
+fn return_lines(conn: &Connection, run_ids: &[i64]) -> Result<Vec<StatutoryReturnLine>, HrmError> {
+    let nics = employee_nics(conn)?;
+    let mut members: BTreeMap<String, StatutoryReturnLine> = BTreeMap::new();
+    for run_id in run_ids {
+        for line in load_payroll_run(conn, *run_id)?.lines {
+            let member = members.entry(line.epf_number.clone()).or_insert_with(|| StatutoryReturnLine {
+                nic: nics.get(&line.epf_number).cloned().flatten(),
+                member_number: member_number(conn, &line.epf_number),
+                name_with_initials: line.name_with_initials.clone(),
+                months: 0,
+                total_earnings: 0.0,
+                epf_employee: 0.0,
+                epf_employer: 0.0,
+                epf_total: 0.0,
+                etf_employer: 0.0,
+            });
+            member.months += 1;
+            member.total_earnings = round2(member.total_earnings + line.basic_salary - line.no_pay_deduction + line.incentive_pay);
+            member.epf_employee = round2(member.epf_employee + line.epf_employee);
+            member.epf_employer = round2(member.epf_employer + line.epf_employer);
+            member.epf_total = round2(member.epf_employee + member.epf_employer);
+            member.etf_employer = round2(member.etf_employer + line.etf_employer);
+        }
+    }
+    Ok(members.into_values().collect())
+}
+
+// Fixed-width text rows for the PDF copy, amounts right-aligned
+fn pdf_rows(document: &ReturnDocument, header: &Vec<String>) -> Vec<PdfBlock> {
+    let mut widths = vec![0; header.len()];
+    for row in std::iter::once(header).chain(&document.rows).chain(std::iter::once(&document.totals)) {
+        for (width, value) in widths.iter_mut().zip(row) {
+            *width = (*width).max(value.chars().count());
+        }
+    }
+    let line = |row: &Vec<String>| {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (value, &w))| if i >= 3 { format!("{:>w$}", value) } else { format!("{:<w$}", value) })
+            .collect();
+        PdfBlock::Row(cells.join("  ").trim_end().to_string())
+    };
+    let rule = || PdfBlock::Row("-".repeat(widths.iter().sum::<usize>() + 2 * (widths.len() - 1)));
+    
+    let mut blocks = vec![line(header), rule()];
+    blocks.extend(document.rows.iter().map(line));
+    blocks.push(rule());
+    blocks.push(line(&document.totals));
+    blocks
+}
+
+fn write_return(conn: &Connection, document: &ReturnDocument, format: &str, destination_path: &str) -> Result<(), HrmError> {
+    let language = default_language(conn);
+    let company = || {
+        let company = get_setting(conn, "company_name").unwrap_or_else(|| DEFAULT_COMPANY_NAME.to_string());
+        translate(conn, &language, "company_name", &company)
+    };
+    let headers: Vec<String> = document.headers.iter().map(|(key, default)| translate(conn, &language, key, default)).collect();
+    let content = match format {
+        "csv" => {
+            let mut csv = String::new();
+            let header_fields: Vec<String> = document.headers.iter().map(|(_, h)| csv_field(h)).collect();
+            csv.push_str(&header_fields.join(","));
+            csv.push_str("\r\n");
+            for row in &document.rows {
+                let fields: Vec<String> = row.iter().map(|v| csv_field(v)).collect();
+                csv.push_str(&fields.join(","));
+                csv.push_str("\r\n");
+            }
+            csv
+        }
+        "html" => {
+            let numeric: Vec<usize> = (3..headers.len()).collect();
+            let headers: Vec<&str> = headers.iter().map(String::as_str).collect();
+            printable_table(
+                &company(),
+                &document.title,
+                &document.details,
+                &headers,
+                &numeric,
+                &document.rows,
+                Some(&document.totals),
+            )
+        }
+        "pdf" => {
+            let mut blocks = vec![PdfBlock::Heading(company()), PdfBlock::Paragraph(document.title.clone())];
+            blocks.extend(document.details.iter().map(|d| PdfBlock::Paragraph(d.clone())));
+            blocks.push(PdfBlock::Paragraph(String::new()));
+            blocks.extend(pdf_rows(document, &headers));
+            return write_pdf(destination_path, true, &blocks);
+        }
+        _ => return Err(HrmError::validation(format!("Unsupported return format: {}", format))),
+    };
+    fs::write(destination_path, content).map_err(|e| HrmError::io(format!("Failed to save file: {}", e)))?;
+    Ok(())
+}
+
+// Member-wise EPF contribution return for one locked payroll run. `format`
+// is "csv" for upload to the EPF e-return portal, or "html" or "pdf" for a
+// printable copy. Returns the number of members.
+#[tauri::command]
+pub fn generate_epf_return(
+    payroll_run_id: i64,
+    format: String,
+    destination_path: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<usize, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_return_access(&user)?;
+    
+    let conn = db.0.lock()?;
+    let run = load_payroll_run(&conn, payroll_run_id)?;
+    if run.status != "locked" {
+        return Err(HrmError::validation("Returns can only be made from a locked payroll run"));
+    }
+    let lines = return_lines(&conn, &[payroll_run_id])?;
+    let employer_number = get_setting(&conn, "employer_epf_number").unwrap_or_default();
+    let language = default_language(&conn);
+    let heading = |key: &str, default: &str| translate(&conn, &language, key, default);
+    
+    let rows: Vec<Vec<String>> = lines
+        .iter()
+        .map(|l| {
+            vec![
+                l.member_number.clone(),
+                l.nic.clone().unwrap_or_default(),
+                l.name_with_initials.clone(),
+                format!("{:.2}", l.total_earnings),
+                format!("{:.2}", l.epf_employer),
+                format!("{:.2}", l.epf_employee),
+                format!("{:.2}", l.epf_total),
+            ]
+        })
+        .collect();
+    let sum = |f: fn(&StatutoryReturnLine) -> f64| format!("{:.2}", lines.iter().map(f).sum::<f64>());
+    let totals = vec![
+        heading("return.total", "Total"),
+        String::new(),
+        format!("{} {}", lines.len(), heading("return.members", "members")),
+        sum(|l| l.total_earnings),
+        sum(|l| l.epf_employer),
+        sum(|l| l.epf_employee),
+        sum(|l| l.epf_total),
+    ];
+    let document = ReturnDocument {
+        title: format!("{} - {}", heading("return.epf_title", "EPF Contribution Return"), run.month),
+        details: vec![
+            format!("{}: {}", heading("return.employer_epf_no", "Employer EPF No"), employer_number),
+            format!("{}: {}", heading("return.contribution_period", "Contribution period"), run.month),
+        ],
+        headers: &[
+            ("return.member_no", "Member No."),
+            ("return.nic", "NIC"),
+            ("return.name", "Name"),
+            ("return.total_earnings", "Total Earnings"),
+            ("return.epf_employer", "Employer (12%)"),
+            ("return.epf_employee", "Employee (8%)"),
+            ("return.epf_total", "Total (20%)"),
+        ],
+        rows,
+        totals,
+    };
+    write_return(&conn, &document, &format, &destination_path)?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "EXPORT",
+        "PAYROLL",
+        Some(&payroll_run_id.to_string()),
+        None,
+        Some(&format),
+        Some(&format!("Generated EPF return for {} ({} members)", run.month, lines.len())),
+    );
+    
+    Ok(lines.len())
+}
+
+// Member-wise ETF return over the locked runs from from_month to to_month
+// (YYYY-MM), normally a half year. Returns the number of members.
+#[tauri::command]
+pub fn generate_etf_return(
+    from_month: String,
+    to_month: String,
+    format: String,
+    destination_path: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<usize, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    require_return_access(&user)?;
+    require_month(&from_month)?;
+    require_month(&to_month)?;
+    if to_month < from_month {
+        return Err(HrmError::field("to_month", "End month cannot be before the start month"));
+    }
+    
+    let conn = db.0.lock()?;
+    let runs: Vec<(i64, String)> = conn
+        .prepare("SELECT id, month FROM payroll_runs WHERE status = 'locked' AND month BETWEEN ?1 AND ?2 ORDER BY month")?
+        .query_map([&from_month, &to_month], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    if runs.is_empty() {
+        return Err(HrmError::validation(format!("No locked payroll runs between {} and {}", from_month, to_month)));
+    }
+    let run_ids: Vec<i64> = runs.iter().map(|(id, _)| *id).collect();
+    let lines = return_lines(&conn, &run_ids)?;
+    let employer_number = get_setting(&conn, "employer_etf_number").unwrap_or_default();
+    let months: Vec<&str> = runs.iter().map(|(_, month)| month.as_str()).collect();
+    let language = default_language(&conn);
+    let heading = |key: &str, default: &str| translate(&conn, &language, key, default);
+    
+    let rows: Vec<Vec<String>> = lines
+        .iter()
+        .map(|l| {
+            vec![
+                l.member_number.clone(),
+                l.nic.clone().unwrap_or_default(),
+                l.name_with_initials.clone(),
+                l.months.to_string(),
+                format!("{:.2}", l.total_earnings),
+                format!("{:.2}", l.etf_employer),
+            ]
+        })
+        .collect();
+    let totals = vec![
+        heading("return.total", "Total"),
+        String::new(),
+        format!("{} {}", lines.len(), heading("return.members", "members")),
+        String::new(),
+        format!("{:.2}", lines.iter().map(|l| l.total_earnings).sum::<f64>()),
+        format!("{:.2}", lines.iter().map(|l| l.etf_employer).sum::<f64>()),
+    ];
+    let document = ReturnDocument {
+        title: format!("{} ({} - {})", heading("return.etf_title", "ETF Remittance Summary"), from_month, to_month),
+        details: vec![
+            format!("{}: {}", heading("return.employer_etf_no", "Employer ETF No"), employer_number),
+            format!("{}: {}", heading("return.months_included", "Months included"), months.join(", ")),
+        ],
+        headers: &[
+            ("return.member_no", "Member No."),
+            ("return.nic", "NIC"),
+            ("return.name", "Name"),
+            ("return.months", "Months"),
+            ("return.total_earnings", "Total Earnings"),
+            ("return.etf", "ETF (3%)"),
+        ],
+        rows,
+        totals,
+    };
+    write_return(&conn, &document, &format, &destination_path)?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "EXPORT",
+        "PAYROLL",
+        None,
+        None,
+        Some(&format),
+        Some(&format!("Generated ETF return for {} to {} ({} members)", from_month, to_month, lines.len())),
+    );
+    
+    Ok(lines.len())
+}
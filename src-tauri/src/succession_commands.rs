@@ -0,0 +1,360 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{KeyPosition, SuccessionGap, Successor, UserSession};
+use crate::skill_commands::QUALIFIED_LEVEL;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use tauri::State;
+
+const READINESS_LEVELS: [&str; 3] = ["ready_now", "1_2_years", "3_plus_years"];
+const RISK_LEVELS: [&str; 3] = ["high", "medium", "low"];
+const ACTIVE: &str = "working_status IN (SELECT code FROM working_statuses WHERE counts_as_active = 1)";
+// Suggestions listed for a position with no successor
+const MAX_SUGGESTIONS: usize = 5;
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+// Succession plans are confidential to HR management
+fn require_manage(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user = logged_in_user(current_user)?;
+    if !user.permissions.can_manage_users {
+        return Err(HrmError::permission_denied("Permission denied. You cannot manage succession plans."));
+    }
+    Ok(user)
+}
+
+fn required_skills(conn: &Connection, position_id: i64) -> rusqlite::Result<Vec<i64>> {
+    let mut stmt = conn.prepare("SELECT skill_id FROM key_position_skills WHERE position_id = ?1 ORDER BY skill_id")?;
+    let skills = stmt.query_map([position_id], |row| row.get(0))?.collect::<Result<Vec<_>, _>>()?;
+    Ok(skills)
+}
+
+fn load_positions(conn: &Connection, condition: &str) -> Result<Vec<KeyPosition>, HrmError> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT p.id, p.title, p.department, p.incumbent_epf, e.name_with_initials, p.risk_level, p.notes,
+                    (SELECT COUNT(*) FROM successors s JOIN employees se ON se.epf_number = s.epf_number
+                     WHERE s.position_id = p.id AND se.{})
+             FROM key_positions p
+             LEFT JOIN employees e ON e.epf_number = p.incumbent_epf
+             WHERE p.is_active = 1 AND {}
+             ORDER BY CASE p.risk_level WHEN 'high' THEN 0 WHEN 'medium' THEN 1 ELSE 2 END, p.title",
+            ACTIVE, condition
+        ))?;
+    let mut positions = stmt
+        .query_map([], |row| {
+            Ok(KeyPosition {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                department: row.get(2)?,
+                incumbent_epf: row.get(3)?,
+                incumbent_name: row.get(4)?,
+                risk_level: row.get(5)?,
+                notes: row.get(6)?,
+                required_skill_ids: Vec::new(),
+                successor_count: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    for position in positions.iter_mut() {
+        position.required_skill_ids = required_skills(conn, position.id)?;
+    }
+    Ok(positions)
+}
+
+// How many of the position's required skills the employee can operate
+fn skills_met(conn: &Connection, position_id: i64, epf_number: &str) -> rusqlite::Result<i32> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM key_position_skills k
+         JOIN employee_skills es ON es.skill_id = k.skill_id
+         WHERE k.position_id = ?1 AND es.epf_number = ?2 AND es.level >= ?3",
+        rusqlite::params![position_id, epf_number, QUALIFIED_LEVEL],
+        |row| row.get(0),
+    )
+}
+
+#[tauri::command]
+pub fn get_key_positions(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<KeyPosition>, HrmError> {
+    require_manage(&current_user)?;
+    let conn = db.0.lock()?;
+    load_positions(&conn, "1=1")
+}
+
+// Flags a new key position (id 0) or updates an existing one
+#[tauri::command]
+pub fn save_key_position(
+    position: KeyPosition,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = require_manage(&current_user)?;
+    let title = position.title.trim();
+    if title.is_empty() {
+        return Err(HrmError::validation("Position title is required"));
+    }
+    if !RISK_LEVELS.contains(&position.risk_level.as_str()) {
+        return Err(HrmError::validation("Risk level must be high, medium or low"));
+    }
+    let incumbent = position.incumbent_epf.as_deref().map(str::trim).filter(|e| !e.is_empty());
+    
+    let mut conn = db.0.lock()?;
+    if let Some(epf) = incumbent {
+        let exists: bool = conn
+            .query_row("SELECT COUNT(*) > 0 FROM employees WHERE epf_number = ?1", [epf], |row| row.get(0))?;
+        if !exists {
+            return Err(HrmError::not_found(format!("Employee {} not found", epf)));
+        }
+    }
+    
+    let tx = conn.transaction()?;
+    let position_id = if position.id == 0 {
+        tx.execute(
+            "INSERT INTO key_positions (title, department, incumbent_epf, risk_level, notes) VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![title, position.department, incumbent, position.risk_level, position.notes],
+        )?;
+        tx.last_insert_rowid()
+    } else {
+        let updated = tx
+            .execute(
+                "UPDATE key_positions SET title = ?1, department = ?2, incumbent_epf = ?3, risk_level = ?4, notes = ?5
+                 WHERE id = ?6 AND is_active = 1",
+                rusqlite::params![title, position.department, incumbent, position.risk_level, position.notes, position.id],
+            )?;
+        if updated == 0 {
+            return Err(HrmError::not_found("Key position not found"));
+        }
+        position.id
+    };
+    tx.execute("DELETE FROM key_position_skills WHERE position_id = ?1", [position_id])?;
+    for skill_id in &position.required_skill_ids {
+        tx.execute(
+            "INSERT OR IGNORE INTO key_position_skills (position_id, skill_id) VALUES (?1, ?2)",
+            rusqlite::params![position_id, skill_id],
+        )?;
+    }
+    
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        if position.id == 0 { "CREATE" } else { "UPDATE" },
+        "KEY_POSITION",
+        Some(&position_id.to_string()),
+        None,
+        Some(title),
+        Some(&format!("Key position '{}' ({} risk)", title, position.risk_level)),
+    );
+    tx.commit()?;
+    
+    Ok(position_id)
+}
+
+// Unflags a key position; its nominations are kept for reference
+#[tauri::command]
+pub fn remove_key_position(
+    position_id: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = require_manage(&current_user)?;
+    let conn = db.0.lock()?;
+    let updated = conn
+        .execute("UPDATE key_positions SET is_active = 0 WHERE id = ?1 AND is_active = 1", [position_id])?;
+    if updated == 0 {
+        return Err(HrmError::not_found("Key position not found"));
+    }
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "DELETE",
+        "KEY_POSITION",
+        Some(&position_id.to_string()),
+        None,
+        None,
+        Some(&format!("Removed key position #{}", position_id)),
+    );
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_successors(
+    position_id: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<Successor>, HrmError> {
+    require_manage(&current_user)?;
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT s.epf_number, e.name_with_initials, e.designation, s.readiness, s.notes, s.nominated_by, s.nominated_on,
+                    e.{}
+             FROM successors s
+             JOIN employees e ON e.epf_number = s.epf_number
+             WHERE s.position_id = ?1
+             ORDER BY CASE s.readiness WHEN 'ready_now' THEN 0 WHEN '1_2_years' THEN 1 ELSE 2 END, e.name_with_initials",
+            ACTIVE
+        ))?;
+    let mut successors = stmt
+        .query_map([position_id], |row| {
+            Ok(Successor {
+                epf_number: row.get(0)?,
+                name_with_initials: row.get(1)?,
+                designation: row.get(2)?,
+                readiness: row.get(3)?,
+                notes: row.get(4)?,
+                nominated_by: row.get(5)?,
+                nominated_on: row.get(6)?,
+                is_active: row.get(7)?,
+                skills_met: 0,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    for successor in successors.iter_mut() {
+        successor.skills_met = skills_met(&conn, position_id, &successor.epf_number)?;
+    }
+    Ok(successors)
+}
+
+// Nominates an active employee as a successor, or updates their readiness
+#[tauri::command]
+pub fn nominate_successor(
+    position_id: i64,
+    epf_number: String,
+    readiness: String,
+    notes: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = require_manage(&current_user)?;
+    if !READINESS_LEVELS.contains(&readiness.as_str()) {
+        return Err(HrmError::validation("Readiness must be ready_now, 1_2_years or 3_plus_years"));
+    }
+    
+    let conn = db.0.lock()?;
+    let position = load_positions(&conn, &format!("p.id = {}", position_id))?
+        .pop()
+        .ok_or_else(|| HrmError::not_found("Key position not found"))?;
+    if position.incumbent_epf.as_deref() == Some(epf_number.as_str()) {
+        return Err(HrmError::validation("The incumbent cannot be their own successor"));
+    }
+    let active: bool = conn
+        .query_row(&format!("SELECT {} FROM employees WHERE epf_number = ?1", ACTIVE), [&epf_number], |row| row.get(0))
+        .map_err(|_| HrmError::not_found(format!("Employee {} not found", epf_number)))?;
+    if !active {
+        return Err(HrmError::validation(format!("Employee {} is not active", epf_number)));
+    }
+    
+    conn.execute(
+        "INSERT INTO successors (position_id, epf_number, readiness, notes, nominated_by, nominated_on)
+         VALUES (?1, ?2, ?3, ?4, ?5, date('now', 'localtime'))
+         ON CONFLICT(position_id, epf_number) DO UPDATE SET readiness = ?3, notes = ?4",
+        rusqlite::params![position_id, epf_number, readiness, notes, user.username],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "NOMINATE",
+        "KEY_POSITION",
+        Some(&position_id.to_string()),
+        None,
+        Some(&readiness),
+        Some(&format!("Employee {} nominated for '{}' ({})", epf_number, position.title, readiness.replace('_', " "))),
+    );
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub fn remove_successor(
+    position_id: i64,
+    epf_number: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = require_manage(&current_user)?;
+    let conn = db.0.lock()?;
+    let deleted = conn
+        .execute(
+            "DELETE FROM successors WHERE position_id = ?1 AND epf_number = ?2",
+            rusqlite::params![position_id, epf_number],
+        )?;
+    if deleted == 0 {
+        return Err(HrmError::not_found("Nomination not found"));
+    }
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "DELETE",
+        "KEY_POSITION",
+        Some(&position_id.to_string()),
+        Some(&epf_number),
+        None,
+        Some(&format!("Employee {} removed as successor", epf_number)),
+    );
+    
+    Ok(())
+}
+
+// Key positions with no active successor. Each gap lists possible
+// successors: the incumbent's direct reports and active employees who can
+// operate the position's required skills, strongest skill match first.
+#[tauri::command]
+pub fn get_succession_gaps(
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<SuccessionGap>, HrmError> {
+    require_manage(&current_user)?;
+    let conn = db.0.lock()?;
+    let positions: Vec<KeyPosition> = load_positions(&conn, "1=1")?
+        .into_iter()
+        .filter(|p| p.successor_count == 0)
+        .collect();
+    
+    let mut gaps = Vec::new();
+    for position in positions {
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT e.epf_number, e.name_with_initials, e.designation,
+                        (SELECT COUNT(*) FROM key_position_skills k
+                         JOIN employee_skills es ON es.skill_id = k.skill_id
+                         WHERE k.position_id = ?1 AND es.epf_number = e.epf_number AND es.level >= ?3) AS met,
+                        (e.reports_to IS NOT NULL AND e.reports_to = ?2) AS direct_report
+                 FROM employees e
+                 WHERE e.{} AND e.epf_number != COALESCE(?2, '')
+                 AND (met > 0 OR direct_report)
+                 ORDER BY met DESC, direct_report DESC, e.name_with_initials
+                 LIMIT {}",
+                ACTIVE, MAX_SUGGESTIONS
+            ))?;
+        let suggestions = stmt
+            .query_map(rusqlite::params![position.id, position.incumbent_epf, QUALIFIED_LEVEL], |row| {
+                Ok(Successor {
+                    epf_number: row.get(0)?,
+                    name_with_initials: row.get(1)?,
+                    designation: row.get(2)?,
+                    readiness: String::new(),
+                    notes: None,
+                    nominated_by: None,
+                    nominated_on: None,
+                    is_active: true,
+                    skills_met: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        gaps.push(SuccessionGap { position, suggestions });
+    }
+    Ok(gaps)
+}
@@ -0,0 +1,190 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::Tag;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use tauri::State;
+
+fn require_edit_permission(current_user: &State<'_, CurrentUser>) -> Result<(i32, String), HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_edit_employees => Ok((session.user_id, session.username.clone())),
+        _ => Err(HrmError::permission_denied("Permission denied. You cannot edit employees.")),
+    }
+}
+
+fn load_tags(conn: &Connection, sql: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<Tag>, HrmError> {
+    let mut stmt = conn.prepare(sql)?;
+    let tags = stmt
+        .query_map(params, |row| {
+            Ok(Tag {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                color: row.get(2)?,
+                employee_count: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(tags)
+}
+
+pub fn delete_employee_tags(conn: &Connection, epf_number: &str) -> rusqlite::Result<usize> {
+    conn.execute("DELETE FROM employee_tags WHERE epf_number = ?1", [epf_number])
+}
+
+#[tauri::command]
+pub fn get_tags(db: State<'_, DbConnection>) -> Result<Vec<Tag>, HrmError> {
+    let conn = db.0.lock()?;
+    load_tags(
+        &conn,
+        "SELECT t.id, t.name, t.color, COUNT(et.epf_number) FROM tags t
+         LEFT JOIN employee_tags et ON et.tag_id = t.id
+         GROUP BY t.id ORDER BY t.name",
+        &[],
+    )
+}
+
+#[tauri::command]
+pub fn get_employee_tags(epf_number: String, db: State<'_, DbConnection>) -> Result<Vec<Tag>, HrmError> {
+    let conn = db.0.lock()?;
+    load_tags(
+        &conn,
+        "SELECT t.id, t.name, t.color,
+                (SELECT COUNT(*) FROM employee_tags c WHERE c.tag_id = t.id)
+         FROM tags t JOIN employee_tags et ON et.tag_id = t.id
+         WHERE et.epf_number = ?1 ORDER BY t.name",
+        &[&epf_number],
+    )
+}
+
+#[tauri::command]
+pub fn create_tag(
+    name: String,
+    color: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let (user_id, username) = require_edit_permission(&current_user)?;
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(HrmError::validation("Tag name is required"));
+    }
+    
+    let conn = db.0.lock()?;
+    conn.execute(
+        "INSERT INTO tags (name, color) VALUES (?1, ?2)",
+        rusqlite::params![name, color],
+    )
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE constraint") {
+            HrmError::conflict("A tag with this name already exists")
+        } else {
+            HrmError::from(e)
+        }
+    })?;
+    let id = conn.last_insert_rowid();
+    
+    log_audit_action(
+        &conn,
+        Some(user_id),
+        &username,
+        "CREATE",
+        "TAG",
+        Some(&id.to_string()),
+        None,
+        Some(&name),
+        Some(&format!("Created tag: {}", name)),
+    );
+    
+    Ok(id)
+}
+
+// Deletes a tag and removes it from every employee
+#[tauri::command]
+pub fn delete_tag(
+    tag_id: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let (user_id, username) = require_edit_permission(&current_user)?;
+    
+    let mut conn = db.0.lock()?;
+    let tx = conn.transaction()?;
+    let name: String = tx
+        .query_row("SELECT name FROM tags WHERE id = ?1", [tag_id], |row| row.get(0))
+        .map_err(|_| HrmError::not_found("Tag not found"))?;
+    tx.execute("DELETE FROM employee_tags WHERE tag_id = ?1", [tag_id])?;
+    tx.execute("DELETE FROM tags WHERE id = ?1", [tag_id])?;
+    
+    log_audit_action(
+        &tx,
+        Some(user_id),
+        &username,
+        "DELETE",
+        "TAG",
+        Some(&tag_id.to_string()),
+        Some(&name),
+        None,
+        Some(&format!("Deleted tag: {}", name)),
+    );
+    tx.commit()?;
+    
+    Ok(())
+}
+
+// Adds or removes a tag on several employees at once. Returns how many
+// employees actually changed.
+#[tauri::command]
+pub fn set_employee_tag(
+    tag_id: i64,
+    epf_numbers: Vec<String>,
+    assigned: bool,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<usize, HrmError> {
+    let (user_id, username) = require_edit_permission(&current_user)?;
+    
+    let mut conn = db.0.lock()?;
+    let tx = conn.transaction()?;
+    let name: String = tx
+        .query_row("SELECT name FROM tags WHERE id = ?1", [tag_id], |row| row.get(0))
+        .map_err(|_| HrmError::not_found("Tag not found"))?;
+    
+    let mut changed = 0;
+    for epf_number in &epf_numbers {
+        changed += if assigned {
+            tx.execute(
+                "INSERT OR IGNORE INTO employee_tags (epf_number, tag_id)
+                 SELECT epf_number, ?2 FROM employees WHERE epf_number = ?1",
+                rusqlite::params![epf_number, tag_id],
+            )
+        } else {
+            tx.execute(
+                "DELETE FROM employee_tags WHERE epf_number = ?1 AND tag_id = ?2",
+                rusqlite::params![epf_number, tag_id],
+            )
+        }?;
+    }
+    
+    let records = serde_json::to_string(&epf_numbers).ok();
+    log_audit_action(
+        &tx,
+        Some(user_id),
+        &username,
+        if assigned { "ASSIGN_TAG" } else { "UNASSIGN_TAG" },
+        "TAG",
+        Some(&tag_id.to_string()),
+        None,
+        records.as_deref(),
+        Some(&format!(
+            "{} tag '{}' {} {} employees",
+            if assigned { "Added" } else { "Removed" },
+            name,
+            if assigned { "to" } else { "from" },
+            changed
+        )),
+    );
+    tx.commit()?;
+    
+    Ok(changed)
+}
@@ -0,0 +1,324 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{Task, UserSession};
+use crate::notification_commands::notify_user;
+use crate::validation::parse_iso_date;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::{Connection, OptionalExtension};
+use tauri::State;
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+const TASK_COLUMNS: &str = "t.id, t.title, t.description, t.assigned_to, a.username, t.created_by, c.username, t.due_date,
+                            t.entity_type, t.entity_id, t.status, t.completed_at, t.completion_note, t.created_at,
+                            t.status = 'open' AND t.due_date < date('now', 'localtime')";
+
+const TASK_JOINS: &str = "FROM tasks t LEFT JOIN users a ON a.id = t.assigned_to LEFT JOIN users c ON c.id = t.created_by";
+
+fn task_from_row(row: &rusqlite::Row) -> rusqlite::Result<Task> {
+    Ok(Task {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        description: row.get(2)?,
+        assigned_to: row.get(3)?,
+        assigned_to_name: row.get(4)?,
+        created_by: row.get(5)?,
+        created_by_name: row.get(6)?,
+        due_date: row.get(7)?,
+        entity_type: row.get(8)?,
+        entity_id: row.get(9)?,
+        status: row.get(10)?,
+        completed_at: row.get(11)?,
+        completion_note: row.get(12)?,
+        created_at: row.get(13)?,
+        is_overdue: row.get(14)?,
+    })
+}
+
+fn query_task(conn: &Connection, task_id: i64) -> Result<Task, HrmError> {
+    conn.query_row(&format!("SELECT {} {} WHERE t.id = ?1", TASK_COLUMNS, TASK_JOINS), [task_id], task_from_row)
+        .optional()?
+        .ok_or_else(|| HrmError::not_found("Task not found"))
+}
+
+// The creator, the assignee and user managers may change a task
+fn check_can_modify(user: &UserSession, task: &Task) -> Result<(), HrmError> {
+    if task.created_by == Some(user.user_id) || task.assigned_to == Some(user.user_id) || user.permissions.can_manage_users {
+        Ok(())
+    } else {
+        Err(HrmError::permission_denied("Permission denied. Only the creator or assignee can change this task."))
+    }
+}
+
+// The active user a task is assigned to, by ID, with their username
+fn require_assignee(conn: &Connection, user_id: i32) -> Result<String, HrmError> {
+    conn.query_row(
+        "SELECT username FROM users WHERE id = ?1 AND is_active = 1 AND deleted_at IS NULL",
+        [user_id],
+        |row| row.get(0),
+    )
+    .optional()?
+    .ok_or_else(|| HrmError::field("assigned_to", "Assign the task to an active user"))
+}
+
+fn notify_assignee(conn: &Connection, task: &Task, assigned_by: &UserSession) {
+    let Some(assignee) = task.assigned_to else { return };
+    if assignee == assigned_by.user_id {
+        return;
+    }
+    let due = task.due_date.as_deref().map(|d| format!(" (due {})", d)).unwrap_or_default();
+    notify_user(
+        conn,
+        assignee,
+        "Task assigned to you",
+        &format!("{} assigned you: {}{}", assigned_by.full_name, task.title, due),
+        "info",
+        Some("TASK"),
+        Some(&task.id.to_string()),
+    );
+}
+
+// Open tasks assigned to or created by the user, soonest due first, then
+// completed ones when include_completed is set
+#[tauri::command]
+pub fn get_my_tasks(
+    include_completed: Option<bool>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<Task>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} {}
+         WHERE (t.assigned_to = ?1 OR t.created_by = ?1) AND (t.status = 'open' OR ?2)
+         ORDER BY t.status != 'open', t.due_date IS NULL, t.due_date, t.id",
+        TASK_COLUMNS, TASK_JOINS
+    ))?;
+    let tasks = stmt
+        .query_map(rusqlite::params![user.user_id, include_completed.unwrap_or(false)], task_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(tasks)
+}
+
+// Checks the employee exists and is in one of the user's departments
+fn check_employee_access(conn: &Connection, user: &UserSession, epf_number: &str) -> Result<(), HrmError> {
+    let department: Option<String> = conn
+        .query_row("SELECT department FROM employees WHERE epf_number = ?1", [epf_number], |row| row.get(0))
+        .optional()?
+        .ok_or_else(|| HrmError::field("entity_id", format!("Employee {} not found", epf_number)))?;
+    if !user.can_access_department(department.as_deref()) {
+        return Err(HrmError::permission_denied("Permission denied. This employee is outside your departments."));
+    }
+    Ok(())
+}
+
+// Tasks linked to a record, e.g. entity_type EMPLOYEE with the EPF number
+#[tauri::command]
+pub fn get_entity_tasks(
+    entity_type: String,
+    entity_id: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<Task>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    let entity_type = entity_type.trim().to_uppercase();
+    let entity_id = entity_id.trim().to_string();
+    
+    let conn = db.0.lock()?;
+    if entity_type == "EMPLOYEE" {
+        check_employee_access(&conn, &user, &entity_id)?;
+    }
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} {}
+         WHERE t.entity_type = ?1 AND t.entity_id = ?2
+         ORDER BY t.status != 'open', t.due_date IS NULL, t.due_date, t.id DESC",
+        TASK_COLUMNS, TASK_JOINS
+    ))?;
+    let tasks = stmt
+        .query_map([entity_type, entity_id], task_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(tasks)
+}
+
+// Creates a task, such as "collect NIC copy" linked to an employee, and
+// notifies the assignee
+#[tauri::command]
+pub fn create_task(
+    task: Task,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    let title = task.title.trim();
+    if title.is_empty() {
+        return Err(HrmError::field("title", "Title is required"));
+    }
+    let text = |value: &Option<String>| value.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+    let due_date = text(&task.due_date);
+    if let Some(due) = &due_date {
+        parse_iso_date(due).ok_or_else(|| HrmError::field("due_date", "Due date must be a valid date (YYYY-MM-DD)"))?;
+    }
+    let entity_type = text(&task.entity_type).map(|t| t.to_uppercase());
+    let entity_id = text(&task.entity_id);
+    if entity_type.is_some() != entity_id.is_some() {
+        return Err(HrmError::field("entity_id", "A linked record needs both its type and ID"));
+    }
+    let assigned_to = task.assigned_to.unwrap_or(user.user_id);
+    
+    let conn = db.0.lock()?;
+    require_assignee(&conn, assigned_to)?;
+    if let (Some("EMPLOYEE"), Some(epf_number)) = (entity_type.as_deref(), entity_id.as_deref()) {
+        check_employee_access(&conn, &user, epf_number)?;
+    }
+    conn.execute(
+        "INSERT INTO tasks (title, description, assigned_to, created_by, due_date, entity_type, entity_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![title, text(&task.description), assigned_to, user.user_id, due_date, entity_type, entity_id],
+    )?;
+    let id = conn.last_insert_rowid();
+    let created = query_task(&conn, id)?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "TASK",
+        Some(&id.to_string()),
+        None,
+        Some(title),
+        Some(&format!("Created task '{}' for {}", title, created.assigned_to_name.as_deref().unwrap_or_default())),
+    );
+    notify_assignee(&conn, &created, &user);
+    
+    Ok(id)
+}
+
+// Hands an open task to another user and notifies them
+#[tauri::command]
+pub fn assign_task(
+    task_id: i64,
+    assigned_to: i32,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let task = query_task(&conn, task_id)?;
+    check_can_modify(&user, &task)?;
+    if task.status != "open" {
+        return Err(HrmError::conflict(format!("This task is already {}", task.status)));
+    }
+    if task.assigned_to == Some(assigned_to) {
+        return Ok(());
+    }
+    let assignee = require_assignee(&conn, assigned_to)?;
+    conn.execute(
+        "UPDATE tasks SET assigned_to = ?1, overdue_notified_on = NULL WHERE id = ?2",
+        rusqlite::params![assigned_to, task_id],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "ASSIGN",
+        "TASK",
+        Some(&task_id.to_string()),
+        task.assigned_to_name.as_deref(),
+        Some(&assignee),
+        Some(&format!("Assigned task '{}' to {}", task.title, assignee)),
+    );
+    notify_assignee(&conn, &query_task(&conn, task_id)?, &user);
+    
+    Ok(())
+}
+
+// Marks a task done, or cancelled when `cancel` is set, and tells the
+// creator if someone else closed it
+#[tauri::command]
+pub fn complete_task(
+    task_id: i64,
+    note: Option<String>,
+    cancel: Option<bool>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    let note = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    let status = if cancel.unwrap_or(false) { "cancelled" } else { "done" };
+    
+    let conn = db.0.lock()?;
+    let task = query_task(&conn, task_id)?;
+    check_can_modify(&user, &task)?;
+    if task.status != "open" {
+        return Err(HrmError::conflict(format!("This task is already {}", task.status)));
+    }
+    conn.execute(
+        "UPDATE tasks SET status = ?1, completed_at = CURRENT_TIMESTAMP, completed_by = ?2, completion_note = ?3
+         WHERE id = ?4",
+        rusqlite::params![status, user.user_id, note, task_id],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "UPDATE",
+        "TASK",
+        Some(&task_id.to_string()),
+        Some("open"),
+        Some(status),
+        Some(&format!("Marked task '{}' {}", task.title, status)),
+    );
+    if let Some(creator) = task.created_by.filter(|id| *id != user.user_id) {
+        let detail = note.as_deref().map(|n| format!(": {}", n)).unwrap_or_default();
+        notify_user(
+            &conn,
+            creator,
+            if status == "done" { "Task completed" } else { "Task cancelled" },
+            &format!("{} marked '{}' {}{}", user.full_name, task.title, status, detail),
+            "info",
+            Some("TASK"),
+            Some(&task_id.to_string()),
+        );
+    }
+    
+    Ok(())
+}
+
+// Reminds assignees once about open tasks past their due date. Called by the
+// scheduler.
+pub fn check_overdue_tasks(conn: &Connection) -> Result<usize, HrmError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, title, assigned_to, due_date FROM tasks
+         WHERE status = 'open' AND assigned_to IS NOT NULL AND overdue_notified_on IS NULL
+           AND due_date < date('now', 'localtime')",
+    )?;
+    let overdue = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, i32>(2)?, row.get::<_, String>(3)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (id, title, assigned_to, due_date) in &overdue {
+        notify_user(
+            conn,
+            *assigned_to,
+            "Task overdue",
+            &format!("'{}' was due on {}", title, due_date),
+            "warning",
+            Some("TASK"),
+            Some(&id.to_string()),
+        );
+        conn.execute(
+            "UPDATE tasks SET overdue_notified_on = date('now', 'localtime') WHERE id = ?1",
+            [id],
+        )?;
+    }
+    Ok(overdue.len())
+}
@@ -0,0 +1,244 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{Training, TrainingGap, UserSession};
+use crate::settings_commands::get_setting;
+use crate::validation::parse_iso_date;
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use tauri::State;
+
+// Courses every active employee must have attended, unless overridden by the
+// mandatory_trainings setting (comma-separated course names)
+const DEFAULT_MANDATORY_TRAININGS: [&str; 2] = ["Fire Safety", "Chemical Handling"];
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+fn mandatory_trainings(conn: &Connection) -> Vec<String> {
+    match get_setting(conn, "mandatory_trainings") {
+        Some(value) => value
+            .split(',')
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect(),
+        None => DEFAULT_MANDATORY_TRAININGS.iter().map(|c| c.to_string()).collect(),
+    }
+}
+
+fn training_from_row(row: &rusqlite::Row) -> rusqlite::Result<Training> {
+    Ok(Training {
+        id: row.get(0)?,
+        course: row.get(1)?,
+        training_date: row.get(2)?,
+        provider: row.get(3)?,
+        cost: row.get(4)?,
+        notes: row.get(5)?,
+        attendee_count: row.get(6)?,
+    })
+}
+
+#[tauri::command]
+pub fn get_trainings(
+    year: Option<i32>,
+    db: State<'_, DbConnection>,
+) -> Result<Vec<Training>, HrmError> {
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.id, t.course, t.training_date, t.provider, t.cost, t.notes,
+                    (SELECT COUNT(*) FROM training_attendance a WHERE a.training_id = t.id)
+             FROM trainings t
+             WHERE ?1 IS NULL OR strftime('%Y', t.training_date) = printf('%04d', ?1)
+             ORDER BY t.training_date DESC, t.id DESC",
+        )?;
+    let trainings = stmt
+        .query_map([year], training_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(trainings)
+}
+
+#[tauri::command]
+pub fn get_employee_trainings(epf_number: String, db: State<'_, DbConnection>) -> Result<Vec<Training>, HrmError> {
+    let conn = db.0.lock()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.id, t.course, t.training_date, t.provider, t.cost, t.notes,
+                    (SELECT COUNT(*) FROM training_attendance c WHERE c.training_id = t.id)
+             FROM trainings t JOIN training_attendance a ON a.training_id = t.id
+             WHERE a.epf_number = ?1
+             ORDER BY t.training_date DESC",
+        )?;
+    let trainings = stmt
+        .query_map([&epf_number], training_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(trainings)
+}
+
+// Records one training session and everyone who attended it
+#[tauri::command]
+pub fn log_training_session(
+    training: Training,
+    epf_numbers: Vec<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit employees."));
+    }
+    if training.course.trim().is_empty() {
+        return Err(HrmError::validation("Course name is required"));
+    }
+    parse_iso_date(&training.training_date).ok_or("Training date must be a valid date (YYYY-MM-DD)")?;
+    if training.cost.map(|c| c < 0.0).unwrap_or(false) {
+        return Err(HrmError::validation("Cost cannot be negative"));
+    }
+    if epf_numbers.is_empty() {
+        return Err(HrmError::validation("Select at least one attendee"));
+    }
+    
+    let mut conn = db.0.lock()?;
+    let tx = conn.transaction()?;
+    
+    tx.execute(
+        "INSERT INTO trainings (course, training_date, provider, cost, notes) VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![
+            training.course.trim(),
+            training.training_date,
+            training.provider,
+            training.cost,
+            training.notes,
+        ],
+    )?;
+    let training_id = tx.last_insert_rowid();
+    
+    for epf_number in &epf_numbers {
+        tx.execute(
+            "INSERT OR IGNORE INTO training_attendance (training_id, epf_number) VALUES (?1, ?2)",
+            rusqlite::params![training_id, epf_number],
+        )?;
+    }
+    
+    let records = serde_json::to_string(&epf_numbers).ok();
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "CREATE",
+        "TRAINING",
+        Some(&training_id.to_string()),
+        None,
+        records.as_deref(),
+        Some(&format!(
+            "Logged training '{}' on {} for {} employees",
+            training.course.trim(),
+            training.training_date,
+            epf_numbers.len()
+        )),
+    );
+    tx.commit()?;
+    
+    Ok(training_id)
+}
+
+#[tauri::command]
+pub fn delete_training(
+    training_id: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_edit_employees {
+        return Err(HrmError::permission_denied("Permission denied. You cannot edit employees."));
+    }
+    
+    let mut conn = db.0.lock()?;
+    let tx = conn.transaction()?;
+    let course: String = tx
+        .query_row("SELECT course FROM trainings WHERE id = ?1", [training_id], |row| row.get(0))
+        .map_err(|_| HrmError::not_found("Training not found"))?;
+    tx.execute("DELETE FROM training_attendance WHERE training_id = ?1", [training_id])?;
+    tx.execute("DELETE FROM trainings WHERE id = ?1", [training_id])?;
+    
+    log_audit_action(
+        &tx,
+        Some(user.user_id),
+        &user.username,
+        "DELETE",
+        "TRAINING",
+        Some(&training_id.to_string()),
+        Some(&course),
+        None,
+        Some(&format!("Deleted training: {}", course)),
+    );
+    tx.commit()?;
+    
+    Ok(())
+}
+
+// Active employees who have not attended one or more mandatory trainings
+#[tauri::command]
+pub fn get_training_needs_report(
+    department: Option<String>,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<Vec<TrainingGap>, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_reports {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view reports."));
+    }
+    
+    let conn = db.0.lock()?;
+    let mandatory = mandatory_trainings(&conn);
+    if mandatory.is_empty() {
+        return Ok(Vec::new());
+    }
+    
+    // Courses attended, matched case-insensitively against the mandatory list
+    let mut attended: HashMap<String, Vec<String>> = HashMap::new();
+    let mut stmt = conn
+        .prepare(
+            "SELECT DISTINCT a.epf_number, LOWER(t.course) FROM training_attendance a
+             JOIN trainings t ON t.id = a.training_id",
+        )?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+    for row in rows {
+        let (epf_number, course) = row?;
+        attended.entry(epf_number).or_default().push(course);
+    }
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT epf_number, name_with_initials, department FROM employees
+             WHERE working_status IN (SELECT code FROM working_statuses WHERE counts_as_active = 1) AND (?1 IS NULL OR department = ?1)
+             ORDER BY department, epf_number",
+        )?;
+    let employees = stmt
+        .query_map([&department], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    let gaps = employees
+        .into_iter()
+        .filter_map(|(epf_number, name_with_initials, department)| {
+            let done = attended.get(&epf_number);
+            let missing: Vec<String> = mandatory
+                .iter()
+                .filter(|course| !done.map(|d| d.contains(&course.to_lowercase())).unwrap_or(false))
+                .cloned()
+                .collect();
+            if missing.is_empty() {
+                None
+            } else {
+                Some(TrainingGap { epf_number, name_with_initials, department, missing_trainings: missing })
+            }
+        })
+        .collect();
+    
+    Ok(gaps)
+}
@@ -0,0 +1,470 @@
+use crate::address_commands::address_errors;
+use crate::custom_fields::custom_value_errors;
+use crate::error::HrmError;
+use crate::export_commands::EXPORT_COLUMNS;
+use crate::models::{Employee, FieldConfig, FieldError, InvalidEmployeeDate};
+use crate::working_status_commands::resolve_status;
+use rusqlite::Connection;
+use std::collections::HashMap;
+
+// Fields that are always required and cannot be reconfigured
+pub const CORE_FIELDS: [&str; 4] = ["epf_number", "name_with_initials", "full_name", "working_status"];
+
+// Working age limits checked against date of birth
+pub(crate) const MIN_AGE: i64 = 16;
+pub(crate) const MAX_AGE: i64 = 70;
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn valid_ymd(year: i64, month: i64, day: i64) -> Option<String> {
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => return None,
+    };
+    if !(1900..=2100).contains(&year) || day < 1 || day > days_in_month {
+        return None;
+    }
+    Some(format!("{:04}-{:02}-{:02}", year, month, day))
+}
+
+// Smallest bare number read as an Excel serial date (1927-05-18)
+const MIN_EXCEL_SERIAL: i64 = 10000;
+
+// Converts an Excel serial day number (1900 date system) to a date
+fn excel_serial_to_date(serial: i64) -> Option<String> {
+    // Excel counts from 1899-12-30 once its fictitious 1900-02-29 is skipped
+    if serial <= 60 {
+        return None;
+    }
+    let z = serial - 25569 + 719468; // days since 1970-01-01, shifted to 0000-03-01
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    valid_ymd(year, month, day)
+}
+
+fn month_from_name(name: &str) -> Option<i64> {
+    const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+    let name = name.to_lowercase();
+    MONTHS
+        .iter()
+        .position(|m| name.starts_with(m))
+        .map(|i| i as i64 + 1)
+}
+
+// Normalizes the date styles found in hand-kept registers to YYYY-MM-DD.
+// Accepts ISO dates, day-first dates with / - or . separators, month names
+// ("15 Jan 2024", "15-Jan-24") and five-digit Excel serial numbers.
+pub fn normalize_date(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    if value.chars().all(|c| c.is_ascii_digit()) && value.len() <= 5 {
+        // Up to four digits is a year ("1985") or a typo, not a real serial
+        let serial: i64 = value.parse().ok()?;
+        return if serial >= MIN_EXCEL_SERIAL { excel_serial_to_date(serial) } else { None };
+    }
+    
+    // Drop a time part such as "2024-01-15 00:00:00"
+    let date_part = value.split_whitespace().collect::<Vec<_>>();
+    let date_part = if date_part.len() == 2 && date_part[1].contains(':') { date_part[0] } else { value };
+    
+    let parts: Vec<&str> = date_part
+        .split(|c: char| c == '/' || c == '-' || c == '.' || c.is_whitespace())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if parts.len() != 3 {
+        return None;
+    }
+    
+    let expand_year = |y: i64, raw: &str| if raw.len() == 2 { if y > 50 { 1900 + y } else { 2000 + y } } else { y };
+    
+    if parts[0].len() == 4 {
+        let year: i64 = parts[0].parse().ok()?;
+        let month = parts[1].parse().ok().or_else(|| month_from_name(parts[1]))?;
+        let day: i64 = parts[2].parse().ok()?;
+        return valid_ymd(year, month, day);
+    }
+    
+    // Registers in Sri Lanka are written day first
+    let day: i64 = parts[0].parse().ok()?;
+    let month = parts[1].parse().ok().or_else(|| month_from_name(parts[1]))?;
+    let year: i64 = parts[2].parse().ok()?;
+    valid_ymd(expand_year(year, parts[2]), month, day)
+}
+
+// Parses a stored YYYY-MM-DD date
+pub fn parse_iso_date(value: &str) -> Option<(i64, i64, i64)> {
+    let parts: Vec<&str> = value.split('-').collect();
+    if parts.len() != 3 || parts[0].len() != 4 {
+        return None;
+    }
+    let (year, month, day) = (parts[0].parse().ok()?, parts[1].parse().ok()?, parts[2].parse().ok()?);
+    valid_ymd(year, month, day).map(|_| (year, month, day))
+}
+
+// A date typed into a form, in any style normalize_date accepts, as
+// YYYY-MM-DD. Bare numbers are refused so a stray "12" is not read as an
+// Excel serial date.
+pub fn normalize_entered_date(value: &str) -> Option<String> {
+    if value.trim().chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    normalize_date(value)
+}
+
+// Display styles for the date_format setting. All are day first or ISO so an
+// exported file reads back through normalize_date.
+pub const DATE_FORMATS: [&str; 5] = ["DD/MM/YYYY", "DD-MM-YYYY", "DD.MM.YYYY", "DD MMM YYYY", "YYYY-MM-DD"];
+
+// Formats a stored YYYY-MM-DD date in one of DATE_FORMATS. With
+// `buddhist_era` the year is shown in the Sri Lankan Buddhist calendar
+// (CE + 544). Values that are not stored dates are returned unchanged.
+pub fn format_date(value: &str, format: &str, buddhist_era: bool) -> String {
+    let Some((year, month, day)) = parse_iso_date(value.trim()) else {
+        return value.to_string();
+    };
+    let year = if buddhist_era { year + 544 } else { year };
+    let formatted = match format {
+        "DD-MM-YYYY" => format!("{:02}-{:02}-{:04}", day, month, year),
+        "DD.MM.YYYY" => format!("{:02}.{:02}.{:04}", day, month, year),
+        "DD MMM YYYY" => {
+            const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+            format!("{:02} {} {:04}", day, MONTHS[(month - 1) as usize], year)
+        }
+        "YYYY-MM-DD" => format!("{:04}-{:02}-{:02}", year, month, day),
+        _ => format!("{:02}/{:02}/{:04}", day, month, year),
+    };
+    if buddhist_era {
+        format!("{} BE", formatted)
+    } else {
+        formatted
+    }
+}
+
+// Employee columns that hold dates
+pub const EMPLOYEE_DATE_COLUMNS: [&str; 3] = ["dob", "date_of_join", "date_of_resign"];
+
+// One-off migration from the free-text era: rewrites employee dates typed in
+// other styles as YYYY-MM-DD. Values that cannot be read are left alone and
+// reported by invalid_employee_dates. Returns the number of values changed.
+pub fn normalize_employee_dates(conn: &Connection) -> rusqlite::Result<usize> {
+    let mut changed = 0;
+    for column in EMPLOYEE_DATE_COLUMNS {
+        let values = conn
+            .prepare(&format!(
+                "SELECT epf_number, {0} FROM employees WHERE {0} <> '' AND {0} IS NOT date({0})",
+                column
+            ))?
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        for (epf_number, value) in values {
+            if let Some(iso) = normalize_date(&value) {
+                conn.execute(
+                    &format!("UPDATE employees SET {} = ?1 WHERE epf_number = ?2", column),
+                    [&iso, &epf_number],
+                )?;
+                changed += 1;
+            }
+        }
+    }
+    Ok(changed)
+}
+
+// Stored employee dates that are not valid YYYY-MM-DD dates, for fixing by hand
+pub fn invalid_employee_dates(conn: &Connection) -> rusqlite::Result<Vec<InvalidEmployeeDate>> {
+    let mut invalid = Vec::new();
+    for column in EMPLOYEE_DATE_COLUMNS {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT epf_number, name_with_initials, {0} FROM employees
+             WHERE {0} <> '' AND {0} IS NOT date({0}) ORDER BY epf_number",
+            column
+        ))?;
+        let rows = stmt.query_map([], |row| {
+            Ok(InvalidEmployeeDate {
+                epf_number: row.get(0)?,
+                name_with_initials: row.get(1)?,
+                field: column.to_string(),
+                value: row.get(2)?,
+            })
+        })?;
+        for row in rows {
+            invalid.push(row?);
+        }
+    }
+    Ok(invalid)
+}
+
+// Whole years between two dates
+fn years_between(from: (i64, i64, i64), to: (i64, i64, i64)) -> i64 {
+    let mut years = to.0 - from.0;
+    if (to.1, to.2) < (from.1, from.2) {
+        years -= 1;
+    }
+    years
+}
+
+// The age a date of birth gives when joining, or today when the join date is
+// unknown, if it falls outside the working age limits
+pub(crate) fn age_outside_working_range(
+    conn: &Connection,
+    dob: (i64, i64, i64),
+    date_of_join: Option<(i64, i64, i64)>,
+) -> Option<i64> {
+    let reference = match date_of_join {
+        Some(join) => join,
+        None => conn
+            .query_row("SELECT date('now', 'localtime')", [], |row| row.get::<_, String>(0))
+            .ok()
+            .and_then(|today| parse_iso_date(&today))?,
+    };
+    let age = years_between(dob, reference);
+    (!(MIN_AGE..=MAX_AGE).contains(&age)).then_some(age)
+}
+
+// Whole months between two dates
+pub fn months_between(from: (i64, i64, i64), to: (i64, i64, i64)) -> i64 {
+    let mut months = (to.0 - from.0) * 12 + (to.1 - from.1);
+    if to.2 < from.2 {
+        months -= 1;
+    }
+    months
+}
+
+// Sri Lankan NIC: 9 digits followed by V or X (old format), or 12 digits
+pub fn normalize_nic(nic: &str) -> Result<String, String> {
+    let nic = nic.trim().to_uppercase();
+    let bytes = nic.as_bytes();
+    let old_format = bytes.len() == 10
+        && bytes[..9].iter().all(u8::is_ascii_digit)
+        && (bytes[9] == b'V' || bytes[9] == b'X');
+    let new_format = bytes.len() == 12 && bytes.iter().all(u8::is_ascii_digit);
+    if old_format || new_format {
+        Ok(nic)
+    } else {
+        Err("NIC must be 9 digits followed by V/X, or 12 digits".to_string())
+    }
+}
+
+// Whether a Sri Lankan NIC belongs to a woman: 500 is added to the birth
+// day-of-year for women. None when the NIC is not in either known format.
+pub fn nic_is_female(nic: &str) -> Option<bool> {
+    let nic = normalize_nic(nic).ok()?;
+    let day_digits = if nic.len() == 10 { &nic[2..5] } else { &nic[4..7] };
+    let day: u32 = day_digits.parse().ok()?;
+    match day {
+        1..=366 => Some(false),
+        501..=866 => Some(true),
+        _ => None,
+    }
+}
+
+// Normalizes a Sri Lankan phone number to +94XXXXXXXXX. Accepts local
+// (0771234567), bare (771234567) and international (94.., +94..) forms with
+// any spaces, dashes or brackets.
+pub fn normalize_mobile(value: &str) -> Result<String, String> {
+    let digits: String = value.chars().filter(|c| c.is_ascii_digit()).collect();
+    // International dialling prefix, as in 0094 77 123 4567
+    let digits = digits.strip_prefix("00").map(str::to_string).unwrap_or(digits);
+    let national = if let Some(rest) = digits.strip_prefix("94").filter(|r| r.len() == 9) {
+        rest
+    } else if let Some(rest) = digits.strip_prefix('0').filter(|r| r.len() == 9) {
+        rest
+    } else if digits.len() == 9 {
+        digits.as_str()
+    } else {
+        return Err(format!("'{}' is not a valid Sri Lankan phone number", value.trim()));
+    };
+    Ok(format!("+94{}", national))
+}
+
+// Visibility of every employee field, applying the field_config table over
+// the defaults (core fields required, everything else optional)
+pub fn field_config(conn: &Connection) -> Vec<FieldConfig> {
+    let configured: HashMap<String, String> = conn
+        .prepare("SELECT field, visibility FROM field_config")
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<HashMap<_, _>, _>>()
+        })
+        .unwrap_or_default();
+    
+    EXPORT_COLUMNS
+        .iter()
+        .map(|(field, label)| {
+            let visibility = if CORE_FIELDS.contains(field) {
+                "required".to_string()
+            } else {
+                configured.get(*field).cloned().unwrap_or_else(|| "optional".to_string())
+            };
+            FieldConfig { field: field.to_string(), label: label.to_string(), visibility }
+        })
+        .collect()
+}
+
+// Clears fields the installation has hidden, so they are never stored
+pub fn clear_hidden_fields(conn: &Connection, employee: &mut Employee) {
+    for config in field_config(conn).iter().filter(|c| c.visibility == "hidden") {
+        let slot = match config.field.as_str() {
+            "dob" => &mut employee.dob,
+            "police_area" => &mut employee.police_area,
+            "transport_route" => &mut employee.transport_route,
+            "mobile_1" => &mut employee.mobile_1,
+            "mobile_2" => &mut employee.mobile_2,
+            "address" => &mut employee.address,
+            "district" => &mut employee.district,
+            "ds_division" => &mut employee.ds_division,
+            "gn_division" => &mut employee.gn_division,
+            "nic" => &mut employee.nic,
+            "date_of_join" => &mut employee.date_of_join,
+            "date_of_resign" => &mut employee.date_of_resign,
+            "marital_status" => &mut employee.marital_status,
+            "cader" => &mut employee.cader,
+            "designation" => &mut employee.designation,
+            "allocation" => &mut employee.allocation,
+            "department" => &mut employee.department,
+            _ => continue,
+        };
+        *slot = None;
+    }
+}
+
+fn field_value<'a>(employee: &'a Employee, field: &str) -> Option<&'a str> {
+    match field {
+        "epf_number" => Some(employee.epf_number.as_str()),
+        "name_with_initials" => Some(employee.name_with_initials.as_str()),
+        "full_name" => Some(employee.full_name.as_str()),
+        "nic" => employee.nic.as_deref(),
+        "working_status" => Some(employee.working_status.as_str()),
+        "dob" => employee.dob.as_deref(),
+        "police_area" => employee.police_area.as_deref(),
+        "transport_route" => employee.transport_route.as_deref(),
+        "mobile_1" => employee.mobile_1.as_deref(),
+        "mobile_2" => employee.mobile_2.as_deref(),
+        "address" => employee.address.as_deref(),
+        "district" => employee.district.as_deref(),
+        "ds_division" => employee.ds_division.as_deref(),
+        "gn_division" => employee.gn_division.as_deref(),
+        "date_of_join" => employee.date_of_join.as_deref(),
+        "date_of_resign" => employee.date_of_resign.as_deref(),
+        "marital_status" => employee.marital_status.as_deref(),
+        "cader" => employee.cader.as_deref(),
+        "designation" => employee.designation.as_deref(),
+        "allocation" => employee.allocation.as_deref(),
+        "department" => employee.department.as_deref(),
+        _ => None,
+    }
+}
+
+fn field_label(field: &str) -> &str {
+    EXPORT_COLUMNS
+        .iter()
+        .find(|(known, _)| *known == field)
+        .map(|(_, label)| *label)
+        .unwrap_or(field)
+}
+
+// Checks an employee before it is saved, normalizing mobile numbers and dates
+// in place.
+// Every problem is collected so the form can show them all at once.
+pub fn validate_employee(conn: &Connection, employee: &mut Employee) -> Result<(), HrmError> {
+    let mut errors: Vec<FieldError> = Vec::new();
+    let mut fail = |field: &str, message: String| errors.push(FieldError { field: field.to_string(), message });
+    
+    employee.epf_number = employee.epf_number.trim().to_string();
+    
+    for config in field_config(conn).iter().filter(|c| c.visibility == "required") {
+        if field_value(employee, &config.field).map(|v| v.trim().is_empty()).unwrap_or(true) {
+            fail(&config.field, format!("{} is required", config.label));
+        }
+    }
+    
+    match resolve_status(conn, &employee.working_status) {
+        Ok(Some(code)) => employee.working_status = code,
+        _ => fail("working_status", format!("Unknown working status '{}'", employee.working_status)),
+    }
+    
+    for field in ["mobile_1", "mobile_2"] {
+        let slot = if field == "mobile_1" { &mut employee.mobile_1 } else { &mut employee.mobile_2 };
+        match slot.as_deref().map(str::trim) {
+            None | Some("") => *slot = None,
+            Some(raw) => match normalize_mobile(raw) {
+                Ok(normalized) => *slot = Some(normalized),
+                Err(message) => fail(field, message),
+            },
+        }
+    }
+    
+    match employee.nic.as_deref().map(str::trim) {
+        None | Some("") => employee.nic = None,
+        Some(raw) => match normalize_nic(raw) {
+            Ok(nic) => {
+                // The same person cannot be on the active roll twice
+                let duplicate: Option<String> = conn
+                    .query_row(
+                        "SELECT epf_number FROM employees WHERE nic = ?1 AND working_status IN (SELECT code FROM working_statuses WHERE counts_as_active = 1) AND epf_number != ?2",
+                        rusqlite::params![nic, employee.epf_number],
+                        |row| row.get(0),
+                    )
+                    .ok();
+                if let Some(other) = duplicate {
+                    fail("nic", format!("NIC {} already belongs to active employee {}", nic, other));
+                }
+                employee.nic = Some(nic);
+            }
+            Err(message) => fail("nic", message),
+        },
+    }
+    
+    // Dates are stored as YYYY-MM-DD whatever style they were typed in
+    let mut dates = std::collections::HashMap::new();
+    for field in EMPLOYEE_DATE_COLUMNS {
+        let slot = match field {
+            "dob" => &mut employee.dob,
+            "date_of_join" => &mut employee.date_of_join,
+            _ => &mut employee.date_of_resign,
+        };
+        let Some(value) = slot.as_deref().map(str::trim).filter(|v| !v.is_empty()) else {
+            continue;
+        };
+        match normalize_entered_date(value).and_then(|iso| parse_iso_date(&iso).map(|date| (iso, date))) {
+            Some((iso, date)) => {
+                dates.insert(field, date);
+                *slot = Some(iso);
+            }
+            None => fail(field, format!("{} must be a valid date, e.g. 15/01/2024 or 2024-01-15", field_label(field))),
+        }
+    }
+    
+    if let (Some(join), Some(resign)) = (dates.get("date_of_join"), dates.get("date_of_resign")) {
+        if resign < join {
+            fail("date_of_resign", "Date of resignation cannot be before the date of joining".to_string());
+        }
+    }
+    
+    if let Some(dob) = dates.get("dob") {
+        if let Some(age) = age_outside_working_range(conn, *dob, dates.get("date_of_join").copied()) {
+            fail("dob", format!("Date of birth gives an age of {}; expected between {} and {}", age, MIN_AGE, MAX_AGE));
+        }
+    }
+    
+    errors.extend(address_errors(conn, employee));
+    errors.extend(custom_value_errors(conn, employee));
+    
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(HrmError::fields(format!("{} field(s) need attention", errors.len()), errors))
+    }
+}
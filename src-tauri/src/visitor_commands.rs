@@ -0,0 +1,182 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{UserSession, Visitor, VisitorDayReport};
+use crate::validation::{normalize_nic, parse_iso_date};
+use crate::{CurrentUser, DbConnection};
+use rusqlite::Connection;
+use tauri::State;
+
+fn logged_in_user(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    user_lock.clone().ok_or_else(HrmError::not_logged_in)
+}
+
+const VISITOR_COLUMNS: &str = "v.id, v.name, v.nic, v.company, v.purpose, v.host_epf_number, e.name_with_initials,
+                               v.badge_number, v.time_in, v.time_out, v.checked_in_by";
+
+fn visitor_from_row(row: &rusqlite::Row) -> rusqlite::Result<Visitor> {
+    Ok(Visitor {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        nic: row.get(2)?,
+        company: row.get(3)?,
+        purpose: row.get(4)?,
+        host_epf_number: row.get(5)?,
+        host_name: row.get(6)?,
+        badge_number: row.get(7)?,
+        time_in: row.get(8)?,
+        time_out: row.get(9)?,
+        checked_in_by: row.get(10)?,
+    })
+}
+
+fn query_visitors(conn: &Connection, condition: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<Visitor>, HrmError> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM visitors v LEFT JOIN employees e ON e.epf_number = v.host_epf_number
+             WHERE {} ORDER BY v.time_in",
+            VISITOR_COLUMNS, condition
+        ))?;
+    let visitors = stmt
+        .query_map(params, visitor_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(visitors)
+}
+
+// Visitors currently on the premises
+#[tauri::command]
+pub fn get_visitors_on_site(db: State<'_, DbConnection>) -> Result<Vec<Visitor>, HrmError> {
+    let conn = db.0.lock()?;
+    query_visitors(&conn, "v.time_out IS NULL", &[])
+}
+
+#[tauri::command]
+pub fn check_in_visitor(
+    visitor: Visitor,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<i64, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if visitor.name.trim().is_empty() {
+        return Err(HrmError::validation("Visitor name is required"));
+    }
+    let nic = normalize_nic(&visitor.nic)?;
+    let badge_number = visitor.badge_number.as_deref().map(str::trim).filter(|b| !b.is_empty());
+    
+    let conn = db.0.lock()?;
+    if let Some(host) = visitor.host_epf_number.as_deref().filter(|h| !h.is_empty()) {
+        let host_exists: bool = conn
+            .query_row("SELECT COUNT(*) > 0 FROM employees WHERE epf_number = ?1", [host], |row| row.get(0))?;
+        if !host_exists {
+            return Err(HrmError::not_found(format!("Host employee {} not found", host)));
+        }
+    }
+    
+    // A visitor cannot be checked in twice, and a badge cannot be handed out twice
+    let already_in: bool = conn
+        .query_row("SELECT COUNT(*) > 0 FROM visitors WHERE nic = ?1 AND time_out IS NULL", [&nic], |row| row.get(0))?;
+    if already_in {
+        return Err(HrmError::validation(format!("Visitor with NIC {} is already checked in", nic)));
+    }
+    if let Some(badge) = badge_number {
+        let badge_in_use: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM visitors WHERE badge_number = ?1 AND time_out IS NULL",
+                [badge],
+                |row| row.get(0),
+            )?;
+        if badge_in_use {
+            return Err(HrmError::validation(format!("Badge {} has not been returned yet", badge)));
+        }
+    }
+    
+    conn.execute(
+        "INSERT INTO visitors (name, nic, company, purpose, host_epf_number, badge_number, time_in, checked_in_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, strftime('%Y-%m-%d %H:%M:%S', 'now', 'localtime'), ?7)",
+        rusqlite::params![
+            visitor.name.trim(),
+            nic,
+            visitor.company,
+            visitor.purpose,
+            visitor.host_epf_number.as_deref().filter(|h| !h.is_empty()),
+            badge_number,
+            user.username,
+        ],
+    )?;
+    let visitor_id = conn.last_insert_rowid();
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CHECK_IN",
+        "VISITOR",
+        Some(&visitor_id.to_string()),
+        None,
+        None,
+        Some(&format!("Visitor {} ({}) checked in", visitor.name.trim(), nic)),
+    );
+    
+    Ok(visitor_id)
+}
+
+#[tauri::command]
+pub fn check_out_visitor(
+    visitor_id: i64,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = logged_in_user(&current_user)?;
+    
+    let conn = db.0.lock()?;
+    let visitor = query_visitors(&conn, "v.id = ?1", &[&visitor_id])?
+        .pop()
+        .ok_or_else(|| HrmError::not_found("Visitor not found"))?;
+    if visitor.time_out.is_some() {
+        return Err(HrmError::validation(format!("{} has already checked out", visitor.name)));
+    }
+    
+    conn.execute(
+        "UPDATE visitors SET time_out = strftime('%Y-%m-%d %H:%M:%S', 'now', 'localtime') WHERE id = ?1",
+        [visitor_id],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "CHECK_OUT",
+        "VISITOR",
+        Some(&visitor_id.to_string()),
+        None,
+        None,
+        Some(&format!("Visitor {} ({}) checked out", visitor.name, visitor.nic)),
+    );
+    
+    Ok(())
+}
+
+// Everyone who came in on the given day, replacing the gate office book
+#[tauri::command]
+pub fn get_daily_visitors_report(
+    date: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<VisitorDayReport, HrmError> {
+    let user = logged_in_user(&current_user)?;
+    if !user.permissions.can_view_reports {
+        return Err(HrmError::permission_denied("Permission denied. You cannot view reports."));
+    }
+    parse_iso_date(&date).ok_or("Date must be a valid date (YYYY-MM-DD)")?;
+    
+    let conn = db.0.lock()?;
+    let visitors = query_visitors(&conn, "date(v.time_in) = ?1", &[&date])?;
+    let still_on_site = visitors.iter().filter(|v| v.time_out.is_none()).count() as i32;
+    
+    Ok(VisitorDayReport {
+        date,
+        total_visitors: visitors.len() as i32,
+        still_on_site,
+        visitors,
+    })
+}
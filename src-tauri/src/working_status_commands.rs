@@ -0,0 +1,171 @@
+use crate::commands::log_audit_action;
+use crate::error::HrmError;
+use crate::models::{UserSession, WorkingStatus};
+use crate::{CurrentUser, DbConnection};
+use rusqlite::{Connection, OptionalExtension};
+use tauri::State;
+
+// Built-in statuses; other code (resignation, rehire) relies on these two
+const SYSTEM_STATUSES: [&str; 2] = ["active", "resign"];
+
+fn require_settings_permission(current_user: &State<'_, CurrentUser>) -> Result<UserSession, HrmError> {
+    let user_lock = current_user.0.lock()?;
+    match &*user_lock {
+        Some(session) if session.permissions.can_manage_settings => Ok(session.clone()),
+        Some(_) => Err(HrmError::permission_denied("Permission denied. You cannot change system settings.")),
+        None => Err(HrmError::not_logged_in()),
+    }
+}
+
+pub(crate) fn load_statuses(conn: &Connection) -> rusqlite::Result<Vec<WorkingStatus>> {
+    let mut stmt = conn.prepare(
+        "SELECT code, label, counts_as_active, color, sort_order, is_system FROM working_statuses ORDER BY sort_order, label",
+    )?;
+    let statuses = stmt
+        .query_map([], |row| {
+            Ok(WorkingStatus {
+                code: row.get(0)?,
+                label: row.get(1)?,
+                counts_as_active: row.get(2)?,
+                color: row.get(3)?,
+                sort_order: row.get(4)?,
+                is_system: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(statuses)
+}
+
+// Resolves a status typed by a user or found in an import (code or label, any
+// case) to its stored code
+pub(crate) fn resolve_status(conn: &Connection, value: &str) -> rusqlite::Result<Option<String>> {
+    conn.query_row(
+        "SELECT code FROM working_statuses WHERE lower(code) = lower(?1) OR lower(label) = lower(?1)",
+        [value.trim()],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+// Whether employees in this status count towards headcount and may use
+// services such as meals and advances
+pub(crate) fn counts_as_active(conn: &Connection, code: &str) -> rusqlite::Result<bool> {
+    Ok(conn
+        .query_row("SELECT counts_as_active FROM working_statuses WHERE code = ?1", [code], |row| row.get(0))
+        .optional()?
+        .unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn get_working_statuses(db: State<'_, DbConnection>) -> Result<Vec<WorkingStatus>, HrmError> {
+    let conn = db.0.lock()?;
+    load_statuses(&conn).map_err(HrmError::from)
+}
+
+// Adds a status or updates an existing one. The built-in statuses can be
+// relabelled and recoloured but always keep their counts-as-active flag.
+#[tauri::command]
+pub fn save_working_status(
+    status: WorkingStatus,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = require_settings_permission(&current_user)?;
+    let code = status.code.trim().to_lowercase().replace(' ', "_");
+    let label = status.label.trim();
+    if code.is_empty() || label.is_empty() {
+        return Err(HrmError::validation("Status code and label are required"));
+    }
+    if !code.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err(HrmError::validation("Status code may only contain letters, numbers, '-' and '_'"));
+    }
+    let color = status.color.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    if let Some(color) = color {
+        let hex = color.strip_prefix('#').unwrap_or("");
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(HrmError::validation("Colour must be a hex value such as #16a34a"));
+        }
+    }
+    
+    let conn = db.0.lock()?;
+    let duplicate_label: Option<String> = conn
+        .query_row(
+            "SELECT code FROM working_statuses WHERE lower(label) = lower(?1) AND code != ?2",
+            rusqlite::params![label, code],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if duplicate_label.is_some() {
+        return Err(HrmError::conflict(format!("A status labelled '{}' already exists", label)));
+    }
+    
+    let existing = load_statuses(&conn)?
+        .into_iter()
+        .find(|s| s.code == code);
+    let counts_as_active = match &existing {
+        Some(s) if s.is_system => s.counts_as_active,
+        _ => status.counts_as_active,
+    };
+    conn.execute(
+        "INSERT INTO working_statuses (code, label, counts_as_active, color, sort_order) VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(code) DO UPDATE SET label = ?2, counts_as_active = ?3, color = ?4, sort_order = ?5",
+        rusqlite::params![code, label, counts_as_active, color, status.sort_order],
+    )?;
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        if existing.is_some() { "UPDATE" } else { "CREATE" },
+        "WORKING_STATUS",
+        Some(&code),
+        existing.as_ref().map(|s| s.label.as_str()),
+        Some(label),
+        Some(&format!(
+            "Working status '{}' ({})",
+            label,
+            if counts_as_active { "counts as active" } else { "not active" }
+        )),
+    );
+    
+    Ok(())
+}
+
+// Only unused, non-built-in statuses can be removed
+#[tauri::command]
+pub fn delete_working_status(
+    code: String,
+    db: State<'_, DbConnection>,
+    current_user: State<'_, CurrentUser>,
+) -> Result<(), HrmError> {
+    let user = require_settings_permission(&current_user)?;
+    if SYSTEM_STATUSES.contains(&code.as_str()) {
+        return Err(HrmError::validation("Built-in statuses cannot be deleted"));
+    }
+    
+    let conn = db.0.lock()?;
+    let in_use: i64 = conn
+        .query_row("SELECT COUNT(*) FROM employees WHERE working_status = ?1", [&code], |row| row.get(0))?;
+    if in_use > 0 {
+        return Err(HrmError::validation(format!("{} employee(s) still have this status", in_use)));
+    }
+    let deleted = conn
+        .execute("DELETE FROM working_statuses WHERE code = ?1", [&code])?;
+    if deleted == 0 {
+        return Err(HrmError::not_found("Working status not found"));
+    }
+    
+    log_audit_action(
+        &conn,
+        Some(user.user_id),
+        &user.username,
+        "DELETE",
+        "WORKING_STATUS",
+        Some(&code),
+        Some(&code),
+        None,
+        Some(&format!("Deleted working status '{}'", code)),
+    );
+    
+    Ok(())
+}
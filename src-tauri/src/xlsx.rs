@@ -0,0 +1,220 @@
+use crate::error::HrmError;
+use crate::import_commands::xml_escape;
+use std::fs::File;
+use std::io::Write;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+// Plain Excel workbooks (Office Open XML) for templates and exports: text is
+// written inline so there is no shared string table, and only the handful of
+// cell styles below exist.
+
+const COLUMN_WIDTH: f32 = 18.0;
+
+#[derive(Clone, Copy)]
+pub(crate) enum CellStyle {
+    Plain,
+    // White on blue, for column headings
+    Header,
+    // White on red, for headings of columns that must be filled in
+    Required,
+    // Grey italics, for sample rows
+    Example,
+}
+
+pub(crate) struct Cell {
+    pub value: String,
+    pub number: bool,
+    pub style: CellStyle,
+}
+
+impl Cell {
+    pub fn text(value: impl Into<String>, style: CellStyle) -> Self {
+        Cell { value: value.into(), number: false, style }
+    }
+    
+    pub fn number(value: impl Into<String>) -> Self {
+        Cell { value: value.into(), number: true, style: CellStyle::Plain }
+    }
+}
+
+// A dropdown on rows 2..=last_row of a column (0-based), offering the cells
+// named by `source`, e.g. "Lists!$A$2:$A$9"
+pub(crate) struct Dropdown {
+    pub column: usize,
+    pub last_row: usize,
+    pub source: String,
+}
+
+pub(crate) struct Worksheet {
+    pub name: String,
+    pub rows: Vec<Vec<Cell>>,
+    pub dropdowns: Vec<Dropdown>,
+}
+
+// Spreadsheet column letters: 0 -> A, 25 -> Z, 26 -> AA
+pub(crate) fn column_name(index: usize) -> String {
+    let mut name = Vec::new();
+    let mut n = index + 1;
+    while n > 0 {
+        n -= 1;
+        name.push(b'A' + (n % 26) as u8);
+        n /= 26;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+fn style_index(style: CellStyle) -> usize {
+    match style {
+        CellStyle::Plain => 0,
+        CellStyle::Header => 1,
+        CellStyle::Required => 2,
+        CellStyle::Example => 3,
+    }
+}
+
+fn sheet_xml(sheet: &Worksheet) -> String {
+    let columns = sheet.rows.iter().map(Vec::len).max().unwrap_or(0);
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+<worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">",
+    );
+    if columns > 0 {
+        xml.push_str(&format!(
+            "<cols><col min=\"1\" max=\"{}\" width=\"{}\" customWidth=\"1\"/></cols>",
+            columns, COLUMN_WIDTH
+        ));
+    }
+    xml.push_str("<sheetData>");
+    for (row_index, row) in sheet.rows.iter().enumerate() {
+        xml.push_str(&format!("<row r=\"{}\">", row_index + 1));
+        for (column, cell) in row.iter().enumerate() {
+            if cell.value.is_empty() && matches!(cell.style, CellStyle::Plain) {
+                continue;
+            }
+            let reference = format!("{}{}", column_name(column), row_index + 1);
+            let style = style_index(cell.style);
+            if cell.number {
+                xml.push_str(&format!("<c r=\"{}\" s=\"{}\"><v>{}</v></c>", reference, style, xml_escape(&cell.value)));
+            } else {
+                xml.push_str(&format!(
+                    "<c r=\"{}\" s=\"{}\" t=\"inlineStr\"><is><t xml:space=\"preserve\">{}</t></is></c>",
+                    reference,
+                    style,
+                    xml_escape(&cell.value)
+                ));
+            }
+        }
+        xml.push_str("</row>");
+    }
+    xml.push_str("</sheetData>");
+    if !sheet.dropdowns.is_empty() {
+        xml.push_str(&format!("<dataValidations count=\"{}\">", sheet.dropdowns.len()));
+        for dropdown in &sheet.dropdowns {
+            let column = column_name(dropdown.column);
+            xml.push_str(&format!(
+                "<dataValidation type=\"list\" allowBlank=\"1\" showErrorMessage=\"1\" sqref=\"{0}2:{0}{1}\"><formula1>{2}</formula1></dataValidation>",
+                column,
+                dropdown.last_row,
+                xml_escape(&dropdown.source)
+            ));
+        }
+        xml.push_str("</dataValidations>");
+    }
+    xml.push_str("</worksheet>");
+    xml
+}
+
+// Writes the sheets, in order, as an .xlsx workbook
+pub(crate) fn write_xlsx(path: &str, sheets: &[Worksheet]) -> Result<(), HrmError> {
+    let zip_error = |e: zip::result::ZipError| HrmError::io(format!("Failed to write workbook: {}", e));
+    let file = File::create(path).map_err(|e| HrmError::io(format!("Failed to save file: {}", e)))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    
+    let mut content_types = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+<Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+<Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+<Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>\
+<Override PartName=\"/xl/styles.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml\"/>",
+    );
+    let mut workbook = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+<workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" \
+xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\"><sheets>",
+    );
+    let mut workbook_rels = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">",
+    );
+    for (index, sheet) in sheets.iter().enumerate() {
+        let number = index + 1;
+        content_types.push_str(&format!(
+            "<Override PartName=\"/xl/worksheets/sheet{}.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>",
+            number
+        ));
+        workbook.push_str(&format!(
+            "<sheet name=\"{}\" sheetId=\"{}\" r:id=\"rId{}\"/>",
+            xml_escape(&sheet.name),
+            number,
+            number
+        ));
+        workbook_rels.push_str(&format!(
+            "<Relationship Id=\"rId{0}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet\" Target=\"worksheets/sheet{0}.xml\"/>",
+            number
+        ));
+    }
+    content_types.push_str("</Types>");
+    workbook.push_str("</sheets></workbook>");
+    workbook_rels.push_str(&format!(
+        "<Relationship Id=\"rId{}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles\" Target=\"styles.xml\"/></Relationships>",
+        sheets.len() + 1
+    ));
+    
+    let mut parts = vec![
+        ("[Content_Types].xml".to_string(), content_types),
+        (
+            "_rels/.rels".to_string(),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+<Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"xl/workbook.xml\"/>\
+</Relationships>"
+                .to_string(),
+        ),
+        ("xl/workbook.xml".to_string(), workbook),
+        ("xl/_rels/workbook.xml.rels".to_string(), workbook_rels),
+        (
+            "xl/styles.xml".to_string(),
+            // Cell formats in CellStyle order: plain, header, required, example
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+<styleSheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">\
+<fonts count=\"3\"><font><sz val=\"11\"/><name val=\"Calibri\"/></font>\
+<font><b/><sz val=\"11\"/><color rgb=\"FFFFFFFF\"/><name val=\"Calibri\"/></font>\
+<font><i/><sz val=\"11\"/><color rgb=\"FF6B7280\"/><name val=\"Calibri\"/></font></fonts>\
+<fills count=\"4\"><fill><patternFill patternType=\"none\"/></fill><fill><patternFill patternType=\"gray125\"/></fill>\
+<fill><patternFill patternType=\"solid\"><fgColor rgb=\"FF2563EB\"/></patternFill></fill>\
+<fill><patternFill patternType=\"solid\"><fgColor rgb=\"FFB91C1C\"/></patternFill></fill></fills>\
+<borders count=\"1\"><border/></borders>\
+<cellStyleXfs count=\"1\"><xf numFmtId=\"0\" fontId=\"0\" fillId=\"0\" borderId=\"0\"/></cellStyleXfs>\
+<cellXfs count=\"4\"><xf numFmtId=\"0\" fontId=\"0\" fillId=\"0\" borderId=\"0\" xfId=\"0\"/>\
+<xf numFmtId=\"0\" fontId=\"1\" fillId=\"2\" borderId=\"0\" xfId=\"0\" applyFont=\"1\" applyFill=\"1\"/>\
+<xf numFmtId=\"0\" fontId=\"1\" fillId=\"3\" borderId=\"0\" xfId=\"0\" applyFont=\"1\" applyFill=\"1\"/>\
+<xf numFmtId=\"0\" fontId=\"2\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyFont=\"1\"/></cellXfs>\
+</styleSheet>"
+                .to_string(),
+        ),
+    ];
+    for (index, sheet) in sheets.iter().enumerate() {
+        parts.push((format!("xl/worksheets/sheet{}.xml", index + 1), sheet_xml(sheet)));
+    }
+    
+    for (name, content) in parts {
+        zip.start_file(name, options).map_err(zip_error)?;
+        zip.write_all(content.as_bytes())?;
+    }
+    zip.finish().map_err(zip_error)?;
+    Ok(())
+}
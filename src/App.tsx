@@ -11,6 +11,7 @@ import Login from "./components/Login";
 import UserManagement from "./components/UserManagement";
 import DatabaseBackup from "./components/DatabaseBackup";
 import AuditLogViewer from "./components/AuditLogViewer";
+import { ErrorService } from "./services/ErrorService";
 
 // Developer info - Update these with your details
 const DEVELOPER_NAME = "Asitha Kanchana";
@@ -29,7 +30,7 @@ function AppContent() {
         setDbInitialized(true);
       } catch (error) {
         console.error("Failed to initialize database:", error);
-        setInitError(String(error));
+        setInitError(ErrorService.describe(error));
       }
     };
     initDb();
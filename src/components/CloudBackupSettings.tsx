@@ -0,0 +1,198 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { save, open } from "@tauri-apps/plugin-dialog";
+import { ErrorService } from "../services/ErrorService";
+import type { CloudBackupConfig, CloudBackupSettings as Settings } from "../types/employee";
+
+const EMPTY_SETTINGS: Settings = {
+  enabled: false,
+  endpoint: "",
+  bucket: "",
+  region: "us-east-1",
+  prefix: "hrm-backups",
+  access_key_id: "",
+  secret_access_key: null,
+  passphrase: null,
+};
+
+function CloudBackupSettings() {
+  const [config, setConfig] = useState<CloudBackupConfig | null>(null);
+  const [form, setForm] = useState<Settings>(EMPTY_SETTINGS);
+  const [busy, setBusy] = useState(false);
+  const [message, setMessage] = useState<{ type: "success" | "error"; text: string } | null>(null);
+
+  useEffect(() => {
+    loadConfig();
+  }, []);
+
+  const loadConfig = async () => {
+    try {
+      const loaded = await invoke<CloudBackupConfig>("get_cloud_backup_config");
+      setConfig(loaded);
+      setForm({
+        enabled: loaded.enabled,
+        endpoint: loaded.endpoint,
+        bucket: loaded.bucket,
+        region: loaded.region,
+        prefix: loaded.prefix,
+        access_key_id: loaded.access_key_id,
+        secret_access_key: null,
+        passphrase: null,
+      });
+    } catch (error) {
+      console.error("Failed to load cloud backup settings:", error);
+    }
+  };
+
+  const run = async (action: () => Promise<string>) => {
+    try {
+      setBusy(true);
+      setMessage(null);
+      setMessage({ type: "success", text: await action() });
+      loadConfig();
+    } catch (error) {
+      setMessage({ type: "error", text: ErrorService.describe(error) });
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const handleSave = () =>
+    run(async () => {
+      await invoke("save_cloud_backup_config", { settings: form });
+      return "Cloud backup settings saved";
+    });
+
+  const handleUploadNow = () =>
+    run(async () => {
+      const objectKey = await invoke<string>("upload_backup_now");
+      return `Uploaded to ${objectKey}`;
+    });
+
+  const handleDecrypt = async () => {
+    const sourcePath = await open({
+      filters: [{ name: "Encrypted HRM Backup", extensions: ["enc"] }],
+      title: "Select Downloaded Backup",
+      multiple: false,
+    });
+    if (!sourcePath) return;
+    const passphrase = prompt("Enter the backup encryption passphrase");
+    if (!passphrase) return;
+    const destinationPath = await save({
+      defaultPath: "hrm_restored.db",
+      filters: [{ name: "SQLite Database", extensions: ["db"] }],
+      title: "Save Decrypted Database",
+    });
+    if (!destinationPath) return;
+    run(() => invoke<string>("decrypt_backup_file", { sourcePath, destinationPath, passphrase }));
+  };
+
+  const field = (key: "endpoint" | "bucket" | "region" | "prefix" | "access_key_id", label: string, placeholder = "") => (
+    <div>
+      <label className="block text-sm font-medium text-gray-700 mb-1">{label}</label>
+      <input
+        type="text"
+        value={form[key]}
+        onChange={(e) => setForm({ ...form, [key]: e.target.value })}
+        className="input-field"
+        placeholder={placeholder}
+      />
+    </div>
+  );
+
+  return (
+    <div className="bg-white rounded-xl shadow-sm border border-gray-200 p-6 mt-6">
+      <h3 className="text-lg font-semibold text-gray-800 mb-1">Offsite Cloud Backup</h3>
+      <p className="text-sm text-gray-500 mb-4">
+        Uploads each day's automatic backup, encrypted, to an S3-compatible bucket (AWS S3, Backblaze B2, Wasabi,
+        Cloudflare R2, MinIO or Google Cloud Storage with HMAC keys).
+      </p>
+
+      {message && (
+        <div
+          className={`mb-4 px-4 py-3 rounded-lg text-sm ${
+            message.type === "success"
+              ? "bg-green-50 border border-green-200 text-green-700"
+              : "bg-red-50 border border-red-200 text-red-700"
+          }`}
+        >
+          {message.text}
+        </div>
+      )}
+
+      {config?.last_error && (
+        <div className="mb-4 px-4 py-3 rounded-lg text-sm bg-yellow-50 border border-yellow-200 text-yellow-800">
+          Last upload failed: {config.last_error}
+        </div>
+      )}
+      {config?.last_upload_at && (
+        <p className="text-sm text-gray-600 mb-4">
+          Last uploaded {config.last_uploaded_backup} at {config.last_upload_at}
+        </p>
+      )}
+
+      <label className="flex items-center gap-2 mb-4 text-sm text-gray-700">
+        <input
+          type="checkbox"
+          checked={form.enabled}
+          onChange={(e) => setForm({ ...form, enabled: e.target.checked })}
+        />
+        Upload automatic backups
+      </label>
+
+      <div className="grid grid-cols-1 md:grid-cols-2 gap-4">
+        {field("endpoint", "Endpoint", "https://s3.eu-central-1.amazonaws.com")}
+        {field("bucket", "Bucket")}
+        {field("region", "Region", "us-east-1")}
+        {field("prefix", "Folder", "hrm-backups")}
+        {field("access_key_id", "Access Key ID")}
+        <div>
+          <label className="block text-sm font-medium text-gray-700 mb-1">Secret Access Key</label>
+          <input
+            type="password"
+            value={form.secret_access_key ?? ""}
+            onChange={(e) => setForm({ ...form, secret_access_key: e.target.value || null })}
+            className="input-field"
+            placeholder={config?.has_secret_access_key ? "Saved - leave blank to keep" : ""}
+          />
+        </div>
+        <div className="md:col-span-2">
+          <label className="block text-sm font-medium text-gray-700 mb-1">Encryption Passphrase</label>
+          <input
+            type="password"
+            value={form.passphrase ?? ""}
+            onChange={(e) => setForm({ ...form, passphrase: e.target.value || null })}
+            className="input-field"
+            placeholder={config?.has_passphrase ? "Saved - leave blank to keep" : "At least 12 characters"}
+          />
+          <p className="text-xs text-gray-500 mt-1">
+            Write this down and keep it away from this PC. Backups cannot be opened without it, and backups
+            uploaded before a change still need the old passphrase.
+          </p>
+        </div>
+      </div>
+
+      <div className="flex flex-wrap gap-3 mt-6">
+        <button onClick={handleSave} disabled={busy} className="btn-primary py-2 px-4 disabled:opacity-50">
+          Save Settings
+        </button>
+        <button
+          onClick={handleUploadNow}
+          disabled={busy || !config?.enabled}
+          className="py-2 px-4 text-sm border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+        >
+          Upload Latest Backup Now
+        </button>
+        <button
+          onClick={handleDecrypt}
+          disabled={busy}
+          className="py-2 px-4 text-sm border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+        >
+          Decrypt Downloaded Backup
+        </button>
+      </div>
+    </div>
+  );
+}
+
+export default CloudBackupSettings;
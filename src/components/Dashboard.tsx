@@ -12,6 +12,7 @@ function Dashboard() {
     allocations: [],
     recent_joinings: 0,
     recent_resignations: 0,
+    status_counts: [],
   });
   const [loading, setLoading] = useState(true);
 
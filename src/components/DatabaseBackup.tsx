@@ -2,6 +2,9 @@ import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { save, open } from "@tauri-apps/plugin-dialog";
 import { useAuth } from "../context/AuthContext";
+import { ErrorService } from "../services/ErrorService";
+import type { BackupInfo } from "../types/employee";
+import CloudBackupSettings from "./CloudBackupSettings";
 
 interface DatabaseInfo {
   path: string;
@@ -12,17 +15,64 @@ interface DatabaseInfo {
 }
 
 function DatabaseBackup() {
-  const { user } = useAuth();
+  const { user, refreshUser } = useAuth();
   const [dbInfo, setDbInfo] = useState<DatabaseInfo | null>(null);
   const [loading, setLoading] = useState(true);
   const [exporting, setExporting] = useState(false);
   const [importing, setImporting] = useState(false);
   const [message, setMessage] = useState<{ type: "success" | "error"; text: string } | null>(null);
+  const [backups, setBackups] = useState<BackupInfo[]>([]);
+  const [backingUp, setBackingUp] = useState(false);
+  const [restoringId, setRestoringId] = useState<string | null>(null);
 
   useEffect(() => {
     loadDatabaseInfo();
+    loadBackups();
   }, []);
 
+  const loadBackups = async () => {
+    try {
+      const list = await invoke<BackupInfo[]>("list_backups");
+      setBackups(list);
+    } catch (error) {
+      console.error("Failed to load backups:", error);
+    }
+  };
+
+  const handleBackupNow = async () => {
+    try {
+      setBackingUp(true);
+      setMessage(null);
+      const backup = await invoke<BackupInfo>("create_backup");
+      setMessage({ type: "success", text: `Backup ${backup.id} created` });
+      loadBackups();
+    } catch (error) {
+      setMessage({ type: "error", text: ErrorService.describe(error) });
+    } finally {
+      setBackingUp(false);
+    }
+  };
+
+  const handleRestore = async (backup: BackupInfo) => {
+    if (
+      !confirm(
+        `Restore the database from ${backup.created_at ?? backup.id}? All current data will be replaced. ` +
+          "The current database is backed up first and everyone will need to log in again."
+      )
+    )
+      return;
+    try {
+      setRestoringId(backup.id);
+      setMessage(null);
+      const result = await invoke<string>("restore_backup", { id: backup.id });
+      alert(result);
+      await refreshUser();
+    } catch (error) {
+      setMessage({ type: "error", text: ErrorService.describe(error) });
+      setRestoringId(null);
+    }
+  };
+
   const loadDatabaseInfo = async () => {
     try {
       setLoading(true);
@@ -63,7 +113,7 @@ function DatabaseBackup() {
 
       setMessage({ type: "success", text: result });
     } catch (error) {
-      setMessage({ type: "error", text: String(error) });
+      setMessage({ type: "error", text: ErrorService.describe(error) });
     } finally {
       setExporting(false);
     }
@@ -90,9 +140,10 @@ function DatabaseBackup() {
         sourcePath: filePath,
       });
 
-      setMessage({ type: "success", text: result });
+      alert(result);
+      await refreshUser();
     } catch (error) {
-      setMessage({ type: "error", text: String(error) });
+      setMessage({ type: "error", text: ErrorService.describe(error) });
     } finally {
       setImporting(false);
     }
@@ -275,6 +326,70 @@ function DatabaseBackup() {
           </div>
         </div>
       </div>
+
+      {/* Saved Backups Card */}
+      <div className="bg-white rounded-xl shadow-sm border border-gray-200 p-6 mt-6">
+        <div className="flex items-center justify-between mb-4">
+          <h3 className="text-lg font-semibold text-gray-800">Saved Backups</h3>
+          <button
+            onClick={handleBackupNow}
+            disabled={backingUp}
+            className="btn-primary py-2 px-4 disabled:opacity-50"
+          >
+            {backingUp ? "Backing up..." : "Back Up Now"}
+          </button>
+        </div>
+
+        {backups.length === 0 ? (
+          <p className="text-gray-500 text-sm">No backups have been saved on this computer yet.</p>
+        ) : (
+          <table className="w-full text-sm">
+            <thead>
+              <tr className="text-left text-gray-500 border-b border-gray-200">
+                <th className="py-2 pr-4 font-medium">Date</th>
+                <th className="py-2 pr-4 font-medium">Size</th>
+                <th className="py-2 pr-4 font-medium">Employees</th>
+                <th className="py-2 pr-4 font-medium">Verified</th>
+                <th className="py-2"></th>
+              </tr>
+            </thead>
+            <tbody>
+              {backups.map((backup) => (
+                <tr key={backup.id} className="border-b border-gray-100">
+                  <td className="py-2 pr-4">
+                    <p className="text-gray-800">{backup.created_at ?? "Unknown date"}</p>
+                    <p className="text-xs font-mono text-gray-400">{backup.id}</p>
+                  </td>
+                  <td className="py-2 pr-4 text-gray-700">{backup.size_display}</td>
+                  <td className="py-2 pr-4 text-gray-700">
+                    {backup.employee_count ?? <span className="text-red-600">Unreadable</span>}
+                  </td>
+                  <td className="py-2 pr-4" title={backup.verification_details ?? undefined}>
+                    {backup.verified === null ? (
+                      <span className="text-gray-400">Not checked</span>
+                    ) : backup.verified ? (
+                      <span className="text-green-600">Passed</span>
+                    ) : (
+                      <span className="text-red-600">Failed</span>
+                    )}
+                  </td>
+                  <td className="py-2 text-right">
+                    <button
+                      onClick={() => handleRestore(backup)}
+                      disabled={restoringId !== null || backup.employee_count === null}
+                      className="px-3 py-1 text-sm text-orange-600 border border-orange-200 rounded-lg hover:bg-orange-50 disabled:opacity-50"
+                    >
+                      {restoringId === backup.id ? "Restoring..." : "Restore"}
+                    </button>
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        )}
+      </div>
+
+      <CloudBackupSettings />
     </div>
   );
 }
@@ -1,7 +1,8 @@
 import { useState, useEffect, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import type { Employee } from "../types/employee";
+import type { Employee, PreviousEmployment, WorkingStatus } from "../types/employee";
 import CustomDatePicker from "./CustomDatePicker";
+import { EmployeeService } from "../services/EmployeeService";
 
 interface EmployeeFormProps {
   employee: Employee | null;
@@ -12,12 +13,14 @@ interface EmployeeFormProps {
   policeAreas: string[];
   designations: string[];
   allocations: string[];
+  workingStatuses: WorkingStatus[];
 }
 
 const emptyEmployee: Employee = {
   epf_number: "",
   name_with_initials: "",
   full_name: "",
+  nic: null,
   dob: null,
   police_area: null,
   transport_route: null,
@@ -33,12 +36,13 @@ const emptyEmployee: Employee = {
   allocation: null,
   department: null,
   image_path: null,
+  previous_epf_number: null,
 };
 
 // Fixed cader options
 const CADER_OPTIONS = ["Direct Cader", "Indirect Cader", "Other"];
 
-function EmployeeForm({ employee, onSubmit, onCancel, departments, transportRoutes, policeAreas, designations, allocations }: EmployeeFormProps) {
+function EmployeeForm({ employee, onSubmit, onCancel, departments, transportRoutes, policeAreas, designations, allocations, workingStatuses }: EmployeeFormProps) {
   const [formData, setFormData] = useState<Employee>(emptyEmployee);
   const [errors, setErrors] = useState<Record<string, string>>({});
   const [newDepartment, setNewDepartment] = useState("");
@@ -63,6 +67,7 @@ function EmployeeForm({ employee, onSubmit, onCancel, departments, transportRout
   const [localPoliceAreas, setLocalPoliceAreas] = useState<string[]>([]);
   const [localDesignations, setLocalDesignations] = useState<string[]>([]);
   const [localAllocations, setLocalAllocations] = useState<string[]>([]);
+  const [formerRecords, setFormerRecords] = useState<PreviousEmployment[]>([]);
 
   useEffect(() => {
     if (employee) {
@@ -81,14 +86,8 @@ function EmployeeForm({ employee, onSubmit, onCancel, departments, transportRout
     }
   }, [employee]);
 
-  const loadEmployeeImage = async (imagePath: string) => {
-    try {
-      const imageDataUrl = await invoke<string>("get_employee_image", { imagePath });
-      setImagePreview(imageDataUrl);
-    } catch (error) {
-      console.error("Failed to load employee image:", error);
-      setImagePreview(null);
-    }
+  const loadEmployeeImage = (imagePath: string) => {
+    setImagePreview(EmployeeService.getImageUrl(imagePath));
   };
 
   // Initialize local lists from props
@@ -119,6 +118,19 @@ function EmployeeForm({ employee, onSubmit, onCancel, departments, transportRout
     }
   };
 
+  // New hires only: look for a former employee with the same NIC to offer a rehire
+  const handleNicBlur = async () => {
+    if (employee || !formData.nic?.trim()) {
+      setFormerRecords([]);
+      return;
+    }
+    try {
+      setFormerRecords(await invoke<PreviousEmployment[]>("find_previous_employment", { nic: formData.nic }));
+    } catch {
+      setFormerRecords([]);
+    }
+  };
+
   const handleImageSelect = (e: React.ChangeEvent<HTMLInputElement>) => {
     const file = e.target.files?.[0];
     if (file) {
@@ -361,6 +373,33 @@ function EmployeeForm({ employee, onSubmit, onCancel, departments, transportRout
             {errors.full_name && <p className="text-red-500 text-sm mt-1">{errors.full_name}</p>}
           </div>
 
+          {/* NIC */}
+          <div>
+            <label className="label">NIC Number</label>
+            <input
+              type="text"
+              className={`input-field ${errors.nic ? "border-red-500" : ""}`}
+              value={formData.nic || ""}
+              onChange={(e) => {
+                handleChange("nic", e.target.value);
+                handleChange("previous_epf_number", null);
+              }}
+              onBlur={handleNicBlur}
+              placeholder="e.g., 199517201234"
+            />
+            {errors.nic && <p className="text-red-500 text-sm mt-1">{errors.nic}</p>}
+            {formerRecords.map((former) => (
+              <label key={former.epf_number} className="flex items-center gap-2 mt-2 text-sm text-amber-700">
+                <input
+                  type="checkbox"
+                  checked={formData.previous_epf_number === former.epf_number}
+                  onChange={(e) => handleChange("previous_epf_number", e.target.checked ? former.epf_number : null)}
+                />
+                Rehire of {former.name_with_initials} (EPF {former.epf_number}, left {former.date_of_resign || "-"})
+              </label>
+            ))}
+          </div>
+
           {/* Date of Birth */}
           <div>
             <label className="label">Date of Birth</label>
@@ -633,8 +672,9 @@ function EmployeeForm({ employee, onSubmit, onCancel, departments, transportRout
               value={formData.working_status}
               onChange={(e) => handleChange("working_status", e.target.value)}
             >
-              <option value="active">Active</option>
-              <option value="resign">Resigned</option>
+              {workingStatuses.map((status) => (
+                <option key={status.code} value={status.code}>{status.label}</option>
+              ))}
             </select>
           </div>
 
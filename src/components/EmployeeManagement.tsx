@@ -1,12 +1,13 @@
 import { useState, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import type { Employee, EmployeeFilters } from "../types/employee";
+import type { Employee, EmployeeFilters, WorkingStatus } from "../types/employee";
 import type { UserPermissions } from "../types/auth";
 import { useAuth } from "../context/AuthContext";
 import EmployeeForm from "./EmployeeForm";
 import EmployeeTable from "./EmployeeTable";
 import EmployeeProfile from "./EmployeeProfile";
 import { ExportService, ExportFormat } from "../services/ExportService";
+import { ErrorService } from "../services/ErrorService";
 
 function EmployeeManagement() {
   const { user } = useAuth();
@@ -27,6 +28,7 @@ function EmployeeManagement() {
   const [policeAreas, setPoliceAreas] = useState<string[]>([]);
   const [designations, setDesignations] = useState<string[]>([]);
   const [allocations, setAllocations] = useState<string[]>([]);
+  const [workingStatuses, setWorkingStatuses] = useState<WorkingStatus[]>([]);
 
   // Get user permissions from session
   const permissions: UserPermissions = user?.permissions || {
@@ -41,6 +43,14 @@ function EmployeeManagement() {
     can_manage_settings: false,
     can_backup_database: false,
     can_view_audit_logs: false,
+    can_login_anytime: false,
+    can_view_sensitive_data: false,
+    can_manage_disciplinary: false,
+    can_approve_attendance: false,
+    can_manage_attendance: false,
+    can_approve_leave: false,
+    can_run_payroll: false,
+    can_view_salaries: false,
   };
 
   const [showExportMenu, setShowExportMenu] = useState(false);
@@ -60,18 +70,20 @@ function EmployeeManagement() {
 
   const loadFilterOptions = async () => {
     try {
-      const [depts, routes, areas, desigs, allocs] = await Promise.all([
+      const [depts, routes, areas, desigs, allocs, statuses] = await Promise.all([
         invoke<string[]>("get_distinct_departments"),
         invoke<string[]>("get_distinct_transport_routes"),
         invoke<string[]>("get_distinct_police_areas"),
         invoke<string[]>("get_distinct_designations"),
         invoke<string[]>("get_distinct_allocations"),
+        invoke<WorkingStatus[]>("get_working_statuses"),
       ]);
       setDepartments(depts);
       setTransportRoutes(routes);
       setPoliceAreas(areas);
       setDesignations(desigs);
       setAllocations(allocs);
+      setWorkingStatuses(statuses);
     } catch (error) {
       console.error("Failed to load filter options:", error);
     }
@@ -134,7 +146,7 @@ function EmployeeManagement() {
       loadFilterOptions();
     } catch (error) {
       console.error("Failed to save employee:", error);
-      alert(`Failed to save employee: ${error}`);
+      alert(`Failed to save employee: ${ErrorService.describe(error)}`);
     }
   };
 
@@ -272,6 +284,7 @@ function EmployeeManagement() {
           policeAreas={policeAreas}
           designations={designations}
           allocations={allocations}
+          workingStatuses={workingStatuses}
         />
       ) : (
         <>
@@ -332,8 +345,9 @@ function EmployeeManagement() {
                   onChange={(e) => handleFilterChange("working_status", e.target.value)}
                 >
                   <option value="">All Status</option>
-                  <option value="active">Active</option>
-                  <option value="resign">Resigned</option>
+                  {workingStatuses.map((status) => (
+                    <option key={status.code} value={status.code}>{status.label}</option>
+                  ))}
                 </select>
               </div>
             </div>
@@ -347,6 +361,7 @@ function EmployeeManagement() {
             onDelete={handleDelete}
             onOpen={handleOpenProfile}
             permissions={permissions}
+            workingStatuses={workingStatuses}
           />
         </>
       )}
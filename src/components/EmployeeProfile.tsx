@@ -5,6 +5,7 @@ import { writeTextFile } from "@tauri-apps/plugin-fs";
 import { open } from "@tauri-apps/plugin-shell";
 import { QRCodeSVG, QRCodeCanvas } from "qrcode.react";
 import type { Employee } from "../types/employee";
+import { ErrorService } from "../services/ErrorService";
 
 interface EmployeeProfileProps {
   epfNumber: string;
@@ -41,7 +42,7 @@ function EmployeeProfile({ epfNumber, onClose, onEdit, canEdit = false, canExpor
         }
       }
     } catch (err) {
-      setError(err as string);
+      setError(ErrorService.describe(err));
     } finally {
       setLoading(false);
     }
@@ -1,6 +1,6 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import type { Employee } from "../types/employee";
+import type { Employee, WorkingStatus } from "../types/employee";
 import type { UserPermissions } from "../types/auth";
 
 interface EmployeeTableProps {
@@ -10,22 +10,11 @@ interface EmployeeTableProps {
   onDelete: (epfNumber: string) => void;
   onOpen: (epfNumber: string) => void;
   permissions: UserPermissions;
+  workingStatuses: WorkingStatus[];
 }
 
-// Component to load and display employee image
-function EmployeeImage({ imagePath }: { imagePath: string | null }) {
-  const [imageUrl, setImageUrl] = useState<string | null>(null);
-
-  useEffect(() => {
-    if (imagePath) {
-      invoke<string>("get_employee_image", { imagePath })
-        .then(setImageUrl)
-        .catch(() => setImageUrl(null));
-    } else {
-      setImageUrl(null);
-    }
-  }, [imagePath]);
-
+// Component to display an employee's thumbnail
+function EmployeeImage({ imageUrl }: { imageUrl: string | null }) {
   if (!imageUrl) {
     return (
       <div className="w-10 h-10 bg-gray-200 rounded-full flex items-center justify-center">
@@ -45,7 +34,21 @@ function EmployeeImage({ imagePath }: { imagePath: string | null }) {
   );
 }
 
-function EmployeeTable({ employees, loading, onEdit, onDelete, onOpen, permissions }: EmployeeTableProps) {
+function EmployeeTable({ employees, loading, onEdit, onDelete, onOpen, permissions, workingStatuses }: EmployeeTableProps) {
+  const [thumbnails, setThumbnails] = useState<Record<string, string>>({});
+
+  // Fetch all thumbnails for the visible list in a single call
+  useEffect(() => {
+    const epfNumbers = employees.filter((e) => e.image_path).map((e) => e.epf_number);
+    if (epfNumbers.length === 0) {
+      setThumbnails({});
+      return;
+    }
+    invoke<Record<string, string>>("get_employee_thumbnails", { epfNumbers })
+      .then(setThumbnails)
+      .catch(() => setThumbnails({}));
+  }, [employees]);
+
   if (loading) {
     return (
       <div className="card">
@@ -112,7 +115,7 @@ function EmployeeTable({ employees, loading, onEdit, onDelete, onOpen, permissio
             {employees.map((employee) => (
               <tr key={employee.epf_number} className="hover:bg-gray-50">
                 <td className="px-4 py-4 whitespace-nowrap">
-                  <EmployeeImage imagePath={employee.image_path} />
+                  <EmployeeImage imageUrl={thumbnails[employee.epf_number] ?? null} />
                 </td>
                 <td className="px-4 py-4 whitespace-nowrap">
                   <span className="font-medium text-gray-900">{employee.epf_number}</span>
@@ -139,15 +142,18 @@ function EmployeeTable({ employees, loading, onEdit, onDelete, onOpen, permissio
                   {employee.mobile_1 || "-"}
                 </td>
                 <td className="px-4 py-4 whitespace-nowrap">
-                  <span
-                    className={`inline-flex px-2 py-1 text-xs font-semibold rounded-full ${
-                      employee.working_status === "active"
-                        ? "bg-green-100 text-green-800"
-                        : "bg-red-100 text-red-800"
-                    }`}
-                  >
-                    {employee.working_status === "active" ? "Active" : "Resigned"}
-                  </span>
+                  {(() => {
+                    const status = workingStatuses.find((s) => s.code === employee.working_status);
+                    const color = status?.color || "#6b7280";
+                    return (
+                      <span
+                        className="inline-flex px-2 py-1 text-xs font-semibold rounded-full"
+                        style={{ backgroundColor: `${color}22`, color }}
+                      >
+                        {status?.label ?? employee.working_status}
+                      </span>
+                    );
+                  })()}
                 </td>
                 <td className="px-4 py-4 whitespace-nowrap text-right text-sm font-medium space-x-2">
                   <button
@@ -1,5 +1,8 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { useAuth } from "../context/AuthContext";
+import { ErrorService } from "../services/ErrorService";
+import type { HealthReport } from "../types/employee";
 
 function Login() {
   const { login } = useAuth();
@@ -7,6 +10,15 @@ function Login() {
   const [password, setPassword] = useState("");
   const [error, setError] = useState("");
   const [loading, setLoading] = useState(false);
+  const [health, setHealth] = useState<HealthReport | null>(null);
+
+  useEffect(() => {
+    invoke<HealthReport>("run_health_check")
+      .then(setHealth)
+      .catch((err) => console.error("Health check failed:", err));
+  }, []);
+
+  const healthProblems = health?.checks.filter((c) => c.status !== "ok") ?? [];
 
   const handleSubmit = async (e: React.FormEvent) => {
     e.preventDefault();
@@ -16,7 +28,7 @@ function Login() {
     try {
       await login({ username, password });
     } catch (err) {
-      setError(err as string);
+      setError(ErrorService.describe(err));
     } finally {
       setLoading(false);
     }
@@ -38,6 +50,23 @@ function Login() {
 
         {/* Login Form */}
         <form onSubmit={handleSubmit} className="space-y-6">
+          {healthProblems.length > 0 && (
+            <div className="space-y-2">
+              {healthProblems.map((c) => (
+                <div
+                  key={c.name}
+                  className={`px-4 py-3 rounded-lg text-sm border ${
+                    c.status === "error"
+                      ? "bg-red-50 border-red-200 text-red-700"
+                      : "bg-yellow-50 border-yellow-200 text-yellow-800"
+                  }`}
+                >
+                  {c.message}
+                </div>
+              ))}
+            </div>
+          )}
+
           {error && (
             <div className="bg-red-50 border border-red-200 text-red-700 px-4 py-3 rounded-lg text-sm">
               {error}
@@ -75,7 +104,7 @@ function Login() {
 
           <button
             type="submit"
-            disabled={loading}
+            disabled={loading || health?.can_proceed === false}
             className="w-full btn-primary py-3 text-lg font-semibold disabled:opacity-50 disabled:cursor-not-allowed"
           >
             {loading ? (
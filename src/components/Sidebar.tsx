@@ -9,7 +9,7 @@ interface SidebarProps {
 }
 
 function Sidebar({ currentPage, setCurrentPage }: SidebarProps) {
-  const { user, logout } = useAuth();
+  const { user, logout, readOnly } = useAuth();
 
   const menuItems: { id: PageType; label: string; icon: JSX.Element }[] = [
     {
@@ -173,6 +173,11 @@ function Sidebar({ currentPage, setCurrentPage }: SidebarProps) {
               <span className={`inline-flex px-2 py-0.5 text-xs font-medium rounded-full ${getRoleBadgeColor(user.role)}`}>
                 {getRoleLabel(user.role)}
               </span>
+              {readOnly && (
+                <span className="ml-1 inline-flex px-2 py-0.5 text-xs font-medium rounded-full bg-gray-200 text-gray-700">
+                  Read-only
+                </span>
+              )}
             </div>
           </div>
           <button
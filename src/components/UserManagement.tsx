@@ -3,6 +3,7 @@ import { invoke } from "@tauri-apps/api/core";
 import { useAuth } from "../context/AuthContext";
 import type { UserInfo, CreateUserRequest, UpdateUserRequest, UserPermissions } from "../types/auth";
 import { ROLES, PERMISSION_DEFINITIONS, getPermissionsByRole, getPermissionsByCategory } from "../types/auth";
+import { ErrorService } from "../services/ErrorService";
 
 // Helper function to get role label
 const getRoleLabel = (role: string): string => {
@@ -23,6 +24,14 @@ const defaultPermissions: UserPermissions = {
   can_manage_settings: false,
   can_backup_database: false,
   can_view_audit_logs: false,
+  can_login_anytime: false,
+  can_view_sensitive_data: false,
+  can_manage_disciplinary: false,
+  can_approve_attendance: false,
+  can_manage_attendance: false,
+  can_approve_leave: false,
+  can_run_payroll: false,
+  can_view_salaries: false,
 };
 
 // Permission Toggle Component
@@ -162,7 +171,7 @@ function UserManagement() {
       });
       loadUsers();
     } catch (error) {
-      setFormError(error as string);
+      setFormError(ErrorService.describe(error));
     }
   };
 
@@ -214,6 +223,7 @@ function UserManagement() {
         department_access: editingUser.department_access,
         is_active: editingUser.is_active,
         permissions: editPermissions,
+        login_hours: editingUser.login_hours ?? null,
       };
       await invoke("update_user", { request });
       setEditingUser(null);
@@ -2,9 +2,35 @@ import { createContext, useContext, useState, useEffect, ReactNode } from "react
 import { invoke } from "@tauri-apps/api/core";
 import type { UserSession, LoginRequest } from "../types/auth";
 
+// Permissions that only lead to changes; cleared on a read-only terminal so
+// pages hide their add/edit/delete actions. The backend rejects them anyway.
+const WRITE_PERMISSIONS = [
+  "can_add_employees",
+  "can_edit_employees",
+  "can_delete_employees",
+  "can_manage_users",
+  "can_manage_settings",
+  "can_backup_database",
+  "can_export_data",
+] as const;
+
+function applyReadOnly(session: UserSession | null, readOnly: boolean): UserSession | null {
+  if (!session || !readOnly) return session;
+  const permissions = { ...session.permissions };
+  WRITE_PERMISSIONS.forEach((key) => {
+    permissions[key] = false;
+  });
+  return { ...session, permissions };
+}
+
+// How often a logged-in session is re-checked against login hours and
+// terminations made from another workstation
+const SESSION_CHECK_INTERVAL_MS = 60_000;
+
 interface AuthContextType {
   user: UserSession | null;
   loading: boolean;
+  readOnly: boolean;
   login: (request: LoginRequest) => Promise<void>;
   logout: () => Promise<void>;
   refreshUser: () => Promise<void>;
@@ -15,11 +41,12 @@ const AuthContext = createContext<AuthContextType | undefined>(undefined);
 export function AuthProvider({ children }: { children: ReactNode }) {
   const [user, setUser] = useState<UserSession | null>(null);
   const [loading, setLoading] = useState(true);
+  const [readOnly, setReadOnly] = useState(false);
 
   const refreshUser = async () => {
     try {
       const session = await invoke<UserSession | null>("get_current_user");
-      setUser(session);
+      setUser(applyReadOnly(session, readOnly));
     } catch (error) {
       console.error("Failed to get current user:", error);
       setUser(null);
@@ -27,12 +54,31 @@ export function AuthProvider({ children }: { children: ReactNode }) {
   };
 
   useEffect(() => {
-    refreshUser().finally(() => setLoading(false));
+    invoke<boolean>("get_read_only_mode")
+      .then(setReadOnly)
+      .catch((error) => console.error("Failed to get read-only mode:", error));
   }, []);
 
+  useEffect(() => {
+    refreshUser().finally(() => setLoading(false));
+  }, [readOnly]);
+
+  useEffect(() => {
+    if (!user) return;
+    const timer = window.setInterval(async () => {
+      try {
+        const valid = await invoke<boolean>("validate_session");
+        if (!valid) setUser(null);
+      } catch (error) {
+        console.error("Failed to validate session:", error);
+      }
+    }, SESSION_CHECK_INTERVAL_MS);
+    return () => window.clearInterval(timer);
+  }, [user?.session_id]);
+
   const login = async (request: LoginRequest) => {
     const session = await invoke<UserSession>("login", { request });
-    setUser(session);
+    setUser(applyReadOnly(session, readOnly));
   };
 
   const logout = async () => {
@@ -41,7 +87,7 @@ export function AuthProvider({ children }: { children: ReactNode }) {
   };
 
   return (
-    <AuthContext.Provider value={{ user, loading, login, logout, refreshUser }}>
+    <AuthContext.Provider value={{ user, loading, readOnly, login, logout, refreshUser }}>
       {children}
     </AuthContext.Provider>
   );
@@ -5,7 +5,10 @@
 
 import { ExportService, ExportFormat } from "../services/ExportService";
 import { EmployeeService } from "../services/EmployeeService";
+import { AuditService } from "../services/AuditService";
 import type { Employee, EmployeeFilters } from "../types/employee";
+import { invoke } from "@tauri-apps/api/core";
+import { save } from "@tauri-apps/plugin-dialog";
 
 export class ExportController {
   /**
@@ -17,17 +20,27 @@ export class ExportController {
   ): Promise<void> {
     const employees = await EmployeeService.getAll(filters);
     await ExportService.exportToExcel(employees, filename);
+    await AuditService.recordExport("excel", employees.map((e) => e.epf_number));
   }
 
   /**
-   * Export employees to CSV
+   * Export employees to CSV.
+   * Written by the backend so department access and sensitive-data
+   * redaction are enforced; an empty column list exports every column.
    */
   static async exportEmployeesToCSV(
     filters?: EmployeeFilters,
-    filename: string = "employees"
+    filename: string = "employees",
+    columns: string[] = []
   ): Promise<void> {
-    const employees = await EmployeeService.getAll(filters);
-    await ExportService.exportToCSV(employees, filename);
+    const destinationPath = await save({
+      defaultPath: `${filename}.csv`,
+      filters: [{ name: "CSV Files", extensions: ["csv"] }],
+    });
+    if (!destinationPath) {
+      return;
+    }
+    await invoke<number>("export_employees_csv", { filters: filters || {}, columns, destinationPath });
   }
 
   /**
@@ -39,6 +52,24 @@ export class ExportController {
   ): Promise<void> {
     const employees = await EmployeeService.getAll(filters);
     await ExportService.exportToPDF(employees, title);
+    await AuditService.recordExport("pdf", employees.map((e) => e.epf_number));
+  }
+
+  /**
+   * Export employee contacts as a vCard (.vcf) file for phones
+   */
+  static async exportEmployeesToVCard(
+    filters: EmployeeFilters,
+    filename: string = "employee_contacts"
+  ): Promise<number> {
+    const destinationPath = await save({
+      defaultPath: `${filename}.vcf`,
+      filters: [{ name: "vCard Files", extensions: ["vcf"] }],
+    });
+    if (!destinationPath) {
+      return 0;
+    }
+    return await invoke<number>("export_vcards", { filters, destinationPath });
   }
 
   /**
@@ -85,6 +116,7 @@ export class ExportController {
       default:
         throw new Error(`Unsupported export format: ${format}`);
     }
+    await AuditService.recordExport(format, employees.map((e) => e.epf_number));
   }
 
   /**
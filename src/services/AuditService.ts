@@ -10,6 +10,7 @@ import type {
   AuditLogResult, 
   AuditLogSummary 
 } from "../types/employee";
+import { ErrorService } from "./ErrorService";
 
 export class AuditService {
   /**
@@ -20,7 +21,7 @@ export class AuditService {
       return await invoke<AuditLogResult>("get_audit_logs", { filters });
     } catch (error) {
       console.error("AuditService.getLogs error:", error);
-      throw new Error(`Failed to fetch audit logs: ${error}`);
+      throw new Error(`Failed to fetch audit logs: ${ErrorService.describe(error)}`);
     }
   }
 
@@ -32,7 +33,7 @@ export class AuditService {
       return await invoke<AuditLogSummary>("get_audit_log_summary");
     } catch (error) {
       console.error("AuditService.getSummary error:", error);
-      throw new Error(`Failed to fetch audit summary: ${error}`);
+      throw new Error(`Failed to fetch audit summary: ${ErrorService.describe(error)}`);
     }
   }
 
@@ -49,7 +50,19 @@ export class AuditService {
       await invoke("create_audit_log", { action, entityType, entityId, details });
     } catch (error) {
       console.error("AuditService.createLog error:", error);
-      throw new Error(`Failed to create audit log: ${error}`);
+      throw new Error(`Failed to create audit log: ${ErrorService.describe(error)}`);
+    }
+  }
+
+  /**
+   * Record which employee records were exported (only stored when
+   * read-access auditing is enabled in settings)
+   */
+  static async recordExport(format: string, epfNumbers: string[]): Promise<void> {
+    try {
+      await invoke("record_data_export", { format, epfNumbers });
+    } catch (error) {
+      console.error("AuditService.recordExport error:", error);
     }
   }
 }
@@ -11,6 +11,7 @@ import type {
   CreateUserRequest,
   UpdateUserRequest 
 } from "../models/User";
+import { ErrorService } from "./ErrorService";
 
 export class AuthService {
   /**
@@ -21,7 +22,7 @@ export class AuthService {
       return await invoke<UserSession>("login", { request: credentials });
     } catch (error) {
       console.error("AuthService.login error:", error);
-      throw new Error(`${error}`);
+      throw new Error(ErrorService.describe(error));
     }
   }
 
@@ -33,7 +34,7 @@ export class AuthService {
       await invoke("logout");
     } catch (error) {
       console.error("AuthService.logout error:", error);
-      throw new Error(`Failed to logout: ${error}`);
+      throw new Error(`Failed to logout: ${ErrorService.describe(error)}`);
     }
   }
 
@@ -57,7 +58,7 @@ export class AuthService {
       await invoke("create_user", { request });
     } catch (error) {
       console.error("AuthService.createUser error:", error);
-      throw new Error(`${error}`);
+      throw new Error(ErrorService.describe(error));
     }
   }
 
@@ -69,7 +70,7 @@ export class AuthService {
       return await invoke<UserInfo[]>("get_all_users");
     } catch (error) {
       console.error("AuthService.getAllUsers error:", error);
-      throw new Error(`Failed to fetch users: ${error}`);
+      throw new Error(`Failed to fetch users: ${ErrorService.describe(error)}`);
     }
   }
 
@@ -81,7 +82,7 @@ export class AuthService {
       await invoke("update_user", { request });
     } catch (error) {
       console.error("AuthService.updateUser error:", error);
-      throw new Error(`${error}`);
+      throw new Error(ErrorService.describe(error));
     }
   }
 
@@ -93,7 +94,7 @@ export class AuthService {
       await invoke("delete_user", { userId });
     } catch (error) {
       console.error("AuthService.deleteUser error:", error);
-      throw new Error(`${error}`);
+      throw new Error(ErrorService.describe(error));
     }
   }
 
@@ -105,7 +106,7 @@ export class AuthService {
       await invoke("reset_user_password", { userId, newPassword });
     } catch (error) {
       console.error("AuthService.resetPassword error:", error);
-      throw new Error(`${error}`);
+      throw new Error(ErrorService.describe(error));
     }
   }
 
@@ -117,7 +118,7 @@ export class AuthService {
       await invoke("change_own_password", { currentPassword, newPassword });
     } catch (error) {
       console.error("AuthService.changeOwnPassword error:", error);
-      throw new Error(`${error}`);
+      throw new Error(ErrorService.describe(error));
     }
   }
 }
@@ -3,8 +3,9 @@
  * Handles all API calls related to employee operations
  */
 
-import { invoke } from "@tauri-apps/api/core";
-import type { Employee, EmployeeFilters } from "../types/employee";
+import { convertFileSrc, invoke } from "@tauri-apps/api/core";
+import type { Employee, EmployeeFilters, FieldConfig } from "../types/employee";
+import { ErrorService } from "./ErrorService";
 
 // Form data type for creating/updating employees
 export interface EmployeeFormData extends Omit<Employee, 'created_at'> {
@@ -20,7 +21,7 @@ export class EmployeeService {
       return await invoke<Employee[]>("get_employees", { filters: filters || {} });
     } catch (error) {
       console.error("EmployeeService.getAll error:", error);
-      throw new Error(`Failed to fetch employees: ${error}`);
+      throw new Error(`Failed to fetch employees: ${ErrorService.describe(error)}`);
     }
   }
 
@@ -32,7 +33,7 @@ export class EmployeeService {
       return await invoke<Employee>("get_employee_by_epf", { epfNumber });
     } catch (error) {
       console.error("EmployeeService.getByEpf error:", error);
-      throw new Error(`Failed to fetch employee: ${error}`);
+      throw new Error(`Failed to fetch employee: ${ErrorService.describe(error)}`);
     }
   }
 
@@ -44,7 +45,7 @@ export class EmployeeService {
       await invoke("create_employee", { employee });
     } catch (error) {
       console.error("EmployeeService.create error:", error);
-      throw new Error(`Failed to create employee: ${error}`);
+      throw new Error(`Failed to create employee: ${ErrorService.describe(error)}`);
     }
   }
 
@@ -56,7 +57,19 @@ export class EmployeeService {
       await invoke("update_employee", { employee });
     } catch (error) {
       console.error("EmployeeService.update error:", error);
-      throw new Error(`Failed to update employee: ${error}`);
+      throw new Error(`Failed to update employee: ${ErrorService.describe(error)}`);
+    }
+  }
+
+  /**
+   * Get which employee fields are required, optional or hidden
+   */
+  static async getFieldConfig(): Promise<FieldConfig[]> {
+    try {
+      return await invoke<FieldConfig[]>("get_field_config");
+    } catch (error) {
+      console.error("EmployeeService.getFieldConfig error:", error);
+      throw new Error(`Failed to fetch field configuration: ${ErrorService.describe(error)}`);
     }
   }
 
@@ -68,7 +81,7 @@ export class EmployeeService {
       await invoke("delete_employee", { epfNumber });
     } catch (error) {
       console.error("EmployeeService.delete error:", error);
-      throw new Error(`Failed to delete employee: ${error}`);
+      throw new Error(`Failed to delete employee: ${ErrorService.describe(error)}`);
     }
   }
 
@@ -84,6 +97,14 @@ export class EmployeeService {
     }
   }
 
+  /**
+   * Get a URL that loads the employee image through the hrm-image:// protocol.
+   * Prefer this over getImage() for display; no base64 round trip is needed.
+   */
+  static getImageUrl(imagePath: string): string {
+    return convertFileSrc(imagePath, "hrm-image");
+  }
+
   /**
    * Get employee count statistics
    */
@@ -112,7 +133,7 @@ export class EmployeeService {
       };
     } catch (error) {
       console.error("EmployeeService.getStats error:", error);
-      throw new Error(`Failed to fetch statistics: ${error}`);
+      throw new Error(`Failed to fetch statistics: ${ErrorService.describe(error)}`);
     }
   }
 }
@@ -0,0 +1,51 @@
+/**
+ * Error Service
+ * Turns errors returned by backend commands into readable messages
+ */
+
+import type { ErrorCode, HrmError } from "../types/employee";
+
+// English fallback shown for a code when the backend message is empty
+const DEFAULT_MESSAGES: Record<ErrorCode, string> = {
+  validation: "Please check the entered details",
+  not_logged_in: "Your session has ended. Please log in again",
+  permission_denied: "You do not have permission to do this",
+  not_found: "The record could not be found",
+  conflict: "This record already exists",
+  database: "A database error occurred",
+  io: "A file could not be read or written",
+  internal: "An unexpected error occurred",
+  read_only: "This terminal is view-only",
+};
+
+export class ErrorService {
+  /**
+   * Whether a caught value is a structured backend error
+   */
+  static isHrmError(error: unknown): error is HrmError {
+    return !!error && typeof error === "object" && "code" in error && "message" in error;
+  }
+
+  /**
+   * Error code of a caught value, or null if it did not come from a command
+   */
+  static code(error: unknown): ErrorCode | null {
+    return this.isHrmError(error) ? error.code : null;
+  }
+
+  /**
+   * Flatten any caught value into a readable message
+   */
+  static describe(error: unknown): string {
+    if (this.isHrmError(error)) {
+      if (error.field_errors.length > 0) {
+        return error.field_errors.map((e) => e.message).join("; ");
+      }
+      return error.message || DEFAULT_MESSAGES[error.code];
+    }
+    if (error instanceof Error) {
+      return error.message;
+    }
+    return String(error);
+  }
+}
@@ -6,6 +6,7 @@
 import type { Employee } from "../types/employee";
 import { save } from "@tauri-apps/plugin-dialog";
 import { writeTextFile } from "@tauri-apps/plugin-fs";
+import { ErrorService } from "./ErrorService";
 
 // Re-export for convenience
 export type ExportFormat = 'excel' | 'csv' | 'pdf';
@@ -65,7 +66,7 @@ export class ExportService {
       }
     } catch (error) {
       console.error("ExportService.exportToExcel error:", error);
-      throw new Error(`Failed to export to Excel: ${error}`);
+      throw new Error(`Failed to export to Excel: ${ErrorService.describe(error)}`);
     }
   }
 
@@ -91,7 +92,7 @@ export class ExportService {
       }
     } catch (error) {
       console.error("ExportService.exportToCSV error:", error);
-      throw new Error(`Failed to export to CSV: ${error}`);
+      throw new Error(`Failed to export to CSV: ${ErrorService.describe(error)}`);
     }
   }
 
@@ -119,7 +120,7 @@ export class ExportService {
       }
     } catch (error) {
       console.error("ExportService.exportToPDF error:", error);
-      throw new Error(`Failed to export to PDF: ${error}`);
+      throw new Error(`Failed to export to PDF: ${ErrorService.describe(error)}`);
     }
   }
 
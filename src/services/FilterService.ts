@@ -5,6 +5,7 @@
 
 import { invoke } from "@tauri-apps/api/core";
 import type { FilterOptions } from "../models/Common";
+import { ErrorService } from "./ErrorService";
 
 export class FilterService {
   /**
@@ -30,7 +31,7 @@ export class FilterService {
       };
     } catch (error) {
       console.error("FilterService.getAllFilterOptions error:", error);
-      throw new Error(`Failed to fetch filter options: ${error}`);
+      throw new Error(`Failed to fetch filter options: ${ErrorService.describe(error)}`);
     }
   }
 
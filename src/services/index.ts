@@ -8,3 +8,4 @@ export * from './AuthService';
 export * from './ExportService';
 export * from './FilterService';
 export * from './AuditService';
+export * from './ErrorService';
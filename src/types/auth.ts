@@ -10,6 +10,20 @@ export interface UserPermissions {
   can_manage_settings: boolean;
   can_backup_database: boolean;
   can_view_audit_logs: boolean;
+  can_login_anytime: boolean;
+  can_view_sensitive_data: boolean;
+  can_manage_disciplinary: boolean;
+  can_approve_attendance: boolean;
+  can_manage_attendance: boolean;
+  can_approve_leave: boolean;
+  can_run_payroll: boolean;
+  can_view_salaries: boolean;
+}
+
+export interface LoginHours {
+  start_time: string; // "HH:MM"
+  end_time: string;   // "HH:MM"; earlier than start_time for overnight windows
+  days: string | null; // comma-separated weekdays, 0 = Sunday
 }
 
 export interface UserSession {
@@ -19,6 +33,16 @@ export interface UserSession {
   role: string;
   department_access: string | null;
   permissions: UserPermissions;
+  session_id?: number | null;
+}
+
+export interface SessionInfo {
+  id: number;
+  user_id: number;
+  username: string;
+  device_name: string | null;
+  created_at: string | null;
+  last_seen_at: string | null;
 }
 
 export interface UserInfo {
@@ -31,6 +55,19 @@ export interface UserInfo {
   created_at: string | null;
   last_login: string | null;
   permissions?: UserPermissions;
+  login_hours?: LoginHours | null;
+  deleted_at?: string | null;
+}
+
+// One field of a user's access that differs between two dates
+export interface PermissionChange {
+  user_id: number;
+  username: string;
+  field: keyof UserPermissions | 'role' | 'department_access' | 'is_active' | 'deleted';
+  label: string;
+  before: string | null; // null when the user had no recorded access yet
+  after: string | null;
+  changed_by: string | null;
 }
 
 export interface CreateUserRequest {
@@ -40,6 +77,7 @@ export interface CreateUserRequest {
   role: string;
   department_access: string | null;
   permissions?: UserPermissions;
+  login_hours?: LoginHours | null;
 }
 
 export interface UpdateUserRequest {
@@ -49,6 +87,7 @@ export interface UpdateUserRequest {
   department_access: string | null;
   is_active: boolean;
   permissions?: UserPermissions;
+  login_hours?: LoginHours | null;
 }
 
 export interface LoginRequest {
@@ -85,12 +124,20 @@ export const PERMISSION_DEFINITIONS: PermissionDefinition[] = [
   // Data category
   { key: 'can_export_data', label: 'Export Data', description: 'Can export to Excel/CSV/PDF', category: 'Data' },
   { key: 'can_view_reports', label: 'View Reports', description: 'Can access analytics and reports', category: 'Data' },
+  { key: 'can_view_sensitive_data', label: 'View Sensitive Data', description: 'Can see DOB, address and phone numbers in exports', category: 'Data' },
+  { key: 'can_manage_disciplinary', label: 'Manage Disciplinary', description: 'Can record and view disciplinary actions', category: 'Employees' },
+  { key: 'can_approve_attendance', label: 'Approve Attendance', description: 'Can approve manual punch corrections and overtime', category: 'Employees' },
+  { key: 'can_manage_attendance', label: 'Manage Attendance', description: 'Can request punch corrections, leave and overtime', category: 'Employees' },
+  { key: 'can_approve_leave', label: 'Approve Leave', description: 'Can approve leave requests and record or cancel leave', category: 'Employees' },
+  { key: 'can_run_payroll', label: 'Run Payroll', description: 'Can calculate and lock payroll runs and approve festival advances', category: 'Data' },
+  { key: 'can_view_salaries', label: 'View Salaries', description: 'Can see salaries, payroll runs and labour cost reports', category: 'Data' },
   
   // Administration category
   { key: 'can_manage_users', label: 'Manage Users', description: 'Can create and manage user accounts', category: 'Administration' },
   { key: 'can_manage_settings', label: 'Manage Settings', description: 'Can modify system settings', category: 'Administration' },
   { key: 'can_backup_database', label: 'Database Backup', description: 'Can backup and restore database', category: 'Administration' },
   { key: 'can_view_audit_logs', label: 'View Audit Logs', description: 'Can view system activity history', category: 'Administration' },
+  { key: 'can_login_anytime', label: 'Login Anytime', description: 'Not limited by allowed login hours', category: 'Administration' },
 ];
 
 // Get permissions for a given role
@@ -109,6 +156,14 @@ export function getPermissionsByRole(role: string): UserPermissions {
         can_manage_settings: true,
         can_backup_database: true,
         can_view_audit_logs: true,
+        can_login_anytime: true,
+        can_view_sensitive_data: true,
+        can_manage_disciplinary: true,
+        can_approve_attendance: true,
+        can_manage_attendance: true,
+        can_approve_leave: true,
+        can_run_payroll: true,
+        can_view_salaries: true,
       };
     case 'hr_manager':
       return {
@@ -123,6 +178,14 @@ export function getPermissionsByRole(role: string): UserPermissions {
         can_manage_settings: false,
         can_backup_database: false,
         can_view_audit_logs: false,
+        can_login_anytime: true,
+        can_view_sensitive_data: true,
+        can_manage_disciplinary: true,
+        can_approve_attendance: true,
+        can_manage_attendance: true,
+        can_approve_leave: true,
+        can_run_payroll: true,
+        can_view_salaries: true,
       };
     case 'hr_staff':
       return {
@@ -137,6 +200,14 @@ export function getPermissionsByRole(role: string): UserPermissions {
         can_manage_settings: false,
         can_backup_database: false,
         can_view_audit_logs: false,
+        can_login_anytime: false,
+        can_view_sensitive_data: false,
+        can_manage_disciplinary: false,
+        can_approve_attendance: false,
+        can_manage_attendance: true,
+        can_approve_leave: false,
+        can_run_payroll: false,
+        can_view_salaries: false,
       };
     case 'viewer':
     default:
@@ -152,6 +223,14 @@ export function getPermissionsByRole(role: string): UserPermissions {
         can_manage_settings: false,
         can_backup_database: false,
         can_view_audit_logs: false,
+        can_login_anytime: false,
+        can_view_sensitive_data: false,
+        can_manage_disciplinary: false,
+        can_approve_attendance: false,
+        can_manage_attendance: false,
+        can_approve_leave: false,
+        can_run_payroll: false,
+        can_view_salaries: false,
       };
   }
 }
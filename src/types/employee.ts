@@ -2,6 +2,7 @@ export interface Employee {
   epf_number: string;
   name_with_initials: string;
   full_name: string;
+  nic?: string | null;
   dob: string | null;
   police_area: string | null;
   transport_route: string | null;
@@ -17,7 +18,116 @@ export interface Employee {
   allocation: string | null;
   department: string | null;
   image_path: string | null;
+  previous_epf_number?: string | null; // Earlier record when the employee was rehired
+  district?: string | null; // residence, from the administrative master lists
+  ds_division?: string | null;
+  gn_division?: string | null;
   created_at?: string;
+  custom_fields?: Record<string, string>;
+  age?: number | null; // whole years, computed from dob
+  service_years?: number | null; // whole years from joining to resignation or today
+}
+
+// A resigned employee whose NIC matches a new hire
+export interface PreviousEmployment {
+  epf_number: string;
+  name_with_initials: string;
+  department: string | null;
+  designation: string | null;
+  date_of_join: string | null;
+  date_of_resign: string | null;
+}
+
+// A stored employee date that could not be read as YYYY-MM-DD
+export interface InvalidEmployeeDate {
+  epf_number: string;
+  name_with_initials: string;
+  field: 'dob' | 'date_of_join' | 'date_of_resign';
+  value: string;
+}
+
+// One problem found by the data quality checks; entity_type/entity_id
+// locate the record to open and field is the form field to correct
+export interface DataQualityIssue {
+  epf_number: string;
+  name_with_initials: string;
+  field: string;
+  detail: string;
+  entity_type: 'EMPLOYEE';
+  entity_id: string;
+}
+
+export interface DataQualityCategory {
+  code: 'invalid_dates' | 'impossible_ages' | 'duplicate_phones' | 'active_with_resign_date' | 'missing_departments';
+  label: string;
+  issues: DataQualityIssue[];
+}
+
+export interface District {
+  name: string;
+  province: string;
+}
+
+export interface GnDivision {
+  id: number;
+  name: string;
+  code: string | null;
+}
+
+// Active employees living in one area; parts below the report level are null
+export interface ResidenceCount {
+  district: string | null;
+  ds_division: string | null;
+  gn_division: string | null;
+  count: number;
+}
+
+// How much of an employee's profile is filled in
+export interface ProfileCompleteness {
+  epf_number: string;
+  name_with_initials: string;
+  department: string | null;
+  score: number;
+  missing: string[];
+}
+
+export interface DepartmentCompleteness {
+  department: string;
+  employee_count: number;
+  incomplete_count: number;
+  average_score: number;
+  least_complete: ProfileCompleteness[];
+}
+
+export interface FieldError {
+  field: string;
+  message: string;
+}
+
+export type ErrorCode =
+  | "validation"
+  | "not_logged_in"
+  | "permission_denied"
+  | "not_found"
+  | "conflict"
+  | "database"
+  | "io"
+  | "internal"
+  | "read_only";
+
+// Returned by every backend command when it fails; field_errors is only
+// filled for validation failures tied to form fields
+export interface HrmError {
+  code: ErrorCode;
+  message: string;
+  field_errors: FieldError[];
+}
+
+// Per-installation form configuration for an employee field
+export interface FieldConfig {
+  field: string;
+  label: string;
+  visibility: "required" | "optional" | "hidden";
 }
 
 export interface EmployeeFilters {
@@ -25,6 +135,48 @@ export interface EmployeeFilters {
   department: string;
   transport_route: string;
   working_status: string;
+  custom_fields?: Record<string, string>;
+  tags?: number[]; // tag ids; employees must carry all of them
+  departments?: string[]; // any of these
+  designations?: string[];
+  allocations?: string[];
+  districts?: string[];
+  ds_divisions?: string[];
+  joined_from?: string | null; // YYYY-MM-DD, inclusive
+  joined_to?: string | null;
+  resigned_from?: string | null;
+  resigned_to?: string | null;
+  age_min?: number | null;
+  age_max?: number | null;
+  service_years_min?: number | null;
+  service_years_max?: number | null;
+  sort_by?: 'epf_number' | 'name' | 'age' | 'service_years' | null;
+  sort_descending?: boolean;
+}
+
+// A named set of employee filters saved by the current user
+export interface SavedFilter {
+  id: number;
+  name: string;
+  filters: EmployeeFilters;
+  created_at: string | null;
+  updated_at: string | null;
+}
+
+export interface Tag {
+  id: number;
+  name: string;
+  color: string | null;
+  employee_count: number;
+}
+
+// Installation-defined employee attribute (locker no., line no., ...)
+export interface CustomFieldDefinition {
+  id: number;
+  name: string;
+  field_type: "text" | "number" | "date" | "select";
+  options: string[];
+  is_active: boolean;
 }
 
 export interface DashboardStats {
@@ -36,6 +188,23 @@ export interface DashboardStats {
   allocations: { name: string; count: number }[];
   recent_joinings: number;
   recent_resignations: number;
+  status_counts: StatusCount[];
+}
+
+export interface WorkingStatus {
+  code: string;
+  label: string;
+  counts_as_active: boolean;
+  color: string | null;
+  sort_order: number;
+  is_system: boolean;
+}
+
+export interface StatusCount {
+  code: string;
+  label: string;
+  color: string | null;
+  count: number;
 }
 
 // Audit Log Types
@@ -74,3 +243,1239 @@ export interface AuditLogSummary {
   action_breakdown: { action: string; count: number }[];
   active_users: { username: string; count: number }[];
 }
+
+export interface EmployeeNote {
+  id: number;
+  epf_number: string;
+  author_id: number | null;
+  author_name: string | null;
+  note: string;
+  is_pinned: boolean;
+  created_at: string | null;
+  updated_at: string | null;
+}
+
+export interface EmployeeQualification {
+  id: number;
+  epf_number: string;
+  level: string; // O/L, A/L, Certificate, Diploma, Degree, Postgraduate, Professional
+  title: string | null;
+  institute: string | null;
+  year: number | null;
+  document_path: string | null;
+  created_at?: string | null;
+}
+
+export interface QualificationDistribution {
+  department: string;
+  level: string;
+  count: number;
+}
+
+export interface Skill {
+  id: number;
+  name: string;
+  category: string | null;
+  description: string | null;
+}
+
+export interface EmployeeSkill {
+  epf_number: string;
+  skill_id: number;
+  skill_name: string;
+  level: number; // 1 learning .. 4 can train others
+  certified_date: string | null;
+  updated_at: string | null;
+}
+
+export interface SkillCoverage {
+  department: string;
+  skill_id: number;
+  skill_name: string;
+  qualified_count: number;
+  certified_count: number;
+  total_count: number;
+}
+
+export interface Training {
+  id: number;
+  course: string;
+  training_date: string;
+  provider: string | null;
+  cost: number | null;
+  notes: string | null;
+  attendee_count: number;
+}
+
+export interface TrainingGap {
+  epf_number: string;
+  name_with_initials: string;
+  department: string | null;
+  missing_trainings: string[];
+}
+
+export type DisciplinaryActionType = "verbal_warning" | "written_warning" | "final_warning" | "suspension";
+
+export interface DisciplinaryAction {
+  id: number;
+  epf_number: string;
+  action_date: string;
+  action_type: DisciplinaryActionType;
+  description: string;
+  document_path: string | null;
+  expires_on: string | null; // null for suspensions
+  is_withdrawn: boolean;
+  recorded_by: string | null;
+  created_at: string | null;
+  is_active: boolean;
+}
+
+export interface DisciplinarySummary {
+  epf_number: string;
+  active_warnings: number;
+  total_actions: number;
+  highest_active_action: DisciplinaryActionType | null;
+  suggested_next_action: DisciplinaryActionType;
+}
+
+export interface HistoryEvent {
+  date: string;
+  kind: "record" | "incident" | "disciplinary";
+  title: string;
+  details: string | null;
+  recorded_by: string | null;
+}
+
+export type IncidentSeverity = "minor" | "moderate" | "major" | "fatal";
+
+export interface Incident {
+  id: number;
+  incident_date: string;
+  location: string;
+  severity: IncidentSeverity;
+  description: string;
+  reported_to_labour_dept: boolean;
+  labour_dept_reported_on: string | null;
+  injured_employees: string[]; // EPF numbers
+  attachments: string[];
+  recorded_by: string | null;
+  created_at: string | null;
+}
+
+export interface IncidentSummary {
+  year: number;
+  total_incidents: number;
+  by_severity: Record<IncidentSeverity, number>;
+  by_month: number[]; // January first
+  injured_count: number;
+  reported_to_labour_dept: number;
+  unreported_serious: number;
+}
+
+export type GrievanceStatus = "open" | "investigating" | "resolved" | "closed";
+
+export interface Grievance {
+  id: number;
+  epf_number: string | null;
+  subject: string;
+  description: string | null;
+  category: string | null;
+  raised_on: string;
+  status: GrievanceStatus;
+  assigned_to: number | null;
+  assigned_to_name: string | null;
+  is_confidential: boolean;
+  resolution: string | null;
+  resolved_at: string | null;
+  recorded_by: string | null;
+  created_at: string | null;
+  updated_at: string | null;
+}
+
+export interface PendingGrievance {
+  id: number;
+  epf_number: string | null;
+  name_with_initials: string | null;
+  subject: string;
+  status: GrievanceStatus;
+  assigned_to_name: string | null;
+  raised_on: string;
+  age_days: number;
+  sla_days: number;
+  is_overdue: boolean;
+  is_confidential: boolean;
+}
+
+export type AssetCategory = "uniform" | "locker" | "tool" | "laptop" | "other";
+
+export interface Asset {
+  id: number;
+  name: string;
+  category: AssetCategory;
+  asset_code: string | null; // Locker number or serial; coded items are issued singly
+  is_returnable: boolean;
+  is_active: boolean;
+  outstanding_quantity: number;
+}
+
+export interface AssetIssue {
+  id: number;
+  asset_id: number;
+  asset_name: string;
+  category: AssetCategory;
+  asset_code: string | null;
+  epf_number: string;
+  name_with_initials: string | null;
+  quantity: number;
+  issued_on: string;
+  returned_on: string | null;
+  return_condition: "good" | "damaged" | "lost" | null;
+  notes: string | null;
+  issued_by: string | null;
+}
+
+export interface Visitor {
+  id: number;
+  name: string;
+  nic: string;
+  company: string | null;
+  purpose: string | null;
+  host_epf_number: string | null;
+  host_name: string | null;
+  badge_number: string | null;
+  time_in: string;
+  time_out: string | null;
+  checked_in_by: string | null;
+}
+
+export interface VisitorDayReport {
+  date: string;
+  total_visitors: number;
+  still_on_site: number;
+  visitors: Visitor[];
+}
+
+export type MealType = "breakfast" | "lunch" | "dinner";
+
+export interface MealDeduction {
+  epf_number: string;
+  name_with_initials: string | null;
+  department: string | null;
+  meal_count: number;
+  gross_cost: number; // LKR
+  subsidy: number;
+  deduction: number;
+}
+
+export interface MealForecast {
+  department: string;
+  meal_type: MealType;
+  active_headcount: number;
+  average_daily_meals: number;
+  forecast: number;
+}
+
+export interface DeductionType {
+  id: number;
+  name: string;
+  payee: string | null;
+  default_amount: number | null;
+  is_active: boolean;
+}
+
+export interface EmployeeDeduction {
+  id: number;
+  epf_number: string;
+  deduction_type_id: number;
+  deduction_type: string;
+  amount: number; // LKR per month; 0 uses the type's default
+  start_date: string;
+  end_date: string | null;
+  notes: string | null;
+}
+
+export interface DeductionLine {
+  epf_number: string;
+  name_with_initials: string | null;
+  deduction_type: string;
+  amount: number;
+}
+
+export interface DeductionRemittance {
+  month: string;
+  deduction_type: string;
+  payee: string | null;
+  employee_count: number;
+  total_amount: number;
+}
+
+export interface FestivalAdvance {
+  id: number;
+  epf_number: string;
+  name_with_initials: string | null;
+  festival: string;
+  amount: number;
+  installments: number; // Months to recover over
+  status: "pending" | "approved" | "rejected";
+  requested_on: string;
+  first_recovery_month: string | null; // YYYY-MM
+  approved_by: string | null;
+  approved_at: string | null;
+  rejection_reason: string | null;
+}
+
+export interface InsurancePolicy {
+  id: number;
+  policy_number: string;
+  insurer: string;
+  policy_type: string | null;
+  start_date: string; // Policy year
+  end_date: string;
+  premium: number | null;
+  notes: string | null;
+  enrolled_count: number;
+}
+
+export interface InsuranceDependent {
+  name: string;
+  relationship: string;
+  dob: string | null;
+}
+
+export interface InsuranceEnrollment {
+  id: number;
+  policy_id: number;
+  policy_number: string;
+  insurer: string;
+  epf_number: string;
+  enrolled_on: string;
+  ended_on: string | null;
+  dependents: InsuranceDependent[];
+}
+
+export interface InsuranceClaim {
+  id: number;
+  enrollment_id: number;
+  epf_number: string;
+  policy_number: string;
+  claimant: string | null; // Dependent's name when not the employee
+  claim_date: string;
+  amount_claimed: number;
+  amount_settled: number | null;
+  status: "submitted" | "approved" | "rejected" | "paid";
+  notes: string | null;
+  documents: string[];
+}
+
+export interface PolicyClaimsSummary {
+  policy_number: string;
+  insurer: string;
+  start_date: string;
+  end_date: string;
+  premium: number | null;
+  enrolled_count: number;
+  claim_count: number;
+  rejected_count: number;
+  open_count: number;
+  total_claimed: number;
+  total_settled: number;
+  loss_ratio: number | null;
+}
+
+export type ExitReason =
+  | "better_pay"
+  | "career_growth"
+  | "relocation"
+  | "family"
+  | "health"
+  | "work_environment"
+  | "supervisor"
+  | "workload"
+  | "further_studies"
+  | "retirement"
+  | "other";
+
+export interface ExitInterview {
+  epf_number: string;
+  interview_date: string;
+  primary_reason: ExitReason;
+  secondary_reasons: ExitReason[];
+  would_rejoin: boolean | null;
+  comments: string | null;
+  interviewer: string | null;
+}
+
+export interface ReasonCount {
+  group: string; // Department or tenure band
+  reason: ExitReason;
+  count: number;
+}
+
+export interface ExitAnalytics {
+  total_interviews: number;
+  would_rejoin_percent: number | null;
+  by_department: ReasonCount[];
+  by_tenure_band: ReasonCount[];
+}
+
+export interface EmploymentPeriod {
+  id: number;
+  epf_number: string;
+  start_date: string;
+  end_date?: string;
+  days: number;
+}
+
+// Transfer out to another branch or company
+export interface EmployeeTransfer {
+  id: number;
+  epf_number: string;
+  from_location: string | null;
+  to_location: string;
+  effective_date: string; // first day at the receiving end
+  reason: string | null;
+  new_epf_number: string | null; // number at the receiving end, when known
+  transferred_by: string | null;
+  created_at: string | null;
+}
+
+// Transfer letter issued with the transfer
+export interface TransferLetter {
+  template_id: number;
+  format: "html" | "docx" | "pdf";
+  destination_path: string;
+}
+
+export interface ServiceSummary {
+  epf_number: string;
+  total_days: number;
+  completed_years: number;
+  current_period_days: number;
+  periods: EmploymentPeriod[];
+}
+
+export interface EmployeePage {
+  employees: Employee[];
+  total_count: number;
+}
+
+export interface MonthlyMovement {
+  month: string; // YYYY-MM
+  joined: number;
+  resigned: number;
+}
+
+export interface HeadcountBudget {
+  id: number;
+  department: string;
+  cader: string | null; // null budgets the whole department
+  budgeted_count: number;
+  effective_from: string;
+  effective_to: string | null;
+  created_by?: string | null;
+}
+
+export interface HeadcountVariance {
+  department: string;
+  cader: string | null;
+  budgeted_count: number;
+  actual_count: number;
+  variance: number; // Negative means unfilled positions
+}
+
+export type CandidateStage = "applied" | "interviewed" | "selected" | "joined" | "rejected";
+
+export interface Vacancy {
+  id: number;
+  title: string;
+  department: string | null;
+  cader: string | null;
+  designation: string | null;
+  positions: number;
+  status: "open" | "filled" | "cancelled";
+  opened_on: string;
+  closed_on: string | null;
+  notes: string | null;
+  created_by: string | null;
+  candidate_count: number;
+  joined_count: number;
+}
+
+export interface Candidate {
+  id: number;
+  vacancy_id: number;
+  full_name: string;
+  name_with_initials: string | null;
+  nic: string | null;
+  dob: string | null;
+  mobile: string | null;
+  address: string | null;
+  applied_on: string;
+  stage: CandidateStage;
+  stage_changed_on: string | null;
+  employee_epf_number: string | null; // Set once the candidate joins
+  notes: string | null;
+}
+
+export interface TimeToFill {
+  vacancy_id: number;
+  title: string;
+  department: string | null;
+  status: string;
+  positions: number;
+  opened_on: string;
+  closed_on: string | null;
+  joined_count: number;
+  days: number; // Opening to filling, or to today while still open
+}
+
+export interface TimeToFillReport {
+  filled_count: number;
+  open_count: number;
+  average_days_to_fill: number | null;
+  vacancies: TimeToFill[];
+}
+
+export interface CriterionScore {
+  criterion: string;
+  score: number; // 1 (poor) to 5 (excellent)
+  comment: string | null;
+}
+
+export interface ScoreSheet {
+  scores: CriterionScore[];
+  recommendation: "hire" | "hold" | "reject";
+  comments: string | null;
+}
+
+export interface Interview {
+  id: number;
+  candidate_id: number;
+  candidate_name: string;
+  vacancy_id: number;
+  interviewer_user_id: number;
+  interviewer_name: string | null;
+  scheduled_at: string; // YYYY-MM-DD HH:MM
+  duration_minutes: number;
+  location: string | null;
+  status: "scheduled" | "completed" | "cancelled";
+  score_sheet: ScoreSheet | null;
+  overall_score: number | null;
+  evaluated_at: string | null;
+  created_by: string | null;
+}
+
+export interface CandidateComparison {
+  candidate_id: number;
+  full_name: string;
+  stage: CandidateStage;
+  interview_count: number;
+  criterion_averages: Record<string, number>;
+  overall_score: number | null;
+  recommendations: Record<string, number>;
+}
+
+export interface SalaryRevision {
+  id: number;
+  epf_number: string;
+  old_salary: number | null; // null for the starting salary
+  new_salary: number;
+  currency: string; // LKR unless paid in a foreign currency
+  increment_percent: number | null;
+  effective_date: string;
+  reason: string;
+  approved_by: string;
+  created_at: string | null;
+}
+
+export interface IncrementSummary {
+  department: string;
+  revision_count: number;
+  average_percent: number;
+  min_percent: number;
+  max_percent: number;
+}
+
+export interface OrgNode {
+  epf_number: string;
+  name_with_initials: string;
+  designation: string | null;
+  department: string | null;
+  reports: OrgNode[]; // Direct reports
+}
+
+export interface PendingReassignment {
+  epf_number: string;
+  name_with_initials: string;
+  supervisor_epf: string; // No longer active
+  supervisor_name: string;
+}
+
+export type Readiness = "ready_now" | "1_2_years" | "3_plus_years";
+
+export interface KeyPosition {
+  id: number; // 0 when flagging a new position
+  title: string;
+  department: string | null;
+  incumbent_epf: string | null;
+  incumbent_name?: string | null;
+  risk_level: "high" | "medium" | "low";
+  notes: string | null;
+  required_skill_ids: number[];
+  successor_count?: number;
+}
+
+export interface Successor {
+  epf_number: string;
+  name_with_initials: string;
+  designation: string | null;
+  readiness: Readiness | ""; // Empty for suggestions
+  notes: string | null;
+  nominated_by: string | null;
+  nominated_on: string | null;
+  is_active: boolean;
+  skills_met: number;
+}
+
+export interface SuccessionGap {
+  position: KeyPosition;
+  suggestions: Successor[];
+}
+
+export interface HeadcountSnapshot {
+  month: string; // YYYY-MM
+  department: string;
+  cader: string;
+  working_status: string;
+  headcount: number;
+  taken_at: string | null;
+}
+
+export interface AnonymizationCandidate {
+  epf_number: string;
+  name_with_initials: string;
+  department: string | null;
+  date_of_resign: string | null;
+}
+
+export interface RetentionRule {
+  rule_key: "audit_logs" | "resigned_employees" | "visitors" | "notifications";
+  description: string;
+  retain_value: number;
+  retain_unit: "days" | "months" | "years";
+  is_enabled: boolean;
+  confirmed_at: string | null; // Set after a dry run is reviewed; cleared on change
+  last_run_at: string | null;
+  last_run_count: number | null;
+  updated_by: string | null;
+}
+
+export interface RetentionPreview {
+  rule: RetentionRule;
+  affected_count: number;
+  sample: string[];
+}
+
+export type ConsentType = "data_processing" | "cctv" | "medical";
+
+export interface EmployeeConsent {
+  id: number;
+  epf_number: string;
+  consent_type: ConsentType;
+  signed_on: string;
+  document_path: string | null; // Link or path to the signed form
+  withdrawn_on: string | null;
+  recorded_by: string | null;
+  created_at: string | null;
+}
+
+export interface MissingConsent {
+  epf_number: string;
+  name_with_initials: string;
+  department: string | null;
+  missing_types: ConsentType[];
+}
+
+export type BloodGroup = "A+" | "A-" | "B+" | "B-" | "AB+" | "AB-" | "O+" | "O-";
+export type FitnessResult = "fit" | "fit_with_restrictions" | "unfit";
+
+export interface FitnessCertificate {
+  id: number;
+  epf_number: string;
+  examined_on: string;
+  valid_until: string | null; // a year from examined_on when left out
+  result: FitnessResult;
+  restrictions: string | null;
+  examined_by: string | null; // doctor or clinic
+  document_path: string | null; // link or path to the certificate
+  recorded_by: string | null;
+  created_at: string | null;
+}
+
+export interface EmployeeMedical {
+  epf_number: string;
+  blood_group: BloodGroup | null;
+  allergies: string | null;
+  updated_by: string | null;
+  updated_at: string | null;
+  certificates: FitnessCertificate[];
+}
+
+// An employee who needs a medical fitness certificate renewed or obtained
+export interface FitnessDue {
+  epf_number: string;
+  name_with_initials: string;
+  department: string | null;
+  designation: string | null;
+  valid_until: string | null;
+  result: FitnessResult | null;
+  days_remaining: number | null; // negative once expired
+  status: "missing" | "unfit" | "expired" | "expiring";
+}
+
+export type LicenseType = "driving" | "heavy_vehicle" | "forklift" | "crane" | "boiler" | "other";
+
+// A driving licence or machine operator certificate
+export interface EmployeeLicense {
+  id: number;
+  epf_number: string;
+  name_with_initials: string;
+  department: string | null;
+  license_type: LicenseType;
+  license_number: string;
+  issued_on: string | null;
+  expires_on: string;
+  document_path: string | null; // link or path to a scan of the licence
+  recorded_by: string | null;
+  created_at: string | null;
+  days_remaining: number | null; // negative once expired
+}
+
+// Value of the log_level setting; get_recent_logs accepts the same values
+export type LogLevel = "error" | "warn" | "info" | "debug" | "trace";
+
+export interface HealthCheck {
+  name: "database" | "schema_version" | "migrations" | "employee_dates" | "disk_space" | "write_access" | "backup";
+  status: "ok" | "warning" | "error";
+  message: string;
+}
+
+// Shown on the login screen; sign-in is blocked while can_proceed is false
+export interface HealthReport {
+  checks: HealthCheck[];
+  can_proceed: boolean;
+}
+
+// A database copy in the app's backups folder; id is its file name
+export interface BackupInfo {
+  id: string;
+  created_at: string | null;
+  size_bytes: number;
+  size_display: string;
+  employee_count: number | null;
+  verified: boolean | null;
+  verified_at: string | null;
+  verification_details: string | null;
+}
+
+// Offsite backup target; secrets are never returned, only whether one is saved
+export interface CloudBackupConfig {
+  enabled: boolean;
+  endpoint: string;
+  bucket: string;
+  region: string;
+  prefix: string;
+  access_key_id: string;
+  has_secret_access_key: boolean;
+  has_passphrase: boolean;
+  last_upload_at: string | null;
+  last_uploaded_backup: string | null;
+  last_error: string | null;
+}
+
+// Secret fields left null keep the saved value
+export interface CloudBackupSettings {
+  enabled: boolean;
+  endpoint: string;
+  bucket: string;
+  region: string;
+  prefix: string;
+  access_key_id: string;
+  secret_access_key: string | null;
+  passphrase: string | null;
+}
+
+// Fingerprint terminal pushing punches to the built-in listener
+export interface AttendanceDevice {
+  id: number;
+  serial_number: string;
+  name: string | null;
+  location: string | null;
+  is_active: boolean;
+  ip_address: string | null;
+  last_seen_at: string | null;
+  status: "online" | "offline" | "pending" | "disabled";
+  punches_today: number;
+}
+
+// Attendance corrections
+export type PunchSource = "device" | "manual" | "corrected";
+
+export interface AttendancePunch {
+  id: number;
+  epf_number: string;
+  punch_time: string;
+  punch_state: number; // 0 in, 1 out, 2 break out, 3 break in, 4 OT in, 5 OT out
+  verify_mode: number | null;
+  device_serial: string | null;
+  source: PunchSource;
+  original_punch_time: string | null;
+  correction_id: number | null;
+  has_pending_correction: boolean;
+}
+
+export type AttendanceCorrectionStatus = "pending" | "approved" | "rejected";
+
+export interface AttendanceCorrection {
+  id: number;
+  epf_number: string;
+  employee_name: string | null;
+  punch_id: number | null; // null when adding a missing punch
+  original_time: string | null;
+  original_state: number | null;
+  new_time: string;
+  new_state: number;
+  reason: string;
+  status: AttendanceCorrectionStatus;
+  requested_by: number | null;
+  requested_by_name: string | null;
+  requested_at: string | null;
+  reviewed_by: number | null;
+  reviewed_by_name: string | null;
+  reviewed_at: string | null;
+  review_note: string | null;
+}
+
+// Overtime asked for on a day; only limits paid overtime while
+// attendance_ot_requires_approval is on
+export interface OvertimeRequest {
+  id: number;
+  epf_number: string;
+  employee_name: string | null;
+  work_date: string;
+  hours: number;
+  reason: string;
+  status: "pending" | "approved" | "rejected";
+  requested_by: number | null;
+  requested_by_name: string | null;
+  requested_at: string | null;
+  reviewed_by: number | null;
+  reviewed_by_name: string | null;
+  reviewed_at: string | null;
+  review_note: string | null;
+}
+
+export type ApprovalRequestType = "attendance_correction" | "festival_advance" | "leave" | "overtime";
+
+export interface ApprovalStep {
+  step_number: number;
+  approver_permission: string; // permission the approver of this step needs
+  status: "pending" | "approved" | "rejected" | "skipped";
+  decided_by: number | null;
+  decided_by_name: string | null;
+  decided_at: string | null;
+  comment: string | null;
+  on_behalf_of: number | null; // absent approver, when decided through a delegation
+  on_behalf_of_name: string | null;
+}
+
+export interface ApprovalComment {
+  id: number;
+  user_id: number | null;
+  username: string;
+  comment: string;
+  created_at: string | null;
+}
+
+// A workflow item going through approval; decisions are made with the
+// workflow's own approve and reject commands
+export interface ApprovalRequest {
+  id: number;
+  request_type: ApprovalRequestType;
+  label: string;
+  entity_id: string; // ID in the workflow's own table
+  epf_number: string | null;
+  employee_name: string | null;
+  department: string | null;
+  summary: string;
+  requested_by: number | null;
+  requested_by_name: string | null;
+  status: "pending" | "approved" | "rejected";
+  current_step: number;
+  created_at: string | null;
+  decided_at: string | null;
+  steps: ApprovalStep[]; // filled by get_approval only
+  comments: ApprovalComment[]; // filled by get_approval only
+}
+
+// An approver's approvals handed to someone else for a period
+export interface ApprovalDelegation {
+  id: number;
+  delegator_id: number;
+  delegator_name: string;
+  delegate_id: number;
+  delegate_name: string;
+  starts_on: string;
+  ends_on: string; // inclusive
+  reason: string | null;
+  created_by: string | null;
+  created_at: string | null;
+  revoked_at: string | null;
+}
+
+// A piece of follow-up work assigned to an HR user
+export interface Task {
+  id: number;
+  title: string;
+  description: string | null;
+  assigned_to: number | null; // the creator when not given
+  assigned_to_name: string | null;
+  created_by: number | null;
+  created_by_name: string | null;
+  due_date: string | null;
+  entity_type: string | null; // linked record, e.g. "EMPLOYEE"
+  entity_id: string | null; // e.g. the EPF number
+  status: "open" | "done" | "cancelled";
+  completed_at: string | null;
+  completion_note: string | null;
+  created_at: string | null;
+  is_overdue: boolean;
+}
+
+// Monthly attendance summary for payroll
+export interface AttendanceSummary {
+  epf_number: string;
+  name_with_initials: string;
+  full_name: string;
+  department: string | null;
+  designation: string | null;
+  allocation: string | null;
+  month: string; // YYYY-MM
+  working_days: number;
+  worked_days: number;
+  ot_hours: number;
+  leave_days: number;
+  annual_leave: number;
+  casual_leave: number;
+  medical_leave: number;
+  maternity_leave: number;
+  no_pay_leave: number;
+  no_pay_days: number;
+}
+
+// Leave
+export type LeaveType = "annual" | "casual" | "medical" | "maternity" | "no_pay";
+
+export interface LeaveRecord {
+  id: number;
+  epf_number: string;
+  leave_date: string;
+  leave_type: LeaveType;
+  days: number; // 1 or 0.5
+  reason: string | null;
+  recorded_by: string | null;
+  created_at: string | null;
+}
+
+// Leave waiting on the approval chain; approval writes the LeaveRecord days
+// Input to record_leave and request_leave
+export interface LeaveEntry {
+  epf_number: string;
+  from_date: string;
+  to_date: string;
+  leave_type: LeaveType;
+  half_day?: boolean;
+  reason?: string | null;
+}
+
+export interface LeaveRequest {
+  id: number;
+  epf_number: string;
+  employee_name: string | null;
+  from_date: string;
+  to_date: string;
+  leave_type: LeaveType;
+  half_day: boolean;
+  reason: string | null;
+  status: "pending" | "approved" | "rejected";
+  requested_by: number | null;
+  requested_by_name: string | null;
+  requested_at: string | null;
+  reviewed_by: number | null;
+  reviewed_by_name: string | null;
+  reviewed_at: string | null;
+  review_note: string | null;
+}
+
+// Night work compliance
+export interface NightWorkRecord {
+  epf_number: string;
+  name_with_initials: string;
+  full_name: string;
+  nic: string | null;
+  department: string | null;
+  designation: string | null;
+  police_area: string | null;
+  transport_route: string | null;
+  mobile_1: string | null;
+  night_shifts: number;
+  first_night: string;
+  last_night: string;
+}
+
+export interface NightWorkReport {
+  from_date: string;
+  to_date: string;
+  employees: NightWorkRecord[]; // women according to their NIC
+  unverified: NightWorkRecord[]; // NIC missing or unreadable
+}
+
+// Working hours limits
+export interface WorkingHoursBreach {
+  epf_number: string;
+  name_with_initials: string;
+  department: string | null;
+  rule: "weekly_hours" | "consecutive_days";
+  period_start: string;
+  period_end: string;
+  value: number; // hours worked or days in a row
+  limit: number;
+}
+
+// Payroll
+export interface PayrollDeduction {
+  deduction_type: string;
+  amount: number;
+}
+
+export interface PayrollLine {
+  epf_number: string;
+  name_with_initials: string;
+  department: string | null;
+  allocation: string | null;
+  basic_salary: number;
+  pay_currency: string;
+  exchange_rate: number; // rupees per unit of pay_currency; 1 for LKR
+  working_days: number;
+  worked_days: number;
+  no_pay_days: number;
+  no_pay_deduction: number;
+  ot_hours: number;
+  ot_pay: number;
+  incentive_pay: number; // piece-rate production earnings
+  gross_pay: number; // basic less no-pay, plus OT and incentives
+  epf_employee: number;
+  epf_employer: number;
+  etf_employer: number;
+  other_deductions: number;
+  net_pay: number;
+  deductions: PayrollDeduction[];
+}
+
+export type PayrollRunStatus = "preview" | "locked" | "reversed";
+
+export interface PayrollRun {
+  id: number | null; // null for a preview
+  month: string; // YYYY-MM
+  status: PayrollRunStatus;
+  employee_count: number;
+  total_gross: number;
+  total_net: number;
+  total_epf_employee: number;
+  total_epf_employer: number;
+  total_etf: number;
+  locked_by: string | null;
+  locked_at: string | null;
+  reversed_by: string | null;
+  reversed_at: string | null;
+  reversal_reason: string | null;
+  lines: PayrollLine[];
+  skipped: string[]; // EPF numbers with no salary on record
+}
+
+// Changes to try in a payroll simulation; anything left out uses the values on record
+export interface PayrollScenario {
+  ot_rate_percent?: number | null;
+  no_pay_divisor?: number | null;
+  salary_increase_percent?: number | null; // applied to every salary on record
+  salaries?: Record<string, number>; // proposed basic salary by EPF number, in the pay currency
+}
+
+export interface PayrollSimulationLine {
+  epf_number: string;
+  name_with_initials: string;
+  department: string | null;
+  baseline_basic: number | null; // null when the employee is not paid in that run
+  simulated_basic: number | null;
+  baseline_net: number | null;
+  simulated_net: number | null;
+  baseline_cost: number; // gross plus employer EPF and ETF
+  simulated_cost: number;
+  cost_change: number;
+}
+
+export interface PayrollSimulation {
+  month: string;
+  baseline: PayrollRun; // totals only; lines are compared in `lines`
+  simulated: PayrollRun;
+  baseline_cost: number;
+  simulated_cost: number;
+  cost_change: number;
+  lines: PayrollSimulationLine[];
+}
+
+export type PayrollVarianceStatus = "new" | "removed" | "changed" | "unchanged";
+
+export interface PayrollVarianceLine {
+  epf_number: string;
+  name_with_initials: string;
+  department: string | null;
+  status: PayrollVarianceStatus;
+  previous_net_pay: number | null;
+  current_net_pay: number | null;
+  net_pay_change: number;
+  net_pay_change_percent: number | null; // null when there was no previous pay
+  previous_ot_hours: number;
+  current_ot_hours: number;
+  ot_hours_change: number;
+  flagged: boolean; // beyond a threshold, joined or left
+}
+
+export interface PayrollVarianceReport {
+  month: string;
+  previous_month: string;
+  status: "preview" | "locked";
+  threshold_percent: number;
+  threshold_ot_hours: number;
+  lines: PayrollVarianceLine[];
+}
+
+export interface PayrollCostCenter {
+  month: string;
+  cost_center: string; // allocation or department
+  employee_count: number;
+  basic_pay: number; // basic less no-pay
+  ot_pay: number;
+  gross_pay: number;
+  epf_employer: number;
+  etf_employer: number;
+  total_cost: number; // gross plus employer EPF and ETF
+}
+
+// Monthly exchange rates
+export interface ExchangeRate {
+  month: string; // YYYY-MM
+  currency: string;
+  rate: number; // rupees per unit
+  updated_by: string | null;
+  updated_at: string | null;
+}
+
+// Letter templates
+export interface LetterTemplate {
+  id?: number | null; // omitted when creating a template
+  name: string;
+  category: string | null; // e.g. confirmation, warning, salary certificate
+  body: string; // text with {{merge_field}} placeholders; blank lines separate paragraphs
+  language?: DocumentLanguage | null; // null follows the employee's preferred language
+  is_active: boolean;
+  updated_by?: string | null;
+  updated_at?: string | null;
+}
+
+export interface LetterIssue {
+  id: number;
+  reference_number: string | null;
+  template_name: string;
+  epf_number: string;
+  name_with_initials: string | null;
+  format: "html" | "docx" | "pdf";
+  issued_by: string | null;
+  issued_at: string | null;
+}
+
+export interface BulkLetterResult {
+  generated: number;
+  failed: { field: string; message: string }[]; // field is the EPF number
+}
+
+// Document localization
+export type DocumentLanguage = 'en' | 'si' | 'ta';
+
+export interface Translation {
+  key: string; // e.g. company_name, pack.title
+  language: DocumentLanguage;
+  text: string;
+  updated_by: string | null;
+  updated_at: string | null;
+}
+
+// Labour cost budgets
+export interface LabourBudget {
+  id: number;
+  department: string;
+  month: string; // YYYY-MM
+  amount: number;
+  updated_by: string | null;
+  updated_at: string | null;
+}
+
+export interface LabourBudgetComparison {
+  department: string;
+  month: string;
+  budget: number | null; // null when no budget was set
+  actual: number; // gross plus employer EPF and ETF
+  variance: number | null; // actual less budget; positive is over budget
+  variance_percent: number | null;
+  trend: number; // average actual over the last three months
+}
+
+// Production incentives
+export interface PieceRate {
+  id: number;
+  product_code: string;
+  description: string | null;
+  rate: number; // paid per unit produced
+  is_active: boolean; // inactive codes are refused on import
+  updated_by: string | null;
+  updated_at: string | null;
+}
+
+export interface ProductionEntry {
+  id: number;
+  month: string;
+  epf_number: string | null; // set for an individual's output
+  line: string | null; // set for a production line's output
+  product_code: string;
+  quantity: number;
+  entered_by: string | null;
+  created_at: string | null;
+}
+
+export interface ProductionImportReport {
+  total_rows: number;
+  imported: number;
+  skipped: { row: number; epf_number: string | null; reason: string }[];
+  dry_run: boolean;
+}
+
+// Salary bank account
+export interface EmployeeBankAccount {
+  epf_number: string;
+  bank_code: string; // 4 digit SLIPS bank code, e.g. 7010
+  branch_code: string; // 3 digits
+  account_number: string;
+  account_name: string;
+  updated_by?: string | null;
+  updated_at?: string | null;
+}